@@ -1,22 +1,30 @@
 use crate::fulgur::{
-    Fulgur, editor_tab, languages::supported_languages::SupportedLanguage, tab::Tab, ui,
+    Fulgur, editor_tab,
+    languages::supported_languages::SupportedLanguage,
+    tab::{Tab, TabId},
+    ui,
+    ui::markdown_preview::{FollowLink, JumpToSource, MarkdownPreviewState, ToggleTask},
+    ui::read_only_editor::read_only_editor,
+    ui::tabs::large_file_tab::LargeFileView,
+    utils::text_transform::TextTransform,
 };
 use gpui::prelude::FluentBuilder;
 use gpui::{
     AnyElement, App, AppContext, Context, DismissEvent, Div, Entity, Focusable, InteractiveElement,
-    IntoElement, MouseButton, MouseDownEvent, ParentElement, SharedString, Styled, Window, div, px,
+    IntoElement, MouseButton, MouseDownEvent, NavigationDirection, ParentElement, SharedString,
+    Stateful, Styled, Window, div, px,
 };
 use gpui_component::{
     ActiveTheme, WindowExt, h_flex,
     input::{Editor, EditorState},
-    menu::PopupMenu,
+    menu::{PopupMenu, PopupMenuItem},
     notification::NotificationType,
     resizable::{h_resizable, resizable_panel},
     scroll::ScrollableElement,
     table::{DataTable, TableState},
-    text::{TextView, TextViewState},
     v_flex,
 };
+use std::{path::Path, rc::Rc};
 
 /// Reading width the Markdown preview is capped at when the width limit is enabled.
 const MARKDOWN_PREVIEW_MAX_WIDTH: f32 = 800.0;
@@ -48,12 +56,75 @@ impl Fulgur {
         let active_tab_id = editor_tab.id;
         let editor_focus = editor_tab.content.focus_handle(cx);
         let has_file = editor_tab.file_path().is_some();
+        let misspelling = self.misspelling_under_cursor(&editor_tab.content, cx);
+        let (lint_fixes, fixable_lint_problems) =
+            self.markdown_lint_fixes_under_cursor(&editor_tab.content, cx);
         let position = event.position;
+        let fulgur = cx.weak_entity();
 
         let menu = PopupMenu::build(window, cx, {
             let editor_focus = editor_focus.clone();
-            move |mut menu, _window, _cx| {
-                menu = menu.action_context(editor_focus);
+            move |mut menu, window, cx| {
+                menu = menu.action_context(editor_focus.clone());
+                if let Some((misspelling, suggestions)) = misspelling {
+                    if suggestions.is_empty() {
+                        menu = menu.label("No Suggestions");
+                    }
+                    for suggestion in suggestions {
+                        let fulgur = fulgur.clone();
+                        let misspelling = misspelling.clone();
+                        menu = menu.item(PopupMenuItem::new(suggestion.clone()).on_click(
+                            move |_, window, cx| {
+                                _ = fulgur.update(cx, |this, cx| {
+                                    this.replace_misspelling(&misspelling, &suggestion, window, cx);
+                                });
+                            },
+                        ));
+                    }
+                    let word = misspelling.word;
+                    let fulgur = fulgur.clone();
+                    menu = menu
+                        .item(
+                            PopupMenuItem::new(format!("Add \"{word}\" to Dictionary")).on_click(
+                                move |_, window, cx| {
+                                    _ = fulgur.update(cx, |this, cx| {
+                                        this.add_word_to_dictionary(&word, window, cx);
+                                    });
+                                },
+                            ),
+                        )
+                        .separator();
+                }
+                let has_lint_items = !lint_fixes.is_empty() || fixable_lint_problems > 1;
+                for fix in lint_fixes {
+                    let fulgur = fulgur.clone();
+                    menu = menu.item(PopupMenuItem::new(fix.label.clone()).on_click(
+                        move |_, window, cx| {
+                            _ = fulgur.update(cx, |this, cx| {
+                                this.apply_markdown_lint_fixes(
+                                    std::slice::from_ref(&fix),
+                                    window,
+                                    cx,
+                                );
+                            });
+                        },
+                    ));
+                }
+                if fixable_lint_problems > 1 {
+                    menu = menu.item(
+                        PopupMenuItem::new(format!(
+                            "Fix All Markdown Lint Problems ({fixable_lint_problems})"
+                        ))
+                        .on_click(move |_, window, cx| {
+                            _ = fulgur.update(cx, |this, cx| {
+                                this.fix_all_markdown_lint_problems(window, cx);
+                            });
+                        }),
+                    );
+                }
+                if has_lint_items {
+                    menu = menu.separator();
+                }
                 if has_file {
                     menu = menu
                         .menu(
@@ -62,11 +133,28 @@ impl Fulgur {
                         )
                         .separator();
                 }
+                let transform_focus = editor_focus;
                 menu.menu("Cut", Box::new(gpui_component::input::Cut))
                     .menu("Copy", Box::new(gpui_component::input::Copy))
                     .menu("Paste", Box::new(gpui_component::input::Paste))
                     .separator()
                     .menu("Select All", Box::new(gpui_component::input::SelectAll))
+                    .separator()
+                    .submenu("Transform", window, cx, move |mut submenu, _window, _cx| {
+                        submenu = submenu.action_context(transform_focus.clone());
+                        for (index, group) in TextTransform::GROUPS.iter().enumerate() {
+                            if index > 0 {
+                                submenu = submenu.separator();
+                            }
+                            for transform in *group {
+                                submenu = submenu.menu(
+                                    transform.label(),
+                                    Box::new(ui::menus::TransformText(*transform)),
+                                );
+                            }
+                        }
+                        submenu
+                    })
             }
         });
 
@@ -140,47 +228,104 @@ impl Fulgur {
     /// - `cx`: The application context
     ///
     /// ### Returns
-    /// - `Some(Entity<TextViewState>)`: The state of the active preview, either a
+    /// - `Some(Entity<MarkdownPreviewState>)`: The state of the active preview, either a
     ///   dedicated preview tab or the inline panel of a markdown editor tab.
     /// - `None`: If no markdown preview is currently displayed.
-    fn active_markdown_preview_state(&self, cx: &App) -> Option<Entity<TextViewState>> {
+    fn active_markdown_preview_state(&self, cx: &App) -> Option<Entity<MarkdownPreviewState>> {
         match self.active_tab(cx)? {
-            Tab::MarkdownPreview(preview) => Some(preview.view_state.clone()),
+            Tab::MarkdownPreview(preview) => Some(preview.preview.clone()),
             Tab::Editor(_) => self.markdown_panel_view_state.clone(),
-            Tab::Settings(_) => None,
+            Tab::Settings(_) | Tab::LargeFile(_) => None,
         }
     }
 
-    /// Ensure the inline preview panel owns a persistent text view state.
+    /// Ensure the inline preview panel owns a persistent preview state.
     ///
     /// ### Arguments
-    /// - `text`: The rendered markdown source to display
     /// - `cx`: The application context
     ///
     /// ### Returns
-    /// - `Entity<TextViewState>`: The persistent state for the inline preview.
+    /// - `Entity<MarkdownPreviewState>`: The persistent state for the inline preview.
     fn ensure_markdown_panel_state(
         &mut self,
-        text: &str,
         cx: &mut Context<Self>,
-    ) -> Entity<TextViewState> {
-        let state = self
-            .markdown_panel_view_state
-            .get_or_insert_with(|| cx.new(|cx| TextViewState::markdown(text, cx)))
-            .clone();
-        state.update(cx, |state, cx| state.set_text(text, cx));
-        state
+    ) -> Entity<MarkdownPreviewState> {
+        self.markdown_panel_view_state
+            .get_or_insert_with(|| cx.new(|_| MarkdownPreviewState::new()))
+            .clone()
+    }
+
+    /// Bring a markdown preview up to date with its source editor and render it.
+    ///
+    /// ### Description
+    /// Reading the source editor here tracks it for this window, so edits, cursor
+    /// moves and scrolling re-render the preview, which follows the editor. A
+    /// double-clicked block places the cursor of the source editor at its first line,
+    /// a clicked link is followed within Fulgur when it leads to a local file, and a
+    /// clicked task-list checkbox toggles in the source editor.
+    ///
+    /// ### Arguments
+    /// - `id`: The element ID of the preview
+    /// - `preview`: The state of the preview
+    /// - `content`: The source editor
+    /// - `base_dir`: The directory of the source file, used to resolve local images
+    /// - `source_tab_id`: The editor tab of the source
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Stateful<Div>`: The scrollable preview
+    fn render_markdown_preview(
+        &self,
+        id: &'static str,
+        preview: &Entity<MarkdownPreviewState>,
+        content: &Entity<EditorState>,
+        base_dir: Option<&Path>,
+        source_tab_id: TabId,
+        cx: &mut Context<Self>,
+    ) -> Stateful<Div> {
+        let (source, cursor_line, scroll_top) = {
+            let editor = content.read(cx);
+            (
+                editor.value().to_string(),
+                editor.cursor_position().line as usize,
+                editor.scroll_offset().y,
+            )
+        };
+        let font_size = self.settings.editor_settings.font_size;
+        preview.update(cx, |preview, cx| {
+            preview.set_source(&source, base_dir, cx);
+            preview.follow_editor(cursor_line, scroll_top, font_size);
+        });
+        let fulgur = cx.weak_entity();
+        let on_jump: JumpToSource = Rc::new(move |line, window, cx| {
+            _ = fulgur.update(cx, |this, cx| {
+                this.jump_to_preview_source(source_tab_id, line, window, cx);
+            });
+        });
+        let fulgur = cx.weak_entity();
+        let on_link: FollowLink = Rc::new(move |url, window, cx| {
+            _ = fulgur.update(cx, |this, cx| {
+                this.follow_preview_link(source_tab_id, url, window, cx);
+            });
+        });
+        let fulgur = cx.weak_entity();
+        let on_task: ToggleTask = Rc::new(move |line, window, cx| {
+            _ = fulgur.update(cx, |this, cx| {
+                this.toggle_preview_task(source_tab_id, line, window, cx);
+            });
+        });
+        MarkdownPreviewState::render(preview, id, &on_jump, &on_link, &on_task, cx)
     }
 
     /// Lay out a markdown preview inside its container, honouring the width limit setting.
     ///
     /// ### Arguments
-    /// - `preview`: The preview text view to lay out
+    /// - `preview`: The scrollable preview to lay out
     ///
     /// ### Returns
     /// - `Div`: A full-size container holding the preview, capped at
     ///   `MARKDOWN_PREVIEW_MAX_WIDTH` and centered when the limit is enabled.
-    fn layout_markdown_preview(&self, preview: TextView) -> Div {
+    fn layout_markdown_preview(&self, preview: Stateful<Div>) -> Div {
         let limited = self
             .settings
             .editor_settings
@@ -194,6 +339,9 @@ impl Fulgur {
 
     /// Wrap a markdown preview element with its context-menu affordances.
     ///
+    /// ### Description
+    /// The back and forward mouse buttons go through the links followed in the preview.
+    ///
     /// ### Arguments
     /// - `child`: The preview element to wrap
     /// - `cx`: The application context
@@ -211,15 +359,24 @@ impl Fulgur {
         });
         let select_all = cx.listener(|this, _: &gpui_component::input::SelectAll, _window, cx| {
             if let Some(state) = this.active_markdown_preview_state(cx) {
-                state.update(cx, TextViewState::select_all);
+                state.update(cx, |state, cx| state.select_all(cx));
             }
         });
 
+        let back = cx.listener(|this, _: &MouseDownEvent, window, cx| {
+            this.markdown_link_back(window, cx);
+        });
+        let forward = cx.listener(|this, _: &MouseDownEvent, window, cx| {
+            this.markdown_link_forward(window, cx);
+        });
+
         div()
             .id("markdown-preview-context")
             .track_focus(&self.markdown_preview_focus)
             .size_full()
             .capture_any_mouse_down(right_click)
+            .on_mouse_down(MouseButton::Navigate(NavigationDirection::Back), back)
+            .on_mouse_down(MouseButton::Navigate(NavigationDirection::Forward), forward)
             .on_action(copy)
             .on_action(select_all)
             .child(child)
@@ -242,6 +399,7 @@ impl Fulgur {
     ) -> AnyElement {
         enum ActiveTabRenderData {
             Editor {
+                id: TabId,
                 language: SupportedLanguage,
                 show_markdown_preview: bool,
                 large_file: bool,
@@ -251,12 +409,15 @@ impl Fulgur {
                 csv_table: Option<Entity<TableState<editor_tab::CsvTableDelegate>>>,
                 log_view: bool,
                 log_content: Option<Entity<EditorState>>,
+                read_only: bool,
             },
             Settings,
+            LargeFile(Entity<LargeFileView>),
             MarkdownPreview {
                 content: Entity<EditorState>,
                 source_path: Option<std::path::PathBuf>,
-                view_state: Entity<gpui_component::text::TextViewState>,
+                source_tab_id: TabId,
+                preview: Entity<MarkdownPreviewState>,
             },
         }
 
@@ -287,6 +448,7 @@ impl Fulgur {
         let active_tab = active_tab_index.and_then(|active_index| {
             tabs_ref.get(active_index).map(|tab| match tab.read(cx) {
                 Tab::Editor(editor_tab) => ActiveTabRenderData::Editor {
+                    id: editor_tab.id,
                     language: editor_tab.language,
                     show_markdown_preview: editor_tab.show_markdown_preview,
                     large_file: editor_tab.large_file,
@@ -296,8 +458,12 @@ impl Fulgur {
                     csv_table: editor_tab.csv_table.clone(),
                     log_view: editor_tab.log_view,
                     log_content: editor_tab.log_content.clone(),
+                    read_only: editor_tab.read_only,
                 },
                 Tab::Settings(_) => ActiveTabRenderData::Settings,
+                Tab::LargeFile(large_file_tab) => {
+                    ActiveTabRenderData::LargeFile(large_file_tab.view.clone())
+                }
                 Tab::MarkdownPreview(preview_tab) => ActiveTabRenderData::MarkdownPreview {
                     content: tabs_ref
                         .iter()
@@ -316,7 +482,8 @@ impl Fulgur {
                         }
                         _ => None,
                     }),
-                    view_state: preview_tab.view_state.clone(),
+                    source_tab_id: preview_tab.source_tab_id,
+                    preview: preview_tab.preview.clone(),
                 },
             })
         });
@@ -324,6 +491,7 @@ impl Fulgur {
         if let Some(tab) = active_tab {
             match tab {
                 ActiveTabRenderData::Editor {
+                    id,
                     language,
                     show_markdown_preview,
                     large_file,
@@ -333,6 +501,7 @@ impl Fulgur {
                     csv_table,
                     log_view,
                     log_content,
+                    read_only,
                 } => {
                     if log_view && let Some(log_content) = log_content {
                         let log_input = Editor::new(&log_content)
@@ -358,12 +527,15 @@ impl Fulgur {
                             .child(DataTable::new(&table).bordered(true).stripe(true))
                             .into_any_element();
                     }
-                    let editor_input = Editor::new(&content)
-                        .bordered(false)
-                        .p_0()
-                        .h_full()
-                        .font_family(self.settings.editor_settings.font_family.clone())
-                        .text_size(px(self.settings.editor_settings.font_size));
+                    let editor_input = read_only_editor(
+                        Editor::new(&content)
+                            .bordered(false)
+                            .p_0()
+                            .h_full()
+                            .font_family(self.settings.editor_settings.font_family.clone())
+                            .text_size(px(self.settings.editor_settings.font_size)),
+                        read_only,
+                    );
                     let capture_right_click =
                         cx.listener(|this, event: &MouseDownEvent, window, cx| {
                             this.on_editor_right_click(event, window, cx);
@@ -374,27 +546,20 @@ impl Fulgur {
                         && self.settings.editor_settings.markdown_settings.preview_mode
                             == crate::fulgur::settings::MarkdownPreviewMode::Panel
                     {
-                        // Reading the content entity here tracks it for this
-                        // window, so edits re-render the panel automatically.
-                        // Multi-line raw-HTML blocks are collapsed first so the
-                        // Markdown renderer never shapes a run containing a
-                        // newline (see `sanitize_markdown_preview`).
-                        let preview_text =
-                            crate::fulgur::utils::sanitize::sanitize_markdown_preview(
-                                &crate::fulgur::utils::markdown_images::rewrite_markdown_image_paths(
-                                    content.read(cx).value().as_ref(),
-                                    path.as_deref().and_then(std::path::Path::parent),
-                                ),
-                            );
-                        let preview_state = self.ensure_markdown_panel_state(&preview_text, cx);
+                        let preview_state = self.ensure_markdown_panel_state(cx);
                         let preview = self
                             .layout_markdown_preview(
-                                TextView::new(&preview_state)
-                                    .flex_none()
-                                    .py_0()
-                                    .px_2()
-                                    .scrollable(true)
-                                    .selectable(true),
+                                self.render_markdown_preview(
+                                    "markdown-panel-preview",
+                                    &preview_state,
+                                    &content,
+                                    path.as_deref().and_then(Path::parent),
+                                    id,
+                                    cx,
+                                )
+                                .flex_none()
+                                .py_0()
+                                .px_2(),
                             )
                             .bg(cx.theme().muted)
                             .into_any_element();
@@ -435,28 +600,27 @@ impl Fulgur {
                         .child(self.render_settings(window, cx))
                         .into_any_element();
                 }
+                ActiveTabRenderData::LargeFile(view) => {
+                    return v_flex().w_full().flex_1().child(view).into_any_element();
+                }
                 ActiveTabRenderData::MarkdownPreview {
                     content,
                     source_path,
-                    view_state,
+                    source_tab_id,
+                    preview,
                 } => {
-                    let base_dir = source_path.as_deref().and_then(std::path::Path::parent);
-                    let preview_text = crate::fulgur::utils::sanitize::sanitize_markdown_preview(
-                        &crate::fulgur::utils::markdown_images::rewrite_markdown_image_paths(
-                            content.read(cx).value().as_ref(),
-                            base_dir,
-                        ),
-                    );
-                    view_state.update(cx, |state, cx| {
-                        state.set_text(&preview_text, cx);
-                    });
                     let preview = self
                         .layout_markdown_preview(
-                            TextView::new(&view_state)
-                                .py_2()
-                                .px_4()
-                                .scrollable(true)
-                                .selectable(true),
+                            self.render_markdown_preview(
+                                "markdown-tab-preview",
+                                &preview,
+                                &content,
+                                source_path.as_deref().and_then(Path::parent),
+                                source_tab_id,
+                                cx,
+                            )
+                            .py_2()
+                            .px_4(),
                         )
                         .into_any_element();
                     return v_flex()
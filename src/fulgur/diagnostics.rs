@@ -0,0 +1,240 @@
+use crate::fulgur::Fulgur;
+use gpui::{Context, Entity, EntityId, WeakEntity};
+use gpui_component::input::EditorState;
+use lsp_types::Diagnostic;
+use std::collections::{BTreeMap, HashMap};
+
+/// Feature owning a set of editor diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiagnosticSource {
+    /// Misspelled words.
+    SpellCheck,
+    /// Problems found by the Markdown linter.
+    MarkdownLint,
+    /// Problems reported by the last task.
+    Task,
+    /// The parse error found by validating a JSON, YAML or TOML document.
+    DataValidation,
+    /// Links of a Markdown document whose target is missing.
+    BrokenLinks,
+}
+
+/// Diagnostics of one editor, grouped by the feature that produced them.
+struct EditorDiagnostics {
+    editor: WeakEntity<EditorState>, // Lets the entries of a closed editor be dropped
+    by_source: BTreeMap<DiagnosticSource, Vec<Diagnostic>>,
+}
+
+/// Diagnostics of the editors of a window, each feature owning its own entries
+///
+/// ### Description
+/// An editor shows a single list of diagnostics. Every feature underlining
+/// text replaces only its own entries here, and the editor is given them all,
+/// so one feature never erases what another one found.
+#[derive(Default)]
+pub struct DiagnosticsStore {
+    editors: HashMap<EntityId, EditorDiagnostics>,
+}
+
+impl DiagnosticsStore {
+    /// Replace the diagnostics a feature has in an editor
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state
+    /// - `source`: The feature the diagnostics belong to
+    /// - `diagnostics`: The new diagnostics; empty to drop the feature's entries
+    fn replace(
+        &mut self,
+        content: &Entity<EditorState>,
+        source: DiagnosticSource,
+        diagnostics: Vec<Diagnostic>,
+    ) {
+        self.editors
+            .retain(|_, editor| editor.editor.upgrade().is_some());
+        if diagnostics.is_empty() {
+            if let Some(editor) = self.editors.get_mut(&content.entity_id()) {
+                editor.by_source.remove(&source);
+                if editor.by_source.is_empty() {
+                    self.editors.remove(&content.entity_id());
+                }
+            }
+            return;
+        }
+        self.editors
+            .entry(content.entity_id())
+            .or_insert_with(|| EditorDiagnostics {
+                editor: content.downgrade(),
+                by_source: BTreeMap::new(),
+            })
+            .by_source
+            .insert(source, diagnostics);
+    }
+
+    /// Get the diagnostics of every feature in an editor
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state
+    ///
+    /// ### Returns
+    /// - `Vec<Diagnostic>`: The diagnostics, grouped by feature
+    fn merged(&self, content: &Entity<EditorState>) -> Vec<Diagnostic> {
+        self.editors
+            .get(&content.entity_id())
+            .map(|editor| editor.by_source.values().flatten().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the diagnostics a feature has in an editor
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state
+    /// - `source`: The feature
+    ///
+    /// ### Returns
+    /// - `&[Diagnostic]`: The diagnostics, empty when the feature has none there
+    pub fn get(&self, content: &Entity<EditorState>, source: DiagnosticSource) -> &[Diagnostic] {
+        self.editors
+            .get(&content.entity_id())
+            .and_then(|editor| editor.by_source.get(&source))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+impl Fulgur {
+    /// Replace the diagnostics a feature shows in an editor
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state
+    /// - `source`: The feature the diagnostics belong to
+    /// - `diagnostics`: The new diagnostics; empty to remove the feature's underlines
+    /// - `cx`: The application context
+    pub(crate) fn set_diagnostics(
+        &mut self,
+        content: &Entity<EditorState>,
+        source: DiagnosticSource,
+        diagnostics: Vec<Diagnostic>,
+        cx: &mut Context<Self>,
+    ) {
+        self.diagnostics.replace(content, source, diagnostics);
+        self.write_diagnostics(content, cx);
+    }
+
+    /// Remove the diagnostics a feature shows in an editor
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state
+    /// - `source`: The feature whose underlines are removed
+    /// - `cx`: The application context
+    pub(crate) fn clear_diagnostics(
+        &mut self,
+        content: &Entity<EditorState>,
+        source: DiagnosticSource,
+        cx: &mut Context<Self>,
+    ) {
+        if self.diagnostics.get(content, source).is_empty() {
+            return;
+        }
+        self.set_diagnostics(content, source, Vec::new(), cx);
+    }
+
+    /// Give an editor the diagnostics of every feature
+    ///
+    /// ### Description
+    /// Nothing is written while the search bar is open, since it highlights its
+    /// matches through the editor diagnostics; closing it writes them back.
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state
+    /// - `cx`: The application context
+    pub(crate) fn write_diagnostics(
+        &mut self,
+        content: &Entity<EditorState>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.search_bar.read(cx).is_visible() {
+            return;
+        }
+        let diagnostics = self.diagnostics.merged(content);
+        content.update(cx, |content, cx| {
+            if let Some(existing) = content.diagnostics_mut() {
+                existing.clear();
+                for diagnostic in diagnostics {
+                    existing.push(diagnostic);
+                }
+            }
+            cx.notify();
+        });
+    }
+}
+
+#[cfg(all(test, feature = "gpui-test-support"))]
+mod tests {
+    use super::DiagnosticSource;
+    use crate::fulgur::files::file_operations::test_helpers::setup_fulgur;
+    use gpui::TestAppContext;
+    use lsp_types::{Diagnostic, Position, Range};
+
+    fn diagnostic(line: u32, message: &str) -> Diagnostic {
+        Diagnostic {
+            range: Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 1 },
+            },
+            message: message.to_string(),
+            ..Diagnostic::default()
+        }
+    }
+
+    #[gpui::test]
+    fn test_each_source_replaces_only_its_own_diagnostics(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        visual_cx.update(|_window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let content = this
+                    .get_active_editor_tab(cx)
+                    .map(|editor_tab| editor_tab.content.clone())
+                    .expect("an editor tab");
+                let shown = |this: &crate::fulgur::Fulgur| {
+                    let mut messages: Vec<String> = this
+                        .diagnostics
+                        .merged(&content)
+                        .into_iter()
+                        .map(|diagnostic| diagnostic.message)
+                        .collect();
+                    messages.sort();
+                    messages
+                };
+
+                this.set_diagnostics(
+                    &content,
+                    DiagnosticSource::SpellCheck,
+                    vec![diagnostic(0, "misspelled")],
+                    cx,
+                );
+                this.set_diagnostics(
+                    &content,
+                    DiagnosticSource::Task,
+                    vec![diagnostic(1, "build error")],
+                    cx,
+                );
+                assert_eq!(shown(this), ["build error", "misspelled"]);
+
+                this.set_diagnostics(
+                    &content,
+                    DiagnosticSource::SpellCheck,
+                    vec![diagnostic(2, "typo")],
+                    cx,
+                );
+                assert_eq!(shown(this), ["build error", "typo"]);
+
+                this.clear_diagnostics(&content, DiagnosticSource::Task, cx);
+                assert_eq!(shown(this), ["typo"]);
+                assert!(
+                    this.diagnostics
+                        .get(&content, DiagnosticSource::Task)
+                        .is_empty()
+                );
+            });
+        });
+    }
+}
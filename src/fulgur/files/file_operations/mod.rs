@@ -13,6 +13,7 @@ pub use encoding::{
 pub use remote_types::{
     PendingRemoteOpenOutcome, RemoteBrowseResult, RemoteFileResult, RemoteOpenResult,
 };
+pub use save_ops::AutoSaveState;
 
 #[cfg(test)]
 pub mod test_helpers;
@@ -1,4 +1,4 @@
-use crate::fulgur::{Fulgur, window_manager};
+use crate::fulgur::{Fulgur, utils::markdown_assets::is_image_path, window_manager};
 use gpui::{Context, ExternalPaths, SharedString, Window};
 use gpui_component::{WindowExt, notification::NotificationType};
 use std::{collections::HashSet, path::PathBuf};
@@ -28,6 +28,7 @@ impl Fulgur {
     /// Handle dropping external file system paths into this window.
     ///
     /// ### Behavior
+    /// - Links dropped images into the active Markdown tab, copying them into its image folder
     /// - Opens dropped files in new tabs (or focuses existing tabs via `do_open_file`)
     /// - Ignores non-file entries (e.g. directories)
     /// - Deduplicates duplicate paths within the same drop gesture
@@ -66,6 +67,11 @@ impl Fulgur {
             }
             return;
         }
+        if dropped_files.iter().all(|path| is_image_path(path))
+            && self.drop_images_into_markdown(&dropped_files, window, cx)
+        {
+            return;
+        }
         log::info!(
             "Opening {} dropped file(s) in window {:?}",
             dropped_files.len(),
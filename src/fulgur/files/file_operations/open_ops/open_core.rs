@@ -3,9 +3,13 @@ use crate::fulgur::{
     Fulgur,
     editor_tab::{EditorTab, FromFileParams},
     tab::Tab,
-    ui::menus,
+    ui::{
+        menus,
+        tabs::large_file_tab::{LargeFileTab, LargeFileView},
+    },
+    utils::atomic_write::is_file_writable,
 };
-use gpui::{AsyncWindowContext, Context, SharedString, WeakEntity, Window};
+use gpui::{AppContext, AsyncWindowContext, Context, SharedString, WeakEntity, Window};
 use gpui_component::{WindowExt, notification::NotificationType};
 use std::path::Path;
 
@@ -57,6 +61,60 @@ impl Fulgur {
         cx.notify();
     }
 
+    /// Open a file above the large-file threshold in a read-only virtualized viewer
+    ///
+    /// ### Arguments
+    /// - `path`: The canonical path of the file
+    /// - `file_size`: The length of the file in bytes
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn open_large_file_tab(
+        &mut self,
+        path: &Path,
+        file_size: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let id = self.allocate_tab_id();
+        let title = SharedString::from(path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().to_string(),
+        ));
+        let settings = self.settings.editor_settings.clone();
+        let large_file_view =
+            cx.new(|cx| LargeFileView::new(path.to_path_buf(), file_size, &settings, window, cx));
+        self.place_editor_tab_reusing_scratch(
+            Tab::LargeFile(LargeFileTab {
+                id,
+                title: title.clone(),
+                path: path.to_path_buf(),
+                view: large_file_view,
+            }),
+            window,
+            cx,
+        );
+        if let Err(e) = self.settings.add_file(path.to_path_buf()) {
+            log::error!("Failed to add file to recent files: {e}");
+        }
+        let shared = Fulgur::shared_state(cx);
+        let update_info = shared.update_info.lock().clone();
+        let update_link = update_info.as_ref().map(|info| info.download_url.clone());
+        let menus = menus::build_menus(&self.settings.get_recent_files(), update_link.as_deref());
+        self.update_menus(menus, cx);
+        self.set_title(Some(title), cx);
+        window.push_notification(
+            (
+                NotificationType::Info,
+                SharedString::from(format!(
+                    "'{}' is too large to edit and was opened read-only",
+                    path.display()
+                )),
+            ),
+            cx,
+        );
+        cx.notify();
+    }
+
     /// Internal helper function to open a file from a path. This function handles reading the file, detecting encoding, and creating the editor tab
     ///
     /// ### Arguments
@@ -76,30 +134,24 @@ impl Fulgur {
         let canonical_path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
         let file_size = std::fs::metadata(&canonical_path).map_or(0, |metadata| metadata.len());
         if file_size > crate::fulgur::ui::tabs::editor_tab::LARGE_FILE_THRESHOLD_BYTES {
-            let file_name = canonical_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("file")
-                .to_string();
+            log::debug!(
+                "Opening {} ({file_size} bytes) in the large file viewer",
+                canonical_path.display()
+            );
             window
                 .update(|window, cx| {
-                    window.push_notification(
-                        (
-                            NotificationType::Info,
-                            SharedString::from(format!(
-                                "Opening large file '{file_name}', this may take a moment..."
-                            )),
-                        ),
-                        cx,
-                    );
+                    _ = view.update(cx, |this, cx| {
+                        this.open_large_file_tab(&canonical_path, file_size, window, cx);
+                    });
                 })
                 .ok();
+            return Some(());
         }
         let read_path = canonical_path.clone();
-        let outcome = window
+        let (outcome, read_only) = window
             .background_executor()
             .spawn(async move {
-                match std::fs::read(&read_path) {
+                let outcome = match std::fs::read(&read_path) {
                     Ok(bytes) => {
                         log::debug!(
                             "Successfully read file: {} ({} bytes)",
@@ -116,7 +168,8 @@ impl Fulgur {
                         log::error!("Failed to read file {}: {e}", read_path.display());
                         FileReadOutcome::Failed
                     }
-                }
+                };
+                (outcome, !is_file_writable(&read_path))
             })
             .await;
         let path = canonical_path.as_path();
@@ -163,6 +216,7 @@ impl Fulgur {
                         &this.settings.editor_settings,
                     );
                     editor_tab.lossy_decode = decoded.lossy;
+                    editor_tab.read_only = read_only;
                     let editor_tab_index =
                         this.place_editor_tab_reusing_scratch(Tab::Editor(editor_tab), window, cx);
                     this.maybe_open_markdown_preview_for_editor(editor_tab_index, cx);
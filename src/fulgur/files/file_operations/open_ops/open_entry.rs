@@ -1,4 +1,4 @@
-use crate::fulgur::{Fulgur, sync::ssh::url::parse_remote_url};
+use crate::fulgur::{Fulgur, editor_tab::Jump, sync::ssh::url::parse_remote_url};
 use gpui::{Context, PathPromptOptions, SharedString, Window};
 use gpui_component::{WindowExt, notification::NotificationType};
 use std::path::PathBuf;
@@ -56,6 +56,23 @@ impl Fulgur {
     /// - `cx`: The application context
     /// - `path`: The path to the file to open
     pub fn do_open_file(&mut self, window: &mut Window, cx: &mut Context<Self>, path: PathBuf) {
+        self.do_open_file_at(window, cx, path, None);
+    }
+
+    /// Open a file from a given path like `do_open_file`, then move its cursor to a position
+    ///
+    /// ### Arguments
+    /// - `window`: The window to open the file in
+    /// - `cx`: The application context
+    /// - `path`: The path to the file to open
+    /// - `jump`: The position to move the cursor to once the file is shown, if any
+    pub fn do_open_file_at(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        path: PathBuf,
+        jump: Option<Jump>,
+    ) {
         let path = std::fs::canonicalize(&path).unwrap_or(path);
         if let Some(tab_index) = self.find_tab_by_path(&path, cx) {
             log::debug!(
@@ -63,6 +80,7 @@ impl Fulgur {
                 path.display()
             );
             self.focus_existing_local_tab_for_open(&path, tab_index, window, cx);
+            self.pending_jump = jump;
             return;
         }
         let window_manager = cx.global::<crate::fulgur::window_manager::WindowManager>();
@@ -82,7 +100,18 @@ impl Fulgur {
             return;
         }
         cx.spawn_in(window, async move |view, window| {
-            Self::open_file_from_path(&view, window, &path).await
+            Self::open_file_from_path(&view, window, &path).await?;
+            if jump.is_some() {
+                window
+                    .update(|_, cx| {
+                        _ = view.update(cx, |this, cx| {
+                            this.pending_jump = jump;
+                            cx.notify();
+                        });
+                    })
+                    .ok();
+            }
+            Some(())
         })
         .detach();
     }
@@ -76,19 +76,18 @@ impl Fulgur {
             let Some(editor_tab) = tab.as_editor_mut() else {
                 return;
             };
+            editor_tab.apply_content_diff(&decoded.content, window, cx);
             let (cursor, scroll_offset) = {
                 let input_state = editor_tab.content.read(cx);
                 (input_state.cursor(), input_state.scroll_offset())
             };
-            editor_tab.content.update(cx, |input_state, cx| {
-                input_state.set_value(&decoded.content, window, cx);
-            });
             editor_tab.set_original_content_from_str(&decoded.content);
             editor_tab.encoding = decoded.encoding;
             editor_tab.lossy_decode = decoded.lossy;
             editor_tab.modified = false;
             editor_tab.update_file_tooltip_cache(decoded.byte_len);
-            tab.update_language(window, cx, &settings);
+            tab.update_language(cx);
+            tab.redetect_indentation(&decoded.content, window, cx, &settings);
             if let Some(editor_tab) = tab.as_editor_mut() {
                 editor_tab.content.update(cx, |input_state, cx| {
                     input_state.set_selected_range(cursor..cursor, cx);
@@ -108,6 +107,8 @@ mod tests {
     #[cfg(feature = "gpui-test-support")]
     use crate::fulgur::files::file_operations::test_helpers::setup_fulgur;
     #[cfg(feature = "gpui-test-support")]
+    use crate::fulgur::languages::supported_languages::SupportedLanguage;
+    #[cfg(feature = "gpui-test-support")]
     use gpui::TestAppContext;
     #[cfg(feature = "gpui-test-support")]
     use std::fmt::Write as _;
@@ -237,6 +238,189 @@ mod tests {
         });
     }
 
+    #[cfg(feature = "gpui-test-support")]
+    #[gpui::test]
+    fn test_reload_tab_from_disk_shifts_caret_past_inserted_lines(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("reload_insert.txt");
+        let initial = "fn main() {\n    run();\n}\n";
+        std::fs::write(&path, initial).expect("failed to write initial file");
+        let caret = initial
+            .find("run")
+            .expect("expected the anchor in the fixture");
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let tab_entity = this.tabs.last().expect("expected at least one tab").clone();
+                tab_entity.update(cx, |tab, cx| {
+                    let editor_tab = tab.as_editor_mut().expect("expected an editor tab");
+                    editor_tab.location = TabLocation::Local(path.clone());
+                    editor_tab.content.update(cx, |input_state, cx| {
+                        input_state.set_value(initial, window, cx);
+                        input_state.set_selected_range(caret..caret, cx);
+                    });
+                    editor_tab.set_original_content_from_str(initial);
+                    editor_tab.modified = false;
+                });
+            });
+        });
+
+        // A formatter adding a header comment above the caret.
+        let updated = format!("// generated\n{initial}");
+        std::fs::write(&path, &updated).expect("failed to overwrite file");
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.reload_tab_from_disk(0, window, cx);
+            });
+        });
+        visual_cx.run_until_parked();
+
+        visual_cx.update(|_window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let editor_tab = this
+                    .tabs
+                    .first()
+                    .and_then(|t| t.read(cx).as_editor())
+                    .expect("expected an editor tab");
+                assert_eq!(editor_tab.content.read(cx).text().to_string(), updated);
+                assert_eq!(
+                    editor_tab.content.read(cx).cursor(),
+                    caret + "// generated\n".len(),
+                    "the caret must stay on the same text when lines are inserted above it"
+                );
+                assert!(!editor_tab.modified);
+            });
+        });
+    }
+
+    #[cfg(feature = "gpui-test-support")]
+    #[gpui::test]
+    fn test_reload_tab_from_disk_is_undone_in_one_step(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("reload_undo.txt");
+        let initial = "first\nsecond\nthird\nfourth\n";
+        std::fs::write(&path, initial).expect("failed to write initial file");
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let tab_entity = this.tabs.last().expect("expected at least one tab").clone();
+                tab_entity.update(cx, |tab, cx| {
+                    let editor_tab = tab.as_editor_mut().expect("expected an editor tab");
+                    editor_tab.location = TabLocation::Local(path.clone());
+                    editor_tab.content.update(cx, |input_state, cx| {
+                        input_state.set_value(initial, window, cx);
+                        input_state.focus(window, cx);
+                    });
+                    editor_tab.set_original_content_from_str(initial);
+                    editor_tab.modified = false;
+                });
+            });
+        });
+        visual_cx.run_until_parked();
+
+        // Several separate hunks, so the reload is made of more than one edit.
+        let updated = "header\nfirst\nSECOND\nthird\nfourth\nfooter\n";
+        std::fs::write(&path, updated).expect("failed to overwrite file");
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.reload_tab_from_disk(0, window, cx);
+            });
+        });
+        visual_cx.run_until_parked();
+        visual_cx.dispatch_action(gpui_component::input::Undo);
+        visual_cx.run_until_parked();
+
+        visual_cx.update(|_window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let content = this
+                    .tabs
+                    .first()
+                    .and_then(|t| t.read(cx).as_editor())
+                    .map(|e| e.content.read(cx).text().to_string())
+                    .unwrap_or_default();
+                assert_eq!(
+                    content, initial,
+                    "a single undo must revert the whole reload"
+                );
+            });
+        });
+    }
+
+    #[cfg(feature = "gpui-test-support")]
+    #[gpui::test]
+    fn test_reload_tab_from_disk_updates_language_and_indentation_in_place(
+        cx: &mut TestAppContext,
+    ) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("reload_language.py");
+        let initial = "print(1)\n";
+        std::fs::write(&path, initial).expect("failed to write initial file");
+
+        let input_before = visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let tab_entity = this.tabs.last().expect("expected at least one tab").clone();
+                tab_entity.update(cx, |tab, cx| {
+                    let editor_tab = tab.as_editor_mut().expect("expected an editor tab");
+                    editor_tab.location = TabLocation::Local(path.clone());
+                    editor_tab.content.update(cx, |input_state, cx| {
+                        input_state.set_value(initial, window, cx);
+                        input_state.focus(window, cx);
+                    });
+                    editor_tab.set_original_content_from_str(initial);
+                    editor_tab.modified = false;
+                    editor_tab.content.entity_id()
+                })
+            })
+        });
+        visual_cx.run_until_parked();
+
+        let updated = "def main():\n\treturn 1\n";
+        std::fs::write(&path, updated).expect("failed to overwrite file");
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.reload_tab_from_disk(0, window, cx);
+            });
+        });
+        visual_cx.run_until_parked();
+
+        visual_cx.update(|_window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let editor_tab = this
+                    .tabs
+                    .first()
+                    .and_then(|t| t.read(cx).as_editor())
+                    .expect("expected an editor tab");
+                assert_eq!(editor_tab.language, SupportedLanguage::Python);
+                assert!(!editor_tab.indentation.use_spaces);
+                assert_eq!(
+                    editor_tab.content.entity_id(),
+                    input_before,
+                    "a new language or indentation must not rebuild the input state"
+                );
+            });
+        });
+        visual_cx.dispatch_action(gpui_component::input::Undo);
+        visual_cx.run_until_parked();
+
+        visual_cx.update(|_window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let content = this
+                    .tabs
+                    .first()
+                    .and_then(|t| t.read(cx).as_editor())
+                    .map(|e| e.content.read(cx).text().to_string())
+                    .unwrap_or_default();
+                assert_eq!(content, initial, "the reload must stay undoable");
+            });
+        });
+    }
+
     #[cfg(feature = "gpui-test-support")]
     #[gpui::test]
     fn test_reload_tab_from_disk_is_noop_without_file_path(cx: &mut TestAppContext) {
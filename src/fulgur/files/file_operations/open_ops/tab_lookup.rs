@@ -13,12 +13,10 @@ impl Fulgur {
     /// - `None`: If the tab was not found
     #[must_use]
     pub fn find_tab_by_path(&self, path: &PathBuf, cx: &gpui::App) -> Option<usize> {
-        self.tabs.iter().position(|tab| {
-            if let Tab::Editor(editor_tab) = tab.read(cx) {
-                editor_tab.file_path().is_some_and(|p| p == path)
-            } else {
-                false
-            }
+        self.tabs.iter().position(|tab| match tab.read(cx) {
+            Tab::Editor(editor_tab) => editor_tab.file_path().is_some_and(|p| p == path),
+            Tab::LargeFile(large_file_tab) => large_file_tab.path == *path,
+            _ => false,
         })
     }
 
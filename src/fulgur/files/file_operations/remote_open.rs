@@ -177,7 +177,7 @@ impl Fulgur {
         use ssh::sftp::{RemotePathKind, classify_remote_path, closest_existing_remote_directory};
 
         match classify_remote_path(session, &spec.path)? {
-            RemotePathKind::File => {
+            RemotePathKind::File { writable } => {
                 let bytes = ssh::sftp::read_remote_file(session, &spec.path)?;
                 let decoded = detect_encoding_and_decode(bytes);
                 Ok(RemoteOpenResult::File(RemoteFileResult {
@@ -186,6 +186,7 @@ impl Fulgur {
                     content: decoded.content,
                     encoding: decoded.encoding,
                     lossy: decoded.lossy,
+                    read_only: !writable,
                 }))
             }
             RemotePathKind::Directory => {
@@ -298,6 +299,10 @@ impl Fulgur {
             },
             move |session, spec| {
                 if target_tab_id.is_some() {
+                    let read_only = matches!(
+                        ssh::sftp::classify_remote_path(session, &spec.path),
+                        Ok(ssh::sftp::RemotePathKind::File { writable: false })
+                    );
                     ssh::sftp::read_remote_file(session, &spec.path).map(|bytes| {
                         let decoded = detect_encoding_and_decode(bytes);
                         RemoteOpenResult::File(RemoteFileResult {
@@ -306,6 +311,7 @@ impl Fulgur {
                             content: decoded.content,
                             encoding: decoded.encoding,
                             lossy: decoded.lossy,
+                            read_only,
                         })
                     })
                 } else {
@@ -378,6 +384,7 @@ mod tests {
             encoding: UTF_8.to_string(),
             lossy: false,
             file_size: 2,
+            read_only: false,
         })
     }
 
@@ -139,6 +139,9 @@ impl Fulgur {
             editor_tab.location =
                 crate::fulgur::editor_tab::TabLocation::Remote(remote_file.spec.clone());
             editor_tab.encoding = remote_file.encoding;
+            if remote_file.read_only {
+                editor_tab.set_read_only(true, cx);
+            }
             editor_tab.set_original_content_from_str(&remote_file.content);
             editor_tab.modified = false;
             editor_tab.update_file_tooltip_cache(remote_file.file_size);
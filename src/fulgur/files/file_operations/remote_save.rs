@@ -254,6 +254,7 @@ impl Fulgur {
             return;
         }
         self.latest_remote_save_request_by_tab.remove(&tab_id);
+        let auto_save = self.take_auto_save_remote_request(tab_id, request_id);
 
         match result {
             Ok(()) => {
@@ -273,6 +274,10 @@ impl Fulgur {
                     self.inflight_remote_restore.remove(&tab_id);
                     cx.notify();
                 }
+                self.clear_auto_save_error(cx);
+            }
+            Err(msg) if auto_save => {
+                self.record_auto_save_error(tab_id, format!("Failed to save: {msg}"), cx);
             }
             Err(msg) => {
                 window.push_notification(
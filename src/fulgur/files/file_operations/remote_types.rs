@@ -26,6 +26,8 @@ pub struct RemoteFileResult {
     pub encoding: String,
     pub lossy: bool,
     pub file_size: usize,
+    /// Whether the remote mode bits deny writing, so the tab opens read-only.
+    pub read_only: bool,
 }
 
 /// Data required to open a remote browsing dialog when the requested path is not a file.
@@ -0,0 +1,502 @@
+use super::super::{EncodedContents, encode_for_save};
+use crate::fulgur::settings::AutoSaveMode;
+use crate::fulgur::sync::ssh::credentials::SshCredKey;
+use crate::fulgur::ui::tabs::tab::TabId;
+use crate::fulgur::{Fulgur, editor_tab::TabLocation};
+use gpui::{Context, SharedString, Task, Window};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Interval at which idle tabs are checked while auto-saving after a delay
+const AUTO_SAVE_TICK: Duration = Duration::from_millis(250);
+
+/// Per-window auto-save bookkeeping
+#[derive(Default)]
+pub struct AutoSaveState {
+    ticker: Option<Task<()>>, // Periodic idle check, running only in `AfterDelay` mode
+    last_active_tab: Option<TabId>, // Active tab seen on the previous render, used to detect tab switches
+    remote_requests: HashMap<TabId, u64>, // Remote-save request ids issued by auto-save, keyed by tab id
+    error: Option<SharedString>, // Last auto-save failure, shown in the status bar until dismissed or a save succeeds
+    failed_edits: HashMap<TabId, Option<Instant>>, // Last edit of each tab whose auto-save failed, skipped until edited again
+}
+
+impl Fulgur {
+    /// Keep the auto-save machinery in line with the current settings, called on each render
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn refresh_auto_save(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mode = self.settings.editor_settings.auto_save;
+        if mode == AutoSaveMode::AfterDelay {
+            if self.auto_save.ticker.is_none() {
+                self.auto_save.ticker = Some(cx.spawn_in(window, async move |this, window| {
+                    loop {
+                        window.background_executor().timer(AUTO_SAVE_TICK).await;
+                        let alive = window
+                            .update(|window, cx| {
+                                this.update(cx, |this, cx| this.auto_save_idle_tabs(window, cx))
+                                    .is_ok()
+                            })
+                            .unwrap_or(false);
+                        if !alive {
+                            break;
+                        }
+                    }
+                }));
+            }
+        } else {
+            self.auto_save.ticker = None;
+        }
+        if self.auto_save.last_active_tab != self.active_tab_id {
+            if mode == AutoSaveMode::OnFocusChange
+                && let Some(previous_tab) = self.auto_save.last_active_tab
+            {
+                self.auto_save_tab(previous_tab, window, cx);
+            }
+            self.auto_save.last_active_tab = self.active_tab_id;
+        }
+    }
+
+    /// Save every modified tab when the window loses focus, if the auto-save mode asks for it
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn on_window_activation_changed(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if window.is_window_active() {
+            return;
+        }
+        if matches!(
+            self.settings.editor_settings.auto_save,
+            AutoSaveMode::OnFocusChange | AutoSaveMode::OnWindowChange
+        ) {
+            let tab_ids: Vec<TabId> = self.tabs.iter().map(|tab| tab.read(cx).id()).collect();
+            for tab_id in tab_ids {
+                self.auto_save_tab(tab_id, window, cx);
+            }
+        }
+    }
+
+    /// Save the tabs whose last edit is older than the configured auto-save delay
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn auto_save_idle_tabs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.settings.editor_settings.auto_save != AutoSaveMode::AfterDelay {
+            return;
+        }
+        let delay = Duration::from_millis(self.settings.editor_settings.auto_save_delay_ms);
+        let idle_tabs: Vec<TabId> = self
+            .tabs
+            .iter()
+            .filter_map(|tab| {
+                let editor_tab = tab.read(cx).as_editor()?;
+                (editor_tab.modified
+                    && editor_tab
+                        .last_edit
+                        .is_some_and(|last_edit| last_edit.elapsed() >= delay))
+                .then_some(editor_tab.id)
+            })
+            .collect();
+        for tab_id in idle_tabs {
+            self.auto_save_tab(tab_id, window, cx);
+        }
+    }
+
+    /// Save a tab in the background without any user interaction
+    ///
+    /// Untitled and read-only tabs, tabs that cannot be encoded losslessly, tabs with a save
+    /// already in flight and remote tabs needing a password prompt are skipped, as are tabs
+    /// whose last auto-save failed and that were not edited since.
+    ///
+    /// ### Arguments
+    /// - `tab_id`: Stable identifier of the tab to save
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn auto_save_tab(&mut self, tab_id: TabId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.tab_entity_of(tab_id, cx) else {
+            return;
+        };
+        let Some(editor_tab) = tab.read(cx).as_editor() else {
+            return;
+        };
+        if !editor_tab.modified || editor_tab.lossy_decode || editor_tab.read_only {
+            return;
+        }
+        if self.auto_save.failed_edits.get(&tab_id) == Some(&editor_tab.last_edit) {
+            log::debug!("Skipping auto-save of tab {tab_id:?}: it failed and was not edited since");
+            return;
+        }
+        let location = editor_tab.location.clone();
+        let encoding = editor_tab.encoding.clone();
+        let contents = editor_tab.content.read(cx).text().to_string();
+        if matches!(location, TabLocation::Untitled) {
+            return;
+        }
+        let EncodedContents::Encoded(bytes) = encode_for_save(&contents, &encoding) else {
+            log::debug!("Skipping auto-save of tab {tab_id:?}: content is not representable");
+            return;
+        };
+        match location {
+            TabLocation::Local(path) => {
+                if !self.inflight_saves.contains_key(&tab_id) {
+                    self.spawn_local_save(tab_id, path, bytes, true, window, cx);
+                }
+            }
+            TabLocation::Remote(spec) => {
+                if !self.settings.editor_settings.auto_save_remote
+                    || self.latest_remote_save_request_by_tab.contains_key(&tab_id)
+                {
+                    return;
+                }
+                let has_cached_password = spec.user.clone().is_some_and(|user| {
+                    let key = SshCredKey::new(spec.host.clone(), spec.port, user);
+                    Fulgur::shared_state(cx)
+                        .ssh_session_cache
+                        .lock()
+                        .contains_key(&key)
+                });
+                if !has_cached_password {
+                    log::debug!(
+                        "Skipping auto-save of remote tab {tab_id:?}: no cached credentials"
+                    );
+                    return;
+                }
+                self.save_remote_file(window, cx, tab_id, spec, contents, bytes);
+                if let Some(request_id) = self.latest_remote_save_request_by_tab.get(&tab_id) {
+                    self.auto_save.remote_requests.insert(tab_id, *request_id);
+                }
+            }
+            TabLocation::Untitled => {}
+        }
+    }
+
+    /// Check whether a remote save request was issued by auto-save, forgetting it
+    ///
+    /// ### Arguments
+    /// - `tab_id`: Stable identifier of the saved tab
+    /// - `request_id`: Identifier of the completed remote save request
+    ///
+    /// ### Returns
+    /// - `true`: If the request was issued by auto-save
+    /// - `false`: Otherwise
+    pub(crate) fn take_auto_save_remote_request(&mut self, tab_id: TabId, request_id: u64) -> bool {
+        if self.auto_save.remote_requests.get(&tab_id) == Some(&request_id) {
+            self.auto_save.remote_requests.remove(&tab_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Record a failed auto-save so it is shown in the status bar
+    ///
+    /// ### Description
+    /// The tab is not auto-saved again until its next edit, so a write that keeps
+    /// failing is not retried on every idle check.
+    ///
+    /// ### Arguments
+    /// - `tab_id`: Stable identifier of the tab that could not be saved
+    /// - `message`: Description of the failure
+    /// - `cx`: The application context
+    pub(crate) fn record_auto_save_error(
+        &mut self,
+        tab_id: TabId,
+        message: String,
+        cx: &mut Context<Self>,
+    ) {
+        let last_edit = self.tab_entity_of(tab_id, cx).and_then(|tab| {
+            tab.read(cx)
+                .as_editor()
+                .map(|editor_tab| editor_tab.last_edit)
+        });
+        if let Some(last_edit) = last_edit {
+            self.auto_save.failed_edits.insert(tab_id, last_edit);
+        }
+        self.auto_save.error = Some(SharedString::from(message));
+        cx.notify();
+    }
+
+    /// Clear the auto-save failure shown in the status bar
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub(crate) fn clear_auto_save_error(&mut self, cx: &mut Context<Self>) {
+        if self.auto_save.error.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Get the last auto-save failure
+    ///
+    /// ### Returns
+    /// - `Some(SharedString)`: The failure message, if an auto-save failed
+    /// - `None`: If no auto-save failure is pending
+    #[must_use]
+    pub(crate) fn auto_save_error(&self) -> Option<SharedString> {
+        self.auto_save.error.clone()
+    }
+}
+
+#[cfg(all(test, feature = "gpui-test-support"))]
+mod tests {
+    use super::AUTO_SAVE_TICK;
+    use crate::fulgur::{
+        Fulgur, editor_tab::TabLocation, files::file_operations::test_helpers::setup_fulgur,
+        settings::AutoSaveMode, tab::TabId,
+    };
+    use gpui::{Context, Entity, TestAppContext, VisualTestContext, Window};
+    use std::{path::PathBuf, time::Instant};
+    use tempfile::TempDir;
+
+    /// Give the active tab unsaved content, and optionally a file on disk
+    fn edit_active_tab(
+        this: &mut Fulgur,
+        location: TabLocation,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<Fulgur>,
+    ) -> TabId {
+        this.update_active_editor_tab(cx, |editor_tab, cx| {
+            editor_tab.location = location;
+            editor_tab.content.update(cx, |state, cx| {
+                state.set_value(text, window, cx);
+            });
+            editor_tab.modified = true;
+            editor_tab.last_edit = Some(Instant::now());
+            editor_tab.id
+        })
+        .expect("expected an active editor tab")
+    }
+
+    /// Check whether a tab still has unsaved changes
+    fn is_modified(
+        fulgur: &Entity<Fulgur>,
+        tab_id: TabId,
+        visual_cx: &mut VisualTestContext,
+    ) -> bool {
+        visual_cx.update(|_window, cx| {
+            let tab = fulgur
+                .read(cx)
+                .tab_entity_of(tab_id, cx)
+                .expect("expected the tab to be open");
+            tab.read(cx)
+                .as_editor()
+                .is_some_and(|editor_tab| editor_tab.modified)
+        })
+    }
+
+    /// Switch to auto-saving once tabs are idle, with no delay, and let the idle check run
+    fn run_idle_auto_save(fulgur: &Entity<Fulgur>, visual_cx: &mut VisualTestContext) {
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.settings.editor_settings.auto_save = AutoSaveMode::AfterDelay;
+                this.settings.editor_settings.auto_save_delay_ms = 0;
+                this.refresh_auto_save(window, cx);
+            });
+        });
+        visual_cx.background_executor.advance_clock(AUTO_SAVE_TICK);
+        visual_cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    fn test_after_delay_saves_idle_tabs(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("idle.txt");
+
+        let tab_id = visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                edit_active_tab(this, TabLocation::Local(path.clone()), "idle", window, cx)
+            })
+        });
+        run_idle_auto_save(&fulgur, &mut visual_cx);
+
+        assert_eq!(std::fs::read_to_string(&path).ok().as_deref(), Some("idle"));
+        assert!(!is_modified(&fulgur, tab_id, &mut visual_cx));
+    }
+
+    #[gpui::test]
+    fn test_after_delay_waits_for_the_delay(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("busy.txt");
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                edit_active_tab(this, TabLocation::Local(path.clone()), "busy", window, cx);
+                this.settings.editor_settings.auto_save = AutoSaveMode::AfterDelay;
+                this.settings.editor_settings.auto_save_delay_ms = 60_000;
+                this.refresh_auto_save(window, cx);
+            });
+        });
+        visual_cx.background_executor.advance_clock(AUTO_SAVE_TICK);
+        visual_cx.run_until_parked();
+
+        assert!(
+            !path.exists(),
+            "a tab edited within the delay must not be saved"
+        );
+    }
+
+    #[gpui::test]
+    fn test_on_focus_change_saves_the_tab_left_behind(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("left.txt");
+
+        let tab_id = visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.settings.editor_settings.auto_save = AutoSaveMode::OnFocusChange;
+                let tab_id =
+                    edit_active_tab(this, TabLocation::Local(path.clone()), "left", window, cx);
+                this.refresh_auto_save(window, cx);
+                this.new_tab(window, cx);
+                this.refresh_auto_save(window, cx);
+                tab_id
+            })
+        });
+        visual_cx.run_until_parked();
+
+        assert_eq!(std::fs::read_to_string(&path).ok().as_deref(), Some("left"));
+        assert!(!is_modified(&fulgur, tab_id, &mut visual_cx));
+    }
+
+    #[gpui::test]
+    fn test_on_window_change_saves_only_when_the_window_loses_focus(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("window.txt");
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.settings.editor_settings.auto_save = AutoSaveMode::OnWindowChange;
+                edit_active_tab(this, TabLocation::Local(path.clone()), "window", window, cx);
+                this.refresh_auto_save(window, cx);
+                this.new_tab(window, cx);
+                this.refresh_auto_save(window, cx);
+            });
+        });
+        visual_cx.run_until_parked();
+        assert!(!path.exists(), "switching tabs must not save in this mode");
+
+        visual_cx.deactivate_window();
+        visual_cx.run_until_parked();
+        assert_eq!(
+            std::fs::read_to_string(&path).ok().as_deref(),
+            Some("window")
+        );
+    }
+
+    #[gpui::test]
+    fn test_untitled_tabs_are_not_auto_saved(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+
+        let tab_id = visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                edit_active_tab(this, TabLocation::Untitled, "draft", window, cx)
+            })
+        });
+        run_idle_auto_save(&fulgur, &mut visual_cx);
+
+        assert!(is_modified(&fulgur, tab_id, &mut visual_cx));
+        assert!(
+            visual_cx.update(|_window, cx| fulgur.read(cx).auto_save_error().is_none()),
+            "skipping an untitled tab is not a failure"
+        );
+    }
+
+    #[gpui::test]
+    fn test_read_only_tabs_are_not_auto_saved(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("locked.txt");
+
+        let tab_id = visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let tab_id =
+                    edit_active_tab(this, TabLocation::Local(path.clone()), "locked", window, cx);
+                this.update_active_editor_tab(cx, |editor_tab, _cx| {
+                    editor_tab.read_only = true;
+                });
+                tab_id
+            })
+        });
+        run_idle_auto_save(&fulgur, &mut visual_cx);
+
+        assert!(!path.exists());
+        assert!(is_modified(&fulgur, tab_id, &mut visual_cx));
+    }
+
+    #[gpui::test]
+    fn test_failed_auto_save_keeps_the_tab_modified(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path: PathBuf = dir.path().join("missing").join("unsaved.txt");
+
+        let tab_id = visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                edit_active_tab(
+                    this,
+                    TabLocation::Local(path.clone()),
+                    "unsaved",
+                    window,
+                    cx,
+                )
+            })
+        });
+        run_idle_auto_save(&fulgur, &mut visual_cx);
+
+        assert!(!path.exists());
+        assert!(
+            is_modified(&fulgur, tab_id, &mut visual_cx),
+            "a failed write must leave the tab modified"
+        );
+        assert!(
+            visual_cx.update(|_window, cx| fulgur.read(cx).auto_save_error().is_some()),
+            "the failure is shown in the status bar"
+        );
+    }
+
+    #[gpui::test]
+    fn test_failed_auto_save_waits_for_the_next_edit(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let folder = dir.path().join("missing");
+        let path: PathBuf = folder.join("retry.txt");
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                edit_active_tab(this, TabLocation::Local(path.clone()), "retry", window, cx);
+            })
+        });
+        run_idle_auto_save(&fulgur, &mut visual_cx);
+        assert!(!path.exists());
+
+        std::fs::create_dir(&folder).expect("failed to create the folder");
+        visual_cx.background_executor.advance_clock(AUTO_SAVE_TICK);
+        visual_cx.run_until_parked();
+        assert!(
+            !path.exists(),
+            "a failed tab must not be retried before it is edited again"
+        );
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                edit_active_tab(this, TabLocation::Local(path.clone()), "edited", window, cx);
+            })
+        });
+        visual_cx.background_executor.advance_clock(AUTO_SAVE_TICK);
+        visual_cx.run_until_parked();
+        assert_eq!(
+            std::fs::read_to_string(&path).ok().as_deref(),
+            Some("edited")
+        );
+    }
+}
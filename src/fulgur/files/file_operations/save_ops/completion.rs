@@ -26,6 +26,8 @@ pub(super) struct SaveCompletion {
     pub(super) byte_len: usize,
     /// Saved baseline captured at dispatch, restored if the write fails
     pub(super) previous_baseline: Option<SavedBaseline>,
+    /// Whether the save was triggered by auto-save, reported in the status bar on failure
+    pub(super) auto_save: bool,
 }
 
 impl Fulgur {
@@ -54,6 +56,10 @@ impl Fulgur {
 
     /// Report a failed background save and roll back the optimistic saved state.
     ///
+    /// ### Description
+    /// On Linux, a save refused for lack of permissions offers to retry with
+    /// elevated privileges instead of only reporting the error.
+    ///
     /// ### Arguments
     /// - `completion`: Dispatch-time context of the save that failed
     /// - `error`: The write error to report
@@ -85,13 +91,20 @@ impl Fulgur {
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("file");
-        window.push_notification(
-            (
-                NotificationType::Error,
-                SharedString::from(format!("Failed to save '{file_name}': {error}")),
-            ),
-            cx,
-        );
+        #[cfg(target_os = "linux")]
+        if !completion.auto_save
+            && crate::fulgur::utils::privileged_write::is_permission_denied(error)
+        {
+            self.show_privileged_save_dialog(completion.tab_id, &completion.path, window, cx);
+            cx.notify();
+            return;
+        }
+        let message = format!("Failed to save '{file_name}': {error}");
+        if completion.auto_save {
+            self.record_auto_save_error(completion.tab_id, message, cx);
+        } else {
+            window.push_notification((NotificationType::Error, SharedString::from(message)), cx);
+        }
         cx.notify();
     }
 }
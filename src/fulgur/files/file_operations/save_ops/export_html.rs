@@ -0,0 +1,176 @@
+use crate::fulgur::{
+    Fulgur,
+    languages::{
+        supported_languages::SupportedLanguage,
+        syntax_spans::{hsla_to_rgb, registry_name_for_tag, syntax_spans},
+    },
+    utils::{
+        atomic_write::atomic_write_file,
+        markdown_export::{
+            ExportPalette, HtmlImages, MarkdownHtml, localize_images, markdown_body_html,
+            palette_css, standalone_html,
+        },
+        markdown_images::rewrite_markdown_image_paths,
+        paths::config_file,
+        sanitize::sanitize_markdown_preview,
+    },
+};
+use gpui::{App, Context, SharedString, Window};
+use gpui_component::{
+    ActiveTheme, WindowExt, highlighter::HighlightTheme, notification::NotificationType,
+};
+use std::path::{Path, PathBuf};
+
+/// File in the configuration directory whose CSS replaces the theme styles of exported documents
+const USER_STYLESHEET: &str = "markdown.css";
+
+/// Get the colors of the current theme for exported and printed documents
+///
+/// ### Arguments
+/// - `cx`: The application context
+///
+/// ### Returns
+/// - `ExportPalette`: The colors of the current theme
+pub(super) fn theme_palette(cx: &App) -> ExportPalette {
+    let theme = cx.theme();
+    ExportPalette {
+        background: hsla_to_rgb(theme.background),
+        foreground: hsla_to_rgb(theme.foreground),
+        muted_background: hsla_to_rgb(theme.muted),
+        muted_foreground: hsla_to_rgb(theme.muted_foreground),
+        border: hsla_to_rgb(theme.border),
+        link: hsla_to_rgb(theme.link),
+    }
+}
+
+/// Get the stylesheet of exported documents
+///
+/// ### Arguments
+/// - `cx`: The application context
+///
+/// ### Returns
+/// - `String`: The user stylesheet when there is one, otherwise CSS from the current theme
+pub(super) fn export_stylesheet(cx: &App) -> String {
+    let user_stylesheet = config_file(USER_STYLESHEET)
+        .ok()
+        .filter(|path| path.is_file());
+    if let Some(path) = user_stylesheet {
+        match std::fs::read_to_string(&path) {
+            Ok(css) => return css,
+            Err(e) => log::warn!("Failed to read {}: {e}", path.display()),
+        }
+    }
+    palette_css(&theme_palette(cx))
+}
+
+/// Render Markdown the way the preview shows it, with code blocks highlighted by the current theme
+///
+/// ### Arguments
+/// - `text`: The Markdown source
+/// - `file_path`: The path of the Markdown file, against which relative image paths resolve
+/// - `highlight_theme`: The syntax theme of the current theme
+///
+/// ### Returns
+/// - `MarkdownHtml`: The HTML body and the local files of its images
+pub(super) fn render_markdown_body(
+    text: &str,
+    file_path: Option<&Path>,
+    highlight_theme: &HighlightTheme,
+) -> MarkdownHtml {
+    let source = sanitize_markdown_preview(&rewrite_markdown_image_paths(
+        text,
+        file_path.and_then(Path::parent),
+    ));
+    markdown_body_html(&source, &|code, lang| {
+        lang.and_then(registry_name_for_tag)
+            .map(|language| syntax_spans(code, &language, highlight_theme))
+            .unwrap_or_default()
+    })
+}
+
+impl Fulgur {
+    /// Export the active Markdown tab as a standalone HTML document
+    ///
+    /// ### Description
+    /// The document is rendered from the same source as the preview, with code
+    /// blocks highlighted by the current theme and local images inlined or
+    /// copied next to the document. The images are read and the document
+    /// written on the background executor.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    /// - `images`: How to include the local images
+    pub fn export_markdown_html(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        images: HtmlImages,
+    ) {
+        let Some(editor_tab) = self.get_active_editor_tab(cx) else {
+            return;
+        };
+        if editor_tab.language != SupportedLanguage::Markdown {
+            window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from("Export as HTML is available for Markdown files"),
+                ),
+                cx,
+            );
+            return;
+        }
+        let file_path = editor_tab.file_path().cloned();
+        let title = file_path.as_deref().and_then(Path::file_stem).map_or_else(
+            || editor_tab.title.to_string(),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+        let markdown = render_markdown_body(
+            editor_tab.content.read(cx).text().to_string().as_str(),
+            file_path.as_deref(),
+            &cx.theme().highlight_theme,
+        );
+        let html = standalone_html(&title, &export_stylesheet(cx), &markdown.body);
+        let image_files = markdown.images;
+        let directory = file_path.as_deref().and_then(Path::parent).map_or_else(
+            || std::env::current_dir().unwrap_or_default(),
+            Path::to_path_buf,
+        );
+        let suggested_name = format!("{title}.html");
+        let path_future = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+        cx.spawn_in(window, async move |_, window| {
+            let path: PathBuf = path_future.await.ok()?.ok()??;
+            let notification = window
+                .background_executor()
+                .spawn(async move {
+                    let localized = localize_images(&html, &image_files, images, &path);
+                    match atomic_write_file(&path, localized.html.as_bytes()) {
+                        Err(e) => {
+                            log::error!("Failed to export {}: {e}", path.display());
+                            (
+                                NotificationType::Error,
+                                SharedString::from(format!("Failed to export: {e}")),
+                            )
+                        }
+                        Ok(()) if !localized.missing_images.is_empty() => (
+                            NotificationType::Warning,
+                            SharedString::from(format!(
+                                "Exported to {}, but {} image(s) could not be included",
+                                path.display(),
+                                localized.missing_images.len()
+                            )),
+                        ),
+                        Ok(()) => (
+                            NotificationType::Success,
+                            SharedString::from(format!("Exported to {}", path.display())),
+                        ),
+                    }
+                })
+                .await;
+            window
+                .update(|window, cx| window.push_notification(notification, cx))
+                .ok()
+        })
+        .detach();
+    }
+}
@@ -1,5 +1,11 @@
+mod auto_save;
 mod completion;
+mod export_html;
 mod notifications;
 mod print;
 mod save_as;
 mod save_local;
+#[cfg(target_os = "linux")]
+mod save_privileged;
+
+pub use auto_save::AutoSaveState;
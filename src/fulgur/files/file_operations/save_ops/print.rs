@@ -1,13 +1,35 @@
-use crate::fulgur::{Fulgur, tab::Tab};
+use super::export_html::{export_stylesheet, render_markdown_body, theme_palette};
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::read_text_and_selection,
+    languages::{
+        supported_languages::{SupportedLanguage, language_registry_name},
+        syntax_spans::syntax_spans,
+    },
+    tab::Tab,
+    ui::components_utils::format_system_time,
+    utils::{
+        markdown_export::{HtmlImages, localize_images},
+        print_html::{
+            PrintHeader, code_lines_html, code_print_css, page_css, print_document,
+            print_header_html,
+        },
+    },
+};
 use gpui::{Context, SharedString, Window};
-use gpui_component::{WindowExt, notification::NotificationType};
+use gpui_component::{ActiveTheme, WindowExt, notification::NotificationType};
+use std::time::SystemTime;
 
 impl Fulgur {
     /// Open the native OS print dialog for the current document
     ///
-    /// Writes the active tab's content to a temporary HTML file and opens it with
-    /// the system's default browser, which automatically triggers the native print dialog.
-    /// This approach works cross-platform without requiring OS-specific print APIs.
+    /// Writes the selection of the active tab, or its whole content without one,
+    /// to a temporary HTML file and opens it with the system's default browser,
+    /// which automatically triggers the native print dialog. Source code keeps
+    /// the syntax colors of the current theme and Markdown is rendered as in the
+    /// preview. This approach works cross-platform without requiring OS-specific
+    /// print APIs. The document is built and written on the background
+    /// executor.
     ///
     /// ### Arguments
     /// - `window`: The window containing the editor
@@ -16,59 +38,86 @@ impl Fulgur {
         let Some(active_tab_index) = self.active_tab_index(cx) else {
             return;
         };
-        let (title, content) = match self.tabs[active_tab_index].read(cx) {
-            Tab::Editor(editor_tab) => {
-                let title = editor_tab.title.clone();
-                let content = editor_tab.content.read(cx).text().to_string();
-                (title, content)
-            }
-            Tab::Settings(_) | Tab::MarkdownPreview(_) => return,
+        let (title, content, language, file_path) = match self.tabs[active_tab_index].read(cx) {
+            Tab::Editor(editor_tab) => (
+                editor_tab.title.to_string(),
+                editor_tab.content.clone(),
+                editor_tab.language,
+                editor_tab.file_path().cloned(),
+            ),
+            Tab::Settings(_) | Tab::MarkdownPreview(_) | Tab::LargeFile(_) => return,
         };
-        let escaped_content = content
-            .replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;");
-        let escaped_title = title
-            .replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;");
-        let html = format!(
-            r#"<!DOCTYPE html>
-<html>
-<head>
-<meta charset="utf-8">
-<title>{escaped_title}</title>
-<style>
-  body {{ margin: 0; padding: 1em; font-family: monospace; white-space: pre-wrap; word-wrap: break-word; }}
-  @media print {{ body {{ margin: 0; }} }}
-</style>
-</head>
-<body>{escaped_content}</body>
-<script>window.onload = function() {{ window.print(); }};</script>
-</html>"#,
-        );
-        let temp_path =
-            std::env::temp_dir().join(format!("fulgur_print_{}.html", std::process::id()));
-        if let Err(e) = std::fs::write(&temp_path, html.as_bytes()) {
-            log::error!("Failed to write print temp file: {e}");
-            window.push_notification(
-                (
-                    NotificationType::Error,
-                    SharedString::from(format!("Failed to prepare print: {e}")),
-                ),
-                cx,
-            );
-            return;
-        }
-        if let Err(e) = open::that(&temp_path) {
-            log::error!("Failed to open print file: {e}");
-            window.push_notification(
-                (
-                    NotificationType::Error,
-                    SharedString::from(format!("Failed to open print dialog: {e}")),
-                ),
-                cx,
-            );
-        }
+        let (text, selection) = read_text_and_selection(&content, window, cx);
+        let options = self.settings.editor_settings.print_settings.clone();
+        let palette = theme_palette(cx);
+        let highlight_theme = cx.theme().highlight_theme.clone();
+        let stylesheet = if language == SupportedLanguage::Markdown {
+            export_stylesheet(cx)
+        } else {
+            code_print_css(&palette, &self.settings.editor_settings.font_family)
+        };
+        let header = options.header.then(|| PrintHeader {
+            file_name: title.clone(),
+            path: file_path.as_ref().map(|path| path.display().to_string()),
+            date: format_system_time(SystemTime::now()).unwrap_or_default(),
+        });
+        cx.spawn_in(window, async move |_, window| {
+            let printed = window
+                .background_executor()
+                .spawn(async move {
+                    let mut css = page_css(&palette, options.footer);
+                    css.push_str(&stylesheet);
+                    let mut body = header.as_ref().map(print_header_html).unwrap_or_default();
+                    let temp_path = std::env::temp_dir()
+                        .join(format!("fulgur_print_{}.html", std::process::id()));
+                    let html = if language == SupportedLanguage::Markdown {
+                        let source = text.get(selection).filter(|selected| !selected.is_empty());
+                        let markdown = render_markdown_body(
+                            source.unwrap_or(&text),
+                            file_path.as_deref(),
+                            &highlight_theme,
+                        );
+                        body.push_str("<main>\n");
+                        body.push_str(&markdown.body);
+                        body.push_str("</main>\n");
+                        let html = print_document(&title, &css, &body);
+                        localize_images(&html, &markdown.images, HtmlImages::Inline, &temp_path)
+                            .html
+                    } else {
+                        let spans = syntax_spans(
+                            &text,
+                            language_registry_name(&language),
+                            &highlight_theme,
+                        );
+                        body.push_str(&code_lines_html(
+                            &text,
+                            &spans,
+                            selection,
+                            options.line_numbers,
+                        ));
+                        print_document(&title, &css, &body)
+                    };
+                    if let Err(e) = std::fs::write(&temp_path, html.as_bytes()) {
+                        log::error!("Failed to write print temp file: {e}");
+                        return Err(format!("Failed to prepare print: {e}"));
+                    }
+                    open::that(&temp_path).map_err(|e| {
+                        log::error!("Failed to open print file: {e}");
+                        format!("Failed to open print dialog: {e}")
+                    })
+                })
+                .await;
+            if let Err(message) = printed {
+                window
+                    .update(|window, cx| {
+                        window.push_notification(
+                            (NotificationType::Error, SharedString::from(message)),
+                            cx,
+                        );
+                    })
+                    .ok();
+            }
+        })
+        .detach();
     }
 }
@@ -34,7 +34,7 @@ impl Fulgur {
                     let suggested = editor_tab.get_suggested_filename();
                     (editor_tab.id, editor_tab.encoding.clone(), dir, suggested)
                 }
-                Tab::Settings(_) | Tab::MarkdownPreview(_) => return,
+                Tab::Settings(_) | Tab::MarkdownPreview(_) | Tab::LargeFile(_) => return,
             };
         let path_future = cx.prompt_for_new_path(&directory, suggested_filename.as_deref());
         cx.spawn_in(window, async move |view, window| {
@@ -117,6 +117,7 @@ impl Fulgur {
             byte_len: bytes.len(),
             previous_baseline: self.capture_saved_baseline(tab_id, cx),
             path: path.to_path_buf(),
+            auto_save: false,
         };
         self.update_editor_tab(tab_id, cx, |editor_tab, cx| {
             editor_tab.mark_as_saved(cx);
@@ -181,7 +182,6 @@ impl Fulgur {
                 self.file_watch_state
                     .last_file_saves
                     .insert(path.to_path_buf(), std::time::Instant::now());
-                let settings = self.settings.editor_settings.clone();
                 tab_entity.update(cx, |tab, cx| {
                     let Some(editor_tab) = tab.as_editor_mut() else {
                         return;
@@ -195,8 +195,7 @@ impl Fulgur {
                         .into();
                     editor_tab.encoding = encoding;
                     editor_tab.update_file_tooltip_cache(byte_len);
-                    tab.update_language(window, cx, &settings);
-                    cx.notify();
+                    tab.update_language(cx);
                 });
                 cx.notify();
                 self.watch_file(path);
@@ -5,7 +5,7 @@ use crate::fulgur::{
     Fulgur, editor_tab::TabLocation, tab::Tab, utils::atomic_write::atomic_write_file,
 };
 use gpui::{Context, Window};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 impl Fulgur {
     /// Save a file
@@ -29,7 +29,7 @@ impl Fulgur {
                 editor_tab.encoding.clone(),
                 editor_tab.lossy_decode,
             ),
-            Tab::Settings(_) | Tab::MarkdownPreview(_) => return,
+            Tab::Settings(_) | Tab::MarkdownPreview(_) | Tab::LargeFile(_) => return,
         };
         if matches!(location, TabLocation::Untitled) {
             self.save_file_as(window, cx);
@@ -52,7 +52,7 @@ impl Fulgur {
         };
         match location {
             TabLocation::Local(path) => {
-                self.spawn_local_save(tab_id, path, bytes, window, cx);
+                self.spawn_local_save(tab_id, path, bytes, false, window, cx);
             }
             TabLocation::Remote(spec) => {
                 self.save_remote_file(window, cx, tab_id, spec, contents, bytes);
@@ -67,16 +67,52 @@ impl Fulgur {
     /// - `tab_id`: Stable identifier of the editor tab being saved
     /// - `path`: Destination path of the local file
     /// - `bytes`: The already-encoded file contents
+    /// - `auto_save`: Whether the save was triggered by auto-save rather than the user
     /// - `window`: The window context
     /// - `cx`: The application context
-    fn spawn_local_save(
+    pub(super) fn spawn_local_save(
         &mut self,
         tab_id: TabId,
         path: PathBuf,
         bytes: Vec<u8>,
+        auto_save: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.spawn_local_write(
+            tab_id,
+            path,
+            bytes,
+            auto_save,
+            atomic_write_file,
+            window,
+            cx,
+        );
+    }
+
+    /// Dispatch a background write of a local tab's encoded content.
+    ///
+    /// ### Arguments
+    /// - `tab_id`: Stable identifier of the editor tab being saved
+    /// - `path`: Destination path of the local file
+    /// - `bytes`: The already-encoded file contents
+    /// - `auto_save`: Whether the save was triggered by auto-save rather than the user
+    /// - `write`: The function performing the write on the background executor
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn spawn_local_write<F>(
+        &mut self,
+        tab_id: TabId,
+        path: PathBuf,
+        bytes: Vec<u8>,
+        auto_save: bool,
+        write: F,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) where
+        F: FnOnce(&Path, &[u8]) -> anyhow::Result<()> + Send + 'static,
+    {
         if self.inflight_saves.contains_key(&tab_id) {
             log::debug!(
                 "Save already in flight for {}; skipping duplicate save",
@@ -91,6 +127,7 @@ impl Fulgur {
             byte_len: bytes.len(),
             previous_baseline: self.capture_saved_baseline(tab_id, cx),
             path,
+            auto_save,
         };
         self.update_editor_tab(tab_id, cx, |editor_tab, cx| {
             editor_tab.mark_as_saved(cx);
@@ -101,7 +138,7 @@ impl Fulgur {
             let write_path = completion.path.clone();
             let write_result = window
                 .background_executor()
-                .spawn(async move { atomic_write_file(&write_path, &bytes) })
+                .spawn(async move { write(&write_path, &bytes) })
                 .await;
             window
                 .update(|window, cx| {
@@ -118,7 +155,7 @@ impl Fulgur {
     ///
     /// ### Arguments
     /// - `completion`: Dispatch-time context of the save being completed
-    /// - `write_result`: The result of the background `atomic_write_file` call
+    /// - `write_result`: The result of the background write
     /// - `window`: The window context
     /// - `cx`: The application context
     fn finish_local_save(
@@ -146,6 +183,8 @@ impl Fulgur {
                     editor_tab.update_file_tooltip_cache(byte_len);
                     cx.notify();
                 });
+                self.clear_auto_save_error(cx);
+                self.forget_available_tasks();
                 cx.notify();
             }
             Err(e) => {
@@ -0,0 +1,62 @@
+use super::super::{EncodedContents, encode_for_save};
+use crate::fulgur::ui::tabs::tab::TabId;
+use crate::fulgur::{
+    Fulgur, editor_tab::TabLocation, utils::privileged_write::privileged_write_file,
+};
+use gpui::{Context, SharedString, Window};
+use gpui_component::{WindowExt, notification::NotificationType};
+
+impl Fulgur {
+    /// Save a local tab through the configured privileged save helper
+    ///
+    /// ### Description
+    /// Used after a regular save was refused for lack of permissions. The tab
+    /// content is encoded again, then written as root by the helper while
+    /// keeping the owner and mode of the destination file.
+    ///
+    /// ### Arguments
+    /// - `tab_id`: Stable identifier of the editor tab to save
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn save_file_with_privileges(
+        &mut self,
+        tab_id: TabId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab) = self.tab_entity_of(tab_id, cx) else {
+            return;
+        };
+        let Some(editor_tab) = tab.read(cx).as_editor() else {
+            return;
+        };
+        let TabLocation::Local(path) = editor_tab.location.clone() else {
+            return;
+        };
+        let contents = editor_tab.content.read(cx).text().to_string();
+        let EncodedContents::Encoded(bytes) = encode_for_save(&contents, &editor_tab.encoding)
+        else {
+            let message = format!(
+                "Cannot save '{}' with elevated privileges: content is not representable in {}",
+                path.display(),
+                editor_tab.encoding
+            );
+            window.push_notification((NotificationType::Error, SharedString::from(message)), cx);
+            return;
+        };
+        let helper = self.settings.app_settings.privileged_save_helper.clone();
+        log::info!(
+            "Saving {} with elevated privileges through '{helper}'",
+            path.display()
+        );
+        self.spawn_local_write(
+            tab_id,
+            path,
+            bytes,
+            false,
+            move |path, bytes| privileged_write_file(&helper, path, bytes),
+            window,
+            cx,
+        );
+    }
+}
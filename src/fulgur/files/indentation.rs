@@ -0,0 +1,391 @@
+use crate::fulgur::settings::{EditorSettings, IndentationPreference};
+
+/// Maximum number of lines sampled when detecting the indentation of a file
+const MAX_SAMPLED_LINES: usize = 10_000;
+
+/// Widths offered by the status bar override menu
+pub const INDENT_WIDTHS: [usize; 4] = [2, 3, 4, 8];
+
+/// Largest indentation step considered when detecting the width
+const MAX_DETECTED_WIDTH: usize = 8;
+
+/// Where the indentation of a tab comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentationSource {
+    /// The global `tab_size` and `use_spaces` settings.
+    Settings,
+    /// Inferred from the content of the file.
+    Detected,
+    /// Set for the language of the tab in `language_indentation`.
+    Language,
+    /// Chosen by the user for this tab, kept across reloads.
+    Override,
+}
+
+/// A change of indentation requested from the status bar menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentationChange {
+    /// Indent with spaces (`true`) or tabs (`false`).
+    UseSpaces(bool),
+    /// Use this indentation width.
+    Width(usize),
+    /// Drop any override and infer the indentation from the content again.
+    Detect,
+}
+
+/// Indent style and width of an editor tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indentation {
+    pub width: usize,
+    pub use_spaces: bool,
+    pub source: IndentationSource,
+}
+
+impl Indentation {
+    /// Get the indentation configured in the settings
+    ///
+    /// ### Arguments
+    /// - `settings`: The editor settings
+    ///
+    /// ### Returns
+    /// - `Indentation`: The global indentation
+    #[must_use]
+    pub fn from_settings(settings: &EditorSettings) -> Self {
+        Self {
+            width: settings.tab_size,
+            use_spaces: settings.use_spaces,
+            source: IndentationSource::Settings,
+        }
+    }
+
+    /// Resolve the indentation of a file from its content and the settings
+    ///
+    /// ### Description
+    /// From lowest to highest precedence: the global settings, the indentation
+    /// detected from the content (unless detection is disabled or the content
+    /// is not indented), then the settings of the language. A language setting
+    /// only the style or the width leaves the other to the sources below it.
+    /// Tab-indented files keep the width from below, since tabs do not reveal it.
+    ///
+    /// ### Arguments
+    /// - `content`: The content of the file
+    /// - `language`: The grammar name of the language of the file
+    /// - `settings`: The editor settings
+    ///
+    /// ### Returns
+    /// - `Indentation`: The indentation to use for the tab
+    #[must_use]
+    pub fn resolve(content: &str, language: &str, settings: &EditorSettings) -> Self {
+        let from_settings = Self::from_settings(settings);
+        let inferred = if settings.detect_indentation {
+            Self::detected_or(content, from_settings)
+        } else {
+            from_settings
+        };
+        inferred.configured(language, settings)
+    }
+
+    /// Layer the language settings over an indentation
+    ///
+    /// ### Arguments
+    /// - `language`: The grammar name of the language of the file
+    /// - `settings`: The editor settings
+    ///
+    /// ### Returns
+    /// - `Indentation`: The indentation with the configured parts replaced
+    fn configured(self, language: &str, settings: &EditorSettings) -> Self {
+        match settings.language_indentation.get(language) {
+            Some(preference) => self.preferred(*preference, IndentationSource::Language),
+            None => self,
+        }
+    }
+
+    /// Replace the parts of the indentation a preference sets
+    ///
+    /// ### Arguments
+    /// - `preference`: The style and/or width to use
+    /// - `source`: Where the preference comes from
+    ///
+    /// ### Returns
+    /// - `Indentation`: The updated indentation, or `self` if the preference is empty
+    fn preferred(self, preference: IndentationPreference, source: IndentationSource) -> Self {
+        if preference.is_empty() {
+            return self;
+        }
+        Self {
+            width: preference.tab_size.unwrap_or(self.width),
+            use_spaces: preference.use_spaces.unwrap_or(self.use_spaces),
+            source,
+        }
+    }
+
+    /// Detect the indentation of the content, falling back when it is not indented
+    ///
+    /// ### Arguments
+    /// - `content`: The content of the file
+    /// - `fallback`: The indentation to use when nothing is detected, whose width
+    ///   is also kept for tab-indented content
+    ///
+    /// ### Returns
+    /// - `Indentation`: The detected indentation, or `fallback`
+    fn detected_or(content: &str, fallback: Self) -> Self {
+        match detect_indentation(content) {
+            Some(DetectedIndentation::Tabs) => Self {
+                use_spaces: false,
+                source: IndentationSource::Detected,
+                ..fallback
+            },
+            Some(DetectedIndentation::Spaces(width)) => Self {
+                width,
+                use_spaces: true,
+                source: IndentationSource::Detected,
+                ..fallback
+            },
+            None => fallback,
+        }
+    }
+
+    /// Apply a change requested from the status bar menu
+    ///
+    /// ### Description
+    /// Choosing a style or width overrides the indentation for the tab, so it
+    /// survives reloads. Detecting again infers it from the content even when
+    /// detection is disabled in the settings, since the user asked for it, but
+    /// the language settings still win over it.
+    ///
+    /// ### Arguments
+    /// - `change`: The requested change
+    /// - `content`: The current content of the tab
+    /// - `language`: The grammar name of the language of the tab
+    /// - `settings`: The editor settings
+    ///
+    /// ### Returns
+    /// - `Indentation`: The new indentation of the tab
+    #[must_use]
+    pub fn changed(
+        self,
+        change: IndentationChange,
+        content: &str,
+        language: &str,
+        settings: &EditorSettings,
+    ) -> Self {
+        match change {
+            IndentationChange::UseSpaces(use_spaces) => Self {
+                use_spaces,
+                source: IndentationSource::Override,
+                ..self
+            },
+            IndentationChange::Width(width) => Self {
+                width,
+                source: IndentationSource::Override,
+                ..self
+            },
+            IndentationChange::Detect => Self::detected_or(content, Self::from_settings(settings))
+                .configured(language, settings),
+        }
+    }
+
+    /// Describe the indentation for the status bar
+    ///
+    /// ### Returns
+    /// - `String`: e.g. `Spaces: 2` or `Tab Size: 4`
+    #[must_use]
+    pub fn label(&self) -> String {
+        if self.use_spaces {
+            format!("Spaces: {}", self.width)
+        } else {
+            format!("Tab Size: {}", self.width)
+        }
+    }
+
+    /// Describe where the indentation comes from, for the status bar tooltip
+    ///
+    /// ### Returns
+    /// - `&'static str`: e.g. `Detected from the content`
+    #[must_use]
+    pub fn source_label(&self) -> &'static str {
+        match self.source {
+            IndentationSource::Settings => "From the settings",
+            IndentationSource::Detected => "Detected from the content",
+            IndentationSource::Language => "Set for the language in the settings",
+            IndentationSource::Override => "Chosen for this tab",
+        }
+    }
+}
+
+/// Indentation inferred from the content of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedIndentation {
+    Tabs,
+    Spaces(usize),
+}
+
+/// Infer the indent style and width from the leading whitespace of the lines
+///
+/// ### Description
+/// Lines indented with tabs are weighed against lines indented with spaces. For
+/// spaces, the width is the most frequent increase of indentation between
+/// consecutive lines, so aligned continuation lines do not skew the result.
+/// Lines continuing a block comment (` * text`) are ignored.
+///
+/// ### Arguments
+/// - `content`: The content of the file
+///
+/// ### Returns
+/// - `Some(DetectedIndentation)`: The dominant indentation
+/// - `None`: If the content has no indented line to learn from
+#[must_use]
+pub fn detect_indentation(content: &str) -> Option<DetectedIndentation> {
+    let mut tab_lines = 0usize;
+    let mut space_lines = 0usize;
+    let mut steps = [0usize; MAX_DETECTED_WIDTH + 1];
+    let mut previous_indent = 0usize;
+    for line in content.lines().take(MAX_SAMPLED_LINES) {
+        let text = line.trim_start_matches([' ', '\t']);
+        if text.is_empty() {
+            continue;
+        }
+        let leading = &line[..line.len() - text.len()];
+        if leading.starts_with('\t') {
+            tab_lines += 1;
+            continue;
+        }
+        if leading.contains('\t') || text.starts_with('*') {
+            continue;
+        }
+        let indent = leading.len();
+        if indent > 0 {
+            space_lines += 1;
+        }
+        if indent > previous_indent && indent - previous_indent <= MAX_DETECTED_WIDTH {
+            steps[indent - previous_indent] += 1;
+        }
+        previous_indent = indent;
+    }
+    if tab_lines == 0 && space_lines == 0 {
+        return None;
+    }
+    if tab_lines > space_lines {
+        return Some(DetectedIndentation::Tabs);
+    }
+    let mut best: Option<(usize, usize)> = None;
+    for (width, &count) in steps.iter().enumerate().skip(2) {
+        if count > 0 && best.is_none_or(|(_, best_count)| count > best_count) {
+            best = Some((width, count));
+        }
+    }
+    best.map(|(width, _)| DetectedIndentation::Spaces(width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DetectedIndentation, Indentation, IndentationChange, IndentationSource, detect_indentation,
+    };
+    use crate::fulgur::settings::{EditorSettings, IndentationPreference};
+
+    #[test]
+    fn detect_indentation_finds_two_space_yaml() {
+        let yaml = "server:\n  host: localhost\n  ports:\n    - 80\n    - 443\nname: demo\n";
+
+        assert_eq!(
+            detect_indentation(yaml),
+            Some(DetectedIndentation::Spaces(2))
+        );
+    }
+
+    #[test]
+    fn detect_indentation_finds_tab_indented_makefile() {
+        let makefile = "all: build\n\nbuild:\n\tcargo build\n\tcargo test\n";
+
+        assert_eq!(
+            detect_indentation(makefile),
+            Some(DetectedIndentation::Tabs)
+        );
+    }
+
+    #[test]
+    fn detect_indentation_ignores_alignment_and_block_comments() {
+        let code = "/**\n * Docs\n */\nfn main() {\n    let x = call(a,\n                 b);\n    if x {\n        run();\n    }\n}\n";
+
+        assert_eq!(
+            detect_indentation(code),
+            Some(DetectedIndentation::Spaces(4))
+        );
+    }
+
+    #[test]
+    fn detect_indentation_returns_none_without_indented_lines() {
+        assert_eq!(detect_indentation("one\ntwo\n\nthree\n"), None);
+        assert_eq!(detect_indentation(""), None);
+    }
+
+    #[test]
+    fn resolve_prefers_detection_unless_disabled() {
+        let mut settings = EditorSettings::new();
+        settings.tab_size = 4;
+        settings.use_spaces = true;
+        let makefile = "build:\n\tcargo build\n";
+
+        let detected = Indentation::resolve(makefile, "make", &settings);
+        assert_eq!(detected.source, IndentationSource::Detected);
+        assert!(!detected.use_spaces);
+        assert_eq!(detected.width, 4);
+        assert_eq!(detected.label(), "Tab Size: 4");
+
+        settings.detect_indentation = false;
+        let configured = Indentation::resolve(makefile, "make", &settings);
+        assert_eq!(configured, Indentation::from_settings(&settings));
+        assert_eq!(configured.label(), "Spaces: 4");
+    }
+
+    #[test]
+    fn changed_overrides_until_detected_again() {
+        let mut settings = EditorSettings::new();
+        settings.detect_indentation = false;
+        let yaml = "root:\n  child: 1\n";
+        let initial = Indentation::resolve(yaml, "yaml", &settings);
+
+        let overridden = initial
+            .changed(IndentationChange::UseSpaces(false), yaml, "yaml", &settings)
+            .changed(IndentationChange::Width(8), yaml, "yaml", &settings);
+        assert_eq!(overridden.source, IndentationSource::Override);
+        assert!(!overridden.use_spaces);
+        assert_eq!(overridden.width, 8);
+
+        let detected = overridden.changed(IndentationChange::Detect, yaml, "yaml", &settings);
+        assert_eq!(detected.source, IndentationSource::Detected);
+        assert!(detected.use_spaces);
+        assert_eq!(detected.width, 2);
+    }
+
+    #[test]
+    fn resolve_layers_language_settings_over_detection() {
+        let mut settings = EditorSettings::new();
+        settings.tab_size = 4;
+        settings.use_spaces = true;
+        let python = "def main():\n  return 1\n";
+
+        let detected = Indentation::resolve(python, "python", &settings);
+        assert_eq!(detected.source, IndentationSource::Detected);
+        assert_eq!(detected.width, 2);
+
+        settings.language_indentation.insert(
+            "python".to_string(),
+            IndentationPreference {
+                tab_size: Some(4),
+                use_spaces: None,
+            },
+        );
+        let configured = Indentation::resolve(python, "python", &settings);
+        assert_eq!(configured.source, IndentationSource::Language);
+        assert_eq!((configured.width, configured.use_spaces), (4, true));
+
+        let overridden =
+            configured.changed(IndentationChange::Width(8), python, "python", &settings);
+        assert_eq!(overridden.source, IndentationSource::Override);
+        let detected_again =
+            overridden.changed(IndentationChange::Detect, python, "python", &settings);
+        assert_eq!(detected_again, configured);
+    }
+}
@@ -0,0 +1,567 @@
+//! Sparse line index and paged reads backing the large-file viewer.
+//!
+//! Only the byte offset of every [`LINES_PER_CHECKPOINT`]-th line is kept, so
+//! the index of a multi-gigabyte file stays within a few megabytes. Any line is
+//! reached by seeking to the closest checkpoint and scanning forward.
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Number of lines between two recorded line-start offsets.
+pub const LINES_PER_CHECKPOINT: usize = 256;
+
+/// Number of bytes scanned by one [`scan_chunk`] call.
+pub const SCAN_CHUNK_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Longest line prefix kept for display; the rest of a longer line is skipped.
+pub const MAX_DISPLAY_LINE_BYTES: usize = 4096;
+
+/// Size of the buffer used for sequential reads.
+const READ_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// Sparse index of line-start offsets, filled incrementally by [`scan_chunk`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineIndex {
+    checkpoints: Vec<u64>, // Byte offset of lines 0, LINES_PER_CHECKPOINT, 2 * LINES_PER_CHECKPOINT, ...
+    newlines: usize,       // Newlines found in the scanned prefix
+    scanned_bytes: u64,    // Length of the scanned prefix
+    file_len: u64,         // Length of the file when indexing started
+}
+
+/// Result of scanning one slice of the file, merged with [`LineIndex::apply`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexChunk {
+    checkpoints: Vec<u64>,
+    newlines: usize,
+    end: u64,
+}
+
+impl LineIndex {
+    /// Create an empty index for a file of the given length
+    ///
+    /// ### Arguments
+    /// - `file_len`: The length of the file in bytes
+    ///
+    /// ### Returns
+    /// - `LineIndex`: An index knowing only that line 0 starts at offset 0
+    #[must_use]
+    pub fn new(file_len: u64) -> Self {
+        Self {
+            checkpoints: vec![0],
+            newlines: 0,
+            scanned_bytes: 0,
+            file_len,
+        }
+    }
+
+    /// Merge a scanned chunk into the index
+    ///
+    /// ### Description
+    /// A chunk that made no progress means the file ended early, e.g. because it
+    /// was truncated after opening, so the index is completed at that point.
+    ///
+    /// ### Arguments
+    /// - `chunk`: The chunk returned by `scan_chunk` for the current scan position
+    pub fn apply(&mut self, chunk: IndexChunk) {
+        self.checkpoints.extend(chunk.checkpoints);
+        self.newlines += chunk.newlines;
+        if chunk.end <= self.scanned_bytes {
+            self.file_len = self.scanned_bytes;
+        }
+        self.scanned_bytes = chunk.end;
+    }
+
+    /// Check whether the whole file has been scanned
+    ///
+    /// ### Returns
+    /// - `true`: if every byte of the file has been indexed
+    /// - `false`: if indexing is still in progress
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.scanned_bytes >= self.file_len
+    }
+
+    /// Get the number of lines known so far
+    ///
+    /// ### Returns
+    /// - `usize`: The number of lines in the scanned prefix, counting the line in progress
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.newlines + 1
+    }
+
+    /// Get the length of the indexed file
+    ///
+    /// ### Returns
+    /// - `u64`: The file length in bytes
+    #[must_use]
+    pub fn file_len(&self) -> u64 {
+        self.file_len
+    }
+
+    /// Get the position the next scan resumes from
+    ///
+    /// ### Returns
+    /// - `(u64, usize)`: The scanned byte count and the number of newlines found so far
+    #[must_use]
+    pub fn scan_position(&self) -> (u64, usize) {
+        (self.scanned_bytes, self.newlines)
+    }
+
+    /// Get the indexing progress
+    ///
+    /// ### Returns
+    /// - `f32`: The scanned fraction of the file, between 0.0 and 1.0
+    #[must_use]
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn progress(&self) -> f32 {
+        if self.file_len == 0 {
+            1.0
+        } else {
+            (self.scanned_bytes as f64 / self.file_len as f64).min(1.0) as f32
+        }
+    }
+
+    /// Find the closest recorded line start at or before a line
+    ///
+    /// ### Arguments
+    /// - `line`: The zero-based line number
+    ///
+    /// ### Returns
+    /// - `(usize, u64)`: The line number and byte offset of the checkpoint
+    fn checkpoint_before(&self, line: usize) -> (usize, u64) {
+        let slot = (line / LINES_PER_CHECKPOINT).min(self.checkpoints.len() - 1);
+        (slot * LINES_PER_CHECKPOINT, self.checkpoints[slot])
+    }
+}
+
+/// Scan a slice of the file for newlines, starting where the index stopped
+///
+/// ### Arguments
+/// - `path`: The path of the indexed file
+/// - `start`: The byte offset to resume from
+/// - `newlines_before`: The number of newlines found before `start`
+/// - `max_bytes`: The maximum number of bytes to scan
+///
+/// ### Errors
+/// Returns an error if the file cannot be opened, seeked or read.
+///
+/// ### Returns
+/// - `Ok(IndexChunk)`: The line starts found in the slice
+pub fn scan_chunk(
+    path: &Path,
+    start: u64,
+    newlines_before: usize,
+    max_bytes: u64,
+) -> io::Result<IndexChunk> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut reader = file.take(max_bytes);
+    let mut buffer = vec![0u8; READ_BUFFER_BYTES];
+    let mut chunk = IndexChunk {
+        checkpoints: Vec::new(),
+        newlines: 0,
+        end: start,
+    };
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        for (position, _) in buffer[..read]
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'\n')
+        {
+            chunk.newlines += 1;
+            if (newlines_before + chunk.newlines).is_multiple_of(LINES_PER_CHECKPOINT) {
+                chunk.checkpoints.push(chunk.end + position as u64 + 1);
+            }
+        }
+        chunk.end += read as u64;
+    }
+    Ok(chunk)
+}
+
+/// Open a reader positioned at the start of a line
+///
+/// ### Arguments
+/// - `path`: The path of the indexed file
+/// - `index`: The line index of the file
+/// - `line`: The zero-based line to position the reader at
+///
+/// ### Errors
+/// Returns an error if the file cannot be opened, seeked or read.
+///
+/// ### Returns
+/// - `Ok((BufReader<File>, u64))`: The reader and the byte offset of the line,
+///   or of the end of the file if it has fewer lines
+fn reader_at_line(
+    path: &Path,
+    index: &LineIndex,
+    line: usize,
+) -> io::Result<(BufReader<File>, u64)> {
+    let (mut current, offset) = index.checkpoint_before(line);
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut reader = BufReader::with_capacity(READ_BUFFER_BYTES, file);
+    let mut position = offset;
+    while current < line {
+        let skipped = skip_line(&mut reader)?;
+        if skipped == 0 {
+            break;
+        }
+        position += skipped;
+        current += 1;
+    }
+    Ok((reader, position))
+}
+
+/// Advance a reader past the next newline
+///
+/// ### Arguments
+/// - `reader`: The reader to advance
+///
+/// ### Errors
+/// Returns an error if the underlying read fails.
+///
+/// ### Returns
+/// - `Ok(u64)`: The number of bytes skipped, 0 at the end of the file
+fn skip_line(reader: &mut impl BufRead) -> io::Result<u64> {
+    let mut skipped = 0u64;
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(skipped);
+        }
+        if let Some(newline) = buffer.iter().position(|byte| *byte == b'\n') {
+            reader.consume(newline + 1);
+            return Ok(skipped + newline as u64 + 1);
+        }
+        let len = buffer.len();
+        reader.consume(len);
+        skipped += len as u64;
+    }
+}
+
+/// Read a range of lines for display
+///
+/// ### Description
+/// Lines are decoded as UTF-8 with invalid sequences replaced, trailing `\r`
+/// is dropped and lines longer than `MAX_DISPLAY_LINE_BYTES` are truncated, so
+/// memory stays bounded whatever the file looks like.
+///
+/// ### Arguments
+/// - `path`: The path of the indexed file
+/// - `index`: The line index of the file
+/// - `first_line`: The zero-based first line to read
+/// - `count`: The maximum number of lines to read
+///
+/// ### Errors
+/// Returns an error if the file cannot be opened, seeked or read.
+///
+/// ### Returns
+/// - `Ok(Vec<String>)`: The lines, fewer than `count` at the end of the file
+pub fn read_lines(
+    path: &Path,
+    index: &LineIndex,
+    first_line: usize,
+    count: usize,
+) -> io::Result<Vec<String>> {
+    let (mut reader, _) = reader_at_line(path, index, first_line)?;
+    let mut lines = Vec::with_capacity(count);
+    let mut line = Vec::with_capacity(MAX_DISPLAY_LINE_BYTES);
+    while lines.len() < count {
+        line.clear();
+        let mut reached_newline = false;
+        loop {
+            let buffer = reader.fill_buf()?;
+            if buffer.is_empty() {
+                break;
+            }
+            let (take, consumed) = match buffer.iter().position(|byte| *byte == b'\n') {
+                Some(newline) => {
+                    reached_newline = true;
+                    (newline, newline + 1)
+                }
+                None => (buffer.len(), buffer.len()),
+            };
+            let room = MAX_DISPLAY_LINE_BYTES.saturating_sub(line.len());
+            line.extend_from_slice(&buffer[..take.min(room)]);
+            reader.consume(consumed);
+            if reached_newline {
+                break;
+            }
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        lines.push(String::from_utf8_lossy(&line).into_owned());
+        if !reached_newline {
+            break;
+        }
+    }
+    Ok(lines)
+}
+
+/// Find the next line containing a query, wrapping around at the end of the file
+///
+/// ### Description
+/// The file is streamed in fixed-size blocks, so matches inside very long lines
+/// are found without loading those lines. Case-insensitive matching folds ASCII
+/// letters only.
+///
+/// ### Arguments
+/// - `path`: The path of the indexed file
+/// - `index`: The line index of the file
+/// - `from_line`: The zero-based line the search starts at, inclusive
+/// - `query`: The text to look for
+/// - `match_case`: Whether letter case must match
+///
+/// ### Errors
+/// Returns an error if the file cannot be opened, seeked or read.
+///
+/// ### Returns
+/// - `Ok(Some(usize))`: The zero-based line of the next match
+/// - `Ok(None)`: If the query is empty or found nowhere
+pub fn find_next(
+    path: &Path,
+    index: &LineIndex,
+    from_line: usize,
+    query: &str,
+    match_case: bool,
+) -> io::Result<Option<usize>> {
+    if query.is_empty() {
+        return Ok(None);
+    }
+    let needle = normalize(query.as_bytes(), match_case);
+    let (reader, start) = reader_at_line(path, index, from_line)?;
+    if let Some(line) = find_in_stream(reader, from_line, &needle, match_case, u64::MAX)? {
+        return Ok(Some(line));
+    }
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(0))?;
+    let wrapped = BufReader::with_capacity(READ_BUFFER_BYTES, file);
+    let limit = start + needle.len() as u64;
+    Ok(find_in_stream(wrapped, 0, &needle, match_case, limit)?.filter(|line| *line < from_line))
+}
+
+/// Lowercase ASCII letters unless the search is case-sensitive
+///
+/// ### Arguments
+/// - `bytes`: The bytes to normalize
+/// - `match_case`: Whether letter case must match
+///
+/// ### Returns
+/// - `Vec<u8>`: The normalized bytes
+fn normalize(bytes: &[u8], match_case: bool) -> Vec<u8> {
+    if match_case {
+        bytes.to_vec()
+    } else {
+        bytes.to_ascii_lowercase()
+    }
+}
+
+/// Count the newlines in a byte slice
+///
+/// ### Arguments
+/// - `bytes`: The bytes to count in
+///
+/// ### Returns
+/// - `usize`: The number of `\n` bytes
+#[allow(clippy::naive_bytecount)]
+fn count_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|byte| **byte == b'\n').count()
+}
+
+/// Search a byte stream for a needle, tracking the line of each position
+///
+/// ### Arguments
+/// - `reader`: The stream, positioned at the start of `first_line`
+/// - `first_line`: The line number of the first byte of the stream
+/// - `needle`: The normalized bytes to look for
+/// - `match_case`: Whether letter case must match
+/// - `limit`: The maximum number of bytes to read
+///
+/// ### Errors
+/// Returns an error if the underlying read fails.
+///
+/// ### Returns
+/// - `Ok(Some(usize))`: The line holding the first match
+/// - `Ok(None)`: If the needle does not occur within the limit
+fn find_in_stream(
+    reader: impl Read,
+    first_line: usize,
+    needle: &[u8],
+    match_case: bool,
+    limit: u64,
+) -> io::Result<Option<usize>> {
+    let mut reader = reader.take(limit);
+    let mut buffer = vec![0u8; READ_BUFFER_BYTES];
+    // Bytes kept from the previous block so matches spanning two blocks are found.
+    let mut window: Vec<u8> = Vec::with_capacity(READ_BUFFER_BYTES + needle.len());
+    let mut window_line = first_line;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        window.extend(normalize(&buffer[..read], match_case));
+        if let Some(position) = window
+            .windows(needle.len())
+            .position(|candidate| candidate == needle)
+        {
+            let newlines = count_newlines(&window[..position]);
+            return Ok(Some(window_line + newlines));
+        }
+        let keep = (needle.len() - 1).min(window.len());
+        let drained = window.len() - keep;
+        window_line += count_newlines(&window[..drained]);
+        window.drain(..drained);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LINES_PER_CHECKPOINT, LineIndex, find_next, read_lines, scan_chunk};
+    use std::fmt::Write;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Index a whole file, scanning it in small chunks to exercise resumption
+    ///
+    /// ### Arguments
+    /// - `path`: The path of the file to index
+    ///
+    /// ### Returns
+    /// - `LineIndex`: The complete index
+    fn index_file(path: &Path) -> LineIndex {
+        let len = std::fs::metadata(path).expect("failed to stat file").len();
+        let mut index = LineIndex::new(len);
+        while !index.is_complete() {
+            let (start, newlines) = index.scan_position();
+            let chunk = scan_chunk(path, start, newlines, 1000).expect("failed to scan chunk");
+            index.apply(chunk);
+        }
+        index
+    }
+
+    /// Write a file of numbered lines
+    ///
+    /// ### Arguments
+    /// - `dir`: The directory to create the file in
+    /// - `lines`: The number of lines to write
+    ///
+    /// ### Returns
+    /// - `PathBuf`: The path of the file
+    fn numbered_file(dir: &TempDir, lines: usize) -> std::path::PathBuf {
+        let mut text = String::new();
+        for line in 0..lines {
+            let _ = writeln!(text, "line {line}");
+        }
+        let path = dir.path().join("numbered.txt");
+        std::fs::write(&path, text).expect("failed to write file");
+        path
+    }
+
+    #[test]
+    fn scan_chunk_counts_lines_across_chunks() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = numbered_file(&dir, 3 * LINES_PER_CHECKPOINT + 10);
+
+        let index = index_file(&path);
+
+        assert!(index.is_complete());
+        assert!((index.progress() - 1.0).abs() < f32::EPSILON);
+        // The trailing newline opens an empty last line, as in the editor.
+        assert_eq!(index.line_count(), 3 * LINES_PER_CHECKPOINT + 11);
+    }
+
+    #[test]
+    fn apply_completes_the_index_when_the_file_shrank() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = numbered_file(&dir, 10);
+        let len = std::fs::metadata(&path).expect("failed to stat file").len();
+        let mut index = LineIndex::new(len + 500);
+
+        for _ in 0..3 {
+            if index.is_complete() {
+                break;
+            }
+            let (start, newlines) = index.scan_position();
+            index.apply(scan_chunk(&path, start, newlines, 1000).expect("failed to scan chunk"));
+        }
+
+        assert!(index.is_complete());
+        assert_eq!(index.line_count(), 11);
+    }
+
+    #[test]
+    fn read_lines_reads_from_any_line_through_checkpoints() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = numbered_file(&dir, 2 * LINES_PER_CHECKPOINT + 50);
+        let index = index_file(&path);
+
+        let lines =
+            read_lines(&path, &index, LINES_PER_CHECKPOINT + 3, 3).expect("failed to read lines");
+
+        assert_eq!(
+            lines,
+            vec![
+                format!("line {}", LINES_PER_CHECKPOINT + 3),
+                format!("line {}", LINES_PER_CHECKPOINT + 4),
+                format!("line {}", LINES_PER_CHECKPOINT + 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_lines_stops_at_end_of_file_and_strips_carriage_returns() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("crlf.txt");
+        std::fs::write(&path, "first\r\nsecond").expect("failed to write file");
+        let index = index_file(&path);
+
+        let lines = read_lines(&path, &index, 0, 10).expect("failed to read lines");
+
+        assert_eq!(lines, vec!["first".to_string(), "second".to_string()]);
+    }
+
+    #[test]
+    fn read_lines_truncates_very_long_lines() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("long.txt");
+        let long_line = "x".repeat(super::MAX_DISPLAY_LINE_BYTES * 3);
+        std::fs::write(&path, format!("{long_line}\nnext\n")).expect("failed to write file");
+        let index = index_file(&path);
+
+        let lines = read_lines(&path, &index, 0, 2).expect("failed to read lines");
+
+        assert_eq!(lines[0].len(), super::MAX_DISPLAY_LINE_BYTES);
+        assert_eq!(lines[1], "next");
+    }
+
+    #[test]
+    fn find_next_finds_later_lines_and_wraps_around() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = numbered_file(&dir, 1000);
+        let index = index_file(&path);
+
+        assert_eq!(
+            find_next(&path, &index, 10, "line 700", true).expect("search failed"),
+            Some(700)
+        );
+        assert_eq!(
+            find_next(&path, &index, 800, "LINE 42\n", false).expect("search failed"),
+            Some(42)
+        );
+        assert_eq!(
+            find_next(&path, &index, 0, "LINE 42", true).expect("search failed"),
+            None
+        );
+        assert_eq!(
+            find_next(&path, &index, 0, "", true).expect("search failed"),
+            None
+        );
+    }
+}
@@ -1,3 +1,5 @@
 pub mod csv_support;
 pub mod file_operations;
 pub mod file_watcher;
+pub mod indentation;
+pub mod line_index;
@@ -0,0 +1,748 @@
+use crate::fulgur::languages::supported_languages::{
+    SupportedLanguage, language_from_filename, language_registry_name,
+};
+use crate::fulgur::utils::text_diff::TextEdit;
+use gpui_component::highlighter::LanguageRegistry;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    ops::Range,
+    sync::{Arc, LazyLock},
+};
+use tree_sitter::{Parser, Query, QueryCursor, StreamingIterator};
+
+/// Compiled injection queries keyed by registry language name, `None` when compilation failed
+static INJECTION_QUERIES: LazyLock<Mutex<HashMap<String, Option<Arc<Query>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Deepest chain of nested injections followed when looking up the language at the cursor
+const MAX_INJECTION_DEPTH: usize = 4;
+
+/// Captures and properties naming the embedded language of an injection
+const CONTENT_CAPTURES: [&str; 2] = ["injection.content", "content"];
+const LANGUAGE_CAPTURES: [&str; 2] = ["injection.language", "language"];
+
+/// Comment delimiters of a language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentTokens {
+    pub line: Option<&'static str>, // Prefix commenting out the rest of the line
+    pub block: Option<(&'static str, &'static str)>, // Opening and closing delimiters
+}
+
+impl CommentTokens {
+    /// Check whether the language has any comment syntax
+    ///
+    /// ### Returns
+    /// - `bool`: `true` when a line or block comment is available
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.line.is_none() && self.block.is_none()
+    }
+}
+
+/// The kind of comment toggled by the editor actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    Line,
+    Block,
+}
+
+/// The result of toggling a comment: one replacement and the selection to restore
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentEdit {
+    pub edit: TextEdit,
+    pub selection: Range<usize>, // Byte range in the text after the edit
+}
+
+/// Get the comment delimiters of a language
+///
+/// ### Arguments
+/// - `language`: The language of the document or of the embedded code at the cursor
+///
+/// ### Returns
+/// - `CommentTokens`: The delimiters, empty for languages without comments (JSON, CSV, plain text)
+#[must_use]
+pub fn comment_tokens(language: SupportedLanguage) -> CommentTokens {
+    const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+    const HTML_BLOCK: Option<(&str, &str)> = Some(("<!--", "-->"));
+    let (line, block) = match language {
+        SupportedLanguage::C
+        | SupportedLanguage::CSharp
+        | SupportedLanguage::Cpp
+        | SupportedLanguage::D
+        | SupportedLanguage::Dart
+        | SupportedLanguage::Go
+        | SupportedLanguage::Groovy
+        | SupportedLanguage::Java
+        | SupportedLanguage::JavaScript
+        | SupportedLanguage::Kotlin
+        | SupportedLanguage::ObjectiveC
+        | SupportedLanguage::Php
+        | SupportedLanguage::Proto
+        | SupportedLanguage::React
+        | SupportedLanguage::Rust
+        | SupportedLanguage::Scala
+        | SupportedLanguage::Scss
+        | SupportedLanguage::Swift
+        | SupportedLanguage::TypeScript => (Some("//"), C_BLOCK),
+        SupportedLanguage::Css | SupportedLanguage::JsDoc => (None, C_BLOCK),
+        SupportedLanguage::Astro
+        | SupportedLanguage::Ejs
+        | SupportedLanguage::Html
+        | SupportedLanguage::Markdown
+        | SupportedLanguage::MarkdownInline
+        | SupportedLanguage::Svelte
+        | SupportedLanguage::Svg
+        | SupportedLanguage::Vue
+        | SupportedLanguage::Xml => (None, HTML_BLOCK),
+        SupportedLanguage::Bash
+        | SupportedLanguage::Dockerfile
+        | SupportedLanguage::Elixir
+        | SupportedLanguage::GraphQl
+        | SupportedLanguage::Make
+        | SupportedLanguage::Perl
+        | SupportedLanguage::Python
+        | SupportedLanguage::R
+        | SupportedLanguage::Ruby
+        | SupportedLanguage::Toml
+        | SupportedLanguage::Yaml => (Some("#"), None),
+        SupportedLanguage::CMake => (Some("#"), Some(("#[[", "]]"))),
+        SupportedLanguage::Julia => (Some("#"), Some(("#=", "=#"))),
+        SupportedLanguage::Powershell => (Some("#"), Some(("<#", "#>"))),
+        SupportedLanguage::Ada => (Some("--"), None),
+        SupportedLanguage::Haskell => (Some("--"), Some(("{-", "-}"))),
+        SupportedLanguage::Lua => (Some("--"), Some(("--[[", "]]"))),
+        SupportedLanguage::Sql => (Some("--"), C_BLOCK),
+        SupportedLanguage::Asm | SupportedLanguage::Clojure | SupportedLanguage::Ini => {
+            (Some(";"), None)
+        }
+        SupportedLanguage::Erlang => (Some("%"), None),
+        SupportedLanguage::Matlab => (Some("%"), Some(("%{", "%}"))),
+        SupportedLanguage::Prolog => (Some("%"), C_BLOCK),
+        SupportedLanguage::Fortran => (Some("!"), None),
+        SupportedLanguage::FSharp => (Some("//"), Some(("(*", "*)"))),
+        SupportedLanguage::Ocaml => (None, Some(("(*", "*)"))),
+        SupportedLanguage::Pascal => (Some("//"), Some(("{", "}"))),
+        SupportedLanguage::Zig => (Some("//"), None),
+        SupportedLanguage::Erb => (None, Some(("<%#", "%>"))),
+        SupportedLanguage::Jinja2 => (None, Some(("{#", "#}"))),
+        SupportedLanguage::Csv
+        | SupportedLanguage::Diff
+        | SupportedLanguage::Json
+        | SupportedLanguage::Plain => (None, None),
+    };
+    CommentTokens { line, block }
+}
+
+/// Map the language name of an injection to a supported language
+///
+/// ### Arguments
+/// - `name`: A registry name (`javascript`) or a short alias (`js`, `ts`)
+///
+/// ### Returns
+/// - `Some(SupportedLanguage)`: The matching language
+/// - `None`: The name is unknown
+fn language_from_injection_name(name: &str) -> Option<SupportedLanguage> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    if let Some(language) = SupportedLanguage::ALL
+        .iter()
+        .find(|language| language_registry_name(language) == name)
+    {
+        return Some(*language);
+    }
+    match language_from_filename(&format!("injection.{name}")) {
+        SupportedLanguage::Plain => None,
+        language => Some(language),
+    }
+}
+
+/// Get the compiled injection query of a language, compiling it on first use
+///
+/// ### Arguments
+/// - `language_name`: The registry name of the language
+///
+/// ### Returns
+/// - `Some((tree_sitter::Language, Arc<Query>))`: The grammar and its compiled query
+/// - `None`: The language is not registered, has no injections, or its query does not compile
+fn injection_query(language_name: &str) -> Option<(tree_sitter::Language, Arc<Query>)> {
+    let config = LanguageRegistry::singleton().language(language_name)?;
+    let language = config.language.clone();
+    let source = config.injections.clone();
+    if source.trim().is_empty() {
+        return None;
+    }
+    let query = INJECTION_QUERIES
+        .lock()
+        .entry(language_name.to_string())
+        .or_insert_with(|| match Query::new(&language, &source) {
+            Ok(query) => Some(Arc::new(query)),
+            Err(e) => {
+                log::error!("Failed to compile the {language_name} injection query: {e}");
+                None
+            }
+        })
+        .clone()?;
+    Some((language, query))
+}
+
+/// Find the embedded region of another language containing an offset
+///
+/// ### Arguments
+/// - `language_name`: The registry name of the host language
+/// - `text`: The text written in the host language
+/// - `offset`: The byte offset to look up
+///
+/// ### Returns
+/// - `Some((SupportedLanguage, Range<usize>))`: The embedded language and its byte range in `text`
+/// - `None`: The offset is not inside an injection
+fn injection_at_offset(
+    language_name: &str,
+    text: &str,
+    offset: usize,
+) -> Option<(SupportedLanguage, Range<usize>)> {
+    let (language, query) = injection_query(language_name)?;
+    let mut parser = Parser::new();
+    if let Err(e) = parser.set_language(&language) {
+        log::error!("Failed to load the {language_name} grammar for injections: {e}");
+        return None;
+    }
+    let tree = parser.parse(text, None)?;
+    let capture_names = query.capture_names();
+    let mut innermost: Option<(SupportedLanguage, Range<usize>)> = None;
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), text.as_bytes());
+    while let Some(query_match) = matches.next() {
+        let mut content = None;
+        let mut language = query
+            .property_settings(query_match.pattern_index)
+            .iter()
+            .find(|property| LANGUAGE_CAPTURES.contains(&&*property.key))
+            .and_then(|property| property.value.as_deref())
+            .and_then(language_from_injection_name);
+        for capture in query_match.captures {
+            let capture_name = capture_names
+                .get(capture.index as usize)
+                .copied()
+                .unwrap_or_default();
+            if CONTENT_CAPTURES.contains(&capture_name) {
+                content = Some(capture.node.byte_range());
+            } else if LANGUAGE_CAPTURES.contains(&capture_name) {
+                language = language
+                    .or_else(|| language_from_injection_name(&text[capture.node.byte_range()]));
+            }
+        }
+        let (Some(range), Some(language)) = (content, language) else {
+            continue;
+        };
+        let contains = range.start <= offset && offset <= range.end;
+        let narrower = innermost
+            .as_ref()
+            .is_none_or(|(_, best)| range.len() < best.len());
+        if contains && narrower {
+            innermost = Some((language, range));
+        }
+    }
+    innermost
+}
+
+/// Find the language of the code at an offset, following tree-sitter injections
+///
+/// ### Description
+/// Mixed-language documents (a `<script>` in Vue or HTML, a fenced block in
+/// Markdown) are parsed with the host grammar's injection query, and the
+/// embedded language whose region contains the offset is used instead. Nested
+/// injections are followed up to a fixed depth.
+///
+/// ### Arguments
+/// - `language`: The language of the document
+/// - `text`: The document text
+/// - `offset`: The byte offset, usually the cursor
+///
+/// ### Returns
+/// - `SupportedLanguage`: The innermost language at the offset
+#[must_use]
+pub fn language_at_offset(
+    language: SupportedLanguage,
+    text: &str,
+    offset: usize,
+) -> SupportedLanguage {
+    let mut current = language;
+    let mut region = 0..text.len();
+    for _ in 0..MAX_INJECTION_DEPTH {
+        let local_offset = offset.saturating_sub(region.start);
+        let Some((embedded, range)) = injection_at_offset(
+            language_registry_name(&current),
+            &text[region.clone()],
+            local_offset,
+        ) else {
+            break;
+        };
+        if embedded == current {
+            break;
+        }
+        current = embedded;
+        region = region.start + range.start..region.start + range.end;
+    }
+    current
+}
+
+/// Check whether a document holds any comment
+///
+/// ### Description
+/// The document is parsed with the language's grammar, so comment markers
+/// inside strings do not count. Without a grammar, any occurrence of the line
+/// comment token counts, erring on the side of finding one.
+///
+/// ### Arguments
+/// - `language`: The language of the document
+/// - `text`: The document
+///
+/// ### Returns
+/// - `true`: The document has a comment, or may have one
+/// - `false`: The document has no comment
+#[must_use]
+pub fn has_comments(language: SupportedLanguage, text: &str) -> bool {
+    let tokens = comment_tokens(language);
+    let fallback = || {
+        tokens.line.is_some_and(|token| text.contains(token))
+            || tokens.block.is_some_and(|(open, _)| text.contains(open))
+    };
+    let language_name = language_registry_name(&language);
+    let Some(config) = LanguageRegistry::singleton().language(language_name) else {
+        return fallback();
+    };
+    let mut parser = Parser::new();
+    if let Err(e) = parser.set_language(&config.language) {
+        log::error!("Failed to load the {language_name} grammar for comments: {e}");
+        return fallback();
+    }
+    let Some(tree) = parser.parse(text, None) else {
+        return fallback();
+    };
+    let mut cursor = tree.walk();
+    let mut nodes = vec![tree.root_node()];
+    while let Some(node) = nodes.pop() {
+        if node.kind().contains("comment") {
+            return true;
+        }
+        nodes.extend(node.children(&mut cursor));
+    }
+    false
+}
+
+/// Get the byte offset of the start of the line containing an offset
+///
+/// ### Arguments
+/// - `text`: The text
+/// - `offset`: A byte offset into `text`
+///
+/// ### Returns
+/// - `usize`: The offset of the first byte of the line
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |index| index + 1)
+}
+
+/// Get the byte offset of the end of the line containing an offset, before its terminator
+///
+/// ### Arguments
+/// - `text`: The text
+/// - `offset`: A byte offset into `text`
+///
+/// ### Returns
+/// - `usize`: The offset of the `\n` ending the line, or the length of `text`
+fn line_end(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .find('\n')
+        .map_or(text.len(), |index| offset + index)
+}
+
+/// Shift an offset past the changes made before it
+///
+/// ### Arguments
+/// - `offset`: The offset in the old text
+/// - `changes`: `(old range, inserted length)` pairs sorted by position
+/// - `stay_before`: Keep an offset equal to an insertion point in front of the inserted text
+///
+/// ### Returns
+/// - `usize`: The offset in the new text
+fn map_through(offset: usize, changes: &[(Range<usize>, usize)], stay_before: bool) -> usize {
+    let mut mapped = offset;
+    for (range, inserted) in changes {
+        if offset < range.start || (stay_before && offset == range.start && range.is_empty()) {
+            break;
+        }
+        if offset >= range.end {
+            mapped = mapped - range.len() + inserted;
+        } else {
+            mapped -= offset - range.start;
+        }
+    }
+    mapped
+}
+
+/// Comment out or uncomment the lines covered by a selection
+///
+/// ### Description
+/// Every line touched by the selection is affected, except a last line the
+/// selection only reaches at its first column. When all non-blank lines already
+/// start with the token, it is removed (with one following space). Otherwise the
+/// token and a space are inserted at the smallest indentation of the non-blank
+/// lines, so the comment markers line up and the indentation is kept. Blank lines
+/// are left alone unless the selection holds nothing else.
+///
+/// ### Arguments
+/// - `text`: The document text
+/// - `selection`: The selected byte range, empty for a cursor
+/// - `token`: The line comment token of the language
+///
+/// ### Returns
+/// - `CommentEdit`: The replacement of the affected lines and the selection to restore
+#[must_use]
+pub fn toggle_line_comment(text: &str, selection: Range<usize>, token: &str) -> CommentEdit {
+    let first_line = line_start(text, selection.start);
+    let mut last_offset = selection.end;
+    if selection.end > selection.start && text[..selection.end].ends_with('\n') {
+        last_offset -= 1;
+    }
+    let last_line_end = line_end(text, last_offset.max(first_line));
+    let lines: Vec<Range<usize>> = {
+        let mut ranges = Vec::new();
+        let mut start = first_line;
+        loop {
+            let end = line_end(text, start);
+            ranges.push(start..end);
+            if end >= last_line_end {
+                break;
+            }
+            start = end + 1;
+        }
+        ranges
+    };
+    let indent_of = |line: &Range<usize>| {
+        let content = &text[line.clone()];
+        content.len() - content.trim_start_matches([' ', '\t']).len()
+    };
+    let mut targets: Vec<&Range<usize>> = lines
+        .iter()
+        .filter(|line| !text[(*line).clone()].trim().is_empty())
+        .collect();
+    if targets.is_empty() {
+        targets = lines.iter().collect();
+    }
+    let uncomment = targets
+        .iter()
+        .all(|line| text[line.start + indent_of(line)..line.end].starts_with(token));
+
+    let mut changes: Vec<(Range<usize>, usize)> = Vec::with_capacity(targets.len());
+    if uncomment {
+        for line in &targets {
+            let start = line.start + indent_of(line);
+            let mut end = start + token.len();
+            if text[end..line.end].starts_with(' ') {
+                end += 1;
+            }
+            changes.push((start..end, 0));
+        }
+    } else {
+        let column = targets
+            .iter()
+            .map(|line| indent_of(line))
+            .min()
+            .unwrap_or(0);
+        for line in &targets {
+            let at = line.start + column;
+            changes.push((at..at, token.len() + 1));
+        }
+    }
+
+    let mut new_text = String::with_capacity(last_line_end - first_line + targets.len() * 4);
+    let mut copied = first_line;
+    for (range, inserted) in &changes {
+        new_text.push_str(&text[copied..range.start]);
+        if *inserted > 0 {
+            new_text.push_str(token);
+            new_text.push(' ');
+        }
+        copied = range.end;
+    }
+    new_text.push_str(&text[copied..last_line_end]);
+
+    CommentEdit {
+        edit: TextEdit {
+            range: first_line..last_line_end,
+            new_text,
+        },
+        selection: map_through(selection.start, &changes, !selection.is_empty())
+            ..map_through(selection.end, &changes, false),
+    }
+}
+
+/// Wrap a selection in a block comment, or unwrap it when already commented
+///
+/// ### Description
+/// Without a selection, the current line minus its indentation is used. A range
+/// whose trimmed text starts with `open` and ends with `close` is unwrapped,
+/// dropping one space inside each delimiter. Otherwise it is wrapped as
+/// `open text close`, and the wrapped text stays selected so the toggle can be
+/// undone by running it again.
+///
+/// ### Arguments
+/// - `text`: The document text
+/// - `selection`: The selected byte range, empty for a cursor
+/// - `open`: The opening delimiter
+/// - `close`: The closing delimiter
+///
+/// ### Returns
+/// - `CommentEdit`: The replacement and the selection to restore
+#[must_use]
+pub fn toggle_block_comment(
+    text: &str,
+    selection: Range<usize>,
+    open: &str,
+    close: &str,
+) -> CommentEdit {
+    let range = if selection.is_empty() {
+        let start = line_start(text, selection.start);
+        let end = line_end(text, selection.start);
+        let line = &text[start..end];
+        let content_start = start + (line.len() - line.trim_start().len());
+        let content_end = start + line.trim_end().len();
+        if content_start < content_end {
+            content_start..content_end
+        } else {
+            selection.clone()
+        }
+    } else {
+        selection.clone()
+    };
+    let selected = &text[range.clone()];
+    let leading = selected.len() - selected.trim_start().len();
+    let trimmed = selected.trim();
+
+    if trimmed.len() >= open.len() + close.len()
+        && trimmed.starts_with(open)
+        && trimmed.ends_with(close)
+    {
+        let inner = &trimmed[open.len()..trimmed.len() - close.len()];
+        let inner = inner.strip_prefix(' ').unwrap_or(inner);
+        let inner = inner.strip_suffix(' ').unwrap_or(inner);
+        let start = range.start + leading;
+        let end = start + trimmed.len();
+        return CommentEdit {
+            edit: TextEdit {
+                range: start..end,
+                new_text: inner.to_string(),
+            },
+            selection: start..start + inner.len(),
+        };
+    }
+
+    if range.is_empty() {
+        let new_text = format!("{open}  {close}");
+        let caret = range.start + open.len() + 1;
+        return CommentEdit {
+            edit: TextEdit {
+                range: range.clone(),
+                new_text,
+            },
+            selection: caret..caret,
+        };
+    }
+
+    let new_text = format!("{open} {selected} {close}");
+    let end = range.start + new_text.len();
+    CommentEdit {
+        selection: if selection.is_empty() {
+            let caret = selection.start + open.len() + 1;
+            caret..caret
+        } else {
+            range.start..end
+        },
+        edit: TextEdit { range, new_text },
+    }
+}
+
+/// Toggle a comment for the language at the cursor
+///
+/// ### Description
+/// The language embedded at the selection start wins over the document language.
+/// A line comment falls back to a block comment for languages without line
+/// comments (HTML, CSS), and a block comment falls back to line comments for
+/// languages without block comments (Python, YAML).
+///
+/// ### Arguments
+/// - `language`: The language of the document
+/// - `text`: The document text
+/// - `selection`: The selected byte range, empty for a cursor
+/// - `kind`: The kind of comment requested
+///
+/// ### Returns
+/// - `Some(CommentEdit)`: The edit to apply
+/// - `None`: The language at the cursor has no comment syntax
+#[must_use]
+pub fn toggle_comment(
+    language: SupportedLanguage,
+    text: &str,
+    selection: Range<usize>,
+    kind: CommentKind,
+) -> Option<CommentEdit> {
+    let tokens = comment_tokens(language_at_offset(language, text, selection.start));
+    match (kind, tokens.line, tokens.block) {
+        (CommentKind::Line, Some(token), _) | (CommentKind::Block, Some(token), None) => {
+            Some(toggle_line_comment(text, selection, token))
+        }
+        (_, _, Some((open, close))) => Some(toggle_block_comment(text, selection, open, close)),
+        (_, None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CommentKind, comment_tokens, has_comments, language_at_offset, toggle_block_comment,
+        toggle_comment, toggle_line_comment,
+    };
+    use crate::fulgur::languages::supported_languages::SupportedLanguage;
+
+    /// Apply a comment toggle to `text` and return the new text and selected slice
+    fn apply_line(text: &str, selection: std::ops::Range<usize>, token: &str) -> (String, String) {
+        let result = toggle_line_comment(text, selection, token);
+        let mut new_text = text.to_string();
+        new_text.replace_range(result.edit.range, &result.edit.new_text);
+        let selected = new_text[result.selection].to_string();
+        (new_text, selected)
+    }
+
+    #[test]
+    fn every_language_with_source_code_has_comment_tokens() {
+        for language in SupportedLanguage::ALL {
+            let tokens = comment_tokens(*language);
+            let commentless = matches!(
+                language,
+                SupportedLanguage::Csv
+                    | SupportedLanguage::Diff
+                    | SupportedLanguage::Json
+                    | SupportedLanguage::Plain
+            );
+            assert_eq!(tokens.is_empty(), commentless, "{language:?}");
+        }
+        assert_eq!(comment_tokens(SupportedLanguage::Ada).line, Some("--"));
+        assert_eq!(comment_tokens(SupportedLanguage::Fortran).line, Some("!"));
+        assert_eq!(comment_tokens(SupportedLanguage::Prolog).line, Some("%"));
+    }
+
+    #[test]
+    fn line_comment_aligns_markers_at_the_smallest_indentation() {
+        let text = "fn main() {\n    if x {\n        run();\n\n    }\n}\n";
+        let start = text.find("    if").unwrap_or_default();
+        let end = text.find("}\n}").unwrap_or_default() + 1;
+
+        let (commented, _) = apply_line(text, start..end, "//");
+        assert_eq!(
+            commented,
+            "fn main() {\n    // if x {\n    //     run();\n\n    // }\n}\n"
+        );
+
+        let end = commented.find("}\n}").unwrap_or_default() + 1;
+        let (uncommented, _) = apply_line(&commented, start..end, "//");
+        assert_eq!(uncommented, text);
+    }
+
+    #[test]
+    fn line_comment_keeps_the_cursor_on_its_character() {
+        let text = "  x = 1\n";
+        let (commented, _) = apply_line(text, 4..4, "#");
+        assert_eq!(commented, "  # x = 1\n");
+        let result = toggle_line_comment(text, 4..4, "#");
+        assert_eq!(result.selection, 6..6);
+    }
+
+    #[test]
+    fn line_comment_skips_a_last_line_reached_at_its_first_column() {
+        let text = "a\nb\nc\n";
+        let (commented, selected) = apply_line(text, 0..4, "--");
+        assert_eq!(commented, "-- a\n-- b\nc\n");
+        assert_eq!(selected, "-- a\n-- b\n");
+    }
+
+    #[test]
+    fn block_comment_wraps_and_unwraps_the_selection() {
+        let text = "let a = b + c;";
+        let wrapped = toggle_block_comment(text, 8..13, "/*", "*/");
+        assert_eq!(wrapped.edit.new_text, "/* b + c */");
+
+        let mut new_text = text.to_string();
+        new_text.replace_range(wrapped.edit.range, &wrapped.edit.new_text);
+        assert_eq!(new_text, "let a = /* b + c */;");
+
+        let unwrapped = toggle_block_comment(&new_text, wrapped.selection, "/*", "*/");
+        new_text.replace_range(unwrapped.edit.range, &unwrapped.edit.new_text);
+        assert_eq!(new_text, text);
+    }
+
+    #[test]
+    fn block_comment_without_selection_wraps_the_line_content() {
+        let text = "    <div>Hi</div>\n";
+        let result = toggle_block_comment(text, 6..6, "<!--", "-->");
+        let mut new_text = text.to_string();
+        new_text.replace_range(result.edit.range, &result.edit.new_text);
+        assert_eq!(new_text, "    <!-- <div>Hi</div> -->\n");
+    }
+
+    #[test]
+    fn toggle_comment_falls_back_between_kinds() {
+        let css = toggle_comment(SupportedLanguage::Css, "a { }", 0..0, CommentKind::Line);
+        assert_eq!(
+            css.map(|result| result.edit.new_text),
+            Some("/* a { } */".to_string())
+        );
+        let yaml = toggle_comment(SupportedLanguage::Yaml, "a: 1", 0..0, CommentKind::Block);
+        assert_eq!(
+            yaml.map(|result| result.edit.new_text),
+            Some("# a: 1".to_string())
+        );
+        assert_eq!(
+            toggle_comment(SupportedLanguage::Json, "{}", 0..0, CommentKind::Line),
+            None
+        );
+    }
+
+    #[test]
+    fn language_at_offset_follows_script_injection() {
+        let text = "<body>\n  <p>Hi</p>\n</body>\n<script>\nconst a = 1;\n</script>\n";
+        let in_script = text.find("const").unwrap_or_default();
+        let in_template = text.find("<p>").unwrap_or_default();
+
+        assert_eq!(
+            language_at_offset(SupportedLanguage::Html, text, in_script),
+            SupportedLanguage::JavaScript
+        );
+        assert_eq!(
+            language_at_offset(SupportedLanguage::Html, text, in_template),
+            SupportedLanguage::Html
+        );
+    }
+
+    #[test]
+    fn test_has_comments_ignores_markers_inside_strings() {
+        assert!(has_comments(
+            SupportedLanguage::Yaml,
+            "# settings\nname: demo\n"
+        ));
+        assert!(has_comments(
+            SupportedLanguage::Toml,
+            "port = 80 # default\n"
+        ));
+        assert!(!has_comments(
+            SupportedLanguage::Yaml,
+            "color: \"#ff0000\"\n"
+        ));
+        assert!(!has_comments(
+            SupportedLanguage::Toml,
+            "color = \"#ff0000\"\n"
+        ));
+    }
+}
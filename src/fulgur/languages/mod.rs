@@ -1,2 +1,6 @@
+pub mod comments;
+pub mod spelling;
 pub mod supported_languages;
+pub mod symbols;
+pub mod syntax_spans;
 mod syntax_highlighting;
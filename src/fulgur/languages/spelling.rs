@@ -0,0 +1,627 @@
+use crate::fulgur::utils::paths::config_subdir;
+use anyhow::{Result, anyhow};
+use gpui_component::highlighter::LanguageRegistry;
+use spellbook::Dictionary;
+use std::{
+    fs,
+    io::Write,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+use tree_sitter::Parser;
+
+/// Name of the config subdirectory holding the Hunspell dictionaries
+pub const DICTIONARIES_DIR: &str = "dictionaries";
+
+/// File inside the dictionaries directory listing the words added by the user, one per line
+pub const USER_DICTIONARY_FILE: &str = "user_dictionary.txt";
+
+/// Maximum number of suggestions offered for a misspelled word
+const MAX_SUGGESTIONS: usize = 5;
+
+/// A Hunspell dictionary extended with the words of the user dictionary
+pub struct SpellChecker {
+    language: String,
+    dictionary: Dictionary,
+}
+
+impl SpellChecker {
+    /// Build a spell checker from the contents of a `.aff` and a `.dic` file
+    ///
+    /// ### Arguments
+    /// - `language`: The dictionary name, such as `en_US`
+    /// - `aff`: The affix file contents
+    /// - `dic`: The word list contents
+    ///
+    /// ### Errors
+    /// Returns an error if the dictionary cannot be parsed.
+    ///
+    /// ### Returns
+    /// - `Ok(SpellChecker)`: The spell checker
+    /// - `Err(anyhow::Error)`: If the dictionary is invalid
+    pub fn from_sources(language: &str, aff: &str, dic: &str) -> Result<Self> {
+        let dictionary = Dictionary::new(aff, dic)
+            .map_err(|e| anyhow!("Failed to parse the {language} dictionary: {e}"))?;
+        Ok(Self {
+            language: language.to_string(),
+            dictionary,
+        })
+    }
+
+    /// Load a dictionary from the dictionaries directory, along with the user dictionary
+    ///
+    /// ### Arguments
+    /// - `dir`: The dictionaries directory
+    /// - `language`: The dictionary name; `<language>.aff` and `<language>.dic` are read
+    ///
+    /// ### Errors
+    /// Returns an error if either dictionary file cannot be read or parsed.
+    ///
+    /// ### Returns
+    /// - `Ok(SpellChecker)`: The spell checker, already extended with the user words
+    /// - `Err(anyhow::Error)`: If the dictionary cannot be loaded
+    pub fn load(dir: &Path, language: &str) -> Result<Self> {
+        let aff_bytes = fs::read(dir.join(format!("{language}.aff")))
+            .map_err(|e| anyhow!("Failed to read {language}.aff: {e}"))?;
+        let dic_bytes = fs::read(dir.join(format!("{language}.dic")))
+            .map_err(|e| anyhow!("Failed to read {language}.dic: {e}"))?;
+        let encoding = affix_encoding(&aff_bytes);
+        let (aff, _, _) = encoding.decode(&aff_bytes);
+        let (dic, _, _) = encoding.decode(&dic_bytes);
+        let mut checker = Self::from_sources(language, &aff, &dic)?;
+        for word in read_user_words(&dir.join(USER_DICTIONARY_FILE)) {
+            checker.add_word(&word);
+        }
+        Ok(checker)
+    }
+
+    /// Get the name of the loaded dictionary
+    ///
+    /// ### Returns
+    /// - `&str`: The dictionary name, such as `en_US`
+    #[must_use]
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Check the spelling of a word
+    ///
+    /// ### Arguments
+    /// - `word`: The word to check
+    ///
+    /// ### Returns
+    /// - `bool`: Whether the word is spelled correctly
+    #[must_use]
+    pub fn check(&self, word: &str) -> bool {
+        self.dictionary.check(word)
+    }
+
+    /// Suggest corrections for a misspelled word
+    ///
+    /// ### Arguments
+    /// - `word`: The misspelled word
+    ///
+    /// ### Returns
+    /// - `Vec<String>`: The suggestions, best first
+    #[must_use]
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        self.dictionary.suggest(word, &mut suggestions);
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    /// Accept a word for the lifetime of this checker
+    ///
+    /// ### Arguments
+    /// - `word`: The word to accept
+    pub fn add_word(&mut self, word: &str) {
+        if let Err(e) = self.dictionary.add(word) {
+            log::warn!(
+                "Failed to add '{word}' to the {} dictionary: {e}",
+                self.language
+            );
+        }
+    }
+}
+
+/// Resolve the encoding declared by the `SET` line of an affix file
+///
+/// ### Arguments
+/// - `aff`: The raw affix file
+///
+/// ### Returns
+/// - `&'static encoding_rs::Encoding`: The declared encoding, UTF-8 when missing or unknown
+fn affix_encoding(aff: &[u8]) -> &'static encoding_rs::Encoding {
+    aff.split(|byte| *byte == b'\n')
+        .find_map(|line| line.strip_prefix(b"SET "))
+        .and_then(|label| encoding_rs::Encoding::for_label(label.trim_ascii()))
+        .unwrap_or(encoding_rs::UTF_8)
+}
+
+/// Get the directory Hunspell dictionaries are loaded from, creating it if needed
+///
+/// ### Errors
+/// Returns an error if the config directory cannot be resolved or created.
+///
+/// ### Returns
+/// - `Ok(PathBuf)`: The dictionaries directory
+/// - `Err(anyhow::Error)`: If the directory is unavailable
+pub fn dictionaries_dir() -> Result<PathBuf> {
+    config_subdir(DICTIONARIES_DIR)
+}
+
+/// List the dictionaries installed in a directory
+///
+/// ### Arguments
+/// - `dir`: The dictionaries directory
+///
+/// ### Returns
+/// - `Vec<String>`: The sorted names of every `.dic` file with a matching `.aff` file
+#[must_use]
+pub fn available_dictionaries(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "dic"))
+        .filter(|path| path.with_extension("aff").is_file())
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Read the words of a user dictionary
+///
+/// ### Arguments
+/// - `path`: The user dictionary file
+///
+/// ### Returns
+/// - `Vec<String>`: The words, empty when the file does not exist
+#[must_use]
+pub fn read_user_words(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|contents| {
+            contents
+                .lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Append a word to a user dictionary
+///
+/// ### Arguments
+/// - `path`: The user dictionary file, created if missing
+/// - `word`: The word to add
+///
+/// ### Errors
+/// Returns an error if the file cannot be opened or written.
+///
+/// ### Returns
+/// - `Ok(())`: The word was appended
+/// - `Err(anyhow::Error)`: If the write failed
+pub fn append_user_word(path: &Path, word: &str) -> Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow!("Failed to open the user dictionary: {e}"))?;
+    writeln!(file, "{word}").map_err(|e| anyhow!("Failed to write the user dictionary: {e}"))
+}
+
+/// A misspelled word of a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    pub word: String,
+    pub range: Range<usize>, // Byte range of the word
+    pub line: u32,           // Zero-based line of the word
+    pub column: u32,         // Character column of the word on its line
+}
+
+/// How the text of a document is selected for spell checking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpellScope<'a> {
+    /// Check every word
+    Prose,
+    /// Check the text of Markdown paragraphs, headings, lists and links, skipping code and HTML
+    Markdown,
+    /// Check the comments and strings of code in the given registry language
+    Code(&'a str),
+}
+
+impl<'a> SpellScope<'a> {
+    /// Select the scope for a registry language
+    ///
+    /// ### Arguments
+    /// - `language_name`: The registry name of the document language
+    ///
+    /// ### Returns
+    /// - `SpellScope`: Markdown and plain text are checked as prose, anything else as code
+    #[must_use]
+    pub fn for_language(language_name: &'a str) -> Self {
+        match language_name {
+            "markdown" | "markdown_inline" => Self::Markdown,
+            "text" | "plain" => Self::Prose,
+            other => Self::Code(other),
+        }
+    }
+}
+
+/// Collect the byte ranges of a document that hold prose
+///
+/// ### Arguments
+/// - `scope`: How the document is checked
+/// - `text`: The document text
+///
+/// ### Returns
+/// - `Vec<Range<usize>>`: The ranges to check, in document order
+#[must_use]
+pub fn checkable_ranges(scope: SpellScope, text: &str) -> Vec<Range<usize>> {
+    match scope {
+        SpellScope::Prose => vec![0..text.len()],
+        SpellScope::Markdown => markdown_text_ranges(text),
+        SpellScope::Code(language_name) => code_prose_ranges(language_name, text),
+    }
+}
+
+/// Collect the byte ranges of Markdown text nodes
+///
+/// ### Arguments
+/// - `text`: The Markdown source
+///
+/// ### Returns
+/// - `Vec<Range<usize>>`: The ranges of every `Text` node, in document order
+fn markdown_text_ranges(text: &str) -> Vec<Range<usize>> {
+    fn collect(node: &markdown::mdast::Node, ranges: &mut Vec<Range<usize>>) {
+        if let markdown::mdast::Node::Text(_) = node
+            && let Some(position) = node.position()
+        {
+            ranges.push(position.start.offset..position.end.offset);
+        }
+        if let Some(children) = node.children() {
+            for child in children {
+                collect(child, ranges);
+            }
+        }
+    }
+    let Ok(ast) = markdown::to_mdast(text, &markdown::ParseOptions::gfm()) else {
+        return Vec::new();
+    };
+    let mut ranges = Vec::new();
+    collect(&ast, &mut ranges);
+    ranges
+}
+
+/// Collect the byte ranges of the comments and strings of a source file
+///
+/// ### Description
+/// Nodes are selected by kind: every node whose kind mentions `comment`, and
+/// every innermost node whose kind mentions `string`, so the quotes of
+/// grammars that split strings into parts are not part of the range.
+///
+/// ### Arguments
+/// - `language_name`: The registry name of the language
+/// - `text`: The source text
+///
+/// ### Returns
+/// - `Vec<Range<usize>>`: The ranges, empty when the language has no grammar
+fn code_prose_ranges(language_name: &str, text: &str) -> Vec<Range<usize>> {
+    let Some(config) = LanguageRegistry::singleton().language(language_name) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if let Err(e) = parser.set_language(&config.language) {
+        log::error!("Failed to load the {language_name} grammar for spell checking: {e}");
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(text, None) else {
+        return Vec::new();
+    };
+    let mut ranges = Vec::new();
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        let is_comment = kind.contains("comment");
+        let is_string = kind.contains("string")
+            && !(0..node.named_child_count())
+                .filter_map(|index| node.named_child(index))
+                .any(|child| child.kind().contains("string"));
+        let descend = if is_comment || is_string {
+            ranges.push(node.byte_range());
+            false
+        } else {
+            cursor.goto_first_child()
+        };
+        if descend {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return ranges;
+            }
+        }
+    }
+}
+
+/// Split a range of text into the words worth spell checking
+///
+/// ### Description
+/// Whitespace-separated chunks that look like URLs, e-mail addresses or paths
+/// are skipped. Inside a chunk, words are runs of letters and apostrophes.
+/// Words with digits or underscores, `camelCase` identifiers, acronyms and
+/// single letters are skipped too.
+///
+/// ### Arguments
+/// - `text`: The document text
+/// - `range`: The byte range to split
+///
+/// ### Returns
+/// - `Vec<Range<usize>>`: The byte ranges of the words
+#[must_use]
+pub fn word_ranges(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let Some(slice) = text.get(range.clone()) else {
+        return Vec::new();
+    };
+    let mut words = Vec::new();
+    let mut chunk_start = None;
+    for (index, ch) in slice
+        .char_indices()
+        .chain(std::iter::once((slice.len(), ' ')))
+    {
+        match (ch.is_whitespace(), chunk_start) {
+            (false, None) => chunk_start = Some(index),
+            (true, Some(start)) => {
+                chunk_start = None;
+                let chunk = &slice[start..index];
+                if !looks_like_address(chunk) {
+                    collect_chunk_words(chunk, range.start + start, &mut words);
+                }
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// Check whether a whitespace-separated chunk is a URL, an e-mail address or a path
+///
+/// ### Arguments
+/// - `chunk`: The chunk to inspect
+///
+/// ### Returns
+/// - `bool`: Whether the chunk should be skipped
+fn looks_like_address(chunk: &str) -> bool {
+    chunk.contains("://") || chunk.contains('@') || chunk.contains('/') || chunk.contains('\\')
+}
+
+/// Collect the words of a chunk
+///
+/// ### Arguments
+/// - `chunk`: The chunk text
+/// - `offset`: The byte offset of the chunk in the document
+/// - `words`: Accumulator for the word ranges
+fn collect_chunk_words(chunk: &str, offset: usize, words: &mut Vec<Range<usize>>) {
+    let is_word_char = |ch: char| ch.is_alphanumeric() || ch == '_' || is_apostrophe(ch);
+    let mut run_start = None;
+    for (index, ch) in chunk
+        .char_indices()
+        .chain(std::iter::once((chunk.len(), ' ')))
+    {
+        match (is_word_char(ch), run_start) {
+            (true, None) => run_start = Some(index),
+            (false, Some(start)) => {
+                run_start = None;
+                let run = &chunk[start..index];
+                let word = run.trim_matches(is_apostrophe);
+                let leading = run.len() - run.trim_start_matches(is_apostrophe).len();
+                if is_checkable_word(word) {
+                    let start = offset + start + leading;
+                    words.push(start..start + word.len());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Check whether a character is a straight or typographic apostrophe
+fn is_apostrophe(ch: char) -> bool {
+    ch == '\'' || ch == '\u{2019}'
+}
+
+/// Check whether a word should be spell checked
+///
+/// ### Arguments
+/// - `word`: The candidate word, without surrounding apostrophes
+///
+/// ### Returns
+/// - `bool`: `false` for single letters, words with digits or underscores,
+///   acronyms and `camelCase` identifiers
+fn is_checkable_word(word: &str) -> bool {
+    if word.chars().nth(1).is_none() {
+        return false;
+    }
+    if word.chars().any(|ch| ch.is_numeric() || ch == '_') {
+        return false;
+    }
+    let letters = word.chars().filter(|ch| ch.is_alphabetic());
+    if letters.clone().all(char::is_uppercase) {
+        return false;
+    }
+    !letters.skip(1).any(char::is_uppercase)
+}
+
+/// Find the misspelled words of a document
+///
+/// ### Arguments
+/// - `checker`: The spell checker
+/// - `scope`: How the document is checked
+/// - `text`: The document text
+///
+/// ### Returns
+/// - `Vec<Misspelling>`: The misspellings, in document order
+#[must_use]
+pub fn find_misspellings(
+    checker: &SpellChecker,
+    scope: SpellScope,
+    text: &str,
+) -> Vec<Misspelling> {
+    let mut misspellings = Vec::new();
+    let mut line = 0u32;
+    let mut line_start = 0usize;
+    let mut scanned = 0usize;
+    for range in checkable_ranges(scope, text) {
+        for word_range in word_ranges(text, range) {
+            let word = &text[word_range.clone()];
+            if checker.check(word) {
+                continue;
+            }
+            let skipped = text
+                .as_bytes()
+                .get(scanned..word_range.start)
+                .unwrap_or_default();
+            for (index, byte) in skipped.iter().enumerate() {
+                if *byte == b'\n' {
+                    line += 1;
+                    line_start = scanned + index + 1;
+                }
+            }
+            scanned = scanned.max(word_range.start);
+            misspellings.push(Misspelling {
+                word: word.to_string(),
+                line,
+                column: u32::try_from(text[line_start..word_range.start].chars().count())
+                    .unwrap_or(u32::MAX),
+                range: word_range,
+            });
+        }
+    }
+    misspellings
+}
+
+/// Find the misspelling touching a byte offset
+///
+/// ### Arguments
+/// - `misspellings`: The misspellings of the document
+/// - `offset`: The byte offset, usually the cursor
+///
+/// ### Returns
+/// - `Some(&Misspelling)`: The misspelling containing the offset or ending at it
+/// - `None`: The offset is not on a misspelled word
+#[must_use]
+pub fn misspelling_at_offset(misspellings: &[Misspelling], offset: usize) -> Option<&Misspelling> {
+    misspellings
+        .iter()
+        .find(|misspelling| misspelling.range.start <= offset && offset <= misspelling.range.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SpellChecker, SpellScope, append_user_word, available_dictionaries, checkable_ranges,
+        find_misspellings, misspelling_at_offset, read_user_words, word_ranges,
+    };
+
+    const AFF: &str = "SET UTF-8\nTRY esianrtolcdugmphbyfvkwz\n";
+    const DIC: &str = "4\nhello\nworld\nspelling\ncheck\n";
+
+    fn checker() -> SpellChecker {
+        SpellChecker::from_sources("test", AFF, DIC).expect("the test dictionary parses")
+    }
+
+    fn words<'a>(text: &'a str) -> Vec<&'a str> {
+        word_ranges(text, 0..text.len())
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_word_ranges_skips_identifiers_addresses_and_acronyms() {
+        assert_eq!(
+            words("hello world, isn't it? 'quoted'"),
+            vec!["hello", "world", "isn't", "it", "quoted"]
+        );
+        assert_eq!(
+            words("see https://fulgur.app or me@fulgur.app in src/main.rs"),
+            vec!["see", "or", "in"]
+        );
+        assert_eq!(words("snake_case camelCase HTTP v2 a Title"), vec!["Title"]);
+    }
+
+    #[test]
+    fn test_checkable_ranges_markdown_skips_code() {
+        let text = "Some text\n\n```rust\nlet wrng = 1;\n```\n\nand `cod` here\n";
+        let checked: String = checkable_ranges(SpellScope::Markdown, text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect();
+        assert!(checked.contains("Some text"));
+        assert!(checked.contains("here"));
+        assert!(!checked.contains("wrng"));
+        assert!(!checked.contains("cod"));
+    }
+
+    #[test]
+    fn test_find_misspellings_reports_positions() {
+        let text = "hello wrld\nspelling chekc";
+        let misspellings = find_misspellings(&checker(), SpellScope::Prose, text);
+        let found: Vec<(&str, u32, u32)> = misspellings
+            .iter()
+            .map(|misspelling| {
+                (
+                    misspelling.word.as_str(),
+                    misspelling.line,
+                    misspelling.column,
+                )
+            })
+            .collect();
+        assert_eq!(found, vec![("wrld", 0, 6), ("chekc", 1, 9)]);
+        assert_eq!(
+            misspelling_at_offset(&misspellings, 8).map(|m| m.word.as_str()),
+            Some("wrld")
+        );
+        assert!(misspelling_at_offset(&misspellings, 2).is_none());
+    }
+
+    #[test]
+    fn test_suggest_and_add_word() {
+        let mut checker = checker();
+        assert!(checker.suggest("wrld").contains(&"world".to_string()));
+        assert!(!checker.check("fulgur"));
+        checker.add_word("fulgur");
+        assert!(checker.check("fulgur"));
+    }
+
+    #[test]
+    fn test_dictionary_files_roundtrip() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        std::fs::write(dir.path().join("en_TEST.aff"), AFF).expect("write aff");
+        std::fs::write(dir.path().join("en_TEST.dic"), DIC).expect("write dic");
+        std::fs::write(dir.path().join("orphan.dic"), DIC).expect("write orphan");
+        assert_eq!(
+            available_dictionaries(dir.path()),
+            vec!["en_TEST".to_string()]
+        );
+
+        let user_dictionary = dir.path().join(super::USER_DICTIONARY_FILE);
+        append_user_word(&user_dictionary, "Fulgur").expect("append user word");
+        assert_eq!(
+            read_user_words(&user_dictionary),
+            vec!["Fulgur".to_string()]
+        );
+
+        let checker = SpellChecker::load(dir.path(), "en_TEST").expect("load dictionary");
+        assert_eq!(checker.language(), "en_TEST");
+        assert!(checker.check("Fulgur"));
+        assert!(checker.check("hello"));
+    }
+}
@@ -0,0 +1,477 @@
+use crate::fulgur::utils::fuzzy::fuzzy_score;
+use gpui_component::highlighter::LanguageRegistry;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    ops::Range,
+    sync::{Arc, LazyLock},
+};
+use tree_sitter::{Parser, Query, QueryCursor, StreamingIterator};
+
+/// Compiled symbol queries keyed by registry language name, `None` when compilation failed
+static SYMBOL_QUERIES: LazyLock<Mutex<HashMap<String, Option<Arc<Query>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Capture holding the label of a symbol
+const NAME_CAPTURE: &str = "name";
+
+/// Prefix of the capture spanning the whole symbol, followed by its kind
+const DEFINITION_CAPTURE_PREFIX: &str = "definition.";
+
+/// The kind of a document symbol, taken from its `@definition.<kind>` capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Interface,
+    Type,
+    Implementation,
+    Module,
+    Macro,
+    Constant,
+    Heading,
+}
+
+impl SymbolKind {
+    /// Parse the kind suffix of a `@definition.<kind>` capture
+    ///
+    /// ### Arguments
+    /// - `key`: The capture suffix, such as `function`
+    ///
+    /// ### Returns
+    /// - `Some(SymbolKind)`: The matching kind
+    /// - `None`: The suffix is unknown
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "function" => Some(Self::Function),
+            "method" => Some(Self::Method),
+            "class" => Some(Self::Class),
+            "interface" => Some(Self::Interface),
+            "type" => Some(Self::Type),
+            "implementation" => Some(Self::Implementation),
+            "module" => Some(Self::Module),
+            "macro" => Some(Self::Macro),
+            "constant" => Some(Self::Constant),
+            "heading" => Some(Self::Heading),
+            _ => None,
+        }
+    }
+
+    /// Get the short label shown next to a symbol in the outline and the picker
+    ///
+    /// ### Returns
+    /// - `&'static str`: The label
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Function => "fn",
+            Self::Method => "method",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Type => "type",
+            Self::Implementation => "impl",
+            Self::Module => "mod",
+            Self::Macro => "macro",
+            Self::Constant => "const",
+            Self::Heading => "#",
+        }
+    }
+}
+
+/// A named item of a document, such as a function, a type or a Markdown heading
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub range: Range<usize>, // Byte range of the whole item
+    pub line: u32,           // Zero-based line of the symbol name
+    pub character: u32,      // Zero-based column, in characters, of the symbol name on its line
+    pub depth: usize,        // Number of enclosing symbols
+}
+
+/// Get the symbol query shipped for a registry language
+///
+/// ### Description
+/// Queries are shipped for the languages whose grammars define named items
+/// worth listing. Data and markup formats other than Markdown and TOML, and the
+/// languages without a tree-sitter grammar, have no symbols: the outline shows
+/// "No symbols" and the symbol picker stays empty for them.
+///
+/// ### Arguments
+/// - `language_name`: The registry name of the language (see `language_registry_name`)
+///
+/// ### Returns
+/// - `Some(&'static str)`: The query source
+/// - `None`: No symbol query is shipped for this language
+#[must_use]
+pub fn symbol_query_source(language_name: &str) -> Option<&'static str> {
+    let source = match language_name {
+        "rust" => include_str!("syntax_highlighting/queries/symbols/rust.scm"),
+        "python" => include_str!("syntax_highlighting/queries/symbols/python.scm"),
+        "javascript" => include_str!("syntax_highlighting/queries/symbols/javascript.scm"),
+        "typescript" | "tsx" => include_str!("syntax_highlighting/queries/symbols/typescript.scm"),
+        "go" => include_str!("syntax_highlighting/queries/symbols/go.scm"),
+        "c" => include_str!("syntax_highlighting/queries/symbols/c.scm"),
+        "cpp" => include_str!("syntax_highlighting/queries/symbols/cpp.scm"),
+        "java" => include_str!("syntax_highlighting/queries/symbols/java.scm"),
+        "ruby" => include_str!("syntax_highlighting/queries/symbols/ruby.scm"),
+        "markdown" => include_str!("syntax_highlighting/queries/symbols/markdown.scm"),
+        "bash" => include_str!("syntax_highlighting/queries/symbols/bash.scm"),
+        "csharp" => include_str!("syntax_highlighting/queries/symbols/csharp.scm"),
+        "elixir" => include_str!("syntax_highlighting/queries/symbols/elixir.scm"),
+        "php" => include_str!("syntax_highlighting/queries/symbols/php.scm"),
+        "swift" => include_str!("syntax_highlighting/queries/symbols/swift.scm"),
+        "toml" => include_str!("syntax_highlighting/queries/symbols/toml.scm"),
+        _ => return None,
+    };
+    Some(source)
+}
+
+/// Get the compiled symbol query of a language, compiling it on first use
+///
+/// ### Arguments
+/// - `language_name`: The registry name of the language
+///
+/// ### Returns
+/// - `Some((tree_sitter::Language, Arc<Query>))`: The grammar and its compiled query
+/// - `None`: The language has no grammar, no query, or its query does not compile
+fn symbol_query(language_name: &str) -> Option<(tree_sitter::Language, Arc<Query>)> {
+    let source = symbol_query_source(language_name)?;
+    let language = LanguageRegistry::singleton()
+        .language(language_name)?
+        .language;
+    let query = SYMBOL_QUERIES
+        .lock()
+        .entry(language_name.to_string())
+        .or_insert_with(|| match Query::new(&language, source) {
+            Ok(query) => Some(Arc::new(query)),
+            Err(e) => {
+                log::error!("Failed to compile the {language_name} symbol query: {e}");
+                None
+            }
+        })
+        .clone()?;
+    Some((language, query))
+}
+
+/// Extract the symbols of a document
+///
+/// ### Description
+/// The document is parsed with the language's tree-sitter grammar and matched
+/// against its symbol query. Symbols are returned in document order, and each
+/// one records how many other symbols enclose it so callers can render a tree.
+///
+/// ### Arguments
+/// - `language_name`: The registry name of the document language
+/// - `text`: The document text
+///
+/// ### Returns
+/// - `Vec<DocumentSymbol>`: The symbols, empty when the language has no symbol query
+#[must_use]
+pub fn extract_symbols(language_name: &str, text: &str) -> Vec<DocumentSymbol> {
+    let Some((language, query)) = symbol_query(language_name) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if let Err(e) = parser.set_language(&language) {
+        log::error!("Failed to load the {language_name} grammar for symbols: {e}");
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(text, None) else {
+        return Vec::new();
+    };
+    let capture_names = query.capture_names();
+    let mut symbols: Vec<DocumentSymbol> = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), text.as_bytes());
+    while let Some(query_match) = matches.next() {
+        let mut name_node = None;
+        let mut definition = None;
+        for capture in query_match.captures {
+            let capture_name = capture_names
+                .get(capture.index as usize)
+                .copied()
+                .unwrap_or_default();
+            if capture_name == NAME_CAPTURE {
+                name_node = Some(capture.node);
+            } else if let Some(kind) = capture_name
+                .strip_prefix(DEFINITION_CAPTURE_PREFIX)
+                .and_then(SymbolKind::from_key)
+            {
+                definition = Some((kind, capture.node.byte_range()));
+            }
+        }
+        let (Some(name_node), Some((kind, range))) = (name_node, definition) else {
+            continue;
+        };
+        let name = symbol_label(&text[name_node.byte_range()], kind);
+        if name.is_empty() {
+            continue;
+        }
+        let position = name_node.start_position();
+        let line_start = name_node.start_byte() - position.column;
+        symbols.push(DocumentSymbol {
+            name,
+            kind,
+            range,
+            line: u32::try_from(position.row).unwrap_or(u32::MAX),
+            character: char_len(&text[line_start..name_node.start_byte()]),
+            depth: 0,
+        });
+    }
+    symbols.sort_by(|a, b| {
+        a.range
+            .start
+            .cmp(&b.range.start)
+            .then(b.range.end.cmp(&a.range.end))
+    });
+    symbols.dedup_by(|a, b| a.range == b.range);
+    assign_depths(&mut symbols);
+    symbols
+}
+
+/// Turn the text of a `@name` capture into a single-line label
+///
+/// ### Arguments
+/// - `raw`: The captured text
+/// - `kind`: The kind of the symbol
+///
+/// ### Returns
+/// - `String`: The label, with whitespace collapsed and closing heading markers removed
+fn symbol_label(raw: &str, kind: SymbolKind) -> String {
+    let mut label = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if kind == SymbolKind::Heading {
+        let trimmed = label.trim_end_matches('#').trim_end();
+        label.truncate(trimmed.len());
+    }
+    label
+}
+
+/// Count the characters of a string, as editor positions do
+///
+/// ### Arguments
+/// - `text`: The text to measure
+///
+/// ### Returns
+/// - `u32`: The number of characters
+fn char_len(text: &str) -> u32 {
+    u32::try_from(text.chars().count()).unwrap_or(u32::MAX)
+}
+
+/// Set the nesting depth of symbols sorted by start offset
+///
+/// ### Arguments
+/// - `symbols`: The symbols, sorted by start offset and then by descending end offset
+fn assign_depths(symbols: &mut [DocumentSymbol]) {
+    let mut enclosing: Vec<Range<usize>> = Vec::new();
+    for symbol in symbols {
+        while enclosing
+            .last()
+            .is_some_and(|range| range.end < symbol.range.end || range.end <= symbol.range.start)
+        {
+            enclosing.pop();
+        }
+        symbol.depth = enclosing.len();
+        enclosing.push(symbol.range.clone());
+    }
+}
+
+/// Find the innermost symbol containing a byte offset
+///
+/// ### Arguments
+/// - `symbols`: The symbols returned by `extract_symbols`
+/// - `offset`: The byte offset, usually the cursor
+///
+/// ### Returns
+/// - `Some(usize)`: The index of the innermost containing symbol
+/// - `None`: No symbol contains the offset
+#[must_use]
+pub fn symbol_at_offset(symbols: &[DocumentSymbol], offset: usize) -> Option<usize> {
+    symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, symbol)| symbol.range.start <= offset && offset < symbol.range.end)
+        .max_by_key(|(_, symbol)| symbol.depth)
+        .map(|(index, _)| index)
+}
+
+/// Rank symbols against a fuzzy query
+///
+/// ### Arguments
+/// - `symbols`: The symbols to search
+/// - `query`: The text typed after the `@` prefix
+///
+/// ### Returns
+/// - `Vec<&DocumentSymbol>`: The matching symbols, best match first, ties kept in document order
+#[must_use]
+pub fn filter_symbols<'a>(symbols: &'a [DocumentSymbol], query: &str) -> Vec<&'a DocumentSymbol> {
+    let query = query.trim();
+    let mut ranked: Vec<(i64, &DocumentSymbol)> = symbols
+        .iter()
+        .filter_map(|symbol| fuzzy_score(query, &symbol.name).map(|score| (score, symbol)))
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0));
+    ranked.into_iter().map(|(_, symbol)| symbol).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DocumentSymbol, SymbolKind, assign_depths, extract_symbols, filter_symbols,
+        symbol_at_offset, symbol_label, symbol_query_source,
+    };
+
+    fn symbol(name: &str, range: std::ops::Range<usize>) -> DocumentSymbol {
+        DocumentSymbol {
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            range,
+            line: 0,
+            character: 0,
+            depth: 0,
+        }
+    }
+
+    #[test]
+    fn test_assign_depths_nests_contained_ranges() {
+        let mut symbols = vec![
+            symbol("outer", 0..100),
+            symbol("inner", 10..50),
+            symbol("innermost", 20..30),
+            symbol("sibling", 60..90),
+            symbol("after", 120..130),
+        ];
+        assign_depths(&mut symbols);
+        let depths: Vec<usize> = symbols.iter().map(|symbol| symbol.depth).collect();
+        assert_eq!(depths, vec![0, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_symbol_at_offset_returns_the_innermost_symbol() {
+        let mut symbols = vec![symbol("outer", 0..100), symbol("inner", 10..50)];
+        assign_depths(&mut symbols);
+        assert_eq!(symbol_at_offset(&symbols, 20), Some(1));
+        assert_eq!(symbol_at_offset(&symbols, 70), Some(0));
+        assert_eq!(symbol_at_offset(&symbols, 150), None);
+    }
+
+    #[test]
+    fn test_filter_symbols_ranks_the_closest_name_first() {
+        let symbols = vec![
+            symbol("set_active_view_entry", 0..10),
+            symbol("save_state", 10..20),
+            symbol("load", 20..30),
+        ];
+        let names: Vec<&str> = filter_symbols(&symbols, "save")
+            .into_iter()
+            .map(|symbol| symbol.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["save_state", "set_active_view_entry"]);
+    }
+
+    #[test]
+    fn test_symbol_label_strips_closing_heading_markers() {
+        assert_eq!(symbol_label("Intro ##", SymbolKind::Heading), "Intro");
+        assert_eq!(
+            symbol_label("multi\n  line", SymbolKind::Heading),
+            "multi line"
+        );
+        assert_eq!(symbol_label("C#", SymbolKind::Class), "C#");
+    }
+
+    #[test]
+    fn test_unknown_languages_have_no_symbols() {
+        assert!(symbol_query_source("plain").is_none());
+        assert!(extract_symbols("plain", "fn main() {}").is_empty());
+    }
+
+    #[test]
+    fn test_extract_symbols_finds_nested_rust_items() {
+        let text = "mod app {\n    struct Editor;\n    impl Editor {\n        fn open(&self) {}\n    }\n}\n";
+        let symbols = extract_symbols("rust", text);
+        let outline: Vec<(&str, SymbolKind, usize)> = symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.depth))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                ("app", SymbolKind::Module, 0),
+                ("Editor", SymbolKind::Type, 1),
+                ("Editor", SymbolKind::Implementation, 1),
+                ("open", SymbolKind::Function, 2),
+            ]
+        );
+        assert_eq!(symbols[3].line, 3);
+        assert_eq!(symbols[3].character, 11);
+    }
+
+    #[test]
+    fn test_extract_symbols_counts_columns_in_characters() {
+        let symbols = extract_symbols("rust", "/* \u{1F600} */ fn open() {}\n");
+        assert_eq!(symbols.first().map(|symbol| symbol.character), Some(11));
+    }
+
+    #[test]
+    fn test_extract_symbols_nests_markdown_headings_by_section() {
+        let text = "# Title\n\nIntro\n\n## Install ##\n\nSteps\n\n## Usage\n";
+        let symbols = extract_symbols("markdown", text);
+        let outline: Vec<(&str, usize)> = symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.depth))
+            .collect();
+        assert_eq!(outline, vec![("Title", 0), ("Install", 1), ("Usage", 1)]);
+        assert!(
+            symbols
+                .iter()
+                .all(|symbol| symbol.kind == SymbolKind::Heading)
+        );
+    }
+
+    #[test]
+    fn test_extract_symbols_names_elixir_definitions_by_their_macro() {
+        let text = "defmodule App.Editor do\n  def open(path), do: path\n  defp close(x) when is_nil(x), do: x\n  defmacro trace(a), do: a\nend\n";
+        let outline: Vec<(String, SymbolKind, usize)> = extract_symbols("elixir", text)
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind, symbol.depth))
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                ("App.Editor".to_string(), SymbolKind::Module, 0),
+                ("open".to_string(), SymbolKind::Function, 1),
+                ("close".to_string(), SymbolKind::Function, 1),
+                ("trace".to_string(), SymbolKind::Macro, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_every_shipped_symbol_query_compiles() {
+        for language in [
+            "rust",
+            "python",
+            "javascript",
+            "typescript",
+            "go",
+            "c",
+            "cpp",
+            "java",
+            "ruby",
+            "markdown",
+            "bash",
+            "csharp",
+            "elixir",
+            "php",
+            "swift",
+            "toml",
+        ] {
+            assert!(
+                super::symbol_query(language).is_some(),
+                "the {language} symbol query must compile against its grammar"
+            );
+        }
+    }
+}
@@ -0,0 +1,3 @@
+; Document symbols for Bash.
+
+(function_definition name: (word) @name) @definition.function
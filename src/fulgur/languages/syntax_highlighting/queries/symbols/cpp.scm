@@ -0,0 +1,16 @@
+; Document symbols for C++.
+
+(function_definition
+  declarator: (function_declarator
+    declarator: [(identifier) (field_identifier) (qualified_identifier) (destructor_name) (operator_name)] @name)) @definition.function
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (function_declarator declarator: (_) @name))) @definition.function
+(function_definition
+  declarator: (reference_declarator
+    (function_declarator declarator: (_) @name))) @definition.function
+(class_specifier name: (type_identifier) @name body: (_)) @definition.class
+(struct_specifier name: (type_identifier) @name body: (_)) @definition.type
+(union_specifier name: (type_identifier) @name body: (_)) @definition.type
+(enum_specifier name: (type_identifier) @name body: (_)) @definition.type
+(namespace_definition name: (namespace_identifier) @name) @definition.module
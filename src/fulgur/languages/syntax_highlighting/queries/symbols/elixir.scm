@@ -0,0 +1,32 @@
+; Document symbols for Elixir.
+; Definitions are ordinary calls, told apart by the name of the called macro.
+
+(call
+  target: (identifier) @_keyword
+  (arguments (alias) @name)
+  (#any-of? @_keyword "defmodule" "defprotocol")) @definition.module
+
+(call
+  target: (identifier) @_keyword
+  (arguments (alias) @name)
+  (#eq? @_keyword "defimpl")) @definition.implementation
+
+(call
+  target: (identifier) @_keyword
+  (arguments
+    [
+      (identifier) @name
+      (call target: (identifier) @name)
+      (binary_operator left: (call target: (identifier) @name) operator: "when")
+    ])
+  (#any-of? @_keyword "def" "defp")) @definition.function
+
+(call
+  target: (identifier) @_keyword
+  (arguments
+    [
+      (identifier) @name
+      (call target: (identifier) @name)
+      (binary_operator left: (call target: (identifier) @name) operator: "when")
+    ])
+  (#any-of? @_keyword "defmacro" "defmacrop")) @definition.macro
@@ -0,0 +1,6 @@
+; Document symbols for Markdown.
+; Each heading is captured with the section it opens, so nested sections become
+; nested outline entries.
+
+(section (atx_heading (inline) @name)) @definition.heading
+(section (setext_heading (paragraph) @name)) @definition.heading
@@ -0,0 +1,4 @@
+; Document symbols for Python.
+
+(class_definition name: (identifier) @name) @definition.class
+(function_definition name: (identifier) @name) @definition.function
@@ -0,0 +1,6 @@
+; Document symbols for Ruby.
+
+(class name: (_) @name) @definition.class
+(module name: (_) @name) @definition.module
+(method name: (_) @name) @definition.method
+(singleton_method name: (_) @name) @definition.method
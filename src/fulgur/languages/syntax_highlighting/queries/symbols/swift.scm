@@ -0,0 +1,8 @@
+; Document symbols for Swift.
+; Classes, structs, enums and extensions all parse as `class_declaration`.
+
+(class_declaration name: (_) @name) @definition.class
+(protocol_declaration name: (type_identifier) @name) @definition.interface
+(typealias_declaration name: (type_identifier) @name) @definition.type
+(function_declaration name: (simple_identifier) @name) @definition.function
+(protocol_function_declaration name: (simple_identifier) @name) @definition.function
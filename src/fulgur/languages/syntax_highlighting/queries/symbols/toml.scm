@@ -0,0 +1,4 @@
+; Document symbols for TOML: the table headers.
+
+(table [(bare_key) (dotted_key) (quoted_key)] @name) @definition.module
+(table_array_element [(bare_key) (dotted_key) (quoted_key)] @name) @definition.module
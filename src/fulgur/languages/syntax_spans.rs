@@ -0,0 +1,93 @@
+use crate::fulgur::{
+    languages::supported_languages::{language_from_filename, language_registry_name},
+    utils::code_html::SyntaxSpan,
+};
+use gpui::{FontStyle, FontWeight, Hsla};
+use gpui_component::{
+    highlighter::{HighlightTheme, LanguageRegistry, SyntaxHighlighter},
+    input::Rope,
+};
+
+/// Convert a theme color to 0xRRGGBB, dropping its alpha
+///
+/// ### Arguments
+/// - `color`: The color
+///
+/// ### Returns
+/// - `u32`: The color as 0xRRGGBB
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn hsla_to_rgb(color: Hsla) -> u32 {
+    let rgb = color.to_rgb();
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u32;
+    (channel(rgb.r) << 16) | (channel(rgb.g) << 8) | channel(rgb.b)
+}
+
+/// Resolve the language tag of a Markdown code block to a registered grammar
+///
+/// ### Arguments
+/// - `tag`: The language tag, e.g. `rust`, `rs` or `py`
+///
+/// ### Returns
+/// - `Some(String)`: The name of the grammar in the `LanguageRegistry`
+/// - `None`: No grammar matches the tag
+#[must_use]
+pub fn registry_name_for_tag(tag: &str) -> Option<String> {
+    let tag = tag
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .next()?
+        .to_ascii_lowercase();
+    if tag.is_empty() {
+        return None;
+    }
+    let registry = LanguageRegistry::singleton();
+    if registry.language(&tag).is_some() {
+        return Some(tag);
+    }
+    // Extensions such as `rs` or `py` resolve like the file names they end
+    let name = language_registry_name(&language_from_filename(&format!("snippet.{tag}")));
+    registry.language(name).is_some().then(|| name.to_string())
+}
+
+/// Compute the syntax styles of a text with the current theme
+///
+/// ### Arguments
+/// - `text`: The text to highlight
+/// - `language`: The name of its grammar in the `LanguageRegistry`
+/// - `theme`: The syntax theme of the current theme
+///
+/// ### Returns
+/// - `Vec<SyntaxSpan>`: The styled spans, in order; empty for an unknown grammar
+#[must_use]
+pub fn syntax_spans(text: &str, language: &str, theme: &HighlightTheme) -> Vec<SyntaxSpan> {
+    if text.is_empty() || LanguageRegistry::singleton().language(language).is_none() {
+        return Vec::new();
+    }
+    let mut highlighter = SyntaxHighlighter::new(language);
+    highlighter.update(None, &Rope::from(text));
+    highlighter
+        .styles(&(0..text.len()), theme)
+        .into_iter()
+        .map(|(range, style)| SyntaxSpan {
+            range,
+            color: style.color.map(hsla_to_rgb),
+            bold: style
+                .font_weight
+                .is_some_and(|weight| weight >= FontWeight::SEMIBOLD),
+            italic: style.font_style == Some(FontStyle::Italic),
+        })
+        .filter(|span| span.color.is_some() || span.bold || span.italic)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hsla_to_rgb;
+
+    #[test]
+    fn test_hsla_to_rgb_roundtrips_rgb_colors() {
+        for color in [0x00_00_00, 0xff_ff_ff, 0x33_66_99, 0xe0_6c_75] {
+            assert_eq!(hsla_to_rgb(gpui::rgb(color).into()), color);
+        }
+    }
+}
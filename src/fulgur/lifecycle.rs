@@ -1,13 +1,23 @@
+#[cfg(target_os = "linux")]
+use crate::fulgur::terminal::TerminalPanel;
 use crate::fulgur::{
-    Fulgur, editor_tab,
-    files::file_watcher::FileWatchState,
+    Fulgur,
+    diagnostics::DiagnosticsStore,
+    editor_tab,
+    files::{file_operations::AutoSaveState, file_watcher::FileWatchState},
     languages,
+    markdown_lint::MarkdownLintState,
+    outline::OutlinePanel,
     settings::Settings,
-    shared_state, state,
+    shared_state,
+    spell_check::SpellCheckState,
+    state,
     tab::{Tab, TabId},
+    task_runner::TaskRunnerState,
     ui::{
         bars::color_picker_bar::{ColorPickerBar, ColorPickerBarEvent},
         bars::csv_toolbar::CsvToolbar,
+        bars::json_query_bar::{JsonQueryBar, JsonQueryBarEvent},
         bars::markdown_toolbar::MarkdownToolbar,
         bars::search_bar::{SearchBar, SearchBarEvent},
         bars::status_bar::{StatusBar, StatusBarEvent},
@@ -16,6 +26,7 @@ use crate::fulgur::{
         tabs::tab_bar::{TabBar, TabBarEvent},
         themes,
     },
+    utils::markdown_links::LinkHistory,
     window_manager,
 };
 use gpui::{App, AppContext, Context, Entity, SharedString, Window, WindowId};
@@ -63,8 +74,9 @@ impl Fulgur {
             .as_ref()
             .and_then(|state| state.windows.get(window_index))
             .map_or_else(state::WindowState::allocate_id, |state| state.window_id);
-        let jump_to_line_input =
-            cx.new(|cx| InputState::new(window, cx).placeholder("Jump to line or line:character"));
+        let jump_to_line_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("Jump to line, line:character or @symbol")
+        });
         let rename_tab_input = cx.new(|cx| InputState::new(window, cx).placeholder("Tab name"));
         let entity = cx.new(|cx| {
             let weak_fulgur = cx.weak_entity();
@@ -100,6 +112,15 @@ impl Fulgur {
                 },
             );
 
+            let json_query_bar = cx.new(|cx| JsonQueryBar::new(window, cx));
+            let json_query_bar_subscription = cx.subscribe_in(
+                &json_query_bar,
+                window,
+                |this: &mut Self, _, event: &JsonQueryBarEvent, window, cx| {
+                    this.on_json_query_bar_event(*event, window, cx);
+                },
+            );
+
             let tab_bar = cx.new(|_| TabBar::new(weak_fulgur));
             let tab_bar_subscription = cx.subscribe_in(
                 &tab_bar,
@@ -120,6 +141,10 @@ impl Fulgur {
                     cx.notify();
                 },
             );
+            let window_activation_subscription =
+                cx.observe_window_activation(window, |this: &mut Self, window, cx| {
+                    this.on_window_activation_changed(window, cx);
+                });
             Self {
                 window_id,
                 persistent_window_id,
@@ -134,6 +159,8 @@ impl Fulgur {
                 csv_toolbar,
                 color_picker_bar,
                 _color_picker_bar_subscription: color_picker_bar_subscription,
+                json_query_bar,
+                _json_query_bar_subscription: json_query_bar_subscription,
                 jump_to_line_input,
                 rename_tab_input,
                 pending_jump: None,
@@ -151,6 +178,8 @@ impl Fulgur {
                 markdown_preview_focus: cx.focus_handle(),
                 markdown_panel_view_state: None,
                 markdown_preview_pending_copy: None,
+                markdown_link_history: LinkHistory::new(),
+                markdown_links_subscription: None,
                 status_bar,
                 _status_bar_subscription: status_bar_subscription,
                 tab_bar,
@@ -168,6 +197,17 @@ impl Fulgur {
                 inflight_saves: HashMap::new(),
                 pending_initial_active_tab: None,
                 has_rendered_once: false,
+                auto_save: AutoSaveState::default(),
+                _window_activation_subscription: window_activation_subscription,
+                split_panes: Vec::new(),
+                data_validation_subscriptions: HashMap::new(),
+                outline: OutlinePanel::default(),
+                diagnostics: DiagnosticsStore::default(),
+                spell_check: SpellCheckState::default(),
+                markdown_lint: MarkdownLintState::default(),
+                task_runner: TaskRunnerState::default(),
+                #[cfg(target_os = "linux")]
+                terminal: TerminalPanel::new(cx),
             }
         });
         entity.update(cx, |this, cx| {
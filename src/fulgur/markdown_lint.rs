@@ -0,0 +1,242 @@
+use crate::fulgur::{
+    Fulgur,
+    diagnostics::DiagnosticSource,
+    editor_tab::apply_minimal_edits,
+    settings::MarkdownLintSettings,
+    tab::TabId,
+    utils::markdown_lint::{LintFix, LintProblem, apply_fixes, fixes_at_offset, lint_markdown},
+};
+use gpui::{Context, Entity, Subscription, Task, Window};
+use gpui_component::input::{EditorState, InputEvent, Position};
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use std::time::Duration;
+
+/// Delay between the last edit and the lint it triggers
+const MARKDOWN_LINT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Source tag set on the diagnostics of Markdown lint problems
+const MARKDOWN_LINT_SOURCE: &str = "markdown lint";
+
+/// Lint problems of the active Markdown tab, underlined through the editor diagnostics
+#[derive(Default)]
+pub struct MarkdownLintState {
+    source: Option<(TabId, Entity<EditorState>)>, // The editor state the problems belong to
+    problems: Vec<LintProblem>,                   // Problems of `source`, in document order
+    rules: Option<MarkdownLintSettings>,          // Rules the problems were found with
+    stale: bool,                                  // Set when `source` changed since the last lint
+    #[allow(dead_code, reason = "RAII guard: keeps the subscription alive")]
+    subscription: Option<Subscription>, // Marks the problems stale on every edit of `source`
+    #[allow(
+        dead_code,
+        reason = "RAII guard: dropping the task cancels a pending lint"
+    )]
+    task: Option<Task<()>>, // The debounced lint in flight
+}
+
+impl Fulgur {
+    /// Keep the lint problems of the active Markdown tab in step with its content
+    ///
+    /// ### Description
+    /// A lint is scheduled when the active tab changes, after it was edited or
+    /// when the rules changed.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn refresh_markdown_lint(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let rules = self.settings.editor_settings.markdown_settings.lint.clone();
+        let active = if rules.enabled && self.is_markdown(cx) {
+            self.get_active_editor_tab(cx)
+                .filter(|editor_tab| !editor_tab.large_file)
+                .map(|editor_tab| (editor_tab.id, editor_tab.content.clone()))
+        } else {
+            None
+        };
+        if active != self.markdown_lint.source {
+            self.clear_markdown_lint_diagnostics(cx);
+            self.markdown_lint.subscription = active.as_ref().map(|(_, content)| {
+                cx.subscribe(content, |this: &mut Self, _, event: &InputEvent, cx| {
+                    if matches!(event, InputEvent::Change) {
+                        this.markdown_lint.stale = true;
+                        cx.notify();
+                    }
+                })
+            });
+            self.markdown_lint.source = active;
+            self.markdown_lint.stale = true;
+        }
+        if self.markdown_lint.rules.as_ref() != Some(&rules) {
+            self.markdown_lint.stale = true;
+        }
+        let Some((_, content)) = self.markdown_lint.source.clone() else {
+            return;
+        };
+        if !self.markdown_lint.stale {
+            return;
+        }
+        let text = content.read(cx).text().clone();
+        self.markdown_lint.stale = false;
+        self.markdown_lint.rules = Some(rules.clone());
+        self.markdown_lint.task = Some(cx.spawn_in(window, async move |this, window| {
+            window
+                .background_executor()
+                .timer(MARKDOWN_LINT_DEBOUNCE)
+                .await;
+            let problems = window
+                .background_executor()
+                .spawn(async move { lint_markdown(&text.to_string(), &rules) })
+                .await;
+            window
+                .update(|_window, cx| {
+                    _ = this.update(cx, |this, cx| {
+                        this.apply_markdown_lint(&content, problems, cx);
+                    });
+                })
+                .ok();
+        }));
+    }
+
+    /// Underline the problems found by a finished lint
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state that was linted
+    /// - `problems`: The problems found
+    /// - `cx`: The application context
+    fn apply_markdown_lint(
+        &mut self,
+        content: &Entity<EditorState>,
+        problems: Vec<LintProblem>,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .markdown_lint
+            .source
+            .as_ref()
+            .is_none_or(|(_, source)| source != content)
+        {
+            return;
+        }
+        let diagnostics = problems.iter().map(lint_problem_diagnostic).collect();
+        self.set_diagnostics(content, DiagnosticSource::MarkdownLint, diagnostics, cx);
+        self.markdown_lint.problems = problems;
+    }
+
+    /// Remove the lint underlines from the linted tab
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    fn clear_markdown_lint_diagnostics(&mut self, cx: &mut Context<Self>) {
+        self.markdown_lint.problems.clear();
+        self.markdown_lint.task = None;
+        if let Some((_, content)) = self.markdown_lint.source.clone() {
+            self.clear_diagnostics(&content, DiagnosticSource::MarkdownLint, cx);
+        }
+    }
+
+    /// Get the fixes of the lint problems under the cursor of an editor
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state the context menu was opened on
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `(Vec<LintFix>, usize)`: The fixes under the cursor, and the number of fixable problems in the document
+    pub(crate) fn markdown_lint_fixes_under_cursor(
+        &self,
+        content: &Entity<EditorState>,
+        cx: &Context<Self>,
+    ) -> (Vec<LintFix>, usize) {
+        match &self.markdown_lint.source {
+            Some((_, source)) if source == content => {
+                let problems = &self.markdown_lint.problems;
+                let fixable = problems
+                    .iter()
+                    .filter(|problem| problem.fix.is_some())
+                    .count();
+                (
+                    fixes_at_offset(problems, content.read(cx).cursor()),
+                    fixable,
+                )
+            }
+            _ => (Vec::new(), 0),
+        }
+    }
+
+    /// Apply lint fixes to the linted tab
+    ///
+    /// ### Arguments
+    /// - `fixes`: The fixes to apply
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn apply_markdown_lint_fixes(
+        &mut self,
+        fixes: &[LintFix],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((tab_id, content)) = self.markdown_lint.source.clone() else {
+            return;
+        };
+        let read_only = self.tab_entity_of(tab_id, cx).is_some_and(|tab| {
+            tab.read(cx)
+                .as_editor()
+                .is_some_and(|editor| editor.read_only)
+        });
+        if read_only {
+            return;
+        }
+        let text = content.read(cx).text().to_string();
+        let fixed = apply_fixes(&text, fixes);
+        apply_minimal_edits(&content, &fixed, window, cx);
+    }
+
+    /// Fix every lint problem of the linted tab that has a fix
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn fix_all_markdown_lint_problems(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let fixes: Vec<LintFix> = self
+            .markdown_lint
+            .problems
+            .iter()
+            .filter_map(|problem| problem.fix.clone())
+            .collect();
+        self.apply_markdown_lint_fixes(&fixes, window, cx);
+    }
+}
+
+/// Build the diagnostic underlining a lint problem
+///
+/// ### Arguments
+/// - `problem`: The lint problem
+///
+/// ### Returns
+/// - `Diagnostic`: A warning spanning the problem, with the code of its rule
+fn lint_problem_diagnostic(problem: &LintProblem) -> Diagnostic {
+    let (start, end) = problem.columns;
+    Diagnostic {
+        range: lsp_types::Range {
+            start: Position {
+                line: problem.line,
+                character: start,
+            },
+            end: Position {
+                line: problem.line,
+                character: end.max(start.saturating_add(1)),
+            },
+        },
+        severity: Some(DiagnosticSeverity::WARNING),
+        message: problem.message.clone(),
+        source: Some(MARKDOWN_LINT_SOURCE.to_string()),
+        code: Some(NumberOrString::String(problem.rule.code().to_string())),
+        related_information: None,
+        tags: None,
+        code_description: None,
+        data: None,
+    }
+}
@@ -1,20 +1,29 @@
 mod content_area;
+mod diagnostics;
 pub mod files;
 pub mod languages;
 mod lifecycle;
+mod markdown_lint;
+mod outline;
 mod render;
 pub mod settings;
 pub mod shared_state;
+pub mod spell_check;
+mod split_pane;
 pub mod state;
 pub mod sync;
+mod task_runner;
+#[cfg(target_os = "linux")]
+mod terminal;
 mod ui;
 pub mod utils;
 pub mod window_manager;
 
 use crate::fulgur::files::{
-    file_operations::PendingRemoteOpenOutcome, file_watcher::FileWatchState,
+    file_operations::{AutoSaveState, PendingRemoteOpenOutcome},
+    file_watcher::FileWatchState,
 };
-use gpui::{Entity, FocusHandle, Pixels, Point, Subscription, WindowId};
+use gpui::{Entity, EntityId, FocusHandle, Pixels, Point, Subscription, WindowId};
 use gpui_component::{input::InputState, menu::PopupMenu};
 use settings::Settings;
 use std::{
@@ -25,6 +34,7 @@ use ui::log_view::LogTailState;
 use ui::{
     bars::color_picker_bar::ColorPickerBar,
     bars::csv_toolbar::CsvToolbar,
+    bars::json_query_bar::JsonQueryBar,
     bars::markdown_toolbar::MarkdownToolbar,
     bars::search_bar::SearchBar,
     bars::status_bar::StatusBar,
@@ -50,9 +60,11 @@ pub struct Fulgur {
     csv_toolbar: Entity<CsvToolbar>, // The CSV structural-edit toolbar view (acts directly on the active tab's table, emits no events)
     color_picker_bar: Entity<ColorPickerBar>, // The color picker bar view (owns the picker widget and its three text inputs)
     _color_picker_bar_subscription: Subscription, // Routes ColorPickerBarEvent from the color picker bar to window-level handlers
-    pub jump_to_line_input: Entity<InputState>,   // Input for jumping to a line in the editor
-    pub rename_tab_input: Entity<InputState>,     // Input for renaming an unsaved tab
-    pending_jump: Option<editor_tab::Jump>,       // Pending jump to line action
+    json_query_bar: Entity<JsonQueryBar>, // The JSON query bar view; its results are listed in a side panel
+    _json_query_bar_subscription: Subscription, // Routes JsonQueryBarEvent from the JSON query bar to window-level handlers
+    pub jump_to_line_input: Entity<InputState>, // Input for jumping to a line in the editor
+    pub rename_tab_input: Entity<InputState>,   // Input for renaming an unsaved tab
+    pending_jump: Option<editor_tab::Jump>,     // Pending jump to line action
     pub settings: Settings, // The settings for the application (local snapshot, refreshed by the SharedAppState observer)
     _shared_state_observation: Subscription, // Global observer keeping the local settings snapshot in sync with SharedAppState and applying editor settings to tabs
     log_tail_state: HashMap<TabId, LogTailState>, // Per-log-tab tail bookkeeping (byte offset, dropped lines, pending text) keyed by tab id
@@ -65,8 +77,10 @@ pub struct Fulgur {
     editor_context_menu: Option<(Point<Pixels>, Entity<PopupMenu>)>, // Custom right-click context menu for the editor and markdown preview
     editor_context_menu_subscription: Option<Subscription>, // Subscription to clear editor_context_menu on dismiss
     markdown_preview_focus: FocusHandle, // Stable dispatch target for the markdown preview context menu (Copy / Select All)
-    markdown_panel_view_state: Option<Entity<gpui_component::text::TextViewState>>, // Owned text view state backing the inline markdown preview panel, created lazily on render
+    markdown_panel_view_state: Option<Entity<ui::markdown_preview::MarkdownPreviewState>>, // Owned preview state backing the inline markdown preview panel, created lazily on render
     markdown_preview_pending_copy: Option<String>, // Preview selection captured at right-click time, before the menu's own click clears it, consumed by the Copy action
+    markdown_link_history: utils::markdown_links::LinkHistory<ui::markdown_preview::PreviewLocation>, // Locations left by following links in the Markdown preview, for Back and Forward
+    markdown_links_subscription: Option<Subscription>, // Clears the broken link diagnostics on the next edit of the checked tab
     status_bar: Entity<StatusBar>, // The status bar view at the bottom of the window
     _status_bar_subscription: Subscription, // Routes StatusBarEvent from the status bar to window-level handlers
     tab_bar: Entity<TabBar>,                // The tab bar view at the top of the window
@@ -84,4 +98,15 @@ pub struct Fulgur {
     inflight_saves: HashMap<TabId, PathBuf>, // Destination path of each background local-file write in flight, keyed by tab id; guards against overlapping saves and suppresses self-save watcher events
     pending_initial_active_tab: Option<TabId>, // Active tab to re-activate after first render so dialogs can open safely
     has_rendered_once: bool, // Tracks first render completion for startup actions that require mounted Root layers
+    auto_save: AutoSaveState, // Auto-save ticker, tab-switch tracking and last failure shown in the status bar
+    _window_activation_subscription: Subscription, // Auto-saves modified tabs when the window loses focus
+    outline: outline::OutlinePanel, // Outline side panel listing the active tab's symbols
+    diagnostics: diagnostics::DiagnosticsStore, // Diagnostics of each editor, keyed by the feature that produced them
+    spell_check: spell_check::SpellCheckState, // Misspellings of the active tab, underlined as diagnostics
+    markdown_lint: markdown_lint::MarkdownLintState, // Lint problems of the active Markdown tab, underlined as diagnostics
+    split_panes: Vec<split_pane::SplitPane>, // Editor panes split off the primary one, in opening order, each showing a tab of the shared pool
+    data_validation_subscriptions: HashMap<EntityId, Subscription>, // Clear the structured data parse error diagnostic on the next edit of each validated editor, keyed by editor entity
+    task_runner: task_runner::TaskRunnerState, // Task panel: the running build or test task, its output and the problems it reported
+    #[cfg(target_os = "linux")]
+    terminal: terminal::TerminalPanel, // Terminal panel: the shells of the window and the grid showing the active one
 }
@@ -0,0 +1,361 @@
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::Jump,
+    languages::{
+        supported_languages::language_registry_name,
+        symbols::{DocumentSymbol, extract_symbols, symbol_at_offset},
+    },
+    tab::TabId,
+};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, Context, Entity, InteractiveElement, IntoElement, ParentElement, SharedString,
+    StatefulInteractiveElement, Styled, Subscription, Task, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, h_flex, input::EditorState, input::InputEvent, scroll::ScrollableElement, v_flex,
+};
+use std::time::Duration;
+
+/// Width of the outline side panel
+const OUTLINE_PANEL_WIDTH: f32 = 240.0;
+
+/// Indentation added per nesting level in the outline
+const OUTLINE_INDENT: f32 = 12.0;
+
+/// Delay after the last edit before the outline symbols are extracted again
+const OUTLINE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Symbols of the active tab, shown in the outline side panel
+#[derive(Default)]
+pub struct OutlinePanel {
+    visible: bool,
+    source: Option<(TabId, Entity<EditorState>)>, // The editor state the symbols were extracted from
+    symbols: Vec<DocumentSymbol>,                 // Symbols of `source`, in document order
+    stale: bool,      // Set when `source` changed since the last extraction
+    up_to_date: bool, // Set once `symbols` were extracted from the current text of `source`
+    #[allow(dead_code, reason = "RAII guard: keeps the subscription alive")]
+    subscription: Option<Subscription>, // Marks the symbols stale on every edit of `source`
+    #[allow(
+        dead_code,
+        reason = "RAII guard: dropping the task cancels a pending extraction"
+    )]
+    task: Option<Task<()>>, // The extraction in flight
+}
+
+impl Fulgur {
+    /// Show or hide the outline side panel
+    ///
+    /// ### Arguments
+    /// - `_window`: The window context
+    /// - `cx`: The application context
+    pub fn toggle_outline(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        self.outline.visible = !self.outline.visible;
+        if !self.outline.visible {
+            self.outline.source = None;
+            self.outline.symbols.clear();
+            self.outline.subscription = None;
+            self.outline.task = None;
+        }
+        cx.notify();
+    }
+
+    /// Get the symbols the outline already holds for the active editor tab
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(Vec<DocumentSymbol>)`: The symbols, when they were extracted from the current text of the active tab
+    /// - `None`: The outline is hidden, shows another tab, or is being refreshed
+    pub(crate) fn outline_symbols_of_active_tab(
+        &self,
+        cx: &Context<Self>,
+    ) -> Option<Vec<DocumentSymbol>> {
+        let (_, source) = self.outline.source.as_ref()?;
+        let active = self.get_active_editor_tab(cx)?;
+        (self.outline.visible && self.outline.up_to_date && active.content == *source)
+            .then(|| self.outline.symbols.clone())
+    }
+
+    /// Extract the symbols of the active editor tab in the background
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Task<Vec<DocumentSymbol>>`: The extraction, giving no symbols for large files or languages without a symbol query
+    pub(crate) fn extract_active_tab_symbols(
+        &self,
+        cx: &Context<Self>,
+    ) -> Task<Vec<DocumentSymbol>> {
+        let source = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.large_file)
+            .map(|editor_tab| {
+                (
+                    language_registry_name(&editor_tab.language),
+                    editor_tab.content.read(cx).text().clone(),
+                )
+            });
+        cx.background_executor().spawn(async move {
+            source
+                .map(|(language_name, text)| extract_symbols(language_name, &text.to_string()))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Keep the outline symbols in step with the active tab
+    ///
+    /// ### Description
+    /// The symbols are re-extracted when the active tab changes, when its editor
+    /// state is rebuilt (on a language change) or after it was edited. Extraction
+    /// parses the whole document, so it runs in the background, and after an edit
+    /// it waits for typing to pause.
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub(crate) fn refresh_outline(&mut self, cx: &mut Context<Self>) {
+        if !self.outline.visible {
+            return;
+        }
+        let active = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.large_file)
+            .map(|editor_tab| (editor_tab.id, editor_tab.content.clone()));
+        let switched = active != self.outline.source;
+        if switched {
+            self.outline.subscription = active.as_ref().map(|(_, content)| {
+                cx.subscribe(content, |this: &mut Self, _, event: &InputEvent, cx| {
+                    if matches!(event, InputEvent::Change) {
+                        this.outline.stale = true;
+                        this.outline.up_to_date = false;
+                        cx.notify();
+                    }
+                })
+            });
+            self.outline.source = active;
+            self.outline.symbols.clear();
+            self.outline.stale = true;
+            self.outline.up_to_date = false;
+        }
+        if !self.outline.stale {
+            return;
+        }
+        self.outline.stale = false;
+        let (Some((_, content)), Some(editor_tab)) =
+            (self.outline.source.clone(), self.get_active_editor_tab(cx))
+        else {
+            self.outline.task = None;
+            return;
+        };
+        let language_name = language_registry_name(&editor_tab.language);
+        let text = content.read(cx).text().clone();
+        self.outline.task = Some(cx.spawn(async move |this, cx| {
+            if !switched {
+                cx.background_executor().timer(OUTLINE_DEBOUNCE).await;
+            }
+            let symbols = cx
+                .background_executor()
+                .spawn(async move { extract_symbols(language_name, &text.to_string()) })
+                .await;
+            _ = this.update(cx, |this, cx| {
+                if this
+                    .outline
+                    .source
+                    .as_ref()
+                    .is_some_and(|(_, source)| *source == content)
+                {
+                    this.outline.symbols = symbols;
+                    this.outline.up_to_date = !this.outline.stale;
+                    cx.notify();
+                }
+            });
+        }));
+    }
+
+    /// Move the cursor of the active tab to a symbol
+    ///
+    /// ### Arguments
+    /// - `jump`: The position of the symbol name
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn jump_to_symbol(&mut self, jump: Jump, window: &mut Window, cx: &mut Context<Self>) {
+        self.update_active_editor_tab(cx, |editor_tab, cx| {
+            editor_tab.jump_to_line(window, cx, jump);
+        });
+    }
+
+    /// Lay the outline panel out to the right of the content, when it is visible
+    ///
+    /// ### Arguments
+    /// - `content`: The rendered content area
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The content with the outline next to it, or `content` unchanged when hidden
+    pub(crate) fn render_outline_layout(
+        &self,
+        content: AnyElement,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        if !self.outline.visible {
+            return content;
+        }
+        let cursor = self
+            .outline
+            .source
+            .as_ref()
+            .map(|(_, content)| content.read(cx).cursor());
+        let current = cursor.and_then(|cursor| symbol_at_offset(&self.outline.symbols, cursor));
+        let mut list = v_flex()
+            .id("outline-list")
+            .size_full()
+            .overflow_y_scrollbar();
+        if self.outline.symbols.is_empty() {
+            list = list.child(
+                div()
+                    .p_2()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("No symbols"),
+            );
+        }
+        for (index, symbol) in self.outline.symbols.iter().enumerate() {
+            let jump = Jump {
+                line: symbol.line,
+                character: Some(symbol.character),
+            };
+            #[allow(clippy::cast_precision_loss)]
+            let indent = px(8.0 + symbol.depth as f32 * OUTLINE_INDENT);
+            let row = h_flex()
+                .id(("outline-symbol", index))
+                .w_full()
+                .gap_1()
+                .py_0p5()
+                .pl(indent)
+                .pr_2()
+                .text_sm()
+                .cursor_pointer()
+                .hover(|this| this.bg(cx.theme().muted))
+                .when(current == Some(index), |this| {
+                    this.bg(cx.theme().accent)
+                        .text_color(cx.theme().accent_foreground)
+                })
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(symbol.kind.label()),
+                )
+                .child(
+                    div()
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .child(SharedString::from(symbol.name.clone())),
+                )
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.jump_to_symbol(jump, window, cx);
+                }));
+            list = list.child(row);
+        }
+        h_flex()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .child(v_flex().h_full().flex_1().min_w_0().child(content))
+            .child(
+                v_flex()
+                    .h_full()
+                    .w(px(OUTLINE_PANEL_WIDTH))
+                    .flex_none()
+                    .border_l_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().tab_bar)
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .border_b_1()
+                            .border_color(cx.theme().border)
+                            .child("OUTLINE"),
+                    )
+                    .child(list),
+            )
+            .into_any_element()
+    }
+}
+
+#[cfg(all(test, feature = "gpui-test-support"))]
+mod tests {
+    use super::OUTLINE_DEBOUNCE;
+    use crate::fulgur::{
+        files::file_operations::test_helpers::setup_fulgur,
+        languages::supported_languages::SupportedLanguage,
+    };
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    fn test_outline_symbols_are_extracted_once_typing_pauses(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.update_active_editor_tab(cx, |editor, cx| {
+                    editor.language = SupportedLanguage::Rust;
+                    editor.content.update(cx, |state, cx| {
+                        state.set_value("fn open() {}\n", window, cx);
+                    });
+                });
+                this.toggle_outline(window, cx);
+                this.refresh_outline(cx);
+            });
+        });
+        visual_cx.run_until_parked();
+        fulgur.read_with(&visual_cx, |this, _| {
+            let names: Vec<&str> = this
+                .outline
+                .symbols
+                .iter()
+                .map(|symbol| symbol.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["open"]);
+        });
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.update_active_editor_tab(cx, |editor, cx| {
+                    editor.content.update(cx, |state, cx| {
+                        state.set_value("fn open() {}\nfn close() {}\n", window, cx);
+                    });
+                });
+                this.refresh_outline(cx);
+            });
+        });
+        visual_cx.run_until_parked();
+        fulgur.read_with(&visual_cx, |this, _| {
+            assert_eq!(
+                this.outline.symbols.len(),
+                1,
+                "an edit must not re-extract the symbols before typing pauses"
+            );
+        });
+
+        visual_cx
+            .background_executor
+            .advance_clock(OUTLINE_DEBOUNCE);
+        visual_cx.run_until_parked();
+        fulgur.read_with(&visual_cx, |this, _| {
+            let names: Vec<&str> = this
+                .outline
+                .symbols
+                .iter()
+                .map(|symbol| symbol.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["open", "close"]);
+        });
+    }
+}
@@ -4,13 +4,18 @@ use crate::fulgur::{
     ui::{
         self, dialogs::about::about, notifications::update_notification::make_update_notification,
     },
+    utils::markdown_table::TableEdit,
 };
 use crate::register_action;
 use gpui::{
     Anchor, App, Context, ExternalPaths, FocusHandle, Focusable, InteractiveElement, IntoElement,
     ParentElement, Render, Styled, Window, div, px,
 };
-use gpui_component::{ActiveTheme, Root, StyledExt, WindowExt, v_flex};
+use gpui_component::{
+    ActiveTheme, Root, StyledExt, WindowExt,
+    input::{IndentInline, OutdentInline, Paste},
+    v_flex,
+};
 
 impl Focusable for Fulgur {
     /// Get the focus handle for the Fulgur instance
@@ -52,6 +57,16 @@ impl Render for Fulgur {
         self.handle_pending_tab_removal(window, cx);
         self.handle_pending_jump_to_line(window, cx);
         self.refresh_window_title(cx);
+        self.refresh_outline(cx);
+        self.refresh_json_query(cx);
+        self.refresh_spell_check(window, cx);
+        self.refresh_markdown_lint(window, cx);
+        self.refresh_task_diagnostics(cx);
+        self.refresh_available_tasks(cx);
+        self.handle_pending_task_jump(window, cx);
+        #[cfg(target_os = "linux")]
+        self.layout_terminals(window);
+        self.refresh_auto_save(window, cx);
         let active_tab_index = self.active_tab_index(cx);
         let app_content = self.build_app_content_with_actions(active_tab_index, window, cx);
         self.assemble_ui_tree(app_content, window, cx)
@@ -111,9 +126,33 @@ impl Fulgur {
         register_action!(app_content, cx, ui::menus::SettingsTab => open_settings);
         register_action!(app_content, cx, ui::menus::FindInFile => find_in_file);
         register_action!(app_content, cx, ui::menus::ToggleColorPicker => toggle_color_picker);
+        register_action!(app_content, cx, ui::menus::SplitRight => split_right);
+        register_action!(app_content, cx, ui::menus::SplitDown => split_down);
+        register_action!(app_content, cx, ui::menus::CloseSplit => close_split);
+        register_action!(app_content, cx, ui::menus::FocusOtherPane => focus_other_pane);
+        register_action!(app_content, cx, ui::menus::ToggleOutline => toggle_outline);
+        register_action!(app_content, cx, ui::menus::ToggleJsonQuery => toggle_json_query);
+        register_action!(app_content, cx, ui::menus::ToggleTaskPanel => toggle_task_panel);
+        register_action!(app_content, cx, ui::menus::RunLastTask => run_last_task);
+        register_action!(app_content, cx, ui::menus::StopTask => stop_task);
+        #[cfg(target_os = "linux")]
+        {
+            register_action!(app_content, cx, ui::menus::ToggleTerminal => toggle_terminal);
+            register_action!(app_content, cx, ui::menus::NewTerminal => new_terminal);
+        }
         register_action!(app_content, cx, ui::menus::NextTab => on_next_tab);
         register_action!(app_content, cx, ui::menus::PreviousTab => on_previous_tab);
         register_action!(app_content, cx, ui::menus::JumpToLine => show_jump_to_line_dialog);
+        register_action!(app_content, cx, ui::menus::GoToSymbol => show_go_to_symbol_dialog);
+        register_action!(app_content, cx, ui::menus::ToggleLineComment => toggle_line_comment);
+        register_action!(app_content, cx, ui::menus::ToggleBlockComment => toggle_block_comment);
+        register_action!(app_content, cx, ui::menus::TransformText => transform_text(.0));
+        register_action!(app_content, cx, ui::menus::PipeThroughCommand => show_pipe_command_dialog);
+        register_action!(app_content, cx, ui::menus::ValidateData => validate_data);
+        register_action!(app_content, cx, ui::menus::PrettyPrintData => pretty_print_data);
+        register_action!(app_content, cx, ui::menus::MinifyData => minify_data);
+        register_action!(app_content, cx, ui::menus::SortDataKeys => sort_data_keys);
+        register_action!(app_content, cx, ui::menus::ConvertData => convert_data(.0));
         register_action!(app_content, cx, ui::menus::SelectTheme => select_theme_sheet);
         register_action!(app_content, cx, ui::menus::About => call about);
         register_action!(app_content, cx, ui::menus::SwitchTheme => switch_to_theme(.0, no_window));
@@ -126,6 +165,7 @@ impl Fulgur {
         register_action!(app_content, cx, ui::tabs::tab_bar::CopyPath => on_copy_path(&action));
         register_action!(app_content, cx, ui::tabs::tab_bar::DuplicateTab => on_duplicate_tab(&action));
         register_action!(app_content, cx, ui::tabs::tab_bar::SetTabColor => on_set_tab_color(&action));
+        register_action!(app_content, cx, ui::tabs::tab_bar::ToggleReadOnly => on_toggle_read_only(&action));
         register_action!(app_content, cx, ui::tabs::tab_bar::RenameTab => on_rename_tab(&action));
         register_action!(app_content, cx, ui::menus::OpenRecentFile => do_open_recent_file(.0));
         register_action!(app_content, cx, ui::menus::CheckForUpdates => check_for_updates);
@@ -134,8 +174,31 @@ impl Fulgur {
         register_action!(app_content, cx, ui::menus::ClearRecentFiles => clear_recent_files(cx_only));
         register_action!(app_content, cx, ui::menus::CloseFile => close_active_tab);
         register_action!(app_content, cx, ui::menus::PrintFile => print_file);
+        register_action!(app_content, cx, ui::menus::ExportMarkdownHtml => export_markdown_html(.0));
+        register_action!(app_content, cx, ui::menus::MarkdownLinkBack => markdown_link_back);
+        register_action!(app_content, cx, ui::menus::MarkdownLinkForward => markdown_link_forward);
+        register_action!(app_content, cx, ui::menus::CheckMarkdownLinks => check_markdown_links);
+        register_action!(app_content, cx, ui::menus::CopyAsRichText => copy_as_rich_text(.0));
         register_action!(app_content, cx, ui::menus::DockActivateTab => handle_dock_activate_tab(&action));
         register_action!(app_content, cx, ui::menus::DockActivateTabByTitle => handle_dock_activate_tab_by_title(&action));
+        // Tab and Shift-Tab move between the cells of a Markdown table before the editor indents
+        app_content = app_content
+            .capture_action(cx.listener(|this, _: &IndentInline, window, cx| {
+                if this.move_between_table_cells(TableEdit::NextCell, window, cx) {
+                    cx.stop_propagation();
+                }
+            }))
+            .capture_action(cx.listener(|this, _: &OutdentInline, window, cx| {
+                if this.move_between_table_cells(TableEdit::PreviousCell, window, cx) {
+                    cx.stop_propagation();
+                }
+            }))
+            // Pasting an image into a Markdown tab saves it next to the document
+            .capture_action(cx.listener(|this, _: &Paste, window, cx| {
+                if this.paste_image_into_markdown(window, cx) {
+                    cx.stop_propagation();
+                }
+            }));
         app_content =
             app_content.on_drop(cx.listener(|this, paths: &ExternalPaths, window, cx| {
                 this.handle_external_paths_drop(paths, window, cx);
@@ -144,13 +207,25 @@ impl Fulgur {
         let markdown_toolbar_visible = self.markdown_toolbar_visible(cx);
         let csv_toolbar_visible = self.csv_toolbar_visible(cx);
         let color_picker_bar_visible = self.color_picker_bar.read(cx).is_visible();
+        let json_query_bar_visible = self.json_query_bar.read(cx).is_visible();
+        let content_area = self.render_content_area(active_tab_index, window, cx);
+        let panels = self.render_task_panel_layout(
+            self.render_json_query_layout(
+                self.render_outline_layout(self.render_split_layout(content_area, cx), cx),
+                cx,
+            ),
+            cx,
+        );
+        #[cfg(target_os = "linux")]
+        let panels = self.render_terminal_panel_layout(panels, window, cx);
         app_content = app_content
             .child(self.tab_bar.clone())
-            .child(self.render_content_area(active_tab_index, window, cx))
+            .child(panels)
             .children(markdown_toolbar_visible.then(|| self.markdown_toolbar.clone()))
             .children(csv_toolbar_visible.then(|| self.csv_toolbar.clone()))
             .children(search_bar_visible.then(|| self.search_bar.clone()))
-            .children(color_picker_bar_visible.then(|| self.color_picker_bar.clone()));
+            .children(color_picker_bar_visible.then(|| self.color_picker_bar.clone()))
+            .children(json_query_bar_visible.then(|| self.json_query_bar.clone()));
         if let Some(Tab::Editor(_)) = self.active_tab(cx) {
             app_content = app_content.child(self.status_bar.clone());
         }
@@ -3,9 +3,9 @@ mod propagation;
 mod types;
 
 pub use types::{
-    AppSettings, DEFAULT_PROFILE_NAME, EditorSettings, MAX_PROFILES, MarkdownPreviewMode,
-    MarkdownSettings, ProfileId, RecentFiles, ServerProfile, Settings, SynchronizationSettings,
-    TabColorStyle, ThemeFile, ThemeInfo, Themes, new_profile_id,
+    AppSettings, AutoSaveMode, DEFAULT_PROFILE_NAME, EditorSettings, IndentationPreference, MAX_PROFILES,
+    MarkdownLintSettings, MarkdownPreviewMode, MarkdownSettings, PrintSettings, ProfileId, RecentFiles, ServerProfile,
+    Settings, SynchronizationSettings, TabColorStyle, ThemeFile, ThemeInfo, Themes, new_profile_id,
 };
 
 #[cfg(test)]
@@ -10,6 +10,7 @@ const FONT_SIZE_MAX: f32 = 72.0;
 const TAB_SIZE_MIN: usize = 1;
 const TAB_SIZE_MAX: usize = 16;
 const MAX_RECENT_FILES_MAX: usize = 100;
+const MAX_RECENT_PIPE_COMMANDS: usize = 10;
 
 impl Settings {
     /// Get the path to the settings file
@@ -227,6 +228,23 @@ impl Settings {
         self.recent_files.add_file(file);
         self.save()
     }
+
+    /// Remember a command run through "Pipe Through Command"
+    ///
+    /// ### Arguments
+    /// - `command`: The command that was run
+    ///
+    /// ### Errors
+    /// Returns an error if persisting the updated settings to disk fails.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: The result of the operation
+    /// - `Err(anyhow::Error)`: If there was an error saving the settings
+    pub fn add_pipe_command(&mut self, command: &str) -> anyhow::Result<()> {
+        self.app_settings
+            .remember_pipe_command(command, MAX_RECENT_PIPE_COMMANDS);
+        self.save()
+    }
 }
 
 /// Check whether a string is a plausibly-valid email address.
@@ -56,5 +56,6 @@ impl Fulgur {
                 tab.update_settings(window, cx, &settings);
             });
         }
+        self.rebuild_split_panes(window, cx);
     }
 }
@@ -1,4 +1,6 @@
-use crate::fulgur::settings::{RecentFiles, ServerProfile, Settings};
+use crate::fulgur::settings::{
+    AppSettings, MarkdownSettings, RecentFiles, ServerProfile, Settings,
+};
 use std::path::PathBuf;
 use tempfile::TempDir;
 
@@ -157,6 +159,23 @@ fn recent_files_clear_empty_list() {
     assert_eq!(recent_files.get_files().len(), 0);
 }
 
+#[test]
+fn remember_pipe_command_moves_to_front_and_caps() {
+    let mut app_settings = AppSettings::new();
+    app_settings.remember_pipe_command("sort", 3);
+    app_settings.remember_pipe_command("uniq", 3);
+    app_settings.remember_pipe_command("  sort  ", 3);
+    app_settings.remember_pipe_command("", 3);
+    assert_eq!(app_settings.recent_pipe_commands, vec!["sort", "uniq"]);
+
+    app_settings.remember_pipe_command("jq .", 3);
+    app_settings.remember_pipe_command("wc -l", 3);
+    assert_eq!(
+        app_settings.recent_pipe_commands,
+        vec!["wc -l", "jq .", "sort"]
+    );
+}
+
 #[test]
 fn settings_load_without_persist_unsaved_buffers_field_defaults_to_enabled() {
     // Settings files written before the option existed must keep the old behavior.
@@ -194,6 +213,54 @@ fn settings_load_without_persist_unsaved_buffers_field_defaults_to_enabled() {
     assert!(Settings::new().app_settings.persist_unsaved_buffers);
 }
 
+#[test]
+fn settings_load_without_print_settings_enables_all_print_options() {
+    let json = r#"{
+        "editor_settings": {
+            "show_line_numbers": true,
+            "show_indent_guides": true,
+            "soft_wrap": false,
+            "font_size": 14.0,
+            "tab_size": 4,
+            "markdown_settings": {
+                "show_markdown_preview": true,
+                "show_markdown_toolbar": false
+            }
+        },
+        "app_settings": {
+            "confirm_exit": true,
+            "theme": "Default Light",
+            "synchronization_settings": {
+                "is_synchronization_activated": false,
+                "profiles": []
+            }
+        },
+        "recent_files": {
+            "files": [],
+            "max_files": 10
+        }
+    }"#;
+    let settings: Settings = serde_json::from_str(json).unwrap();
+    let print_settings = &settings.editor_settings.print_settings;
+    assert!(print_settings.line_numbers);
+    assert!(print_settings.header);
+    assert!(print_settings.footer);
+}
+
+#[test]
+fn settings_load_without_markdown_lint_settings_uses_default_rules() {
+    let json = r#"{
+        "show_markdown_preview": true,
+        "show_markdown_toolbar": false
+    }"#;
+    let markdown_settings: MarkdownSettings = serde_json::from_str(json).unwrap();
+    let lint = &markdown_settings.lint;
+    assert!(lint.enabled);
+    assert!(lint.heading_increment);
+    assert!(!lint.line_length);
+    assert_eq!(lint.max_line_length, 120);
+}
+
 #[test]
 fn settings_load_without_is_deduplication_field_defaults_it_to_true() {
     let json = r#"{
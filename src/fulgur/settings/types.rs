@@ -2,7 +2,7 @@ use crate::fulgur::themes::{BundledThemes, themes_directory_path};
 use gpui::SharedString;
 use gpui_component::scroll::ScrollbarMode;
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
 /// Stable identifier for a server profile. Generated as a UUID v4 string
 /// at profile creation and never reused.
@@ -186,6 +186,20 @@ impl SynchronizationSettings {
     }
 }
 
+/// Determines when modified tabs are saved without an explicit save
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Debug)]
+pub enum AutoSaveMode {
+    /// Only save on an explicit save
+    #[default]
+    Off,
+    /// Save once a tab has not been edited for `auto_save_delay_ms`
+    AfterDelay,
+    /// Save a tab when another tab is activated or the window loses focus
+    OnFocusChange,
+    /// Save every modified tab when the window loses focus
+    OnWindowChange,
+}
+
 /// Determines how the Markdown preview is displayed
 #[derive(Clone, Serialize, Deserialize, PartialEq, Default)]
 pub enum MarkdownPreviewMode {
@@ -202,6 +216,11 @@ pub struct MarkdownSettings {
     pub show_markdown_toolbar: bool,
     #[serde(default = "default_limit_preview_width")]
     pub limit_preview_width: bool,
+    /// Folder, relative to the document, that pasted and dropped images are saved into.
+    #[serde(default = "default_image_folder")]
+    pub image_folder: String,
+    #[serde(default)]
+    pub lint: MarkdownLintSettings,
 }
 
 impl Default for MarkdownSettings {
@@ -222,6 +241,101 @@ impl MarkdownSettings {
             show_markdown_preview: true,
             show_markdown_toolbar: false,
             limit_preview_width: true,
+            image_folder: default_image_folder(),
+            lint: MarkdownLintSettings::new(),
+        }
+    }
+}
+
+/// Rules of the Markdown linter, each one on or off
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct MarkdownLintSettings {
+    pub enabled: bool,
+    pub heading_increment: bool,  // Headings go down one level at a time
+    pub trailing_spaces: bool,    // Lines end without spaces, but for hard line breaks
+    pub list_marker_style: bool,  // Bullet lists use the same marker throughout a document
+    pub missing_alt_text: bool,   // Images have alternative text
+    pub duplicate_headings: bool, // No two headings have the same text
+    pub line_length: bool,        // Lines are at most `max_line_length` characters long
+    pub max_line_length: usize,
+}
+
+impl Default for MarkdownLintSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownLintSettings {
+    /// Create a new Markdown lint settings instance
+    ///
+    /// ### Returns
+    /// - `MarkdownLintSettings`: Every rule on, but the line length
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            heading_increment: true,
+            trailing_spaces: true,
+            list_marker_style: true,
+            missing_alt_text: true,
+            duplicate_headings: true,
+            line_length: false,
+            max_line_length: 120,
+        }
+    }
+}
+
+/// Indent style and width set for a language
+///
+/// ### Description
+/// Either part may be left out, in which case it comes from the next source in
+/// line: the detected indentation, then the global settings.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IndentationPreference {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_spaces: Option<bool>,
+}
+
+impl IndentationPreference {
+    /// Check whether the preference sets neither the style nor the width
+    ///
+    /// ### Returns
+    /// - `true`: Nothing is set
+    /// - `false`: The style, the width or both are set
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tab_size.is_none() && self.use_spaces.is_none()
+    }
+}
+
+/// Options of printed documents
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct PrintSettings {
+    pub line_numbers: bool,
+    pub header: bool, // File name, path and date above the document
+    pub footer: bool, // Page numbers below each page
+}
+
+impl Default for PrintSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrintSettings {
+    /// Create a new print settings instance
+    ///
+    /// ### Returns
+    /// - `PrintSettings`: The new print settings instance
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            line_numbers: true,
+            header: true,
+            footer: true,
         }
     }
 }
@@ -242,8 +356,25 @@ pub struct EditorSettings {
     pub watch_files: bool,
     #[serde(default = "default_use_spaces")]
     pub use_spaces: bool,
+    #[serde(default = "default_detect_indentation")]
+    pub detect_indentation: bool,
+    /// Indentation of each language, keyed by grammar name such as `python`.
+    #[serde(default)]
+    pub language_indentation: BTreeMap<String, IndentationPreference>,
     #[serde(default = "default_highlight_colors")]
     pub highlight_colors: bool,
+    #[serde(default)]
+    pub spell_check: bool,
+    #[serde(default = "default_spell_check_language")]
+    pub spell_check_language: String,
+    #[serde(default)]
+    pub auto_save: AutoSaveMode,
+    #[serde(default = "default_auto_save_delay_ms")]
+    pub auto_save_delay_ms: u64,
+    #[serde(default)]
+    pub auto_save_remote: bool,
+    #[serde(default)]
+    pub print_settings: PrintSettings,
 }
 
 /// How a tab's color tag is shown in the tab bar.
@@ -270,6 +401,12 @@ pub struct AppSettings {
     pub tab_color_style: TabColorStyle,
     #[serde(default = "default_persist_unsaved_buffers")]
     pub persist_unsaved_buffers: bool,
+    /// Command used to write files that need root privileges on Linux, such as `pkexec` or `sudo -A`.
+    #[serde(default = "default_privileged_save_helper")]
+    pub privileged_save_helper: String,
+    /// Shell commands last run through "Pipe Through Command", most recent first.
+    #[serde(default)]
+    pub recent_pipe_commands: Vec<String>,
 }
 
 /// Default value for `debug_mode` setting
@@ -296,6 +433,14 @@ fn default_limit_preview_width() -> bool {
     true
 }
 
+/// Default value for `image_folder` setting
+///
+/// ### Returns
+/// - `String`: an `images` folder next to the document
+fn default_image_folder() -> String {
+    "images".to_string()
+}
+
 /// Default value for `use_spaces` setting
 ///
 /// ### Returns
@@ -304,6 +449,14 @@ fn default_use_spaces() -> bool {
     true
 }
 
+/// Default value for `detect_indentation` setting
+///
+/// ### Returns
+/// - `true`: infer the indentation of opened files from their content by default
+fn default_detect_indentation() -> bool {
+    true
+}
+
 /// Default value for `font_family` setting
 fn default_font_family() -> String {
     "Monaco".to_string()
@@ -317,6 +470,22 @@ fn default_highlight_colors() -> bool {
     true
 }
 
+/// Default value for `spell_check_language` setting
+///
+/// ### Returns
+/// - `String`: the Hunspell dictionary name loaded by default
+fn default_spell_check_language() -> String {
+    "en_US".to_string()
+}
+
+/// Default value for `auto_save_delay_ms` setting
+///
+/// ### Returns
+/// - `1000`: save one second after the last edit by default
+fn default_auto_save_delay_ms() -> u64 {
+    1000
+}
+
 /// Default value for `persist_unsaved_buffers` setting
 ///
 /// ### Returns
@@ -325,6 +494,14 @@ fn default_persist_unsaved_buffers() -> bool {
     true
 }
 
+/// Default value for `privileged_save_helper` setting
+///
+/// ### Returns
+/// - `String`: the polkit helper, which prompts for credentials graphically
+fn default_privileged_save_helper() -> String {
+    "pkexec".to_string()
+}
+
 /// Default value for `is_deduplication` setting
 ///
 /// ### Returns
@@ -357,7 +534,15 @@ impl EditorSettings {
             markdown_settings: MarkdownSettings::new(),
             watch_files: default_watch_files(),
             use_spaces: default_use_spaces(),
+            detect_indentation: default_detect_indentation(),
+            language_indentation: BTreeMap::new(),
             highlight_colors: default_highlight_colors(),
+            spell_check: false,
+            spell_check_language: default_spell_check_language(),
+            auto_save: AutoSaveMode::Off,
+            auto_save_delay_ms: default_auto_save_delay_ms(),
+            auto_save_remote: false,
+            print_settings: PrintSettings::new(),
         }
     }
 }
@@ -383,7 +568,24 @@ impl AppSettings {
             debug_mode: false,
             tab_color_style: TabColorStyle::TextColor,
             persist_unsaved_buffers: default_persist_unsaved_buffers(),
+            privileged_save_helper: default_privileged_save_helper(),
+            recent_pipe_commands: Vec::new(),
+        }
+    }
+
+    /// Move a piped command to the front of the recent commands
+    ///
+    /// ### Arguments
+    /// - `command`: The command that was run
+    /// - `max_commands`: The maximum number of commands to keep
+    pub fn remember_pipe_command(&mut self, command: &str, max_commands: usize) {
+        let command = command.trim();
+        if command.is_empty() {
+            return;
         }
+        self.recent_pipe_commands.retain(|recent| recent != command);
+        self.recent_pipe_commands.insert(0, command.to_string());
+        self.recent_pipe_commands.truncate(max_commands);
     }
 }
 
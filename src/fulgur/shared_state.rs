@@ -1,4 +1,5 @@
 use crate::fulgur::settings::ProfileId;
+use crate::fulgur::spell_check::SharedSpellChecker;
 use crate::fulgur::state::{StateWriter, WindowsState};
 use crate::fulgur::sync::sse::SseState;
 use crate::fulgur::sync::ssh::credentials::SshCredentialCache;
@@ -167,6 +168,8 @@ pub struct SharedAppState {
     pub restore_state: Arc<Mutex<Option<WindowsState>>>,
     /// Sender for user-facing notifications produced anywhere in the app.
     pub notification_tx: UnboundedSender<AppNotification>,
+    /// Spell checker shared by every window, loaded on first use.
+    pub spell_checker: Arc<SharedSpellChecker>,
     /// Receiver side of the notification channel, taken exactly once by `spawn_notification_consumer`.
     notification_rx: Mutex<Option<UnboundedReceiver<AppNotification>>>,
 }
@@ -229,6 +232,7 @@ impl SharedAppState {
             state_writer: Arc::new(StateWriter::new(state_db)),
             restore_state: Arc::new(Mutex::new(restore_state)),
             notification_tx,
+            spell_checker: Arc::new(SharedSpellChecker::default()),
             notification_rx: Mutex::new(Some(notification_rx)),
         }
     }
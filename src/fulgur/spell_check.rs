@@ -0,0 +1,420 @@
+use crate::fulgur::{
+    Fulgur,
+    diagnostics::DiagnosticSource,
+    languages::{
+        spelling::{
+            Misspelling, SpellChecker, SpellScope, USER_DICTIONARY_FILE, append_user_word,
+            dictionaries_dir, find_misspellings, misspelling_at_offset,
+        },
+        supported_languages::language_registry_name,
+    },
+    tab::TabId,
+};
+use anyhow::{Result, anyhow};
+use gpui::{Context, Entity, Subscription, Task, Window};
+use gpui_component::{
+    WindowExt,
+    input::{EditorState, InputEvent, Position},
+    notification::NotificationType,
+};
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+use parking_lot::{Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Delay between the last edit and the spell check it triggers
+const SPELL_CHECK_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Source tag set on the diagnostics underlining misspelled words
+const SPELL_CHECK_SOURCE: &str = "spelling";
+
+/// The spell checker shared by every window
+#[derive(Default)]
+pub struct SharedSpellChecker {
+    checker: RwLock<Option<SpellChecker>>,
+    revision: AtomicU64, // Bumped whenever the checker is replaced or extended
+    requested: Mutex<Option<String>>, // Last requested dictionary, so a missing one is not retried every frame
+}
+
+impl SharedSpellChecker {
+    /// Get the revision of the checker
+    ///
+    /// ### Returns
+    /// - `u64`: A counter that changes whenever misspellings need to be recomputed
+    #[must_use]
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::Acquire)
+    }
+
+    /// Check whether a checker is loaded
+    ///
+    /// ### Returns
+    /// - `bool`: Whether words can be checked
+    #[must_use]
+    pub fn is_loaded(&self) -> bool {
+        self.checker.read().is_some()
+    }
+
+    /// Record a request for a dictionary
+    ///
+    /// ### Arguments
+    /// - `language`: The dictionary name
+    ///
+    /// ### Returns
+    /// - `bool`: `true` when the dictionary was not requested yet and should be loaded
+    fn request(&self, language: &str) -> bool {
+        let mut requested = self.requested.lock();
+        if requested.as_deref() == Some(language) {
+            return false;
+        }
+        *requested = Some(language.to_string());
+        true
+    }
+
+    /// Replace the checker
+    ///
+    /// ### Arguments
+    /// - `checker`: The newly loaded checker
+    fn install(&self, checker: SpellChecker) {
+        *self.checker.write() = Some(checker);
+        self.revision.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Find the misspelled words of a document
+    ///
+    /// ### Arguments
+    /// - `scope`: How the document is checked
+    /// - `text`: The document text
+    ///
+    /// ### Returns
+    /// - `Vec<Misspelling>`: The misspellings, empty when no checker is loaded
+    #[must_use]
+    pub fn find_misspellings(&self, scope: SpellScope, text: &str) -> Vec<Misspelling> {
+        self.checker
+            .read()
+            .as_ref()
+            .map(|checker| find_misspellings(checker, scope, text))
+            .unwrap_or_default()
+    }
+
+    /// Suggest corrections for a misspelled word
+    ///
+    /// ### Arguments
+    /// - `word`: The misspelled word
+    ///
+    /// ### Returns
+    /// - `Vec<String>`: The suggestions, best first
+    #[must_use]
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        self.checker
+            .read()
+            .as_ref()
+            .map(|checker| checker.suggest(word))
+            .unwrap_or_default()
+    }
+
+    /// Add a word to the user dictionary and accept it from now on
+    ///
+    /// ### Arguments
+    /// - `word`: The word to accept
+    ///
+    /// ### Errors
+    /// Returns an error if the user dictionary cannot be written.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: The word was added
+    /// - `Err(anyhow::Error)`: If the user dictionary could not be updated
+    pub fn add_to_user_dictionary(&self, word: &str) -> Result<()> {
+        append_user_word(&dictionaries_dir()?.join(USER_DICTIONARY_FILE), word)?;
+        if let Some(checker) = self.checker.write().as_mut() {
+            checker.add_word(word);
+        }
+        self.revision.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+}
+
+/// Misspellings of the active tab, underlined through the editor diagnostics
+#[derive(Default)]
+pub struct SpellCheckState {
+    source: Option<(TabId, Entity<EditorState>)>, // The editor state the misspellings belong to
+    misspellings: Vec<Misspelling>,               // Misspellings of `source`, in document order
+    revision: u64, // Checker revision the misspellings were found with
+    stale: bool,   // Set when `source` changed since the last check
+    #[allow(dead_code, reason = "RAII guard: keeps the subscription alive")]
+    subscription: Option<Subscription>, // Marks the misspellings stale on every edit of `source`
+    #[allow(
+        dead_code,
+        reason = "RAII guard: dropping the task cancels a pending check"
+    )]
+    task: Option<Task<()>>, // The debounced check in flight
+}
+
+impl Fulgur {
+    /// Load the configured dictionary in the background, once per dictionary name
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    fn ensure_spell_checker(&mut self, cx: &mut Context<Self>) {
+        let language = self.settings.editor_settings.spell_check_language.clone();
+        let shared = Fulgur::shared_state(cx).spell_checker.clone();
+        if !shared.request(&language) {
+            return;
+        }
+        let bg = cx.background_executor().clone();
+        cx.spawn(async move |_, cx| {
+            let load_language = language.clone();
+            let result = bg
+                .spawn(async move { SpellChecker::load(&dictionaries_dir()?, &load_language) })
+                .await;
+            match result {
+                Ok(checker) => {
+                    log::info!("Loaded the {language} spell checking dictionary");
+                    shared.install(checker);
+                    cx.update(|cx| cx.refresh_windows());
+                }
+                Err(e) => {
+                    log::warn!("Spell checking unavailable: {e}");
+                    cx.update(|cx| {
+                        Fulgur::shared_state(cx).notify((
+                            NotificationType::Warning,
+                            format!("Spell checking is unavailable: {e}").into(),
+                        ));
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Keep the misspellings of the active tab in step with its content
+    ///
+    /// ### Description
+    /// A check is scheduled when the active tab changes, after it was edited or
+    /// when the dictionary changed.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn refresh_spell_check(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.settings.editor_settings.spell_check {
+            if self.spell_check.source.is_some() {
+                self.clear_spell_check_diagnostics(cx);
+                self.spell_check = SpellCheckState::default();
+            }
+            return;
+        }
+        self.ensure_spell_checker(cx);
+        let active = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.large_file)
+            .map(|editor_tab| (editor_tab.id, editor_tab.content.clone()));
+        if active != self.spell_check.source {
+            self.clear_spell_check_diagnostics(cx);
+            self.spell_check.subscription = active.as_ref().map(|(_, content)| {
+                cx.subscribe(content, |this: &mut Self, _, event: &InputEvent, cx| {
+                    if matches!(event, InputEvent::Change) {
+                        this.spell_check.stale = true;
+                        cx.notify();
+                    }
+                })
+            });
+            self.spell_check.source = active;
+            self.spell_check.stale = true;
+        }
+        let shared = Fulgur::shared_state(cx).spell_checker.clone();
+        if shared.revision() != self.spell_check.revision {
+            self.spell_check.stale = true;
+        }
+        if !self.spell_check.stale || !shared.is_loaded() {
+            return;
+        }
+        let Some(editor_tab) = self.get_active_editor_tab(cx) else {
+            return;
+        };
+        let content = editor_tab.content.clone();
+        let language_name = language_registry_name(&editor_tab.language);
+        let text = content.read(cx).text().clone();
+        self.spell_check.stale = false;
+        self.spell_check.revision = shared.revision();
+        self.spell_check.task = Some(cx.spawn_in(window, async move |this, window| {
+            window
+                .background_executor()
+                .timer(SPELL_CHECK_DEBOUNCE)
+                .await;
+            let misspellings = window
+                .background_executor()
+                .spawn(async move {
+                    shared.find_misspellings(
+                        SpellScope::for_language(language_name),
+                        &text.to_string(),
+                    )
+                })
+                .await;
+            window
+                .update(|_window, cx| {
+                    _ = this.update(cx, |this, cx| {
+                        this.apply_misspellings(&content, misspellings, cx);
+                    });
+                })
+                .ok();
+        }));
+    }
+
+    /// Underline the misspellings found by a finished check
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state that was checked
+    /// - `misspellings`: The misspellings found
+    /// - `cx`: The application context
+    fn apply_misspellings(
+        &mut self,
+        content: &Entity<EditorState>,
+        misspellings: Vec<Misspelling>,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .spell_check
+            .source
+            .as_ref()
+            .is_none_or(|(_, source)| source != content)
+        {
+            return;
+        }
+        let diagnostics = misspellings.iter().map(misspelling_diagnostic).collect();
+        self.set_diagnostics(content, DiagnosticSource::SpellCheck, diagnostics, cx);
+        self.spell_check.misspellings = misspellings;
+    }
+
+    /// Remove the spelling underlines from the checked tab
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    fn clear_spell_check_diagnostics(&mut self, cx: &mut Context<Self>) {
+        self.spell_check.misspellings.clear();
+        self.spell_check.task = None;
+        if let Some((_, content)) = self.spell_check.source.clone() {
+            self.clear_diagnostics(&content, DiagnosticSource::SpellCheck, cx);
+        }
+    }
+
+    /// Find the misspelled word under the cursor of an editor, with its suggestions
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state the context menu was opened on
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some((Misspelling, Vec<String>))`: The misspelling and its suggested corrections
+    /// - `None`: The cursor is not on a misspelled word
+    pub(crate) fn misspelling_under_cursor(
+        &self,
+        content: &Entity<EditorState>,
+        cx: &Context<Self>,
+    ) -> Option<(Misspelling, Vec<String>)> {
+        let (_, source) = self.spell_check.source.as_ref()?;
+        if source != content {
+            return None;
+        }
+        let cursor = content.read(cx).cursor();
+        let misspelling = misspelling_at_offset(&self.spell_check.misspellings, cursor)?.clone();
+        let suggestions = Fulgur::shared_state(cx)
+            .spell_checker
+            .suggest(&misspelling.word);
+        Some((misspelling, suggestions))
+    }
+
+    /// Replace a misspelled word with a suggestion
+    ///
+    /// ### Arguments
+    /// - `misspelling`: The misspelling to correct
+    /// - `replacement`: The chosen suggestion
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn replace_misspelling(
+        &mut self,
+        misspelling: &Misspelling,
+        replacement: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((tab_id, content)) = self.spell_check.source.clone() else {
+            return;
+        };
+        let read_only = self.tab_entity_of(tab_id, cx).is_some_and(|tab| {
+            tab.read(cx)
+                .as_editor()
+                .is_some_and(|editor| editor.read_only)
+        });
+        if read_only {
+            return;
+        }
+        let text = content.read(cx).text().to_string();
+        if text.get(misspelling.range.clone()) != Some(misspelling.word.as_str()) {
+            log::debug!(
+                "Skipping stale spelling correction of '{}'",
+                misspelling.word
+            );
+            return;
+        }
+        let mut corrected = text;
+        corrected.replace_range(misspelling.range.clone(), replacement);
+        crate::fulgur::editor_tab::apply_minimal_edits(&content, &corrected, window, cx);
+    }
+
+    /// Add a word to the user dictionary
+    ///
+    /// ### Arguments
+    /// - `word`: The word to accept
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn add_word_to_dictionary(
+        &mut self,
+        word: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let shared = Fulgur::shared_state(cx).spell_checker.clone();
+        match shared
+            .add_to_user_dictionary(word)
+            .map_err(|e| anyhow!("Failed to add '{word}' to the user dictionary: {e}"))
+        {
+            Ok(()) => cx.refresh_windows(),
+            Err(e) => {
+                log::error!("{e}");
+                window.push_notification((NotificationType::Error, e.to_string().into()), cx);
+            }
+        }
+    }
+}
+
+/// Build the diagnostic underlining a misspelled word
+///
+/// ### Arguments
+/// - `misspelling`: The misspelled word
+///
+/// ### Returns
+/// - `Diagnostic`: An information diagnostic spanning the word
+fn misspelling_diagnostic(misspelling: &Misspelling) -> Diagnostic {
+    let length = u32::try_from(misspelling.word.chars().count()).unwrap_or(u32::MAX);
+    Diagnostic {
+        range: lsp_types::Range {
+            start: Position {
+                line: misspelling.line,
+                character: misspelling.column,
+            },
+            end: Position {
+                line: misspelling.line,
+                character: misspelling.column.saturating_add(length),
+            },
+        },
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        message: format!("Unknown word: {}", misspelling.word),
+        source: Some(SPELL_CHECK_SOURCE.to_string()),
+        code: None,
+        related_information: None,
+        tags: None,
+        code_description: None,
+        data: None,
+    }
+}
@@ -0,0 +1,924 @@
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::apply_minimal_edits,
+    state::SerializedSplit,
+    tab::{Tab, TabId},
+    ui::{icons::CustomIcon, read_only_editor::read_only_editor},
+    utils::text_diff::{TextEdit, rope_edit},
+};
+use gpui::{
+    Anchor, AnyElement, App, AppContext, Context, Entity, EntityInputHandler, Focusable,
+    IntoElement, ParentElement, SharedString, Styled, Subscription, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Editor, EditorState, InputEvent},
+    menu::{DropdownMenu, PopupMenuItem},
+    resizable::{h_resizable, resizable_panel, v_resizable},
+    v_flex,
+};
+use ropey::Rope;
+
+/// Direction in which a pane is split off the pane it came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitOrientation {
+    Horizontal, // Panes side by side, the new one on the right
+    Vertical,   // Panes stacked, the new one below
+}
+
+impl SplitOrientation {
+    /// Get the key this orientation is persisted under
+    ///
+    /// ### Returns
+    /// - `&'static str`: The persisted key
+    #[must_use]
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Horizontal => "horizontal",
+            Self::Vertical => "vertical",
+        }
+    }
+
+    /// Parse a persisted orientation key
+    ///
+    /// ### Arguments
+    /// - `key`: The persisted key
+    ///
+    /// ### Returns
+    /// - `Some(SplitOrientation)`: The orientation matching the key
+    /// - `None`: The key is unknown
+    #[must_use]
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "horizontal" => Some(Self::Horizontal),
+            "vertical" => Some(Self::Vertical),
+            _ => None,
+        }
+    }
+}
+
+/// Identity of a split pane, unique within its window while the window is open
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaneId(u64);
+
+/// An editor pane split off the primary pane or off another split pane
+///
+/// The pane shows one editor tab of the window's shared tab pool through its own
+/// `EditorState`, so it keeps a cursor, selection and scroll position independent
+/// from every other pane, even when they show the same tab. Edits made in either
+/// state are mirrored into the other one.
+pub struct SplitPane {
+    id: PaneId,
+    parent: Option<PaneId>, // The pane this one was split off, `None` for the primary pane
+    orientation: SplitOrientation, // Direction of the split off `parent`
+    tab_id: TabId,          // The tab shown in the pane
+    source: Entity<EditorState>, // The tab's own editor state, shown in the primary pane
+    content: Entity<EditorState>, // The pane's editor state, kept in sync with `source`
+    source_text: Rope,      // Text of `source` when both states were last in sync
+    content_text: Rope,     // Text of `content` when both states were last in sync
+    _subscriptions: [Subscription; 3], // Mirror edits between `source` and `content`, and follow the tab
+}
+
+impl Fulgur {
+    /// Split the focused pane with its tab shown on the right
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn split_right(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.split_focused_pane(SplitOrientation::Horizontal, window, cx);
+    }
+
+    /// Split the focused pane with its tab shown below
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn split_down(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.split_focused_pane(SplitOrientation::Vertical, window, cx);
+    }
+
+    /// Open the tab of the focused pane in a new pane split off it, and focus the new pane
+    ///
+    /// ### Description
+    /// When no split pane has the focus, the primary pane is split with its active tab.
+    ///
+    /// ### Arguments
+    /// - `orientation`: The direction of the split
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn split_focused_pane(
+        &mut self,
+        orientation: SplitOrientation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (parent, tab_id) = match self.focused_split_pane(window, cx) {
+            Some(pane) => (Some(pane.id), pane.tab_id),
+            None => {
+                let Some(tab_id) = self
+                    .get_active_editor_tab(cx)
+                    .map(|editor_tab| editor_tab.id)
+                else {
+                    return;
+                };
+                (None, tab_id)
+            }
+        };
+        if let Some(id) = self.open_split_pane(parent, tab_id, orientation, window, cx)
+            && let Some(pane) = self.split_pane(id)
+        {
+            let focus_handle = pane.content.read(cx).focus_handle(cx);
+            window.focus(&focus_handle, cx);
+        }
+    }
+
+    /// Get the split pane holding the focus
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(&SplitPane)`: The focused split pane
+    /// - `None`: The focus is in the primary pane or outside the editors
+    fn focused_split_pane(&self, window: &Window, cx: &Context<Self>) -> Option<&SplitPane> {
+        self.split_panes
+            .iter()
+            .find(|pane| pane.content.read(cx).focus_handle(cx).is_focused(window))
+    }
+
+    /// Get a split pane by identity
+    ///
+    /// ### Arguments
+    /// - `id`: The identity of the pane
+    ///
+    /// ### Returns
+    /// - `Some(&SplitPane)`: The pane
+    /// - `None`: The pane was closed
+    fn split_pane(&self, id: PaneId) -> Option<&SplitPane> {
+        self.split_panes.iter().find(|pane| pane.id == id)
+    }
+
+    /// Open an editor tab in a new pane split off an existing one
+    ///
+    /// ### Arguments
+    /// - `parent`: The pane to split, `None` for the primary pane
+    /// - `tab_id`: The editor tab to show
+    /// - `orientation`: The direction of the split
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(PaneId)`: The identity of the new pane
+    /// - `None`: The tab does not exist or cannot be split
+    pub(crate) fn open_split_pane(
+        &mut self,
+        parent: Option<PaneId>,
+        tab_id: TabId,
+        orientation: SplitOrientation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<PaneId> {
+        let id = PaneId(
+            self.split_panes
+                .iter()
+                .map(|pane| pane.id.0 + 1)
+                .max()
+                .unwrap_or_default(),
+        );
+        let pane = self.build_split_pane(id, parent, orientation, tab_id, window, cx)?;
+        self.split_panes.push(pane);
+        self.save_state_async(cx, window);
+        cx.notify();
+        Some(id)
+    }
+
+    /// Show another editor tab in an existing split pane
+    ///
+    /// ### Description
+    /// When the pane already shows the tab, its cursor is carried over to the new
+    /// editor state, which is how the pane picks up new editor settings.
+    ///
+    /// ### Arguments
+    /// - `id`: The pane
+    /// - `tab_id`: The editor tab to show
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `true`: The pane now shows the tab
+    /// - `false`: The pane or the tab does not exist, or the tab cannot be split
+    pub(crate) fn show_tab_in_split_pane(
+        &mut self,
+        id: PaneId,
+        tab_id: TabId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(index) = self.split_panes.iter().position(|pane| pane.id == id) else {
+            return false;
+        };
+        let (parent, orientation) = {
+            let pane = &self.split_panes[index];
+            (pane.parent, pane.orientation)
+        };
+        let previous_cursor = Some(&self.split_panes[index])
+            .filter(|pane| pane.tab_id == tab_id)
+            .map(|pane| pane.content.read(cx).cursor_position());
+        let Some(pane) = self.build_split_pane(id, parent, orientation, tab_id, window, cx) else {
+            return false;
+        };
+        if let Some(cursor) = previous_cursor {
+            pane.content.update(cx, |state, cx| {
+                state.set_cursor_position(cursor, window, cx);
+            });
+        }
+        self.split_panes[index] = pane;
+        self.save_state_async(cx, window);
+        cx.notify();
+        true
+    }
+
+    /// Build a split pane over an editor tab
+    ///
+    /// ### Description
+    /// Large-file and log-view tabs cannot be split, because their content is not
+    /// edited through a regular editor state.
+    ///
+    /// ### Arguments
+    /// - `id`: The identity of the pane
+    /// - `parent`: The pane it is split off, `None` for the primary pane
+    /// - `orientation`: The direction of the split
+    /// - `tab_id`: The editor tab to show
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(SplitPane)`: The pane, mirroring the tab's editor state
+    /// - `None`: The tab does not exist or cannot be split
+    fn build_split_pane(
+        &mut self,
+        id: PaneId,
+        parent: Option<PaneId>,
+        orientation: SplitOrientation,
+        tab_id: TabId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<SplitPane> {
+        let tab_entity = self.tab_entity_of(tab_id, cx)?;
+        let settings = self.settings.editor_settings.clone();
+        let Some((source, content)) = tab_entity.update(cx, |tab, cx| {
+            let editor_tab = tab
+                .as_editor()
+                .filter(|editor_tab| !editor_tab.large_file && !editor_tab.log_view)?;
+            Some((
+                editor_tab.content.clone(),
+                editor_tab.make_mirror_content(window, cx, &settings),
+            ))
+        }) else {
+            log::debug!("Tab {tab_id:?} cannot be shown in a split pane");
+            return None;
+        };
+        let sync_from_source = cx.subscribe_in(
+            &source,
+            window,
+            move |this: &mut Self, _, event: &InputEvent, window, cx| {
+                if matches!(event, InputEvent::Change) {
+                    this.sync_split_pane(id, false, window, cx);
+                }
+            },
+        );
+        let sync_from_pane = cx.subscribe_in(
+            &content,
+            window,
+            move |this: &mut Self, _, event: &InputEvent, window, cx| {
+                if matches!(event, InputEvent::Change) {
+                    this.sync_split_pane(id, true, window, cx);
+                }
+            },
+        );
+        let follow_tab = cx.observe_in(&tab_entity, window, |this: &mut Self, _, window, cx| {
+            this.refresh_split_panes(window, cx);
+        });
+        Some(SplitPane {
+            id,
+            parent,
+            orientation,
+            tab_id,
+            source_text: source.read(cx).text().clone(),
+            content_text: content.read(cx).text().clone(),
+            source,
+            content,
+            _subscriptions: [sync_from_source, sync_from_pane, follow_tab],
+        })
+    }
+
+    /// Copy an edit made in one of a pane's editor states into the other one
+    ///
+    /// ### Description
+    /// The edit is found against the text the state had when both were last in
+    /// sync, which shares all untouched chunks with the current text, so finding
+    /// it does not scan the document. It is then replayed as a single replacement,
+    /// and the receiving state keeps its own cursor and scroll position. Applying
+    /// it emits a change event back, which finds nothing left to mirror. Should the
+    /// states have drifted apart, the whole text is reconciled instead.
+    ///
+    /// ### Arguments
+    /// - `id`: The pane
+    /// - `from_pane`: Whether the pane's own state was edited, rather than the tab's
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn sync_split_pane(
+        &mut self,
+        id: PaneId,
+        from_pane: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(pane) = self.split_panes.iter_mut().find(|pane| pane.id == id) else {
+            return;
+        };
+        let (changed, target, synced) = if from_pane {
+            (&pane.content, &pane.source, &pane.content_text)
+        } else {
+            (&pane.source, &pane.content, &pane.source_text)
+        };
+        let text = changed.read(cx).text().clone();
+        if let Some(edit) = rope_edit(synced, &text) {
+            if target.read(cx).text().len() == synced.len() {
+                apply_mirrored_edit(target, &edit, window, cx);
+            } else {
+                apply_minimal_edits(target, &text.to_string(), window, cx);
+            }
+        }
+        pane.source_text = pane.source.read(cx).text().clone();
+        pane.content_text = pane.content.read(cx).text().clone();
+    }
+
+    /// Close the focused split pane, or the last one opened when the primary pane has the focus
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn close_split(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let id = self
+            .focused_split_pane(window, cx)
+            .or_else(|| self.split_panes.last())
+            .map(|pane| pane.id);
+        if let Some(id) = id {
+            self.close_split_pane(id, window, cx);
+        }
+    }
+
+    /// Close a split pane
+    ///
+    /// ### Description
+    /// The panes split off it move up to the pane it was split off, and the focus
+    /// goes back to the primary pane.
+    ///
+    /// ### Arguments
+    /// - `id`: The pane to close
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn close_split_pane(
+        &mut self,
+        id: PaneId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(index) = self.split_panes.iter().position(|pane| pane.id == id) else {
+            return;
+        };
+        let closed = self.split_panes.remove(index);
+        for pane in &mut self.split_panes {
+            if pane.parent == Some(id) {
+                pane.parent = closed.parent;
+            }
+        }
+        self.focus_active_tab(window, cx);
+        self.save_state_async(cx, window);
+        cx.notify();
+    }
+
+    /// Move the focus to the next pane, in opening order, wrapping back to the primary pane
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn focus_other_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.split_panes.is_empty() {
+            return;
+        }
+        let next = match self.focused_split_pane(window, cx) {
+            Some(focused) => self
+                .split_panes
+                .iter()
+                .skip_while(|pane| pane.id != focused.id)
+                .nth(1),
+            None => self.split_panes.first(),
+        };
+        match next {
+            Some(pane) => {
+                let focus_handle = pane.content.read(cx).focus_handle(cx);
+                window.focus(&focus_handle, cx);
+            }
+            None => self.focus_active_tab(window, cx),
+        }
+    }
+
+    /// Rebuild the editor state of every split pane, for example after the editor settings changed
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn rebuild_split_panes(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let panes: Vec<(PaneId, TabId)> = self
+            .split_panes
+            .iter()
+            .map(|pane| (pane.id, pane.tab_id))
+            .collect();
+        for (id, tab_id) in panes {
+            if !self.show_tab_in_split_pane(id, tab_id, window, cx) {
+                self.close_split_pane(id, window, cx);
+            }
+        }
+    }
+
+    /// Keep the split panes consistent with the tabs they show
+    ///
+    /// ### Description
+    /// A pane is closed once its tab is closed or can no longer be split, and its
+    /// editor state is rebuilt when the tab replaced its own, which happens when
+    /// the language changes. Runs whenever a tab shown in a pane notifies, and
+    /// after a tab is closed or switches to the log view.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn refresh_split_panes(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let stale: Vec<(PaneId, TabId, bool)> = self
+            .split_panes
+            .iter()
+            .filter_map(|pane| {
+                let current_source = self.tab_entity_of(pane.tab_id, cx).and_then(|tab| match tab
+                    .read(cx)
+                {
+                    Tab::Editor(editor_tab) if !editor_tab.large_file && !editor_tab.log_view => {
+                        Some(editor_tab.content.clone())
+                    }
+                    _ => None,
+                });
+                match current_source {
+                    None => Some((pane.id, pane.tab_id, false)),
+                    Some(source) if source != pane.source => Some((pane.id, pane.tab_id, true)),
+                    Some(_) => None,
+                }
+            })
+            .collect();
+        for (id, tab_id, rebuild) in stale {
+            if !rebuild || !self.show_tab_in_split_pane(id, tab_id, window, cx) {
+                self.close_split_pane(id, window, cx);
+            }
+        }
+    }
+
+    /// Get the split layout to persist with the window
+    ///
+    /// ### Returns
+    /// - `Vec<SerializedSplit>`: The split panes in opening order, empty when the window shows a single pane
+    pub(crate) fn split_state(&self) -> Vec<SerializedSplit> {
+        self.split_panes
+            .iter()
+            .map(|pane| SerializedSplit {
+                orientation: pane.orientation.key().to_string(),
+                tab_id: pane.tab_id.0,
+                parent: pane
+                    .parent
+                    .and_then(|parent| self.split_panes.iter().position(|pane| pane.id == parent)),
+            })
+            .collect()
+    }
+
+    /// Reopen the split panes of a restored window
+    ///
+    /// ### Description
+    /// A pane whose tab is gone is skipped, and the panes split off it are split
+    /// off the pane it came from instead.
+    ///
+    /// ### Arguments
+    /// - `splits`: The persisted split panes, in opening order
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn restore_split_panes(
+        &mut self,
+        splits: &[SerializedSplit],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut opened: Vec<Option<PaneId>> = Vec::with_capacity(splits.len());
+        let mut parents: Vec<Option<PaneId>> = Vec::with_capacity(splits.len());
+        for split in splits {
+            let parent = split.parent.and_then(|index| {
+                opened
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .or_else(|| parents.get(index).copied().flatten())
+            });
+            let id = SplitOrientation::from_key(&split.orientation).and_then(|orientation| {
+                self.open_split_pane(parent, TabId(split.tab_id), orientation, window, cx)
+            });
+            opened.push(id);
+            parents.push(parent);
+        }
+    }
+
+    /// Lay the primary content out next to the split panes, when the window is split
+    ///
+    /// ### Arguments
+    /// - `primary`: The rendered primary pane
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The split layout, or `primary` unchanged when the window is not split
+    pub(crate) fn render_split_layout(
+        &self,
+        primary: AnyElement,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        if self.split_panes.is_empty() {
+            return primary;
+        }
+        v_flex()
+            .w_full()
+            .flex_1()
+            .child(self.render_split_tree(None, primary, cx))
+            .into_any_element()
+    }
+
+    /// Lay a pane out next to the panes split off it, in the order they were opened
+    ///
+    /// ### Arguments
+    /// - `parent`: The pane, `None` for the primary pane
+    /// - `own`: The rendered pane
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The pane and, recursively, every pane split off it
+    fn render_split_tree(
+        &self,
+        parent: Option<PaneId>,
+        own: AnyElement,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let children: Vec<&SplitPane> = self
+            .split_panes
+            .iter()
+            .filter(|pane| pane.parent == parent)
+            .collect();
+        children.into_iter().fold(own, |layout, pane| {
+            let pane_element = self.render_split_pane(pane, cx);
+            let pane_tree = self.render_split_tree(Some(pane.id), pane_element, cx);
+            let id = SharedString::from(format!("editor-split-{}", pane.id.0));
+            let container = match pane.orientation {
+                SplitOrientation::Horizontal => h_resizable(id),
+                SplitOrientation::Vertical => v_resizable(id),
+            };
+            container
+                .child(resizable_panel().child(v_flex().size_full().child(layout)))
+                .child(resizable_panel().child(pane_tree))
+                .into_any_element()
+        })
+    }
+
+    /// Render a split pane: its header above its editor
+    ///
+    /// ### Arguments
+    /// - `pane`: The split pane
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The pane
+    fn render_split_pane(&self, pane: &SplitPane, cx: &mut Context<Self>) -> AnyElement {
+        let read_only = self.tab_entity_of(pane.tab_id, cx).is_some_and(|tab| {
+            tab.read(cx)
+                .as_editor()
+                .is_some_and(|editor| editor.read_only)
+        });
+        v_flex()
+            .size_full()
+            .child(self.render_split_pane_header(pane, cx))
+            .child(
+                div().flex_1().min_h_0().child(read_only_editor(
+                    Editor::new(&pane.content)
+                        .bordered(false)
+                        .p_0()
+                        .h_full()
+                        .font_family(self.settings.editor_settings.font_family.clone())
+                        .text_size(px(self.settings.editor_settings.font_size)),
+                    read_only,
+                )),
+            )
+            .into_any_element()
+    }
+
+    /// Render a split pane header: a tab chooser over the shared tab pool and a close button
+    ///
+    /// ### Arguments
+    /// - `pane`: The split pane
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `impl IntoElement`: The header row
+    fn render_split_pane_header(
+        &self,
+        pane: &SplitPane,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let choices: Vec<(TabId, SharedString)> = self
+            .tabs
+            .iter()
+            .filter_map(|tab| match tab.read(cx) {
+                Tab::Editor(editor_tab) if !editor_tab.large_file && !editor_tab.log_view => {
+                    Some((editor_tab.id, tab.read(cx).title()))
+                }
+                _ => None,
+            })
+            .collect();
+        let title = choices
+            .iter()
+            .find(|(id, _)| *id == pane.tab_id)
+            .map(|(_, title)| title.clone())
+            .unwrap_or_default();
+        let current = pane.tab_id;
+        let pane_id = pane.id;
+        let fulgur = cx.entity().downgrade();
+        h_flex()
+            .w_full()
+            .px_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().tab_bar)
+            .child(
+                Button::new(SharedString::from(format!("split-pane-tab-{}", pane_id.0)))
+                    .label(title)
+                    .dropdown_caret(true)
+                    .ghost()
+                    .xsmall()
+                    .dropdown_menu_with_anchor(Anchor::TopLeft, move |mut menu, _window, _cx| {
+                        for (tab_id, title) in &choices {
+                            let tab_id = *tab_id;
+                            let fulgur = fulgur.clone();
+                            menu = menu.item(
+                                PopupMenuItem::new(title.clone())
+                                    .checked(tab_id == current)
+                                    .on_click(move |_, window, cx| {
+                                        _ = fulgur.update(cx, |this, cx| {
+                                            this.show_tab_in_split_pane(
+                                                pane_id, tab_id, window, cx,
+                                            );
+                                        });
+                                    }),
+                            );
+                        }
+                        menu
+                    }),
+            )
+            .child(
+                Button::new(SharedString::from(format!(
+                    "close-split-pane-{}",
+                    pane_id.0
+                )))
+                .icon(CustomIcon::Close)
+                .ghost()
+                .xsmall()
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.close_split_pane(pane_id, window, cx);
+                })),
+            )
+    }
+}
+
+/// Replay an edit mirrored from another editor state, keeping this state's cursor and scroll position
+///
+/// ### Arguments
+/// - `target`: The editor state receiving the edit, holding the text the edit was found against
+/// - `edit`: The edit, in byte offsets of that text
+/// - `window`: The window context
+/// - `cx`: The application context
+fn apply_mirrored_edit(
+    target: &Entity<EditorState>,
+    edit: &TextEdit,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    target.update(cx, |state, cx| {
+        let range = {
+            let text = state.text();
+            text.byte_to_utf16_idx(edit.range.start)..text.byte_to_utf16_idx(edit.range.end)
+        };
+        let cursor = mirrored_offset(edit, state.cursor());
+        let scroll_offset = state.scroll_offset();
+        state.replace_text_in_range(Some(range), &edit.new_text, window, cx);
+        state.set_selected_range(cursor..cursor, cx);
+        state.set_scroll_offset(scroll_offset, cx);
+    });
+}
+
+/// Move a byte offset over an edit
+///
+/// ### Arguments
+/// - `edit`: The edit
+/// - `offset`: A byte offset in the text before the edit
+///
+/// ### Returns
+/// - `usize`: The offset of the same text after the edit; an offset inside the
+///   replaced range moves to the end of the replacement
+fn mirrored_offset(edit: &TextEdit, offset: usize) -> usize {
+    if offset <= edit.range.start {
+        offset
+    } else if offset >= edit.range.end {
+        offset - edit.range.len() + edit.new_text.len()
+    } else {
+        edit.range.start + edit.new_text.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SplitOrientation, mirrored_offset};
+    #[cfg(feature = "gpui-test-support")]
+    use crate::fulgur::files::file_operations::test_helpers::setup_fulgur;
+    use crate::fulgur::utils::text_diff::TextEdit;
+    #[cfg(feature = "gpui-test-support")]
+    use gpui::TestAppContext;
+
+    #[test]
+    fn test_split_orientation_key_roundtrip() {
+        for orientation in [SplitOrientation::Horizontal, SplitOrientation::Vertical] {
+            assert_eq!(
+                SplitOrientation::from_key(orientation.key()),
+                Some(orientation)
+            );
+        }
+        assert_eq!(SplitOrientation::from_key("diagonal"), None);
+    }
+
+    #[test]
+    fn test_mirrored_offset_follows_the_text_around_the_edit() {
+        let edit = TextEdit {
+            range: 4..6,
+            new_text: "abcd".to_string(),
+        };
+        assert_eq!(mirrored_offset(&edit, 2), 2);
+        assert_eq!(mirrored_offset(&edit, 5), 8);
+        assert_eq!(mirrored_offset(&edit, 10), 12);
+    }
+
+    #[cfg(feature = "gpui-test-support")]
+    #[gpui::test]
+    fn test_split_pane_mirrors_edits_with_its_own_cursor(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let source = this
+                    .get_active_editor_tab(cx)
+                    .expect("expected an editor tab")
+                    .content
+                    .clone();
+                source.update(cx, |state, cx| {
+                    state.set_value("first\nsecond\n", window, cx);
+                    state.set_selected_range(0..0, cx);
+                });
+                this.split_right(window, cx);
+                let pane = this.split_panes.first().expect("expected a split pane");
+                assert_eq!(pane.content.read(cx).text().to_string(), "first\nsecond\n");
+                let content = pane.content.clone();
+                content.update(cx, |state, cx| {
+                    state.set_selected_range(13..13, cx);
+                    state.insert("third\n", window, cx);
+                });
+            });
+        });
+        visual_cx.run_until_parked();
+
+        visual_cx.update(|_window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let editor_tab = this
+                    .get_active_editor_tab(cx)
+                    .expect("expected an editor tab");
+                assert_eq!(
+                    editor_tab.content.read(cx).text().to_string(),
+                    "first\nsecond\nthird\n"
+                );
+                assert_eq!(
+                    editor_tab.content.read(cx).cursor(),
+                    0,
+                    "an edit in the split pane must not move the primary pane's cursor"
+                );
+                assert!(editor_tab.modified);
+                assert_eq!(
+                    this.split_state()
+                        .first()
+                        .map(|split| split.orientation.clone()),
+                    Some("horizontal".to_string())
+                );
+            });
+        });
+    }
+
+    #[cfg(feature = "gpui-test-support")]
+    #[gpui::test]
+    fn test_nested_split_panes_share_edits_and_persist_their_tree(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let tab_id = this
+                    .get_active_editor_tab(cx)
+                    .expect("expected an editor tab")
+                    .id;
+                let first = this
+                    .open_split_pane(None, tab_id, SplitOrientation::Horizontal, window, cx)
+                    .expect("expected a first pane");
+                let second = this
+                    .open_split_pane(Some(first), tab_id, SplitOrientation::Vertical, window, cx)
+                    .expect("expected a nested pane");
+                let content = this
+                    .split_pane(second)
+                    .expect("expected the nested pane")
+                    .content
+                    .clone();
+                content.update(cx, |state, cx| {
+                    state.insert("shared", window, cx);
+                });
+            });
+        });
+        visual_cx.run_until_parked();
+
+        visual_cx.update(|_window, cx| {
+            fulgur.update(cx, |this, cx| {
+                for pane in &this.split_panes {
+                    assert_eq!(pane.content.read(cx).text().to_string(), "shared");
+                }
+                let splits = this.split_state();
+                assert_eq!(splits.len(), 2);
+                assert_eq!(splits[0].parent, None);
+                assert_eq!(splits[1].parent, Some(0));
+            });
+        });
+    }
+
+    #[cfg(feature = "gpui-test-support")]
+    #[gpui::test]
+    fn test_closing_a_pane_keeps_the_panes_split_off_it(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                let tab_id = this
+                    .get_active_editor_tab(cx)
+                    .expect("expected an editor tab")
+                    .id;
+                let first = this
+                    .open_split_pane(None, tab_id, SplitOrientation::Horizontal, window, cx)
+                    .expect("expected a first pane");
+                this.open_split_pane(Some(first), tab_id, SplitOrientation::Vertical, window, cx)
+                    .expect("expected a nested pane");
+                this.close_split_pane(first, window, cx);
+                let splits = this.split_state();
+                assert_eq!(splits.len(), 1);
+                assert_eq!(
+                    splits[0].parent, None,
+                    "the nested pane moves up to the primary pane"
+                );
+            });
+        });
+    }
+
+    #[cfg(feature = "gpui-test-support")]
+    #[gpui::test]
+    fn test_split_pane_closes_with_its_tab(cx: &mut TestAppContext) {
+        let (fulgur, mut visual_cx) = setup_fulgur(cx);
+
+        visual_cx.update(|window, cx| {
+            fulgur.update(cx, |this, cx| {
+                this.split_down(window, cx);
+                let tab_id = this
+                    .split_panes
+                    .first()
+                    .expect("expected a split pane")
+                    .tab_id;
+                this.remove_tab_by_id(tab_id, window, cx);
+                assert!(this.split_panes.is_empty());
+                assert!(this.split_state().is_empty());
+            });
+        });
+    }
+}
@@ -0,0 +1,14 @@
+-- Split editor panes of a window.
+--
+-- One row per pane, in the order the panes were opened. `parent` is the
+-- position of the pane this one was split off, NULL when it was split off the
+-- primary pane, so nested splits are restored as the same tree. Panes reference
+-- their tab by identity, like `active_tab_id`.
+CREATE TABLE split_panes (
+    window_id   INTEGER NOT NULL REFERENCES windows(id) ON DELETE CASCADE,
+    position    INTEGER NOT NULL,
+    orientation TEXT    NOT NULL,
+    tab_id      INTEGER NOT NULL,
+    parent      INTEGER,
+    PRIMARY KEY (window_id, position)
+) STRICT;
@@ -0,0 +1,5 @@
+-- Per-tab read-only flag.
+--
+-- Set automatically for files that were not writable when opened, or toggled
+-- by hand; existing rows default to editable.
+ALTER TABLE tabs ADD COLUMN read_only INTEGER NOT NULL DEFAULT 0;
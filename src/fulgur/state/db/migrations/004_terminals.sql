@@ -0,0 +1,10 @@
+-- Working directories of the terminals open in a window's terminal panel.
+--
+-- One row per terminal, in tab order. BLOB for the same reason as
+-- `tabs.file_path`: OS paths are not guaranteed to be valid UTF-8.
+CREATE TABLE terminals (
+    window_id   INTEGER NOT NULL REFERENCES windows(id) ON DELETE CASCADE,
+    position    INTEGER NOT NULL,
+    working_dir BLOB    NOT NULL,
+    PRIMARY KEY (window_id, position)
+) STRICT;
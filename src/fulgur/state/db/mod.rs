@@ -36,6 +36,10 @@ pub struct ApplyStats {
     pub tabs_content_written: usize,
     /// Tab rows deleted because the tab closed.
     pub tabs_deleted: usize,
+    /// Windows whose terminal rows were rewritten.
+    pub terminals_written: usize,
+    /// Windows whose split pane rows were rewritten.
+    pub splits_written: usize,
 }
 
 impl ApplyStats {
@@ -3,10 +3,12 @@
 use super::StateDb;
 use super::paths::path_from_bytes;
 use crate::fulgur::state::persistence::{
-    SerializedRemoteSpec, SerializedWindowBounds, TabContent, TabState, WindowState, WindowsState,
+    SerializedRemoteSpec, SerializedSplit, SerializedWindowBounds, TabContent, TabState,
+    WindowState, WindowsState,
 };
 use anyhow::anyhow;
 use rusqlite::Row;
+use std::path::PathBuf;
 
 impl StateDb {
     /// Read every persisted window, in restore order, with its tabs.
@@ -40,6 +42,8 @@ impl StateDb {
         };
         for (window_id, active_tab_id, window_bounds) in windows {
             let tabs = self.load_tabs(window_id)?;
+            let splits = self.load_splits(window_id)?;
+            let terminals = self.load_terminals(window_id)?;
             let active_tab_index = active_tab_id.and_then(|active| {
                 let active = u64::try_from(active).ok()?;
                 tabs.iter().position(|tab| tab.tab_id == active)
@@ -49,6 +53,8 @@ impl StateDb {
                 tabs,
                 active_tab_index,
                 window_bounds,
+                splits,
+                terminals,
             });
         }
         Ok(state)
@@ -70,7 +76,7 @@ impl StateDb {
             .conn
             .prepare(
                 "SELECT id, title, file_path, content, last_saved, log_view, color_tag,
-                        remote_host, remote_port, remote_user, remote_path
+                        remote_host, remote_port, remote_user, remote_path, read_only
                  FROM tabs
                  WHERE window_id = ?1
                  ORDER BY position, id",
@@ -83,6 +89,73 @@ impl StateDb {
             .map_err(|e| anyhow!("Failed to decode a persisted tab: {e}"))?;
         Ok(tabs)
     }
+
+    /// Read the split panes of one window, in the order they were opened.
+    ///
+    /// ### Arguments
+    /// - `window_id`: Identity of the owning window
+    ///
+    /// ### Errors
+    /// - Returns an error if the query fails or a row cannot be decoded.
+    ///
+    /// ### Returns
+    /// - `Ok(Vec<SerializedSplit>)`: The split panes in opening order
+    /// - `Err(anyhow::Error)`: The split panes could not be read
+    fn load_splits(&self, window_id: i64) -> anyhow::Result<Vec<SerializedSplit>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT orientation, tab_id, parent FROM split_panes
+                 WHERE window_id = ?1
+                 ORDER BY position",
+            )
+            .map_err(|e| anyhow!("Failed to prepare the split pane query: {e}"))?;
+        let splits = stmt
+            .query_map([window_id], |row| {
+                let tab_id: i64 = row.get(1)?;
+                let parent: Option<i64> = row.get(2)?;
+                Ok(SerializedSplit {
+                    orientation: row.get(0)?,
+                    tab_id: u64::try_from(tab_id).unwrap_or_default(),
+                    parent: parent.and_then(|parent| usize::try_from(parent).ok()),
+                })
+            })
+            .map_err(|e| anyhow!("Failed to query persisted split panes: {e}"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("Failed to decode a persisted split pane: {e}"))?;
+        Ok(splits)
+    }
+
+    /// Read the working directories of the terminals of one window, in tab order.
+    ///
+    /// ### Arguments
+    /// - `window_id`: Identity of the owning window
+    ///
+    /// ### Errors
+    /// - Returns an error if the query fails or a row cannot be decoded.
+    ///
+    /// ### Returns
+    /// - `Ok(Vec<PathBuf>)`: The working directories in tab order
+    /// - `Err(anyhow::Error)`: The terminals could not be read
+    fn load_terminals(&self, window_id: i64) -> anyhow::Result<Vec<PathBuf>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT working_dir FROM terminals
+                 WHERE window_id = ?1
+                 ORDER BY position",
+            )
+            .map_err(|e| anyhow!("Failed to prepare the terminal query: {e}"))?;
+        let terminals = stmt
+            .query_map([window_id], |row| {
+                let bytes: Vec<u8> = row.get(0)?;
+                Ok(path_from_bytes(&bytes))
+            })
+            .map_err(|e| anyhow!("Failed to query persisted terminals: {e}"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("Failed to decode a persisted terminal: {e}"))?;
+        Ok(terminals)
+    }
 }
 
 /// Decode the window bounds columns of a window row.
@@ -141,5 +214,6 @@ fn tab_state_from_row(row: &Row<'_>) -> rusqlite::Result<TabState> {
         remote,
         log_view: row.get(5)?,
         color_tag: row.get(6)?,
+        read_only: row.get(11)?,
     })
 }
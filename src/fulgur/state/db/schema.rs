@@ -5,11 +5,16 @@ use rusqlite::{Connection, TransactionBehavior};
 
 /// Schema version this build expects. Bumping it requires appending a step to
 /// `MIGRATIONS`; the existing steps must never be edited.
-pub const SCHEMA_VERSION: i64 = 1;
+pub const SCHEMA_VERSION: i64 = 4;
 
 /// Ordered schema migrations. Index `n` upgrades `user_version` from `n` to
 /// `n + 1`, so a fresh database runs every step in order.
-const MIGRATIONS: &[&str] = &[include_str!("migrations/001_initial.sql")];
+const MIGRATIONS: &[&str] = &[
+    include_str!("migrations/001_initial.sql"),
+    include_str!("migrations/002_split_panes.sql"),
+    include_str!("migrations/003_read_only_tabs.sql"),
+    include_str!("migrations/004_terminals.sql"),
+];
 
 /// How long a connection waits for a lock held by another connection.
 const BUSY_TIMEOUT_MS: i64 = 5_000;
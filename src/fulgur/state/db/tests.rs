@@ -2,7 +2,8 @@
 
 use super::StateDb;
 use crate::fulgur::state::persistence::{
-    SerializedRemoteSpec, SerializedWindowBounds, TabContent, TabState, WindowState, WindowsState,
+    SerializedRemoteSpec, SerializedSplit, SerializedWindowBounds, TabContent, TabState,
+    WindowState, WindowsState,
 };
 
 /// Build a tab state with a given identity and title.
@@ -16,6 +17,7 @@ fn tab(tab_id: u64, title: &str, content: Option<&str>) -> TabState {
         remote: None,
         log_view: false,
         color_tag: None,
+        read_only: false,
     }
 }
 
@@ -27,6 +29,8 @@ fn state_with(window_id: i64, tabs: Vec<TabState>) -> WindowsState {
             tabs,
             active_tab_index: Some(0),
             window_bounds: SerializedWindowBounds::default(),
+            splits: Vec::new(),
+            terminals: Vec::new(),
         }],
     }
 }
@@ -81,6 +85,87 @@ fn window_bounds_roundtrip() {
     assert_eq!(bounds.display_id, Some(2));
 }
 
+#[test]
+fn nested_split_layout_roundtrips_and_can_be_cleared() {
+    let mut db = memory_db();
+    let mut snapshot = state_with(1, vec![tab(0, "a.txt", None), tab(4, "b.txt", None)]);
+    snapshot.windows[0].splits = vec![
+        SerializedSplit {
+            orientation: "horizontal".to_string(),
+            tab_id: 4,
+            parent: None,
+        },
+        SerializedSplit {
+            orientation: "vertical".to_string(),
+            tab_id: 0,
+            parent: Some(0),
+        },
+    ];
+    db.apply(&snapshot).expect("apply split snapshot");
+    assert_eq!(
+        db.load().expect("load").windows[0].splits,
+        snapshot.windows[0].splits
+    );
+    let stats = db.apply(&snapshot).expect("reapply split snapshot");
+    assert_eq!(
+        stats.splits_written, 0,
+        "an unchanged layout is not rewritten"
+    );
+
+    snapshot.windows[0].splits.clear();
+    let stats = db.apply(&snapshot).expect("apply unsplit snapshot");
+    assert_eq!(
+        stats.splits_written, 1,
+        "closing the split rewrites the panes"
+    );
+    assert!(db.load().expect("load").windows[0].splits.is_empty());
+}
+
+#[test]
+fn read_only_flag_roundtrips_as_a_metadata_change() {
+    let mut db = memory_db();
+    db.apply(&state_with(1, vec![tab(0, "hosts", Some("body"))]))
+        .expect("initial apply");
+
+    let mut locked = tab(0, "hosts", Some("body"));
+    locked.read_only = true;
+    let stats = db
+        .apply(&state_with(1, vec![locked]))
+        .expect("apply read-only");
+
+    assert_eq!(stats.tabs_content_written, 0);
+    assert_eq!(stats.tabs_metadata_updated, 1);
+    assert!(db.load().expect("load").windows[0].tabs[0].read_only);
+}
+
+#[test]
+fn terminal_directories_roundtrip_in_order() {
+    let mut db = memory_db();
+    let mut snapshot = state_with(1, vec![tab(0, "a.txt", None)]);
+    snapshot.windows[0].terminals = vec![
+        std::path::PathBuf::from("/work/b"),
+        std::path::PathBuf::from("/work/a"),
+    ];
+    db.apply(&snapshot).expect("apply terminals snapshot");
+    assert_eq!(
+        db.load().expect("load").windows[0].terminals,
+        snapshot.windows[0].terminals
+    );
+
+    let stats = db.apply(&snapshot).expect("reapply terminals snapshot");
+    assert_eq!(
+        stats.terminals_written, 0,
+        "unchanged terminals are not rewritten"
+    );
+
+    snapshot.windows[0].terminals.clear();
+    let stats = db
+        .apply(&snapshot)
+        .expect("apply snapshot without terminals");
+    assert_eq!(stats.terminals_written, 1);
+    assert!(db.load().expect("load").windows[0].terminals.is_empty());
+}
+
 #[test]
 fn reapplying_an_identical_snapshot_writes_nothing() {
     let mut db = memory_db();
@@ -220,6 +305,8 @@ fn closing_a_window_deletes_it_with_its_tabs() {
         tabs: vec![tab(0, "b.txt", Some("second window"))],
         active_tab_index: Some(0),
         window_bounds: SerializedWindowBounds::default(),
+        splits: Vec::new(),
+        terminals: Vec::new(),
     });
     db.apply(&two_windows).expect("initial apply");
 
@@ -391,6 +478,8 @@ fn windows_are_restored_in_persisted_order() {
         tabs: vec![tab(0, "second-window.txt", None)],
         active_tab_index: Some(0),
         window_bounds: SerializedWindowBounds::default(),
+        splits: Vec::new(),
+        terminals: Vec::new(),
     });
     db.apply(&state).expect("apply");
 
@@ -23,6 +23,7 @@ struct StoredTab {
     last_saved: Option<String>,
     log_view: bool,
     color_tag: Option<String>,
+    read_only: bool,
     remote_host: Option<String>,
     remote_port: Option<u16>,
     remote_user: Option<String>,
@@ -68,6 +69,8 @@ impl StateDb {
                 .map_err(|e| anyhow!("Window position does not fit an integer: {e}"))?;
             apply_window(&tx, window, position, &mut stats)?;
             apply_tabs(&tx, window, &mut stats)?;
+            apply_terminals(&tx, window, &mut stats)?;
+            apply_splits(&tx, window, &mut stats)?;
         }
         for closed in self.owned_windows.difference(&present) {
             let deleted = tx
@@ -254,6 +257,153 @@ fn apply_tabs(
     Ok(())
 }
 
+/// Rewrite the terminal rows of one window when they differ from the snapshot.
+///
+/// ### Arguments
+/// - `conn`: The open transaction
+/// - `window`: The window whose terminals are being persisted
+/// - `stats`: Counters updated when the rows are rewritten
+///
+/// ### Errors
+/// - Returns an error if the existing rows cannot be read or a write fails.
+///
+/// ### Returns
+/// - `Ok(())`: The terminal rows match the snapshot
+/// - `Err(anyhow::Error)`: The rows could not be reconciled
+fn apply_terminals(
+    conn: &Connection,
+    window: &WindowState,
+    stats: &mut ApplyStats,
+) -> anyhow::Result<()> {
+    let desired: Vec<Vec<u8>> = window
+        .terminals
+        .iter()
+        .map(|dir| path_to_bytes(dir))
+        .collect();
+    let stored = conn
+        .prepare("SELECT working_dir FROM terminals WHERE window_id = ?1 ORDER BY position")
+        .and_then(|mut stmt| {
+            stmt.query_map([window.window_id], |row| row.get::<_, Vec<u8>>(0))?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| {
+            anyhow!(
+                "Failed to read the terminals of window {}: {e}",
+                window.window_id
+            )
+        })?;
+    if stored == desired {
+        return Ok(());
+    }
+    conn.execute(
+        "DELETE FROM terminals WHERE window_id = ?1",
+        [window.window_id],
+    )
+    .map_err(|e| {
+        anyhow!(
+            "Failed to clear the terminals of window {}: {e}",
+            window.window_id
+        )
+    })?;
+    for (position, dir) in desired.iter().enumerate() {
+        let position = i64::try_from(position)
+            .map_err(|e| anyhow!("Terminal position does not fit an integer: {e}"))?;
+        conn.execute(
+            "INSERT INTO terminals (window_id, position, working_dir) VALUES (?1, ?2, ?3)",
+            params![window.window_id, position, dir],
+        )
+        .map_err(|e| {
+            anyhow!(
+                "Failed to write a terminal of window {}: {e}",
+                window.window_id
+            )
+        })?;
+    }
+    stats.terminals_written += 1;
+    Ok(())
+}
+
+/// Rewrite the split pane rows of one window when they differ from the snapshot.
+///
+/// ### Arguments
+/// - `conn`: The open transaction
+/// - `window`: The window whose split panes are being persisted
+/// - `stats`: Counters updated when the rows are rewritten
+///
+/// ### Errors
+/// - Returns an error if the existing rows cannot be read or a write fails.
+///
+/// ### Returns
+/// - `Ok(())`: The split pane rows match the snapshot
+/// - `Err(anyhow::Error)`: The rows could not be reconciled
+fn apply_splits(
+    conn: &Connection,
+    window: &WindowState,
+    stats: &mut ApplyStats,
+) -> anyhow::Result<()> {
+    let desired = window
+        .splits
+        .iter()
+        .map(|split| {
+            let tab_id = i64::try_from(split.tab_id)
+                .map_err(|e| anyhow!("Split tab id does not fit an integer: {e}"))?;
+            let parent = split
+                .parent
+                .map(i64::try_from)
+                .transpose()
+                .map_err(|e| anyhow!("Split parent does not fit an integer: {e}"))?;
+            Ok((split.orientation.clone(), tab_id, parent))
+        })
+        .collect::<anyhow::Result<Vec<(String, i64, Option<i64>)>>>()?;
+    let stored = conn
+        .prepare(
+            "SELECT orientation, tab_id, parent FROM split_panes
+             WHERE window_id = ?1 ORDER BY position",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([window.window_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| {
+            anyhow!(
+                "Failed to read the split panes of window {}: {e}",
+                window.window_id
+            )
+        })?;
+    if stored == desired {
+        return Ok(());
+    }
+    conn.execute(
+        "DELETE FROM split_panes WHERE window_id = ?1",
+        [window.window_id],
+    )
+    .map_err(|e| {
+        anyhow!(
+            "Failed to clear the split panes of window {}: {e}",
+            window.window_id
+        )
+    })?;
+    for (position, (orientation, tab_id, parent)) in desired.iter().enumerate() {
+        let position = i64::try_from(position)
+            .map_err(|e| anyhow!("Split pane position does not fit an integer: {e}"))?;
+        conn.execute(
+            "INSERT INTO split_panes (window_id, position, orientation, tab_id, parent)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![window.window_id, position, orientation, tab_id, parent],
+        )
+        .map_err(|e| {
+            anyhow!(
+                "Failed to write a split pane of window {}: {e}",
+                window.window_id
+            )
+        })?;
+    }
+    stats.splits_written += 1;
+    Ok(())
+}
+
 /// Read the comparable state of every tab row of a window.
 ///
 /// ### Arguments
@@ -273,7 +423,8 @@ fn read_stored_tabs(
     let mut stmt = conn
         .prepare(
             "SELECT id, position, title, file_path, last_saved, log_view, color_tag,
-                    remote_host, remote_port, remote_user, remote_path, content_hash, content_len
+                    remote_host, remote_port, remote_user, remote_path, content_hash, content_len,
+                    read_only
              FROM tabs WHERE window_id = ?1",
         )
         .map_err(|e| anyhow!("Failed to prepare the stored tab query: {e}"))?;
@@ -287,6 +438,7 @@ fn read_stored_tabs(
                 last_saved: row.get(4)?,
                 log_view: row.get(5)?,
                 color_tag: row.get(6)?,
+                read_only: row.get(13)?,
                 remote_host: row.get(7)?,
                 remote_port: row.get(8)?,
                 remote_user: row.get(9)?,
@@ -333,6 +485,7 @@ fn stored_tab_from_snapshot(tab: &TabState, position: i64) -> StoredTab {
         last_saved: tab.last_saved.clone(),
         log_view: tab.log_view,
         color_tag: tab.color_tag.clone(),
+        read_only: tab.read_only,
         remote_host: tab.remote.as_ref().map(|remote| remote.host.clone()),
         remote_port: tab.remote.as_ref().map(|remote| remote.port),
         remote_user: tab.remote.as_ref().map(|remote| remote.user.clone()),
@@ -395,8 +548,8 @@ fn insert_tab(
         // far better than failing the save and losing every window's state.
         "INSERT INTO tabs (window_id, id, position, title, file_path, content, content_hash,
                            content_len, last_saved, log_view, color_tag, remote_host, remote_port,
-                           remote_user, remote_path)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                           remote_user, remote_path, read_only)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
          ON CONFLICT(window_id, id) DO UPDATE SET
              position = excluded.position,
              title = excluded.title,
@@ -410,7 +563,8 @@ fn insert_tab(
              remote_host = excluded.remote_host,
              remote_port = excluded.remote_port,
              remote_user = excluded.remote_user,
-             remote_path = excluded.remote_path",
+             remote_path = excluded.remote_path,
+             read_only = excluded.read_only",
         params![
             window_id,
             id,
@@ -427,6 +581,7 @@ fn insert_tab(
             desired.remote_port,
             desired.remote_user,
             desired.remote_path,
+            desired.read_only,
         ],
     )
     .map_err(|e| anyhow!("Failed to insert tab {id}: {e}"))?;
@@ -462,7 +617,7 @@ fn update_tab(
         "UPDATE tabs SET position = ?3, title = ?4, file_path = ?5, content = ?6,
                          content_hash = ?7, content_len = ?8, last_saved = ?9, log_view = ?10,
                          color_tag = ?11, remote_host = ?12, remote_port = ?13, remote_user = ?14,
-                         remote_path = ?15
+                         remote_path = ?15, read_only = ?16
          WHERE window_id = ?1 AND id = ?2",
         params![
             window_id,
@@ -480,6 +635,7 @@ fn update_tab(
             desired.remote_port,
             desired.remote_user,
             desired.remote_path,
+            desired.read_only,
         ],
     )
     .map_err(|e| anyhow!("Failed to update tab {id}: {e}"))?;
@@ -510,7 +666,7 @@ fn update_tab_metadata(
     conn.execute(
         "UPDATE tabs SET position = ?3, title = ?4, file_path = ?5, last_saved = ?6,
                          log_view = ?7, color_tag = ?8, remote_host = ?9, remote_port = ?10,
-                         remote_user = ?11, remote_path = ?12
+                         remote_user = ?11, remote_path = ?12, read_only = ?13
          WHERE window_id = ?1 AND id = ?2",
         params![
             window_id,
@@ -525,6 +681,7 @@ fn update_tab_metadata(
             desired.remote_port,
             desired.remote_user,
             desired.remote_path,
+            desired.read_only,
         ],
     )
     .map_err(|e| anyhow!("Failed to update tab metadata for {id}: {e}"))?;
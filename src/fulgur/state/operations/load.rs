@@ -3,13 +3,17 @@ use super::decision::{TabRestoreDecision, determine_tab_restore_strategy};
 use crate::fulgur::{
     Fulgur,
     editor_tab::{EditorTab, FromFileParams, TabLocation},
-    files::file_operations::{RemoteFileResult, detect_encoding_and_decode},
+    files::{
+        file_operations::{RemoteFileResult, detect_encoding_and_decode},
+        indentation::Indentation,
+    },
     languages::supported_languages::{
         SupportedLanguage, language_from_content, language_registry_name,
     },
     tab::{Tab, TabId},
     ui::components_utils::{UNTITLED, UTF_8},
     ui::tabs::color_tag::ColorTag,
+    utils::atomic_write::is_file_writable,
 };
 use gpui::{App, AppContext, Context, Window};
 use gpui_component::input::TabSize;
@@ -73,6 +77,23 @@ impl Fulgur {
                 self.activate_log_view(active_id, window, cx);
             }
 
+            // Split panes build their own editor state, so they are reopened once
+            // the real editor settings are back in place.
+            if !window_state.splits.is_empty() {
+                let splits = window_state.splits;
+                cx.defer_in(window, move |this, window, cx| {
+                    this.restore_split_panes(&splits, window, cx);
+                });
+            }
+
+            #[cfg(target_os = "linux")]
+            if !window_state.terminals.is_empty() {
+                let dirs = window_state.terminals;
+                cx.defer_in(window, move |this, window, cx| {
+                    this.restore_terminals(dirs, window, cx);
+                });
+            }
+
             cx.notify();
         } else {
             log::warn!("No saved state for window {window_index}, starting fresh");
@@ -157,6 +178,7 @@ impl Fulgur {
                         content: restored_content,
                         encoding: UTF_8.to_string(),
                         lossy: false,
+                        read_only: tab_state.read_only,
                     },
                     window,
                     cx,
@@ -214,14 +236,19 @@ impl Fulgur {
             };
             let (csv_view_mode, csv_delimiter) =
                 crate::fulgur::ui::tabs::editor_tab::initial_csv_state(language, &content);
+            let indentation = Indentation::resolve(
+                &content,
+                language_registry_name(&language),
+                &self.settings.editor_settings,
+            );
             let content_entity = cx.new(|cx| {
                 gpui_component::input::EditorState::new(window, cx)
                     .language(language_name)
                     .line_number(self.settings.editor_settings.show_line_numbers)
                     .indent_guides(self.settings.editor_settings.show_indent_guides)
                     .tab_size(TabSize {
-                        tab_size: self.settings.editor_settings.tab_size,
-                        hard_tabs: false,
+                        tab_size: indentation.width,
+                        hard_tabs: !indentation.use_spaces,
                     })
                     .soft_wrap(self.settings.editor_settings.soft_wrap && !large_file)
                     .show_whitespaces(self.settings.editor_settings.show_whitespaces)
@@ -261,6 +288,9 @@ impl Fulgur {
                 log_follow: true,
                 log_full: false,
                 log_content: None,
+                last_edit: None,
+                read_only: false,
+                indentation,
                 content_subscription: None,
             }
         };
@@ -272,6 +302,10 @@ impl Fulgur {
             tab.log_view = true;
         }
         tab.color_tag = color_tag;
+        tab.read_only = tab_state.read_only
+            || tab
+                .file_path()
+                .is_some_and(|path| path.exists() && !is_file_writable(path));
         Some(tab)
     }
 }
@@ -4,6 +4,7 @@ use super::super::persistence::{
 };
 use crate::fulgur::{Fulgur, editor_tab::TabLocation, tab::Tab, ui::components_utils::UNTITLED};
 use gpui::{App, Window};
+use std::path::PathBuf;
 
 impl Fulgur {
     /// Save the current app state to disk (saves all windows in multi-window mode)
@@ -97,6 +98,7 @@ impl Fulgur {
                                 title: editor_tab.title.to_string(),
                                 log_view: editor_tab.log_view,
                                 color_tag: editor_tab.color_tag.map(|c| c.key().to_string()),
+                                read_only: editor_tab.read_only,
                                 file_path: Some(path.clone()),
                                 content: Some(TabContent::Rope(current_content)),
                                 last_saved: get_file_modified_time(path),
@@ -108,6 +110,7 @@ impl Fulgur {
                                 title: editor_tab.title.to_string(),
                                 log_view: editor_tab.log_view,
                                 color_tag: editor_tab.color_tag.map(|c| c.key().to_string()),
+                                read_only: editor_tab.read_only,
                                 file_path: Some(path.clone()),
                                 content: None,
                                 last_saved: None,
@@ -129,6 +132,7 @@ impl Fulgur {
                             title: editor_tab.title.to_string(),
                             log_view: editor_tab.log_view,
                             color_tag: editor_tab.color_tag.map(|c| c.key().to_string()),
+                            read_only: editor_tab.read_only,
                             file_path: None,
                             content,
                             last_saved: None,
@@ -160,6 +164,7 @@ impl Fulgur {
                             title: editor_tab.title.to_string(),
                             log_view: editor_tab.log_view,
                             color_tag: editor_tab.color_tag.map(|c| c.key().to_string()),
+                            read_only: editor_tab.read_only,
                             file_path: None,
                             content: Some(current_content),
                             last_saved: None,
@@ -183,14 +188,14 @@ impl Fulgur {
     ///
     /// ### Returns
     /// - `Some(usize)`: the active editor tab index
-    /// - `None`: if the active tab is a Settings tab (not persisted).
+    /// - `None`: if the active tab is a Settings or large-file tab (not persisted).
     fn active_editor_index_for_state(&self, cx: &App) -> Option<usize> {
         let active = self.active_tab_index(cx)?;
         let active_tab = self.tabs.get(active)?.read(cx);
         let editor_tab_id = match active_tab {
             Tab::Editor(et) => et.id,
             Tab::MarkdownPreview(pt) => pt.source_tab_id,
-            Tab::Settings(_) => return None,
+            Tab::Settings(_) | Tab::LargeFile(_) => return None,
         };
         let mut editor_index = 0;
         for tab in &self.tabs {
@@ -204,6 +209,22 @@ impl Fulgur {
         None
     }
 
+    /// Collect the working directories of the open terminals
+    ///
+    /// ### Returns
+    /// - `Vec<PathBuf>`: The directory of each terminal, in tab order; empty where
+    ///   the terminal panel is not available
+    fn terminals_state(&self) -> Vec<PathBuf> {
+        #[cfg(target_os = "linux")]
+        {
+            self.terminal_dirs()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Vec::new()
+        }
+    }
+
     /// Build `WindowState` for this window without window bounds (for cross-window saves)
     ///
     /// ### Arguments
@@ -218,6 +239,8 @@ impl Fulgur {
             tabs: self.build_tab_states(cx),
             active_tab_index: self.active_editor_index_for_state(cx),
             window_bounds,
+            splits: self.split_state(),
+            terminals: self.terminals_state(),
         }
     }
 
@@ -240,6 +263,8 @@ impl Fulgur {
             tabs: self.build_tab_states(cx),
             active_tab_index: self.active_editor_index_for_state(cx),
             window_bounds,
+            splits: self.split_state(),
+            terminals: self.terminals_state(),
         }
     }
 }
@@ -6,4 +6,4 @@ mod windows;
 pub use bounds::SerializedWindowBounds;
 pub use tabs::{SerializedRemoteSpec, TabContent, TabState};
 pub use timestamps::{get_file_modified_time, is_file_newer};
-pub use windows::{WindowState, WindowsState};
+pub use windows::{SerializedSplit, WindowState, WindowsState};
@@ -198,6 +198,9 @@ pub struct TabState {
     /// Stable key of the tab's color tag, if any. See `ColorTag::key`.
     #[serde(default)]
     pub color_tag: Option<String>,
+    /// Whether edits to the tab are blocked.
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 #[cfg(test)]
@@ -19,6 +19,24 @@ pub struct WindowState {
     /// Window position, size, and display state (windowed/maximized/fullscreen)
     #[serde(default)]
     pub window_bounds: SerializedWindowBounds,
+    /// Split editor panes, in the order they were opened; empty when the window is not split
+    #[serde(default)]
+    pub splits: Vec<SerializedSplit>,
+    /// Working directories of the open terminals, in tab order
+    #[serde(default)]
+    pub terminals: Vec<PathBuf>,
+}
+
+/// Persisted layout of one split editor pane of a window
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SerializedSplit {
+    /// Split direction key (`horizontal` or `vertical`)
+    pub orientation: String,
+    /// Identity of the tab shown in the pane
+    pub tab_id: u64,
+    /// Index of the pane this one was split off, `None` for the primary pane
+    #[serde(default)]
+    pub parent: Option<usize>,
 }
 
 impl WindowState {
@@ -196,6 +214,7 @@ mod tests {
             remote: None,
             log_view: false,
             color_tag: None,
+            read_only: false,
         }
     }
 
@@ -232,6 +251,8 @@ mod tests {
                 tabs: vec![file_tab_state(0, "a.rs", "fulgur_state_a.rs", None, None)],
                 active_tab_index: Some(0),
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             }],
         };
         original.save_to_path(&path).unwrap();
@@ -271,6 +292,8 @@ mod tests {
                         height: 900.0,
                         display_id: Some(1),
                     },
+                    splits: Vec::new(),
+                    terminals: Vec::new(),
                 },
                 WindowState {
                     window_id: 2,
@@ -283,6 +306,7 @@ mod tests {
                         remote: None,
                         log_view: false,
                         color_tag: None,
+                        read_only: false,
                     }],
                     active_tab_index: Some(0),
                     window_bounds: SerializedWindowBounds {
@@ -293,6 +317,8 @@ mod tests {
                         height: 1080.0,
                         display_id: Some(2),
                     },
+                    splits: Vec::new(),
+                    terminals: Vec::new(),
                 },
             ],
         };
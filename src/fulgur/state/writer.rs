@@ -361,9 +361,12 @@ mod tests {
                     remote: None,
                     log_view: false,
                     color_tag: None,
+                    read_only: false,
                 }],
                 active_tab_index: Some(0),
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             }],
         }
     }
@@ -37,8 +37,9 @@ pub fn read_remote_file(session: &SshSession, remote_path: &str) -> Result<Vec<u
 
 /// Type classification for a remote SFTP path.
 pub enum RemotePathKind {
-    /// Path points to a regular file.
-    File,
+    /// Path points to a regular file. `writable` is false when its mode bits
+    /// grant write permission to nobody.
+    File { writable: bool },
     /// Path points to a directory.
     Directory,
     /// Path does not exist on the remote host.
@@ -64,7 +65,7 @@ pub struct RemoteDirectoryEntry {
 /// reason other than the path being missing.
 ///
 /// ### Returns
-/// - `Ok(RemotePathKind::File)`: Path exists and is a file.
+/// - `Ok(RemotePathKind::File { .. })`: Path exists and is a file.
 /// - `Ok(RemotePathKind::Directory)`: Path exists and is a directory.
 /// - `Ok(RemotePathKind::Missing)`: Path does not exist.
 /// - `Err(SshError::SftpError)`: Metadata lookup failed for another reason.
@@ -81,14 +82,16 @@ pub fn classify_remote_path(
                 if perm & 0o170_000 == 0o040_000 {
                     return Ok(RemotePathKind::Directory);
                 }
-                return Ok(RemotePathKind::File);
+                return Ok(RemotePathKind::File {
+                    writable: perm & 0o222 != 0,
+                });
             }
 
             // Fallback when the server omits mode bits.
             if session.sftp.opendir(path).is_ok() {
                 Ok(RemotePathKind::Directory)
             } else {
-                Ok(RemotePathKind::File)
+                Ok(RemotePathKind::File { writable: true })
             }
         }
         Err(err) => match err.code() {
@@ -125,7 +128,7 @@ pub fn closest_existing_remote_directory(
     loop {
         match classify_remote_path(session, &candidate)? {
             RemotePathKind::Directory => return Ok(candidate),
-            RemotePathKind::File | RemotePathKind::Missing => {
+            RemotePathKind::File { .. } | RemotePathKind::Missing => {
                 if candidate == REMOTE_ROOT_PATH {
                     return Ok(REMOTE_ROOT_PATH.to_string());
                 }
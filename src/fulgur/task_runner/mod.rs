@@ -0,0 +1,753 @@
+mod panel;
+
+use crate::fulgur::{
+    Fulgur,
+    diagnostics::DiagnosticSource,
+    editor_tab::Jump,
+    languages::supported_languages::SupportedLanguage,
+    tab::TabId,
+    utils::{
+        ansi::{AnsiSpan, AnsiStyle, parse_ansi},
+        task_output::{FileReference, Problem, ProblemSeverity, find_references, parse_problems},
+        task_process::{TaskEvent, TaskProcess},
+        tasks::{ResolvedTask, available_tasks},
+    },
+};
+use futures::StreamExt;
+use gpui::{Context, ScrollHandle, SharedString, Subscription, Task, Window};
+use gpui_component::{
+    WindowExt,
+    input::{InputEvent, Position},
+    notification::NotificationType,
+};
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Most lines of output kept for the running task, older lines being dropped
+const MAX_OUTPUT_LINES: usize = 5000;
+
+/// Most output events applied in one UI update
+const EVENT_BATCH: usize = 256;
+
+/// Longest a clicked reference waits for its file to open before its jump is dropped
+const REFERENCE_JUMP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Source tag set on the diagnostics of the problems reported by a task
+const TASK_SOURCE: &str = "task";
+
+/// One line of task output, parsed once when it arrives
+struct OutputLine {
+    spans: Vec<AnsiSpan>,           // Styled runs, escape sequences removed
+    text: String,                   // The visible text of the line
+    references: Vec<FileReference>, // References to files that exist
+}
+
+/// The tasks of one file, resolved on the background executor
+struct TaskList {
+    context: (PathBuf, SupportedLanguage), // File and language the tasks are resolved for
+    tasks: Option<Result<Vec<ResolvedTask>, String>>, // `None` while they are resolving
+}
+
+/// Where the task shown in the panel stands
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+enum TaskStatus {
+    #[default]
+    Idle,
+    Running,
+    Stopping,
+    Exited(Option<i32>),
+    Failed(String),
+}
+
+/// The task runner: the running task, its output and the problems it reported
+#[derive(Default)]
+pub struct TaskRunnerState {
+    visible: bool,
+    current: Option<ResolvedTask>, // The task whose output is shown, also run again by "Run Last Task"
+    process: Option<TaskProcess>,  // Stops the task when dropped
+    #[allow(
+        dead_code,
+        reason = "RAII guard: dropping the task stops reading the output"
+    )]
+    consumer: Option<Task<()>>, // Applies the output events of `process`
+    status: TaskStatus,
+    lines: VecDeque<OutputLine>,
+    dropped_lines: usize, // Lines dropped from the front of `lines`
+    style: AnsiStyle,     // Style in effect at the end of the last line
+    existing_files: HashMap<PathBuf, bool>, // Whether referenced paths are files, checked once per run
+    problems: Vec<Problem>, // Problems of the last finished run, with canonical paths
+    problems_revision: u64, // Bumped whenever `problems` changes
+    #[allow(
+        dead_code,
+        reason = "RAII guard: dropping the task discards the problems it collects"
+    )]
+    problems_task: Option<Task<()>>, // Canonicalizes the problem paths of the run that just finished
+    task_list: Option<TaskList>, // Tasks of the active tab, listed by the "Run Task" menu
+    #[allow(
+        dead_code,
+        reason = "RAII guard: dropping the task discards the tasks it resolves"
+    )]
+    task_list_task: Option<Task<()>>, // Resolves `task_list`
+    diagnostics_applied: Option<(TabId, u64)>, // Tab and problems revision the diagnostics were last written for
+    diagnostics_shown_on: Option<TabId>,       // Tab currently underlining task problems
+    #[allow(dead_code, reason = "RAII guard: keeps the subscription alive")]
+    diagnostics_subscription: Option<Subscription>, // Drops the problems of `diagnostics_shown_on` once it is edited
+    pending_jump: Option<(PathBuf, Jump, Instant)>, // Clicked reference waiting for its file to open
+    scroll_handle: ScrollHandle,
+}
+
+/// Convert a 1-based position reported by a task to a 0-based editor position
+fn zero_based(position: u32) -> u32 {
+    position.saturating_sub(1)
+}
+
+/// Build the diagnostic underlining a problem reported by a task
+///
+/// ### Arguments
+/// - `problem`: The problem
+///
+/// ### Returns
+/// - `Diagnostic`: A diagnostic spanning one character at the problem position
+fn problem_diagnostic(problem: &Problem) -> Diagnostic {
+    let line = zero_based(problem.line);
+    let character = problem.column.map_or(0, zero_based);
+    Diagnostic {
+        range: lsp_types::Range {
+            start: Position { line, character },
+            end: Position {
+                line,
+                character: character.saturating_add(1),
+            },
+        },
+        severity: Some(match problem.severity {
+            ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+            ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+            ProblemSeverity::Note => DiagnosticSeverity::INFORMATION,
+        }),
+        message: problem.message.clone(),
+        source: Some(TASK_SOURCE.to_string()),
+        code: None,
+        related_information: None,
+        tags: None,
+        code_description: None,
+        data: None,
+    }
+}
+
+impl Fulgur {
+    /// Show or hide the task panel
+    ///
+    /// ### Arguments
+    /// - `_window`: The window context
+    /// - `cx`: The application context
+    pub fn toggle_task_panel(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        self.task_runner.visible = !self.task_runner.visible;
+        if self.task_runner.visible {
+            self.forget_available_tasks();
+        }
+        cx.notify();
+    }
+
+    /// Drop the tasks listed by the "Run Task" menu, so they are resolved again
+    ///
+    /// ### Description
+    /// Called once a file is saved, since it may be the `tasks.json` of the
+    /// active tab.
+    pub(crate) fn forget_available_tasks(&mut self) {
+        self.task_runner.task_list = None;
+        self.task_runner.task_list_task = None;
+    }
+
+    /// Resolve the tasks of the active tab on the background executor, while the task panel is visible
+    ///
+    /// ### Description
+    /// The tasks are kept until the active tab changes, the panel is shown
+    /// again or a file is saved, so the "Run Task" menu never reads
+    /// `tasks.json` on the UI thread.
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub(crate) fn refresh_available_tasks(&mut self, cx: &mut Context<Self>) {
+        if !self.task_runner.visible {
+            return;
+        }
+        let Some(context) = self.active_task_context(cx) else {
+            self.forget_available_tasks();
+            return;
+        };
+        let resolved = self
+            .task_runner
+            .task_list
+            .as_ref()
+            .is_some_and(|list| list.context == context);
+        if resolved {
+            return;
+        }
+        let (file, language) = context.clone();
+        self.task_runner.task_list = Some(TaskList {
+            context,
+            tasks: None,
+        });
+        self.task_runner.task_list_task = Some(cx.spawn(async move |this, cx| {
+            let tasks = cx
+                .background_executor()
+                .spawn(async move { available_tasks(&file, language).map_err(|e| e.to_string()) })
+                .await;
+            if let Err(e) = &tasks {
+                log::warn!("Failed to load tasks: {e}");
+            }
+            _ = this.update(cx, |this, cx| {
+                if let Some(list) = &mut this.task_runner.task_list {
+                    list.tasks = Some(tasks);
+                }
+                cx.notify();
+            });
+        }));
+    }
+
+    /// Get the file and language the tasks of the active tab are resolved for
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some((PathBuf, SupportedLanguage))`: The file of the active tab and its language
+    /// - `None`: The active tab is not an editor tab saved to a local file
+    pub(crate) fn active_task_context(
+        &self,
+        cx: &Context<Self>,
+    ) -> Option<(PathBuf, SupportedLanguage)> {
+        let editor_tab = self.get_active_editor_tab(cx)?;
+        Some((editor_tab.file_path()?.clone(), editor_tab.language))
+    }
+
+    /// Run the last task again, or the first task of the active tab when none ran yet
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn run_last_task(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(task) = self.task_runner.current.clone() {
+            self.run_task(task, window, cx);
+            return;
+        }
+        let Some((file, language)) = self.active_task_context(cx) else {
+            window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from("Save the file to run its tasks"),
+                ),
+                cx,
+            );
+            return;
+        };
+        let cached = self
+            .task_runner
+            .task_list
+            .as_ref()
+            .filter(|list| list.context.0 == file && list.context.1 == language)
+            .and_then(|list| list.tasks.clone());
+        if let Some(tasks) = cached {
+            self.run_first_task(tasks, window, cx);
+            return;
+        }
+        cx.spawn_in(window, async move |view, window| {
+            let tasks = window
+                .background_executor()
+                .spawn(async move { available_tasks(&file, language).map_err(|e| e.to_string()) })
+                .await;
+            window
+                .update(|window, cx| {
+                    _ = view.update(cx, |this, cx| this.run_first_task(tasks, window, cx));
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Run the first of the tasks resolved for the active tab
+    ///
+    /// ### Arguments
+    /// - `tasks`: The resolved tasks, or why they could not be resolved
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn run_first_task(
+        &mut self,
+        tasks: Result<Vec<ResolvedTask>, String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match tasks {
+            Ok(tasks) => match tasks.into_iter().next() {
+                Some(task) => self.run_task(task, window, cx),
+                None => window.push_notification(
+                    (
+                        NotificationType::Info,
+                        SharedString::from(
+                            "No tasks for this file, add them to .fulgur/tasks.json",
+                        ),
+                    ),
+                    cx,
+                ),
+            },
+            Err(e) => {
+                log::warn!("Failed to load tasks: {e}");
+                window.push_notification((NotificationType::Error, SharedString::from(e)), cx);
+            }
+        }
+    }
+
+    /// Run a task, replacing the one shown in the task panel
+    ///
+    /// ### Description
+    /// A task still running is stopped first. The output is read on helper
+    /// threads and applied in batches, so a chatty task does not flood the UI.
+    ///
+    /// ### Arguments
+    /// - `task`: The task to run
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn run_task(
+        &mut self,
+        task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let runner = &mut self.task_runner;
+        runner.process = None;
+        runner.consumer = None;
+        runner.lines.clear();
+        runner.dropped_lines = 0;
+        runner.style = AnsiStyle::default();
+        runner.existing_files.clear();
+        runner.problems_task = None;
+        runner.visible = true;
+        runner.current = Some(task.clone());
+        log::info!("Running task '{}' in {}", task.command, task.cwd.display());
+        let (sender, events) = futures::channel::mpsc::unbounded();
+        let spawned = TaskProcess::spawn(&task.command, &task.cwd, move |event| {
+            // The receiver is gone once another task replaced this one
+            let _ = sender.unbounded_send(event);
+        });
+        match spawned {
+            Ok(process) => {
+                runner.process = Some(process);
+                runner.status = TaskStatus::Running;
+            }
+            Err(e) => {
+                log::warn!("{e}");
+                runner.status = TaskStatus::Failed(e.to_string());
+                cx.notify();
+                return;
+            }
+        }
+        runner.consumer = Some(cx.spawn_in(window, async move |view, window| {
+            let mut batches = events.ready_chunks(EVENT_BATCH);
+            while let Some(batch) = batches.next().await {
+                let applied = window.update(|window, cx| {
+                    view.update(cx, |this, cx| this.apply_task_events(batch, window, cx))
+                });
+                if !matches!(applied, Ok(Ok(()))) {
+                    break;
+                }
+            }
+        }));
+        cx.notify();
+    }
+
+    /// Stop the running task and the processes it started
+    ///
+    /// ### Arguments
+    /// - `_window`: The window context
+    /// - `cx`: The application context
+    pub fn stop_task(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(process) = &self.task_runner.process {
+            process.stop();
+            self.task_runner.status = TaskStatus::Stopping;
+            cx.notify();
+        }
+    }
+
+    /// Remove the output of the last task and the diagnostics of its problems
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub(crate) fn clear_task_output(&mut self, cx: &mut Context<Self>) {
+        let runner = &mut self.task_runner;
+        runner.lines.clear();
+        runner.dropped_lines = 0;
+        if runner.process.is_none() {
+            runner.status = TaskStatus::Idle;
+        }
+        runner.problems_task = None;
+        if !runner.problems.is_empty() {
+            runner.problems.clear();
+            runner.problems_revision += 1;
+        }
+        cx.notify();
+    }
+
+    /// Append the output of the running task, and collect its problems once it exits
+    ///
+    /// ### Arguments
+    /// - `events`: The events reported since the last update
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn apply_task_events(
+        &mut self,
+        events: Vec<TaskEvent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(cwd) = self
+            .task_runner
+            .current
+            .as_ref()
+            .map(|task| task.cwd.clone())
+        else {
+            return;
+        };
+        for event in events {
+            match event {
+                TaskEvent::Output(raw) => self.push_task_line(&raw, &cwd),
+                TaskEvent::Exited(code) => {
+                    self.task_runner.status = TaskStatus::Exited(code);
+                    self.finish_task(&cwd, window, cx);
+                }
+                TaskEvent::Failed(message) => {
+                    log::warn!("{message}");
+                    self.task_runner.status = TaskStatus::Failed(message);
+                    self.finish_task(&cwd, window, cx);
+                }
+            }
+        }
+        self.task_runner.scroll_handle.scroll_to_bottom();
+        cx.notify();
+    }
+
+    /// Parse one line of task output and append it
+    ///
+    /// ### Arguments
+    /// - `raw`: The line, escape sequences included
+    /// - `cwd`: The working directory of the task
+    fn push_task_line(&mut self, raw: &str, cwd: &Path) {
+        let runner = &mut self.task_runner;
+        let spans = parse_ansi(raw, &mut runner.style);
+        let text: String = spans.iter().map(|span| span.text.as_str()).collect();
+        let references = find_references(&text, cwd)
+            .into_iter()
+            .filter(|reference| {
+                *runner
+                    .existing_files
+                    .entry(reference.path.clone())
+                    .or_insert_with(|| reference.path.is_file())
+            })
+            .collect();
+        runner.lines.push_back(OutputLine {
+            spans,
+            text,
+            references,
+        });
+        if runner.lines.len() > MAX_OUTPUT_LINES {
+            runner.lines.pop_front();
+            runner.dropped_lines += 1;
+        }
+    }
+
+    /// Collect the problems of a finished task and report how it ended
+    ///
+    /// ### Description
+    /// The problem paths are canonicalized on the background executor, and the
+    /// summary is reported once they are in place.
+    ///
+    /// ### Arguments
+    /// - `cwd`: The working directory of the task
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn finish_task(&mut self, cwd: &Path, window: &mut Window, cx: &mut Context<Self>) {
+        let runner = &mut self.task_runner;
+        runner.process = None;
+        let problems = parse_problems(runner.lines.iter().map(|line| line.text.as_str()), cwd);
+        runner.problems_task = Some(cx.spawn_in(window, async move |view, window| {
+            let problems = window
+                .background_executor()
+                .spawn(async move {
+                    problems
+                        .into_iter()
+                        .filter_map(|mut problem| {
+                            problem.path = std::fs::canonicalize(&problem.path).ok()?;
+                            Some(problem)
+                        })
+                        .collect()
+                })
+                .await;
+            window
+                .update(|window, cx| {
+                    _ = view.update(cx, |this, cx| {
+                        this.report_task_problems(problems, window, cx)
+                    });
+                })
+                .ok();
+        }));
+    }
+
+    /// Keep the problems of a finished task and report how it ended
+    ///
+    /// ### Arguments
+    /// - `problems`: The problems of the task, with canonical paths
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn report_task_problems(
+        &mut self,
+        problems: Vec<Problem>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.task_runner.problems = problems;
+        self.task_runner.problems_revision += 1;
+        cx.notify();
+        let summary = self.task_summary();
+        log::info!("{summary}");
+        if !self.task_runner.visible {
+            let kind = match self.task_runner.status {
+                TaskStatus::Exited(Some(0)) => NotificationType::Success,
+                _ => NotificationType::Error,
+            };
+            window.push_notification((kind, SharedString::from(summary)), cx);
+        }
+    }
+
+    /// Describe where the task shown in the panel stands
+    ///
+    /// ### Returns
+    /// - `String`: e.g. `cargo check exited with status 101: 2 errors, 1 warning`
+    fn task_summary(&self) -> String {
+        let runner = &self.task_runner;
+        let label = runner
+            .current
+            .as_ref()
+            .map_or("Task", |task| task.label.as_str());
+        let status = match &runner.status {
+            TaskStatus::Idle => return "No task has run yet".to_string(),
+            TaskStatus::Running => return format!("Running {label}..."),
+            TaskStatus::Stopping => return format!("Stopping {label}..."),
+            TaskStatus::Exited(Some(code)) => format!("{label} exited with status {code}"),
+            TaskStatus::Exited(None) => format!("{label} was stopped"),
+            TaskStatus::Failed(message) => return message.clone(),
+        };
+        let count = |severity: ProblemSeverity| {
+            runner
+                .problems
+                .iter()
+                .filter(|problem| problem.severity == severity)
+                .count()
+        };
+        let plural = |count: usize, noun: &str| {
+            format!("{count} {noun}{}", if count == 1 { "" } else { "s" })
+        };
+        let (errors, warnings) = (
+            count(ProblemSeverity::Error),
+            count(ProblemSeverity::Warning),
+        );
+        if errors + warnings == 0 {
+            status
+        } else {
+            format!(
+                "{status}: {}, {}",
+                plural(errors, "error"),
+                plural(warnings, "warning")
+            )
+        }
+    }
+
+    /// Open the file of a reference clicked in task or terminal output, at its line and column
+    ///
+    /// ### Description
+    /// Opening a file that is not open yet completes asynchronously, so the jump
+    /// is kept until the file becomes the active tab.
+    ///
+    /// ### Arguments
+    /// - `reference`: The clicked reference
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn open_file_reference(
+        &mut self,
+        reference: &FileReference,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let path =
+            std::fs::canonicalize(&reference.path).unwrap_or_else(|_| reference.path.clone());
+        let jump = Jump {
+            line: zero_based(reference.line),
+            character: reference.column.map(zero_based),
+        };
+        self.task_runner.pending_jump = Some((path.clone(), jump, Instant::now()));
+        self.do_open_file(window, cx, path);
+        self.handle_pending_task_jump(window, cx);
+    }
+
+    /// Move the cursor to a clicked reference once its file is the active tab
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn handle_pending_task_jump(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((path, jump, requested)) = &self.task_runner.pending_jump else {
+            return;
+        };
+        if requested.elapsed() > REFERENCE_JUMP_TIMEOUT {
+            self.task_runner.pending_jump = None;
+            return;
+        }
+        let is_active = self
+            .get_active_editor_tab(cx)
+            .and_then(|editor_tab| editor_tab.file_path())
+            .is_some_and(|active| active == path);
+        if !is_active {
+            return;
+        }
+        let jump = *jump;
+        self.task_runner.pending_jump = None;
+        self.update_active_editor_tab(cx, |editor_tab, cx| {
+            editor_tab.jump_to_line(window, cx, jump);
+        });
+    }
+
+    /// Get the diagnostics of the task problems located in a tab's file
+    ///
+    /// ### Arguments
+    /// - `tab_id`: The tab
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Vec<Diagnostic>`: The diagnostics, empty when the tab has no problems or no file
+    fn task_diagnostics(&self, tab_id: TabId, cx: &Context<Self>) -> Vec<Diagnostic> {
+        if self.task_runner.problems.is_empty() {
+            return Vec::new();
+        }
+        let Some(tab) = self.tab_entity_of(tab_id, cx) else {
+            return Vec::new();
+        };
+        let Some(path) = tab
+            .read(cx)
+            .as_editor()
+            .and_then(|editor_tab| editor_tab.file_path())
+        else {
+            return Vec::new();
+        };
+        self.task_runner
+            .problems
+            .iter()
+            .filter(|problem| problem.path == *path)
+            .map(problem_diagnostic)
+            .collect()
+    }
+
+    /// Underline the problems of the last task in the active tab
+    ///
+    /// ### Description
+    /// The problems of a file are dropped on its next edit, since their
+    /// positions no longer hold.
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub(crate) fn refresh_task_diagnostics(&mut self, cx: &mut Context<Self>) {
+        let active = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.large_file)
+            .map(|editor_tab| (editor_tab.id, editor_tab.content.clone()));
+        let Some((tab_id, content)) = active else {
+            return;
+        };
+        let revision = self.task_runner.problems_revision;
+        if self.task_runner.diagnostics_applied == Some((tab_id, revision)) {
+            return;
+        }
+        self.task_runner.diagnostics_applied = Some((tab_id, revision));
+        let diagnostics = self.task_diagnostics(tab_id, cx);
+        if diagnostics.is_empty() && self.task_runner.diagnostics_shown_on != Some(tab_id) {
+            return;
+        }
+        let shown = !diagnostics.is_empty();
+        self.set_diagnostics(&content, DiagnosticSource::Task, diagnostics, cx);
+        if !shown {
+            self.task_runner.diagnostics_shown_on = None;
+            self.task_runner.diagnostics_subscription = None;
+            return;
+        }
+        self.task_runner.diagnostics_shown_on = Some(tab_id);
+        self.task_runner.diagnostics_subscription = Some(cx.subscribe(
+            &content,
+            move |this: &mut Self, _, event: &InputEvent, cx| {
+                if !matches!(event, InputEvent::Change) {
+                    return;
+                }
+                this.task_runner.diagnostics_subscription = None;
+                let Some(path) = this
+                    .tab_entity_of(tab_id, cx)
+                    .and_then(|tab| tab.read(cx).as_editor()?.file_path().cloned())
+                else {
+                    return;
+                };
+                this.task_runner
+                    .problems
+                    .retain(|problem| problem.path != path);
+                this.task_runner.problems_revision += 1;
+                cx.notify();
+            },
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_diagnostic_is_zero_based() {
+        let diagnostic = problem_diagnostic(&Problem {
+            path: PathBuf::from("/project/src/lib.rs"),
+            line: 4,
+            column: Some(9),
+            severity: ProblemSeverity::Warning,
+            message: "unused variable".to_string(),
+        });
+        assert_eq!(
+            diagnostic.range.start,
+            Position {
+                line: 3,
+                character: 8
+            }
+        );
+        assert_eq!(
+            diagnostic.range.end,
+            Position {
+                line: 3,
+                character: 9
+            }
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(diagnostic.source.as_deref(), Some(TASK_SOURCE));
+    }
+
+    #[test]
+    fn test_problem_diagnostic_without_column_starts_the_line() {
+        let diagnostic = problem_diagnostic(&Problem {
+            path: PathBuf::from("/project/test_app.py"),
+            line: 1,
+            column: None,
+            severity: ProblemSeverity::Error,
+            message: "AssertionError".to_string(),
+        });
+        assert_eq!(
+            diagnostic.range.start,
+            Position {
+                line: 0,
+                character: 0
+            }
+        );
+    }
+}
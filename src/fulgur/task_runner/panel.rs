@@ -0,0 +1,276 @@
+use super::{OutputLine, TaskStatus};
+use crate::fulgur::{
+    Fulgur,
+    ui::{icons::CustomIcon, menus::RunLastTask},
+    utils::ansi::{AnsiSpan, split_spans},
+};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    Anchor, AnyElement, App, ClickEvent, Context, Div, FontWeight, InteractiveElement, IntoElement,
+    ParentElement, SharedString, StatefulInteractiveElement, Styled, Window, div, px, rgb,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    menu::{DropdownMenu, PopupMenuItem},
+    v_flex,
+};
+use std::ops::Range;
+
+/// Height of the task panel
+const TASK_PANEL_HEIGHT: f32 = 220.0;
+
+/// Most lines of output rendered, counted from the end
+const MAX_RENDERED_LINES: usize = 1000;
+
+/// Render a run of output in its terminal style
+///
+/// ### Arguments
+/// - `span`: The styled run
+///
+/// ### Returns
+/// - `Div`: The run, colored with the terminal palette
+fn render_span(span: AnsiSpan) -> Div {
+    let style = span.style;
+    div()
+        .flex_none()
+        .child(SharedString::from(span.text))
+        .when_some(style.foreground, |this, color| {
+            this.text_color(rgb(color.to_rgb()))
+        })
+        .when_some(style.background, |this, color| this.bg(rgb(color.to_rgb())))
+        .when(style.bold, |this| this.font_weight(FontWeight::BOLD))
+        .when(style.italic, |this| this.italic())
+        .when(style.underline, |this| this.underline())
+        .when(style.dim, |this| this.opacity(0.6))
+}
+
+impl Fulgur {
+    /// Render one line of task output, its file references clickable
+    ///
+    /// ### Arguments
+    /// - `index`: The index of the line among the rendered lines
+    /// - `line`: The line
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The rendered line
+    fn render_task_line(index: usize, line: &OutputLine, cx: &mut Context<Self>) -> AnyElement {
+        let ranges: Vec<Range<usize>> = line
+            .references
+            .iter()
+            .map(|reference| reference.range.clone())
+            .collect();
+        let mut row = h_flex()
+            .id(("task-output-line", index))
+            .w_full()
+            .min_h(px(16.))
+            .whitespace_nowrap()
+            .overflow_hidden();
+        for (piece, (span, reference)) in split_spans(&line.spans, &ranges).into_iter().enumerate()
+        {
+            let element = render_span(span);
+            let Some(reference) = reference.map(|index| line.references[index].clone()) else {
+                row = row.child(element);
+                continue;
+            };
+            row = row.child(
+                element
+                    .id(("task-reference", piece))
+                    .underline()
+                    .cursor_pointer()
+                    .hover(|this| this.text_color(cx.theme().primary))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_file_reference(&reference, window, cx);
+                    })),
+            );
+        }
+        row.into_any_element()
+    }
+
+    /// Render the button listing the tasks of the active tab
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Button`: The "Run Task" button and its menu, listing the tasks resolved in the background
+    fn render_task_menu_button(&self, cx: &mut Context<Self>) -> Button {
+        let fulgur = cx.entity();
+        let context = self.active_task_context(cx);
+        let resolved = self
+            .task_runner
+            .task_list
+            .as_ref()
+            .filter(|list| context.as_ref() == Some(&list.context))
+            .and_then(|list| list.tasks.clone());
+        Button::new("task-run-menu")
+            .label("Run Task")
+            .ghost()
+            .xsmall()
+            .dropdown_menu_with_anchor(Anchor::BottomRight, move |mut menu, _window, _cx| {
+                if context.is_none() {
+                    return menu.menu_with_disabled(
+                        "Save the file to run its tasks",
+                        Box::new(RunLastTask),
+                        true,
+                    );
+                }
+                let tasks = match &resolved {
+                    None => {
+                        return menu.menu_with_disabled(
+                            "Loading tasks...",
+                            Box::new(RunLastTask),
+                            true,
+                        );
+                    }
+                    Some(Ok(tasks)) => tasks.clone(),
+                    Some(Err(e)) => {
+                        return menu.menu_with_disabled(e.clone(), Box::new(RunLastTask), true);
+                    }
+                };
+                if tasks.is_empty() {
+                    return menu.menu_with_disabled(
+                        "No tasks, add them to .fulgur/tasks.json",
+                        Box::new(RunLastTask),
+                        true,
+                    );
+                }
+                for task in tasks {
+                    let fulgur = fulgur.clone();
+                    let label = format!("{}  ({})", task.label, task.command);
+                    menu = menu.item(PopupMenuItem::new(label).on_click(
+                        move |_: &ClickEvent, window: &mut Window, cx: &mut App| {
+                            fulgur.update(cx, |this, cx| this.run_task(task.clone(), window, cx));
+                        },
+                    ));
+                }
+                menu
+            })
+    }
+
+    /// Render the header of the task panel: the status and the task controls
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Div`: The rendered header
+    fn render_task_header(&self, cx: &mut Context<Self>) -> Div {
+        let running = self.task_runner.process.is_some();
+        let status_color = match self.task_runner.status {
+            TaskStatus::Exited(Some(0))
+            | TaskStatus::Idle
+            | TaskStatus::Running
+            | TaskStatus::Stopping => cx.theme().muted_foreground,
+            _ => cx.theme().danger,
+        };
+        h_flex()
+            .w_full()
+            .flex_none()
+            .gap_1()
+            .px_2()
+            .py_0p5()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("TASKS"),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .text_xs()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .text_color(status_color)
+                    .child(self.task_summary()),
+            )
+            .child(self.render_task_menu_button(cx))
+            .when(running, |this| {
+                this.child(
+                    Button::new("task-stop")
+                        .label("Stop")
+                        .ghost()
+                        .xsmall()
+                        .on_click(cx.listener(|this, _, window, cx| this.stop_task(window, cx))),
+                )
+            })
+            .child(
+                Button::new("task-clear")
+                    .label("Clear")
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, _window, cx| this.clear_task_output(cx))),
+            )
+            .child(
+                Button::new("task-close")
+                    .icon(CustomIcon::Close)
+                    .ghost()
+                    .xsmall()
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.toggle_task_panel(window, cx)),
+                    ),
+            )
+    }
+
+    /// Lay the task panel out below the content, when it is visible
+    ///
+    /// ### Arguments
+    /// - `content`: The rendered content area
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The content with the task panel below it, or `content` unchanged when hidden
+    pub(crate) fn render_task_panel_layout(
+        &self,
+        content: AnyElement,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        if !self.task_runner.visible {
+            return content;
+        }
+        let runner = &self.task_runner;
+        let hidden = runner.lines.len().saturating_sub(MAX_RENDERED_LINES);
+        let mut output = v_flex()
+            .id("task-output")
+            .size_full()
+            .px_2()
+            .py_1()
+            .overflow_y_scroll()
+            .track_scroll(&runner.scroll_handle)
+            .text_xs()
+            .font_family(self.settings.editor_settings.font_family.clone());
+        if runner.dropped_lines + hidden > 0 {
+            output = output.child(div().text_color(cx.theme().muted_foreground).child(format!(
+                "{} earlier lines not shown",
+                runner.dropped_lines + hidden
+            )));
+        }
+        for (index, line) in runner.lines.iter().skip(hidden).enumerate() {
+            output = output.child(Self::render_task_line(index, line, cx));
+        }
+        v_flex()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .child(v_flex().w_full().flex_1().min_h_0().child(content))
+            .child(
+                v_flex()
+                    .w_full()
+                    .h(px(TASK_PANEL_HEIGHT))
+                    .flex_none()
+                    .border_t_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().tab_bar)
+                    .child(self.render_task_header(cx))
+                    .child(div().flex_1().min_h_0().child(output)),
+            )
+            .into_any_element()
+    }
+}
@@ -0,0 +1,670 @@
+mod panel;
+mod session;
+
+use crate::fulgur::{
+    Fulgur,
+    utils::{
+        task_output::find_references,
+        terminal_input::{KeyModifiers, encode_key},
+    },
+};
+use alacritty_terminal::index::Side;
+use futures::StreamExt;
+use gpui::{
+    App, AppContext, Bounds, ClipboardItem, Context, Entity, FocusHandle, KeyDownEvent, Keystroke,
+    MouseDownEvent, MouseMoveEvent, Pixels, ScrollWheelEvent, SharedString, Size, Subscription,
+    Window, px, size,
+};
+use gpui_component::{
+    WindowExt,
+    input::{InputEvent, InputState},
+    notification::NotificationType,
+};
+use session::{TerminalEvent, TerminalSession, TerminalSize};
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Most emulator events applied in one UI update
+const EVENT_BATCH: usize = 256;
+
+/// Grid size used until the panel has been laid out once
+const DEFAULT_SIZE: TerminalSize = TerminalSize {
+    columns: 80,
+    lines: 12,
+};
+
+/// Scrollback search of the active terminal
+struct TerminalSearch {
+    input: Entity<InputState>,
+    _subscription: Subscription,
+}
+
+/// A mouse press on the grid, kept until release to tell clicks from selections
+#[derive(Clone, Copy)]
+struct GridPress {
+    line: usize,
+    column: usize,
+    dragged: bool,
+}
+
+/// The terminal panel: its terminals and how they are shown
+pub struct TerminalPanel {
+    visible: bool,
+    sessions: Vec<TerminalSession>,
+    active: usize,
+    next_id: u64,
+    focus_handle: FocusHandle,
+    size: TerminalSize,      // Grid size shared by every terminal of the panel
+    cell_size: Size<Pixels>, // Measured from the editor font on each render
+    grid_bounds: Rc<Cell<Bounds<Pixels>>>, // Where the grid was last painted, to map the mouse to cells
+    press: Option<GridPress>,
+    scroll_remainder: f32, // Fraction of a line scrolled by the touchpad but not applied yet
+    search: Option<TerminalSearch>,
+}
+
+impl TerminalPanel {
+    /// Create the hidden, empty terminal panel of a window
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `TerminalPanel`: The panel, with no terminal started
+    pub fn new(cx: &mut App) -> Self {
+        Self {
+            visible: false,
+            sessions: Vec::new(),
+            active: 0,
+            next_id: 0,
+            focus_handle: cx.focus_handle(),
+            size: DEFAULT_SIZE,
+            cell_size: size(px(8.), px(18.)),
+            grid_bounds: Rc::new(Cell::new(Bounds::default())),
+            press: None,
+            scroll_remainder: 0.0,
+            search: None,
+        }
+    }
+
+    /// Get the terminal shown in the panel
+    fn active_session(&self) -> Option<&TerminalSession> {
+        self.sessions.get(self.active)
+    }
+
+    /// Get the terminal shown in the panel, mutably
+    fn active_session_mut(&mut self) -> Option<&mut TerminalSession> {
+        self.sessions.get_mut(self.active)
+    }
+
+    /// Map a mouse position to the cell under it
+    ///
+    /// ### Arguments
+    /// - `position`: The mouse position, in window coordinates
+    ///
+    /// ### Returns
+    /// - `(usize, usize, Side)`: The line and column on screen, and the half of the cell
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn cell_at(&self, position: gpui::Point<Pixels>) -> (usize, usize, Side) {
+        let origin = self.grid_bounds.get().origin;
+        let x =
+            f32::from(position.x - origin.x).max(0.0) / f32::from(self.cell_size.width).max(1.0);
+        let y =
+            f32::from(position.y - origin.y).max(0.0) / f32::from(self.cell_size.height).max(1.0);
+        let line = (y as usize).min(self.size.lines.saturating_sub(1));
+        let column = (x as usize).min(self.size.columns.saturating_sub(1));
+        let side = if x.fract() < 0.5 {
+            Side::Left
+        } else {
+            Side::Right
+        };
+        (line, column, side)
+    }
+}
+
+/// Get the home directory of the user, where terminals start without a better folder
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+impl Fulgur {
+    /// Show or hide the terminal panel, starting a terminal if there is none
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn toggle_terminal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.terminal.visible {
+            self.terminal.visible = false;
+            self.focus_active_tab(window, cx);
+        } else if self.terminal.sessions.is_empty() {
+            self.new_terminal(window, cx);
+            return;
+        } else {
+            self.terminal.visible = true;
+            window.focus(&self.terminal.focus_handle, cx);
+        }
+        cx.notify();
+    }
+
+    /// Start a terminal in the folder of the active tab and show it
+    ///
+    /// ### Description
+    /// The folder is only set when the shell starts; nothing is typed into a
+    /// running shell when another tab becomes active.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn new_terminal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let dir = self
+            .active_tab_folder(cx)
+            .or_else(home_dir)
+            .unwrap_or_else(|| PathBuf::from("/"));
+        if !self.spawn_terminal(&dir, window, cx) {
+            return;
+        }
+        self.terminal.active = self.terminal.sessions.len() - 1;
+        self.terminal.visible = true;
+        window.focus(&self.terminal.focus_handle, cx);
+        cx.notify();
+    }
+
+    /// Start the terminals of a restored window, without showing the panel
+    ///
+    /// ### Arguments
+    /// - `dirs`: The working directory of each terminal, in tab order
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn restore_terminals(
+        &mut self,
+        dirs: Vec<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for dir in dirs {
+            if dir.is_dir() {
+                self.spawn_terminal(&dir, window, cx);
+            } else {
+                log::debug!(
+                    "Not restoring a terminal in missing folder {}",
+                    dir.display()
+                );
+            }
+        }
+        cx.notify();
+    }
+
+    /// Get the working directory of each terminal, for the session state
+    ///
+    /// ### Returns
+    /// - `Vec<PathBuf>`: The current directory of each terminal, in tab order
+    pub(crate) fn terminal_dirs(&self) -> Vec<PathBuf> {
+        self.terminal
+            .sessions
+            .iter()
+            .map(TerminalSession::working_directory)
+            .collect()
+    }
+
+    /// Get the folder of the file of the active tab
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(PathBuf)`: The folder, when the active tab is saved to a local file
+    /// - `None`: The active tab has no local file
+    fn active_tab_folder(&self, cx: &App) -> Option<PathBuf> {
+        let file = self.get_active_editor_tab(cx)?.file_path()?;
+        file.parent()
+            .filter(|folder| folder.is_dir())
+            .map(Path::to_path_buf)
+    }
+
+    /// Start a shell in a new terminal and read its events
+    ///
+    /// ### Arguments
+    /// - `dir`: The working directory of the shell
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `bool`: `true` if the terminal started, `false` if the failure was reported
+    fn spawn_terminal(&mut self, dir: &Path, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let id = self.terminal.next_id;
+        self.terminal.next_id += 1;
+        let (sender, events) = futures::channel::mpsc::unbounded();
+        let mut session = match TerminalSession::spawn(id, dir, self.terminal.size, sender) {
+            Ok(session) => session,
+            Err(e) => {
+                log::error!("{e}");
+                window.push_notification(
+                    (NotificationType::Error, SharedString::from(e.to_string())),
+                    cx,
+                );
+                return false;
+            }
+        };
+        session.consumer = Some(cx.spawn_in(window, async move |view, window| {
+            let mut batches = events.ready_chunks(EVENT_BATCH);
+            while let Some(batch) = batches.next().await {
+                let applied = window.update(|window, cx| {
+                    view.update(cx, |this, cx| {
+                        this.apply_terminal_events(id, batch, window, cx)
+                    })
+                });
+                if !matches!(applied, Ok(Ok(()))) {
+                    break;
+                }
+            }
+        }));
+        self.terminal.sessions.push(session);
+        true
+    }
+
+    /// Apply the events a terminal reported since the last update
+    ///
+    /// ### Arguments
+    /// - `id`: The terminal
+    /// - `events`: The events
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn apply_terminal_events(
+        &mut self,
+        id: u64,
+        events: Vec<TerminalEvent>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(index) = self.terminal.sessions.iter().position(|s| s.id == id) else {
+            return;
+        };
+        for event in events {
+            let session = &mut self.terminal.sessions[index];
+            match event {
+                TerminalEvent::Wakeup => {}
+                TerminalEvent::Title(title) => session.set_title(title),
+                TerminalEvent::ClipboardStore(text) => {
+                    cx.write_to_clipboard(ClipboardItem::new_string(text));
+                }
+                TerminalEvent::PtyWrite(text) => session.write(text.into_bytes()),
+                TerminalEvent::Exited => {
+                    self.close_terminal(id, window, cx);
+                    return;
+                }
+            }
+        }
+        // A command may have changed the working directory the tab is named after
+        self.terminal.sessions[index].refresh_label();
+        cx.notify();
+    }
+
+    /// Close a terminal, ending its shell
+    ///
+    /// ### Arguments
+    /// - `id`: The terminal
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn close_terminal(&mut self, id: u64, window: &mut Window, cx: &mut Context<Self>) {
+        let panel = &mut self.terminal;
+        let Some(index) = panel.sessions.iter().position(|s| s.id == id) else {
+            return;
+        };
+        panel.sessions.remove(index);
+        if index < panel.active || panel.active >= panel.sessions.len() {
+            panel.active = panel.active.saturating_sub(1);
+        }
+        panel.press = None;
+        if panel.sessions.is_empty() {
+            let had_focus = panel.focus_handle.contains_focused(window, cx);
+            panel.visible = false;
+            panel.search = None;
+            if had_focus {
+                self.focus_active_tab(window, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Show another terminal of the panel
+    ///
+    /// ### Arguments
+    /// - `index`: The index of the terminal
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn activate_terminal(
+        &mut self,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if index < self.terminal.sessions.len() {
+            self.terminal.active = index;
+            self.terminal.press = None;
+            self.refresh_terminal_search(cx);
+            window.focus(&self.terminal.focus_handle, cx);
+            cx.notify();
+        }
+    }
+
+    /// Measure the grid and resize every terminal to it
+    ///
+    /// ### Description
+    /// The cell size comes from the editor font; the grid size from where the
+    /// grid was painted last, so a resized window reaches the shells one frame
+    /// later.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub(crate) fn layout_terminals(&mut self, window: &mut Window) {
+        if !self.terminal.visible {
+            return;
+        }
+        let font_size = px(self.settings.editor_settings.font_size);
+        let font = gpui::font(self.settings.editor_settings.font_family.clone());
+        let text_system = window.text_system();
+        let font_id = text_system.resolve_font(&font);
+        let cell_width = text_system
+            .advance(font_id, font_size, 'm')
+            .map_or(font_size * 0.6, |advance| advance.width);
+        let cell_height = (font_size * panel::LINE_HEIGHT_FACTOR).round();
+        let panel = &mut self.terminal;
+        panel.cell_size = size(cell_width, cell_height);
+        let bounds = panel.grid_bounds.get().size;
+        if bounds.width <= px(0.) || bounds.height <= px(0.) {
+            return;
+        }
+        let columns = (f32::from(bounds.width) / f32::from(cell_width).max(1.0)) as usize;
+        let lines = (f32::from(bounds.height) / f32::from(cell_height).max(1.0)) as usize;
+        panel.size = TerminalSize {
+            columns: columns.max(2),
+            lines: lines.max(1),
+        };
+        let (width, height) = (
+            f32::from(cell_width).round() as u16,
+            f32::from(cell_height) as u16,
+        );
+        for session in &mut panel.sessions {
+            session.resize(panel.size, width, height);
+        }
+    }
+
+    /// Send a key press to the active terminal
+    ///
+    /// ### Arguments
+    /// - `keystroke`: The key press
+    /// - `cx`: The application context
+    fn send_terminal_keystroke(&mut self, keystroke: &Keystroke, cx: &mut Context<Self>) {
+        let Some(session) = self.terminal.active_session_mut() else {
+            return;
+        };
+        let modifiers = KeyModifiers {
+            control: keystroke.modifiers.control,
+            alt: keystroke.modifiers.alt,
+            shift: keystroke.modifiers.shift,
+        };
+        let bytes = encode_key(
+            &keystroke.key,
+            keystroke.key_char.as_deref(),
+            modifiers,
+            session.application_cursor(),
+        );
+        if let Some(bytes) = bytes {
+            session.input(bytes);
+            cx.notify();
+        }
+    }
+
+    /// Send a keystroke bound by the application to the active terminal instead
+    ///
+    /// ### Arguments
+    /// - `keystroke`: The keystroke, e.g. `ctrl-w`
+    /// - `cx`: The application context
+    pub(crate) fn send_terminal_key(&mut self, keystroke: &str, cx: &mut Context<Self>) {
+        match Keystroke::parse(keystroke) {
+            Ok(keystroke) => self.send_terminal_keystroke(&keystroke, cx),
+            Err(e) => log::warn!("Failed to parse terminal keystroke '{keystroke}': {e}"),
+        }
+    }
+
+    /// Handle a key press in the terminal grid
+    ///
+    /// ### Description
+    /// Shift-Page Up and Down scroll through the scrollback; every other key
+    /// without a binding goes to the shell.
+    ///
+    /// ### Arguments
+    /// - `event`: The key press
+    /// - `cx`: The application context
+    fn terminal_key_down(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        let modifiers = &keystroke.modifiers;
+        if modifiers.platform || modifiers.function {
+            return;
+        }
+        let scroll_page = modifiers.shift && !modifiers.control && !modifiers.alt;
+        match keystroke.key.as_str() {
+            "pageup" | "pagedown" if scroll_page => {
+                if let Some(session) = self.terminal.active_session() {
+                    session.scroll_page(keystroke.key == "pageup");
+                }
+                cx.notify();
+            }
+            _ => self.send_terminal_keystroke(keystroke, cx),
+        }
+        cx.stop_propagation();
+    }
+
+    /// Copy the selection of the active terminal to the clipboard
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub(crate) fn copy_terminal_selection(&mut self, cx: &mut Context<Self>) {
+        if let Some(text) = self
+            .terminal
+            .active_session()
+            .and_then(TerminalSession::selected_text)
+        {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
+
+    /// Paste the clipboard text into the active terminal
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub(crate) fn paste_into_terminal(&mut self, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        if let Some(session) = self.terminal.active_session_mut() {
+            session.paste(&text);
+            cx.notify();
+        }
+    }
+
+    /// Start a selection, or a click, where the mouse was pressed on the grid
+    ///
+    /// ### Arguments
+    /// - `event`: The mouse press
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn terminal_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        window.focus(&self.terminal.focus_handle, cx);
+        let (line, column, side) = self.terminal.cell_at(event.position);
+        if let Some(session) = self.terminal.active_session() {
+            session.start_selection(session.point_at(line, column), side);
+        }
+        self.terminal.press = Some(GridPress {
+            line,
+            column,
+            dragged: false,
+        });
+        cx.notify();
+    }
+
+    /// Extend the selection while the mouse is dragged over the grid
+    ///
+    /// ### Arguments
+    /// - `event`: The mouse move
+    /// - `cx`: The application context
+    fn terminal_mouse_move(&mut self, event: &MouseMoveEvent, cx: &mut Context<Self>) {
+        let Some(press) = self.terminal.press else {
+            return;
+        };
+        if !event.dragging() {
+            return;
+        }
+        let (line, column, side) = self.terminal.cell_at(event.position);
+        if !press.dragged && (line, column) == (press.line, press.column) {
+            return;
+        }
+        if let Some(session) = self.terminal.active_session() {
+            session.update_selection(session.point_at(line, column), side);
+        }
+        self.terminal.press = Some(GridPress {
+            dragged: true,
+            ..press
+        });
+        cx.notify();
+    }
+
+    /// Finish a selection, or open the `path:line` reference under a click
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn terminal_mouse_up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(press) = self.terminal.press.take() else {
+            return;
+        };
+        if press.dragged {
+            return;
+        }
+        let Some(session) = self.terminal.active_session() else {
+            return;
+        };
+        session.clear_selection();
+        let point = session.point_at(press.line, press.column);
+        let (text, offsets) = session.line_text(point.line);
+        let Some(&offset) = offsets.get(press.column) else {
+            return;
+        };
+        let cwd = session.working_directory();
+        let reference = find_references(&text, &cwd)
+            .into_iter()
+            .find(|reference| reference.range.contains(&offset) && reference.path.is_file());
+        if let Some(reference) = reference {
+            self.open_file_reference(&reference, window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Scroll through the scrollback with the mouse wheel or touchpad
+    ///
+    /// ### Arguments
+    /// - `event`: The scroll wheel event
+    /// - `cx`: The application context
+    #[allow(clippy::cast_possible_truncation)]
+    fn terminal_scroll(&mut self, event: &ScrollWheelEvent, cx: &mut Context<Self>) {
+        let line_height = self.terminal.cell_size.height;
+        let delta = event.delta.pixel_delta(line_height);
+        let lines = self.terminal.scroll_remainder + f32::from(delta.y) / f32::from(line_height);
+        let whole = lines.trunc();
+        self.terminal.scroll_remainder = lines - whole;
+        if whole == 0.0 {
+            return;
+        }
+        if let Some(session) = self.terminal.active_session_mut() {
+            session.scroll(whole as i32);
+            cx.notify();
+        }
+    }
+
+    /// Show the scrollback search of the terminal panel
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn open_terminal_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(search) = &self.terminal.search {
+            search.input.update(cx, |input, cx| input.focus(window, cx));
+            return;
+        }
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Find in terminal"));
+        let subscription = cx.subscribe_in(
+            &input,
+            window,
+            |this: &mut Self, _, event: &InputEvent, _window, cx| match event {
+                InputEvent::Change => this.refresh_terminal_search(cx),
+                InputEvent::PressEnter { secondary } => this.step_terminal_search(!secondary, cx),
+                _ => {}
+            },
+        );
+        input.update(cx, |input, cx| input.focus(window, cx));
+        self.terminal.search = Some(TerminalSearch {
+            input,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    /// Hide the scrollback search and give the focus back to the grid
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn close_terminal_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.terminal.search = None;
+        if let Some(session) = self.terminal.active_session_mut() {
+            session.search("");
+            session.clear_selection();
+        }
+        window.focus(&self.terminal.focus_handle, cx);
+        cx.notify();
+    }
+
+    /// Search the active terminal for the text of the search field
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    fn refresh_terminal_search(&mut self, cx: &mut Context<Self>) {
+        let query = self
+            .terminal
+            .search
+            .as_ref()
+            .map(|search| search.input.read(cx).value().to_string())
+            .unwrap_or_default();
+        if let Some(session) = self.terminal.active_session_mut() {
+            session.search(&query);
+        }
+        cx.notify();
+    }
+
+    /// Move to the next match of the scrollback search
+    ///
+    /// ### Arguments
+    /// - `older`: Whether to move towards older output
+    /// - `cx`: The application context
+    pub(crate) fn step_terminal_search(&mut self, older: bool, cx: &mut Context<Self>) {
+        if let Some(session) = self.terminal.active_session_mut() {
+            session.step_search(older);
+            cx.notify();
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use super::session::{CellColor, CellRun, CellStyle, TerminalSession};
+use crate::fulgur::{
+    Fulgur,
+    ui::{
+        icons::CustomIcon,
+        menus::{
+            CopyTerminalSelection, FindInTerminal, KEY_CONTEXT_TERMINAL, PasteIntoTerminal,
+            SendTerminalKey,
+        },
+    },
+};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, Context, Div, FontWeight, Hsla, InteractiveElement, IntoElement, MouseButton,
+    ParentElement, SharedString, StatefulInteractiveElement, Styled, Window, canvas, div, px, rgb,
+};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::Input,
+    v_flex,
+};
+
+/// Height of the terminal panel
+const TERMINAL_PANEL_HEIGHT: f32 = 260.0;
+
+/// Line height of the grid, relative to the font size
+pub(super) const LINE_HEIGHT_FACTOR: f32 = 1.3;
+
+/// Resolve the color of a cell against the theme
+///
+/// ### Arguments
+/// - `color`: The color of the cell
+/// - `default`: The theme color used for the default color
+///
+/// ### Returns
+/// - `Hsla`: The color to paint
+fn cell_color(color: CellColor, default: Hsla) -> Hsla {
+    match color {
+        CellColor::Rgb(value) => rgb(value).into(),
+        CellColor::Foreground | CellColor::Background => default,
+    }
+}
+
+/// Render a run of cells in its terminal style
+///
+/// ### Arguments
+/// - `run`: The run of cells
+/// - `cx`: The application context
+///
+/// ### Returns
+/// - `Div`: The run, colored with the terminal palette over the theme
+fn render_run(run: CellRun, cx: &Context<Fulgur>) -> Div {
+    let CellStyle {
+        foreground,
+        background,
+        ..
+    } = run.style;
+    let theme = cx.theme();
+    let background = if run.style.selected {
+        Some(theme.selection)
+    } else if run.style.matched {
+        Some(theme.warning.opacity(0.4))
+    } else {
+        (background != CellColor::Background).then(|| cell_color(background, theme.background))
+    };
+    div()
+        .flex_none()
+        .child(SharedString::from(run.text))
+        .text_color(cell_color(foreground, theme.foreground))
+        .when_some(background, |this, color| this.bg(color))
+        .when(run.style.bold, |this| this.font_weight(FontWeight::BOLD))
+        .when(run.style.italic, |this| this.italic())
+        .when(run.style.underline, |this| this.underline())
+        .when(run.style.strikeout, |this| this.line_through())
+        .when(run.style.dim, |this| this.opacity(0.6))
+}
+
+impl Fulgur {
+    /// Render the header of the terminal panel: a tab per terminal and the panel controls
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Div`: The rendered header
+    fn render_terminal_header(&self, cx: &mut Context<Self>) -> Div {
+        let mut tabs = h_flex().flex_1().min_w_0().gap_1().overflow_hidden();
+        for (index, session) in self.terminal.sessions.iter().enumerate() {
+            let id = session.id;
+            let active = index == self.terminal.active;
+            tabs = tabs.child(
+                h_flex()
+                    .id(("terminal-tab", id))
+                    .flex_none()
+                    .gap_1()
+                    .pl_2()
+                    .rounded_sm()
+                    .text_xs()
+                    .cursor_pointer()
+                    .when(active, |this| {
+                        this.bg(cx.theme().tab_active)
+                            .text_color(cx.theme().tab_active_foreground)
+                    })
+                    .when(!active, |this| {
+                        this.text_color(cx.theme().tab_foreground)
+                            .hover(|this| this.bg(cx.theme().muted))
+                    })
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.activate_terminal(index, window, cx);
+                    }))
+                    .child(session.label())
+                    .child(
+                        Button::new(("terminal-tab-close", id))
+                            .icon(CustomIcon::Close)
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                cx.stop_propagation();
+                                this.close_terminal(id, window, cx);
+                            })),
+                    ),
+            );
+        }
+        h_flex()
+            .w_full()
+            .flex_none()
+            .gap_1()
+            .px_2()
+            .py_0p5()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .flex_none()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("TERMINAL"),
+            )
+            .child(tabs)
+            .child(
+                Button::new("terminal-new")
+                    .label("New")
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, window, cx| this.new_terminal(window, cx))),
+            )
+            .child(
+                Button::new("terminal-search")
+                    .label("Search")
+                    .ghost()
+                    .xsmall()
+                    .on_click(
+                        cx.listener(|this, _, window, cx| this.open_terminal_search(window, cx)),
+                    ),
+            )
+            .child(
+                Button::new("terminal-close")
+                    .icon(CustomIcon::Close)
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, window, cx| this.toggle_terminal(window, cx))),
+            )
+    }
+
+    /// Render the scrollback search row, when the search is open
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Option<Div>`: The search field and the match count, or `None` when closed
+    fn render_terminal_search(&self, cx: &mut Context<Self>) -> Option<Div> {
+        let search = self.terminal.search.as_ref()?;
+        let status = self
+            .terminal
+            .active_session()
+            .map_or_else(String::new, |session| {
+                match (session.search_index, session.search_matches.len()) {
+                    (_, 0) => "No results".to_string(),
+                    (Some(index), count) => format!("{}/{count}", count - index),
+                    (None, count) => format!("{count} matches"),
+                }
+            });
+        Some(
+            h_flex()
+                .w_full()
+                .flex_none()
+                .gap_1()
+                .px_2()
+                .py_0p5()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .on_key_down(cx.listener(|this, event: &gpui::KeyDownEvent, window, cx| {
+                    if event.keystroke.key == "escape" {
+                        this.close_terminal_search(window, cx);
+                        cx.stop_propagation();
+                    }
+                }))
+                .child(div().w(px(240.)).child(Input::new(&search.input).xsmall()))
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(status),
+                )
+                .child(
+                    Button::new("terminal-search-older")
+                        .label("Previous")
+                        .ghost()
+                        .xsmall()
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.step_terminal_search(true, cx);
+                        })),
+                )
+                .child(
+                    Button::new("terminal-search-newer")
+                        .label("Next")
+                        .ghost()
+                        .xsmall()
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.step_terminal_search(false, cx);
+                        })),
+                )
+                .child(
+                    Button::new("terminal-search-close")
+                        .icon(CustomIcon::Close)
+                        .ghost()
+                        .xsmall()
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.close_terminal_search(window, cx);
+                        })),
+                ),
+        )
+    }
+
+    /// Render the grid of the active terminal
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The grid, which takes the keyboard and mouse input of the terminal
+    fn render_terminal_grid(&self, window: &Window, cx: &mut Context<Self>) -> AnyElement {
+        let panel = &self.terminal;
+        let font_size = px(self.settings.editor_settings.font_size);
+        let line_height = panel.cell_size.height;
+        let snapshot = panel.active_session().map(TerminalSession::snapshot);
+        let focused = panel.focus_handle.contains_focused(window, cx);
+        let mut grid = div()
+            .id("terminal-grid")
+            .key_context(KEY_CONTEXT_TERMINAL)
+            .track_focus(&panel.focus_handle)
+            .relative()
+            .size_full()
+            .overflow_hidden()
+            .bg(cx.theme().background)
+            .font_family(self.settings.editor_settings.font_family.clone())
+            .text_size(font_size)
+            .line_height(line_height)
+            .cursor_text()
+            .on_key_down(cx.listener(|this, event, _window, cx| {
+                this.terminal_key_down(event, cx);
+            }))
+            .on_action(cx.listener(|this, action: &SendTerminalKey, _window, cx| {
+                this.send_terminal_key(&action.0, cx);
+            }))
+            .on_action(cx.listener(|this, _: &CopyTerminalSelection, _window, cx| {
+                this.copy_terminal_selection(cx);
+            }))
+            .on_action(cx.listener(|this, _: &PasteIntoTerminal, _window, cx| {
+                this.paste_into_terminal(cx);
+            }))
+            .on_action(cx.listener(|this, _: &FindInTerminal, window, cx| {
+                this.open_terminal_search(window, cx);
+            }))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, event, window, cx| this.terminal_mouse_down(event, window, cx)),
+            )
+            .on_mouse_move(cx.listener(|this, event, _window, cx| {
+                this.terminal_mouse_move(event, cx);
+            }))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, window, cx| this.terminal_mouse_up(window, cx)),
+            )
+            .on_scroll_wheel(cx.listener(|this, event, _window, cx| {
+                this.terminal_scroll(event, cx);
+            }));
+        let grid_bounds = panel.grid_bounds.clone();
+        let rows = div().absolute().top_0().left_0().size_full().child(
+            canvas(
+                move |bounds, _window, _cx| grid_bounds.set(bounds),
+                |_, (), _window, _cx| {},
+            )
+            .size_full(),
+        );
+        grid = grid.child(rows);
+        let Some(snapshot) = snapshot else {
+            return grid.into_any_element();
+        };
+        for runs in snapshot.lines {
+            let mut row = h_flex().h(line_height).whitespace_nowrap();
+            for run in runs {
+                row = row.child(render_run(run, cx));
+            }
+            grid = grid.child(row);
+        }
+        if let Some((line, column)) = snapshot.cursor {
+            let cell = panel.cell_size;
+            #[allow(clippy::cast_precision_loss)]
+            let (x, y) = (cell.width * column as f32, cell.height * line as f32);
+            let cursor = div()
+                .absolute()
+                .left(x)
+                .top(y)
+                .w(cell.width)
+                .h(cell.height)
+                .when(focused, |this| this.bg(cx.theme().caret.opacity(0.6)))
+                .when(!focused, |this| {
+                    this.border_1().border_color(cx.theme().caret)
+                });
+            grid = grid.child(cursor);
+        }
+        grid.into_any_element()
+    }
+
+    /// Lay the terminal panel out below the content, when it is visible
+    ///
+    /// ### Arguments
+    /// - `content`: The rendered content area
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The content with the terminal panel below it, or `content` unchanged when hidden
+    pub(crate) fn render_terminal_panel_layout(
+        &self,
+        content: AnyElement,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        if !self.terminal.visible {
+            return content;
+        }
+        v_flex()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .child(v_flex().w_full().flex_1().min_h_0().child(content))
+            .child(
+                v_flex()
+                    .w_full()
+                    .h(px(TERMINAL_PANEL_HEIGHT))
+                    .flex_none()
+                    .border_t_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().tab_bar)
+                    .child(self.render_terminal_header(cx))
+                    .children(self.render_terminal_search(cx))
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_h_0()
+                            .child(self.render_terminal_grid(window, cx)),
+                    ),
+            )
+            .into_any_element()
+    }
+}
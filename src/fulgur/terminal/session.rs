@@ -0,0 +1,603 @@
+use crate::fulgur::utils::{ansi::AnsiColor, terminal_input::encode_paste};
+use alacritty_terminal::{
+    Term,
+    event::{Event, EventListener, Notify, OnResize, WindowSize},
+    event_loop::{EventLoop, Notifier},
+    grid::{Dimensions, Scroll},
+    index::{Column, Direction, Line, Point, Side},
+    selection::{Selection, SelectionType},
+    sync::FairMutex,
+    term::{
+        Config, TermMode,
+        cell::Flags,
+        color::Colors,
+        search::{Match, RegexIter, RegexSearch},
+    },
+    tty,
+    vte::ansi::{Color, CursorShape, NamedColor, Rgb},
+};
+use anyhow::anyhow;
+use futures::channel::mpsc::UnboundedSender;
+use gpui::{SharedString, Task};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Lines of scrollback kept by each terminal
+const SCROLLBACK_LINES: usize = 10_000;
+
+/// Most matches collected by a scrollback search
+const MAX_SEARCH_MATCHES: usize = 1000;
+
+/// What a terminal reports to the panel showing it
+#[derive(Debug)]
+pub(super) enum TerminalEvent {
+    Wakeup,                 // The screen changed
+    Title(Option<String>),  // The shell set the title, or reset it
+    ClipboardStore(String), // An application asked to copy text
+    PtyWrite(String),       // The emulator answers a query of the application
+    Exited,                 // The shell exited
+}
+
+/// Forwards the events of the emulator to the panel
+#[derive(Clone)]
+pub(super) struct EventProxy(UnboundedSender<TerminalEvent>);
+
+impl EventListener for EventProxy {
+    fn send_event(&self, event: Event) {
+        let event = match event {
+            Event::Wakeup => TerminalEvent::Wakeup,
+            Event::Title(title) => TerminalEvent::Title(Some(title)),
+            Event::ResetTitle => TerminalEvent::Title(None),
+            Event::ClipboardStore(_, text) => TerminalEvent::ClipboardStore(text),
+            Event::PtyWrite(text) => TerminalEvent::PtyWrite(text),
+            Event::Exit | Event::ChildExit(_) => TerminalEvent::Exited,
+            _ => return,
+        };
+        // The receiver is gone once the terminal was closed
+        let _ = self.0.unbounded_send(event);
+    }
+}
+
+/// Size of a terminal grid, in cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct TerminalSize {
+    pub columns: usize,
+    pub lines: usize,
+}
+
+impl Dimensions for TerminalSize {
+    fn total_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn screen_lines(&self) -> usize {
+        self.lines
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+}
+
+impl TerminalSize {
+    /// Get the size in the form the PTY is told about
+    ///
+    /// ### Arguments
+    /// - `cell_width`: The width of a cell, in pixels
+    /// - `cell_height`: The height of a cell, in pixels
+    ///
+    /// ### Returns
+    /// - `WindowSize`: The size of the grid in cells and of each cell in pixels
+    fn window_size(self, cell_width: u16, cell_height: u16) -> WindowSize {
+        WindowSize {
+            num_lines: u16::try_from(self.lines).unwrap_or(u16::MAX),
+            num_cols: u16::try_from(self.columns).unwrap_or(u16::MAX),
+            cell_width,
+            cell_height,
+        }
+    }
+}
+
+/// A color of a cell, resolved except for the theme colors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum CellColor {
+    Foreground, // The default text color of the theme
+    Background, // The background color of the theme
+    Rgb(u32),
+}
+
+/// The style of a cell, as rendered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub(super) struct CellStyle {
+    pub foreground: CellColor,
+    pub background: CellColor,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikeout: bool,
+    pub dim: bool,
+    pub selected: bool,
+    pub matched: bool, // Part of a scrollback search match
+}
+
+/// A run of cells of one line sharing one style
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct CellRun {
+    pub text: String,
+    pub style: CellStyle,
+}
+
+/// The visible part of a terminal, ready to render
+pub(super) struct TerminalSnapshot {
+    pub lines: Vec<Vec<CellRun>>,
+    pub cursor: Option<(usize, usize)>, // Line and column of the cursor, when it is shown
+}
+
+/// Pack a color of the emulator into `0xRRGGBB`
+fn pack(rgb: Rgb) -> u32 {
+    (u32::from(rgb.r) << 16) | (u32::from(rgb.g) << 8) | u32::from(rgb.b)
+}
+
+/// Resolve a cell color against the palette of the terminal
+///
+/// ### Arguments
+/// - `color`: The color of the cell
+/// - `colors`: The palette entries changed by the application, if any
+///
+/// ### Returns
+/// - `CellColor`: The color, the default foreground and background left to the theme
+fn resolve_color(color: Color, colors: &Colors) -> CellColor {
+    let indexed = |index: usize| {
+        colors[index].map_or_else(
+            || CellColor::Rgb(AnsiColor::Indexed(u8::try_from(index).unwrap_or_default()).to_rgb()),
+            |rgb| CellColor::Rgb(pack(rgb)),
+        )
+    };
+    match color {
+        Color::Spec(rgb) => CellColor::Rgb(pack(rgb)),
+        Color::Indexed(index) => indexed(usize::from(index)),
+        Color::Named(NamedColor::Background) => CellColor::Background,
+        Color::Named(named) => {
+            let index = named as usize;
+            let dim = NamedColor::DimBlack as usize..=NamedColor::DimWhite as usize;
+            if index < 16 {
+                indexed(index)
+            } else if dim.contains(&index) {
+                indexed(index - NamedColor::DimBlack as usize)
+            } else {
+                CellColor::Foreground
+            }
+        }
+    }
+}
+
+/// Get the working directory of a process
+///
+/// ### Arguments
+/// - `pid`: The process
+///
+/// ### Returns
+/// - `Some(PathBuf)`: Its working directory
+/// - `None`: The process is gone or not readable
+fn process_cwd(pid: u32) -> Option<PathBuf> {
+    fs::read_link(format!("/proc/{pid}/cwd")).ok()
+}
+
+/// Name a terminal tab after a directory
+///
+/// ### Arguments
+/// - `dir`: The working directory of the shell
+///
+/// ### Returns
+/// - `SharedString`: The last component of the directory, or the whole path for `/`
+fn directory_label(dir: &Path) -> SharedString {
+    dir.file_name().map_or_else(
+        || SharedString::from(dir.display().to_string()),
+        |name| SharedString::from(name.to_string_lossy().into_owned()),
+    )
+}
+
+/// One terminal: a shell running in a PTY and the emulator reading it
+pub(super) struct TerminalSession {
+    pub id: u64,
+    title: Option<String>, // Set by the shell through an escape sequence
+    label: SharedString,   // Label of the terminal tab, refreshed as the shell reports events
+    start_dir: PathBuf,
+    term: Arc<FairMutex<Term<EventProxy>>>,
+    notifier: Notifier, // Shuts the PTY down when dropped
+    shell_pid: u32,
+    size: TerminalSize,
+    pub search_matches: Vec<Match>,
+    pub search_index: Option<usize>,
+    #[allow(
+        dead_code,
+        reason = "RAII guard: dropping the task stops reading the events"
+    )]
+    pub consumer: Option<Task<()>>, // Applies the events of the emulator to the panel
+}
+
+impl TerminalSession {
+    /// Start the login shell of the user in a new PTY
+    ///
+    /// ### Arguments
+    /// - `id`: The identity of the terminal within its window
+    /// - `dir`: The working directory of the shell
+    /// - `size`: The size of the grid
+    /// - `events`: The channel receiving the events of the emulator
+    ///
+    /// ### Errors
+    /// Returns an error if the PTY or the shell cannot be started.
+    ///
+    /// ### Returns
+    /// - `Ok(TerminalSession)`: The running terminal
+    pub fn spawn(
+        id: u64,
+        dir: &Path,
+        size: TerminalSize,
+        events: UnboundedSender<TerminalEvent>,
+    ) -> anyhow::Result<Self> {
+        let options = tty::Options {
+            working_directory: Some(dir.to_path_buf()),
+            drain_on_exit: true,
+            env: HashMap::from([
+                ("TERM".to_string(), "xterm-256color".to_string()),
+                ("COLORTERM".to_string(), "truecolor".to_string()),
+                ("TERM_PROGRAM".to_string(), "Fulgur".to_string()),
+            ]),
+            ..tty::Options::default()
+        };
+        let proxy = EventProxy(events);
+        let config = Config {
+            scrolling_history: SCROLLBACK_LINES,
+            ..Config::default()
+        };
+        let term = Arc::new(FairMutex::new(Term::new(config, &size, proxy.clone())));
+        let pty = tty::new(&options, size.window_size(0, 0), id)
+            .map_err(|e| anyhow!("Failed to start a shell in {}: {e}", dir.display()))?;
+        let shell_pid = pty.child().id();
+        let event_loop = EventLoop::new(Arc::clone(&term), proxy, pty, true, false)
+            .map_err(|e| anyhow!("Failed to start the terminal: {e}"))?;
+        let notifier = Notifier(event_loop.channel());
+        event_loop.spawn();
+        Ok(Self {
+            id,
+            title: None,
+            label: directory_label(dir),
+            start_dir: dir.to_path_buf(),
+            term,
+            notifier,
+            shell_pid,
+            size,
+            search_matches: Vec::new(),
+            search_index: None,
+            consumer: None,
+        })
+    }
+
+    /// Get the current working directory of the shell
+    ///
+    /// ### Returns
+    /// - `PathBuf`: The directory of the shell, or the one it started in if unreadable
+    pub fn working_directory(&self) -> PathBuf {
+        process_cwd(self.shell_pid).unwrap_or_else(|| self.start_dir.clone())
+    }
+
+    /// Get the label of the terminal tab
+    ///
+    /// ### Returns
+    /// - `SharedString`: The title set by the shell, or the name of its working directory
+    pub fn label(&self) -> SharedString {
+        self.label.clone()
+    }
+
+    /// Keep the title the shell set, or reset it
+    ///
+    /// ### Arguments
+    /// - `title`: The new title, `None` to go back to the working directory
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.title = title.filter(|title| !title.is_empty());
+        self.refresh_label();
+    }
+
+    /// Compute the label of the terminal tab again
+    ///
+    /// ### Description
+    /// Called once per batch of events rather than on each render, since the
+    /// working directory is read from `/proc`.
+    pub fn refresh_label(&mut self) {
+        self.label = match &self.title {
+            Some(title) => SharedString::from(title.clone()),
+            None => directory_label(&self.working_directory()),
+        };
+    }
+
+    /// Check whether the application asked for application cursor keys
+    pub fn application_cursor(&self) -> bool {
+        self.term.lock().mode().contains(TermMode::APP_CURSOR)
+    }
+
+    /// Write bytes to the shell, as produced by the emulator itself
+    ///
+    /// ### Arguments
+    /// - `bytes`: The bytes to write
+    pub fn write(&self, bytes: Vec<u8>) {
+        self.notifier.notify(bytes);
+    }
+
+    /// Write typed input to the shell, scrolling back to the prompt
+    ///
+    /// ### Arguments
+    /// - `bytes`: The encoded key presses
+    pub fn input(&mut self, bytes: Vec<u8>) {
+        {
+            let mut term = self.term.lock();
+            term.scroll_display(Scroll::Bottom);
+            term.selection = None;
+        }
+        self.write(bytes);
+    }
+
+    /// Paste text into the shell
+    ///
+    /// ### Arguments
+    /// - `text`: The clipboard text
+    pub fn paste(&mut self, text: &str) {
+        let bracketed = self.term.lock().mode().contains(TermMode::BRACKETED_PASTE);
+        self.input(encode_paste(text, bracketed));
+    }
+
+    /// Resize the grid and tell the shell about it
+    ///
+    /// ### Arguments
+    /// - `size`: The new size of the grid
+    /// - `cell_width`: The width of a cell, in pixels
+    /// - `cell_height`: The height of a cell, in pixels
+    pub fn resize(&mut self, size: TerminalSize, cell_width: u16, cell_height: u16) {
+        if size == self.size {
+            return;
+        }
+        self.size = size;
+        self.term.lock().resize(size);
+        self.notifier
+            .on_resize(size.window_size(cell_width, cell_height));
+    }
+
+    /// Scroll through the scrollback
+    ///
+    /// ### Arguments
+    /// - `lines`: The number of lines to scroll, positive towards older output
+    pub fn scroll(&self, lines: i32) {
+        self.term.lock().scroll_display(Scroll::Delta(lines));
+    }
+
+    /// Scroll through the scrollback by one screen
+    ///
+    /// ### Arguments
+    /// - `up`: Whether to scroll towards older output
+    pub fn scroll_page(&self, up: bool) {
+        let scroll = if up { Scroll::PageUp } else { Scroll::PageDown };
+        self.term.lock().scroll_display(scroll);
+    }
+
+    /// Convert a position on screen to a point of the grid
+    ///
+    /// ### Arguments
+    /// - `line`: The line on screen, from the top
+    /// - `column`: The column on screen
+    ///
+    /// ### Returns
+    /// - `Point`: The point, accounting for the scrollback shown
+    pub fn point_at(&self, line: usize, column: usize) -> Point {
+        let offset = self.term.lock().grid().display_offset();
+        let line = i32::try_from(line).unwrap_or(i32::MAX);
+        let offset = i32::try_from(offset).unwrap_or(i32::MAX);
+        Point::new(
+            Line(line.saturating_sub(offset)),
+            Column(column.min(self.size.columns.saturating_sub(1))),
+        )
+    }
+
+    /// Start selecting text at a point
+    ///
+    /// ### Arguments
+    /// - `point`: Where the mouse was pressed
+    /// - `side`: The half of the cell that was pressed
+    pub fn start_selection(&self, point: Point, side: Side) {
+        self.term.lock().selection = Some(Selection::new(SelectionType::Simple, point, side));
+    }
+
+    /// Extend the selection to a point
+    ///
+    /// ### Arguments
+    /// - `point`: Where the mouse is
+    /// - `side`: The half of the cell under the mouse
+    pub fn update_selection(&self, point: Point, side: Side) {
+        if let Some(selection) = self.term.lock().selection.as_mut() {
+            selection.update(point, side);
+        }
+    }
+
+    /// Remove the selection
+    pub fn clear_selection(&self) {
+        self.term.lock().selection = None;
+    }
+
+    /// Get the selected text
+    ///
+    /// ### Returns
+    /// - `Some(String)`: The text, when something is selected
+    /// - `None`: Nothing is selected
+    pub fn selected_text(&self) -> Option<String> {
+        let term = self.term.lock();
+        let selection = term.selection.as_ref()?;
+        if selection.is_empty() {
+            return None;
+        }
+        term.selection_to_string().filter(|text| !text.is_empty())
+    }
+
+    /// Get the text of a line of the grid
+    ///
+    /// ### Arguments
+    /// - `line`: The line
+    ///
+    /// ### Returns
+    /// - `(String, Vec<usize>)`: The text, and the byte offset of each column in it
+    pub fn line_text(&self, line: Line) -> (String, Vec<usize>) {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let mut text = String::new();
+        let mut offsets = Vec::with_capacity(grid.columns());
+        if line < grid.topmost_line() || line > grid.bottommost_line() {
+            return (text, offsets);
+        }
+        let row = &grid[line];
+        for column in 0..grid.columns() {
+            offsets.push(text.len());
+            let cell = &row[Column(column)];
+            if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                text.push(cell.c);
+            }
+        }
+        (text, offsets)
+    }
+
+    /// Find every match of a text in the scrollback and the screen
+    ///
+    /// ### Arguments
+    /// - `query`: The text to find, matched case-insensitively
+    ///
+    /// ### Returns
+    /// - `usize`: The number of matches, the last one being selected
+    pub fn search(&mut self, query: &str) -> usize {
+        self.search_matches.clear();
+        self.search_index = None;
+        if query.is_empty() {
+            return 0;
+        }
+        let pattern = format!("(?i){}", regex::escape(query));
+        let Ok(mut regex) = RegexSearch::new(&pattern) else {
+            log::warn!("Failed to build the terminal search for '{query}'");
+            return 0;
+        };
+        {
+            let term = self.term.lock();
+            let grid = term.grid();
+            let start = Point::new(grid.topmost_line(), Column(0));
+            let end = Point::new(grid.bottommost_line(), grid.last_column());
+            let mut matches: Vec<Match> =
+                RegexIter::new(start, end, Direction::Right, &term, &mut regex).collect();
+            if matches.len() > MAX_SEARCH_MATCHES {
+                matches.drain(..matches.len() - MAX_SEARCH_MATCHES);
+            }
+            self.search_matches = matches;
+        }
+        let count = self.search_matches.len();
+        if count > 0 {
+            self.select_match(count - 1);
+        }
+        count
+    }
+
+    /// Move to the next or previous search match
+    ///
+    /// ### Arguments
+    /// - `older`: Whether to move towards older output
+    pub fn step_search(&mut self, older: bool) {
+        let count = self.search_matches.len();
+        if count == 0 {
+            return;
+        }
+        let index = match self.search_index {
+            Some(index) if older => (index + count - 1) % count,
+            Some(index) => (index + 1) % count,
+            None => count - 1,
+        };
+        self.select_match(index);
+    }
+
+    /// Scroll a search match into view and select it
+    ///
+    /// ### Arguments
+    /// - `index`: The index of the match
+    fn select_match(&mut self, index: usize) {
+        let Some(found) = self.search_matches.get(index) else {
+            return;
+        };
+        self.search_index = Some(index);
+        let mut term = self.term.lock();
+        let mut selection = Selection::new(SelectionType::Simple, *found.start(), Side::Left);
+        selection.update(*found.end(), Side::Right);
+        term.selection = Some(selection);
+        term.scroll_to_point(*found.start());
+    }
+
+    /// Capture the visible part of the terminal
+    ///
+    /// ### Returns
+    /// - `TerminalSnapshot`: The visible lines as styled runs, and the cursor
+    pub fn snapshot(&self) -> TerminalSnapshot {
+        let term = self.term.lock();
+        let content = term.renderable_content();
+        let offset = i32::try_from(content.display_offset).unwrap_or(i32::MAX);
+        let visible_line = |point: Point| usize::try_from(point.line.0.saturating_add(offset)).ok();
+        let mut lines: Vec<Vec<CellRun>> = vec![Vec::new(); self.size.lines];
+        for indexed in content.display_iter {
+            let cell = indexed.cell;
+            if cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                continue;
+            }
+            let Some(runs) = visible_line(indexed.point).and_then(|line| lines.get_mut(line))
+            else {
+                continue;
+            };
+            let mut foreground = resolve_color(cell.fg, content.colors);
+            let mut background = resolve_color(cell.bg, content.colors);
+            if cell.flags.contains(Flags::INVERSE) {
+                std::mem::swap(&mut foreground, &mut background);
+                if foreground == CellColor::Background {
+                    foreground = CellColor::Rgb(0);
+                }
+            }
+            let point = indexed.point;
+            let style = CellStyle {
+                foreground,
+                background,
+                bold: cell.flags.contains(Flags::BOLD),
+                italic: cell.flags.contains(Flags::ITALIC),
+                underline: cell.flags.intersects(Flags::ALL_UNDERLINES),
+                strikeout: cell.flags.contains(Flags::STRIKEOUT),
+                dim: cell.flags.contains(Flags::DIM),
+                selected: content
+                    .selection
+                    .as_ref()
+                    .is_some_and(|range| range.contains(point)),
+                matched: self
+                    .search_matches
+                    .iter()
+                    .any(|found| found.contains(&point)),
+            };
+            let character = if cell.flags.contains(Flags::HIDDEN) {
+                ' '
+            } else {
+                cell.c
+            };
+            match runs.last_mut() {
+                Some(last) if last.style == style => last.text.push(character),
+                _ => runs.push(CellRun {
+                    text: character.to_string(),
+                    style,
+                }),
+            }
+        }
+        let cursor = (content.cursor.shape != CursorShape::Hidden)
+            .then(|| visible_line(content.cursor.point))
+            .flatten()
+            .filter(|line| *line < self.size.lines)
+            .map(|line| (line, content.cursor.point.column.0));
+        TerminalSnapshot { lines, cursor }
+    }
+}
@@ -221,12 +221,13 @@ impl ColorPickerBar {
     ///
     /// ### Returns
     /// - `Some(Entity<EditorState>)`: The active editor tab's content
-    /// - `None`: If the window is gone or the active tab is not an editor
+    /// - `None`: If the window is gone or the active tab is not an editable editor
     fn active_editor_content(&self, cx: &App) -> Option<Entity<EditorState>> {
         let fulgur = self.fulgur.upgrade()?;
         fulgur
             .read(cx)
             .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.read_only)
             .map(|editor_tab| editor_tab.content.clone())
     }
 
@@ -70,7 +70,9 @@ impl CsvToolbar {
             return;
         };
         table.update(cx, |state, cx| {
-            edit(state.delegate_mut(), window, cx);
+            if !state.delegate().is_read_only() {
+                edit(state.delegate_mut(), window, cx);
+            }
         });
     }
 }
@@ -82,10 +84,12 @@ impl Fulgur {
     /// - `cx`: The application context
     ///
     /// ### Returns
-    /// - `bool`: True if the active tab is a CSV tab in table view
+    /// - `bool`: True if the active tab is an editable CSV tab in table view
     pub(crate) fn csv_toolbar_visible(&self, cx: &gpui::App) -> bool {
         self.get_active_editor_tab(cx).is_some_and(|editor| {
-            editor.language == SupportedLanguage::Csv && editor.csv_view_mode == CsvViewMode::Table
+            editor.language == SupportedLanguage::Csv
+                && editor.csv_view_mode == CsvViewMode::Table
+                && !editor.read_only
         })
     }
 
@@ -179,6 +183,8 @@ impl Render for CsvToolbar {
             )
             .child(
                 h_flex()
+                    .border_r_1()
+                    .border_color(border)
                     .child(
                         search_bar_button_factory(
                             "csv-add-column-before",
@@ -221,6 +227,23 @@ impl Render for CsvToolbar {
                         })),
                     ),
             )
+            .child(
+                h_flex().child(
+                    search_bar_button_factory(
+                        "csv-copy-markdown-table",
+                        "Copy as Markdown table",
+                        CustomIcon::Table,
+                        border,
+                    )
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(fulgur) = this.fulgur.upgrade() {
+                            fulgur.update(cx, |fulgur, cx| {
+                                fulgur.copy_csv_as_markdown_table(window, cx);
+                            });
+                        }
+                    })),
+                ),
+            )
     }
 }
 
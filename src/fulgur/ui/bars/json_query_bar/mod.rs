@@ -0,0 +1,5 @@
+mod panel;
+mod render;
+mod state;
+
+pub(crate) use state::{JsonQueryBar, JsonQueryBarEvent};
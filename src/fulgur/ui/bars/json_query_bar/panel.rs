@@ -0,0 +1,157 @@
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::Jump,
+    utils::{json_query, text_diff::line_and_column},
+};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    AnyElement, Context, InteractiveElement, IntoElement, ParentElement, SharedString,
+    StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{ActiveTheme, h_flex, scroll::ScrollableElement, v_flex};
+
+/// Width of the JSON query results panel
+const RESULTS_PANEL_WIDTH: f32 = 320.0;
+
+/// Most results listed in the panel, the rest being reachable through "filter to new tab"
+const MAX_LISTED_RESULTS: usize = 500;
+
+/// Most characters of a result value shown in its row
+const PREVIEW_CHARS: usize = 120;
+
+impl Fulgur {
+    /// Move the cursor of the active tab to the start of a query result
+    ///
+    /// ### Arguments
+    /// - `offset`: The byte offset of the result in the active tab
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn jump_to_json_query_result(
+        &mut self,
+        offset: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_active_editor_tab(cx, |editor_tab, cx| {
+            let (line, character) =
+                line_and_column(&editor_tab.content.read(cx).text().to_string(), offset);
+            editor_tab.jump_to_line(
+                window,
+                cx,
+                Jump {
+                    line,
+                    character: Some(character),
+                },
+            );
+        });
+    }
+
+    /// Lay the JSON query results panel out to the right of the content, when the bar is visible
+    ///
+    /// ### Arguments
+    /// - `content`: The rendered content area
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The content with the results next to it, or `content` unchanged when hidden
+    pub(crate) fn render_json_query_layout(
+        &self,
+        content: AnyElement,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let bar = self.json_query_bar.read(cx);
+        if !bar.is_visible() {
+            return content;
+        }
+        let results = bar.results();
+        let mut list = v_flex()
+            .id("json-query-results")
+            .size_full()
+            .overflow_y_scrollbar();
+        if results.is_empty() {
+            list = list.child(
+                div()
+                    .p_2()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(bar.error().unwrap_or("No results").to_string()),
+            );
+        }
+        for (index, result) in results.iter().take(MAX_LISTED_RESULTS).enumerate() {
+            let offset = result.span.as_ref().map(|span| span.start);
+            let row = v_flex()
+                .id(("json-query-result", index))
+                .w_full()
+                .py_0p5()
+                .px_2()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .when(offset.is_some(), |this| {
+                    this.cursor_pointer()
+                        .hover(|this| this.bg(cx.theme().muted))
+                })
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .child(SharedString::from(result.path.clone())),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .font_family(self.settings.editor_settings.font_family.clone())
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .child(json_query::preview(&result.value, PREVIEW_CHARS)),
+                )
+                .when_some(offset, |this, offset| {
+                    this.on_click(cx.listener(move |this, _, window, cx| {
+                        this.jump_to_json_query_result(offset, window, cx);
+                    }))
+                });
+            list = list.child(row);
+        }
+        if results.len() > MAX_LISTED_RESULTS {
+            list = list.child(
+                div()
+                    .p_2()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!(
+                        "{} more, filter to a new tab to see them all",
+                        results.len() - MAX_LISTED_RESULTS
+                    )),
+            );
+        }
+        h_flex()
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .child(v_flex().h_full().flex_1().min_w_0().child(content))
+            .child(
+                v_flex()
+                    .h_full()
+                    .w(px(RESULTS_PANEL_WIDTH))
+                    .flex_none()
+                    .border_l_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().tab_bar)
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .border_b_1()
+                            .border_color(cx.theme().border)
+                            .child("QUERY RESULTS"),
+                    )
+                    .child(list),
+            )
+            .into_any_element()
+    }
+}
@@ -0,0 +1,162 @@
+use super::state::{JsonQueryBar, JsonQueryBarEvent};
+use crate::fulgur::ui::{
+    components_utils::{CORNERS_SIZE, LINE_HEIGHT, SEARCH_BAR_HEIGHT, TEXT_SIZE},
+    icons::CustomIcon,
+};
+use gpui::{Context, Div, IntoElement, ParentElement, Render, Styled, Window, div};
+use gpui_component::{ActiveTheme, StyledExt, input::Input};
+
+use super::super::search_bar::search_bar_button_factory;
+
+impl Render for JsonQueryBar {
+    /// Render the JSON query bar
+    ///
+    /// ### Arguments
+    /// - `_window`: The window to render the JSON query bar in
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `impl IntoElement`: The rendered JSON query bar, or an empty element when hidden
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if !self.show_query {
+            return div().into_any_element();
+        }
+        div()
+            .flex()
+            .justify_between()
+            .items_center()
+            .bg(cx.theme().tab_bar)
+            .p_0()
+            .m_0()
+            .w_full()
+            .h(SEARCH_BAR_HEIGHT)
+            .border_t_1()
+            .border_color(cx.theme().border)
+            .child(self.render_query_input_section(cx))
+            .child(self.render_query_status_section(cx))
+            .child(Self::render_query_close_button(cx))
+            .into_any_element()
+    }
+}
+
+impl JsonQueryBar {
+    /// Render the query input section (left part of the bar)
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Div`: The rendered query input section
+    fn render_query_input_section(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .p_0()
+            .m_0()
+            .flex_1()
+            .h(SEARCH_BAR_HEIGHT)
+            .bg(cx.theme().background)
+            .text_color(cx.theme().muted_foreground)
+            .child(
+                div()
+                    .text_xs()
+                    .px_2()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Query"),
+            )
+            .child(
+                Input::new(&self.query_input)
+                    .appearance(false)
+                    .bordered(false)
+                    .flex_1()
+                    .text_size(TEXT_SIZE)
+                    .line_height(LINE_HEIGHT)
+                    .m_0()
+                    .py_0()
+                    .pl_0()
+                    .pr_0()
+                    .h(SEARCH_BAR_HEIGHT)
+                    .corner_radii(CORNERS_SIZE)
+                    .text_color(cx.theme().muted_foreground)
+                    .bg(cx.theme().background),
+            )
+    }
+
+    /// Render the result count, or the error, and the "filter to new tab" button
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Div`: The rendered status section
+    fn render_query_status_section(&self, cx: &mut Context<Self>) -> Div {
+        let (status, color) = match &self.error {
+            Some(error) => (error.clone(), cx.theme().danger),
+            None => (
+                match self.results.len() {
+                    0 => "No results".to_string(),
+                    1 => "1 result".to_string(),
+                    count => format!("{count} results"),
+                },
+                cx.theme().muted_foreground,
+            ),
+        };
+        div()
+            .flex()
+            .items_center()
+            .p_0()
+            .m_0()
+            .max_w(gpui::px(420.0))
+            .border_l_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .text_xs()
+                    .px_2()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .text_color(color)
+                    .child(status),
+            )
+            .child(
+                search_bar_button_factory(
+                    "json-query-filter-button",
+                    "Filter to new tab",
+                    CustomIcon::FileCode,
+                    cx.theme().border,
+                )
+                .on_click(cx.listener(|_, _, _window, cx| {
+                    cx.emit(JsonQueryBarEvent::FilterToNewTab);
+                })),
+            )
+    }
+
+    /// Render the close button for the JSON query bar
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Div`: The rendered close button
+    fn render_query_close_button(cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .p_0()
+            .m_0()
+            .border_l_1()
+            .border_color(cx.theme().border)
+            .child(
+                search_bar_button_factory(
+                    "close-json-query-button",
+                    "Close",
+                    CustomIcon::Close,
+                    cx.theme().border,
+                )
+                .on_click(cx.listener(|this, _, _window, cx| {
+                    this.close(cx);
+                })),
+            )
+    }
+}
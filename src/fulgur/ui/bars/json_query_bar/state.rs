@@ -0,0 +1,350 @@
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::EditorTab,
+    tab::{Tab, TabId},
+    utils::{
+        json_query::{self, JsonNode, QueryMatch},
+        structured_data::{self, DataFormat},
+    },
+};
+use gpui::{
+    AppContext, Context, Entity, EventEmitter, Focusable, SharedString, Subscription, Task, Window,
+};
+use gpui_component::{
+    WindowExt,
+    input::{EditorState, InputEvent, InputState},
+    notification::NotificationType,
+};
+use std::time::Duration;
+
+/// File name given to the tab holding the results of a query
+const RESULTS_FILE_NAME: &str = "query-results.json";
+
+/// Delay after the last edit of the source before it is parsed again
+const REPARSE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The JSON query bar, rendered as its own entity
+///
+/// The bar evaluates its `JSONPath` or jq-style query against the active tab each
+/// time the query or the tab changes. The results are listed by the window in a
+/// side panel next to the content.
+pub(crate) struct JsonQueryBar {
+    pub(super) show_query: bool,
+    pub(super) query_input: Entity<InputState>,
+    source: Option<(TabId, Entity<EditorState>)>, // The editor state the document is parsed from
+    document: Option<Result<JsonNode, String>>,   // The parsed source, or why it is not JSON
+    pub(super) results: Vec<QueryMatch>,
+    pub(super) error: Option<String>, // Why the query produced no results: invalid JSON or query
+    _query_input_subscription: Subscription,
+    source_subscription: Option<Subscription>, // Re-parses `source` and re-runs the query after its edits
+    parse_task: Option<Task<()>>,              // The background parse of `source` in flight
+}
+
+/// Typed events emitted by the JSON query bar toward the owning `Fulgur` window
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum JsonQueryBarEvent {
+    Closed,
+    ResultsChanged,
+    FilterToNewTab,
+}
+
+impl EventEmitter<JsonQueryBarEvent> for JsonQueryBar {}
+
+impl JsonQueryBar {
+    /// Create a new JSON query bar view owning its query input
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The JSON query bar context
+    ///
+    /// ### Returns
+    /// - `JsonQueryBar`: The new, hidden JSON query bar view
+    pub(crate) fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let query_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("JSONPath ($.items[0].name) or jq (.items[] | .name)")
+        });
+        let query_input_subscription = cx.subscribe_in(
+            &query_input,
+            window,
+            |this: &mut Self, _, event: &InputEvent, _window, cx| {
+                if let InputEvent::Change = event
+                    && this.show_query
+                {
+                    this.refresh_results(cx);
+                    cx.emit(JsonQueryBarEvent::ResultsChanged);
+                    cx.notify();
+                }
+            },
+        );
+        Self {
+            show_query: false,
+            query_input,
+            source: None,
+            document: None,
+            results: Vec::new(),
+            error: None,
+            _query_input_subscription: query_input_subscription,
+            source_subscription: None,
+            parse_task: None,
+        }
+    }
+
+    /// Whether the JSON query bar is currently shown
+    ///
+    /// ### Returns
+    /// - `bool`: True if the bar is visible
+    pub(crate) fn is_visible(&self) -> bool {
+        self.show_query
+    }
+
+    /// Get the results of the query
+    ///
+    /// ### Returns
+    /// - `&[QueryMatch]`: The values selected in the active tab
+    pub(crate) fn results(&self) -> &[QueryMatch] {
+        &self.results
+    }
+
+    /// Get why the query produced no results
+    ///
+    /// ### Returns
+    /// - `Some(&str)`: The JSON parse error or query syntax error
+    /// - `None`: The query ran
+    pub(crate) fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Toggle the JSON query bar, focusing its input when it opens
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The JSON query bar context
+    pub(crate) fn toggle(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.show_query {
+            self.close(cx);
+        } else {
+            self.show_query = true;
+            let query_focus = self.query_input.read(cx).focus_handle(cx);
+            window.focus(&query_focus, cx);
+            cx.notify();
+        }
+    }
+
+    /// Hide the JSON query bar, drop the parsed document and notify the owning window
+    ///
+    /// ### Arguments
+    /// - `cx`: The JSON query bar context
+    pub(super) fn close(&mut self, cx: &mut Context<Self>) {
+        self.show_query = false;
+        self.source = None;
+        self.source_subscription = None;
+        self.parse_task = None;
+        self.document = None;
+        self.results.clear();
+        self.error = None;
+        cx.emit(JsonQueryBarEvent::Closed);
+        cx.notify();
+    }
+
+    /// Empty the query, e.g. to start over on a tab holding filtered results
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The JSON query bar context
+    pub(crate) fn clear_query(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.query_input
+            .update(cx, |input, cx| input.set_value("", window, cx));
+    }
+
+    /// Point the bar at the active editor tab, re-running the query when it changed
+    ///
+    /// ### Arguments
+    /// - `source`: The identifier and editor state of the active editor tab, if any
+    /// - `cx`: The JSON query bar context
+    pub(crate) fn set_source(
+        &mut self,
+        source: Option<(TabId, Entity<EditorState>)>,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.show_query || source == self.source {
+            return;
+        }
+        self.source_subscription = source.as_ref().map(|(_, content)| {
+            cx.subscribe(content, |this: &mut Self, _, event: &InputEvent, cx| {
+                if matches!(event, InputEvent::Change) {
+                    this.reparse(REPARSE_DEBOUNCE, cx);
+                }
+            })
+        });
+        self.source = source;
+        self.document = None;
+        self.refresh_results(cx);
+        self.reparse(Duration::ZERO, cx);
+        cx.notify();
+    }
+
+    /// Parse the source document in the background, then re-run the query
+    ///
+    /// ### Description
+    /// A parse still waiting for its delay is replaced, so a burst of edits is
+    /// parsed once.
+    ///
+    /// ### Arguments
+    /// - `delay`: How long to wait before parsing
+    /// - `cx`: The JSON query bar context
+    fn reparse(&mut self, delay: Duration, cx: &mut Context<Self>) {
+        let Some((_, content)) = self.source.clone() else {
+            self.parse_task = None;
+            self.document = None;
+            return;
+        };
+        let text = content.read(cx).text().clone();
+        self.parse_task = Some(cx.spawn(async move |this, cx| {
+            if !delay.is_zero() {
+                cx.background_executor().timer(delay).await;
+            }
+            let document = cx
+                .background_executor()
+                .spawn(async move {
+                    json_query::parse_spanned(&text.to_string()).map_err(|e| e.to_string())
+                })
+                .await;
+            _ = this.update(cx, |this, cx| {
+                this.document = Some(document);
+                this.refresh_results(cx);
+                cx.emit(JsonQueryBarEvent::ResultsChanged);
+                cx.notify();
+            });
+        }));
+    }
+
+    /// Run the query against the parsed document
+    ///
+    /// ### Arguments
+    /// - `cx`: The JSON query bar context
+    fn refresh_results(&mut self, cx: &mut Context<Self>) {
+        self.results.clear();
+        self.error = None;
+        let query = self.query_input.read(cx).value().trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+        match &self.document {
+            None => {}
+            Some(Err(e)) => self.error = Some(format!("Not valid JSON: {e}")),
+            Some(Ok(root)) => match json_query::compile(&query) {
+                Ok(compiled) => self.results = compiled.evaluate(root),
+                Err(e) => self.error = Some(e.to_string()),
+            },
+        }
+    }
+}
+
+impl Fulgur {
+    /// Dispatch a JSON query bar event to the matching window-level handler
+    ///
+    /// ### Arguments
+    /// - `event`: The JSON query bar event to handle
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn on_json_query_bar_event(
+        &mut self,
+        event: JsonQueryBarEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            JsonQueryBarEvent::Closed => {
+                self.focus_active_tab(window, cx);
+                cx.notify();
+            }
+            JsonQueryBarEvent::ResultsChanged => cx.notify(),
+            JsonQueryBarEvent::FilterToNewTab => self.filter_json_query_to_new_tab(window, cx),
+        }
+    }
+
+    /// Toggle the JSON query bar and its results panel
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn toggle_json_query(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.json_query_bar
+            .update(cx, |bar, cx| bar.toggle(window, cx));
+        cx.notify();
+    }
+
+    /// Keep the JSON query bar pointed at the active editor tab
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub(crate) fn refresh_json_query(&mut self, cx: &mut Context<Self>) {
+        if !self.json_query_bar.read(cx).is_visible() {
+            return;
+        }
+        let active = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.large_file)
+            .map(|editor_tab| (editor_tab.id, editor_tab.content.clone()));
+        self.json_query_bar
+            .update(cx, |bar, cx| bar.set_source(active, cx));
+    }
+
+    /// Open the results of the JSON query in a new tab
+    ///
+    /// ### Description
+    /// A single result is written as is, several results as an array. The JSON is
+    /// indented like the queried tab. The query is cleared so the new tab can be
+    /// queried further.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn filter_json_query_to_new_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let results = self.json_query_bar.read(cx).results();
+        if results.is_empty() {
+            window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from("The query has no results"),
+                ),
+                cx,
+            );
+            return;
+        }
+        let value = json_query::results_value(results);
+        let Some(indentation) = self
+            .get_active_editor_tab(cx)
+            .map(|editor_tab| editor_tab.indentation)
+        else {
+            return;
+        };
+        let text = match structured_data::write(DataFormat::Json, &value, indentation) {
+            Ok(text) => text,
+            Err(e) => {
+                log::warn!("Failed to write query results: {e}");
+                window.push_notification(
+                    (NotificationType::Error, SharedString::from(e.to_string())),
+                    cx,
+                );
+                return;
+            }
+        };
+        let id = self.allocate_tab_id();
+        let editor_tab = EditorTab::from_content(
+            id,
+            &text,
+            RESULTS_FILE_NAME.to_string(),
+            window,
+            cx,
+            &self.settings.editor_settings,
+        );
+        self.place_editor_tab_reusing_scratch(Tab::Editor(editor_tab), window, cx);
+        self.json_query_bar
+            .update(cx, |bar, cx| bar.clear_query(window, cx));
+        self.focus_active_tab(window, cx);
+        self.save_state_async(cx, window);
+        cx.notify();
+    }
+}
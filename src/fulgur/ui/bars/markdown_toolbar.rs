@@ -2,12 +2,95 @@ use crate::fulgur::{
     Fulgur,
     ui::components_utils::{MARKDOWN_BAR_BUTTON_SIZE, MARKDOWN_BAR_HEIGHT, button_factory},
     ui::icons::CustomIcon,
+    utils::markdown_table::{ColumnAlignment, TableEdit, is_table_row},
 };
 
 use gpui::{
-    App, Context, Entity, Hsla, IntoElement, ParentElement, Render, Styled, WeakEntity, Window, div,
+    App, Context, Div, Entity, Hsla, IntoElement, ParentElement, Render, Styled, WeakEntity,
+    Window, div,
 };
-use gpui_component::{ActiveTheme, button::Button, h_flex, input::EditorState};
+use gpui_component::{
+    ActiveTheme,
+    button::Button,
+    h_flex,
+    input::{EditorState, RopeExt},
+};
+
+/// The buttons of the table group: ID, tooltip, icon and edit
+const TABLE_BUTTONS: [(&str, &str, CustomIcon, TableEdit); 12] = [
+    (
+        "markdown-table-row-above-button",
+        "Add row above",
+        CustomIcon::AddRowAbove,
+        TableEdit::InsertRowAbove,
+    ),
+    (
+        "markdown-table-row-below-button",
+        "Add row below",
+        CustomIcon::AddRowBelow,
+        TableEdit::InsertRowBelow,
+    ),
+    (
+        "markdown-table-delete-row-button",
+        "Delete row",
+        CustomIcon::DeleteRow,
+        TableEdit::DeleteRow,
+    ),
+    (
+        "markdown-table-column-before-button",
+        "Add column before",
+        CustomIcon::AddColumnBefore,
+        TableEdit::InsertColumnBefore,
+    ),
+    (
+        "markdown-table-column-after-button",
+        "Add column after",
+        CustomIcon::AddColumnAfter,
+        TableEdit::InsertColumnAfter,
+    ),
+    (
+        "markdown-table-delete-column-button",
+        "Delete column",
+        CustomIcon::DeleteColumn,
+        TableEdit::DeleteColumn,
+    ),
+    (
+        "markdown-table-move-left-button",
+        "Move column left",
+        CustomIcon::ChevronLeft,
+        TableEdit::MoveColumnLeft,
+    ),
+    (
+        "markdown-table-move-right-button",
+        "Move column right",
+        CustomIcon::ChevronRight,
+        TableEdit::MoveColumnRight,
+    ),
+    (
+        "markdown-table-align-left-button",
+        "Align column left",
+        CustomIcon::AlignLeft,
+        TableEdit::Align(ColumnAlignment::Left),
+    ),
+    (
+        "markdown-table-align-center-button",
+        "Center column",
+        CustomIcon::AlignCenter,
+        TableEdit::Align(ColumnAlignment::Center),
+    ),
+    (
+        "markdown-table-align-right-button",
+        "Align column right",
+        CustomIcon::AlignRight,
+        TableEdit::Align(ColumnAlignment::Right),
+    ),
+    (
+        "markdown-table-format-button",
+        "Format table",
+        CustomIcon::Table,
+        TableEdit::Format,
+    ),
+];
 
 /// Create a markdown bar button
 ///
@@ -54,12 +137,13 @@ impl MarkdownToolbar {
     ///
     /// ### Returns
     /// - `Some(Entity<EditorState>)`: The active editor tab's content
-    /// - `None`: If the window is gone or the active tab is not an editor
+    /// - `None`: If the window is gone or the active tab is not an editable editor
     fn active_editor_content(&self, cx: &App) -> Option<Entity<EditorState>> {
         let fulgur = self.fulgur.upgrade()?;
         fulgur
             .read(cx)
             .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.read_only)
             .map(|editor_tab| editor_tab.content.clone())
     }
 
@@ -86,6 +170,56 @@ impl MarkdownToolbar {
             });
         }
     }
+
+    /// Check whether the cursor of the active editor is on a table row
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `bool`: True if the cursor line holds an unescaped pipe
+    fn cursor_in_table(&self, cx: &App) -> bool {
+        self.active_editor_content(cx).is_some_and(|content| {
+            let state = content.read(cx);
+            let row = state.cursor_position().line as usize;
+            row < state.text().lines_len()
+                && is_table_row(&state.text().slice_line(row).to_string())
+        })
+    }
+
+    /// Edit the table around the cursor of the active editor
+    ///
+    /// ### Arguments
+    /// - `edit`: The edit to apply
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn edit_table(&mut self, edit: TableEdit, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(fulgur) = self.fulgur.upgrade() {
+            fulgur.update(cx, |fulgur, cx| {
+                fulgur.edit_markdown_table(window, cx, edit);
+            });
+        }
+    }
+
+    /// Render the table group, shown while the cursor is in a table
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Div`: The table buttons
+    fn render_table_group(cx: &mut Context<Self>) -> Div {
+        h_flex()
+            .border_r_1()
+            .border_color(cx.theme().border)
+            .children(TABLE_BUTTONS.map(|(id, tooltip, icon, edit)| {
+                markdown_bar_button_factory(id, tooltip, icon, cx.theme().border).on_click(
+                    cx.listener(move |this, _, window, cx| {
+                        this.edit_table(edit, window, cx);
+                    }),
+                )
+            }))
+    }
 }
 
 impl Fulgur {
@@ -114,6 +248,7 @@ impl Render for MarkdownToolbar {
     /// ### Returns
     /// - `impl IntoElement`: The rendered markdown toolbar
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let in_table = self.cursor_in_table(cx);
         div()
             .flex()
             .items_center()
@@ -170,6 +305,21 @@ impl Render for MarkdownToolbar {
                         .on_click(cx.listener(|this, _, window, cx| {
                             this.insert_or_surround("[", "](https://)", window, cx);
                         })),
+                    )
+                    .child(
+                        markdown_bar_button_factory(
+                            "markdown-check-links-button",
+                            "Check links",
+                            CustomIcon::CircleCheck,
+                            cx.theme().border,
+                        )
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            if let Some(fulgur) = this.fulgur.upgrade() {
+                                fulgur.update(cx, |fulgur, cx| {
+                                    fulgur.check_markdown_links(window, cx);
+                                });
+                            }
+                        })),
                     ),
             )
             .child(
@@ -349,8 +499,24 @@ impl Render for MarkdownToolbar {
                                 cx,
                             );
                         })),
+                    )
+                    .child(
+                        markdown_bar_button_factory(
+                            "markdown-paste-csv-button",
+                            "Paste CSV as table",
+                            CustomIcon::ClipboardPaste,
+                            cx.theme().border,
+                        )
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            if let Some(fulgur) = this.fulgur.upgrade() {
+                                fulgur.update(cx, |fulgur, cx| {
+                                    fulgur.paste_csv_as_markdown_table(window, cx);
+                                });
+                            }
+                        })),
                     ),
             )
+            .children(in_table.then(|| Self::render_table_group(cx)))
     }
 }
 
@@ -1,5 +1,6 @@
 pub mod color_picker_bar;
 pub mod csv_toolbar;
+pub mod json_query_bar;
 pub mod markdown_toolbar;
 pub mod search_bar;
 pub mod status_bar;
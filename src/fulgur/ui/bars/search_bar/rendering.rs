@@ -226,7 +226,7 @@ impl SearchBar {
                             cx.theme().border,
                         )
                         .on_click(cx.listener(|this, _, window, cx| {
-                            let content = this.active_editor_content(cx);
+                            let content = this.active_writable_editor_content(cx);
                             this.replace_current(content, window, cx);
                         })),
                     )
@@ -238,7 +238,7 @@ impl SearchBar {
                             cx.theme().border,
                         )
                         .on_click(cx.listener(|this, _, window, cx| {
-                            let content = this.active_editor_content(cx);
+                            let content = this.active_writable_editor_content(cx);
                             this.replace_all(content, window, cx);
                         })),
                     ),
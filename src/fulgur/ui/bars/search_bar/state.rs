@@ -106,6 +106,23 @@ impl SearchBar {
             .get_active_editor_tab(cx)
             .map(|editor_tab| editor_tab.content.clone())
     }
+
+    /// Get the active editor's content entity when it may be edited
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(Entity<EditorState>)`: The active editor tab's content
+    /// - `None`: If the window is gone or the active tab is not an editable editor
+    pub(super) fn active_writable_editor_content(&self, cx: &App) -> Option<Entity<EditorState>> {
+        let fulgur = self.fulgur.upgrade()?;
+        fulgur
+            .read(cx)
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.read_only)
+            .map(|editor_tab| editor_tab.content.clone())
+    }
 }
 
 impl Fulgur {
@@ -123,6 +140,13 @@ impl Fulgur {
     ) {
         match event {
             SearchBarEvent::Closed => {
+                // The search matches are gone; bring back the underlines of the other features.
+                if let Some(content) = self
+                    .get_active_editor_tab(cx)
+                    .map(|editor_tab| editor_tab.content.clone())
+                {
+                    self.write_diagnostics(&content, cx);
+                }
                 self.focus_active_tab(window, cx);
                 cx.notify();
             }
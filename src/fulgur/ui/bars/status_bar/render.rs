@@ -6,16 +6,24 @@ use super::{
     },
 };
 use crate::fulgur::{
+    files::indentation::{INDENT_WIDTHS, IndentationChange, IndentationSource},
     languages::supported_languages::SupportedLanguage,
     settings::MarkdownPreviewMode,
     tab::Tab,
     ui::{icons::CustomIcon, log_view::log_toggle_available, tabs::editor_tab::CsvViewMode},
 };
 use gpui::{
-    Context, InteractiveElement, IntoElement, MouseButton, MouseDownEvent, ParentElement, Render,
-    StatefulInteractiveElement, Styled, Window, div, prelude::FluentBuilder,
+    Anchor, App, ClickEvent, Context, InteractiveElement, IntoElement, MouseButton, MouseDownEvent,
+    ParentElement, Render, StatefulInteractiveElement, Styled, Window, div, prelude::FluentBuilder,
+};
+use gpui_component::{
+    ActiveTheme, Icon, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    menu::{DropdownMenu, PopupMenuItem},
+    tooltip::Tooltip,
+    v_flex,
 };
-use gpui_component::{ActiveTheme, Icon, StyledExt, h_flex, tooltip::Tooltip, v_flex};
 
 impl Render for StatusBar {
     /// Render the status bar from the owning window's current state
@@ -222,6 +230,70 @@ impl Render for StatusBar {
                 cx.emit(StatusBarEvent::ToggleColorPicker);
             }),
         );
+        let indentation_button = active_editor_tab.map(|tab| {
+            let indentation = tab.indentation;
+            let status_bar = cx.entity();
+            Button::new("indentation-button")
+                .label(indentation.label())
+                .tooltip(indentation.source_label())
+                .ghost()
+                .xsmall()
+                .dropdown_menu_with_anchor(Anchor::BottomRight, move |menu, _window, _cx| {
+                    let emit = |change: IndentationChange| {
+                        let status_bar = status_bar.clone();
+                        move |_: &ClickEvent, _: &mut Window, cx: &mut App| {
+                            status_bar.update(cx, |_, cx| {
+                                cx.emit(StatusBarEvent::ChangeIndentation(change));
+                            });
+                        }
+                    };
+                    let mut menu = menu
+                        .item(
+                            PopupMenuItem::new("Indent Using Spaces")
+                                .checked(indentation.use_spaces)
+                                .on_click(emit(IndentationChange::UseSpaces(true))),
+                        )
+                        .item(
+                            PopupMenuItem::new("Indent Using Tabs")
+                                .checked(!indentation.use_spaces)
+                                .on_click(emit(IndentationChange::UseSpaces(false))),
+                        )
+                        .separator();
+                    for width in INDENT_WIDTHS {
+                        menu = menu.item(
+                            PopupMenuItem::new(format!("Width: {width}"))
+                                .checked(indentation.width == width)
+                                .on_click(emit(IndentationChange::Width(width))),
+                        );
+                    }
+                    menu.separator().item(
+                        PopupMenuItem::new("Detect from Content")
+                            .checked(!matches!(
+                                indentation.source,
+                                IndentationSource::Settings | IndentationSource::Override
+                            ))
+                            .on_click(emit(IndentationChange::Detect)),
+                    )
+                })
+        });
+        let auto_save_error = fulgur.auto_save_error();
+        let auto_save_error_button = auto_save_error.map(|error| {
+            status_bar_button_factory(
+                "Auto-save failed".to_string(),
+                cx.theme().border,
+                cx.theme().danger_hover,
+            )
+            .id("auto-save-error-button")
+            .bg(cx.theme().danger)
+            .text_color(cx.theme().danger_foreground)
+            .tooltip(move |window, cx| Tooltip::new(error.clone()).build(window, cx))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|_, _event: &MouseDownEvent, _window, cx| {
+                    cx.emit(StatusBarEvent::DismissAutoSaveError);
+                }),
+            )
+        });
         h_flex()
             .justify_between()
             .bg(cx.theme().tab_bar)
@@ -258,7 +330,9 @@ impl Render for StatusBar {
                 div()
                     .flex()
                     .justify_end()
+                    .children(auto_save_error_button)
                     .child(color_button)
+                    .children(indentation_button)
                     .child(jump_to_line_button)
                     .child(status_bar_right_item_factory(
                         labels.encoding_label,
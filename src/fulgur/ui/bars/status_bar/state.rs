@@ -1,6 +1,7 @@
 use super::widgets::SyncButtonState;
 use crate::fulgur::{
     Fulgur,
+    files::indentation::IndentationChange,
     languages::supported_languages::{SupportedLanguage, pretty_name},
     settings::{MarkdownPreviewMode, ServerProfile},
     sync::synchronization::SynchronizationStatus,
@@ -32,6 +33,8 @@ pub(crate) enum StatusBarEvent {
     LoadFullLog,
     OpenShareSheet,
     ToggleColorPicker,
+    DismissAutoSaveError,
+    ChangeIndentation(IndentationChange),
 }
 
 impl EventEmitter<StatusBarEvent> for StatusBar {}
@@ -233,6 +236,10 @@ impl Fulgur {
             StatusBarEvent::LoadFullLog => self.load_full_log(window, cx),
             StatusBarEvent::OpenShareSheet => self.open_share_file_sheet(window, cx),
             StatusBarEvent::ToggleColorPicker => self.toggle_color_picker(window, cx),
+            StatusBarEvent::DismissAutoSaveError => self.clear_auto_save_error(cx),
+            StatusBarEvent::ChangeIndentation(change) => {
+                self.change_active_tab_indentation(change, window, cx);
+            }
         }
     }
 }
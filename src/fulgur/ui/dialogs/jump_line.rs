@@ -1,6 +1,7 @@
-use gpui::{Context, Focusable, ParentElement, Styled, Window, div, px};
-use gpui_component::{WindowExt, button::ButtonVariant, dialog::DialogButtonProps, input::Input};
+use gpui::{AppContext, Context, Focusable, ParentElement, Styled, Window, div, px};
+use gpui_component::{WindowExt, button::ButtonVariant, dialog::DialogButtonProps};
 
+use super::symbol_picker::{SYMBOL_PREFIX, SymbolPicker, symbol_jump};
 use crate::fulgur::{Fulgur, editor_tab};
 
 impl Fulgur {
@@ -10,20 +11,66 @@ impl Fulgur {
     /// - `window`: The window to show the dialog in
     /// - `cx`: The application context
     pub fn show_jump_to_line_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.open_jump_dialog("", window, cx);
+    }
+
+    /// Show the jump dialog in `@symbol` mode
+    ///
+    /// ### Arguments
+    /// - `window`: The window to show the dialog in
+    /// - `cx`: The application context
+    pub fn show_go_to_symbol_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.open_jump_dialog(&SYMBOL_PREFIX.to_string(), window, cx);
+    }
+
+    /// Show the jump dialog, accepting `line`, `line:character` or `@symbol`
+    ///
+    /// ### Description
+    /// The symbols come from the outline when it holds those of the active tab,
+    /// and are otherwise extracted in the background and listed once ready.
+    ///
+    /// ### Arguments
+    /// - `initial`: The text the input starts with
+    /// - `window`: The window to show the dialog in
+    /// - `cx`: The application context
+    fn open_jump_dialog(&mut self, initial: &str, window: &mut Window, cx: &mut Context<Self>) {
         self.jump_to_line_input.update(cx, |input_state, cx| {
-            input_state.set_value("", window, cx);
+            input_state.set_value(initial, window, cx);
             cx.notify();
         });
         let jump_to_line_input = self.jump_to_line_input.clone();
         let entity = cx.entity().clone();
+        let cached_symbols = self.outline_symbols_of_active_tab(cx);
+        let extraction = cached_symbols
+            .is_none()
+            .then(|| self.extract_active_tab_symbols(cx));
+        let weak_entity = entity.downgrade();
+        let input_for_picker = jump_to_line_input.clone();
+        let symbol_picker = cx.new(|cx| {
+            SymbolPicker::new(
+                weak_entity,
+                input_for_picker,
+                cached_symbols.unwrap_or_default(),
+                cx,
+            )
+        });
+        if let Some(extraction) = extraction {
+            let picker = symbol_picker.downgrade();
+            cx.spawn(async move |_, cx| {
+                let symbols = extraction.await;
+                _ = picker.update(cx, |picker, cx| picker.set_symbols(symbols, cx));
+            })
+            .detach();
+        }
         window.open_alert_dialog(cx, move |modal, window, cx| {
             let focus_handle = jump_to_line_input.read(cx).focus_handle(cx);
             window.focus(&focus_handle, cx);
             let jump_to_line_input_clone = jump_to_line_input.clone();
             let entity_for_ok = entity.clone();
             let entity_for_cancel = entity.clone();
+            let picker_for_ok = symbol_picker.clone();
             modal
-                .title(div().text_size(px(16.)).child("Jump to line..."))
+                .title(div().text_size(px(16.)).child("Jump to line or symbol..."))
                 .keyboard(true)
                 .button_props(
                     DialogButtonProps::default()
@@ -34,10 +81,15 @@ impl Fulgur {
                         .ok_variant(ButtonVariant::Primary),
                 )
                 .close_button(false)
-                .child(Input::new(&jump_to_line_input))
+                .child(symbol_picker.clone())
                 .on_ok(move |_, _window, cx| {
                     let text = jump_to_line_input_clone.read(cx).value();
-                    let jump_result = editor_tab::extract_line_number(&text);
+                    let jump_result = if text.trim_start().starts_with(SYMBOL_PREFIX) {
+                        symbol_jump(&text, picker_for_ok.read(cx).symbols())
+                            .ok_or_else(|| anyhow::anyhow!("No matching symbol"))
+                    } else {
+                        editor_tab::extract_line_number(&text)
+                    };
                     let is_ok = jump_result.is_ok();
                     entity_for_ok.update(cx, |this, cx| {
                         if let Ok(jump) = jump_result {
@@ -61,11 +113,23 @@ impl Fulgur {
 
     /// Handle pending jump-to-line action
     ///
+    /// ### Description
+    /// In the large file viewer, the target line is scrolled into view and highlighted.
+    ///
     /// ### Arguments
     /// - `window`: The window containing the editor
     /// - `cx`: The application context
     pub fn handle_pending_jump_to_line(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(jump) = self.pending_jump.take() {
+            let large_file_view = self
+                .active_tab_entity(cx)
+                .and_then(|tab| tab.read(cx).as_large_file().map(|tab| tab.view.clone()));
+            if let Some(large_file_view) = large_file_view {
+                large_file_view.update(cx, |view, cx| {
+                    view.scroll_to_line(jump.line as usize, cx);
+                });
+                return;
+            }
             self.update_active_editor_tab(cx, |editor_tab, cx| {
                 editor_tab.jump_to_line(window, cx, jump);
             });
@@ -7,7 +7,11 @@ pub mod lossy_save;
 pub mod open_path;
 pub mod open_remote;
 pub mod path_browser;
+pub mod pipe_command;
+#[cfg(target_os = "linux")]
+pub mod privileged_save;
 pub mod remote_path_browser;
 pub mod rename_tab;
 pub mod ssh_host_fingerprint;
 pub mod ssh_password;
+pub mod symbol_picker;
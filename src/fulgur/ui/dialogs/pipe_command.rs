@@ -0,0 +1,233 @@
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    App, AppContext, Context, Entity, Focusable, InteractiveElement, IntoElement, ParentElement,
+    Render, SharedString, StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, WindowExt,
+    button::{Button, ButtonVariant, ButtonVariants},
+    dialog::DialogButtonProps,
+    h_flex,
+    input::{Input, InputState},
+    notification::NotificationType,
+    v_flex,
+};
+
+use crate::fulgur::Fulgur;
+
+/// Where the output of a piped command goes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PipeOutputMode {
+    /// Replace the selection, or the whole buffer without a selection
+    #[default]
+    Replace,
+    /// Insert on new lines below the selection or the cursor line
+    InsertBelow,
+    /// Open in a new tab
+    NewTab,
+}
+
+impl PipeOutputMode {
+    /// Every output mode, in the order offered by the dialog
+    pub const ALL: [Self; 3] = [Self::Replace, Self::InsertBelow, Self::NewTab];
+
+    /// Get the label of the output mode
+    ///
+    /// ### Returns
+    /// - `&'static str`: The label shown in the dialog
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Replace => "Replace",
+            Self::InsertBelow => "Insert Below",
+            Self::NewTab => "New Tab",
+        }
+    }
+
+    /// Whether the output mode edits the source tab
+    ///
+    /// ### Returns
+    /// - `bool`: `true` unless the output opens in a new tab
+    #[must_use]
+    pub fn edits_source(self) -> bool {
+        self != Self::NewTab
+    }
+}
+
+/// The pipe command dialog body: the command input, the output mode and the
+/// recently run commands.
+pub struct PipeCommandForm {
+    input: Entity<InputState>,
+    mode: PipeOutputMode,
+    recent_commands: Vec<String>,
+}
+
+impl PipeCommandForm {
+    /// Create the form, prefilled with the last command run
+    ///
+    /// ### Arguments
+    /// - `recent_commands`: The recently run commands, most recent first
+    /// - `window`: The window context
+    /// - `cx`: The form context
+    ///
+    /// ### Returns
+    /// - `PipeCommandForm`: The new form view
+    pub fn new(recent_commands: Vec<String>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let initial = recent_commands.first().cloned().unwrap_or_default();
+        let input = cx.new(|cx| {
+            let mut input_state = InputState::new(window, cx).placeholder("e.g. sort -u, jq .");
+            input_state.set_value(initial, window, cx);
+            input_state
+        });
+        Self {
+            input,
+            mode: PipeOutputMode::default(),
+            recent_commands,
+        }
+    }
+
+    /// Get the command and where its output goes
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `(String, PipeOutputMode)`: The trimmed command and the output mode
+    fn request(&self, cx: &App) -> (String, PipeOutputMode) {
+        (self.input.read(cx).value().trim().to_string(), self.mode)
+    }
+}
+
+impl Render for PipeCommandForm {
+    /// Render the command input, the output mode buttons and the recent commands
+    ///
+    /// ### Arguments
+    /// - `_window`: The parent window (unused)
+    /// - `cx`: The form context
+    ///
+    /// ### Returns
+    /// - `impl IntoElement`: The rendered form
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut modes = h_flex().gap_1();
+        for (index, mode) in PipeOutputMode::ALL.into_iter().enumerate() {
+            modes = modes.child(
+                Button::new(("pipe-output-mode", index))
+                    .label(mode.label())
+                    .small()
+                    .map(|button| {
+                        if mode == self.mode {
+                            button.primary()
+                        } else {
+                            button.ghost()
+                        }
+                    })
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.mode = mode;
+                        cx.notify();
+                    })),
+            );
+        }
+        let mut recent = v_flex().w_full();
+        for (index, command) in self.recent_commands.iter().enumerate() {
+            let value = command.clone();
+            recent = recent.child(
+                div()
+                    .id(("pipe-recent-command", index))
+                    .w_full()
+                    .px_2()
+                    .py_0p5()
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .text_sm()
+                    .overflow_hidden()
+                    .whitespace_nowrap()
+                    .text_ellipsis()
+                    .hover(|this| this.bg(cx.theme().muted))
+                    .child(SharedString::from(command.clone()))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.input.update(cx, |input_state, cx| {
+                            input_state.set_value(value.clone(), window, cx);
+                        });
+                    })),
+            );
+        }
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(Input::new(&self.input))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Output:"),
+                    )
+                    .child(modes),
+            )
+            .when(!self.recent_commands.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("Recent commands"),
+                )
+                .child(recent)
+            })
+    }
+}
+
+impl Fulgur {
+    /// Show the dialog piping the selection, or the whole buffer, through a shell command
+    ///
+    /// ### Arguments
+    /// - `window`: The window to show the dialog in
+    /// - `cx`: The application context
+    pub fn show_pipe_command_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.get_active_editor_tab(cx).is_none() {
+            window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from("Open a text tab to pipe it through a command"),
+                ),
+                cx,
+            );
+            return;
+        }
+        let recent_commands = self.settings.app_settings.recent_pipe_commands.clone();
+        let form = cx.new(|cx| PipeCommandForm::new(recent_commands, window, cx));
+        let entity = cx.entity().clone();
+        window.open_alert_dialog(cx, move |modal, window, cx| {
+            let focus_handle = form.read(cx).input.read(cx).focus_handle(cx);
+            window.focus(&focus_handle, cx);
+            let form_for_ok = form.clone();
+            let entity_for_ok = entity.clone();
+            modal
+                .title(div().text_size(px(16.)).child("Pipe through command..."))
+                .keyboard(true)
+                .button_props(
+                    DialogButtonProps::default()
+                        .show_cancel(true)
+                        .cancel_text("Cancel")
+                        .cancel_variant(ButtonVariant::Secondary)
+                        .ok_text("Run")
+                        .ok_variant(ButtonVariant::Primary),
+                )
+                .close_button(false)
+                .child(form.clone())
+                .on_ok(move |_, window, cx| {
+                    let (command, mode) = form_for_ok.read(cx).request(cx);
+                    if command.is_empty() {
+                        return false;
+                    }
+                    entity_for_ok.update(cx, |this, cx| {
+                        this.pipe_through_command(command, mode, window, cx);
+                    });
+                    true
+                })
+                .on_cancel(|_, _, _| true)
+        });
+    }
+}
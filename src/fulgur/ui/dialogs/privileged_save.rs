@@ -0,0 +1,58 @@
+use crate::fulgur::ui::tabs::tab::TabId;
+use std::path::Path;
+
+use gpui::{Context, ParentElement, Styled, Window, div, px};
+use gpui_component::{WindowExt, button::ButtonVariant, dialog::DialogButtonProps, v_flex};
+
+use crate::fulgur::Fulgur;
+
+impl Fulgur {
+    /// Offer to retry a save that was refused for lack of permissions with elevated privileges.
+    ///
+    /// ### Arguments
+    /// - `tab_id`: Stable id of the tab being saved
+    /// - `path`: The path of the file that could not be written
+    /// - `window`: The window to show the dialog in
+    /// - `cx`: The application context
+    pub fn show_privileged_save_dialog(
+        &self,
+        tab_id: TabId,
+        path: &Path,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let entity = cx.entity().clone();
+        let path = path.display().to_string();
+        let helper = self.settings.app_settings.privileged_save_helper.clone();
+        window.open_alert_dialog(cx, move |modal, _, _| {
+            let entity_for_ok = entity.clone();
+            modal
+                .title(div().text_size(px(16.)).child("Permission Denied"))
+                .keyboard(true)
+                .button_props(
+                    DialogButtonProps::default()
+                        .show_cancel(true)
+                        .cancel_text("Cancel")
+                        .cancel_variant(ButtonVariant::Secondary)
+                        .ok_text("Save with Elevated Privileges")
+                        .ok_variant(ButtonVariant::Primary),
+                )
+                .close_button(false)
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(format!("You do not have permission to write '{path}'."))
+                        .child(format!(
+                            "Save it as administrator through '{helper}'? You may be asked for your password."
+                        )),
+                )
+                .on_ok(move |_, window, cx| {
+                    entity_for_ok.update(cx, |this, cx| {
+                        this.save_file_with_privileges(tab_id, window, cx);
+                    });
+                    true
+                })
+                .on_cancel(|_, _, _| true)
+        });
+    }
+}
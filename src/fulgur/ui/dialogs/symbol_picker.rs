@@ -0,0 +1,207 @@
+use gpui::{
+    Context, Entity, InteractiveElement, IntoElement, ParentElement, Render, SharedString,
+    StatefulInteractiveElement, Styled, Subscription, WeakEntity, Window, div,
+};
+use gpui_component::{
+    ActiveTheme, WindowExt, h_flex,
+    input::{Input, InputEvent, InputState},
+    scroll::ScrollableElement,
+    v_flex,
+};
+
+use super::file_browser::browser_list_height;
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::Jump,
+    languages::symbols::{DocumentSymbol, filter_symbols},
+};
+
+/// Prefix switching the jump dialog from line numbers to symbol search
+pub const SYMBOL_PREFIX: char = '@';
+
+/// Maximum number of symbols listed below the jump input
+const MAX_LISTED_SYMBOLS: usize = 50;
+
+/// Resolve an `@symbol` destination to the position of its best match
+///
+/// ### Arguments
+/// - `destination`: The text typed in the jump dialog
+/// - `symbols`: The symbols of the active document
+///
+/// ### Returns
+/// - `Some(Jump)`: The position of the best matching symbol name
+/// - `None`: The destination is not a symbol search or nothing matches
+#[must_use]
+pub fn symbol_jump(destination: &str, symbols: &[DocumentSymbol]) -> Option<Jump> {
+    let query = destination.trim_start().strip_prefix(SYMBOL_PREFIX)?;
+    filter_symbols(symbols, query).first().map(|symbol| Jump {
+        line: symbol.line,
+        character: Some(symbol.character),
+    })
+}
+
+/// The jump dialog body: the destination input, followed by the matching
+/// symbols of the active document while an `@symbol` search is typed.
+pub struct SymbolPicker {
+    fulgur: WeakEntity<Fulgur>,
+    input: Entity<InputState>,
+    symbols: Vec<DocumentSymbol>,
+    #[allow(dead_code, reason = "RAII guard: keeps the subscription alive")]
+    input_subscription: Subscription,
+}
+
+impl SymbolPicker {
+    /// Create the picker over an existing input
+    ///
+    /// ### Arguments
+    /// - `fulgur`: Weak handle to the window the chosen symbol is jumped to in
+    /// - `input`: The destination input
+    /// - `symbols`: The symbols of the active document
+    /// - `cx`: The picker context
+    ///
+    /// ### Returns
+    /// - `SymbolPicker`: The new picker view
+    pub fn new(
+        fulgur: WeakEntity<Fulgur>,
+        input: Entity<InputState>,
+        symbols: Vec<DocumentSymbol>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input_subscription = cx.subscribe(&input, |_, _, ev: &InputEvent, cx| {
+            if let InputEvent::Change = ev {
+                cx.notify();
+            }
+        });
+        Self {
+            fulgur,
+            input,
+            symbols,
+            input_subscription,
+        }
+    }
+
+    /// Get the symbols of the active document
+    ///
+    /// ### Returns
+    /// - `&[DocumentSymbol]`: The symbols the picker searches
+    pub fn symbols(&self) -> &[DocumentSymbol] {
+        &self.symbols
+    }
+
+    /// Replace the symbols the picker searches, once they were extracted
+    ///
+    /// ### Arguments
+    /// - `symbols`: The symbols of the active document
+    /// - `cx`: The picker context
+    pub fn set_symbols(&mut self, symbols: Vec<DocumentSymbol>, cx: &mut Context<Self>) {
+        self.symbols = symbols;
+        cx.notify();
+    }
+}
+
+impl Render for SymbolPicker {
+    /// Render the input and, during an `@symbol` search, the matching symbols
+    ///
+    /// ### Arguments
+    /// - `_window`: The parent window (unused)
+    /// - `cx`: The picker context
+    ///
+    /// ### Returns
+    /// - `impl IntoElement`: The rendered picker
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let container = v_flex().w_full().gap_1().child(Input::new(&self.input));
+        let value = self.input.read(cx).value();
+        let Some(query) = value.trim_start().strip_prefix(SYMBOL_PREFIX) else {
+            return container;
+        };
+        let matches = filter_symbols(&self.symbols, query);
+        if matches.is_empty() {
+            return container.child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("No matching symbols"),
+            );
+        }
+        let mut list = v_flex()
+            .id("symbol-picker-list")
+            .overflow_y_scrollbar()
+            .max_h(browser_list_height())
+            .w_full();
+        for (index, symbol) in matches.into_iter().take(MAX_LISTED_SYMBOLS).enumerate() {
+            let jump = Jump {
+                line: symbol.line,
+                character: Some(symbol.character),
+            };
+            let fulgur = self.fulgur.clone();
+            list = list.child(
+                h_flex()
+                    .id(("symbol-picker-row", index))
+                    .w_full()
+                    .gap_2()
+                    .px_2()
+                    .py_0p5()
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .hover(|this| this.bg(cx.theme().muted))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(symbol.kind.label()),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(SharedString::from(symbol.name.clone())),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child((symbol.line + 1).to_string()),
+                    )
+                    .on_click(move |_, window, cx| {
+                        _ = fulgur.update(cx, |this, cx| {
+                            this.pending_jump = Some(jump);
+                            cx.notify();
+                        });
+                        window.close_dialog(cx);
+                    }),
+            );
+        }
+        container.child(list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::symbol_jump;
+    use crate::fulgur::languages::symbols::{DocumentSymbol, SymbolKind};
+
+    fn symbol(name: &str, line: u32) -> DocumentSymbol {
+        DocumentSymbol {
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            range: 0..1,
+            line,
+            character: 4,
+            depth: 0,
+        }
+    }
+
+    #[test]
+    fn test_symbol_jump_targets_the_best_match() {
+        let symbols = vec![symbol("load_state", 3), symbol("save_state", 10)];
+        let jump = symbol_jump("@save", &symbols).expect("a symbol should match");
+        assert_eq!(jump.line, 10);
+        assert_eq!(jump.character, Some(4));
+    }
+
+    #[test]
+    fn test_symbol_jump_ignores_line_destinations_and_misses() {
+        let symbols = vec![symbol("load_state", 3)];
+        assert!(symbol_jump("12", &symbols).is_none());
+        assert!(symbol_jump("@zzz", &symbols).is_none());
+    }
+}
@@ -7,6 +7,9 @@ pub enum CustomIcon {
     AddColumnBefore,
     AddRowAbove,
     AddRowBelow,
+    AlignCenter,
+    AlignLeft,
+    AlignRight,
     ALargeSmall,
     Asterisk,
     Bold,
@@ -17,6 +20,7 @@ pub enum CustomIcon {
     ChevronUp,
     CircleCheck,
     CircleX,
+    ClipboardPaste,
     Close,
     Code,
     Computer,
@@ -42,6 +46,7 @@ pub enum CustomIcon {
     Italic,
     Laptop,
     Link,
+    Lock,
     Minus,
     Palette,
     Plus,
@@ -77,6 +82,9 @@ impl CustomIcon {
             Self::AddColumnBefore => "icons/add-column-before.svg",
             Self::AddRowAbove => "icons/add-row-above.svg",
             Self::AddRowBelow => "icons/add-row-below.svg",
+            Self::AlignCenter => "icons/align-center.svg",
+            Self::AlignLeft => "icons/align-left.svg",
+            Self::AlignRight => "icons/align-right.svg",
             Self::ALargeSmall => "icons/a-large-small.svg",
             Self::Asterisk => "icons/asterisk.svg",
             Self::Bold => "icons/bold.svg",
@@ -85,6 +93,7 @@ impl CustomIcon {
             Self::ChevronLeft => "icons/chevron-left.svg",
             Self::ChevronRight => "icons/chevron-right.svg",
             Self::ChevronUp => "icons/chevron-up.svg",
+            Self::ClipboardPaste => "icons/clipboard-paste.svg",
             Self::Close => "icons/close.svg",
             Self::Code => "icons/code.svg",
             Self::CircleCheck => "icons/circle-check.svg",
@@ -111,6 +120,7 @@ impl CustomIcon {
             Self::Italic => "icons/italic.svg",
             Self::Laptop => "icons/laptop.svg",
             Self::Link => "icons/link.svg",
+            Self::Lock => "icons/lock.svg",
             Self::LoaderCircle => "icons/loader-circle.svg",
             Self::Minus => "icons/minus.svg",
             Self::Palette => "icons/palette.svg",
@@ -146,6 +146,7 @@ impl Fulgur {
             editor.log_full = false;
             editor.log_content = Some(log_content);
         });
+        self.refresh_split_panes(window, cx);
         self.log_tail_state
             .insert(tab_id, LogTailState::new(byte_offset, dropped));
         self.start_log_poll_task(tab_id, path, window, cx);
@@ -0,0 +1,358 @@
+//! A Markdown preview rendered block by block, so that it can follow the editor and lead back to it.
+
+use crate::fulgur::{
+    ui::{icons::CustomIcon, tabs::tab::TabId},
+    utils::{
+        markdown_blocks::{MarkdownBlock, block_at_line, split_markdown_blocks},
+        markdown_extensions::{FrontMatter, preview_source, task_link_line},
+        markdown_images::rewrite_markdown_image_paths,
+        sanitize::sanitize_markdown_preview,
+    },
+};
+use gpui::{
+    AnyElement, App, AppContext, ClickEvent, Context, Div, ElementId, Entity, InteractiveElement,
+    IntoElement, ParentElement, Pixels, ScrollHandle, SharedString, Stateful,
+    StatefulInteractiveElement, Styled, Window, div, point, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, StyledExt, h_flex,
+    scroll::ScrollableElement,
+    text::{TextView, TextViewState},
+    v_flex,
+};
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// Line height of the editor relative to its font size (gpui's default), used to
+/// estimate the first line the editor shows from its scroll offset
+const EDITOR_LINE_HEIGHT: f32 = 1.618;
+
+/// Width of the key column of the front matter table
+const FRONT_MATTER_KEY_WIDTH: f32 = 160.0;
+
+/// Called with the first source line of a double-clicked preview block
+pub type JumpToSource = Rc<dyn Fn(usize, &mut Window, &mut App)>;
+
+/// Called with the target of a clicked link, as written in the source
+pub type FollowLink = Rc<dyn Fn(&str, &mut Window, &mut App)>;
+
+/// Called with the source line of a clicked task-list checkbox
+pub type ToggleTask = Rc<dyn Fn(usize, &mut Window, &mut App)>;
+
+/// A line of a Markdown document shown by a preview, remembered to come back to it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PreviewLocation {
+    pub tab_id: TabId, // The editor tab of the document
+    pub line: usize,   // The zero-based source line
+}
+
+/// The rendering of a block of the preview
+struct BlockView {
+    text: String, // The Markdown rendered by `state`
+    state: Entity<TextViewState>,
+}
+
+/// The blocks of a Markdown preview and the editor position it last followed
+pub struct MarkdownPreviewState {
+    scroll_handle: ScrollHandle,
+    source: String,
+    base_dir: Option<PathBuf>,
+    blocks: Vec<MarkdownBlock>,
+    views: Vec<BlockView>,
+    front_matter: Option<FrontMatter>, // Shown by the first block, instead of its Markdown
+    front_matter_expanded: bool,
+    followed_cursor_line: Option<usize>,
+    followed_scroll_top: Option<Pixels>,
+}
+
+impl MarkdownPreviewState {
+    /// Create an empty preview
+    ///
+    /// ### Returns
+    /// - `MarkdownPreviewState`: A preview without blocks, scrolled to the top
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            scroll_handle: ScrollHandle::new(),
+            source: String::new(),
+            base_dir: None,
+            blocks: Vec::new(),
+            views: Vec::new(),
+            front_matter: None,
+            front_matter_expanded: false,
+            followed_cursor_line: None,
+            followed_scroll_top: None,
+        }
+    }
+
+    /// Render a new version of the Markdown source
+    ///
+    /// ### Description
+    /// Blocks whose text did not change keep their state, so that editing one
+    /// paragraph does not parse the whole document again. The front matter gets
+    /// a block of its own, shown as a table.
+    ///
+    /// ### Arguments
+    /// - `source`: The Markdown source
+    /// - `base_dir`: The directory of the source file, used to resolve local images
+    /// - `cx`: The application context
+    pub fn set_source(&mut self, source: &str, base_dir: Option<&Path>, cx: &mut Context<Self>) {
+        if source == self.source && base_dir == self.base_dir.as_deref() {
+            return;
+        }
+        let (front_matter, text, ast) = preview_source(source);
+        self.blocks = split_markdown_blocks(&text, ast.as_ref());
+        if let Some(front_matter) = &front_matter {
+            self.blocks.insert(
+                0,
+                MarkdownBlock {
+                    lines: front_matter.lines.clone(),
+                    text: String::new(),
+                },
+            );
+        }
+        self.front_matter = front_matter;
+        self.views.truncate(self.blocks.len());
+        for (index, block) in self.blocks.iter().enumerate() {
+            // Multi-line raw-HTML blocks are collapsed so the Markdown renderer
+            // never shapes a run containing a newline (see `sanitize_markdown_preview`)
+            let text =
+                sanitize_markdown_preview(&rewrite_markdown_image_paths(&block.text, base_dir));
+            if let Some(view) = self.views.get_mut(index) {
+                if view.text != text {
+                    view.state.update(cx, |state, cx| state.set_text(&text, cx));
+                    view.text = text;
+                }
+            } else {
+                let state = cx.new(|cx| TextViewState::markdown(&text, cx));
+                self.views.push(BlockView { text, state });
+            }
+        }
+        self.source = source.to_string();
+        self.base_dir = base_dir.map(Path::to_path_buf);
+    }
+
+    /// Scroll to the block the editor is at when its cursor moved or it scrolled
+    ///
+    /// ### Description
+    /// A moved cursor wins over a scroll, since moving the cursor out of view
+    /// scrolls the editor too.
+    ///
+    /// ### Arguments
+    /// - `cursor_line`: The zero-based line of the editor cursor
+    /// - `scroll_top`: The vertical scroll offset of the editor
+    /// - `font_size`: The font size of the editor, in pixels
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn follow_editor(&mut self, cursor_line: usize, scroll_top: Pixels, font_size: f32) {
+        let cursor_moved = self.followed_cursor_line.replace(cursor_line) != Some(cursor_line);
+        let scrolled = self
+            .followed_scroll_top
+            .replace(scroll_top)
+            .is_some_and(|followed| followed != scroll_top);
+        if cursor_moved {
+            self.scroll_to_line(cursor_line);
+        } else if scrolled {
+            let line_height = (font_size * EDITOR_LINE_HEIGHT).max(1.0);
+            let top_line = (-f32::from(scroll_top) / line_height).max(0.0) as usize;
+            self.scroll_to_line(top_line);
+        }
+    }
+
+    /// Scroll the preview so that a source line is at its top
+    ///
+    /// ### Description
+    /// Within a block, the position is interpolated between its first and last
+    /// line. Before the first layout, the block is only scrolled into view.
+    ///
+    /// ### Arguments
+    /// - `line`: The zero-based source line
+    #[allow(clippy::cast_precision_loss)]
+    pub fn scroll_to_line(&self, line: usize) {
+        let Some(index) = block_at_line(&self.blocks, line) else {
+            return;
+        };
+        let Some(item) = self.scroll_handle.bounds_for_item(index) else {
+            self.scroll_handle.scroll_to_item(index);
+            return;
+        };
+        let lines = &self.blocks[index].lines;
+        let progress =
+            line.saturating_sub(lines.start).min(lines.len()) as f32 / lines.len().max(1) as f32;
+        let offset = self.scroll_handle.offset();
+        let top = f32::from(item.origin.y - self.scroll_handle.bounds().origin.y - offset.y)
+            + f32::from(item.size.height) * progress;
+        self.scroll_handle
+            .set_offset(point(offset.x, px(-top.max(0.0))));
+    }
+
+    /// Get the first source line of the block at the top of the preview
+    ///
+    /// ### Returns
+    /// - `usize`: The zero-based line, 0 before the first layout
+    #[must_use]
+    pub fn top_line(&self) -> usize {
+        let top = self.scroll_handle.bounds().origin.y;
+        (0..self.blocks.len())
+            .find(|&index| {
+                self.scroll_handle
+                    .bounds_for_item(index)
+                    .is_some_and(|item| item.bottom() > top)
+            })
+            .map_or(0, |index| self.blocks[index].lines.start)
+    }
+
+    /// Select the text of every block
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    pub fn select_all(&self, cx: &mut App) {
+        for view in &self.views {
+            view.state.update(cx, TextViewState::select_all);
+        }
+    }
+
+    /// Render the front matter as a table that can be collapsed
+    ///
+    /// ### Arguments
+    /// - `preview`: The entity of this preview
+    /// - `front_matter`: The front matter of the document
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `AnyElement`: The front matter, collapsed to its header by default
+    fn render_front_matter(
+        &self,
+        preview: &Entity<Self>,
+        front_matter: &FrontMatter,
+        cx: &App,
+    ) -> AnyElement {
+        let expanded = self.front_matter_expanded;
+        let count = front_matter.fields.len();
+        let preview = preview.clone();
+        v_flex()
+            .w_full()
+            .mb_2()
+            .text_sm()
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded_md()
+            .child(
+                h_flex()
+                    .id("front-matter-header")
+                    .gap_1()
+                    .px_2()
+                    .py_1()
+                    .cursor_pointer()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(Icon::new(if expanded {
+                        CustomIcon::ChevronDown
+                    } else {
+                        CustomIcon::ChevronRight
+                    }))
+                    .child(SharedString::from(format!(
+                        "Front matter ({count} {})",
+                        if count == 1 { "field" } else { "fields" }
+                    )))
+                    .on_click(move |_, _, cx| {
+                        cx.stop_propagation();
+                        preview.update(cx, |preview, cx| {
+                            preview.front_matter_expanded = !preview.front_matter_expanded;
+                            cx.notify();
+                        });
+                    }),
+            )
+            .when(expanded, |this| {
+                this.children(front_matter.fields.iter().map(|(key, value)| {
+                    h_flex()
+                        .items_start()
+                        .gap_2()
+                        .px_2()
+                        .py_1()
+                        .border_t_1()
+                        .border_color(cx.theme().border)
+                        .child(
+                            div()
+                                .w(px(FRONT_MATTER_KEY_WIDTH))
+                                .flex_none()
+                                .font_semibold()
+                                .child(SharedString::from(key.clone())),
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .child(SharedString::from(value.clone())),
+                        )
+                }))
+            })
+            .into_any_element()
+    }
+
+    /// Render the blocks of a preview in a scrollable column
+    ///
+    /// ### Arguments
+    /// - `preview`: The preview to render
+    /// - `id`: The element ID of the column
+    /// - `on_jump`: Called when a block is double-clicked
+    /// - `on_link`: Called when a link is clicked, instead of opening it in the browser
+    /// - `on_task`: Called when a task-list checkbox is clicked
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Stateful<Div>`: The scrollable preview
+    pub fn render(
+        preview: &Entity<Self>,
+        id: impl Into<ElementId>,
+        on_jump: &JumpToSource,
+        on_link: &FollowLink,
+        on_task: &ToggleTask,
+        cx: &App,
+    ) -> Stateful<Div> {
+        let this = preview.read(cx);
+        v_flex()
+            .id(id)
+            .size_full()
+            .overflow_y_scroll()
+            .track_scroll(&this.scroll_handle)
+            .children(this.blocks.iter().zip(&this.views).enumerate().map(
+                |(index, (block, view))| {
+                    let line = block.lines.start;
+                    let on_jump = on_jump.clone();
+                    let on_link = on_link.clone();
+                    let on_task = on_task.clone();
+                    let content = match this.front_matter.as_ref().filter(|_| index == 0) {
+                        Some(front_matter) => this.render_front_matter(preview, front_matter, cx),
+                        None => TextView::new(&view.state)
+                            .selectable(true)
+                            .on_link_click(move |url: &str, window, cx| {
+                                if let Some(line) = task_link_line(url) {
+                                    on_task(line, window, cx);
+                                } else {
+                                    on_link(url, window, cx);
+                                }
+                            })
+                            .into_any_element(),
+                    };
+                    div()
+                        .id(index)
+                        .w_full()
+                        .flex_none()
+                        .child(content)
+                        .on_click(move |event: &ClickEvent, window, cx| {
+                            if event.click_count() == 2 {
+                                on_jump(line, window, cx);
+                            }
+                        })
+                },
+            ))
+            .vertical_scrollbar(&this.scroll_handle)
+    }
+}
+
+impl Default for MarkdownPreviewState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,7 @@
+use crate::fulgur::utils::{
+    markdown_export::HtmlImages, rich_clipboard::RichTextFormat, structured_data::DataFormat,
+    text_transform::TextTransform,
+};
 use gpui::{SharedString, actions};
 use gpui_macros::Action;
 use std::path::PathBuf;
@@ -29,6 +33,31 @@ actions!(
         CheckForUpdates,
         PrintFile,
         ToggleColorPicker,
+        SplitRight,
+        SplitDown,
+        CloseSplit,
+        FocusOtherPane,
+        ToggleOutline,
+        ToggleJsonQuery,
+        GoToSymbol,
+        ToggleLineComment,
+        ToggleBlockComment,
+        ValidateData,
+        PrettyPrintData,
+        MinifyData,
+        SortDataKeys,
+        PipeThroughCommand,
+        ToggleTaskPanel,
+        RunLastTask,
+        StopTask,
+        ToggleTerminal,
+        NewTerminal,
+        CopyTerminalSelection,
+        PasteIntoTerminal,
+        FindInTerminal,
+        MarkdownLinkBack,
+        MarkdownLinkForward,
+        CheckMarkdownLinks,
     ]
 );
 
@@ -47,3 +76,23 @@ pub struct DockActivateTab(pub PathBuf);
 #[derive(Action, Clone, PartialEq)]
 #[action(namespace = fulgur, no_json)]
 pub struct DockActivateTabByTitle(pub SharedString);
+
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = fulgur, no_json)]
+pub struct TransformText(pub TextTransform);
+
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = fulgur, no_json)]
+pub struct ConvertData(pub DataFormat);
+
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = fulgur, no_json)]
+pub struct ExportMarkdownHtml(pub HtmlImages);
+
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = fulgur, no_json)]
+pub struct CopyAsRichText(pub RichTextFormat);
+
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = fulgur, no_json)]
+pub struct SendTerminalKey(pub SharedString);
@@ -1,10 +1,22 @@
 use super::actions::{
-    About, CheckForUpdates, ClearRecentFiles, CloseAllFiles, CloseFile, CloseWindow, FindInFile,
-    GetTheme, JumpToLine, NewFile, NewWindow, NextTab, NoneAction, OpenFile, OpenPath,
-    OpenRecentFile, OpenRemote, PreviousTab, PrintFile, Quit, SaveFile, SaveFileAs, SelectTheme,
-    SettingsTab, ToggleColorPicker,
+    About, CheckForUpdates, CheckMarkdownLinks, ClearRecentFiles, CloseAllFiles, CloseFile,
+    CloseSplit, CloseWindow, ConvertData, CopyAsRichText, ExportMarkdownHtml, FindInFile,
+    FocusOtherPane, GetTheme, GoToSymbol, JumpToLine, MarkdownLinkBack, MarkdownLinkForward,
+    MinifyData, NewFile, NewWindow, NextTab, NoneAction, OpenFile, OpenPath, OpenRecentFile,
+    OpenRemote, PipeThroughCommand, PrettyPrintData, PreviousTab, PrintFile, Quit, RunLastTask,
+    SaveFile, SaveFileAs, SelectTheme, SettingsTab, SortDataKeys, SplitDown, SplitRight, StopTask,
+    ToggleBlockComment, ToggleColorPicker, ToggleJsonQuery, ToggleLineComment, ToggleOutline,
+    ToggleTaskPanel, TransformText, ValidateData,
+};
+#[cfg(target_os = "linux")]
+use super::actions::{NewTerminal, ToggleTerminal};
+use crate::fulgur::{
+    Fulgur,
+    utils::{
+        markdown_export::HtmlImages, rich_clipboard::RichTextFormat, structured_data::DataFormat,
+        text_transform::TextTransform,
+    },
 };
-use crate::fulgur::Fulgur;
 use gpui::{Context, Menu, MenuItem};
 #[cfg(not(target_os = "macos"))]
 use gpui_component::GlobalState;
@@ -69,6 +81,20 @@ pub fn build_menus(recent_files: &[PathBuf], update_link: Option<&str>) -> Vec<M
                 MenuItem::action("Save as...", SaveFileAs),
                 MenuItem::separator(),
                 MenuItem::action("Print...", PrintFile),
+                MenuItem::Submenu(Menu {
+                    name: "Export Markdown as HTML".into(),
+                    disabled: false,
+                    items: vec![
+                        MenuItem::action(
+                            "With Embedded Images...",
+                            ExportMarkdownHtml(HtmlImages::Inline),
+                        ),
+                        MenuItem::action(
+                            "With Image Files...",
+                            ExportMarkdownHtml(HtmlImages::CopyFiles),
+                        ),
+                    ],
+                }),
                 MenuItem::separator(),
                 MenuItem::action("Close file", CloseFile),
                 MenuItem::action("Close all files", CloseAllFiles),
@@ -83,9 +109,25 @@ pub fn build_menus(recent_files: &[PathBuf], update_link: Option<&str>) -> Vec<M
                 MenuItem::separator(),
                 MenuItem::action("Cut", gpui_component::input::Cut),
                 MenuItem::action("Copy", gpui_component::input::Copy),
+                MenuItem::action("Copy as HTML", CopyAsRichText(RichTextFormat::Html)),
+                MenuItem::action("Copy as RTF", CopyAsRichText(RichTextFormat::Rtf)),
                 MenuItem::action("Paste", gpui_component::input::Paste),
                 MenuItem::separator(),
                 MenuItem::action("Find & Replace", FindInFile),
+                MenuItem::separator(),
+                MenuItem::action("Toggle Line Comment", ToggleLineComment),
+                MenuItem::action("Toggle Block Comment", ToggleBlockComment),
+                MenuItem::Submenu(Menu {
+                    name: "Transform".into(),
+                    disabled: false,
+                    items: transform_menu_items(),
+                }),
+                MenuItem::Submenu(Menu {
+                    name: "Structured Data".into(),
+                    disabled: false,
+                    items: structured_data_menu_items(),
+                }),
+                MenuItem::action("Pipe Through Command...", PipeThroughCommand),
             ],
         },
         Menu {
@@ -93,7 +135,17 @@ pub fn build_menus(recent_files: &[PathBuf], update_link: Option<&str>) -> Vec<M
             disabled: false,
             items: vec![
                 MenuItem::action("Color picker", ToggleColorPicker),
+                MenuItem::action("Outline", ToggleOutline),
+                MenuItem::action("JSON Query", ToggleJsonQuery),
+                #[cfg(target_os = "linux")]
+                MenuItem::action("Terminal", ToggleTerminal),
+                #[cfg(target_os = "linux")]
+                MenuItem::action("New Terminal", NewTerminal),
                 MenuItem::separator(),
+                MenuItem::action("Split Right", SplitRight),
+                MenuItem::action("Split Down", SplitDown),
+                MenuItem::action("Focus Next Pane", FocusOtherPane),
+                MenuItem::action("Close Split", CloseSplit),
             ],
         },
         Menu {
@@ -104,11 +156,66 @@ pub fn build_menus(recent_files: &[PathBuf], update_link: Option<&str>) -> Vec<M
                 MenuItem::action("Previous Tab", PreviousTab),
                 MenuItem::Separator,
                 MenuItem::action("Jump to line", JumpToLine),
+                MenuItem::action("Go to Symbol", GoToSymbol),
+                MenuItem::Separator,
+                MenuItem::action("Link Back", MarkdownLinkBack),
+                MenuItem::action("Link Forward", MarkdownLinkForward),
+                MenuItem::action("Check Markdown Links", CheckMarkdownLinks),
+            ],
+        },
+        Menu {
+            name: "Tasks".into(),
+            disabled: false,
+            items: vec![
+                MenuItem::action("Run Last Task", RunLastTask),
+                MenuItem::action("Stop Task", StopTask),
+                MenuItem::Separator,
+                MenuItem::action("Task Panel", ToggleTaskPanel),
             ],
         },
     ]
 }
 
+/// Build the items of the Transform submenu, with a separator between groups
+///
+/// ### Returns
+/// - `Vec<MenuItem>`: One item per text transform
+fn transform_menu_items() -> Vec<MenuItem> {
+    let mut items = Vec::new();
+    for (index, group) in TextTransform::GROUPS.iter().enumerate() {
+        if index > 0 {
+            items.push(MenuItem::separator());
+        }
+        items.extend(
+            group
+                .iter()
+                .map(|transform| MenuItem::action(transform.label(), TransformText(*transform))),
+        );
+    }
+    items
+}
+
+/// Build the items of the Structured Data submenu
+///
+/// ### Returns
+/// - `Vec<MenuItem>`: The rewrite commands followed by one conversion per format
+fn structured_data_menu_items() -> Vec<MenuItem> {
+    let mut items = vec![
+        MenuItem::action("Validate", ValidateData),
+        MenuItem::action("Pretty Print", PrettyPrintData),
+        MenuItem::action("Minify", MinifyData),
+        MenuItem::action("Sort Keys", SortDataKeys),
+        MenuItem::separator(),
+    ];
+    items.extend(DataFormat::ALL.iter().map(|format| {
+        MenuItem::action(
+            format!("Convert to {}", format.label()),
+            ConvertData(*format),
+        )
+    }));
+    items
+}
+
 impl Fulgur {
     /// Set the application menus and sync them to the `AppMenuBar` on non-macOS platforms.
     ///
@@ -1,12 +1,23 @@
 use super::actions::{
-    CloseAllFiles, CloseFile, FindInFile, JumpToLine, NewFile, NewWindow, NextTab, OpenFile,
-    OpenPath, OpenRemote, PreviousTab, PrintFile, Quit, SaveFile, SaveFileAs, ToggleColorPicker,
+    CloseAllFiles, CloseFile, FindInFile, FocusOtherPane, GoToSymbol, JumpToLine, NewFile,
+    NewWindow, NextTab, OpenFile, OpenPath, OpenRemote, PipeThroughCommand, PreviousTab, PrintFile,
+    Quit, RunLastTask, SaveFile, SaveFileAs, SplitRight, ToggleBlockComment, ToggleColorPicker,
+    ToggleJsonQuery, ToggleLineComment, TransformText,
 };
+#[cfg(target_os = "linux")]
+use super::actions::{
+    CopyTerminalSelection, FindInTerminal, NewTerminal, PasteIntoTerminal, SendTerminalKey,
+    ToggleTerminal,
+};
+use crate::fulgur::utils::text_transform::TextTransform;
 use gpui::KeyBinding;
 
 /// Key context set on the application content element, used to scope keybindings.
 pub const KEY_CONTEXT_FULGUR: &str = "Fulgur";
 
+/// Key context set on the grid of the terminal panel, which takes most keystrokes for the shell.
+pub const KEY_CONTEXT_TERMINAL: &str = "Terminal";
+
 /// Context predicate for keybindings scoped to the application content.
 const SCOPED_BINDING_PREDICATE: &str = "Fulgur || (Fulgur > Input)";
 
@@ -29,6 +40,25 @@ enum KeybindingDispatchAction {
     JumpToLine,
     PrintFile,
     ToggleColorPicker,
+    ToggleJsonQuery,
+    SplitRight,
+    FocusOtherPane,
+    GoToSymbol,
+    ToggleLineComment,
+    ToggleBlockComment,
+    PipeThroughCommand,
+    RunLastTask,
+    Transform(TextTransform),
+    #[cfg(target_os = "linux")]
+    ToggleTerminal,
+    #[cfg(target_os = "linux")]
+    NewTerminal,
+    #[cfg(target_os = "linux")]
+    CopyTerminalSelection,
+    #[cfg(target_os = "linux")]
+    PasteIntoTerminal,
+    #[cfg(target_os = "linux")]
+    FindInTerminal,
 }
 
 /// A platform keybinding dispatch specification used to build runtime keybindings.
@@ -101,6 +131,53 @@ impl KeybindingDispatchSpec {
             KeybindingDispatchAction::ToggleColorPicker => {
                 KeyBinding::new(self.keystroke, ToggleColorPicker, context)
             }
+            KeybindingDispatchAction::ToggleJsonQuery => {
+                KeyBinding::new(self.keystroke, ToggleJsonQuery, context)
+            }
+            KeybindingDispatchAction::SplitRight => {
+                KeyBinding::new(self.keystroke, SplitRight, context)
+            }
+            KeybindingDispatchAction::FocusOtherPane => {
+                KeyBinding::new(self.keystroke, FocusOtherPane, context)
+            }
+            KeybindingDispatchAction::GoToSymbol => {
+                KeyBinding::new(self.keystroke, GoToSymbol, context)
+            }
+            KeybindingDispatchAction::ToggleLineComment => {
+                KeyBinding::new(self.keystroke, ToggleLineComment, context)
+            }
+            KeybindingDispatchAction::ToggleBlockComment => {
+                KeyBinding::new(self.keystroke, ToggleBlockComment, context)
+            }
+            KeybindingDispatchAction::PipeThroughCommand => {
+                KeyBinding::new(self.keystroke, PipeThroughCommand, context)
+            }
+            KeybindingDispatchAction::RunLastTask => {
+                KeyBinding::new(self.keystroke, RunLastTask, context)
+            }
+            KeybindingDispatchAction::Transform(transform) => {
+                KeyBinding::new(self.keystroke, TransformText(transform), context)
+            }
+            #[cfg(target_os = "linux")]
+            KeybindingDispatchAction::ToggleTerminal => {
+                KeyBinding::new(self.keystroke, ToggleTerminal, context)
+            }
+            #[cfg(target_os = "linux")]
+            KeybindingDispatchAction::NewTerminal => {
+                KeyBinding::new(self.keystroke, NewTerminal, context)
+            }
+            #[cfg(target_os = "linux")]
+            KeybindingDispatchAction::CopyTerminalSelection => {
+                KeyBinding::new(self.keystroke, CopyTerminalSelection, context)
+            }
+            #[cfg(target_os = "linux")]
+            KeybindingDispatchAction::PasteIntoTerminal => {
+                KeyBinding::new(self.keystroke, PasteIntoTerminal, context)
+            }
+            #[cfg(target_os = "linux")]
+            KeybindingDispatchAction::FindInTerminal => {
+                KeyBinding::new(self.keystroke, FindInTerminal, context)
+            }
         }
     }
 }
@@ -128,7 +205,22 @@ impl KeybindingDispatchAction {
             | Self::PreviousTab
             | Self::JumpToLine
             | Self::PrintFile
-            | Self::ToggleColorPicker => Some(SCOPED_BINDING_PREDICATE),
+            | Self::ToggleColorPicker
+            | Self::ToggleJsonQuery
+            | Self::SplitRight
+            | Self::FocusOtherPane
+            | Self::GoToSymbol
+            | Self::ToggleLineComment
+            | Self::ToggleBlockComment
+            | Self::PipeThroughCommand
+            | Self::RunLastTask
+            | Self::Transform(_) => Some(SCOPED_BINDING_PREDICATE),
+            #[cfg(target_os = "linux")]
+            Self::ToggleTerminal | Self::NewTerminal => None,
+            #[cfg(target_os = "linux")]
+            Self::CopyTerminalSelection | Self::PasteIntoTerminal | Self::FindInTerminal => {
+                Some(KEY_CONTEXT_TERMINAL)
+            }
         }
     }
 }
@@ -199,24 +291,165 @@ fn default_keybinding_dispatch_specs() -> Vec<KeybindingDispatchSpec> {
         KeybindingDispatchSpec::new("cmd-shift-c", KeybindingDispatchAction::ToggleColorPicker),
         #[cfg(not(target_os = "macos"))]
         KeybindingDispatchSpec::new("ctrl-shift-c", KeybindingDispatchAction::ToggleColorPicker),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new("cmd-shift-j", KeybindingDispatchAction::ToggleJsonQuery),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new("ctrl-shift-j", KeybindingDispatchAction::ToggleJsonQuery),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new("cmd-\\", KeybindingDispatchAction::SplitRight),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new("ctrl-\\", KeybindingDispatchAction::SplitRight),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new("cmd-k o", KeybindingDispatchAction::FocusOtherPane),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new("ctrl-k o", KeybindingDispatchAction::FocusOtherPane),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new("cmd-r", KeybindingDispatchAction::GoToSymbol),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new("ctrl-r", KeybindingDispatchAction::GoToSymbol),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new("cmd-/", KeybindingDispatchAction::ToggleLineComment),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new("ctrl-/", KeybindingDispatchAction::ToggleLineComment),
+        KeybindingDispatchSpec::new("alt-shift-a", KeybindingDispatchAction::ToggleBlockComment),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new("cmd-k p", KeybindingDispatchAction::PipeThroughCommand),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new("ctrl-k p", KeybindingDispatchAction::PipeThroughCommand),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new("cmd-shift-b", KeybindingDispatchAction::RunLastTask),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new("ctrl-shift-b", KeybindingDispatchAction::RunLastTask),
+        KeybindingDispatchSpec::new(
+            "f9",
+            KeybindingDispatchAction::Transform(TextTransform::SortNatural),
+        ),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new(
+            "cmd-k u",
+            KeybindingDispatchAction::Transform(TextTransform::UpperCase),
+        ),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new(
+            "ctrl-k u",
+            KeybindingDispatchAction::Transform(TextTransform::UpperCase),
+        ),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new(
+            "cmd-k l",
+            KeybindingDispatchAction::Transform(TextTransform::LowerCase),
+        ),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new(
+            "ctrl-k l",
+            KeybindingDispatchAction::Transform(TextTransform::LowerCase),
+        ),
+        #[cfg(target_os = "macos")]
+        KeybindingDispatchSpec::new(
+            "cmd-j",
+            KeybindingDispatchAction::Transform(TextTransform::JoinLines),
+        ),
+        #[cfg(not(target_os = "macos"))]
+        KeybindingDispatchSpec::new(
+            "ctrl-j",
+            KeybindingDispatchAction::Transform(TextTransform::JoinLines),
+        ),
+        #[cfg(target_os = "linux")]
+        KeybindingDispatchSpec::new("ctrl-`", KeybindingDispatchAction::ToggleTerminal),
+        #[cfg(target_os = "linux")]
+        KeybindingDispatchSpec::new("ctrl-shift-`", KeybindingDispatchAction::NewTerminal),
+        #[cfg(target_os = "linux")]
+        KeybindingDispatchSpec::new(
+            "ctrl-shift-c",
+            KeybindingDispatchAction::CopyTerminalSelection,
+        ),
+        #[cfg(target_os = "linux")]
+        KeybindingDispatchSpec::new("ctrl-shift-v", KeybindingDispatchAction::PasteIntoTerminal),
+        #[cfg(target_os = "linux")]
+        KeybindingDispatchSpec::new("ctrl-shift-f", KeybindingDispatchAction::FindInTerminal),
     ]
 }
 
+/// Get the application shortcuts the terminal takes for the shell instead.
+///
+/// ### Description
+/// Control with a single key means something to the shell (Control-W deletes a
+/// word, Control-R searches the history), so inside the terminal grid those
+/// shortcuts are sent to the shell. Chords starting with such a key are
+/// returned too, so they can be disabled there rather than wait for their
+/// second key.
+///
+/// ### Parameters:
+/// - `specs`: The application keybinding specifications.
+///
+/// ### Returns:
+/// - `(Vec<&'static str>, Vec<&'static str>)`: The keystrokes to send to the shell,
+///   and the chords to disable in the terminal.
+#[cfg(target_os = "linux")]
+fn terminal_passthrough_keystrokes(
+    specs: &[KeybindingDispatchSpec],
+) -> (Vec<&'static str>, Vec<&'static str>) {
+    let is_control_key = |keystroke: &str| {
+        keystroke
+            .strip_prefix("ctrl-")
+            .is_some_and(|key| key.chars().count() == 1 && key != "`")
+    };
+    let mut keystrokes = Vec::new();
+    let mut chords = Vec::new();
+    for spec in specs {
+        if spec.action.key_context() == Some(KEY_CONTEXT_TERMINAL) {
+            continue;
+        }
+        let mut strokes = spec.keystroke.split(' ');
+        let Some(first) = strokes.next().filter(|first| is_control_key(first)) else {
+            continue;
+        };
+        if strokes.next().is_some() && !chords.contains(&spec.keystroke) {
+            chords.push(spec.keystroke);
+        }
+        if !keystrokes.contains(&first) {
+            keystrokes.push(first);
+        }
+    }
+    (keystrokes, chords)
+}
+
 /// Build the default runtime keybindings for the application.
 ///
 /// ### Returns:
 /// - `Vec<KeyBinding>`: The platform-specific list of GPUI keybindings.
 pub fn build_default_key_bindings() -> Vec<KeyBinding> {
-    default_keybinding_dispatch_specs()
+    let specs = default_keybinding_dispatch_specs();
+    #[cfg(target_os = "linux")]
+    let (passthrough, disabled_chords) = terminal_passthrough_keystrokes(&specs);
+    #[cfg_attr(not(target_os = "linux"), allow(unused_mut))]
+    let mut bindings: Vec<KeyBinding> = specs
         .into_iter()
         .map(KeybindingDispatchSpec::into_key_binding)
-        .collect()
+        .collect();
+    #[cfg(target_os = "linux")]
+    {
+        bindings.extend(
+            disabled_chords
+                .into_iter()
+                .map(|chord| KeyBinding::new(chord, gpui::NoAction, Some(KEY_CONTEXT_TERMINAL))),
+        );
+        bindings.extend(passthrough.into_iter().map(|keystroke| {
+            KeyBinding::new(
+                keystroke,
+                SendTerminalKey(keystroke.into()),
+                Some(KEY_CONTEXT_TERMINAL),
+            )
+        }));
+    }
+    bindings
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        KeybindingDispatchAction, build_default_key_bindings, default_keybinding_dispatch_specs,
+        KeybindingDispatchAction, TextTransform, build_default_key_bindings,
+        default_keybinding_dispatch_specs,
     };
     use core::prelude::v1::test;
     use std::collections::HashSet;
@@ -296,7 +529,43 @@ mod tests {
     fn test_build_default_key_bindings_matches_dispatch_spec_count() {
         let specs = default_keybinding_dispatch_specs();
         let keybindings = build_default_key_bindings();
-        assert_eq!(keybindings.len(), specs.len());
+        #[cfg(target_os = "linux")]
+        let terminal_bindings = {
+            let (passthrough, chords) = super::terminal_passthrough_keystrokes(&specs);
+            passthrough.len() + chords.len()
+        };
+        #[cfg(not(target_os = "linux"))]
+        let terminal_bindings = 0;
+        assert_eq!(keybindings.len(), specs.len() + terminal_bindings);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_terminal_passthrough_sends_control_keys_to_the_shell() {
+        let specs = default_keybinding_dispatch_specs();
+        let (passthrough, chords) = super::terminal_passthrough_keystrokes(&specs);
+        for keystroke in ["ctrl-w", "ctrl-r", "ctrl-g", "ctrl-k"] {
+            assert!(
+                passthrough.contains(&keystroke),
+                "{keystroke} should pass through"
+            );
+        }
+        for keystroke in ["ctrl-`", "ctrl-shift-c", "ctrl-shift-right", "alt-f4"] {
+            assert!(
+                !passthrough.contains(&keystroke),
+                "{keystroke} should stay bound"
+            );
+        }
+        assert!(chords.contains(&"ctrl-k o"));
+        assert_eq!(
+            KeybindingDispatchAction::ToggleTerminal.key_context(),
+            None,
+            "the terminal must toggle from anywhere"
+        );
+        assert_eq!(
+            KeybindingDispatchAction::CopyTerminalSelection.key_context(),
+            Some(super::KEY_CONTEXT_TERMINAL)
+        );
     }
 
     #[test]
@@ -358,6 +627,15 @@ mod tests {
             KeybindingDispatchAction::JumpToLine,
             KeybindingDispatchAction::PrintFile,
             KeybindingDispatchAction::ToggleColorPicker,
+            KeybindingDispatchAction::ToggleJsonQuery,
+            KeybindingDispatchAction::SplitRight,
+            KeybindingDispatchAction::FocusOtherPane,
+            KeybindingDispatchAction::GoToSymbol,
+            KeybindingDispatchAction::ToggleLineComment,
+            KeybindingDispatchAction::ToggleBlockComment,
+            KeybindingDispatchAction::PipeThroughCommand,
+            KeybindingDispatchAction::RunLastTask,
+            KeybindingDispatchAction::Transform(TextTransform::SortNatural),
         ];
         for action in editor_scoped {
             assert_eq!(
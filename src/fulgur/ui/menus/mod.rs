@@ -13,4 +13,4 @@ pub use app_menus::build_menus;
 pub use dock::DockMenuTab;
 #[cfg(target_os = "macos")]
 pub use dock::build_dock_menu;
-pub use keybindings::{KEY_CONTEXT_FULGUR, build_default_key_bindings};
+pub use keybindings::{KEY_CONTEXT_FULGUR, KEY_CONTEXT_TERMINAL, build_default_key_bindings};
@@ -5,8 +5,10 @@ pub mod dialogs;
 pub mod icons;
 pub mod insert_button;
 pub mod log_view;
+pub mod markdown_preview;
 pub mod menus;
 pub mod notifications;
+pub mod read_only_editor;
 pub mod sheets;
 pub mod tabs;
 pub mod themes;
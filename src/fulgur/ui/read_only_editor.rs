@@ -0,0 +1,93 @@
+use gpui::{
+    AnyElement, InteractiveElement, IntoElement, KeyDownEvent, Keystroke, ParentElement, Styled,
+    div,
+};
+use gpui_component::input::{
+    Backspace, Cut, Delete, DeleteToBeginningOfLine, DeleteToEndOfLine, DeleteToNextWordEnd,
+    DeleteToPreviousWordStart, Enter, Indent, IndentInline, Outdent, OutdentInline, Paste, Redo,
+    Undo,
+};
+
+/// Stop an editing action before it reaches the editor
+macro_rules! refuse {
+    ($element:expr, $($action:ty),+ $(,)?) => {
+        $element$(.capture_action(|_: &$action, _window, cx| cx.stop_propagation()))+
+    };
+}
+
+/// Wrap an editor so a read-only tab refuses edits
+///
+/// ### Description
+/// Unlike a disabled editor, the wrapped one still takes the focus, so its
+/// text can be selected, searched and copied. Typed text and the editing
+/// actions are stopped during the capture phase, before the editor sees them;
+/// edits made by Fulgur itself check the tab's `read_only` flag instead.
+///
+/// ### Arguments
+/// - `editor`: The editor element
+/// - `read_only`: Whether the tab is read-only
+///
+/// ### Returns
+/// - `AnyElement`: The guarded editor, or `editor` unchanged when the tab is editable
+pub fn read_only_editor(editor: impl IntoElement, read_only: bool) -> AnyElement {
+    if !read_only {
+        return editor.into_any_element();
+    }
+    let guard = div()
+        .size_full()
+        .capture_key_down(|event: &KeyDownEvent, _window, cx| {
+            if inserts_text(&event.keystroke) {
+                cx.stop_propagation();
+            }
+        });
+    refuse!(
+        guard,
+        Backspace,
+        Delete,
+        DeleteToBeginningOfLine,
+        DeleteToEndOfLine,
+        DeleteToPreviousWordStart,
+        DeleteToNextWordEnd,
+        Enter,
+        Indent,
+        Outdent,
+        IndentInline,
+        OutdentInline,
+        Cut,
+        Paste,
+        Undo,
+        Redo,
+    )
+    .child(editor)
+    .into_any_element()
+}
+
+/// Check whether a keystroke types text rather than triggering a shortcut
+///
+/// ### Arguments
+/// - `keystroke`: The keystroke
+///
+/// ### Returns
+/// - `true`: The keystroke produces a character and no command modifier is held
+/// - `false`: Otherwise
+fn inserts_text(keystroke: &Keystroke) -> bool {
+    keystroke.key_char.is_some() && !keystroke.modifiers.control && !keystroke.modifiers.platform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inserts_text;
+    use gpui::Keystroke;
+
+    #[test]
+    fn test_typed_characters_are_refused_but_shortcuts_are_not() {
+        let parse = |source: &str| Keystroke::parse(source).expect("valid keystroke");
+        let mut typed = parse("a");
+        typed.key_char = Some("a".to_string());
+        assert!(inserts_text(&typed));
+        let mut copy = parse("ctrl-c");
+        copy.key_char = Some("c".to_string());
+        assert!(!inserts_text(&copy));
+        assert!(!inserts_text(&parse("down")));
+    }
+}
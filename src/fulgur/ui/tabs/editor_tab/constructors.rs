@@ -2,6 +2,7 @@ use super::{
     EditorTab, FromDuplicateParams, FromFileParams, TabLocation, TabTransferData, initial_csv_state,
 };
 use crate::fulgur::files::file_operations::RemoteFileResult;
+use crate::fulgur::files::indentation::Indentation;
 use crate::fulgur::languages::supported_languages::{
     language_from_content, language_registry_name,
 };
@@ -33,6 +34,7 @@ impl EditorTab {
     ) -> Self {
         let language = crate::fulgur::languages::supported_languages::SupportedLanguage::Plain;
         let (original_content_hash, original_content_len) = super::content_fingerprint_from_str("");
+        let indentation = Indentation::from_settings(settings);
         let content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -40,6 +42,7 @@ impl EditorTab {
                 language_registry_name(&language),
                 None,
                 settings,
+                indentation,
                 false,
             )
         });
@@ -68,6 +71,9 @@ impl EditorTab {
             log_follow: true,
             log_full: false,
             log_content: None,
+            last_edit: None,
+            read_only: false,
+            indentation,
             content_subscription: None,
         }
     }
@@ -97,6 +103,8 @@ impl EditorTab {
         let large_file = super::is_large_file(contents.len());
         let (csv_view_mode, csv_delimiter) = initial_csv_state(language, contents);
         let (original_content_hash, original_content_len) = super::content_fingerprint_from_str("");
+        let indentation =
+            Indentation::resolve(contents, language_registry_name(&language), settings);
         let content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -104,6 +112,7 @@ impl EditorTab {
                 language_registry_name(&language),
                 Some(contents.to_string()),
                 settings,
+                indentation,
                 large_file,
             )
         });
@@ -132,6 +141,9 @@ impl EditorTab {
             log_follow: true,
             log_full: false,
             log_content: None,
+            last_edit: None,
+            read_only: false,
+            indentation,
             content_subscription: None,
         }
     }
@@ -170,6 +182,11 @@ impl EditorTab {
 
         let language = language_from_content(&file_name, &params.contents);
         let (csv_view_mode, csv_delimiter) = initial_csv_state(language, &params.contents);
+        let indentation = Indentation::resolve(
+            &params.contents,
+            language_registry_name(&language),
+            settings,
+        );
         let content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -177,6 +194,7 @@ impl EditorTab {
                 language_registry_name(&language),
                 Some(params.contents),
                 settings,
+                indentation,
                 large_file,
             )
         });
@@ -210,6 +228,9 @@ impl EditorTab {
             log_follow: true,
             log_full: false,
             log_content: None,
+            last_edit: None,
+            read_only: false,
+            indentation,
             content_subscription: None,
         }
     }
@@ -237,6 +258,7 @@ impl EditorTab {
         let (csv_view_mode, csv_delimiter) =
             initial_csv_state(params.language, &params.current_content);
         let (original_content_hash, original_content_len) = super::content_fingerprint_from_str("");
+        let indentation = params.indentation;
         let content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -244,6 +266,7 @@ impl EditorTab {
                 language_registry_name(&params.language),
                 Some(params.current_content),
                 settings,
+                indentation,
                 large_file,
             )
         });
@@ -272,6 +295,9 @@ impl EditorTab {
             log_follow: true,
             log_full: false,
             log_content: None,
+            last_edit: None,
+            read_only: false,
+            indentation,
             content_subscription: None,
         }
     }
@@ -303,6 +329,7 @@ impl EditorTab {
         let language = language_from_content(&file_name, "");
         let (csv_view_mode, csv_delimiter) = initial_csv_state(language, "");
         let (original_content_hash, original_content_len) = super::content_fingerprint_from_str("");
+        let indentation = Indentation::from_settings(settings);
         let content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -310,6 +337,7 @@ impl EditorTab {
                 language_registry_name(&language),
                 None,
                 settings,
+                indentation,
                 false,
             )
         });
@@ -338,6 +366,9 @@ impl EditorTab {
             log_follow: true,
             log_full: false,
             log_content: None,
+            last_edit: None,
+            read_only: false,
+            indentation,
             content_subscription: None,
         }
     }
@@ -372,6 +403,8 @@ impl EditorTab {
         let (original_content_hash, original_content_len) =
             super::content_fingerprint_from_str(&result.content);
         let large_file = super::is_large_file(original_content_len);
+        let indentation =
+            Indentation::resolve(&result.content, language_registry_name(&language), settings);
         let content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -379,6 +412,7 @@ impl EditorTab {
                 language_registry_name(&language),
                 Some(result.content),
                 settings,
+                indentation,
                 large_file,
             )
         });
@@ -407,6 +441,9 @@ impl EditorTab {
             log_follow: true,
             log_full: false,
             log_content: None,
+            last_edit: None,
+            read_only: result.read_only,
+            indentation,
             content_subscription: None,
         }
     }
@@ -435,6 +472,7 @@ impl EditorTab {
     ) -> Self {
         let cursor_position = data.cursor_position;
         let large_file = super::is_large_file(data.content.len());
+        let indentation = data.indentation;
         let content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -442,6 +480,7 @@ impl EditorTab {
                 language_registry_name(&data.language),
                 Some(data.content),
                 settings,
+                indentation,
                 large_file,
             )
         });
@@ -473,6 +512,9 @@ impl EditorTab {
             log_follow: true,
             log_full: false,
             log_content: None,
+            last_edit: None,
+            read_only: data.read_only,
+            indentation,
             content_subscription: None,
         }
     }
@@ -55,6 +55,9 @@ impl CsvTableDelegate {
         window: &mut Window,
         cx: &mut Context<TableState<Self>>,
     ) {
+        if self.read_only {
+            return;
+        }
         let current = self.value_at(target);
         let input = self.dialog_input.clone();
         input.update(cx, |state, cx| {
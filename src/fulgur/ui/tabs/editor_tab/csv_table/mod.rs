@@ -60,6 +60,8 @@ pub struct CsvTableDelegate {
     /// Fingerprint of the last text this delegate committed to the buffer, so
     /// `ensure_csv_table` can tell the table's own commits from external edits.
     last_commit_hash: Option<u64>,
+    /// Whether the owning tab is read-only, blocking cell and structural edits.
+    read_only: bool,
 }
 
 impl CsvTableDelegate {
@@ -89,9 +91,27 @@ impl CsvTableDelegate {
             dialog_input,
             last_selection: None,
             last_commit_hash: None,
+            read_only: false,
         }
     }
 
+    /// Block or allow edits through the table.
+    ///
+    /// ### Arguments
+    /// - `read_only`: Whether the owning tab is read-only
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Whether edits through the table are blocked.
+    ///
+    /// ### Returns
+    /// - `bool`: True if the owning tab is read-only
+    #[must_use]
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     /// Record the table's selection events on the delegate.
     ///
     /// ### Arguments
@@ -11,15 +11,20 @@ mod tests;
 pub use csv_table::CsvTableDelegate;
 pub use location::TabLocation;
 pub use navigation::{Jump, extract_line_number};
+pub(crate) use operations::{
+    apply_minimal_edits, read_text_and_selection, replace_and_select, toggle_comment_in,
+    transform_text_in,
+};
 
 use gpui::{App, AppContext, Context, Entity, SharedString, Window};
 use gpui_component::input::{EditorState, InputState, Rope, TabSize};
 use gpui_component::table::TableState;
 use std::path::PathBuf;
 use std::rc::Rc;
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 use crate::fulgur::files::csv_support::{DEFAULT_DELIMITER, detect_delimiter, parse_csv};
+use crate::fulgur::files::indentation::Indentation;
 use crate::fulgur::languages::supported_languages::{SupportedLanguage, language_registry_name};
 use crate::fulgur::settings::EditorSettings;
 use crate::fulgur::ui::tabs::color_tag::ColorTag;
@@ -85,6 +90,12 @@ pub struct EditorTab {
     /// log view first activates. Kept separate from the editable `content` so the
     /// line cap never truncates the saveable buffer.
     pub log_content: Option<Entity<EditorState>>,
+    /// When the content was last edited, used to delay auto-saves until typing pauses.
+    pub last_edit: Option<Instant>,
+    /// Whether edits are blocked, set for unwritable files or toggled by hand.
+    pub read_only: bool,
+    /// Indent style and width, detected from the content or chosen for this tab.
+    pub indentation: Indentation,
     /// Subscription to the content entity keeping `modified` current. Owned by
     /// the tab entity, attached by `Tab::attach_content_subscription`, and
     /// replaced whenever the content entity is swapped.
@@ -111,6 +122,8 @@ pub struct TabTransferData {
     pub csv_delimiter: u8,
     pub color_tag: Option<ColorTag>,
     pub log_view: bool,
+    pub read_only: bool,
+    pub indentation: Indentation,
 }
 
 /// Parameters for creating an editor tab as a duplicate of another
@@ -121,6 +134,7 @@ pub struct FromDuplicateParams {
     pub encoding: String,
     pub lossy_decode: bool,
     pub language: SupportedLanguage,
+    pub indentation: Indentation,
 }
 
 /// Compute the initial CSV view mode and delimiter for a freshly opened tab.
@@ -154,6 +168,18 @@ impl EditorTab {
         self.location.local_path()
     }
 
+    /// Block or allow edits to the tab, including edits through its CSV table.
+    ///
+    /// ### Arguments
+    /// - `read_only`: Whether the tab should be read-only
+    /// - `cx`: The application context
+    pub fn set_read_only(&mut self, read_only: bool, cx: &mut App) {
+        self.read_only = read_only;
+        if let Some(table) = &self.csv_table {
+            table.update(cx, |state, _| state.delegate_mut().set_read_only(read_only));
+        }
+    }
+
     /// Ensure the CSV table state is built and reflects the current text.
     ///
     /// ### Arguments
@@ -193,8 +219,9 @@ impl EditorTab {
 
         let content = self.content.clone();
         let dialog_input = cx.new(|cx| InputState::new(window, cx));
-        let delegate =
+        let mut delegate =
             CsvTableDelegate::new(outcome.data, self.csv_delimiter, content, dialog_input);
+        delegate.set_read_only(self.read_only);
         let table = cx.new(|cx| {
             TableState::new(delegate, window, cx)
                 .cell_selectable(true)
@@ -266,6 +293,7 @@ pub struct FromFileParams {
 /// - `language_name`: The language registry name for syntax highlighting
 /// - `content`: The content of the editor state
 /// - `settings`: The settings for the editor state
+/// - `indentation`: The indent style and width of the tab
 /// - `large_file`: Whether to open in large-file mode
 ///
 /// ### Returns
@@ -276,6 +304,7 @@ fn make_input_state(
     language_name: &str,
     content: Option<String>,
     settings: &EditorSettings,
+    indentation: Indentation,
     large_file: bool,
 ) -> EditorState {
     // In large-file mode, substitute a language with no registered grammar so
@@ -290,8 +319,8 @@ fn make_input_state(
         .line_number(settings.show_line_numbers)
         .indent_guides(settings.show_indent_guides && !large_file)
         .tab_size(TabSize {
-            tab_size: settings.tab_size,
-            hard_tabs: !settings.use_spaces,
+            tab_size: indentation.width,
+            hard_tabs: !indentation.use_spaces,
         })
         .soft_wrap(settings.soft_wrap && !large_file)
         .folding(!large_file)
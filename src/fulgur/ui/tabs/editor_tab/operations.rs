@@ -1,12 +1,18 @@
 use super::{EditorTab, Jump};
+use crate::fulgur::files::indentation::Indentation;
+use crate::fulgur::languages::comments::{CommentKind, toggle_comment};
 use crate::fulgur::languages::supported_languages::{
     SupportedLanguage, language_from_content, language_registry_name,
 };
 use crate::fulgur::settings::EditorSettings;
 use crate::fulgur::ui::components_utils::UNTITLED;
-use gpui::{App, AppContext, Window};
-use gpui_component::input::Position;
-use std::time::SystemTime;
+use crate::fulgur::utils::text_diff::{
+    TextEdit, byte_offset_from_utf16, line_edits, map_offset, utf16_ranges,
+};
+use crate::fulgur::utils::text_transform::TextTransform;
+use gpui::{App, AppContext, Entity, EntityInputHandler, Window};
+use gpui_component::input::{EditorState, Position, TabSize};
+use std::{ops::Range, time::SystemTime};
 
 impl EditorTab {
     /// Update cached metadata used by tab tooltip rendering.
@@ -52,6 +58,52 @@ impl EditorTab {
         });
     }
 
+    /// Replace the buffer content through minimal line edits.
+    ///
+    /// ### Arguments
+    /// - `new_content`: The content the buffer should hold afterwards
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn apply_content_diff(&mut self, new_content: &str, window: &mut Window, cx: &mut App) {
+        apply_minimal_edits(&self.content, new_content, window, cx);
+    }
+
+    /// Create a second editor state over this tab's text, with its own cursor and scroll position.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    /// - `settings`: The editor settings for the new input state
+    ///
+    /// ### Returns
+    /// - `Entity<EditorState>`: The new editor state, starting at this tab's cursor
+    pub fn make_mirror_content(
+        &self,
+        window: &mut Window,
+        cx: &mut App,
+        settings: &EditorSettings,
+    ) -> Entity<EditorState> {
+        let cursor = self.content.read(cx).cursor_position();
+        let current_content = self.content.read(cx).text().to_string();
+        let large_file = self.large_file;
+        let indentation = self.indentation;
+        let mirror = cx.new(|cx| {
+            super::make_input_state(
+                window,
+                cx,
+                language_registry_name(&self.language),
+                Some(current_content),
+                settings,
+                indentation,
+                large_file,
+            )
+        });
+        mirror.update(cx, |state, cx| {
+            state.set_cursor_position(cursor, window, cx);
+        });
+        mirror
+    }
+
     /// Rebuild the `EditorState` to apply `highlight_colors` changes.
     ///
     /// The `document_color_provider` can only be set at creation time (Lsp internal
@@ -71,6 +123,7 @@ impl EditorTab {
         let cursor = self.content.read(cx).cursor_position();
         let current_content = self.content.read(cx).text().to_string();
         let large_file = self.large_file;
+        let indentation = self.indentation;
         self.content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -78,6 +131,7 @@ impl EditorTab {
                 language_registry_name(&self.language),
                 Some(current_content),
                 settings,
+                indentation,
                 large_file,
             )
         });
@@ -86,6 +140,34 @@ impl EditorTab {
         });
     }
 
+    /// Change the indent style and width of the tab
+    ///
+    /// ### Description
+    /// The tab size is set on the existing input state, so undo history, cursor
+    /// and scroll position are kept.
+    ///
+    /// ### Arguments
+    /// - `indentation`: The new indentation
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn set_indentation(&mut self, indentation: Indentation, window: &mut Window, cx: &mut App) {
+        let changed = indentation.width != self.indentation.width
+            || indentation.use_spaces != self.indentation.use_spaces;
+        self.indentation = indentation;
+        if changed {
+            self.content.update(cx, |input_state, cx| {
+                input_state.set_tab_size(
+                    TabSize {
+                        tab_size: indentation.width,
+                        hard_tabs: !indentation.use_spaces,
+                    },
+                    window,
+                    cx,
+                );
+            });
+        }
+    }
+
     /// Check if the tab's content has been modified
     ///
     /// ### Arguments
@@ -198,22 +280,28 @@ impl EditorTab {
 
     /// Update the language/syntax highlighting based on the file extension
     ///
+    /// ### Description
+    /// The highlighter of the existing input state is switched in place, so a
+    /// reload or a save under a new name keeps undo history and scroll position.
+    ///
     /// ### Arguments
-    /// - `window`: The window context
     /// - `cx`: The application context
-    /// - `settings`: The editor settings for the new input state
-    pub fn update_language(
-        &mut self,
-        window: &mut Window,
-        cx: &mut App,
-        settings: &EditorSettings,
-    ) {
+    pub fn update_language(&mut self, cx: &mut App) {
         if let Some(path) = self.location.local_path() {
             let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
             let current_content = self.content.read(cx).text().to_string();
             let language = language_from_content(file_name, &current_content);
             if language != self.language {
-                self.force_language(window, cx, language, settings);
+                self.language = language;
+                // Large files keep a language with no registered grammar, as at creation.
+                let highlighted = if self.large_file {
+                    SupportedLanguage::Plain
+                } else {
+                    language
+                };
+                self.content.update(cx, |input_state, cx| {
+                    input_state.set_highlighter(language_registry_name(&highlighted), cx);
+                });
             }
         }
     }
@@ -239,6 +327,7 @@ impl EditorTab {
         let current_content = self.content.read(cx).text().to_string();
         self.language = language;
         let large_file = self.large_file;
+        let indentation = self.indentation;
         self.content = cx.new(|cx| {
             super::make_input_state(
                 window,
@@ -246,6 +335,7 @@ impl EditorTab {
                 language_registry_name(&language),
                 Some(current_content),
                 settings,
+                indentation,
                 large_file,
             )
         });
@@ -275,3 +365,170 @@ impl EditorTab {
         });
     }
 }
+
+/// Replace the content of an editor state through minimal line edits.
+///
+/// ### Description
+/// Unchanged lines keep their position, the cursor follows the text around it and the
+/// scroll offset is kept. The edits are applied back to back so the editor history
+/// groups them into a single undo step. Nothing happens when the content is already equal.
+///
+/// ### Arguments
+/// - `content`: The editor state to update
+/// - `new_content`: The content the editor state should hold afterwards
+/// - `window`: The window context
+/// - `cx`: The application context
+pub(crate) fn apply_minimal_edits(
+    content: &Entity<EditorState>,
+    new_content: &str,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let (old_content, cursor, scroll_offset) = {
+        let input_state = content.read(cx);
+        (
+            input_state.text().to_string(),
+            input_state.cursor(),
+            input_state.scroll_offset(),
+        )
+    };
+    let edits = line_edits(&old_content, new_content);
+    if edits.is_empty() {
+        return;
+    }
+    let ranges = utf16_ranges(&old_content, &edits);
+    let cursor = map_offset(&edits, cursor, new_content);
+    content.update(cx, |input_state, cx| {
+        for (edit, range) in edits.iter().zip(ranges).rev() {
+            input_state.replace_text_in_range(Some(range), &edit.new_text, window, cx);
+        }
+        input_state.set_selected_range(cursor..cursor, cx);
+        input_state.set_scroll_offset(scroll_offset, cx);
+    });
+}
+
+/// Toggle a line or block comment over the selection of an editor state.
+///
+/// ### Description
+/// The comment tokens come from the language at the selection start, so code
+/// embedded in another language (a `<script>` in Vue or HTML) is commented with
+/// its own syntax. The change is applied as a single edit, so one undo reverts it.
+///
+/// ### Arguments
+/// - `content`: The editor state to update
+/// - `language`: The language of the document
+/// - `kind`: The kind of comment to toggle
+/// - `window`: The window context
+/// - `cx`: The application context
+///
+/// ### Returns
+/// - `bool`: `false` when the language at the cursor has no comment syntax
+pub(crate) fn toggle_comment_in(
+    content: &Entity<EditorState>,
+    language: SupportedLanguage,
+    kind: CommentKind,
+    window: &mut Window,
+    cx: &mut App,
+) -> bool {
+    let (text, selection) = read_text_and_selection(content, window, cx);
+    let Some(result) = toggle_comment(language, &text, selection, kind) else {
+        return false;
+    };
+    replace_and_select(content, &text, &result.edit, result.selection, window, cx);
+    true
+}
+
+/// Apply a text transform to the selection of an editor state, or to its whole content.
+///
+/// ### Description
+/// A selection is replaced in one edit and the result stays selected. Without a
+/// selection, the whole buffer is transformed through minimal line edits so the
+/// cursor and scroll position are kept. Either way, one undo reverts the transform.
+///
+/// ### Arguments
+/// - `content`: The editor state to update
+/// - `transform`: The transform to apply
+/// - `window`: The window context
+/// - `cx`: The application context
+///
+/// ### Errors
+/// - When the text is not valid input for the transform (e.g. decoding invalid base64)
+pub(crate) fn transform_text_in(
+    content: &Entity<EditorState>,
+    transform: TextTransform,
+    window: &mut Window,
+    cx: &mut App,
+) -> anyhow::Result<()> {
+    let (text, selection) = read_text_and_selection(content, window, cx);
+    if selection.is_empty() {
+        let transformed = transform.apply(&text)?;
+        apply_minimal_edits(content, &transformed, window, cx);
+        return Ok(());
+    }
+    let transformed = transform.apply(&text[selection.clone()])?;
+    if transformed == text[selection.clone()] {
+        return Ok(());
+    }
+    let new_selection = selection.start..selection.start + transformed.len();
+    let edit = TextEdit {
+        range: selection,
+        new_text: transformed,
+    };
+    replace_and_select(content, &text, &edit, new_selection, window, cx);
+    Ok(())
+}
+
+/// Read the text of an editor state and its selection as a byte range.
+///
+/// ### Arguments
+/// - `content`: The editor state
+/// - `window`: The window context
+/// - `cx`: The application context
+///
+/// ### Returns
+/// - `(String, Range<usize>)`: The text and the selected byte range, empty at the cursor
+pub(crate) fn read_text_and_selection(
+    content: &Entity<EditorState>,
+    window: &mut Window,
+    cx: &mut App,
+) -> (String, Range<usize>) {
+    content.update(cx, |input_state, cx| {
+        let text = input_state.text().to_string();
+        let cursor = input_state.cursor();
+        let selection =
+            input_state
+                .selected_text_range(true, window, cx)
+                .map_or(cursor..cursor, |selection| {
+                    byte_offset_from_utf16(&text, selection.range.start)
+                        ..byte_offset_from_utf16(&text, selection.range.end)
+                });
+        (text, selection)
+    })
+}
+
+/// Apply one edit to an editor state and select a byte range of the result.
+///
+/// ### Arguments
+/// - `content`: The editor state to update
+/// - `text`: The text the edit was computed against
+/// - `edit`: The replacement, in byte offsets of `text`
+/// - `selection`: The byte range to select afterwards
+/// - `window`: The window context
+/// - `cx`: The application context
+pub(crate) fn replace_and_select(
+    content: &Entity<EditorState>,
+    text: &str,
+    edit: &TextEdit,
+    selection: Range<usize>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let ranges = utf16_ranges(text, std::slice::from_ref(edit));
+    content.update(cx, |input_state, cx| {
+        if let Some(range) = ranges.into_iter().next() {
+            input_state.replace_text_in_range(Some(range), &edit.new_text, window, cx);
+        }
+        input_state.set_selected_range(selection, cx);
+        cx.notify();
+    });
+}
@@ -2,6 +2,7 @@ use super::{
     EditorTab, FromDuplicateParams, FromFileParams, TabLocation, TabTransferData,
     content_fingerprint_from_str,
 };
+use crate::fulgur::files::indentation::{Indentation, IndentationSource};
 use crate::fulgur::languages::supported_languages::SupportedLanguage;
 use crate::fulgur::settings::EditorSettings;
 use crate::fulgur::ui::tabs::tab::TabId;
@@ -52,6 +53,8 @@ fn make_transfer_data() -> TabTransferData {
         csv_delimiter: b',',
         color_tag: None,
         log_view: false,
+        read_only: false,
+        indentation: Indentation::from_settings(&EditorSettings::new()),
     }
 }
 
@@ -168,6 +171,30 @@ fn test_editor_tab_from_file_construction(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_editor_tab_from_file_detects_indentation(cx: &mut TestAppContext) {
+    cx.update(gpui_component::init);
+    let settings = EditorSettings::new();
+    let params = FromFileParams {
+        id: TabId(14),
+        path: temp_test_path("Makefile"),
+        contents: "build:\n\tcargo build\n".to_string(),
+        encoding: "UTF-8".to_string(),
+        is_modified: false,
+    };
+
+    cx.update(|cx| {
+        cx.open_window(WindowOptions::default(), |window, cx| {
+            let tab = EditorTab::from_file(params, window, cx, &settings);
+            assert!(!tab.indentation.use_spaces);
+            assert_eq!(tab.indentation.width, settings.tab_size);
+            assert_eq!(tab.indentation.source, IndentationSource::Detected);
+            cx.new(|_| EmptyView)
+        })
+        .expect("failed to open test window");
+    });
+}
+
 #[gpui::test]
 fn test_editor_tab_from_duplicate_construction(cx: &mut TestAppContext) {
     cx.update(gpui_component::init);
@@ -179,6 +206,7 @@ fn test_editor_tab_from_duplicate_construction(cx: &mut TestAppContext) {
         encoding: "UTF-8".to_string(),
         lossy_decode: false,
         language: SupportedLanguage::Rust,
+        indentation: Indentation::from_settings(&settings),
     };
 
     cx.update(|cx| {
@@ -485,6 +513,8 @@ fn test_from_transfer_untitled_no_file_metadata(cx: &mut TestAppContext) {
         csv_delimiter: b',',
         color_tag: None,
         log_view: false,
+        read_only: false,
+        indentation: Indentation::from_settings(&EditorSettings::new()),
     };
     cx.update(|cx| {
         cx.open_window(WindowOptions::default(), |window, cx| {
@@ -522,6 +552,8 @@ fn test_from_transfer_modified_state_preserved(cx: &mut TestAppContext) {
         csv_delimiter: b',',
         color_tag: None,
         log_view: false,
+        read_only: false,
+        indentation: Indentation::from_settings(&EditorSettings::new()),
     };
     cx.update(|cx| {
         cx.open_window(WindowOptions::default(), |window, cx| {
@@ -562,6 +594,8 @@ fn test_from_transfer_preserves_language(cx: &mut TestAppContext) {
         csv_delimiter: b',',
         color_tag: None,
         log_view: false,
+        read_only: false,
+        indentation: Indentation::from_settings(&EditorSettings::new()),
     };
     cx.update(|cx| {
         cx.open_window(WindowOptions::default(), |window, cx| {
@@ -596,6 +630,8 @@ fn test_from_transfer_preserves_markdown_flags(cx: &mut TestAppContext) {
         csv_delimiter: b',',
         color_tag: None,
         log_view: false,
+        read_only: false,
+        indentation: Indentation::from_settings(&EditorSettings::new()),
     };
     cx.update(|cx| {
         cx.open_window(WindowOptions::default(), |window, cx| {
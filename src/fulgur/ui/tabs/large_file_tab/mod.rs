@@ -0,0 +1,399 @@
+mod rendering;
+
+use crate::fulgur::{
+    files::line_index::{self, LineIndex, SCAN_CHUNK_BYTES},
+    settings::EditorSettings,
+    ui::tabs::tab::TabId,
+};
+use gpui::{AppContext, Context, Entity, FocusHandle, SharedString, Subscription, Task, Window};
+use gpui_component::input::{InputEvent, InputState};
+use std::{path::PathBuf, sync::Arc};
+
+/// A read-only tab paging a file too large to load into an editor.
+pub struct LargeFileTab {
+    pub id: TabId,
+    pub title: SharedString,
+    pub path: PathBuf,
+    pub view: Entity<LargeFileView>,
+}
+
+/// Number of viewports of lines read above and below the visible one, so short scrolls need no read
+const OVERSCAN_VIEWPORTS: usize = 1;
+
+/// Lines read around the viewport, reused until the viewport leaves them.
+struct LineWindow {
+    first_line: usize,
+    lines: Vec<SharedString>,
+}
+
+impl LineWindow {
+    /// Check whether the window holds every line of a viewport
+    ///
+    /// ### Arguments
+    /// - `top_line`: The first line of the viewport
+    /// - `rows`: The number of rows of the viewport
+    /// - `line_count`: The number of lines known so far
+    ///
+    /// ### Returns
+    /// - `true`: if every visible line is in the window, or the window reaches the last known line
+    /// - `false`: if lines have to be read
+    fn covers(&self, top_line: usize, rows: usize, line_count: usize) -> bool {
+        let end = self.first_line + self.lines.len();
+        self.first_line <= top_line && (top_line + rows <= end || end >= line_count)
+    }
+}
+
+/// Virtualized view over a large file, backed by a sparse line index built in the background.
+pub struct LargeFileView {
+    path: PathBuf,
+    index: Arc<LineIndex>, // Shared with background reads and searches, copied only when extended while one runs
+    top_line: usize,
+    visible_rows: usize,
+    scroll_remainder: f32, // Fraction of a line accumulated from smooth scrolling
+    line_window: Option<LineWindow>,
+    line_window_task: Option<(usize, Task<()>)>, // First line of the window being read, and its read
+    highlighted_line: Option<usize>,             // Last search hit or jump target
+    search_input: Entity<InputState>,
+    match_case: bool,
+    search_task: Option<Task<()>>,
+    status: Option<SharedString>, // Last search outcome or read failure
+    index_error: Option<SharedString>,
+    font_family: SharedString,
+    font_size: f32,
+    focus_handle: FocusHandle,
+    _indexing_task: Task<()>,
+    _search_input_subscription: Subscription,
+}
+
+impl LargeFileView {
+    /// Create a viewer and start indexing the file in the background
+    ///
+    /// ### Arguments
+    /// - `path`: The path of the file to view
+    /// - `file_len`: The length of the file in bytes
+    /// - `settings`: The editor settings providing the font
+    /// - `window`: The window context
+    /// - `cx`: The view context
+    ///
+    /// ### Returns
+    /// - `LargeFileView`: The viewer, showing the start of the file
+    pub fn new(
+        path: PathBuf,
+        file_len: u64,
+        settings: &EditorSettings,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let search_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search"));
+        let search_input_subscription = cx.subscribe_in(
+            &search_input,
+            window,
+            |this: &mut Self, _, event: &InputEvent, _window, cx| {
+                if let InputEvent::PressEnter { .. } = event {
+                    this.find_next(cx);
+                }
+            },
+        );
+        let indexing_task = Self::spawn_indexing(path.clone(), cx);
+        Self {
+            path,
+            index: Arc::new(LineIndex::new(file_len)),
+            top_line: 0,
+            visible_rows: 0,
+            scroll_remainder: 0.0,
+            line_window: None,
+            line_window_task: None,
+            highlighted_line: None,
+            search_input,
+            match_case: false,
+            search_task: None,
+            status: None,
+            index_error: None,
+            font_family: settings.font_family.clone().into(),
+            font_size: settings.font_size,
+            focus_handle: cx.focus_handle(),
+            _indexing_task: indexing_task,
+            _search_input_subscription: search_input_subscription,
+        }
+    }
+
+    /// Build the line index chunk by chunk, refreshing the view after each chunk
+    ///
+    /// ### Arguments
+    /// - `path`: The path of the file to index
+    /// - `cx`: The view context
+    ///
+    /// ### Returns
+    /// - `Task<()>`: The indexing task, cancelled when the view is dropped
+    fn spawn_indexing(path: PathBuf, cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            loop {
+                let Ok((start, newlines)) =
+                    this.read_with(cx, |view, _| view.index.scan_position())
+                else {
+                    return;
+                };
+                let scan_path = path.clone();
+                let chunk = cx
+                    .background_executor()
+                    .spawn(async move {
+                        line_index::scan_chunk(&scan_path, start, newlines, SCAN_CHUNK_BYTES)
+                    })
+                    .await;
+                let keep_indexing = this
+                    .update(cx, |view, cx| {
+                        let keep_indexing = match chunk {
+                            Ok(chunk) => {
+                                Arc::make_mut(&mut view.index).apply(chunk);
+                                !view.index.is_complete()
+                            }
+                            Err(e) => {
+                                log::error!("Failed to index {}: {e}", view.path.display());
+                                view.index_error = Some(format!("Indexing failed: {e}").into());
+                                false
+                            }
+                        };
+                        cx.notify();
+                        keep_indexing
+                    })
+                    .unwrap_or(false);
+                if !keep_indexing {
+                    log::debug!("Finished indexing {}", path.display());
+                    return;
+                }
+            }
+        })
+    }
+
+    /// Get the number of lines known so far
+    ///
+    /// ### Returns
+    /// - `usize`: The number of indexed lines
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.index.line_count()
+    }
+
+    /// Check whether the line index is still being built
+    ///
+    /// ### Returns
+    /// - `true`: if indexing is in progress
+    /// - `false`: if the whole file has been indexed or indexing failed
+    #[must_use]
+    pub fn is_indexing(&self) -> bool {
+        !self.index.is_complete() && self.index_error.is_none()
+    }
+
+    /// Apply the font settings of the editor
+    ///
+    /// ### Arguments
+    /// - `settings`: The editor settings to apply
+    /// - `cx`: The view context
+    pub fn update_settings(&mut self, settings: &EditorSettings, cx: &mut Context<Self>) {
+        self.font_family = settings.font_family.clone().into();
+        self.font_size = settings.font_size;
+        cx.notify();
+    }
+
+    /// Scroll so a line is visible near the top of the viewport and highlight it
+    ///
+    /// ### Description
+    /// Lines past the indexed part of the file are clamped to the last indexed
+    /// line, so reads never scan far from a checkpoint.
+    ///
+    /// ### Arguments
+    /// - `line`: The zero-based line to show
+    /// - `cx`: The view context
+    pub fn scroll_to_line(&mut self, line: usize, cx: &mut Context<Self>) {
+        let line = line.min(self.line_count().saturating_sub(1));
+        self.highlighted_line = Some(line);
+        self.set_top_line(line.saturating_sub(self.visible_rows / 3), cx);
+        cx.notify();
+    }
+
+    /// Move the viewport by a number of lines
+    ///
+    /// ### Arguments
+    /// - `delta`: The number of lines to move, negative to move up
+    /// - `cx`: The view context
+    fn scroll_by(&mut self, delta: isize, cx: &mut Context<Self>) {
+        self.set_top_line(self.top_line.saturating_add_signed(delta), cx);
+    }
+
+    /// Set the first visible line, clamped to the known lines
+    ///
+    /// ### Arguments
+    /// - `line`: The zero-based line to show first
+    /// - `cx`: The view context
+    fn set_top_line(&mut self, line: usize, cx: &mut Context<Self>) {
+        let max_top = self.line_count().saturating_sub(self.visible_rows.max(1));
+        let line = line.min(max_top);
+        if line != self.top_line {
+            self.top_line = line;
+            cx.notify();
+        }
+    }
+
+    /// Get the lines of the viewport, starting a background read when it left the cached window
+    ///
+    /// ### Description
+    /// Until the read finishes, the lines already cached are kept and the others
+    /// are shown empty, so rendering never waits on the disk.
+    ///
+    /// ### Arguments
+    /// - `cx`: The view context
+    ///
+    /// ### Returns
+    /// - `Vec<SharedString>`: The visible lines, starting at the top line
+    fn visible_lines(&mut self, cx: &mut Context<Self>) -> Vec<SharedString> {
+        let rows = self.visible_rows.max(1);
+        let line_count = self.line_count();
+        let covered = self
+            .line_window
+            .as_ref()
+            .is_some_and(|window| window.covers(self.top_line, rows, line_count));
+        if !covered {
+            self.read_line_window(rows, cx);
+        }
+        let Some(window) = &self.line_window else {
+            return Vec::new();
+        };
+        let end = if covered {
+            (self.top_line + rows).min(window.first_line + window.lines.len())
+        } else {
+            (self.top_line + rows).min(line_count)
+        };
+        (self.top_line..end)
+            .map(|line| {
+                line.checked_sub(window.first_line)
+                    .and_then(|offset| window.lines.get(offset))
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Read the lines around the viewport in the background
+    ///
+    /// ### Arguments
+    /// - `rows`: The number of rows of the viewport
+    /// - `cx`: The view context
+    fn read_line_window(&mut self, rows: usize, cx: &mut Context<Self>) {
+        let first_line = self.top_line.saturating_sub(rows * OVERSCAN_VIEWPORTS);
+        if self
+            .line_window_task
+            .as_ref()
+            .is_some_and(|(pending, _)| *pending == first_line)
+        {
+            return;
+        }
+        let count = rows * (1 + 2 * OVERSCAN_VIEWPORTS);
+        let path = self.path.clone();
+        let index = Arc::clone(&self.index);
+        let task = cx.spawn(async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move { line_index::read_lines(&path, &index, first_line, count) })
+                .await;
+            _ = this.update(cx, |view, cx| {
+                view.line_window_task = None;
+                let lines = match result {
+                    Ok(lines) => lines.into_iter().map(SharedString::from).collect(),
+                    Err(e) => {
+                        log::error!("Failed to read {}: {e}", view.path.display());
+                        view.status = Some(format!("Failed to read file: {e}").into());
+                        // Blank lines keep the window covering the viewport, so the
+                        // read is retried once the viewport moves, not on every render
+                        vec![SharedString::default(); count]
+                    }
+                };
+                view.line_window = Some(LineWindow { first_line, lines });
+                cx.notify();
+            });
+        });
+        self.line_window_task = Some((first_line, task));
+    }
+
+    /// Search for the query of the search input, starting after the highlighted line
+    ///
+    /// ### Arguments
+    /// - `cx`: The view context
+    pub fn find_next(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_input.read(cx).value().to_string();
+        if query.is_empty() {
+            return;
+        }
+        let from_line = self
+            .highlighted_line
+            .map_or(self.top_line, |line| line + 1)
+            .min(self.line_count().saturating_sub(1));
+        let path = self.path.clone();
+        let index = Arc::clone(&self.index);
+        let match_case = self.match_case;
+        self.status = Some("Searching...".into());
+        cx.notify();
+        self.search_task = Some(cx.spawn(async move |this, cx| {
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    line_index::find_next(&path, &index, from_line, &query, match_case)
+                })
+                .await;
+            _ = this.update(cx, |view, cx| {
+                view.search_task = None;
+                match result {
+                    Ok(Some(line)) if line >= view.line_count() => {
+                        view.status =
+                            Some(format!("Match on line {}, not indexed yet", line + 1).into());
+                    }
+                    Ok(Some(line)) => {
+                        view.status = None;
+                        view.scroll_to_line(line, cx);
+                    }
+                    Ok(None) => view.status = Some("No match".into()),
+                    Err(e) => {
+                        log::error!("Failed to search {}: {e}", view.path.display());
+                        view.status = Some(format!("Search failed: {e}").into());
+                    }
+                }
+                cx.notify();
+            });
+        }));
+    }
+
+    /// Toggle case-sensitive search
+    ///
+    /// ### Arguments
+    /// - `cx`: The view context
+    fn toggle_match_case(&mut self, cx: &mut Context<Self>) {
+        self.match_case = !self.match_case;
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineWindow;
+    use gpui::SharedString;
+
+    fn window(first_line: usize, len: usize) -> LineWindow {
+        LineWindow {
+            first_line,
+            lines: vec![SharedString::default(); len],
+        }
+    }
+
+    #[test]
+    fn test_line_window_covers_viewports_inside_it() {
+        assert!(window(10, 30).covers(10, 10, 1_000));
+        assert!(window(10, 30).covers(30, 10, 1_000));
+        assert!(!window(10, 30).covers(31, 10, 1_000));
+        assert!(!window(10, 30).covers(5, 10, 1_000));
+    }
+
+    #[test]
+    fn test_line_window_covers_a_viewport_past_the_last_line() {
+        assert!(window(90, 10).covers(95, 10, 100));
+        assert!(!window(90, 10).covers(95, 10, 200));
+    }
+}
@@ -0,0 +1,246 @@
+use super::LargeFileView;
+use crate::fulgur::ui::{
+    bars::search_bar::{search_bar_button_factory, search_bar_toggle_button_factory},
+    components_utils::{LINE_HEIGHT, SEARCH_BAR_HEIGHT, TEXT_SIZE, format_file_size},
+    icons::CustomIcon,
+};
+use gpui::{
+    Context, Div, InteractiveElement, IntoElement, KeyDownEvent, MouseButton, ParentElement,
+    Pixels, Render, ScrollWheelEvent, SharedString, Styled, Window, div, prelude::FluentBuilder,
+    px, relative,
+};
+use gpui_component::{ActiveTheme, h_flex, input::Input, v_flex};
+
+/// Line height of the viewer, relative to the font size
+const LINE_HEIGHT_FACTOR: f32 = 1.5;
+
+/// Compute how many lines fit in a height
+///
+/// ### Arguments
+/// - `height`: The available height
+/// - `line_height`: The height of one line
+///
+/// ### Returns
+/// - `usize`: The number of lines, counting a partially visible last line
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn rows_for_height(height: Pixels, line_height: Pixels) -> usize {
+    let line_height = f32::from(line_height).max(1.0);
+    (f32::from(height) / line_height).ceil().max(1.0) as usize
+}
+
+impl LargeFileView {
+    /// Scroll the viewport with the mouse wheel or touchpad
+    ///
+    /// ### Arguments
+    /// - `event`: The scroll wheel event
+    /// - `line_height`: The height of one line
+    /// - `cx`: The view context
+    #[allow(clippy::cast_possible_truncation)]
+    fn on_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        line_height: Pixels,
+        cx: &mut Context<Self>,
+    ) {
+        let delta = event.delta.pixel_delta(line_height);
+        let lines = self.scroll_remainder - f32::from(delta.y) / f32::from(line_height);
+        let whole = lines.trunc();
+        self.scroll_remainder = lines - whole;
+        if whole != 0.0 {
+            self.scroll_by(whole as isize, cx);
+        }
+    }
+
+    /// Scroll the viewport with the navigation keys
+    ///
+    /// ### Arguments
+    /// - `event`: The key down event
+    /// - `cx`: The view context
+    fn on_key_down(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) {
+        let page = isize::try_from(self.visible_rows.saturating_sub(1).max(1)).unwrap_or(1);
+        match event.keystroke.key.as_str() {
+            "up" => self.scroll_by(-1, cx),
+            "down" => self.scroll_by(1, cx),
+            "pageup" => self.scroll_by(-page, cx),
+            "pagedown" => self.scroll_by(page, cx),
+            "home" => self.set_top_line(0, cx),
+            "end" => self.set_top_line(usize::MAX, cx),
+            _ => return,
+        }
+        cx.stop_propagation();
+    }
+
+    /// Describe the state of the viewer for the toolbar
+    ///
+    /// ### Returns
+    /// - `SharedString`: The indexing progress or line count, followed by the last search outcome
+    fn status_text(&self) -> SharedString {
+        let lines = self.line_count();
+        let size = format_file_size(self.index.file_len());
+        let summary = if let Some(error) = &self.index_error {
+            error.to_string()
+        } else if self.is_indexing() {
+            format!(
+                "Indexing {:.0}%: {lines} lines so far ({size})",
+                self.index.progress() * 100.0
+            )
+        } else {
+            format!("{lines} lines ({size}), read-only")
+        };
+        match &self.status {
+            Some(status) => format!("{summary} · {status}").into(),
+            None => summary.into(),
+        }
+    }
+
+    /// Render the search toolbar with the indexing status
+    ///
+    /// ### Arguments
+    /// - `cx`: The view context
+    ///
+    /// ### Returns
+    /// - `Div`: The rendered toolbar
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> Div {
+        h_flex()
+            .w_full()
+            .h(SEARCH_BAR_HEIGHT)
+            .bg(cx.theme().tab_bar)
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .w(px(320.))
+                    .h(SEARCH_BAR_HEIGHT)
+                    .bg(cx.theme().background)
+                    .child(
+                        Input::new(&self.search_input)
+                            .appearance(false)
+                            .bordered(false)
+                            .flex_1()
+                            .text_size(TEXT_SIZE)
+                            .line_height(LINE_HEIGHT)
+                            .pl_2()
+                            .h(SEARCH_BAR_HEIGHT),
+                    ),
+            )
+            .child(
+                search_bar_toggle_button_factory(
+                    "large-file-match-case",
+                    "Match case",
+                    CustomIcon::CaseSensitive,
+                    cx.theme().border,
+                    cx.theme().tab_bar,
+                    cx.theme().accent,
+                    self.match_case,
+                )
+                .on_click(cx.listener(|this, _, _window, cx| this.toggle_match_case(cx))),
+            )
+            .child(
+                search_bar_button_factory(
+                    "large-file-find-next",
+                    "Find next",
+                    CustomIcon::ChevronDown,
+                    cx.theme().border,
+                )
+                .on_click(cx.listener(|this, _, _window, cx| this.find_next(cx))),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .px_3()
+                    .text_size(TEXT_SIZE)
+                    .text_color(cx.theme().muted_foreground)
+                    .whitespace_nowrap()
+                    .overflow_hidden()
+                    .child(self.status_text()),
+            )
+    }
+}
+
+impl Render for LargeFileView {
+    /// Render the visible lines of the file below the search toolbar
+    ///
+    /// ### Arguments
+    /// - `window`: The window, whose height bounds the number of lines read
+    /// - `cx`: The view context
+    ///
+    /// ### Returns
+    /// - `impl IntoElement`: The rendered viewer
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let line_height = px(self.font_size * LINE_HEIGHT_FACTOR);
+        self.visible_rows = rows_for_height(window.viewport_size().height, line_height);
+        let top_line = self.top_line;
+        let highlighted_line = self.highlighted_line;
+        let lines = self.visible_lines(cx);
+        let last_line_number = (top_line + lines.len()).to_string();
+        #[allow(clippy::cast_precision_loss)]
+        let gutter_width =
+            px(last_line_number.len() as f32 * self.font_size * 0.6 + self.font_size * 2.0);
+        let highlight = cx.theme().accent;
+        let gutter_color = cx.theme().muted_foreground;
+        let rows = lines.into_iter().enumerate().map(|(offset, text)| {
+            let line = top_line + offset;
+            h_flex()
+                .h(line_height)
+                .w_full()
+                .when(highlighted_line == Some(line), |row| row.bg(highlight))
+                .child(
+                    div()
+                        .w(gutter_width)
+                        .flex_shrink_0()
+                        .pr_3()
+                        .text_right()
+                        .text_color(gutter_color)
+                        .child((line + 1).to_string()),
+                )
+                .child(div().whitespace_nowrap().child(text))
+        });
+        let indexing = self.is_indexing();
+        let progress = self.index.progress();
+        v_flex()
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .when(indexing, |this| {
+                this.child(
+                    div()
+                        .w_full()
+                        .h(px(2.))
+                        .bg(cx.theme().border)
+                        .child(div().h_full().w(relative(progress)).bg(cx.theme().accent)),
+                )
+            })
+            .child(
+                div()
+                    .id("large-file-lines")
+                    .track_focus(&self.focus_handle)
+                    .flex_1()
+                    .w_full()
+                    .overflow_hidden()
+                    .bg(cx.theme().background)
+                    .font_family(self.font_family.clone())
+                    .text_size(px(self.font_size))
+                    .line_height(line_height)
+                    .on_scroll_wheel(cx.listener(
+                        move |this, event: &ScrollWheelEvent, _window, cx| {
+                            this.on_scroll_wheel(event, line_height, cx);
+                        },
+                    ))
+                    .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                        this.on_key_down(event, cx);
+                    }))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _, window, cx| {
+                            window.focus(&this.focus_handle, cx);
+                        }),
+                    )
+                    .children(rows),
+            )
+    }
+}
@@ -1,7 +1,6 @@
-use crate::fulgur::ui::tabs::tab::TabId;
+use crate::fulgur::ui::{markdown_preview::MarkdownPreviewState, tabs::tab::TabId};
 use gpui::{Entity, SharedString};
 use gpui_component::input::EditorState;
-use gpui_component::text::TextViewState;
 
 /// A read-only tab that renders a live Markdown preview for a linked editor tab.
 pub struct MarkdownPreviewTab {
@@ -9,7 +8,7 @@ pub struct MarkdownPreviewTab {
     pub title: SharedString,
     pub source_tab_id: TabId,
     pub content: Entity<EditorState>,
-    /// Persistent text view state retained across renders so that the scroll
+    /// Persistent preview state retained across renders so that the scroll
     /// position survives switching to another tab and back within a session.
-    pub view_state: Entity<TextViewState>,
+    pub preview: Entity<MarkdownPreviewState>,
 }
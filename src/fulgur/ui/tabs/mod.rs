@@ -1,5 +1,6 @@
 pub mod color_tag;
 pub mod editor_tab;
+pub mod large_file_tab;
 pub mod markdown_preview_tab;
 pub mod settings_tab;
 pub mod tab;
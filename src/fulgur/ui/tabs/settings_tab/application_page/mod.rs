@@ -1,5 +1,5 @@
 use crate::fulgur::{Fulgur, settings::AppSettings};
-use gpui::{App, Entity};
+use gpui::{App, Entity, SharedString};
 use gpui_component::setting::{SettingField, SettingGroup, SettingItem, SettingPage};
 
 mod general;
@@ -109,6 +109,42 @@ pub fn create_application_page(entity: &Entity<Fulgur>) -> SettingPage {
                     }),
                 )
                 .description("How a tab's color tag is shown: title text or a dot."),
+                SettingItem::new(
+                    "Privileged Save Helper",
+                    SettingField::input(
+                        {
+                            let entity = entity.clone();
+                            move |cx: &App| {
+                                SharedString::from(
+                                    entity
+                                        .read(cx)
+                                        .settings
+                                        .app_settings
+                                        .privileged_save_helper
+                                        .clone(),
+                                )
+                            }
+                        },
+                        {
+                            let entity = entity.clone();
+                            move |val: SharedString, cx: &mut App| {
+                                entity.update(cx, |this, cx| {
+                                    this.settings.app_settings.privileged_save_helper =
+                                        val.trim().to_string();
+                                    if let Err(e) = this.update_and_propagate_settings(cx) {
+                                        log::error!("Failed to save settings: {e}");
+                                    }
+                                });
+                            }
+                        },
+                    )
+                    .default_value(SharedString::from(
+                        default_app_settings.privileged_save_helper.clone(),
+                    )),
+                )
+                .description(
+                    "Linux only. Command used to save files you lack permission to write, such as pkexec or sudo -A.",
+                ),
             ]),
             SettingGroup::new().title("Synchronization").items(vec![
                 render_sync_error_banner(),
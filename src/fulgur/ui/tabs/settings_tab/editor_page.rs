@@ -1,6 +1,7 @@
 use crate::fulgur::{
     Fulgur,
-    settings::{EditorSettings, MarkdownPreviewMode},
+    languages::spelling::{available_dictionaries, dictionaries_dir},
+    settings::{AutoSaveMode, EditorSettings, MarkdownPreviewMode},
 };
 use gpui::prelude::FluentBuilder as _;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Styled, Subscription, px};
@@ -38,6 +39,46 @@ fn slider_val_to_tab_size(val: f64) -> usize {
     val as usize
 }
 
+/// Convert a number field `f64` value to an auto-save delay in milliseconds.
+///
+/// The number field always provides `f64`; delays in the UI range (250–10000)
+/// are non-negative integers well within `u64` range, so the cast is safe here.
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn number_val_to_delay_ms(val: f64) -> u64 {
+    val as u64
+}
+
+/// Convert a number field `f64` value to a maximum line length in characters.
+///
+/// Line lengths in the UI range (40–400) are non-negative integers well within
+/// `usize` range, so the cast is safe here.
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn number_val_to_line_length(val: f64) -> usize {
+    val as usize
+}
+
+/// List the installed spell checking dictionaries as dropdown options
+///
+/// ### Arguments
+/// - `fallback`: The default dictionary, listed even when it is not installed
+///
+/// ### Returns
+/// - `Vec<(SharedString, SharedString)>`: The `(value, label)` dropdown options
+fn dictionary_options(fallback: &str) -> Vec<(SharedString, SharedString)> {
+    let mut names = dictionaries_dir()
+        .map(|dir| available_dictionaries(&dir))
+        .unwrap_or_default();
+    if !names.iter().any(|name| name == fallback) {
+        names.insert(0, fallback.to_string());
+    }
+    names
+        .into_iter()
+        .map(|name| (SharedString::from(name.clone()), SharedString::from(name)))
+        .collect()
+}
+
 /// Create a number input setting field that correctly propagates both step-button
 /// clicks and manual text edits to the entity.
 ///
@@ -234,6 +275,26 @@ pub fn create_editor_page(
                 .default_value(default_editor_settings.use_spaces),
             )
             .description("Insert spaces when pressing Tab instead of a tab character. Takes effect on new tabs."),
+            SettingItem::new(
+                "Detect Indentation",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| entity.read(cx).settings.editor_settings.detect_indentation
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.detect_indentation = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.detect_indentation),
+            )
+            .description("Infer the indent style and width from the content of opened files, overriding the two settings above. Takes effect on newly opened files."),
             SettingItem::new(
                 "Show Indent Guides",
                 SettingField::switch(
@@ -346,6 +407,63 @@ pub fn create_editor_page(
             .description(
                 "Show colored backgrounds for hex color codes (#RGB and #RRGGBB) in the editor.",
             ),
+            SettingItem::new(
+                "Spell Check",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| entity.read(cx).settings.editor_settings.spell_check
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.spell_check = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.spell_check),
+            )
+            .description(
+                "Underline misspelled words in Markdown, plain text, and code comments and strings.",
+            ),
+            SettingItem::new(
+                "Spell Check Dictionary",
+                SettingField::dropdown(
+                    dictionary_options(&default_editor_settings.spell_check_language),
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            SharedString::from(
+                                entity
+                                    .read(cx)
+                                    .settings
+                                    .editor_settings
+                                    .spell_check_language
+                                    .clone(),
+                            )
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: SharedString, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.spell_check_language =
+                                    val.to_string();
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(SharedString::from(
+                    default_editor_settings.spell_check_language.clone(),
+                )),
+            )
+            .description(
+                "Hunspell dictionary (.aff and .dic files) loaded from the dictionaries folder of the configuration directory.",
+            ),
         ]),
         SettingGroup::new().title("Markdown").items(vec![
             SettingItem::new(
@@ -487,6 +605,363 @@ pub fn create_editor_page(
                 ),
             )
             .description("Cap the preview at 800px and center it in its panel."),
+            SettingItem::new(
+                "Image Folder",
+                SettingField::input(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            SharedString::from(
+                                entity
+                                    .read(cx)
+                                    .settings
+                                    .editor_settings
+                                    .markdown_settings
+                                    .image_folder
+                                    .clone(),
+                            )
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: SharedString, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.markdown_settings.image_folder =
+                                    val.trim().to_string();
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(SharedString::from(
+                    default_editor_settings
+                        .markdown_settings
+                        .image_folder
+                        .clone(),
+                )),
+            )
+            .description("Folder, relative to the document, where pasted and dropped images are saved."),
+        ]),
+        SettingGroup::new().title("Markdown Lint").items(vec![
+            SettingItem::new(
+                "Lint Markdown",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .markdown_settings
+                                .lint
+                                .enabled
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.markdown_settings.lint.enabled = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.markdown_settings.lint.enabled),
+            )
+            .description("Underline Markdown style problems in the editor, with quick fixes in the context menu."),
+            SettingItem::new(
+                "Heading Levels",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .markdown_settings
+                                .lint
+                                .heading_increment
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.markdown_settings.lint.heading_increment = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.markdown_settings.lint.heading_increment),
+            )
+            .description("Headings go down one level at a time."),
+            SettingItem::new(
+                "Trailing Spaces",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .markdown_settings
+                                .lint
+                                .trailing_spaces
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.markdown_settings.lint.trailing_spaces = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.markdown_settings.lint.trailing_spaces),
+            )
+            .description("Lines end without spaces, except the two of a hard line break."),
+            SettingItem::new(
+                "List Markers",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .markdown_settings
+                                .lint
+                                .list_marker_style
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.markdown_settings.lint.list_marker_style = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.markdown_settings.lint.list_marker_style),
+            )
+            .description("Bullet lists use the same marker throughout a document."),
+            SettingItem::new(
+                "Image Alt Text",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .markdown_settings
+                                .lint
+                                .missing_alt_text
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.markdown_settings.lint.missing_alt_text = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.markdown_settings.lint.missing_alt_text),
+            )
+            .description("Images have alternative text."),
+            SettingItem::new(
+                "Duplicate Headings",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .markdown_settings
+                                .lint
+                                .duplicate_headings
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.markdown_settings.lint.duplicate_headings = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.markdown_settings.lint.duplicate_headings),
+            )
+            .description("No two headings have the same text."),
+            SettingItem::new(
+                "Line Length",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .markdown_settings
+                                .lint
+                                .line_length
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.markdown_settings.lint.line_length = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.markdown_settings.lint.line_length),
+            )
+            .description("Lines are no longer than the maximum line length."),
+            SettingItem::new(
+                "Maximum Line Length",
+                make_number_field(
+                    "editor-markdown-max-line-length".into(),
+                    &NumberFieldOptions {
+                        min: 40.0,
+                        max: 400.0,
+                        step: 10.0,
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            // The line length is at most a few hundred; the f64 cast is exact.
+                            #[allow(clippy::cast_precision_loss)]
+                            let max_line_length = entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .markdown_settings
+                                .lint
+                                .max_line_length as f64;
+                            max_line_length
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: f64, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings
+                                    .editor_settings
+                                    .markdown_settings
+                                    .lint
+                                    .max_line_length = number_val_to_line_length(val);
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                ),
+            )
+            .description("Longest line accepted by the line length rule, in characters."),
+        ]),
+        SettingGroup::new().title("Printing").items(vec![
+            SettingItem::new(
+                "Line Numbers",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .print_settings
+                                .line_numbers
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.print_settings.line_numbers = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.print_settings.line_numbers),
+            )
+            .description("Print line numbers next to source code."),
+            SettingItem::new(
+                "Header",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .print_settings
+                                .header
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.print_settings.header = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.print_settings.header),
+            )
+            .description("Print the file name, path and date above the document."),
+            SettingItem::new(
+                "Page Numbers",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            entity
+                                .read(cx)
+                                .settings
+                                .editor_settings
+                                .print_settings
+                                .footer
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.print_settings.footer = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.print_settings.footer),
+            )
+            .description("Print the page number at the bottom of each page."),
         ]),
         SettingGroup::new().title("File Monitoring").items(vec![
             SettingItem::new(
@@ -515,5 +990,95 @@ pub fn create_editor_page(
             )
             .description("Monitor files for external changes."),
         ]),
+        SettingGroup::new().title("Saving").items(vec![
+            SettingItem::new(
+                "Auto Save",
+                SettingField::dropdown(
+                    vec![
+                        ("off".into(), "Off".into()),
+                        ("after_delay".into(), "After Delay".into()),
+                        ("on_focus_change".into(), "On Focus Change".into()),
+                        ("on_window_change".into(), "On Window Change".into()),
+                    ],
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| match entity.read(cx).settings.editor_settings.auto_save {
+                            AutoSaveMode::Off => "off".into(),
+                            AutoSaveMode::AfterDelay => "after_delay".into(),
+                            AutoSaveMode::OnFocusChange => "on_focus_change".into(),
+                            AutoSaveMode::OnWindowChange => "on_window_change".into(),
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: SharedString, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.auto_save = match val.as_ref() {
+                                    "after_delay" => AutoSaveMode::AfterDelay,
+                                    "on_focus_change" => AutoSaveMode::OnFocusChange,
+                                    "on_window_change" => AutoSaveMode::OnWindowChange,
+                                    _ => AutoSaveMode::Off,
+                                };
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(SharedString::from("off")),
+            )
+            .description("Save modified files automatically. Untitled tabs are never auto-saved."),
+            SettingItem::new(
+                "Auto Save Delay",
+                make_number_field(
+                    "editor-auto-save-delay".into(),
+                    &NumberFieldOptions {
+                        min: 250.0,
+                        max: 10000.0,
+                        step: 250.0,
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| {
+                            // The delay is at most a few seconds in ms; the f64 cast is exact.
+                            #[allow(clippy::cast_precision_loss)]
+                            let delay =
+                                entity.read(cx).settings.editor_settings.auto_save_delay_ms as f64;
+                            delay
+                        }
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: f64, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.auto_save_delay_ms =
+                                    number_val_to_delay_ms(val);
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                ),
+            )
+            .description("Milliseconds without typing before a file is saved in After Delay mode."),
+            SettingItem::new(
+                "Auto Save Remote Files",
+                SettingField::switch(
+                    {
+                        let entity = entity.clone();
+                        move |cx: &App| entity.read(cx).settings.editor_settings.auto_save_remote
+                    },
+                    {
+                        let entity = entity.clone();
+                        move |val: bool, cx: &mut App| {
+                            entity.update(cx, |this, cx| {
+                                this.settings.editor_settings.auto_save_remote = val;
+                                let _ = this.update_and_propagate_settings(cx);
+                            });
+                        }
+                    },
+                )
+                .default_value(default_editor_settings.auto_save_remote),
+            )
+            .description("Also auto-save files opened over SSH, once their password is cached."),
+        ]),
     ])
 }
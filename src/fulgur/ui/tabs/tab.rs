@@ -1,12 +1,16 @@
 use crate::fulgur::{
     Fulgur,
+    files::indentation::{Indentation, IndentationChange, IndentationSource},
+    languages::supported_languages::language_registry_name,
     settings::EditorSettings,
     ui::tabs::{
-        editor_tab::EditorTab, markdown_preview_tab::MarkdownPreviewTab, settings_tab::SettingsTab,
+        editor_tab::EditorTab, large_file_tab::LargeFileTab,
+        markdown_preview_tab::MarkdownPreviewTab, settings_tab::SettingsTab,
     },
 };
 use gpui::{App, AppContext, Context, Entity, SharedString, Window};
 use gpui_component::input::InputEvent;
+use std::time::Instant;
 
 /// Stable identifier of a tab, unique within a window for the process lifetime
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -36,6 +40,7 @@ pub enum Tab {
     Editor(EditorTab),
     Settings(SettingsTab),
     MarkdownPreview(MarkdownPreviewTab),
+    LargeFile(LargeFileTab),
 }
 
 impl Tab {
@@ -48,6 +53,7 @@ impl Tab {
             Tab::Editor(tab) => tab.id,
             Tab::Settings(tab) => tab.id,
             Tab::MarkdownPreview(tab) => tab.id,
+            Tab::LargeFile(tab) => tab.id,
         }
     }
 
@@ -60,6 +66,7 @@ impl Tab {
             Tab::Editor(tab) => tab.title.clone(),
             Tab::Settings(tab) => tab.title.clone(),
             Tab::MarkdownPreview(tab) => tab.title.clone(),
+            Tab::LargeFile(tab) => tab.title.clone(),
         }
     }
 
@@ -70,7 +77,7 @@ impl Tab {
     pub fn is_modified(&self) -> bool {
         match self {
             Tab::Editor(tab) => tab.modified,
-            Tab::Settings(_) | Tab::MarkdownPreview(_) => false,
+            Tab::Settings(_) | Tab::MarkdownPreview(_) | Tab::LargeFile(_) => false,
         }
     }
 
@@ -98,6 +105,18 @@ impl Tab {
         }
     }
 
+    /// Get the large-file tab if this is a large-file viewer
+    ///
+    /// ### Returns
+    /// - `Some(&LargeFileTab)`: The large-file tab if this is a large-file viewer
+    /// - `None`: If this is not a large-file viewer
+    pub fn as_large_file(&self) -> Option<&LargeFileTab> {
+        match self {
+            Tab::LargeFile(tab) => Some(tab),
+            _ => None,
+        }
+    }
+
     /// Get the editor tab mutably if this is an editor tab
     ///
     /// ### Returns
@@ -141,6 +160,7 @@ impl Tab {
                     return;
                 }
                 if let Tab::Editor(editor_tab) = this {
+                    editor_tab.last_edit = Some(Instant::now());
                     if editor_tab.large_file {
                         if !editor_tab.modified {
                             editor_tab.modified = true;
@@ -170,6 +190,12 @@ impl Tab {
         cx: &mut Context<Tab>,
         settings: &EditorSettings,
     ) {
+        if let Tab::LargeFile(large_file_tab) = self {
+            large_file_tab
+                .view
+                .update(cx, |view, cx| view.update_settings(settings, cx));
+            return;
+        }
         let Tab::Editor(editor_tab) = self else {
             return;
         };
@@ -178,24 +204,16 @@ impl Tab {
         self.reattach_if_content_swapped(content_before, cx);
     }
 
-    /// Update the language from the file extension, re-attaching the content subscription
+    /// Update the language from the file extension
     ///
     /// ### Arguments
-    /// - `window`: The window context
     /// - `cx`: The tab entity context
-    /// - `settings`: The editor settings for the new input state
-    pub fn update_language(
-        &mut self,
-        window: &mut Window,
-        cx: &mut Context<Tab>,
-        settings: &EditorSettings,
-    ) {
+    pub fn update_language(&mut self, cx: &mut Context<Tab>) {
         let Tab::Editor(editor_tab) = self else {
             return;
         };
-        let content_before = editor_tab.content.entity_id();
-        editor_tab.update_language(window, cx, settings);
-        self.reattach_if_content_swapped(content_before, cx);
+        editor_tab.update_language(cx);
+        cx.notify();
     }
 
     /// Rename an editor tab and re-detect its language from the new name
@@ -255,6 +273,61 @@ impl Tab {
         self.reattach_if_content_swapped(content_before, cx);
     }
 
+    /// Apply an indentation change requested from the status bar
+    ///
+    /// ### Arguments
+    /// - `change`: The requested change
+    /// - `window`: The window context
+    /// - `cx`: The tab entity context
+    /// - `settings`: The editor settings
+    pub fn change_indentation(
+        &mut self,
+        change: IndentationChange,
+        window: &mut Window,
+        cx: &mut Context<Tab>,
+        settings: &EditorSettings,
+    ) {
+        let Tab::Editor(editor_tab) = self else {
+            return;
+        };
+        let content = editor_tab.content.read(cx).text().to_string();
+        let language = language_registry_name(&editor_tab.language);
+        let indentation = editor_tab
+            .indentation
+            .changed(change, &content, language, settings);
+        editor_tab.set_indentation(indentation, window, cx);
+        cx.notify();
+    }
+
+    /// Resolve the indentation again after the content was reloaded, unless it was overridden
+    ///
+    /// ### Arguments
+    /// - `content`: The reloaded content
+    /// - `window`: The window context
+    /// - `cx`: The tab entity context
+    /// - `settings`: The editor settings
+    pub fn redetect_indentation(
+        &mut self,
+        content: &str,
+        window: &mut Window,
+        cx: &mut Context<Tab>,
+        settings: &EditorSettings,
+    ) {
+        let Tab::Editor(editor_tab) = self else {
+            return;
+        };
+        if editor_tab.indentation.source == IndentationSource::Override {
+            return;
+        }
+        let indentation = Indentation::resolve(
+            content,
+            language_registry_name(&editor_tab.language),
+            settings,
+        );
+        editor_tab.set_indentation(indentation, window, cx);
+        cx.notify();
+    }
+
     /// Re-attach the content subscription when the content entity was replaced
     ///
     /// ### Arguments
@@ -1,6 +1,6 @@
 use super::{
     CloseAllOtherTabs, CloseAllTabsAction, CloseTabAction, CloseTabsToLeft, CloseTabsToRight,
-    CopyPath, DuplicateTab, RenameTab, SetTabColor, ShowInFileManager,
+    CopyPath, DuplicateTab, RenameTab, SetTabColor, ShowInFileManager, ToggleReadOnly,
 };
 use crate::fulgur::Fulgur;
 use gpui::{ClipboardItem, Context, Window};
@@ -200,6 +200,27 @@ impl Fulgur {
         }
     }
 
+    /// Handle toggle read-only action from the context menu
+    ///
+    /// ### Arguments
+    /// - `action`: The action carrying the tab ID
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn on_toggle_read_only(
+        &mut self,
+        action: &ToggleReadOnly,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let updated = self.update_editor_tab(action.0, cx, |editor, cx| {
+            editor.set_read_only(!editor.read_only, cx);
+            cx.notify();
+        });
+        if updated.is_some() {
+            self.save_state_async(cx, window);
+        }
+    }
+
     /// Handle rename tab action from the context menu.
     ///
     /// ### Arguments
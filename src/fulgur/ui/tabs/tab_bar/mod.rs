@@ -52,6 +52,10 @@ pub struct SetTabColor(pub TabId, pub Option<ColorTag>);
 #[action(namespace = fulgur, no_json)]
 pub struct RenameTab(pub TabId);
 
+#[derive(Action, Clone, PartialEq)]
+#[action(namespace = fulgur, no_json)]
+pub struct ToggleReadOnly(pub TabId);
+
 gpui::actions!(fulgur, [CloseAllTabsAction, SendTabToWindowNoOp]);
 
 /// Create a tab bar button
@@ -1,7 +1,7 @@
 use super::{
     CloseAllOtherTabs, CloseAllTabsAction, CloseTabAction, CloseTabsToLeft, CloseTabsToRight,
     CopyPath, DuplicateTab, RenameTab, SendTabToWindowNoOp, SetTabColor, ShowInFileManager, TabBar,
-    TabBarEvent, tab_bar_button_factory,
+    TabBarEvent, ToggleReadOnly, tab_bar_button_factory,
 };
 use crate::fulgur::{
     Fulgur,
@@ -267,6 +267,9 @@ impl TabBar {
         });
         let has_file_path = file_path.is_some();
         let is_editor_tab = tab.as_editor().is_some();
+        let is_read_only = tab
+            .as_editor()
+            .is_some_and(|editor_tab| editor_tab.read_only);
         let is_renameable = tab
             .as_editor()
             .is_some_and(crate::fulgur::editor_tab::EditorTab::is_renameable);
@@ -405,6 +408,14 @@ impl TabBar {
                     .child(remote_indicator),
             );
         }
+        if is_read_only {
+            title_container = title_container.child(
+                CustomIcon::Lock
+                    .icon()
+                    .size(px(12.))
+                    .text_color(title_color),
+            );
+        }
         title_container = title_container.child(
             div()
                 .text_sm()
@@ -590,6 +601,11 @@ impl TabBar {
             } else {
                 this.menu_with_disabled("Color Tag", Box::new(SetTabColor(tab_id, None)), true)
             };
+            let this = if is_editor_tab {
+                this.menu_with_check("Read Only", is_read_only, Box::new(ToggleReadOnly(tab_id)))
+            } else {
+                this.menu_with_disabled("Read Only", Box::new(ToggleReadOnly(tab_id)), true)
+            };
             this.separator()
                 .menu("Close Tab", Box::new(CloseTabAction(tab_id)))
                 .menu_with_disabled(
@@ -341,3 +341,27 @@ fn test_on_previous_tab_is_noop_when_no_active_tab(cx: &mut TestAppContext) {
         });
     });
 }
+
+// ========== on_toggle_read_only tests ==========
+
+#[gpui::test]
+fn test_on_toggle_read_only_flips_the_editor_tab_flag(cx: &mut TestAppContext) {
+    let (fulgur, mut visual_cx) = setup_fulgur(cx);
+
+    visual_cx.update(|window, cx| {
+        fulgur.update(cx, |this, cx| {
+            let tab_id = this
+                .active_tab_id
+                .expect("expected an active tab after setup");
+            let is_read_only = |this: &Fulgur, cx: &gpui::App| {
+                this.tab_entity_of(tab_id, cx)
+                    .and_then(|tab| tab.read(cx).as_editor().map(|editor| editor.read_only))
+            };
+            assert_eq!(is_read_only(this, cx), Some(false));
+            this.on_toggle_read_only(&super::ToggleReadOnly(tab_id), window, cx);
+            assert_eq!(is_read_only(this, cx), Some(true));
+            this.on_toggle_read_only(&super::ToggleReadOnly(tab_id), window, cx);
+            assert_eq!(is_read_only(this, cx), Some(false));
+        });
+    });
+}
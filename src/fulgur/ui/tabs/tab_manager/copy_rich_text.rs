@@ -0,0 +1,104 @@
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::read_text_and_selection,
+    languages::{
+        supported_languages::language_registry_name,
+        syntax_spans::{hsla_to_rgb, syntax_spans},
+    },
+    utils::{
+        code_html::{CodeStyle, clip_spans, code_fragment_html},
+        code_rtf::highlighted_code_rtf,
+        rich_clipboard::{RichTextFormat, write_rich_text},
+    },
+};
+use gpui::{ClipboardItem, Context, SharedString, Window};
+use gpui_component::{ActiveTheme, WindowExt, notification::NotificationType};
+
+impl Fulgur {
+    /// Copy the selection of the active editor tab, or its whole content, with its syntax colors
+    ///
+    /// ### Description
+    /// The text is rendered with the current theme and the editor font. When the
+    /// system clipboard cannot hold the format, the text is copied as plain text
+    /// and a notification says so. The text is highlighted and written to the
+    /// clipboard on the background executor.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    /// - `format`: The rich text format to copy
+    pub(crate) fn copy_as_rich_text(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        format: RichTextFormat,
+    ) {
+        let Some(editor_tab) = self.get_active_editor_tab(cx) else {
+            return;
+        };
+        let content = editor_tab.content.clone();
+        let language = editor_tab.language;
+        let (text, selection) = read_text_and_selection(&content, window, cx);
+        let range = if selection.is_empty() {
+            0..text.len()
+        } else {
+            selection
+        };
+        if text.get(range.clone()).is_none_or(str::is_empty) {
+            return;
+        }
+        let theme = cx.theme();
+        let highlight_theme = theme.highlight_theme.clone();
+        let style = CodeStyle {
+            font_family: self.settings.editor_settings.font_family.clone(),
+            font_size: self.settings.editor_settings.font_size,
+            background: hsla_to_rgb(theme.background),
+            foreground: hsla_to_rgb(theme.foreground),
+        };
+        cx.spawn_in(window, async move |_, window| {
+            let (code, written) = window
+                .background_executor()
+                .spawn(async move {
+                    let spans = clip_spans(
+                        &syntax_spans(&text, language_registry_name(&language), &highlight_theme),
+                        &range,
+                    );
+                    let code = &text[range];
+                    let rich = match format {
+                        RichTextFormat::Html => code_fragment_html(code, &spans, &style),
+                        RichTextFormat::Rtf => highlighted_code_rtf(code, &spans, &style),
+                    };
+                    let written = write_rich_text(format, &rich, code);
+                    (code.to_string(), written)
+                })
+                .await;
+            let notification = match written {
+                Ok(true) => return,
+                Ok(false) => (
+                    NotificationType::Info,
+                    SharedString::from(format!(
+                        "{} is not available on this system, copied as plain text",
+                        format.label()
+                    )),
+                ),
+                Err(e) => {
+                    log::error!("Failed to copy as {}: {e}", format.label());
+                    (
+                        NotificationType::Warning,
+                        SharedString::from(format!(
+                            "Failed to copy as {}, copied as plain text: {e}",
+                            format.label()
+                        )),
+                    )
+                }
+            };
+            window
+                .update(|window, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(code));
+                    window.push_notification(notification, cx);
+                })
+                .ok();
+        })
+        .detach();
+    }
+}
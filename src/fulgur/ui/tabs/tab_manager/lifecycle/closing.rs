@@ -298,6 +298,7 @@ impl Fulgur {
                 self.tabs.remove(preview_pos);
                 self.close_tab_manage_focus(window, cx, preview_pos);
             }
+            self.refresh_split_panes(window, cx);
             cx.notify();
         }
     }
@@ -141,6 +141,7 @@ impl Fulgur {
         let raw_title = editor_tab.title.to_string();
         let encoding = editor_tab.encoding.clone();
         let lossy_decode = editor_tab.lossy_decode;
+        let indentation = editor_tab.indentation;
         let settings = self.settings.editor_settings.clone();
         let clean_title: SharedString = raw_title.trim_end_matches(" •").trim().to_string().into();
         let id = self.allocate_tab_id();
@@ -152,6 +153,7 @@ impl Fulgur {
                 encoding,
                 lossy_decode,
                 language,
+                indentation,
             },
             window,
             cx,
@@ -0,0 +1,253 @@
+use crate::fulgur::{
+    Fulgur,
+    tab::TabId,
+    utils::markdown_assets::{
+        image_extension_for_mime, image_markdown, import_image, pasted_image_stem, save_image,
+    },
+};
+use gpui::{ClipboardEntry, Context, Entity, Focusable, SharedString, Window};
+use gpui_component::{WindowExt, input::EditorState, notification::NotificationType};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+/// The Markdown tab receiving images: its ID, its content and the directory of its file
+type ImageTarget = (TabId, Entity<EditorState>, PathBuf);
+
+impl Fulgur {
+    /// Get the active Markdown tab, its content and the directory of its file
+    ///
+    /// ### Description
+    /// Notifies the user when the document has never been saved, as its images
+    /// have no folder to go to yet.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(ImageTarget)`: The tab, its content and the directory of its file
+    /// - `None`: The active tab is not an editable Markdown tab, or it has no file yet
+    fn markdown_image_target(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<ImageTarget> {
+        let content = self.active_markdown_content(cx)?;
+        let editor_tab = self.get_active_editor_tab(cx)?;
+        let tab_id = editor_tab.id;
+        let document_dir = editor_tab
+            .file_path()
+            .and_then(|path| path.parent())
+            .map(Path::to_path_buf);
+        if document_dir.is_none() {
+            window.push_notification(
+                (
+                    NotificationType::Warning,
+                    SharedString::from("Save the document before adding images to it"),
+                ),
+                cx,
+            );
+        }
+        Some((tab_id, content, document_dir?))
+    }
+
+    /// Insert image links at the cursor of a Markdown tab
+    ///
+    /// ### Arguments
+    /// - `content`: The content of the Markdown tab
+    /// - `images`: The paths of the images, relative to the document
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn insert_image_links(
+        content: &Entity<EditorState>,
+        images: &[PathBuf],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if images.is_empty() {
+            return;
+        }
+        let links = images
+            .iter()
+            .map(|image| {
+                let alt = image
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                image_markdown(&alt, image)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        content.update(cx, |input_state, cx| {
+            input_state.replace(links, window, cx);
+            cx.notify();
+        });
+    }
+
+    /// Write images next to a Markdown document in the background, then link them
+    ///
+    /// ### Description
+    /// The files are written on the background executor, so a large image or a
+    /// slow disk never blocks typing. The links are inserted at the cursor once
+    /// the files are in place, unless the tab was closed meanwhile.
+    ///
+    /// ### Arguments
+    /// - `tab_id`: The tab receiving the links
+    /// - `content`: The content of the tab
+    /// - `failure`: The start of the message reporting an image that could not be written
+    /// - `write`: The job writing the images, returning the path of each relative to the document
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn add_images_in_background<F>(
+        tab_id: TabId,
+        content: Entity<EditorState>,
+        failure: &'static str,
+        write: F,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) where
+        F: FnOnce() -> Vec<anyhow::Result<PathBuf>> + Send + 'static,
+    {
+        cx.spawn_in(window, async move |view, window| {
+            let results = window
+                .background_executor()
+                .spawn(async move { write() })
+                .await;
+            window
+                .update(|window, cx| {
+                    _ = view.update(cx, |this, cx| {
+                        let mut linked = Vec::new();
+                        for result in results {
+                            match result {
+                                Ok(path) => linked.push(path),
+                                Err(e) => {
+                                    log::error!("{failure}: {e}");
+                                    window.push_notification(
+                                        (
+                                            NotificationType::Error,
+                                            SharedString::from(format!("{failure}: {e}")),
+                                        ),
+                                        cx,
+                                    );
+                                }
+                            }
+                        }
+                        if this.tab_entity_of(tab_id, cx).is_some() {
+                            Self::insert_image_links(&content, &linked, window, cx);
+                        }
+                    });
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Save the image of the clipboard next to the active Markdown document and link it
+    ///
+    /// ### Description
+    /// Only applies while the editor of the active Markdown tab has focus and the
+    /// clipboard holds an image without text, so that text pastes are untouched.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `bool`: True if the paste was handled as an image
+    pub(crate) fn paste_image_into_markdown(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let focused = self
+            .active_markdown_content(cx)
+            .is_some_and(|content| content.focus_handle(cx).is_focused(window));
+        if !focused {
+            return false;
+        }
+        let Some(item) = cx.read_from_clipboard() else {
+            return false;
+        };
+        if item.text().is_some_and(|text| !text.is_empty()) {
+            return false;
+        }
+        let Some(image) = item.entries().iter().find_map(|entry| match entry {
+            ClipboardEntry::Image(image) => Some(image.clone()),
+            _ => None,
+        }) else {
+            return false;
+        };
+        let Some((tab_id, content, document_dir)) = self.markdown_image_target(window, cx) else {
+            return true;
+        };
+        let folder = self
+            .settings
+            .editor_settings
+            .markdown_settings
+            .image_folder
+            .clone();
+        let stem = pasted_image_stem(OffsetDateTime::now_utc());
+        let extension = image_extension_for_mime(image.format.mime_type()).to_string();
+        Self::add_images_in_background(
+            tab_id,
+            content,
+            "Failed to save pasted image",
+            move || {
+                vec![save_image(
+                    &document_dir,
+                    &folder,
+                    &stem,
+                    &extension,
+                    &image.bytes,
+                )]
+            },
+            window,
+            cx,
+        );
+        true
+    }
+
+    /// Copy dropped image files next to the active Markdown document and link them
+    ///
+    /// ### Arguments
+    /// - `images`: The dropped image files
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `bool`: True if the active tab is a Markdown tab and the drop was handled
+    pub(crate) fn drop_images_into_markdown(
+        &mut self,
+        images: &[PathBuf],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.active_markdown_content(cx).is_none() {
+            return false;
+        }
+        let Some((tab_id, content, document_dir)) = self.markdown_image_target(window, cx) else {
+            return true;
+        };
+        let folder = self
+            .settings
+            .editor_settings
+            .markdown_settings
+            .image_folder
+            .clone();
+        let images = images.to_vec();
+        Self::add_images_in_background(
+            tab_id,
+            content,
+            "Failed to add dropped image",
+            move || {
+                images
+                    .iter()
+                    .map(|image| import_image(&document_dir, &folder, image))
+                    .collect()
+            },
+            window,
+            cx,
+        );
+        true
+    }
+}
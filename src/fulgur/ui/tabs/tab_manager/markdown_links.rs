@@ -0,0 +1,505 @@
+use crate::fulgur::{
+    Fulgur,
+    diagnostics::DiagnosticSource,
+    editor_tab::Jump,
+    files::file_operations::looks_binary,
+    tab::Tab,
+    ui::{markdown_preview::PreviewLocation, tabs::tab::TabId},
+    utils::{
+        markdown_extensions::footnote_anchors,
+        markdown_links::{
+            BrokenLink, LinkTarget, anchor_line, find_broken_links, heading_anchors,
+            is_markdown_path, resolve_link,
+        },
+    },
+};
+use gpui::{App, Context, Entity, SharedString, Window};
+use gpui_component::{
+    WindowExt,
+    input::{EditorState, InputEvent, Position},
+    notification::NotificationType,
+};
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Source tag set on the diagnostics marking broken links
+const LINK_CHECK_SOURCE: &str = "markdown links";
+
+/// Number of bytes read to tell a text file from a binary one
+const TEXT_SNIFF_LEN: u64 = 8_000;
+
+/// Number of broken links named in the notification of a link check
+const MAX_REPORTED_LINKS: usize = 5;
+
+/// The Markdown document behind the active tab: its content and path
+type MarkdownSource = (Entity<EditorState>, Option<PathBuf>);
+
+impl Fulgur {
+    /// Get the Markdown document shown by the active tab
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(MarkdownSource)`: The active Markdown editor tab, or the source of the active preview tab
+    /// - `None`: The active tab shows no Markdown document
+    fn active_markdown_source(&self, cx: &App) -> Option<MarkdownSource> {
+        let tab_id = match self.active_tab(cx)? {
+            Tab::Editor(editor_tab) if self.is_markdown(cx) => editor_tab.id,
+            Tab::MarkdownPreview(preview_tab) => preview_tab.source_tab_id,
+            _ => return None,
+        };
+        let index = self.tab_index_of(tab_id, cx)?;
+        let editor_tab = self.tabs.get(index)?.read(cx).as_editor()?;
+        Some((editor_tab.content.clone(), editor_tab.file_path().cloned()))
+    }
+
+    /// Get the location of the Markdown document the active tab shows
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(PreviewLocation)`: The cursor line of an editor tab, or the top line of a preview tab
+    /// - `None`: The active tab is neither an editor nor a preview
+    fn current_preview_location(&self, cx: &App) -> Option<PreviewLocation> {
+        match self.active_tab(cx)? {
+            Tab::Editor(editor_tab) => Some(PreviewLocation {
+                tab_id: editor_tab.id,
+                line: editor_tab.content.read(cx).cursor_position().line as usize,
+            }),
+            Tab::MarkdownPreview(preview_tab) => Some(PreviewLocation {
+                tab_id: preview_tab.source_tab_id,
+                line: preview_tab.preview.read(cx).top_line(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Show a line of a Markdown document
+    ///
+    /// ### Description
+    /// A preview tab of the document scrolls to the line. Otherwise the editor tab
+    /// of the document is activated with its cursor on the line, which its
+    /// preview panel follows.
+    ///
+    /// ### Arguments
+    /// - `location`: The location to show
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `bool`: False if the tab of the document was closed
+    fn show_preview_location(
+        &mut self,
+        location: PreviewLocation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let preview = match self.active_tab(cx) {
+            Some(Tab::MarkdownPreview(preview_tab))
+                if preview_tab.source_tab_id == location.tab_id =>
+            {
+                Some(preview_tab.preview.clone())
+            }
+            _ => None,
+        };
+        if let Some(preview) = preview {
+            preview.read(cx).scroll_to_line(location.line);
+            cx.notify();
+            return true;
+        }
+        if self.tab_index_of(location.tab_id, cx).is_none() {
+            return false;
+        }
+        self.jump_to_preview_source(location.tab_id, location.line, window, cx);
+        true
+    }
+
+    /// Remember the location of the active tab before following a link
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    fn remember_preview_location(&mut self, cx: &App) {
+        if let Some(location) = self.current_preview_location(cx) {
+            self.markdown_link_history.visit(location);
+        }
+    }
+
+    /// Follow a link clicked in a Markdown preview
+    ///
+    /// ### Description
+    /// Web addresses open in the browser. A heading of the document is scrolled
+    /// to. A local Markdown or text file opens in a tab, or is focused when it is
+    /// already open, at the heading the link names; other files open with their
+    /// default application.
+    ///
+    /// ### Arguments
+    /// - `source_tab_id`: The editor tab of the previewed document
+    /// - `url`: The target of the link, as written in the document
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn follow_preview_link(
+        &mut self,
+        source_tab_id: TabId,
+        url: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((source, base_dir)) = self
+            .tab_index_of(source_tab_id, cx)
+            .and_then(|index| self.tabs.get(index))
+            .and_then(|tab| tab.read(cx).as_editor())
+            .map(|editor_tab| {
+                (
+                    editor_tab.content.read(cx).text().to_string(),
+                    editor_tab
+                        .file_path()
+                        .and_then(|path| path.parent())
+                        .map(Path::to_path_buf),
+                )
+            })
+        else {
+            return;
+        };
+        match resolve_link(url, base_dir.as_deref()) {
+            None => window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from(format!("Save the document to follow the link to {url}")),
+                ),
+                cx,
+            ),
+            Some(LinkTarget::External(url)) => cx.open_url(&url),
+            Some(LinkTarget::Anchor(anchor)) => {
+                // Footnotes and their references get anchors of their own in the preview
+                let mut anchors = heading_anchors(&source);
+                anchors.extend(footnote_anchors(&source));
+                let Some(line) = anchor_line(&anchors, &anchor) else {
+                    window.push_notification(
+                        (
+                            NotificationType::Warning,
+                            SharedString::from(format!("No heading matches #{anchor}")),
+                        ),
+                        cx,
+                    );
+                    return;
+                };
+                self.remember_preview_location(cx);
+                self.show_preview_location(
+                    PreviewLocation {
+                        tab_id: source_tab_id,
+                        line,
+                    },
+                    window,
+                    cx,
+                );
+            }
+            Some(LinkTarget::File { path, anchor }) => {
+                self.open_linked_file(&path, anchor.as_deref(), window, cx);
+            }
+        }
+    }
+
+    /// Open a local file a Markdown link leads to
+    ///
+    /// ### Arguments
+    /// - `path`: The linked file
+    /// - `anchor`: The linked heading, for a Markdown file
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn open_linked_file(
+        &mut self,
+        path: &Path,
+        anchor: Option<&str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !path.exists() {
+            window.push_notification(
+                (
+                    NotificationType::Warning,
+                    SharedString::from(format!("No file at {}", path.display())),
+                ),
+                cx,
+            );
+            return;
+        }
+        if path.is_dir() || !is_text_file(path) {
+            if let Err(e) = open::that(path) {
+                log::error!("Failed to open {}: {e}", path.display());
+                window.push_notification(
+                    (
+                        NotificationType::Error,
+                        SharedString::from(format!("Failed to open {}: {e}", path.display())),
+                    ),
+                    cx,
+                );
+            }
+            return;
+        }
+        let line = anchor
+            .filter(|_| is_markdown_path(path))
+            .and_then(|anchor| {
+                let text = self.linked_file_text(path, cx)?;
+                let line = anchor_line(&heading_anchors(&text), anchor);
+                if line.is_none() {
+                    window.push_notification(
+                        (
+                            NotificationType::Warning,
+                            SharedString::from(format!("No heading matches #{anchor}")),
+                        ),
+                        cx,
+                    );
+                }
+                line
+            });
+        self.remember_preview_location(cx);
+        let jump = line.map(|line| Jump {
+            line: u32::try_from(line).unwrap_or(u32::MAX),
+            character: None,
+        });
+        self.do_open_file_at(window, cx, path.to_path_buf(), jump);
+    }
+
+    /// Read a linked file, from its tab when it is already open
+    ///
+    /// ### Arguments
+    /// - `path`: The linked file
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(String)`: The text of the file, with the unsaved edits of its tab
+    /// - `None`: The file could not be read
+    fn linked_file_text(&self, path: &Path, cx: &App) -> Option<String> {
+        let open_text = std::fs::canonicalize(path)
+            .ok()
+            .and_then(|canonical| self.find_tab_by_path(&canonical, cx))
+            .and_then(|index| self.tabs.get(index))
+            .and_then(|tab| tab.read(cx).as_editor())
+            .map(|editor_tab| editor_tab.content.read(cx).text().to_string());
+        open_text.or_else(|| std::fs::read_to_string(path).ok())
+    }
+
+    /// Go back to where the last link of a Markdown preview was followed from
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn markdown_link_back(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(current) = self.current_preview_location(cx) else {
+            return;
+        };
+        if let Some(location) = self.markdown_link_history.back(current) {
+            self.show_history_location(location, window, cx);
+        }
+    }
+
+    /// Go forward to where going back in a Markdown preview came from
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn markdown_link_forward(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(current) = self.current_preview_location(cx) else {
+            return;
+        };
+        if let Some(location) = self.markdown_link_history.forward(current) {
+            self.show_history_location(location, window, cx);
+        }
+    }
+
+    /// Show a location of the link history, telling when its document was closed
+    ///
+    /// ### Arguments
+    /// - `location`: The location to show
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn show_history_location(
+        &mut self,
+        location: PreviewLocation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.show_preview_location(location, window, cx) {
+            window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from("The document of this location was closed"),
+                ),
+                cx,
+            );
+        }
+    }
+
+    /// Check the links of the active Markdown document and mark the broken ones
+    ///
+    /// ### Description
+    /// Broken links are underlined as warnings in the editor and listed in a
+    /// notification. The warnings are cleared on the next edit of the document.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn check_markdown_links(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((content, path)) = self.active_markdown_source(cx) else {
+            window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from("Links can only be checked in a Markdown document"),
+                ),
+                cx,
+            );
+            return;
+        };
+        let text = content.read(cx).text().to_string();
+        let broken = find_broken_links(&text, path.as_deref().and_then(Path::parent));
+        self.mark_broken_links(&content, &broken, cx);
+        let notification = if broken.is_empty() {
+            (
+                NotificationType::Success,
+                SharedString::from("No broken links"),
+            )
+        } else {
+            (
+                NotificationType::Warning,
+                SharedString::from(broken_links_summary(&broken)),
+            )
+        };
+        window.push_notification(notification, cx);
+    }
+
+    /// Underline broken links in an editor until its next edit
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state holding the document
+    /// - `broken`: The broken links of the document
+    /// - `cx`: The application context
+    fn mark_broken_links(
+        &mut self,
+        content: &Entity<EditorState>,
+        broken: &[BrokenLink],
+        cx: &mut Context<Self>,
+    ) {
+        self.set_diagnostics(
+            content,
+            DiagnosticSource::BrokenLinks,
+            broken.iter().map(broken_link_diagnostic).collect(),
+            cx,
+        );
+        self.markdown_links_subscription = Some(cx.subscribe(
+            content,
+            |this: &mut Self, content, event: &InputEvent, cx| {
+                if !matches!(event, InputEvent::Change) {
+                    return;
+                }
+                this.markdown_links_subscription = None;
+                this.clear_diagnostics(&content, DiagnosticSource::BrokenLinks, cx);
+                cx.notify();
+            },
+        ));
+    }
+}
+
+/// Check whether a file holds text, from its first bytes
+///
+/// ### Arguments
+/// - `path`: The file to check
+///
+/// ### Returns
+/// - `bool`: True if the file could be read and its first bytes look like text
+fn is_text_file(path: &Path) -> bool {
+    let mut prefix = Vec::new();
+    std::fs::File::open(path)
+        .and_then(|file| file.take(TEXT_SNIFF_LEN).read_to_end(&mut prefix))
+        .is_ok_and(|_| !looks_binary(&prefix))
+}
+
+/// Describe the broken links found by a link check
+///
+/// ### Arguments
+/// - `broken`: The broken links, at least one
+///
+/// ### Returns
+/// - `String`: The number of broken links, followed by the first targets and their lines
+fn broken_links_summary(broken: &[BrokenLink]) -> String {
+    let listed = broken
+        .iter()
+        .take(MAX_REPORTED_LINKS)
+        .map(|link| format!("{} (line {})", link.url, link.line + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let count = broken.len();
+    let noun = if count == 1 { "link" } else { "links" };
+    if count > MAX_REPORTED_LINKS {
+        format!(
+            "{count} broken {noun}: {listed} and {} more",
+            count - MAX_REPORTED_LINKS
+        )
+    } else {
+        format!("{count} broken {noun}: {listed}")
+    }
+}
+
+/// Build the warning diagnostic underlining a broken link
+///
+/// ### Arguments
+/// - `link`: The broken link
+///
+/// ### Returns
+/// - `Diagnostic`: A warning spanning the link, explaining what is missing
+fn broken_link_diagnostic(link: &BrokenLink) -> Diagnostic {
+    let line = u32::try_from(link.line).unwrap_or(u32::MAX);
+    let (start, end) = link.columns;
+    Diagnostic {
+        range: lsp_types::Range {
+            start: Position {
+                line,
+                character: u32::try_from(start).unwrap_or(u32::MAX),
+            },
+            end: Position {
+                line,
+                character: u32::try_from(end).unwrap_or(u32::MAX),
+            },
+        },
+        severity: Some(DiagnosticSeverity::WARNING),
+        message: link.problem.clone(),
+        source: Some(LINK_CHECK_SOURCE.to_string()),
+        code: None,
+        related_information: None,
+        tags: None,
+        code_description: None,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::broken_links_summary;
+    use crate::fulgur::utils::markdown_links::BrokenLink;
+
+    fn broken(url: &str, line: usize) -> BrokenLink {
+        BrokenLink {
+            line,
+            columns: (0, 1),
+            url: url.to_string(),
+            problem: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_broken_links_summary_lists_the_first_targets() {
+        assert_eq!(
+            broken_links_summary(&[broken("a.md", 0)]),
+            "1 broken link: a.md (line 1)"
+        );
+        let many: Vec<_> = (0..7).map(|line| broken("#x", line)).collect();
+        assert_eq!(
+            broken_links_summary(&many),
+            "7 broken links: #x (line 1), #x (line 2), #x (line 3), #x (line 4), #x (line 5) and 2 more"
+        );
+    }
+}
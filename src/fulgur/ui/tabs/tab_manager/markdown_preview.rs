@@ -3,10 +3,18 @@ use crate::fulgur::{
     languages::supported_languages::SupportedLanguage,
     settings::MarkdownPreviewMode,
     tab::Tab,
-    ui::tabs::{markdown_preview_tab::MarkdownPreviewTab, tab::TabId},
+    ui::{
+        markdown_preview::MarkdownPreviewState,
+        tabs::{
+            editor_tab::{Jump, apply_minimal_edits},
+            markdown_preview_tab::MarkdownPreviewTab,
+            tab::TabId,
+        },
+    },
+    utils::markdown_extensions::toggle_task,
 };
 use gpui::{App, AppContext, Context, Entity, SharedString, Window};
-use gpui_component::{input::EditorState, text::TextViewState};
+use gpui_component::{WindowExt, input::EditorState, notification::NotificationType};
 
 impl Fulgur {
     /// Build a Markdown preview tab bound to a source editor tab
@@ -15,10 +23,10 @@ impl Fulgur {
     /// - `source_tab_id`: Identifier of the editor tab the preview mirrors
     /// - `source_title`: Title of the source editor tab, used to derive the preview title
     /// - `content`: Input state of the source editor tab, rendered by the preview
-    /// - `cx`: The application context, used to allocate the per-preview view state
+    /// - `cx`: The application context, used to allocate the per-preview state
     ///
     /// ### Returns
-    /// - `Tab`: A `Tab::MarkdownPreview` carrying a freshly allocated tab id and view state
+    /// - `Tab`: A `Tab::MarkdownPreview` carrying a freshly allocated tab id and preview state
     fn build_preview_tab(
         &mut self,
         source_tab_id: TabId,
@@ -26,16 +34,82 @@ impl Fulgur {
         content: Entity<EditorState>,
         cx: &mut Context<Self>,
     ) -> Tab {
-        let view_state = cx.new(|cx| TextViewState::markdown("", cx));
+        let preview = cx.new(|_| MarkdownPreviewState::new());
         Tab::MarkdownPreview(MarkdownPreviewTab {
             id: self.allocate_tab_id(),
             title: SharedString::from(format!("Preview - {source_title}")),
             source_tab_id,
             content,
-            view_state,
+            preview,
         })
     }
 
+    /// Place the cursor of a preview's source editor at a line and show it
+    ///
+    /// ### Arguments
+    /// - `source_tab_id`: The editor tab the preview mirrors
+    /// - `line`: The zero-based source line
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn jump_to_preview_source(
+        &mut self,
+        source_tab_id: TabId,
+        line: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(index) = self.tab_index_of(source_tab_id, cx) else {
+            return;
+        };
+        if self.active_tab_index(cx) != Some(index) {
+            self.set_active_tab(index, window, cx);
+        }
+        let jump = Jump {
+            line: u32::try_from(line).unwrap_or(u32::MAX),
+            character: None,
+        };
+        if let Some(tab) = self.tabs.get(index).cloned() {
+            tab.update(cx, |tab, cx| {
+                if let Tab::Editor(editor_tab) = tab {
+                    editor_tab.jump_to_line(window, cx, jump);
+                }
+            });
+        }
+    }
+
+    /// Check or uncheck a task item of a preview's source editor
+    ///
+    /// ### Arguments
+    /// - `source_tab_id`: The editor tab the preview mirrors
+    /// - `line`: The zero-based source line of the task item
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn toggle_preview_task(
+        &mut self,
+        source_tab_id: TabId,
+        line: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(editor_tab) = self
+            .tab_index_of(source_tab_id, cx)
+            .and_then(|index| self.tabs.get(index))
+            .and_then(|tab| tab.read(cx).as_editor())
+        else {
+            return;
+        };
+        if editor_tab.read_only {
+            let message = format!("'{}' is read-only", editor_tab.title);
+            window.push_notification((NotificationType::Info, SharedString::from(message)), cx);
+            return;
+        }
+        let content = editor_tab.content.clone();
+        let text = content.read(cx).text().to_string();
+        if let Some(toggled) = toggle_task(&text, line) {
+            apply_minimal_edits(&content, &toggled, window, cx);
+        }
+    }
+
     /// Collect the data needed to preview the editor tab at the given position
     ///
     /// ### Arguments
@@ -0,0 +1,145 @@
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::{read_text_and_selection, replace_and_select},
+    files::csv_support::detect_delimiter,
+    languages::supported_languages::SupportedLanguage,
+    utils::markdown_table::{TableEdit, csv_to_markdown_table, edit_table},
+};
+use gpui::{ClipboardItem, Context, Entity, Focusable, SharedString, Window};
+use gpui_component::{WindowExt, input::EditorState, notification::NotificationType};
+
+impl Fulgur {
+    /// Get the content of the active tab when it is an editable Markdown tab
+    ///
+    /// ### Arguments
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(Entity<EditorState>)`: The content of the active Markdown tab
+    /// - `None`: The active tab is not an editable Markdown tab
+    pub(super) fn active_markdown_content(&self, cx: &gpui::App) -> Option<Entity<EditorState>> {
+        if !self.is_markdown(cx) {
+            return None;
+        }
+        self.get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.read_only)
+            .map(|editor_tab| editor_tab.content.clone())
+    }
+
+    /// Edit and realign the pipe table around the cursor of the active Markdown tab
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    /// - `edit`: The edit to apply
+    ///
+    /// ### Returns
+    /// - `bool`: True if the cursor was in a table and the edit was applied
+    pub(crate) fn edit_markdown_table(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        edit: TableEdit,
+    ) -> bool {
+        let Some(content) = self.active_markdown_content(cx) else {
+            return false;
+        };
+        let (text, selection) = read_text_and_selection(&content, window, cx);
+        let Some(result) = edit_table(&text, selection.start, edit) else {
+            return false;
+        };
+        replace_and_select(&content, &text, &result.edit, result.selection, window, cx);
+        true
+    }
+
+    /// Move between the cells of a Markdown table with Tab and Shift-Tab
+    ///
+    /// ### Description
+    /// Only applies while the editor of the active Markdown tab has focus and its
+    /// cursor is in a table, so that Tab keeps indenting everywhere else.
+    ///
+    /// ### Arguments
+    /// - `edit`: `TableEdit::NextCell` or `TableEdit::PreviousCell`
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `bool`: True if the key moved the cursor to another cell
+    pub(crate) fn move_between_table_cells(
+        &mut self,
+        edit: TableEdit,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let focused = self
+            .active_markdown_content(cx)
+            .is_some_and(|content| content.focus_handle(cx).is_focused(window));
+        focused && self.edit_markdown_table(window, cx, edit)
+    }
+
+    /// Insert the CSV content of the clipboard as a Markdown table at the cursor
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn paste_csv_as_markdown_table(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(content) = self.active_markdown_content(cx) else {
+            return;
+        };
+        let csv = cx
+            .read_from_clipboard()
+            .and_then(|item| item.text())
+            .unwrap_or_default();
+        let Some(table) = csv_to_markdown_table(&csv, detect_delimiter(&csv)) else {
+            window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from("The clipboard holds no CSV to paste as a table"),
+                ),
+                cx,
+            );
+            return;
+        };
+        content.update(cx, |input_state, cx| {
+            input_state.replace(table, window, cx);
+            cx.notify();
+        });
+    }
+
+    /// Copy the active CSV tab to the clipboard as a Markdown table
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn copy_csv_as_markdown_table(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(editor_tab) = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| editor_tab.language == SupportedLanguage::Csv)
+        else {
+            return;
+        };
+        let csv = editor_tab.content.read(cx).text().to_string();
+        let notification = match csv_to_markdown_table(&csv, editor_tab.csv_delimiter) {
+            Some(table) => {
+                cx.write_to_clipboard(ClipboardItem::new_string(table));
+                (
+                    NotificationType::Success,
+                    SharedString::from("Copied the table as Markdown"),
+                )
+            }
+            None => (
+                NotificationType::Info,
+                SharedString::from("The table is empty, nothing was copied"),
+            ),
+        };
+        window.push_notification(notification, cx);
+    }
+}
@@ -1,6 +1,12 @@
+mod copy_rich_text;
 mod lifecycle;
+mod markdown_assets;
+mod markdown_links;
 mod markdown_preview;
+mod markdown_table;
 mod operations;
+mod pipe_command;
+mod structured_data;
 mod transfer;
 
 #[cfg(all(test, feature = "gpui-test-support"))]
@@ -1,9 +1,14 @@
 use crate::fulgur::{
     Fulgur,
+    editor_tab::{toggle_comment_in, transform_text_in},
+    files::indentation::IndentationChange,
+    languages::{comments::CommentKind, supported_languages::pretty_name},
     tab::{Tab, TabId},
     ui::components_utils::MAX_TAB_NAME_LENGTH,
+    utils::text_transform::TextTransform,
 };
-use gpui::{Context, Window};
+use gpui::{Context, SharedString, Window};
+use gpui_component::{WindowExt, notification::NotificationType};
 
 impl Fulgur {
     /// Rename a tab identified by its stable identifier
@@ -43,6 +48,108 @@ impl Fulgur {
         renamed
     }
 
+    /// Override or re-detect the indentation of the active editor tab
+    ///
+    /// ### Arguments
+    /// - `change`: The change picked in the status bar menu
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn change_active_tab_indentation(
+        &mut self,
+        change: IndentationChange,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab_entity) = self.active_tab_entity(cx) else {
+            return;
+        };
+        let settings = self.settings.editor_settings.clone();
+        tab_entity.update(cx, |tab, cx| {
+            tab.change_indentation(change, window, cx, &settings);
+        });
+        self.focus_active_tab(window, cx);
+        cx.notify();
+    }
+
+    /// Toggle a line comment on the current line or the selected lines of the active tab
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn toggle_line_comment(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_active_tab_comment(CommentKind::Line, window, cx);
+    }
+
+    /// Toggle a block comment around the selection or the current line of the active tab
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn toggle_block_comment(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_active_tab_comment(CommentKind::Block, window, cx);
+    }
+
+    /// Toggle a comment in the active editor tab, unless it is read-only
+    ///
+    /// ### Arguments
+    /// - `kind`: The kind of comment to toggle
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn toggle_active_tab_comment(
+        &mut self,
+        kind: CommentKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((content, language)) = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.read_only)
+            .map(|editor_tab| (editor_tab.content.clone(), editor_tab.language))
+        else {
+            return;
+        };
+        if !toggle_comment_in(&content, language, kind, window, cx) {
+            window.push_notification(
+                (
+                    NotificationType::Info,
+                    SharedString::from(format!("{} has no comment syntax", pretty_name(&language))),
+                ),
+                cx,
+            );
+        }
+    }
+
+    /// Transform the selection of the active editor tab, or its whole content without a selection
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    /// - `transform`: The transform to apply
+    pub fn transform_text(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        transform: TextTransform,
+    ) {
+        let Some(content) = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editor_tab.read_only)
+            .map(|editor_tab| editor_tab.content.clone())
+        else {
+            return;
+        };
+        if let Err(e) = transform_text_in(&content, transform, window, cx) {
+            log::warn!("Failed to apply '{}': {e}", transform.label());
+            window.push_notification(
+                (
+                    NotificationType::Warning,
+                    SharedString::from(format!("{}: {e}", transform.label())),
+                ),
+                cx,
+            );
+        }
+    }
+
     /// Refresh the title of the Markdown preview tabs of a renamed editor tab
     ///
     /// ### Arguments
@@ -0,0 +1,236 @@
+use crate::fulgur::{
+    Fulgur,
+    editor_tab::{EditorTab, apply_minimal_edits, read_text_and_selection, replace_and_select},
+    tab::{Tab, TabId},
+    ui::{
+        dialogs::pipe_command::PipeOutputMode,
+        notifications::progress::{CancelCallback, start_progress},
+    },
+    utils::{
+        shell_pipe::{PIPE_TIMEOUT, PipeOutput, fit_replacement, insert_below, run_pipe_command},
+        text_diff::TextEdit,
+    },
+};
+use gpui::{Context, Entity, SharedString, Window};
+use gpui_component::{WindowExt, input::EditorState, notification::NotificationType};
+use std::{
+    ops::Range,
+    sync::{Arc, atomic::Ordering},
+};
+
+/// File name given to the tab holding the output of a piped command
+const OUTPUT_FILE_NAME: &str = "command-output.txt";
+
+/// Most characters of standard error shown in the notification of a piped command
+const MAX_STDERR_CHARS: usize = 500;
+
+/// A piped command and the tab it reads from, captured when the command starts
+struct PipeRun {
+    command: String,
+    mode: PipeOutputMode,
+    tab_id: TabId,
+    content: Entity<EditorState>,
+    text: String,
+    selection: Range<usize>,
+}
+
+impl Fulgur {
+    /// Pipe the selection of the active editor tab, or its whole content, through a shell command
+    ///
+    /// ### Description
+    /// The command runs on a background thread behind a progress notification with
+    /// a Cancel button, and is killed after `PIPE_TIMEOUT`. The command is added to
+    /// the recent commands offered by the dialog.
+    ///
+    /// ### Arguments
+    /// - `command`: The command line
+    /// - `mode`: Where the output goes
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub(crate) fn pipe_through_command(
+        &mut self,
+        command: String,
+        mode: PipeOutputMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(editor_tab) = self.get_active_editor_tab(cx) else {
+            return;
+        };
+        if mode.edits_source() && editor_tab.read_only {
+            let message = format!(
+                "'{}' is read-only, send the output to a new tab instead",
+                editor_tab.title
+            );
+            window.push_notification((NotificationType::Info, SharedString::from(message)), cx);
+            return;
+        }
+        let tab_id = editor_tab.id;
+        let content = editor_tab.content.clone();
+        let (text, selection) = read_text_and_selection(&content, window, cx);
+        let input = if selection.is_empty() {
+            text.clone()
+        } else {
+            text[selection.clone()].to_string()
+        };
+        let run = PipeRun {
+            command: command.clone(),
+            mode,
+            tab_id,
+            content,
+            text,
+            selection,
+        };
+        if let Err(e) = self.settings.add_pipe_command(&command) {
+            log::error!("Failed to remember piped command: {e}");
+        }
+        log::debug!("Piping {} bytes through '{command}'", input.len());
+        // The command polls the cancel flag itself, there is nothing more to undo
+        let on_cancel: Option<CancelCallback> = Some(Box::new(|_window, _cx| {}));
+        let progress = start_progress(
+            window,
+            cx,
+            SharedString::from(format!("Running '{command}'...")),
+            on_cancel,
+        );
+        let cancel = progress.cancel_flag();
+        cx.spawn_in(window, async move |view, window| {
+            let run_cancel = Arc::clone(&cancel);
+            let result = window
+                .background_executor()
+                .spawn(async move {
+                    let _progress = progress;
+                    run_pipe_command(&command, &input, PIPE_TIMEOUT, &run_cancel)
+                })
+                .await;
+            let cancelled = cancel.load(Ordering::Acquire);
+            window
+                .update(|window, cx| {
+                    _ = view.update(cx, |this, cx| {
+                        this.finish_pipe_command(run, result, cancelled, window, cx);
+                    });
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Apply the output of a piped command back on the UI thread
+    ///
+    /// ### Description
+    /// Output of a command that failed is discarded. When the source tab was closed
+    /// or edited while the command ran, the output opens in a new tab rather than
+    /// landing at a stale position.
+    ///
+    /// ### Arguments
+    /// - `run`: The command and the tab it read from
+    /// - `result`: The outcome of the command
+    /// - `cancelled`: Whether the user cancelled the command
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn finish_pipe_command(
+        &mut self,
+        run: PipeRun,
+        result: anyhow::Result<PipeOutput>,
+        cancelled: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let output = match result {
+            Ok(output) => output,
+            Err(e) => {
+                log::warn!("Piped command failed: {e}");
+                let kind = if cancelled {
+                    NotificationType::Info
+                } else {
+                    NotificationType::Error
+                };
+                window.push_notification((kind, SharedString::from(e.to_string())), cx);
+                return;
+            }
+        };
+        let mut message = pipe_status_message(&run.command, &output);
+        if !output.status.success() {
+            log::warn!("{message}");
+            window.push_notification((NotificationType::Error, SharedString::from(message)), cx);
+            return;
+        }
+        let source_unchanged = self
+            .tab_entity_of(run.tab_id, cx)
+            .is_some_and(|_| run.content.read(cx).text().to_string() == run.text);
+        let diverted = run.mode.edits_source() && !source_unchanged;
+        if run.mode.edits_source() && source_unchanged {
+            apply_pipe_output(&run, &output.stdout, window, cx);
+        } else {
+            if diverted {
+                message.push_str("\nThe tab changed while the command ran, ");
+                message.push_str("so the output opened in a new tab");
+            }
+            let id = self.allocate_tab_id();
+            let editor_tab = EditorTab::from_content(
+                id,
+                &output.stdout,
+                OUTPUT_FILE_NAME.to_string(),
+                window,
+                cx,
+                &self.settings.editor_settings,
+            );
+            self.place_editor_tab_reusing_scratch(Tab::Editor(editor_tab), window, cx);
+            self.focus_active_tab(window, cx);
+            self.save_state_async(cx, window);
+        }
+        let kind = if output.stderr.trim().is_empty() && !diverted {
+            NotificationType::Success
+        } else {
+            NotificationType::Warning
+        };
+        window.push_notification((kind, SharedString::from(message)), cx);
+        cx.notify();
+    }
+}
+
+/// Write the output of a piped command into the tab it read from
+///
+/// ### Arguments
+/// - `run`: The command and the tab it read from, unchanged since
+/// - `stdout`: The output of the command
+/// - `window`: The window context
+/// - `cx`: The application context
+fn apply_pipe_output(run: &PipeRun, stdout: &str, window: &mut Window, cx: &mut Context<Fulgur>) {
+    let edit = match run.mode {
+        PipeOutputMode::Replace if run.selection.is_empty() => {
+            apply_minimal_edits(&run.content, stdout, window, cx);
+            return;
+        }
+        PipeOutputMode::Replace => TextEdit {
+            range: run.selection.clone(),
+            new_text: fit_replacement(&run.text[run.selection.clone()], stdout).to_string(),
+        },
+        PipeOutputMode::InsertBelow => insert_below(&run.text, run.selection.clone(), stdout),
+        PipeOutputMode::NewTab => return,
+    };
+    let start = edit.range.start;
+    let selection = start..start + edit.new_text.len();
+    replace_and_select(&run.content, &run.text, &edit, selection, window, cx);
+}
+
+/// Describe how a piped command exited, with the start of what it wrote to standard error
+///
+/// ### Arguments
+/// - `command`: The command line
+/// - `output`: The outcome of the command
+///
+/// ### Returns
+/// - `String`: e.g. `'sort' exited with status 0`
+fn pipe_status_message(command: &str, output: &PipeOutput) -> String {
+    let mut message = format!("'{command}' {}", output.status_summary());
+    let stderr = output.stderr.trim();
+    if !stderr.is_empty() {
+        message.push_str(":\n");
+        message.extend(stderr.chars().take(MAX_STDERR_CHARS));
+        if stderr.chars().count() > MAX_STDERR_CHARS {
+            message.push('…');
+        }
+    }
+    message
+}
@@ -0,0 +1,275 @@
+use crate::fulgur::{
+    Fulgur,
+    diagnostics::DiagnosticSource,
+    editor_tab::{EditorTab, Jump, apply_minimal_edits},
+    files::indentation::Indentation,
+    languages::supported_languages::pretty_name,
+    tab::Tab,
+    utils::structured_data::{self, DataError, DataFormat, DataRewrite},
+};
+use gpui::{Context, Entity, SharedString, Window};
+use gpui_component::{
+    WindowExt,
+    input::{EditorState, InputEvent, Position},
+    notification::NotificationType,
+};
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+
+/// Source tag set on the diagnostic marking a structured data parse error
+const VALIDATION_SOURCE: &str = "data validation";
+
+/// The active data tab: its editor state, format, title and indentation
+type DataTab = (Entity<EditorState>, DataFormat, String, Indentation);
+
+impl Fulgur {
+    /// Get the editor content and data format of the active tab
+    ///
+    /// ### Arguments
+    /// - `editable`: Whether read-only tabs should be skipped
+    /// - `window`: The window context, used to report a tab that is not JSON, YAML or TOML
+    /// - `cx`: The application context
+    ///
+    /// ### Returns
+    /// - `Some(DataTab)`: The active data tab
+    /// - `None`: No suitable tab is active
+    fn active_data_tab(
+        &self,
+        editable: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<DataTab> {
+        let editor_tab = self
+            .get_active_editor_tab(cx)
+            .filter(|editor_tab| !editable || !editor_tab.read_only)?;
+        let Some(format) = DataFormat::from_language(editor_tab.language) else {
+            let message = format!(
+                "{} is not JSON, YAML or TOML",
+                pretty_name(&editor_tab.language)
+            );
+            window.push_notification((NotificationType::Info, SharedString::from(message)), cx);
+            return None;
+        };
+        Some((
+            editor_tab.content.clone(),
+            format,
+            editor_tab.title.to_string(),
+            editor_tab.indentation,
+        ))
+    }
+
+    /// Validate the active JSON, YAML or TOML tab and mark the first parse error
+    ///
+    /// ### Description
+    /// The error is underlined as a diagnostic and the cursor moves to it. The
+    /// diagnostic is cleared on the next edit of the tab.
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn validate_data(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((content, format, _, _)) = self.active_data_tab(false, window, cx) else {
+            return;
+        };
+        let text = content.read(cx).text().to_string();
+        match structured_data::validate(format, &text) {
+            Ok(()) => window.push_notification(
+                (
+                    NotificationType::Success,
+                    SharedString::from(format!("Valid {}", format.label())),
+                ),
+                cx,
+            ),
+            Err(error) => {
+                self.mark_data_error(&content, &error, window, cx);
+                window.push_notification(
+                    (
+                        NotificationType::Error,
+                        SharedString::from(format!("Invalid {}: {error}", format.label())),
+                    ),
+                    cx,
+                );
+            }
+        }
+    }
+
+    /// Underline a parse error in an editor and move the cursor to it
+    ///
+    /// ### Arguments
+    /// - `content`: The editor state holding the document
+    /// - `error`: The parse error
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn mark_data_error(
+        &mut self,
+        content: &Entity<EditorState>,
+        error: &DataError,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_diagnostics(
+            content,
+            DiagnosticSource::DataValidation,
+            vec![data_error_diagnostic(error)],
+            cx,
+        );
+        let subscription = cx.subscribe(
+            content,
+            |this: &mut Self, content, event: &InputEvent, cx| {
+                if !matches!(event, InputEvent::Change) {
+                    return;
+                }
+                this.data_validation_subscriptions
+                    .remove(&content.entity_id());
+                this.clear_diagnostics(&content, DiagnosticSource::DataValidation, cx);
+                cx.notify();
+            },
+        );
+        self.data_validation_subscriptions
+            .insert(content.entity_id(), subscription);
+        self.update_active_editor_tab(cx, |editor_tab, cx| {
+            editor_tab.jump_to_line(
+                window,
+                cx,
+                Jump {
+                    line: error.line,
+                    character: Some(error.column),
+                },
+            );
+        });
+    }
+
+    /// Pretty-print the active data tab with its indentation
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn pretty_print_data(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.rewrite_active_data_tab(DataRewrite::PrettyPrint, window, cx);
+    }
+
+    /// Minify the active data tab
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn minify_data(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.rewrite_active_data_tab(DataRewrite::Minify, window, cx);
+    }
+
+    /// Sort the keys of the active data tab, recursively
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    pub fn sort_data_keys(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.rewrite_active_data_tab(DataRewrite::SortKeys, window, cx);
+    }
+
+    /// Rewrite the active data tab in its own format, as a single undo step
+    ///
+    /// ### Arguments
+    /// - `rewrite`: The rewrite to perform
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    fn rewrite_active_data_tab(
+        &mut self,
+        rewrite: DataRewrite,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((content, format, _, indentation)) = self.active_data_tab(true, window, cx) else {
+            return;
+        };
+        let text = content.read(cx).text().to_string();
+        match structured_data::rewrite(format, &text, rewrite, indentation) {
+            Ok(rewritten) => apply_minimal_edits(&content, &rewritten, window, cx),
+            Err(e) => {
+                log::warn!("Failed to rewrite {}: {e}", format.label());
+                window.push_notification(
+                    (NotificationType::Error, SharedString::from(e.to_string())),
+                    cx,
+                );
+            }
+        }
+    }
+
+    /// Convert the active data tab to another format, in a new tab
+    ///
+    /// ### Arguments
+    /// - `window`: The window context
+    /// - `cx`: The application context
+    /// - `target`: The format to convert to
+    pub fn convert_data(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        target: DataFormat,
+    ) {
+        let Some((content, format, title, indentation)) = self.active_data_tab(false, window, cx)
+        else {
+            return;
+        };
+        let text = content.read(cx).text().to_string();
+        let converted = match structured_data::convert(format, target, &text, indentation) {
+            Ok(converted) => converted,
+            Err(e) => {
+                log::warn!(
+                    "Failed to convert {} to {}: {e}",
+                    format.label(),
+                    target.label()
+                );
+                window.push_notification(
+                    (NotificationType::Error, SharedString::from(e.to_string())),
+                    cx,
+                );
+                return;
+            }
+        };
+        let stem = title
+            .rsplit_once('.')
+            .map_or(title.as_str(), |(stem, _)| stem);
+        let id = self.allocate_tab_id();
+        let editor_tab = EditorTab::from_content(
+            id,
+            &converted,
+            format!("{stem}.{}", target.extension()),
+            window,
+            cx,
+            &self.settings.editor_settings,
+        );
+        self.place_editor_tab_reusing_scratch(Tab::Editor(editor_tab), window, cx);
+        self.focus_active_tab(window, cx);
+        self.save_state_async(cx, window);
+        cx.notify();
+    }
+}
+
+/// Build the diagnostic underlining a parse error
+///
+/// ### Arguments
+/// - `error`: The parse error
+///
+/// ### Returns
+/// - `Diagnostic`: An error diagnostic spanning one character at the error position
+fn data_error_diagnostic(error: &DataError) -> Diagnostic {
+    Diagnostic {
+        range: lsp_types::Range {
+            start: Position {
+                line: error.line,
+                character: error.column,
+            },
+            end: Position {
+                line: error.line,
+                character: error.column.saturating_add(1),
+            },
+        },
+        severity: Some(DiagnosticSeverity::ERROR),
+        message: error.message.clone(),
+        source: Some(VALIDATION_SOURCE.to_string()),
+        code: None,
+        related_information: None,
+        tags: None,
+        code_description: None,
+        data: None,
+    }
+}
@@ -1,7 +1,8 @@
 use crate::fulgur::{
     Fulgur,
+    files::indentation::Indentation,
     languages::supported_languages::SupportedLanguage,
-    settings::Settings,
+    settings::{EditorSettings, Settings},
     shared_state::SharedAppState,
     tab::Tab,
     ui::tabs::editor_tab::{TabLocation, TabTransferData},
@@ -1007,6 +1008,8 @@ fn make_transfer_data() -> TabTransferData {
         csv_delimiter: b',',
         color_tag: None,
         log_view: false,
+        read_only: false,
+        indentation: Indentation::from_settings(&EditorSettings::new()),
     }
 }
 
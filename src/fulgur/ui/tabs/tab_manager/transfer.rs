@@ -42,6 +42,8 @@ impl Fulgur {
             csv_delimiter: editor.csv_delimiter,
             color_tag: editor.color_tag,
             log_view: editor.log_view,
+            read_only: editor.read_only,
+            indentation: editor.indentation,
         })
     }
 
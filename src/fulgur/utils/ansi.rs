@@ -0,0 +1,338 @@
+use std::ops::Range;
+
+/// A terminal color: an entry of the 256-color palette or a true color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnsiColor {
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// The 16 standard terminal colors, readable on light and dark backgrounds
+#[allow(clippy::unreadable_literal)]
+const STANDARD_COLORS: [u32; 16] = [
+    0x000000, 0xcd3131, 0x0dbc79, 0xc19c00, 0x2472c8, 0xbc3fbc, 0x11a8cd, 0xa0a0a0, 0x666666,
+    0xf14c4c, 0x23d18b, 0xd7ba7d, 0x3b8eea, 0xd670d6, 0x29b8db, 0xe5e5e5,
+];
+
+/// Levels of each channel in the 6x6x6 color cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl AnsiColor {
+    /// Get the color as a `0xRRGGBB` value
+    ///
+    /// ### Returns
+    /// - `u32`: The color, using the xterm layout for the 256-color palette
+    #[must_use]
+    pub fn to_rgb(self) -> u32 {
+        let pack = |r: u8, g: u8, b: u8| (u32::from(r) << 16) | (u32::from(g) << 8) | u32::from(b);
+        match self {
+            Self::Rgb(r, g, b) => pack(r, g, b),
+            Self::Indexed(index @ 0..=15) => STANDARD_COLORS[usize::from(index)],
+            Self::Indexed(index @ 16..=231) => {
+                let cube = index - 16;
+                pack(
+                    CUBE_LEVELS[usize::from(cube / 36)],
+                    CUBE_LEVELS[usize::from(cube / 6 % 6)],
+                    CUBE_LEVELS[usize::from(cube % 6)],
+                )
+            }
+            Self::Indexed(index) => {
+                let level = 8 + (index - 232) * 10;
+                pack(level, level, level)
+            }
+        }
+    }
+}
+
+/// The text attributes set by SGR escape sequences
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AnsiStyle {
+    pub foreground: Option<AnsiColor>,
+    pub background: Option<AnsiColor>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// A run of text sharing one style
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnsiSpan {
+    pub text: String,
+    pub style: AnsiStyle,
+}
+
+/// Read an extended color (`38;5;n` or `38;2;r;g;b`) from the SGR parameters
+///
+/// ### Arguments
+/// - `params`: The parameters following the `38` or `48`
+///
+/// ### Returns
+/// - `(Option<AnsiColor>, usize)`: The color, if well formed, and the number of parameters consumed
+fn extended_color(params: &[u16]) -> (Option<AnsiColor>, usize) {
+    let channel = |index: usize| {
+        params
+            .get(index)
+            .and_then(|value| u8::try_from(*value).ok())
+    };
+    match params.first() {
+        Some(5) => (channel(1).map(AnsiColor::Indexed), 2),
+        Some(2) => match (channel(1), channel(2), channel(3)) {
+            (Some(r), Some(g), Some(b)) => (Some(AnsiColor::Rgb(r, g, b)), 4),
+            _ => (None, params.len()),
+        },
+        _ => (None, params.len()),
+    }
+}
+
+/// Apply the parameters of an SGR (`ESC [ ... m`) sequence to a style
+///
+/// ### Arguments
+/// - `params`: The numeric parameters, empty meaning reset
+/// - `style`: The style to update
+fn apply_sgr(params: &[u16], style: &mut AnsiStyle) {
+    if params.is_empty() {
+        *style = AnsiStyle::default();
+        return;
+    }
+    let mut index = 0;
+    while index < params.len() {
+        let param = params[index];
+        index += 1;
+        match param {
+            0 => *style = AnsiStyle::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            22 => {
+                style.bold = false;
+                style.dim = false;
+            }
+            23 => style.italic = false,
+            24 => style.underline = false,
+            30..=37 => style.foreground = u8::try_from(param - 30).ok().map(AnsiColor::Indexed),
+            90..=97 => style.foreground = u8::try_from(param - 82).ok().map(AnsiColor::Indexed),
+            39 => style.foreground = None,
+            40..=47 => style.background = u8::try_from(param - 40).ok().map(AnsiColor::Indexed),
+            100..=107 => style.background = u8::try_from(param - 92).ok().map(AnsiColor::Indexed),
+            49 => style.background = None,
+            38 | 48 => {
+                let (color, consumed) = extended_color(&params[index..]);
+                index += consumed;
+                if param == 38 {
+                    style.foreground = color;
+                } else {
+                    style.background = color;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Split a line of terminal output into styled runs, dropping the escape sequences
+///
+/// ### Description
+/// Only SGR sequences change the style; other CSI sequences (cursor movement,
+/// erasing) and OSC sequences (window titles, hyperlinks) are dropped. The style
+/// carries over from one line to the next, as it does in a terminal.
+///
+/// ### Arguments
+/// - `text`: The text to parse
+/// - `style`: The style in effect before `text`, updated to the style after it
+///
+/// ### Returns
+/// - `Vec<AnsiSpan>`: The non-empty runs of text, in order
+pub fn parse_ansi(text: &str, style: &mut AnsiStyle) -> Vec<AnsiSpan> {
+    let mut spans: Vec<AnsiSpan> = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            current.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                let mut sequence = String::new();
+                let mut terminator = None;
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        terminator = Some(c);
+                        break;
+                    }
+                    sequence.push(c);
+                }
+                if terminator == Some('m') {
+                    if !current.is_empty() {
+                        spans.push(AnsiSpan {
+                            text: std::mem::take(&mut current),
+                            style: *style,
+                        });
+                    }
+                    let params: Vec<u16> = sequence
+                        .split([';', ':'])
+                        .filter(|param| !param.is_empty())
+                        .map(|param| param.parse().unwrap_or(0))
+                        .collect();
+                    apply_sgr(&params, style);
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if !current.is_empty() {
+        spans.push(AnsiSpan {
+            text: current,
+            style: *style,
+        });
+    }
+    spans
+}
+
+/// Remove the escape sequences from terminal output
+///
+/// ### Arguments
+/// - `text`: The text to clean
+///
+/// ### Returns
+/// - `String`: The visible text
+#[must_use]
+pub fn strip_ansi(text: &str) -> String {
+    parse_ansi(text, &mut AnsiStyle::default())
+        .into_iter()
+        .map(|span| span.text)
+        .collect()
+}
+
+/// Split styled runs at byte ranges of their concatenated text
+///
+/// ### Arguments
+/// - `spans`: The runs of one line
+/// - `ranges`: Sorted, non-overlapping byte ranges of the line text, e.g. links
+///
+/// ### Returns
+/// - `Vec<(AnsiSpan, Option<usize>)>`: The runs, each tagged with the index of the range it
+///   falls in
+#[must_use]
+pub fn split_spans(spans: &[AnsiSpan], ranges: &[Range<usize>]) -> Vec<(AnsiSpan, Option<usize>)> {
+    let mut pieces = Vec::new();
+    let mut offset = 0;
+    for span in spans {
+        let end = offset + span.text.len();
+        let mut position = offset;
+        while position < end {
+            let inside = ranges
+                .iter()
+                .position(|range| range.start <= position && position < range.end);
+            let boundary = match inside {
+                Some(index) => ranges[index].end,
+                None => ranges
+                    .iter()
+                    .map(|range| range.start)
+                    .filter(|start| *start > position)
+                    .min()
+                    .unwrap_or(end),
+            }
+            .min(end);
+            pieces.push((
+                AnsiSpan {
+                    text: span.text[position - offset..boundary - offset].to_string(),
+                    style: span.style,
+                },
+                inside,
+            ));
+            position = boundary;
+        }
+        offset = end;
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ansi_colors_and_resets() {
+        let mut style = AnsiStyle::default();
+        let spans = parse_ansi("\x1b[1;31merror\x1b[0m: mismatched types", &mut style);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "error");
+        assert!(spans[0].style.bold);
+        assert_eq!(spans[0].style.foreground, Some(AnsiColor::Indexed(1)));
+        assert_eq!(spans[1].text, ": mismatched types");
+        assert_eq!(spans[1].style, AnsiStyle::default());
+    }
+
+    #[test]
+    fn test_parse_ansi_carries_style_across_lines() {
+        let mut style = AnsiStyle::default();
+        parse_ansi("\x1b[32mok", &mut style);
+        let spans = parse_ansi("still green", &mut style);
+        assert_eq!(spans[0].style.foreground, Some(AnsiColor::Indexed(2)));
+    }
+
+    #[test]
+    #[allow(clippy::unreadable_literal)]
+    fn test_parse_ansi_extended_colors() {
+        let mut style = AnsiStyle::default();
+        parse_ansi("\x1b[38;5;196;48;2;1;2;3m", &mut style);
+        assert_eq!(style.foreground, Some(AnsiColor::Indexed(196)));
+        assert_eq!(style.background, Some(AnsiColor::Rgb(1, 2, 3)));
+        assert_eq!(AnsiColor::Indexed(196).to_rgb(), 0xff0000);
+        assert_eq!(AnsiColor::Indexed(232).to_rgb(), 0x080808);
+        assert_eq!(AnsiColor::Indexed(9).to_rgb(), 0xf14c4c);
+    }
+
+    #[test]
+    fn test_strip_ansi_drops_other_sequences() {
+        assert_eq!(
+            strip_ansi("\x1b]0;title\x07\x1b[2K\x1b[1mBuilding\x1b[0m crate"),
+            "Building crate"
+        );
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://x\x1b\\link\x1b]8;;\x1b\\"),
+            "link"
+        );
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn test_split_spans_at_ranges() {
+        let mut style = AnsiStyle::default();
+        let spans = parse_ansi("\x1b[1m --> \x1b[0msrc/main.rs:3:5 here", &mut style);
+        let pieces = split_spans(&spans, &[5..20]);
+        let texts: Vec<(&str, Option<usize>)> = pieces
+            .iter()
+            .map(|(span, range)| (span.text.as_str(), *range))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                (" --> ", None),
+                ("src/main.rs:3:5", Some(0)),
+                (" here", None)
+            ]
+        );
+        assert!(pieces[0].0.style.bold);
+        assert!(!pieces[1].0.style.bold);
+        let pieces = split_spans(&spans, &[2..7]);
+        let texts: Vec<&str> = pieces.iter().map(|(span, _)| span.text.as_str()).collect();
+        assert_eq!(texts, [" -", "-> ", "sr", "c/main.rs:3:5 here"]);
+        assert_eq!(pieces[2].1, Some(0));
+    }
+}
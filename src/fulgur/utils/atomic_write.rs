@@ -14,7 +14,7 @@ static ATOMIC_WRITE_COUNTER: AtomicU64 = AtomicU64::new(0);
 /// ### Returns
 /// - `PathBuf`: the canonicalized path, or the original path if canonicalization fails
 #[cfg(unix)]
-fn resolve_target(path: &Path) -> PathBuf {
+pub(crate) fn resolve_target(path: &Path) -> PathBuf {
     fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
 }
 
@@ -27,7 +27,7 @@ fn resolve_target(path: &Path) -> PathBuf {
 /// - `PathBuf`: the original filename joined onto the canonicalized parent
 ///   directory, or the original path if the parent cannot be canonicalized
 #[cfg(windows)]
-fn resolve_target(path: &Path) -> PathBuf {
+pub(crate) fn resolve_target(path: &Path) -> PathBuf {
     match (path.parent(), path.file_name()) {
         (Some(parent), Some(filename)) if !parent.as_os_str().is_empty() => {
             fs::canonicalize(parent).map_or_else(|_| path.to_path_buf(), |dir| dir.join(filename))
@@ -36,6 +36,47 @@ fn resolve_target(path: &Path) -> PathBuf {
     }
 }
 
+/// Check whether the current user may write to an existing file.
+///
+/// ### Description
+/// Asks the kernel through `access(2)`, which honours ownership, ACLs and
+/// read-only mounts where mode bits alone would not, without opening the file:
+/// opening a FIFO or a file on a stalled network mount for writing could block.
+///
+/// ### Arguments
+/// - `path`: the path to the file to check
+///
+/// ### Returns
+/// - `true`: the file exists and can be written to
+/// - `false`: the file is missing or writing to it is denied
+#[cfg(unix)]
+#[must_use]
+pub fn is_file_writable(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+    let Ok(c_path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: `c_path` is a valid NUL-terminated string that outlives the call.
+    unsafe { libc::access(c_path.as_ptr(), libc::W_OK) == 0 }
+}
+
+/// Check whether the current user may write to an existing file.
+///
+/// ### Description
+/// Reads the file attributes only, so the check never opens the file.
+///
+/// ### Arguments
+/// - `path`: the path to the file to check
+///
+/// ### Returns
+/// - `true`: the file exists and is not marked read-only
+/// - `false`: the file is missing or marked read-only
+#[cfg(windows)]
+#[must_use]
+pub fn is_file_writable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|meta| meta.is_file() && !meta.permissions().readonly())
+}
+
 /// Write file contents atomically by writing to a sibling temporary file,
 /// syncing it, then renaming it over the destination path.
 ///
@@ -88,12 +129,14 @@ pub fn atomic_write_file(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
             use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
             open_options.mode(permissions.mode());
         }
+        // Keep the I/O error as the source so permission denials can be told apart.
         let mut tmp_file = open_options.open(&tmp_path).map_err(|e| {
-            anyhow::anyhow!(
+            let message = format!(
                 "Failed to create temp file '{}' for atomic write: {}",
                 tmp_path.display(),
                 e
-            )
+            );
+            anyhow::Error::new(e).context(message)
         })?;
 
         tmp_file.write_all(contents).map_err(|e| {
@@ -120,12 +163,13 @@ pub fn atomic_write_file(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
             })?;
         }
         fs::rename(&tmp_path, &target).map_err(|e| {
-            anyhow::anyhow!(
+            let message = format!(
                 "Failed to replace '{}' with '{}' atomically: {}",
                 target.display(),
                 tmp_path.display(),
                 e
-            )
+            );
+            anyhow::Error::new(e).context(message)
         })?;
         #[cfg(unix)]
         {
@@ -200,11 +244,26 @@ pub fn cleanup_orphan_temp_files(dir: &Path) {
 
 #[cfg(test)]
 mod tests {
-    use super::atomic_write_file;
+    use super::{atomic_write_file, is_file_writable};
     use std::fs;
     use std::path::{Path, PathBuf};
     use tempfile::TempDir;
 
+    #[test]
+    fn is_file_writable_accepts_existing_files_and_rejects_missing_ones() {
+        let temp_dir = TempDir::new().expect("failed to create temp dir");
+        let path = temp_dir.path().join("notes.txt");
+        fs::write(&path, b"keep").expect("failed to write file");
+
+        assert!(is_file_writable(&path));
+        assert!(!is_file_writable(&temp_dir.path().join("missing.txt")));
+        assert_eq!(
+            fs::read(&path).expect("failed to read file"),
+            b"keep",
+            "probing must not truncate the file"
+        );
+    }
+
     #[test]
     fn backup_path_for_appends_bak_extension() {
         let path = Path::new("/config/settings.json");
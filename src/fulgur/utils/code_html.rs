@@ -0,0 +1,206 @@
+//! Turn syntax-highlighted source code into HTML with inline styles.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+/// A run of source code and the style the syntax theme gives it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxSpan {
+    pub range: Range<usize>, // Byte range in the highlighted text
+    pub color: Option<u32>,  // Text color as 0xRRGGBB, `None` for the default color
+    pub bold: bool,
+    pub italic: bool,
+}
+
+/// How a copied code fragment looks outside the editor
+pub struct CodeStyle {
+    pub font_family: String,
+    pub font_size: f32, // In pixels, as in the editor settings
+    pub background: u32,
+    pub foreground: u32,
+}
+
+/// Restrict spans to a range of the text, with offsets relative to its start
+///
+/// ### Arguments
+/// - `spans`: The styled spans of the whole text, in order
+/// - `range`: The byte range to keep
+///
+/// ### Returns
+/// - `Vec<SyntaxSpan>`: The spans clipped to `range`
+#[must_use]
+pub fn clip_spans(spans: &[SyntaxSpan], range: &Range<usize>) -> Vec<SyntaxSpan> {
+    spans
+        .iter()
+        .take_while(|span| span.range.start < range.end)
+        .filter(|span| span.range.end > range.start)
+        .map(|span| SyntaxSpan {
+            range: span.range.start.max(range.start) - range.start
+                ..span.range.end.min(range.end) - range.start,
+            ..span.clone()
+        })
+        .collect()
+}
+
+/// Escape text for use in HTML content or a quoted attribute value
+///
+/// ### Arguments
+/// - `text`: The text to escape
+///
+/// ### Returns
+/// - `String`: The text with `&`, `<`, `>` and `"` escaped
+#[must_use]
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Render source code as escaped HTML, each styled span in a `<span>` with inline CSS
+///
+/// ### Description
+/// Spans are expected in order and without overlap, as a syntax highlighter
+/// reports them; a span overlapping the previous one or not on character
+/// boundaries is skipped, its text rendered unstyled.
+///
+/// ### Arguments
+/// - `code`: The source code
+/// - `spans`: The styled spans of `code`
+///
+/// ### Returns
+/// - `String`: The HTML, without an enclosing element
+#[must_use]
+pub fn highlighted_code_html(code: &str, spans: &[SyntaxSpan]) -> String {
+    let mut html = String::with_capacity(code.len() * 2);
+    let mut position = 0;
+    for span in spans {
+        let Some(text) = code.get(span.range.clone()) else {
+            continue;
+        };
+        if span.range.start < position || text.is_empty() {
+            continue;
+        }
+        html.push_str(&escape_html(&code[position..span.range.start]));
+        let mut style = String::new();
+        if let Some(color) = span.color {
+            let _ = write!(style, "color:#{color:06x};");
+        }
+        if span.bold {
+            style.push_str("font-weight:bold;");
+        }
+        if span.italic {
+            style.push_str("font-style:italic;");
+        }
+        if style.is_empty() {
+            html.push_str(&escape_html(text));
+        } else {
+            let _ = write!(html, "<span style=\"{style}\">{}</span>", escape_html(text));
+        }
+        position = span.range.end;
+    }
+    html.push_str(&escape_html(&code[position..]));
+    html
+}
+
+/// Render highlighted code as a `<pre>` fragment styled inline, for pasting into documents
+///
+/// ### Arguments
+/// - `code`: The source code
+/// - `spans`: The styled spans of `code`
+/// - `style`: The font and colors of the fragment
+///
+/// ### Returns
+/// - `String`: The `<pre>` element
+#[must_use]
+pub fn code_fragment_html(code: &str, spans: &[SyntaxSpan], style: &CodeStyle) -> String {
+    let font_family = style
+        .font_family
+        .replace(['"', '\'', '\\', ';', '<', '>'], "");
+    let CodeStyle {
+        font_size,
+        background,
+        foreground,
+        ..
+    } = *style;
+    format!(
+        "<pre style=\"font-family:'{font_family}',ui-monospace,Menlo,Consolas,monospace;\
+         font-size:{font_size}px;background:#{background:06x};color:#{foreground:06x};\
+         padding:0.6em;white-space:pre-wrap;\">{}</pre>",
+        highlighted_code_html(code, spans)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(range: Range<usize>, color: Option<u32>, bold: bool) -> SyntaxSpan {
+        SyntaxSpan {
+            range,
+            color,
+            bold,
+            italic: false,
+        }
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("a < b && \"c\" > d"),
+            "a &lt; b &amp;&amp; &quot;c&quot; &gt; d"
+        );
+    }
+
+    #[test]
+    fn test_highlighted_code_html_wraps_styled_spans() {
+        let code = "let x = \"<a>\";";
+        let spans = [
+            span(0..3, Some(0x00_80_ff), true),
+            span(8..13, Some(0xa0_40_00), false),
+            span(13..14, None, false),
+        ];
+        assert_eq!(
+            highlighted_code_html(code, &spans),
+            "<span style=\"color:#0080ff;font-weight:bold;\">let</span> x = \
+             <span style=\"color:#a04000;\">&quot;&lt;a&gt;&quot;</span>;"
+        );
+    }
+
+    #[test]
+    fn test_clip_spans_rebases_spans_on_the_range() {
+        let spans = [span(0..4, Some(1), false), span(5..9, Some(2), false)];
+        assert_eq!(
+            clip_spans(&spans, &(2..7)),
+            vec![span(0..2, Some(1), false), span(3..5, Some(2), false)]
+        );
+    }
+
+    #[test]
+    fn test_code_fragment_html_applies_font_and_colors() {
+        let style = CodeStyle {
+            font_family: "Fira \"Code\"".to_string(),
+            font_size: 14.0,
+            background: 0x28_2c_34,
+            foreground: 0xab_b2_bf,
+        };
+        let html = code_fragment_html("a<b", &[], &style);
+        assert!(html.starts_with("<pre style=\"font-family:'Fira Code',"));
+        assert!(html.contains("font-size:14px;background:#282c34;color:#abb2bf;"));
+        assert!(html.ends_with(">a&lt;b</pre>"));
+    }
+
+    #[test]
+    fn test_highlighted_code_html_skips_invalid_spans() {
+        let code = "é = 1";
+        let spans = [span(0..1, Some(0xff_00_00), false), span(4..9, None, true)];
+        assert_eq!(highlighted_code_html(code, &spans), "é = 1");
+    }
+}
@@ -0,0 +1,158 @@
+//! Turn syntax-highlighted source code into RTF for pasting into documents.
+
+use crate::fulgur::utils::code_html::{CodeStyle, SyntaxSpan};
+use std::fmt::Write;
+
+/// Append text to an RTF document, escaping control characters and non-ASCII
+///
+/// ### Arguments
+/// - `text`: The text to append
+/// - `rtf`: The RTF document
+fn push_escaped(text: &str, rtf: &mut String) {
+    for character in text.chars() {
+        match character {
+            '\\' | '{' | '}' => {
+                rtf.push('\\');
+                rtf.push(character);
+            }
+            '\n' => rtf.push_str("\\par\n"),
+            '\r' => {}
+            '\t' => rtf.push_str("\\tab "),
+            ' '..='~' => rtf.push(character),
+            _ => {
+                let mut units = [0; 2];
+                for unit in character.encode_utf16(&mut units) {
+                    // RTF expects UTF-16 code units as signed 16-bit numbers
+                    let _ = write!(rtf, "\\u{}?", i16::from_ne_bytes(unit.to_ne_bytes()));
+                }
+            }
+        }
+    }
+}
+
+/// Get the index of a color in the color table, adding it when missing
+///
+/// ### Arguments
+/// - `colors`: The colors of the table, after its default entry
+/// - `color`: The color as 0xRRGGBB
+///
+/// ### Returns
+/// - `usize`: The index to use with `\cf`
+fn color_index(colors: &mut Vec<u32>, color: u32) -> usize {
+    let position = colors
+        .iter()
+        .position(|known| *known == color)
+        .unwrap_or_else(|| {
+            colors.push(color);
+            colors.len() - 1
+        });
+    // Entry 0 of an RTF color table is the default color
+    position + 1
+}
+
+/// Render source code as an RTF document with the colors of its styled spans
+///
+/// ### Description
+/// Spans are expected in order and without overlap; a span overlapping the
+/// previous one or not on character boundaries is skipped, as in
+/// `highlighted_code_html`.
+///
+/// ### Arguments
+/// - `code`: The source code
+/// - `spans`: The styled spans of `code`
+/// - `style`: The font and colors of the document
+///
+/// ### Returns
+/// - `String`: The RTF document
+#[must_use]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn highlighted_code_rtf(code: &str, spans: &[SyntaxSpan], style: &CodeStyle) -> String {
+    let mut colors = vec![style.background, style.foreground];
+    let mut body = String::with_capacity(code.len() * 2);
+    let mut position = 0;
+    for span in spans {
+        let Some(text) = code.get(span.range.clone()) else {
+            continue;
+        };
+        if span.range.start < position || text.is_empty() {
+            continue;
+        }
+        push_escaped(&code[position..span.range.start], &mut body);
+        body.push('{');
+        if let Some(color) = span.color {
+            let _ = write!(body, "\\cf{}", color_index(&mut colors, color));
+        }
+        if span.bold {
+            body.push_str("\\b");
+        }
+        if span.italic {
+            body.push_str("\\i");
+        }
+        body.push(' ');
+        push_escaped(text, &mut body);
+        body.push('}');
+        position = span.range.end;
+    }
+    push_escaped(&code[position..], &mut body);
+    let mut color_table = String::from(";");
+    for color in &colors {
+        let _ = write!(
+            color_table,
+            "\\red{}\\green{}\\blue{};",
+            (color >> 16) & 0xff,
+            (color >> 8) & 0xff,
+            color & 0xff
+        );
+    }
+    let font_family = style.font_family.replace(['\\', '{', '}', ';'], "");
+    // Font sizes are in half points; editor sizes are in pixels of 3/4 point
+    let half_points = (style.font_size.max(1.0) * 1.5).round() as u32;
+    format!(
+        "{{\\rtf1\\ansi\\ansicpg1252\\deff0{{\\fonttbl{{\\f0\\fmodern\\fcharset0 {font_family};}}}}\
+         {{\\colortbl{color_table}}}\n\\f0\\fs{half_points}\\cb1\\chcbpat1\\cf2 {body}}}"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style() -> CodeStyle {
+        CodeStyle {
+            font_family: "Monaco".to_string(),
+            font_size: 14.0,
+            background: 0xff_ff_ff,
+            foreground: 0x00_00_00,
+        }
+    }
+
+    #[test]
+    fn test_highlighted_code_rtf_builds_the_color_table() {
+        let spans = [
+            SyntaxSpan {
+                range: 0..2,
+                color: Some(0xff_00_00),
+                bold: true,
+                italic: false,
+            },
+            SyntaxSpan {
+                range: 3..4,
+                color: Some(0xff_00_00),
+                bold: false,
+                italic: true,
+            },
+        ];
+        assert_eq!(
+            highlighted_code_rtf("fn x", &spans, &style()),
+            "{\\rtf1\\ansi\\ansicpg1252\\deff0{\\fonttbl{\\f0\\fmodern\\fcharset0 Monaco;}}\
+             {\\colortbl;\\red255\\green255\\blue255;\\red0\\green0\\blue0;\\red255\\green0\\blue0;}\n\
+             \\f0\\fs21\\cb1\\chcbpat1\\cf2 {\\cf3\\b fn} {\\cf3\\i x}}"
+        );
+    }
+
+    #[test]
+    fn test_highlighted_code_rtf_escapes_text() {
+        let rtf = highlighted_code_rtf("{a\\b}\r\n\té😀", &[], &style());
+        assert!(rtf.ends_with("\\{a\\\\b\\}\\par\n\\tab \\u233?\\u-10179?\\u-8704?}"));
+    }
+}
@@ -0,0 +1,86 @@
+/// Bonus for a matched character that directly follows the previous match.
+const CONSECUTIVE_BONUS: i64 = 8;
+
+/// Bonus for a matched character that starts a word in the candidate.
+const WORD_START_BONUS: i64 = 6;
+
+/// Penalty for every skipped candidate character between two matches.
+const GAP_PENALTY: i64 = 1;
+
+/// Score how well `query` fuzzy-matches `candidate`
+///
+/// ### Description
+/// Every query character must appear in the candidate in order, compared
+/// case-insensitively. Consecutive matches and matches at word starts (after a
+/// separator or at a lower-to-upper case change) score higher, and gaps between
+/// matches lower the score. An empty query matches everything with a score of `0`.
+///
+/// ### Arguments
+/// - `query`: The text typed by the user
+/// - `candidate`: The text to match against
+///
+/// ### Returns
+/// - `Some(i64)`: The score, higher is better
+/// - `None`: The query is not a subsequence of the candidate
+#[must_use]
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let mut score = 0;
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut previous: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+    for (index, ch) in candidate.chars().enumerate() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        if ch.to_lowercase().eq(std::iter::once(wanted)) {
+            let word_start = previous.is_none_or(|prev| {
+                !prev.is_alphanumeric() || (prev.is_lowercase() && ch.is_uppercase())
+            });
+            if word_start {
+                score += WORD_START_BONUS;
+            }
+            match last_match {
+                Some(last) if last + 1 == index => score += CONSECUTIVE_BONUS,
+                Some(last) => score -= GAP_PENALTY * i64::try_from(index - last - 1).unwrap_or(0),
+                None => {}
+            }
+            last_match = Some(index);
+            query_chars.next();
+        }
+        previous = Some(ch);
+    }
+    query_chars.peek().is_none().then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn test_fuzzy_score_requires_an_ordered_subsequence() {
+        assert!(fuzzy_score("lst", "load_state").is_some());
+        assert!(fuzzy_score("tsl", "load_state").is_none());
+        assert!(fuzzy_score("xyz", "load_state").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_score_is_case_insensitive() {
+        assert!(fuzzy_score("LOAD", "load_state").is_some());
+        assert!(fuzzy_score("ls", "LoadState").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts_and_consecutive_matches() {
+        let exact = fuzzy_score("save", "save_state").expect("matches");
+        let scattered = fuzzy_score("save", "set_active_view_entry").expect("matches");
+        assert!(exact > scattered);
+        let camel = fuzzy_score("ls", "LoadState").expect("matches");
+        let inner = fuzzy_score("ls", "tools").expect("matches");
+        assert!(camel > inner);
+    }
+
+    #[test]
+    fn test_fuzzy_score_empty_query_matches_everything() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+}
@@ -0,0 +1,1272 @@
+use crate::fulgur::utils::text_diff::line_and_column;
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Number, Value};
+use std::ops::Range;
+
+/// Deepest nesting the spanned parser accepts
+const MAX_DEPTH: usize = 256;
+
+/// Most values a single query step may produce, so `..` chains cannot explode
+const MAX_ITEMS: usize = 100_000;
+
+/// A JSON value together with the byte range it spans in its source
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonNode {
+    pub value: JsonValue,
+    pub span: Range<usize>,
+}
+
+/// The value of a `JsonNode`, whose children keep their own spans
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<JsonNode>),
+    Object(Vec<(String, JsonNode)>), // Entries in source order, duplicates included
+}
+
+impl JsonNode {
+    /// Convert the node into a plain JSON value
+    ///
+    /// ### Returns
+    /// - `Value`: The value, where duplicate keys keep their last occurrence
+    #[must_use]
+    pub fn to_value(&self) -> Value {
+        match &self.value {
+            JsonValue::Null => Value::Null,
+            JsonValue::Bool(b) => Value::Bool(*b),
+            JsonValue::Number(n) => Value::Number(n.clone()),
+            JsonValue::String(s) => Value::String(s.clone()),
+            JsonValue::Array(items) => Value::Array(items.iter().map(Self::to_value).collect()),
+            JsonValue::Object(entries) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, node)| (key.clone(), node.to_value()))
+                    .collect::<Map<String, Value>>(),
+            ),
+        }
+    }
+
+    /// Look up the value of a key, the last one winning when the key is duplicated
+    ///
+    /// ### Arguments
+    /// - `key`: The key to look up
+    ///
+    /// ### Returns
+    /// - `Some(&JsonNode)`: The value of the key
+    /// - `None`: The node is not an object or has no such key
+    fn get(&self, key: &str) -> Option<&JsonNode> {
+        match &self.value {
+            JsonValue::Object(entries) => entries
+                .iter()
+                .rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, node)| node),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a JSON document, keeping the byte range of every value
+///
+/// ### Arguments
+/// - `text`: The JSON document
+///
+/// ### Returns
+/// - `Ok(JsonNode)`: The root value
+///
+/// ### Errors
+/// - When the document is not valid JSON, with the line and column of the error
+pub fn parse_spanned(text: &str) -> Result<JsonNode> {
+    let mut parser = Parser { text, pos: 0 };
+    parser.skip_whitespace();
+    let root = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.pos < text.len() {
+        return Err(parser.error("Trailing characters"));
+    }
+    Ok(root)
+}
+
+/// Recursive descent parser building `JsonNode`s
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize, // Byte offset of the next character
+}
+
+impl Parser<'_> {
+    /// Build an error located at the current position
+    ///
+    /// ### Arguments
+    /// - `message`: The description of the error
+    ///
+    /// ### Returns
+    /// - `anyhow::Error`: The error, with a one-based line and column
+    fn error(&self, message: &str) -> anyhow::Error {
+        let (line, column) = line_and_column(self.text, self.pos);
+        anyhow!(
+            "{message} at line {}, column {}",
+            u64::from(line) + 1,
+            u64::from(column) + 1
+        )
+    }
+
+    /// Get the byte at the current position
+    ///
+    /// ### Returns
+    /// - `Some(u8)`: The byte
+    /// - `None`: At the end of the input
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    /// Consume a byte if it is next
+    ///
+    /// ### Arguments
+    /// - `byte`: The expected byte
+    ///
+    /// ### Returns
+    /// - `bool`: Whether the byte was consumed
+    fn eat(&mut self, byte: u8) -> bool {
+        let found = self.peek() == Some(byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Skip JSON whitespace
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse the value starting at the current position
+    ///
+    /// ### Arguments
+    /// - `depth`: The nesting depth of the value
+    ///
+    /// ### Returns
+    /// - `Ok(JsonNode)`: The value and its span
+    ///
+    /// ### Errors
+    /// - When the value is invalid or nested too deeply
+    fn value(&mut self, depth: usize) -> Result<JsonNode> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("Nesting too deep"));
+        }
+        let start = self.pos;
+        let value = match self.peek() {
+            Some(b'{') => self.object(depth)?,
+            Some(b'[') => self.array(depth)?,
+            Some(b'"') => JsonValue::String(self.string()?),
+            Some(b't') => self.keyword("true", JsonValue::Bool(true))?,
+            Some(b'f') => self.keyword("false", JsonValue::Bool(false))?,
+            Some(b'n') => self.keyword("null", JsonValue::Null)?,
+            Some(b'-' | b'0'..=b'9') => self.number()?,
+            Some(_) => return Err(self.error("Expected a value")),
+            None => return Err(self.error("Unexpected end of input")),
+        };
+        Ok(JsonNode {
+            value,
+            span: start..self.pos,
+        })
+    }
+
+    /// Parse a literal keyword
+    ///
+    /// ### Arguments
+    /// - `word`: The keyword
+    /// - `value`: The value the keyword stands for
+    ///
+    /// ### Returns
+    /// - `Ok(JsonValue)`: `value`
+    ///
+    /// ### Errors
+    /// - When the input does not spell the keyword
+    fn keyword(&mut self, word: &str, value: JsonValue) -> Result<JsonValue> {
+        if !self.text[self.pos..].starts_with(word) {
+            return Err(self.error("Expected a value"));
+        }
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    /// Parse a number
+    ///
+    /// ### Returns
+    /// - `Ok(JsonValue)`: The number
+    ///
+    /// ### Errors
+    /// - When the number is malformed
+    fn number(&mut self) -> Result<JsonValue> {
+        let start = self.pos;
+        while matches!(
+            self.peek(),
+            Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+        ) {
+            self.pos += 1;
+        }
+        let text = self.text;
+        if let Ok(number) = serde_json::from_str::<Number>(&text[start..self.pos]) {
+            return Ok(JsonValue::Number(number));
+        }
+        self.pos = start;
+        Err(self.error("Invalid number"))
+    }
+
+    /// Parse a string, decoding its escapes
+    ///
+    /// ### Returns
+    /// - `Ok(String)`: The decoded string
+    ///
+    /// ### Errors
+    /// - When the string is unterminated or holds an invalid escape or control character
+    fn string(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => self.pos += 2,
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+        self.pos += 1;
+        let text = self.text;
+        if let Ok(string) = serde_json::from_str::<String>(&text[start..self.pos]) {
+            return Ok(string);
+        }
+        self.pos = start;
+        Err(self.error("Invalid string"))
+    }
+
+    /// Parse an array
+    ///
+    /// ### Arguments
+    /// - `depth`: The nesting depth of the array
+    ///
+    /// ### Returns
+    /// - `Ok(JsonValue)`: The array
+    ///
+    /// ### Errors
+    /// - When an item is invalid or a separator is missing
+    fn array(&mut self, depth: usize) -> Result<JsonValue> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.eat(b']') {
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            if self.eat(b']') {
+                return Ok(JsonValue::Array(items));
+            }
+            if !self.eat(b',') {
+                return Err(self.error("Expected ',' or ']'"));
+            }
+        }
+    }
+
+    /// Parse an object
+    ///
+    /// ### Arguments
+    /// - `depth`: The nesting depth of the object
+    ///
+    /// ### Returns
+    /// - `Ok(JsonValue)`: The object, with its entries in source order
+    ///
+    /// ### Errors
+    /// - When a key or value is invalid or a separator is missing
+    fn object(&mut self, depth: usize) -> Result<JsonValue> {
+        self.pos += 1;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.eat(b'}') {
+            return Ok(JsonValue::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("Expected a key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            if !self.eat(b':') {
+                return Err(self.error("Expected ':'"));
+            }
+            self.skip_whitespace();
+            entries.push((key, self.value(depth + 1)?));
+            self.skip_whitespace();
+            if self.eat(b'}') {
+                return Ok(JsonValue::Object(entries));
+            }
+            if !self.eat(b',') {
+                return Err(self.error("Expected ',' or '}'"));
+            }
+        }
+    }
+}
+
+/// One step of a compiled query, applied to every value produced by the previous step
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Children,
+    Descendants, // The value itself, then every value nested in it
+    Select(Predicate),
+    Keys,
+    Length,
+}
+
+/// A filter condition: a relative path, optionally compared with a literal
+#[derive(Debug, Clone, PartialEq)]
+struct Predicate {
+    path: Vec<Step>,
+    test: Option<(Comparison, Value)>, // `None` keeps values whose path is truthy
+}
+
+/// A comparison operator of a filter condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+/// A compiled `JSONPath` or jq-style query
+///
+/// ### Description
+/// Expressions starting with `$` are `JSONPath`: `.key`, `['key']`, `[0]`, `[-1]`,
+/// `[1:3]`, `*`, `..` and `[?(@.key > 1)]` filters. Anything else is a jq subset:
+/// `.key`, `."key"`, `.[0]`, `.[1:3]`, `.[]`, `..`, `|` pipes, `select(.key == "x")`,
+/// `keys` and `length`. Missing keys and out-of-range indices produce nothing
+/// rather than an error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    steps: Vec<Step>,
+}
+
+/// One value selected by a query
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryMatch {
+    pub path: String,               // Normalized JSONPath of the value
+    pub value: Value,               // The selected value
+    pub span: Option<Range<usize>>, // Byte range of the value in the source, `None` for computed values
+}
+
+/// A value flowing between query steps
+#[derive(Debug, Clone)]
+enum Target<'a> {
+    Node(&'a JsonNode),
+    Computed(Value), // Produced by `keys` or `length`, without a location in the source
+}
+
+/// A value flowing between query steps, with its path
+#[derive(Debug, Clone)]
+struct Item<'a> {
+    target: Target<'a>,
+    path: String,
+}
+
+/// Compile a `JSONPath` or jq-style expression
+///
+/// ### Arguments
+/// - `expression`: The expression
+///
+/// ### Returns
+/// - `Ok(Query)`: The compiled query
+///
+/// ### Errors
+/// - When the expression is malformed, with the one-based position of the error
+pub fn compile(expression: &str) -> Result<Query> {
+    let mut cursor = Cursor {
+        text: expression,
+        pos: 0,
+    };
+    cursor.skip_whitespace();
+    let steps = if cursor.eat('$') {
+        parse_jsonpath(&mut cursor)?
+    } else {
+        parse_jq(&mut cursor)?
+    };
+    cursor.skip_whitespace();
+    if let Some(c) = cursor.peek() {
+        return Err(cursor.error(&format!("Unexpected '{c}'")));
+    }
+    Ok(Query { steps })
+}
+
+impl Query {
+    /// Run the query against a parsed document
+    ///
+    /// ### Arguments
+    /// - `root`: The root of the document
+    ///
+    /// ### Returns
+    /// - `Vec<QueryMatch>`: The selected values, in document order for each step
+    #[must_use]
+    pub fn evaluate(&self, root: &JsonNode) -> Vec<QueryMatch> {
+        run_steps(&self.steps, vec![Item::root(root)])
+            .into_iter()
+            .map(|item| match item.target {
+                Target::Node(node) => QueryMatch {
+                    path: item.path,
+                    value: node.to_value(),
+                    span: Some(node.span.clone()),
+                },
+                Target::Computed(value) => QueryMatch {
+                    path: item.path,
+                    value,
+                    span: None,
+                },
+            })
+            .collect()
+    }
+}
+
+impl<'a> Item<'a> {
+    /// Wrap the root of a document
+    ///
+    /// ### Arguments
+    /// - `root`: The root node
+    ///
+    /// ### Returns
+    /// - `Item`: The root item, with path `$`
+    fn root(root: &'a JsonNode) -> Self {
+        Self {
+            target: Target::Node(root),
+            path: "$".to_string(),
+        }
+    }
+
+    /// Get the node of an item read from the source
+    ///
+    /// ### Returns
+    /// - `Some(&JsonNode)`: The node
+    /// - `None`: The item was computed
+    fn node(&self) -> Option<&'a JsonNode> {
+        match self.target {
+            Target::Node(node) => Some(node),
+            Target::Computed(_) => None,
+        }
+    }
+
+    /// Get the plain value of an item
+    ///
+    /// ### Returns
+    /// - `Value`: The value
+    fn value(&self) -> Value {
+        match &self.target {
+            Target::Node(node) => node.to_value(),
+            Target::Computed(value) => value.clone(),
+        }
+    }
+
+    /// Build a child item reached through a key
+    ///
+    /// ### Arguments
+    /// - `key`: The key
+    /// - `node`: The value of the key
+    ///
+    /// ### Returns
+    /// - `Item`: The child item
+    fn key_child(&self, key: &str, node: &'a JsonNode) -> Self {
+        let is_identifier = key
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let path = if is_identifier {
+            format!("{}.{key}", self.path)
+        } else {
+            format!(
+                "{}['{}']",
+                self.path,
+                key.replace('\\', "\\\\").replace('\'', "\\'")
+            )
+        };
+        Self {
+            target: Target::Node(node),
+            path,
+        }
+    }
+
+    /// Build a child item reached through an index
+    ///
+    /// ### Arguments
+    /// - `index`: The index
+    /// - `node`: The item at the index
+    ///
+    /// ### Returns
+    /// - `Item`: The child item
+    fn index_child(&self, index: usize, node: &'a JsonNode) -> Self {
+        Self {
+            target: Target::Node(node),
+            path: format!("{}[{index}]", self.path),
+        }
+    }
+
+    /// Push the direct children of an item
+    ///
+    /// ### Arguments
+    /// - `out`: The items to push to
+    fn push_children(&self, out: &mut Vec<Item<'a>>) {
+        let Some(node) = self.node() else {
+            return;
+        };
+        match &node.value {
+            JsonValue::Array(items) => out.extend(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, child)| self.index_child(index, child)),
+            ),
+            JsonValue::Object(entries) => out.extend(
+                entries
+                    .iter()
+                    .map(|(key, child)| self.key_child(key, child)),
+            ),
+            _ => {}
+        }
+    }
+
+    /// Push an item and every item nested in it, depth first
+    ///
+    /// ### Arguments
+    /// - `out`: The items to push to
+    fn push_descendants(self, out: &mut Vec<Item<'a>>) {
+        if out.len() >= MAX_ITEMS {
+            return;
+        }
+        let mut children = Vec::new();
+        self.push_children(&mut children);
+        out.push(self);
+        for child in children {
+            child.push_descendants(out);
+        }
+    }
+}
+
+/// Run query steps over a list of items
+///
+/// ### Arguments
+/// - `steps`: The steps to run, in order
+/// - `items`: The input items
+///
+/// ### Returns
+/// - `Vec<Item>`: The items produced by the last step
+fn run_steps<'a>(steps: &[Step], mut items: Vec<Item<'a>>) -> Vec<Item<'a>> {
+    for step in steps {
+        let mut out = Vec::new();
+        for item in items {
+            if out.len() >= MAX_ITEMS {
+                break;
+            }
+            apply_step(step, item, &mut out);
+        }
+        out.truncate(MAX_ITEMS);
+        items = out;
+    }
+    items
+}
+
+/// Apply one query step to an item
+///
+/// ### Arguments
+/// - `step`: The step
+/// - `item`: The input item
+/// - `out`: The items to push the results to
+fn apply_step<'a>(step: &Step, item: Item<'a>, out: &mut Vec<Item<'a>>) {
+    match step {
+        Step::Key(key) => {
+            if let Some(child) = item.node().and_then(|node| node.get(key)) {
+                out.push(item.key_child(key, child));
+            }
+        }
+        Step::Index(index) => {
+            if let Some(JsonValue::Array(items)) = item.node().map(|node| &node.value)
+                && let Some(resolved) = resolve_index(*index, items.len())
+            {
+                out.push(item.index_child(resolved, &items[resolved]));
+            }
+        }
+        Step::Slice(start, end) => {
+            if let Some(JsonValue::Array(items)) = item.node().map(|node| &node.value) {
+                let len = items.len();
+                let start = start.map_or(0, |start| clamp_index(start, len));
+                let end = end.map_or(len, |end| clamp_index(end, len));
+                for (index, child) in items.iter().enumerate().take(end).skip(start) {
+                    out.push(item.index_child(index, child));
+                }
+            }
+        }
+        Step::Children => item.push_children(out),
+        Step::Descendants => item.push_descendants(out),
+        Step::Select(predicate) => {
+            if predicate.matches(&item) {
+                out.push(item);
+            }
+        }
+        Step::Keys => {
+            let keys = match item.node().map(|node| &node.value) {
+                Some(JsonValue::Object(entries)) => {
+                    let mut keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+                    keys.sort_unstable();
+                    keys.dedup();
+                    keys.into_iter().map(Value::from).collect()
+                }
+                Some(JsonValue::Array(items)) => (0..items.len()).map(Value::from).collect(),
+                _ => return,
+            };
+            out.push(Item {
+                target: Target::Computed(Value::Array(keys)),
+                path: format!("keys({})", item.path),
+            });
+        }
+        Step::Length => {
+            let length = match item.value() {
+                Value::Array(items) => items.len(),
+                Value::Object(entries) => entries.len(),
+                Value::String(s) => s.chars().count(),
+                Value::Null => 0,
+                _ => return,
+            };
+            out.push(Item {
+                target: Target::Computed(Value::from(length)),
+                path: format!("length({})", item.path),
+            });
+        }
+    }
+}
+
+/// Resolve a possibly negative index against a length
+///
+/// ### Arguments
+/// - `index`: The index, counted from the end when negative
+/// - `len`: The length of the array
+///
+/// ### Returns
+/// - `Some(usize)`: The index
+/// - `None`: The index is out of range
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let len = i64::try_from(len).ok()?;
+    let resolved = if index < 0 { len + index } else { index };
+    if (0..len).contains(&resolved) {
+        usize::try_from(resolved).ok()
+    } else {
+        None
+    }
+}
+
+/// Clamp a possibly negative slice bound to a length
+///
+/// ### Arguments
+/// - `bound`: The bound, counted from the end when negative
+/// - `len`: The length of the array
+///
+/// ### Returns
+/// - `usize`: The bound, between 0 and `len`
+fn clamp_index(bound: i64, len: usize) -> usize {
+    let signed_len = i64::try_from(len).unwrap_or(i64::MAX);
+    let resolved = if bound < 0 {
+        signed_len.saturating_add(bound)
+    } else {
+        bound
+    };
+    usize::try_from(resolved.clamp(0, signed_len)).unwrap_or(len)
+}
+
+impl Predicate {
+    /// Check whether an item passes the predicate
+    ///
+    /// ### Arguments
+    /// - `item`: The item to check
+    ///
+    /// ### Returns
+    /// - `bool`: Whether the item is kept
+    fn matches(&self, item: &Item<'_>) -> bool {
+        let found = run_steps(&self.path, vec![item.clone()])
+            .first()
+            .map_or(Value::Null, Item::value);
+        match &self.test {
+            None => !matches!(found, Value::Null | Value::Bool(false)),
+            Some((comparison, literal)) => compare(&found, *comparison, literal),
+        }
+    }
+}
+
+/// Compare two values
+///
+/// ### Description
+/// Numbers compare by value and strings lexicographically. Ordering between
+/// values of different types is always false.
+///
+/// ### Arguments
+/// - `left`: The value found in the document
+/// - `comparison`: The operator
+/// - `right`: The literal of the predicate
+///
+/// ### Returns
+/// - `bool`: The result of the comparison
+fn compare(left: &Value, comparison: Comparison, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .zip(b.as_f64())
+            .and_then(|(a, b)| a.partial_cmp(&b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match comparison {
+        Comparison::Equal => ordering.map_or(left == right, std::cmp::Ordering::is_eq),
+        Comparison::NotEqual => ordering.map_or(left != right, std::cmp::Ordering::is_ne),
+        Comparison::Less => ordering.is_some_and(std::cmp::Ordering::is_lt),
+        Comparison::LessOrEqual => ordering.is_some_and(std::cmp::Ordering::is_le),
+        Comparison::Greater => ordering.is_some_and(std::cmp::Ordering::is_gt),
+        Comparison::GreaterOrEqual => ordering.is_some_and(std::cmp::Ordering::is_ge),
+    }
+}
+
+/// Character cursor over a query expression
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize, // Byte offset of the next character
+}
+
+impl Cursor<'_> {
+    /// Build an error located at the current position
+    ///
+    /// ### Arguments
+    /// - `message`: The description of the error
+    ///
+    /// ### Returns
+    /// - `anyhow::Error`: The error, with a one-based character position
+    fn error(&self, message: &str) -> anyhow::Error {
+        let position = self.text[..self.pos].chars().count() + 1;
+        anyhow!("{message} at position {position}")
+    }
+
+    /// Get the rest of the expression
+    ///
+    /// ### Returns
+    /// - `&str`: The unread part of the expression
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    /// Get the next character
+    ///
+    /// ### Returns
+    /// - `Some(char)`: The character
+    /// - `None`: At the end of the expression
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Skip whitespace
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consume a character if it is next
+    ///
+    /// ### Arguments
+    /// - `c`: The expected character
+    ///
+    /// ### Returns
+    /// - `bool`: Whether the character was consumed
+    fn eat(&mut self, c: char) -> bool {
+        self.eat_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Consume a string if it is next
+    ///
+    /// ### Arguments
+    /// - `s`: The expected string
+    ///
+    /// ### Returns
+    /// - `bool`: Whether the string was consumed
+    fn eat_str(&mut self, s: &str) -> bool {
+        let found = self.rest().starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    /// Consume a keyword if it is next and not the start of a longer identifier
+    ///
+    /// ### Arguments
+    /// - `word`: The keyword
+    ///
+    /// ### Returns
+    /// - `bool`: Whether the keyword was consumed
+    fn eat_keyword(&mut self, word: &str) -> bool {
+        let found = self.rest().starts_with(word)
+            && !self.rest()[word.len()..]
+                .chars()
+                .next()
+                .is_some_and(is_identifier_char);
+        if found {
+            self.pos += word.len();
+        }
+        found
+    }
+
+    /// Consume a character that must be next
+    ///
+    /// ### Arguments
+    /// - `c`: The expected character
+    ///
+    /// ### Errors
+    /// - When another character, or the end of the expression, comes next
+    fn expect(&mut self, c: char) -> Result<()> {
+        self.skip_whitespace();
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{c}'")))
+        }
+    }
+
+    /// Consume an identifier
+    ///
+    /// ### Returns
+    /// - `Some(String)`: The identifier
+    /// - `None`: No identifier comes next
+    fn identifier(&mut self) -> Option<String> {
+        let rest = self.rest();
+        if !rest
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_')
+        {
+            return None;
+        }
+        let len = rest
+            .find(|c: char| !is_identifier_char(c))
+            .unwrap_or(rest.len());
+        let identifier = rest[..len].to_string();
+        self.pos += len;
+        Some(identifier)
+    }
+
+    /// Consume an optionally negative integer
+    ///
+    /// ### Returns
+    /// - `Some(i64)`: The integer
+    /// - `None`: No integer comes next
+    fn integer(&mut self) -> Option<i64> {
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let digits = rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign);
+        let value = rest[..sign + digits].parse().ok()?;
+        self.pos += sign + digits;
+        Some(value)
+    }
+
+    /// Consume a single- or double-quoted string
+    ///
+    /// ### Returns
+    /// - `Ok(String)`: The string, with `\\`, `\'`, `\"` and JSON escapes decoded
+    ///
+    /// ### Errors
+    /// - When the string is unterminated
+    fn quoted(&mut self) -> Result<String> {
+        let start = self.pos;
+        let Some(quote) = self.peek().filter(|c| matches!(c, '\'' | '"')) else {
+            return Err(self.error("Expected a quoted string"));
+        };
+        self.pos += 1;
+        let mut value = String::new();
+        let mut chars = self.rest().chars();
+        loop {
+            match chars.next() {
+                Some(c) if c == quote => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                Some(c) => value.push(c),
+                None => {
+                    self.pos = start;
+                    return Err(self.error("Unterminated string"));
+                }
+            }
+        }
+        self.pos = self.text.len() - chars.as_str().len();
+        Ok(value)
+    }
+}
+
+/// Check whether a character may continue an identifier
+///
+/// ### Arguments
+/// - `c`: The character
+///
+/// ### Returns
+/// - `bool`: Whether `c` is alphanumeric, `_` or `-`
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Parse the steps of a `JSONPath` expression, after its `$` or `@`
+///
+/// ### Arguments
+/// - `cursor`: The cursor, positioned after the root marker
+///
+/// ### Returns
+/// - `Ok(Vec<Step>)`: The steps
+///
+/// ### Errors
+/// - When a member or bracket is malformed
+fn parse_jsonpath(cursor: &mut Cursor<'_>) -> Result<Vec<Step>> {
+    let mut steps = Vec::new();
+    loop {
+        if cursor.eat_str("..") {
+            steps.push(Step::Descendants);
+            if cursor.peek() != Some('[') {
+                parse_member(cursor, &mut steps)?;
+            }
+        } else if cursor.eat('.') {
+            parse_member(cursor, &mut steps)?;
+        } else if cursor.eat('[') {
+            parse_bracket(cursor, &mut steps)?;
+        } else {
+            return Ok(steps);
+        }
+    }
+}
+
+/// Parse the member following a `.`: a key or `*`
+///
+/// ### Arguments
+/// - `cursor`: The cursor, positioned after the dot
+/// - `steps`: The steps to push to
+///
+/// ### Errors
+/// - When neither a key nor `*` follows
+fn parse_member(cursor: &mut Cursor<'_>, steps: &mut Vec<Step>) -> Result<()> {
+    if cursor.eat('*') {
+        steps.push(Step::Children);
+        return Ok(());
+    }
+    if cursor.peek() == Some('"') {
+        steps.push(Step::Key(cursor.quoted()?));
+        return Ok(());
+    }
+    let key = cursor
+        .identifier()
+        .ok_or_else(|| cursor.error("Expected a key"))?;
+    steps.push(Step::Key(key));
+    Ok(())
+}
+
+/// Parse a bracket, after its `[`: `[]`, `[*]`, `['key']`, `[0]`, `[1:3]` or `[?(...)]`
+///
+/// ### Arguments
+/// - `cursor`: The cursor, positioned after the opening bracket
+/// - `steps`: The steps to push to
+///
+/// ### Errors
+/// - When the bracket is malformed or unclosed
+fn parse_bracket(cursor: &mut Cursor<'_>, steps: &mut Vec<Step>) -> Result<()> {
+    cursor.skip_whitespace();
+    if cursor.eat(']') {
+        steps.push(Step::Children);
+        return Ok(());
+    }
+    if cursor.eat('*') {
+        steps.push(Step::Children);
+    } else if cursor.eat('?') {
+        cursor.expect('(')?;
+        cursor.skip_whitespace();
+        cursor.expect('@')?;
+        let path = parse_jsonpath(cursor)?;
+        let predicate = parse_test(cursor, path)?;
+        cursor.expect(')')?;
+        steps.push(Step::Children);
+        steps.push(Step::Select(predicate));
+    } else if matches!(cursor.peek(), Some('\'' | '"')) {
+        steps.push(Step::Key(cursor.quoted()?));
+    } else {
+        let start = cursor.integer();
+        cursor.skip_whitespace();
+        if cursor.eat(':') {
+            cursor.skip_whitespace();
+            steps.push(Step::Slice(start, cursor.integer()));
+        } else {
+            let index = start.ok_or_else(|| cursor.error("Expected an index, a key or '*'"))?;
+            steps.push(Step::Index(index));
+        }
+    }
+    cursor.expect(']')
+}
+
+/// Parse the optional comparison following the path of a filter
+///
+/// ### Arguments
+/// - `cursor`: The cursor, positioned after the path
+/// - `path`: The path of the filter
+///
+/// ### Returns
+/// - `Ok(Predicate)`: The filter
+///
+/// ### Errors
+/// - When the literal after an operator is malformed
+fn parse_test(cursor: &mut Cursor<'_>, path: Vec<Step>) -> Result<Predicate> {
+    cursor.skip_whitespace();
+    let operators = [
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+    let Some(comparison) = operators
+        .iter()
+        .find(|(operator, _)| cursor.eat_str(operator))
+        .map(|(_, comparison)| *comparison)
+    else {
+        return Ok(Predicate { path, test: None });
+    };
+    cursor.skip_whitespace();
+    let literal = if matches!(cursor.peek(), Some('\'' | '"')) {
+        Value::String(cursor.quoted()?)
+    } else {
+        let rest = cursor.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, ')' | ']' | '|'))
+            .unwrap_or(rest.len());
+        let literal = serde_json::from_str::<Value>(&rest[..len])
+            .map_err(|_| cursor.error("Expected a number, a string, true, false or null"))?;
+        cursor.pos += len;
+        literal
+    };
+    Ok(Predicate {
+        path,
+        test: Some((comparison, literal)),
+    })
+}
+
+/// Parse a jq-style pipeline: terms separated by `|`
+///
+/// ### Arguments
+/// - `cursor`: The cursor, positioned at the start of the expression
+///
+/// ### Returns
+/// - `Ok(Vec<Step>)`: The steps of every term, in order
+///
+/// ### Errors
+/// - When a term is malformed
+fn parse_jq(cursor: &mut Cursor<'_>) -> Result<Vec<Step>> {
+    let mut steps = Vec::new();
+    loop {
+        cursor.skip_whitespace();
+        if cursor.eat_keyword("keys") {
+            steps.push(Step::Keys);
+        } else if cursor.eat_keyword("length") {
+            steps.push(Step::Length);
+        } else if cursor.eat_keyword("select") {
+            cursor.expect('(')?;
+            cursor.skip_whitespace();
+            let path = parse_jq_path(cursor)?;
+            let predicate = parse_test(cursor, path)?;
+            cursor.expect(')')?;
+            steps.push(Step::Select(predicate));
+        } else if cursor.peek() == Some('.') {
+            steps.extend(parse_jq_path(cursor)?);
+        } else {
+            return Err(cursor.error("Expected '.', '$', 'keys', 'length' or 'select(...)'"));
+        }
+        cursor.skip_whitespace();
+        if !cursor.eat('|') {
+            return Ok(steps);
+        }
+    }
+}
+
+/// Parse a jq path such as `.`, `..`, `.a.b`, `.a[0]`, `.[]` or `."key"`
+///
+/// ### Arguments
+/// - `cursor`: The cursor, positioned at the leading dot
+///
+/// ### Returns
+/// - `Ok(Vec<Step>)`: The steps of the path, empty for `.`
+///
+/// ### Errors
+/// - When the path does not start with a dot or a bracket is malformed
+fn parse_jq_path(cursor: &mut Cursor<'_>) -> Result<Vec<Step>> {
+    let mut steps = Vec::new();
+    if cursor.eat_str("..") {
+        steps.push(Step::Descendants);
+    } else {
+        cursor.expect('.')?;
+        if cursor.peek() == Some('"') {
+            steps.push(Step::Key(cursor.quoted()?));
+        } else if let Some(key) = cursor.identifier() {
+            steps.push(Step::Key(key));
+        }
+    }
+    loop {
+        if cursor.eat('[') {
+            parse_bracket(cursor, &mut steps)?;
+        } else if cursor.peek() == Some('.') && !cursor.rest().starts_with("..") {
+            cursor.eat('.');
+            parse_member(cursor, &mut steps)?;
+        } else if !cursor.eat('?') {
+            return Ok(steps);
+        }
+    }
+}
+
+/// Combine the values of query matches into one document
+///
+/// ### Arguments
+/// - `matches`: The matches
+///
+/// ### Returns
+/// - `Value`: The only value when there is one match, an array of the values otherwise
+#[must_use]
+pub fn results_value(matches: &[QueryMatch]) -> Value {
+    match matches {
+        [single] => single.value.clone(),
+        _ => Value::Array(matches.iter().map(|m| m.value.clone()).collect()),
+    }
+}
+
+/// Render a value on one line, shortened for display
+///
+/// ### Arguments
+/// - `value`: The value
+/// - `max_chars`: The most characters to keep
+///
+/// ### Returns
+/// - `String`: The compact JSON of the value, ending with `…` when shortened
+#[must_use]
+pub fn preview(value: &Value, max_chars: usize) -> String {
+    let text = value.to_string();
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonValue, compile, parse_spanned, preview, results_value};
+    use crate::fulgur::utils::text_diff::line_and_column;
+    use serde_json::{Value, json};
+
+    const DOCUMENT: &str = r#"{
+  "store": {
+    "books": [
+      { "title": "Dune", "price": 9.5, "tags": ["sf"] },
+      { "title": "Emma", "price": 12, "in-stock": true }
+    ],
+    "owner": "Zoë"
+  }
+}"#;
+
+    fn run(expression: &str) -> Vec<(String, Value)> {
+        let root = parse_spanned(DOCUMENT).expect("document should parse");
+        compile(expression)
+            .expect("expression should compile")
+            .evaluate(&root)
+            .into_iter()
+            .map(|m| (m.path, m.value))
+            .collect()
+    }
+
+    #[test]
+    fn parse_spanned_keeps_value_ranges() {
+        let root = parse_spanned(DOCUMENT).expect("document should parse");
+        assert_eq!(root.span, 0..DOCUMENT.len());
+        let matches = compile("$.store.owner")
+            .expect("expression should compile")
+            .evaluate(&root);
+        let span = matches[0].span.clone().expect("source values have a span");
+        assert_eq!(&DOCUMENT[span.clone()], "\"Zoë\"");
+        assert_eq!(line_and_column(DOCUMENT, span.start), (6, 13));
+
+        assert!(parse_spanned("{\"a\": [1, 2,]}").is_err());
+        assert!(parse_spanned("[1] 2").is_err());
+        let nested = parse_spanned("[[]]").expect("arrays should parse");
+        assert!(matches!(nested.value, JsonValue::Array(ref items) if items[0].span == (1..3)));
+    }
+
+    #[test]
+    fn jsonpath_selects_members_indices_and_wildcards() {
+        assert_eq!(
+            run("$.store.books[0].title"),
+            vec![("$.store.books[0].title".to_string(), json!("Dune"))]
+        );
+        assert_eq!(run("$.store.books[-1].price")[0].1, json!(12));
+        assert_eq!(run("$..title").len(), 2);
+        assert_eq!(
+            run("$.store.books[*]['in-stock']")[0].0,
+            "$.store.books[1]['in-stock']"
+        );
+        assert_eq!(run("$.store.books[0:1]").len(), 1);
+        assert_eq!(
+            run("$.store.books[?(@.price < 10)].title"),
+            vec![("$.store.books[0].title".to_string(), json!("Dune"))]
+        );
+        assert!(run("$.missing[3]").is_empty());
+    }
+
+    #[test]
+    fn jq_supports_pipes_select_keys_and_length() {
+        assert_eq!(
+            run(".store.books[] | select(.title == \"Emma\") | .price"),
+            vec![("$.store.books[1].price".to_string(), json!(12))]
+        );
+        assert_eq!(run(".store.books | length")[0].1, json!(2));
+        assert_eq!(run(".store | keys")[0].1, json!(["books", "owner"]));
+        assert_eq!(run(".")[0].0, "$");
+        assert_eq!(run(".store.books[].tags?").len(), 1);
+        assert_eq!(run("..|select(.price >= 12)|.title")[0].1, json!("Emma"));
+    }
+
+    #[test]
+    fn compile_reports_the_error_position() {
+        let error = compile("$.store[").err().map(|e| e.to_string());
+        assert_eq!(
+            error.as_deref(),
+            Some("Expected an index, a key or '*' at position 9")
+        );
+        assert!(compile("store").is_err());
+        assert!(compile(".a | select(.b == )").is_err());
+    }
+
+    #[test]
+    fn results_and_previews() {
+        let root = parse_spanned("[1, 2]").expect("document should parse");
+        let matches = compile(".[]")
+            .expect("expression should compile")
+            .evaluate(&root);
+        assert_eq!(results_value(&matches), json!([1, 2]));
+        assert_eq!(results_value(&matches[..1]), json!(1));
+        assert_eq!(preview(&json!({"a": "long text"}), 6), "{\"a\":\"…");
+    }
+}
@@ -0,0 +1,286 @@
+//! Save images pasted or dropped into a Markdown document next to it.
+
+use anyhow::anyhow;
+use std::path::{Component, Path, PathBuf};
+use time::{OffsetDateTime, macros::format_description};
+
+/// Extensions of the image files the Markdown preview can show
+const IMAGE_EXTENSIONS: [&str; 10] = [
+    "png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "tif", "tiff", "ico",
+];
+
+/// Check whether a file is an image, from its extension
+///
+/// ### Arguments
+/// - `path`: The path of the file
+///
+/// ### Returns
+/// - `bool`: True if the extension is one of an image format
+#[must_use]
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|image| image.eq_ignore_ascii_case(extension))
+        })
+}
+
+/// Get the file extension of an image MIME type
+///
+/// ### Arguments
+/// - `mime_type`: The MIME type, such as `image/png`
+///
+/// ### Returns
+/// - `&str`: The extension, `png` for an unknown type
+#[must_use]
+pub fn image_extension_for_mime(mime_type: &str) -> &str {
+    match mime_type.strip_prefix("image/") {
+        Some("jpeg") => "jpg",
+        Some("svg+xml") => "svg",
+        Some(subtype) if IMAGE_EXTENSIONS.contains(&subtype) => subtype,
+        _ => "png",
+    }
+}
+
+/// Build a file name that no file uses yet
+///
+/// ### Description
+/// Whitespace in the stem becomes hyphens and other characters that would need
+/// escaping in a Markdown link are dropped. A `-1`, `-2`... suffix is added
+/// while the name is taken.
+///
+/// ### Arguments
+/// - `stem`: The wanted name, without extension
+/// - `extension`: The extension of the file
+/// - `taken`: Whether a file name is already used
+///
+/// ### Returns
+/// - `String`: The free file name
+#[must_use]
+pub fn unique_file_name(stem: &str, extension: &str, taken: impl Fn(&str) -> bool) -> String {
+    let mut clean: String = stem
+        .trim()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                Some(c)
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect();
+    if clean.trim_matches('.').is_empty() {
+        clean = "image".to_string();
+    }
+    let mut name = format!("{clean}.{extension}");
+    let mut suffix = 1;
+    while taken(&name) {
+        name = format!("{clean}-{suffix}.{extension}");
+        suffix += 1;
+    }
+    name
+}
+
+/// Name a pasted image after the moment it was pasted
+///
+/// ### Arguments
+/// - `now`: The time of the paste
+///
+/// ### Returns
+/// - `String`: The file name without extension, such as `image-20240131-094512`
+#[must_use]
+pub fn pasted_image_stem(now: OffsetDateTime) -> String {
+    now.format(format_description!(
+        "image-[year][month][day]-[hour][minute][second]"
+    ))
+    .unwrap_or_else(|_| "image".to_string())
+}
+
+/// Build the Markdown of an image
+///
+/// ### Description
+/// A path holding spaces, parentheses or angle brackets is written between
+/// `<` and `>`, so that the whole path stays the destination of the link.
+///
+/// ### Arguments
+/// - `alt`: The alternative text of the image
+/// - `path`: The path of the image, relative to the document
+///
+/// ### Returns
+/// - `String`: The image link, with `/` separators on every platform
+#[must_use]
+pub fn image_markdown(alt: &str, path: &Path) -> String {
+    let alt = alt.replace('[', "\\[").replace(']', "\\]");
+    let parts: Vec<String> = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            Component::ParentDir => Some("..".to_string()),
+            _ => None,
+        })
+        .collect();
+    let destination = parts.join("/");
+    let needs_brackets = destination
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>' | '\\'));
+    if needs_brackets {
+        let escaped = destination
+            .replace('\\', "\\\\")
+            .replace('<', "\\<")
+            .replace('>', "\\>");
+        format!("![{alt}](<{escaped}>)")
+    } else {
+        format!("![{alt}]({destination})")
+    }
+}
+
+/// Write an image into the image folder of a document
+///
+/// ### Arguments
+/// - `document_dir`: The directory of the document
+/// - `folder`: The image folder, relative to the document
+/// - `stem`: The wanted file name, without extension
+/// - `extension`: The extension of the image
+/// - `bytes`: The encoded image
+///
+/// ### Returns
+/// - `Ok(PathBuf)`: The path of the saved image, relative to the document
+///
+/// ### Errors
+/// - Returns an error if the folder cannot be created or the image cannot be written
+pub fn save_image(
+    document_dir: &Path,
+    folder: &str,
+    stem: &str,
+    extension: &str,
+    bytes: &[u8],
+) -> anyhow::Result<PathBuf> {
+    let target_dir = document_dir.join(folder);
+    std::fs::create_dir_all(&target_dir)
+        .map_err(|e| anyhow!("Failed to create {}: {e}", target_dir.display()))?;
+    let name = unique_file_name(stem, extension, |name| target_dir.join(name).exists());
+    let target = target_dir.join(&name);
+    std::fs::write(&target, bytes)
+        .map_err(|e| anyhow!("Failed to write {}: {e}", target.display()))?;
+    Ok(Path::new(folder).join(name))
+}
+
+/// Bring an image file into the image folder of a document
+///
+/// ### Description
+/// An image already inside the directory of the document is linked where it
+/// is. Any other image is copied into the image folder.
+///
+/// ### Arguments
+/// - `document_dir`: The directory of the document
+/// - `folder`: The image folder, relative to the document
+/// - `image`: The image file
+///
+/// ### Returns
+/// - `Ok(PathBuf)`: The path of the image, relative to the document
+///
+/// ### Errors
+/// - Returns an error if the folder cannot be created or the image cannot be copied
+pub fn import_image(document_dir: &Path, folder: &str, image: &Path) -> anyhow::Result<PathBuf> {
+    if let (Ok(document_dir), Ok(image)) = (
+        std::fs::canonicalize(document_dir),
+        std::fs::canonicalize(image),
+    ) && let Ok(relative) = image.strip_prefix(&document_dir)
+    {
+        return Ok(relative.to_path_buf());
+    }
+    let target_dir = document_dir.join(folder);
+    std::fs::create_dir_all(&target_dir)
+        .map_err(|e| anyhow!("Failed to create {}: {e}", target_dir.display()))?;
+    let stem = image
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = image
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name = unique_file_name(&stem, &extension, |name| target_dir.join(name).exists());
+    let target = target_dir.join(&name);
+    std::fs::copy(image, &target).map_err(|e| {
+        anyhow!(
+            "Failed to copy {} to {}: {e}",
+            image.display(),
+            target.display()
+        )
+    })?;
+    Ok(Path::new(folder).join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_image_path_and_mime_extensions() {
+        assert!(is_image_path(Path::new("shots/Screen.PNG")));
+        assert!(!is_image_path(Path::new("notes.md")));
+        assert_eq!(image_extension_for_mime("image/jpeg"), "jpg");
+        assert_eq!(image_extension_for_mime("image/svg+xml"), "svg");
+        assert_eq!(image_extension_for_mime("image/webp"), "webp");
+        assert_eq!(image_extension_for_mime("text/plain"), "png");
+    }
+
+    #[test]
+    fn test_unique_file_name_cleans_and_counts() {
+        let taken = ["my-shot.png", "my-shot-1.png"];
+        assert_eq!(
+            unique_file_name(" my shot(1)", "png", |name| taken.contains(&name)),
+            "my-shot1.png"
+        );
+        assert_eq!(
+            unique_file_name("my shot", "png", |name| taken.contains(&name)),
+            "my-shot-2.png"
+        );
+        assert_eq!(unique_file_name("()", "gif", |_| false), "image.gif");
+    }
+
+    #[test]
+    fn test_image_markdown_uses_forward_slashes() {
+        let path = Path::new(".").join("images").join("shot.png");
+        assert_eq!(
+            image_markdown("a [b]", &path),
+            "![a \\[b\\]](images/shot.png)"
+        );
+        let spaced = Path::new("my images").join("shot (1).png");
+        assert_eq!(
+            image_markdown("shot", &spaced),
+            "![shot](<my images/shot (1).png>)"
+        );
+        assert_eq!(
+            image_markdown("shot", Path::new("a<b>.png")),
+            "![shot](<a\\<b\\>.png>)"
+        );
+        let pasted = OffsetDateTime::from_unix_timestamp(1_706_694_312).expect("valid time");
+        assert_eq!(pasted_image_stem(pasted), "image-20240131-094512");
+    }
+
+    #[test]
+    fn test_save_and_import_images() {
+        let docs = TempDir::new().expect("failed to create temp dir");
+        let saved = save_image(docs.path(), "images", "shot", "png", b"one").expect("save");
+        assert_eq!(saved, Path::new("images").join("shot.png"));
+        let again = save_image(docs.path(), "images", "shot", "png", b"two").expect("save");
+        assert_eq!(again, Path::new("images").join("shot-1.png"));
+
+        let elsewhere = TempDir::new().expect("failed to create temp dir");
+        let outside = elsewhere.path().join("logo.svg");
+        std::fs::write(&outside, "<svg/>").expect("failed to write");
+        let imported = import_image(docs.path(), "images", &outside).expect("import");
+        assert_eq!(imported, Path::new("images").join("logo.svg"));
+        assert!(docs.path().join(&imported).exists());
+
+        let inside = import_image(docs.path(), "assets", &docs.path().join(&saved)).expect("link");
+        assert_eq!(inside, saved);
+    }
+}
@@ -0,0 +1,168 @@
+//! Split Markdown into its top-level blocks so the preview can follow the editor.
+
+use markdown::mdast::Node;
+use std::ops::Range;
+
+/// A top-level block of a Markdown document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkdownBlock {
+    pub lines: Range<usize>, // Zero-based source lines, end excluded
+    pub text: String,        // The block source, followed by the definitions shared by all blocks
+}
+
+/// Split Markdown into its top-level blocks
+///
+/// ### Description
+/// The blocks come from the top-level nodes of a tree the caller already
+/// parsed, and are cut from `text` by line, so the tree may be the one of a
+/// rewrite of the document keeping its lines. Each block starts at the
+/// beginning of its first line so that the indentation of its other lines
+/// keeps its meaning. Link reference and footnote definitions render nothing in
+/// place but are needed by the references of every block: they do not form
+/// blocks of their own and are appended to the text of each block instead.
+///
+/// ### Arguments
+/// - `text`: The Markdown document
+/// - `ast`: The tree of the document, `None` when it failed to parse
+///
+/// ### Returns
+/// - `Vec<MarkdownBlock>`: The blocks in document order; the whole document as a
+///   single block when it failed to parse, and nothing when it is blank
+#[must_use]
+pub fn split_markdown_blocks(text: &str, ast: Option<&Node>) -> Vec<MarkdownBlock> {
+    if text.trim().is_empty() {
+        return Vec::new();
+    }
+    let Some(ast) = ast else {
+        return vec![MarkdownBlock {
+            lines: 0..text.lines().count(),
+            text: text.to_string(),
+        }];
+    };
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(newline, _)| newline + 1))
+        .collect();
+    let source_of = |lines: &Range<usize>| {
+        let start = line_starts.get(lines.start).copied().unwrap_or(text.len());
+        let end = line_starts
+            .get(lines.end)
+            .map_or(text.len(), |&next| next - 1)
+            .max(start);
+        text[start..end].trim_end_matches('\r')
+    };
+    let children = ast.children().map(Vec::as_slice).unwrap_or_default();
+    let mut definitions = String::new();
+    let mut blocks = Vec::new();
+    for child in children {
+        let Some(position) = child.position() else {
+            continue;
+        };
+        let lines = position.start.line - 1..position.end.line;
+        if matches!(child, Node::Definition(_) | Node::FootnoteDefinition(_)) {
+            definitions.push('\n');
+            definitions.push_str(source_of(&lines));
+        } else {
+            blocks.push(lines);
+        }
+    }
+    if !definitions.is_empty() {
+        definitions.insert(0, '\n');
+    }
+    blocks
+        .into_iter()
+        .map(|lines| MarkdownBlock {
+            text: format!("{}{definitions}", source_of(&lines)),
+            lines,
+        })
+        .collect()
+}
+
+/// Find the block showing a source line
+///
+/// ### Arguments
+/// - `blocks`: The blocks of the document, in document order
+/// - `line`: The zero-based source line
+///
+/// ### Returns
+/// - `Some(usize)`: The index of the last block starting at or before the line,
+///   or of the first block for the lines above it
+/// - `None`: There is no block
+#[must_use]
+pub fn block_at_line(blocks: &[MarkdownBlock], line: usize) -> Option<usize> {
+    if blocks.is_empty() {
+        return None;
+    }
+    Some(
+        blocks
+            .partition_point(|block| block.lines.start <= line)
+            .saturating_sub(1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(source: &str) -> Vec<MarkdownBlock> {
+        let ast = markdown::to_mdast(source, &markdown::ParseOptions::gfm()).ok();
+        split_markdown_blocks(source, ast.as_ref())
+    }
+
+    #[test]
+    fn test_split_markdown_blocks_maps_lines() {
+        let blocks = split("# Title\n\nSome\ntext\n\n- a\n- b\n");
+        let lines: Vec<_> = blocks.iter().map(|block| block.lines.clone()).collect();
+        assert_eq!(lines, vec![0..1, 2..4, 5..7]);
+        assert_eq!(blocks[1].text, "Some\ntext");
+        assert_eq!(blocks[2].text, "- a\n- b");
+    }
+
+    #[test]
+    fn test_split_markdown_blocks_shares_link_definitions() {
+        let blocks = split("[Fulgur][home]\n\n[home]: https://example.com\n");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(
+            blocks[0].text,
+            "[Fulgur][home]\n\n[home]: https://example.com"
+        );
+    }
+
+    #[test]
+    fn test_split_markdown_blocks_shares_footnote_definitions() {
+        let blocks = split("Intro\n\nSee[^note].\n\n[^note]: A note\n    over lines.\n");
+        let lines: Vec<_> = blocks.iter().map(|block| block.lines.clone()).collect();
+        assert_eq!(lines, vec![0..1, 2..3]);
+        assert_eq!(
+            blocks[1].text,
+            "See[^note].\n\n[^note]: A note\n    over lines."
+        );
+    }
+
+    #[test]
+    fn test_split_markdown_blocks_follows_the_lines_of_a_rewrite() {
+        let ast = markdown::to_mdast("- [ ] a\n\ntext\n", &markdown::ParseOptions::gfm()).ok();
+        let blocks = split_markdown_blocks("- [☐](task) a\n\ntext\n", ast.as_ref());
+        assert_eq!(blocks[0].text, "- [☐](task) a");
+        assert_eq!(blocks[1].text, "text");
+        assert_eq!(split_markdown_blocks("a\nb", None)[0].lines, 0..2);
+    }
+
+    #[test]
+    fn test_split_markdown_blocks_keeps_fences_whole() {
+        let blocks = split("Intro\n\n```\na\n\nb\n```\n");
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].lines, 2..7);
+        assert!(split(" \n\n").is_empty());
+    }
+
+    #[test]
+    fn test_block_at_line() {
+        let blocks = split("\n\nFirst\n\nSecond\nline\n\nThird\n");
+        assert_eq!(block_at_line(&blocks, 0), Some(0));
+        assert_eq!(block_at_line(&blocks, 2), Some(0));
+        assert_eq!(block_at_line(&blocks, 3), Some(0));
+        assert_eq!(block_at_line(&blocks, 5), Some(1));
+        assert_eq!(block_at_line(&blocks, 40), Some(2));
+        assert_eq!(block_at_line(&[], 0), None);
+    }
+}
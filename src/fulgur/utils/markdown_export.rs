@@ -0,0 +1,635 @@
+//! Render Markdown to a standalone HTML document, matching the preview.
+
+use crate::fulgur::utils::code_html::{SyntaxSpan, escape_html, highlighted_code_html};
+use anyhow::anyhow;
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use http_client::Url;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How the local images of an exported document are included
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HtmlImages {
+    Inline,    // Embedded in the document as data URIs
+    CopyFiles, // Copied to a folder next to the document
+}
+
+/// Colors of the exported document, as 0xRRGGBB
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExportPalette {
+    pub background: u32,
+    pub foreground: u32,
+    pub muted_background: u32, // Code blocks, table headers and inline code
+    pub muted_foreground: u32, // Block quotes and captions
+    pub border: u32,
+    pub link: u32,
+}
+
+/// A Markdown document compiled to HTML
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkdownHtml {
+    pub body: String,
+    pub images: HashSet<PathBuf>, // Local files shown by the image nodes of the source, the only ones included on export
+}
+
+/// An exported document once its local images were inlined or copied
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalizedHtml {
+    pub html: String,
+    pub missing_images: Vec<PathBuf>, // Images that could not be read, left pointing at their file
+}
+
+/// A fenced or indented code block of the source, in document order
+struct CodeBlock {
+    value: String,
+    lang: Option<String>,
+}
+
+/// The nodes of a Markdown tree the export needs
+#[derive(Default)]
+struct SourceParts {
+    code_blocks: Vec<CodeBlock>,
+    image_urls: Vec<String>,              // URLs of the inline images
+    image_references: Vec<String>,        // Identifiers of the images using a link definition
+    definitions: HashMap<String, String>, // Link definitions, by identifier
+}
+
+/// Collect the code blocks, images and link definitions of a Markdown tree in document order
+///
+/// ### Arguments
+/// - `node`: The current node of the tree
+/// - `parts`: Accumulator for the nodes
+fn collect_source_parts(node: &markdown::mdast::Node, parts: &mut SourceParts) {
+    use markdown::mdast::Node;
+    match node {
+        Node::Code(code) => parts.code_blocks.push(CodeBlock {
+            value: code.value.clone(),
+            lang: code.lang.clone(),
+        }),
+        Node::Image(image) => parts.image_urls.push(image.url.clone()),
+        Node::ImageReference(reference) => {
+            parts.image_references.push(reference.identifier.clone());
+        }
+        Node::Definition(definition) => {
+            parts
+                .definitions
+                .entry(definition.identifier.clone())
+                .or_insert_with(|| definition.url.clone());
+        }
+        _ => {}
+    }
+    if let Some(children) = node.children() {
+        for child in children {
+            collect_source_parts(child, parts);
+        }
+    }
+}
+
+/// Get the local file a `file://` URL points to
+///
+/// ### Arguments
+/// - `url`: The URL
+///
+/// ### Returns
+/// - `Some(PathBuf)`: The file
+/// - `None`: The URL is not a `file://` URL
+fn file_url_path(url: &str) -> Option<PathBuf> {
+    Url::parse(url)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok())
+}
+
+/// Reverse the escaping the Markdown compiler applies to text
+///
+/// ### Arguments
+/// - `text`: The escaped text
+///
+/// ### Returns
+/// - `String`: The text as written in the source
+fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+/// Highlight the code blocks of compiled HTML
+///
+/// ### Description
+/// Each `<pre><code>` element is matched with the next code block of the
+/// source whose text it holds, so `<pre>` elements written as raw HTML in the
+/// document are left alone.
+///
+/// ### Arguments
+/// - `html`: The compiled HTML
+/// - `blocks`: The code blocks of the source, in document order
+/// - `highlight`: Computes the styled spans of a block from its text and language tag
+///
+/// ### Returns
+/// - `String`: The HTML with the highlighted code blocks
+fn highlight_code_blocks(
+    html: &str,
+    blocks: &[CodeBlock],
+    highlight: &dyn Fn(&str, Option<&str>) -> Vec<SyntaxSpan>,
+) -> String {
+    const OPEN: &str = "<pre><code";
+    const CLOSE: &str = "</code></pre>";
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    let mut blocks = blocks.iter();
+    while let Some(start) = rest.find(OPEN) {
+        let Some(content_start) = rest[start..].find('>').map(|end| start + end + 1) else {
+            break;
+        };
+        let Some(content_end) = rest[content_start..]
+            .find(CLOSE)
+            .map(|end| content_start + end)
+        else {
+            break;
+        };
+        output.push_str(&rest[..content_start]);
+        let content = &rest[content_start..content_end];
+        let text = unescape_html(content);
+        let block = blocks
+            .clone()
+            .position(|block| block.value.trim_end_matches('\n') == text.trim_end_matches('\n'));
+        let spans = match block.and_then(|index| blocks.nth(index)) {
+            Some(block) => highlight(&block.value, block.lang.as_deref()),
+            None => Vec::new(),
+        };
+        if spans.is_empty() {
+            output.push_str(content);
+        } else {
+            output.push_str(&highlighted_code_html(&text, &spans));
+        }
+        rest = &rest[content_end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Render Markdown to HTML with GitHub Flavored Markdown, highlighting its code blocks
+///
+/// ### Description
+/// Raw HTML and every link protocol are kept, as the preview renders them and
+/// the local images arrive as `file://` URLs. Pass the source through the same
+/// rewriting as the preview first so both show the same document.
+///
+/// ### Arguments
+/// - `source`: The Markdown text
+/// - `highlight`: Computes the styled spans of a code block from its text and language tag
+///
+/// ### Returns
+/// - `MarkdownHtml`: The HTML of the document body, the escaped source in a `<pre>` if it cannot be compiled, and the local files of its images
+#[must_use]
+pub fn markdown_body_html(
+    source: &str,
+    highlight: &dyn Fn(&str, Option<&str>) -> Vec<SyntaxSpan>,
+) -> MarkdownHtml {
+    let options = markdown::Options {
+        parse: markdown::ParseOptions::gfm(),
+        compile: markdown::CompileOptions {
+            allow_dangerous_html: true,
+            allow_dangerous_protocol: true,
+            ..markdown::CompileOptions::gfm()
+        },
+    };
+    let html = match markdown::to_html_with_options(source, &options) {
+        Ok(html) => html,
+        Err(e) => {
+            log::warn!("Failed to compile Markdown to HTML: {e}");
+            return MarkdownHtml {
+                body: format!("<pre>{}</pre>\n", escape_html(source)),
+                images: HashSet::new(),
+            };
+        }
+    };
+    let mut parts = SourceParts::default();
+    if let Ok(ast) = markdown::to_mdast(source, &options.parse) {
+        collect_source_parts(&ast, &mut parts);
+    }
+    let referenced = parts
+        .image_references
+        .iter()
+        .filter_map(|identifier| parts.definitions.get(identifier));
+    let images = parts
+        .image_urls
+        .iter()
+        .chain(referenced)
+        .filter_map(|url| file_url_path(url))
+        .collect();
+    let body = if parts.code_blocks.is_empty() {
+        html
+    } else {
+        highlight_code_blocks(&html, &parts.code_blocks, highlight)
+    };
+    MarkdownHtml { body, images }
+}
+
+/// Build the stylesheet of an exported document from a palette
+///
+/// ### Arguments
+/// - `palette`: The colors of the current theme
+///
+/// ### Returns
+/// - `String`: The CSS
+#[must_use]
+pub fn palette_css(palette: &ExportPalette) -> String {
+    let ExportPalette {
+        background,
+        foreground,
+        muted_background,
+        muted_foreground,
+        border,
+        link,
+    } = *palette;
+    format!(
+        r#"body {{ margin: 0; background: #{background:06x}; color: #{foreground:06x}; font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; line-height: 1.6; }}
+main {{ max-width: 860px; margin: 0 auto; padding: 2em; }}
+a {{ color: #{link:06x}; }}
+h1, h2 {{ border-bottom: 1px solid #{border:06x}; padding-bottom: 0.3em; }}
+code, pre {{ font-family: ui-monospace, "SF Mono", Menlo, Consolas, monospace; font-size: 0.9em; }}
+code {{ background: #{muted_background:06x}; padding: 0.1em 0.3em; border-radius: 4px; }}
+pre {{ background: #{muted_background:06x}; padding: 1em; border-radius: 6px; overflow-x: auto; }}
+pre code {{ background: none; padding: 0; }}
+blockquote {{ margin: 0; padding: 0 1em; color: #{muted_foreground:06x}; border-left: 4px solid #{border:06x}; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #{border:06x}; padding: 0.4em 0.8em; }}
+th {{ background: #{muted_background:06x}; }}
+hr {{ border: none; border-top: 1px solid #{border:06x}; }}
+img {{ max-width: 100%; }}
+li > input[type="checkbox"] {{ margin-right: 0.4em; }}
+@media print {{ main {{ max-width: none; padding: 0; }} pre {{ white-space: pre-wrap; }} }}
+"#
+    )
+}
+
+/// Wrap a document body into a standalone HTML document
+///
+/// ### Arguments
+/// - `title`: The title of the document
+/// - `css`: The stylesheet, embedded in the document
+/// - `body`: The HTML of the body
+///
+/// ### Returns
+/// - `String`: The complete HTML document
+#[must_use]
+pub fn standalone_html(title: &str, css: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>\n{css}</style>\n</head>\n<body>\n<main>\n{body}</main>\n</body>\n</html>\n",
+        escape_html(title)
+    )
+}
+
+/// Get the media type of an image from its extension
+///
+/// ### Arguments
+/// - `path`: The image file
+///
+/// ### Returns
+/// - `Some(&'static str)`: The media type
+/// - `None`: The extension is not one of an image
+fn image_media_type(path: &Path) -> Option<&'static str> {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => Some("image/png"),
+        Some("jpg" | "jpeg") => Some("image/jpeg"),
+        Some("gif") => Some("image/gif"),
+        Some("svg") => Some("image/svg+xml"),
+        Some("webp") => Some("image/webp"),
+        Some("avif") => Some("image/avif"),
+        Some("bmp") => Some("image/bmp"),
+        Some("ico") => Some("image/x-icon"),
+        _ => None,
+    }
+}
+
+/// Check that the content of a file is an image of the given media type
+///
+/// ### Arguments
+/// - `bytes`: The content of the file
+/// - `media_type`: The media type named by its extension
+///
+/// ### Returns
+/// - `bool`: `true` if the content starts with the signature of that image format
+fn is_image_data(bytes: &[u8], media_type: &str) -> bool {
+    match media_type {
+        "image/png" => bytes.starts_with(b"\x89PNG\r\n\x1a\n"),
+        "image/jpeg" => bytes.starts_with(&[0xFF, 0xD8, 0xFF]),
+        "image/gif" => bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a"),
+        "image/webp" => bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP".as_slice()),
+        "image/avif" => matches!(bytes.get(4..12), Some(b"ftypavif" | b"ftypavis")),
+        "image/bmp" => bytes.starts_with(b"BM"),
+        "image/x-icon" => bytes.starts_with(&[0, 0, 1, 0]),
+        "image/svg+xml" => std::str::from_utf8(bytes).is_ok_and(|text| text.contains("<svg")),
+        _ => false,
+    }
+}
+
+/// Read a local image of an exported document
+///
+/// ### Arguments
+/// - `path`: The image file
+///
+/// ### Errors
+/// Returns an error if the file cannot be read, or is not an image of the type its extension names.
+///
+/// ### Returns
+/// - `Ok((Vec<u8>, &'static str))`: The content of the image and its media type
+fn read_image(path: &Path) -> anyhow::Result<(Vec<u8>, &'static str)> {
+    let media_type =
+        image_media_type(path).ok_or_else(|| anyhow!("{} is not an image", path.display()))?;
+    let bytes = fs::read(path).map_err(|e| anyhow!("Failed to read {}: {e}", path.display()))?;
+    if !is_image_data(&bytes, media_type) {
+        return Err(anyhow!("{} is not a {media_type} image", path.display()));
+    }
+    Ok((bytes, media_type))
+}
+
+/// Percent-encode a file name for use as a relative URL
+///
+/// ### Arguments
+/// - `name`: The file name
+///
+/// ### Returns
+/// - `String`: The name with reserved and non-ASCII bytes encoded
+fn encode_url_segment(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// Copies the local images of an exported document to a folder next to it
+struct ImageCopier {
+    dir: PathBuf,
+    dir_name: String,
+    copied: HashMap<PathBuf, String>, // Source image to its relative URL, so each image is copied once
+    names: HashSet<String>,           // File names taken in the folder
+}
+
+impl ImageCopier {
+    /// Copy an image to the folder, under a name not taken by another image
+    ///
+    /// ### Arguments
+    /// - `path`: The image file
+    ///
+    /// ### Errors
+    /// Returns an error if the image cannot be read, or the folder or the copy cannot be written.
+    ///
+    /// ### Returns
+    /// - `Ok(String)`: The URL of the copy, relative to the document
+    fn copy(&mut self, path: &Path) -> anyhow::Result<String> {
+        if let Some(url) = self.copied.get(path) {
+            return Ok(url.clone());
+        }
+        let (bytes, _) = read_image(path)?;
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| anyhow!("Failed to create {}: {e}", self.dir.display()))?;
+        let name = path.file_name().map_or_else(
+            || "image".to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => {
+                (stem.to_string(), format!(".{extension}"))
+            }
+            _ => (name.clone(), String::new()),
+        };
+        let mut file_name = name;
+        let mut counter = 1;
+        while self.names.contains(&file_name) {
+            counter += 1;
+            file_name = format!("{stem}-{counter}{extension}");
+        }
+        fs::write(self.dir.join(&file_name), bytes)
+            .map_err(|e| anyhow!("Failed to copy {}: {e}", path.display()))?;
+        let url = format!(
+            "{}/{}",
+            encode_url_segment(&self.dir_name),
+            encode_url_segment(&file_name)
+        );
+        self.names.insert(file_name);
+        self.copied.insert(path.to_path_buf(), url.clone());
+        Ok(url)
+    }
+}
+
+/// Inline or copy the local images of an exported document
+///
+/// ### Description
+/// Rewrites the `src` attributes holding the `file://` URL of an image node of
+/// the source, as produced by `rewrite_markdown_image_paths`. Other files, such
+/// as those named by raw HTML, are never read, and a file whose content is not
+/// an image of the type its extension names is left out. Inlined images become
+/// data URIs; copied images go to a `<document name>_files` folder next to the
+/// document.
+///
+/// ### Arguments
+/// - `html`: The exported document
+/// - `image_files`: The local files of the image nodes, from `MarkdownHtml::images`
+/// - `images`: How to include the images
+/// - `output`: Where the document will be written
+///
+/// ### Returns
+/// - `LocalizedHtml`: The document, and the images that could not be included
+#[must_use]
+pub fn localize_images(
+    html: &str,
+    image_files: &HashSet<PathBuf>,
+    images: HtmlImages,
+    output: &Path,
+) -> LocalizedHtml {
+    let stem = output.file_stem().map_or_else(
+        || "export".to_string(),
+        |stem| stem.to_string_lossy().into_owned(),
+    );
+    let dir_name = format!("{stem}_files");
+    let mut copier = ImageCopier {
+        dir: output.with_file_name(&dir_name),
+        dir_name,
+        copied: HashMap::new(),
+        names: HashSet::new(),
+    };
+    let mut missing_images = Vec::new();
+    let mut localized = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(found) = rest.find("src=") {
+        let value_start = found + "src=".len();
+        localized.push_str(&rest[..value_start]);
+        rest = &rest[value_start..];
+        let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let Some(value_len) = rest[1..].find(quote) else {
+            continue;
+        };
+        let value = &rest[1..=value_len];
+        let Some(path) =
+            file_url_path(&unescape_html(value)).filter(|path| image_files.contains(path))
+        else {
+            continue;
+        };
+        let replacement = match images {
+            HtmlImages::Inline => read_image(&path).map(|(bytes, media_type)| {
+                format!("data:{media_type};base64,{}", BASE64.encode(bytes))
+            }),
+            HtmlImages::CopyFiles => copier.copy(&path),
+        };
+        match replacement {
+            Ok(replacement) => {
+                localized.push(quote);
+                localized.push_str(&replacement);
+                localized.push(quote);
+                rest = &rest[value_len + 2..];
+            }
+            Err(e) => {
+                log::warn!("{e}");
+                missing_images.push(path);
+            }
+        }
+    }
+    localized.push_str(rest);
+    LocalizedHtml {
+        html: localized,
+        missing_images,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_highlight(_code: &str, _lang: Option<&str>) -> Vec<SyntaxSpan> {
+        Vec::new()
+    }
+
+    #[test]
+    fn test_markdown_body_html_renders_gfm() {
+        let html = markdown_body_html(
+            "# Title\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n- [x] done\n\n~~old~~\n",
+            &no_highlight,
+        )
+        .body;
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<table>"));
+        assert!(html.contains("type=\"checkbox\""));
+        assert!(html.contains("<del>old</del>"));
+    }
+
+    #[test]
+    fn test_markdown_body_html_highlights_code_blocks() {
+        let source = "<pre><code>raw</code></pre>\n\n```rust\nlet a = 1 < 2;\n```\n";
+        let highlight = |code: &str, lang: Option<&str>| {
+            assert_eq!(code, "let a = 1 < 2;");
+            assert_eq!(lang, Some("rust"));
+            vec![SyntaxSpan {
+                range: 0..3,
+                color: Some(0x11_22_33),
+                bold: false,
+                italic: false,
+            }]
+        };
+        let html = markdown_body_html(source, &highlight).body;
+        assert!(html.contains("<pre><code>raw</code></pre>"));
+        assert!(html.contains(
+            "<code class=\"language-rust\"><span style=\"color:#112233;\">let</span> a = 1 &lt; 2;\n</code>"
+        ));
+    }
+
+    /// The signature of a PNG file, enough to pass for an image
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    #[test]
+    fn test_markdown_body_html_collects_local_images() {
+        let source = "![a](file:///doc/a.png) ![b][logo] ![c](https://example.com/c.png)\n\n\
+                      <img src=\"file:///etc/raw.png\">\n\n[logo]: file:///doc/logo%20one.svg\n";
+        let images = markdown_body_html(source, &no_highlight).images;
+        assert_eq!(
+            images,
+            HashSet::from([
+                PathBuf::from("/doc/a.png"),
+                PathBuf::from("/doc/logo one.svg")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_localize_images_inlines_and_copies() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let image = dir.path().join("my image.png");
+        fs::write(&image, PNG).expect("write image");
+        let url = Url::from_file_path(&image).expect("file url");
+        let missing_path = dir.path().join("missing.png");
+        let missing = Url::from_file_path(&missing_path).expect("file url");
+        let html = format!(
+            "<img src=\"{url}\"><img src=\"https://example.com/a.png\"><img src='{missing}'>"
+        );
+        let image_files = HashSet::from([image, missing_path.clone()]);
+        let output = dir.path().join("out").join("doc.html");
+
+        let inlined = localize_images(&html, &image_files, HtmlImages::Inline, &output);
+        assert!(
+            inlined
+                .html
+                .starts_with("<img src=\"data:image/png;base64,iVBORw0KGgo=\">")
+        );
+        assert!(inlined.html.contains("https://example.com/a.png"));
+        assert_eq!(inlined.missing_images, vec![missing_path]);
+
+        let copied = localize_images(&html, &image_files, HtmlImages::CopyFiles, &output);
+        assert!(
+            copied
+                .html
+                .starts_with("<img src=\"doc_files/my%20image.png\">")
+        );
+        assert!(dir.path().join("out/doc_files/my image.png").is_file());
+        assert_eq!(copied.missing_images.len(), 1);
+    }
+
+    #[test]
+    fn test_localize_images_only_reads_images_of_the_source() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let secret = dir.path().join("secret.png");
+        fs::write(&secret, PNG).expect("write image");
+        let text = dir.path().join("notes.png");
+        fs::write(&text, "not an image").expect("write text");
+        let config = dir.path().join("config.toml");
+        fs::write(&config, "token = 1").expect("write config");
+        let html = [&secret, &text, &config]
+            .map(|path| {
+                let url = Url::from_file_path(path).expect("file url");
+                format!("<img src=\"{url}\">")
+            })
+            .concat();
+        let image_files = HashSet::from([text.clone(), config.clone()]);
+
+        let localized = localize_images(
+            &html,
+            &image_files,
+            HtmlImages::Inline,
+            &dir.path().join("doc.html"),
+        );
+        assert_eq!(localized.html, html);
+        assert_eq!(localized.missing_images, vec![text, config]);
+    }
+
+    #[test]
+    fn test_standalone_html_escapes_title() {
+        let html = standalone_html("a <b>", "body {}\n", "<p>x</p>\n");
+        assert!(html.contains("<title>a &lt;b&gt;</title>"));
+        assert!(html.contains("<main>\n<p>x</p>\n</main>"));
+    }
+}
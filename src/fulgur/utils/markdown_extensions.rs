@@ -0,0 +1,500 @@
+//! Front matter, footnotes and task lists, which the Markdown preview renders itself.
+
+use crate::fulgur::utils::markdown_links::heading_slug;
+use markdown::mdast::{Node, Paragraph};
+use std::{collections::HashMap, ops::Range};
+
+/// Prefix of the links task-list checkboxes become in the preview, followed by
+/// the zero-based source line of their item
+pub const TASK_LINK_PREFIX: &str = "fulgur-task:";
+
+/// The YAML front matter at the top of a Markdown document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrontMatter {
+    pub lines: Range<usize>, // Zero-based source lines, fences included, end excluded
+    pub fields: Vec<(String, String)>, // The top-level keys and their values, in document order
+}
+
+/// The task items and footnotes found in a Markdown document
+#[derive(Default)]
+struct Extensions {
+    tasks: Vec<(usize, bool)>, // Offset of each task item and whether it is checked
+    references: Vec<(String, Range<usize>)>, // Identifier and range of each footnote reference
+    definitions: Vec<(String, Range<usize>, Option<usize>)>, // Identifier, range and end of the last paragraph of each footnote
+}
+
+/// Find the YAML front matter at the top of a Markdown document
+///
+/// ### Description
+/// The front matter starts on the first line with `---` and ends with `---` or
+/// `...`. Between them must be a YAML mapping, or nothing, so that a document
+/// opening with a thematic break is not mistaken for one.
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+///
+/// ### Returns
+/// - `Some(FrontMatter)`: The lines and fields of the front matter
+/// - `None`: The document has no front matter
+#[must_use]
+pub fn front_matter(source: &str) -> Option<FrontMatter> {
+    let mut lines = source.lines();
+    if lines.next()?.trim_end() != "---" {
+        return None;
+    }
+    let mut yaml = String::new();
+    for (index, line) in lines.enumerate() {
+        if matches!(line.trim_end(), "---" | "...") {
+            let fields = match serde_yaml_ng::from_str::<serde_yaml_ng::Value>(&yaml).ok()? {
+                serde_yaml_ng::Value::Null => Vec::new(),
+                serde_yaml_ng::Value::Mapping(mapping) => mapping
+                    .iter()
+                    .map(|(key, value)| (yaml_text(key), yaml_text(value)))
+                    .collect(),
+                _ => return None,
+            };
+            return Some(FrontMatter {
+                lines: 0..index + 2,
+                fields,
+            });
+        }
+        yaml.push_str(line);
+        yaml.push('\n');
+    }
+    None
+}
+
+/// Show a YAML value as text
+///
+/// ### Arguments
+/// - `value`: The YAML value
+///
+/// ### Returns
+/// - `String`: Scalars as written, lists of scalars separated by commas and anything else as YAML
+fn yaml_text(value: &serde_yaml_ng::Value) -> String {
+    use serde_yaml_ng::Value;
+
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        Value::String(value) => value.clone(),
+        Value::Sequence(items)
+            if items
+                .iter()
+                .all(|item| !matches!(item, Value::Sequence(_) | Value::Mapping(_))) =>
+        {
+            items.iter().map(yaml_text).collect::<Vec<_>>().join(", ")
+        }
+        value => serde_yaml_ng::to_string(value)
+            .map(|text| text.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Blank out the front matter of a document
+///
+/// ### Description
+/// Every byte of the front matter but its line breaks becomes a space, so that
+/// offsets and lines of the rest of the document are unchanged.
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+/// - `front_matter`: The front matter of the document
+///
+/// ### Returns
+/// - `String`: The document without its front matter
+#[must_use]
+pub fn without_front_matter(source: &str, front_matter: Option<&FrontMatter>) -> String {
+    let Some(front_matter) = front_matter else {
+        return source.to_string();
+    };
+    source
+        .split_inclusive('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            if front_matter.lines.contains(&index) {
+                let content = line.trim_end_matches(['\r', '\n']);
+                format!("{}{}", " ".repeat(content.len()), &line[content.len()..])
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Parse a document and collect its task items and footnotes
+///
+/// ### Arguments
+/// - `source`: The Markdown document, without front matter
+///
+/// ### Returns
+/// - `Extensions`: The task items and footnotes, in document order
+fn find_extensions(source: &str) -> Extensions {
+    let ast = markdown::to_mdast(source, &markdown::ParseOptions::gfm()).ok();
+    extensions_of(ast.as_ref())
+}
+
+/// Collect the task items and footnotes of a parsed document
+///
+/// ### Arguments
+/// - `ast`: The tree of the document, `None` when it failed to parse
+///
+/// ### Returns
+/// - `Extensions`: The task items and footnotes, in document order
+fn extensions_of(ast: Option<&Node>) -> Extensions {
+    let mut found = Extensions::default();
+    if let Some(ast) = ast {
+        collect_extensions(ast, &mut found);
+    }
+    found
+}
+
+/// Walk the AST, collecting task items and footnotes
+///
+/// ### Arguments
+/// - `node`: The current AST node being visited
+/// - `found`: Accumulator for the task items and footnotes
+fn collect_extensions(node: &Node, found: &mut Extensions) {
+    if let Some(position) = node.position() {
+        let range = position.start.offset..position.end.offset;
+        match node {
+            Node::ListItem(item) => {
+                if let Some(checked) = item.checked {
+                    found.tasks.push((range.start, checked));
+                }
+            }
+            Node::FootnoteReference(reference) => {
+                found.references.push((reference.identifier.clone(), range));
+            }
+            Node::FootnoteDefinition(definition) => {
+                let paragraph_end = match definition.children.last() {
+                    Some(Node::Paragraph(paragraph)) => paragraph
+                        .position
+                        .as_ref()
+                        .map(|position| position.end.offset),
+                    _ => None,
+                };
+                found
+                    .definitions
+                    .push((definition.identifier.clone(), range, paragraph_end));
+            }
+            _ => {}
+        }
+    }
+    for child in node.children().map(Vec::as_slice).unwrap_or_default() {
+        collect_extensions(child, found);
+    }
+}
+
+/// Find the `[ ]` or `[x]` marker of a task item
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+/// - `item_start`: The offset of the list marker of the item
+///
+/// ### Returns
+/// - `Some(Range<usize>)`: The source range of the checkbox marker
+/// - `None`: The item has no checkbox marker
+fn task_marker(source: &str, item_start: usize) -> Option<Range<usize>> {
+    let rest = source.get(item_start..)?.trim_start_matches([' ', '\t']);
+    let after_bullet = if let Some(after) = rest.strip_prefix(['-', '*', '+']) {
+        after
+    } else {
+        let after = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+        if after.len() == rest.len() {
+            return None;
+        }
+        after.strip_prefix(['.', ')'])?
+    };
+    let marker = after_bullet.trim_start_matches([' ', '\t']);
+    let start = source.len() - marker.len();
+    matches!(marker.get(..3)?, "[ ]" | "[x]" | "[X]").then_some(start..start + 3)
+}
+
+/// Get the zero-based line of an offset
+///
+/// ### Arguments
+/// - `source`: The text
+/// - `offset`: The byte offset
+///
+/// ### Returns
+/// - `usize`: The number of line breaks before the offset
+fn line_of(source: &str, offset: usize) -> usize {
+    source.as_bytes()[..offset.min(source.len())]
+        .iter()
+        .filter(|&&byte| byte == b'\n')
+        .count()
+}
+
+/// Number the footnotes of a document
+///
+/// ### Description
+/// Footnotes are numbered in the order of their first reference, as on GitHub,
+/// and footnotes nothing refers to come last.
+///
+/// ### Arguments
+/// - `found`: The footnotes of the document
+///
+/// ### Returns
+/// - `HashMap<String, usize>`: The number of each footnote identifier
+fn footnote_numbers(found: &Extensions) -> HashMap<String, usize> {
+    let mut numbers = HashMap::new();
+    let identifiers = found
+        .references
+        .iter()
+        .map(|(identifier, _)| identifier)
+        .chain(
+            found
+                .definitions
+                .iter()
+                .map(|(identifier, _, _)| identifier),
+        );
+    for identifier in identifiers {
+        let next = numbers.len() + 1;
+        numbers.entry(identifier.clone()).or_insert(next);
+    }
+    numbers
+}
+
+/// Split a document into its front matter and the Markdown the preview renders
+///
+/// ### Description
+/// The Markdown keeps the lines of the document, so that its blocks map back to
+/// the source:
+/// - The front matter is blanked out
+/// - Task-list checkboxes become `fulgur-task:` links to toggle them
+/// - Footnote references become links to their footnote, and each footnote
+///   starts with its number and ends with a back-link to its first reference
+///
+/// The tree of the document is returned so that the preview splits it into
+/// blocks without parsing it again. Its positions hold for the lines of the
+/// Markdown to render, and the footnotes it rewrote are paragraphs in it, shown
+/// in place rather than shared by every block.
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+///
+/// ### Returns
+/// - `(Option<FrontMatter>, String, Option<Node>)`: The front matter, the Markdown
+///   to render and its tree; `None` when the document failed to parse
+#[must_use]
+pub fn preview_source(source: &str) -> (Option<FrontMatter>, String, Option<Node>) {
+    let front_matter = front_matter(source);
+    let text = without_front_matter(source, front_matter.as_ref());
+    let mut ast = markdown::to_mdast(&text, &markdown::ParseOptions::gfm()).ok();
+    let found = extensions_of(ast.as_ref());
+    let mut rewritten = Vec::new();
+    let numbers = footnote_numbers(&found);
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    for &(offset, checked) in &found.tasks {
+        if let Some(marker) = task_marker(&text, offset) {
+            let checkbox = if checked { "☑" } else { "☐" };
+            let line = line_of(&text, offset);
+            edits.push((marker, format!("[{checkbox}]({TASK_LINK_PREFIX}{line})")));
+        }
+    }
+    for (identifier, range) in &found.references {
+        let number = numbers.get(identifier).copied().unwrap_or_default();
+        let slug = heading_slug(identifier);
+        edits.push((range.clone(), format!("[\\[{number}\\]](#fn-{slug})")));
+    }
+    for (identifier, range, paragraph_end) in &found.definitions {
+        let Some(label_end) = text[range.clone()].find("]:") else {
+            continue;
+        };
+        let number = numbers.get(identifier).copied().unwrap_or_default();
+        let slug = heading_slug(identifier);
+        let referenced = found
+            .references
+            .iter()
+            .any(|(other, _)| other == identifier);
+        let back_link = format!("[↩](#fnref-{slug})");
+        let label = range.start..range.start + label_end + 2;
+        rewritten.push(range.start);
+        match paragraph_end {
+            Some(end) if referenced => {
+                edits.push((label, format!("\\[{number}\\]")));
+                edits.push((*end..*end, format!(" {back_link}")));
+            }
+            _ if referenced => edits.push((label, format!("\\[{number}\\] {back_link}"))),
+            _ => edits.push((label, format!("\\[{number}\\]"))),
+        }
+        // Later paragraphs of a footnote are indented, which would make them code
+        let mut line_start = text[range.clone()]
+            .find('\n')
+            .map(|newline| range.start + newline + 1);
+        while let Some(start) = line_start.filter(|&start| start < range.end) {
+            let indent = text[start..]
+                .bytes()
+                .take(4)
+                .take_while(|&byte| byte == b' ')
+                .count();
+            if indent > 0 {
+                edits.push((start..start + indent, String::new()));
+            }
+            line_start = text[start..].find('\n').map(|newline| start + newline + 1);
+        }
+    }
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse((range.start, range.end)));
+    let mut rendered = text;
+    for (range, replacement) in edits {
+        rendered.replace_range(range, &replacement);
+    }
+    if let Some(Node::Root(root)) = &mut ast {
+        for child in &mut root.children {
+            let Node::FootnoteDefinition(definition) = child else {
+                continue;
+            };
+            let position = definition.position.clone();
+            if position
+                .as_ref()
+                .is_some_and(|position| rewritten.contains(&position.start.offset))
+            {
+                *child = Node::Paragraph(Paragraph {
+                    children: Vec::new(),
+                    position,
+                });
+            }
+        }
+    }
+    (front_matter, rendered, ast)
+}
+
+/// List the anchors the preview gives to footnotes
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+///
+/// ### Returns
+/// - `Vec<(String, usize)>`: `fn-` anchors at each footnote and `fnref-` anchors at
+///   its first reference, with their zero-based lines
+#[must_use]
+pub fn footnote_anchors(source: &str) -> Vec<(String, usize)> {
+    let text = without_front_matter(source, front_matter(source).as_ref());
+    let found = find_extensions(&text);
+    let mut anchors: Vec<(String, usize)> = Vec::new();
+    for (identifier, range, _) in &found.definitions {
+        anchors.push((
+            format!("fn-{}", heading_slug(identifier)),
+            line_of(&text, range.start),
+        ));
+    }
+    for (identifier, range) in &found.references {
+        let anchor = format!("fnref-{}", heading_slug(identifier));
+        if !anchors.iter().any(|(other, _)| *other == anchor) {
+            anchors.push((anchor, line_of(&text, range.start)));
+        }
+    }
+    anchors
+}
+
+/// Check or uncheck the task item on a line
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+/// - `line`: The zero-based line of the task item
+///
+/// ### Returns
+/// - `Some(String)`: The document with the checkbox of the item toggled
+/// - `None`: There is no task item on the line
+#[must_use]
+pub fn toggle_task(source: &str, line: usize) -> Option<String> {
+    let text = without_front_matter(source, front_matter(source).as_ref());
+    let found = find_extensions(&text);
+    let (marker, checked) = found.tasks.iter().find_map(|&(offset, checked)| {
+        (line_of(&text, offset) == line)
+            .then(|| task_marker(&text, offset))
+            .flatten()
+            .map(|marker| (marker, checked))
+    })?;
+    let mut toggled = source.to_string();
+    toggled.replace_range(marker, if checked { "[ ]" } else { "[x]" });
+    Some(toggled)
+}
+
+/// Get the line a task checkbox link of the preview toggles
+///
+/// ### Arguments
+/// - `url`: The target of a clicked link
+///
+/// ### Returns
+/// - `Some(usize)`: The zero-based line of the task item
+/// - `None`: The link is not a task checkbox
+#[must_use]
+pub fn task_link_line(url: &str) -> Option<usize> {
+    url.strip_prefix(TASK_LINK_PREFIX)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fulgur::utils::markdown_blocks::split_markdown_blocks;
+
+    #[test]
+    fn test_front_matter_fields() {
+        let source = "---\ntitle: Guide\ntags: [docs, markdown]\ndraft: false\n---\n# Guide\n";
+        let front_matter = front_matter(source).expect("front matter");
+        assert_eq!(front_matter.lines, 0..5);
+        assert_eq!(
+            front_matter.fields,
+            vec![
+                ("title".to_string(), "Guide".to_string()),
+                ("tags".to_string(), "docs, markdown".to_string()),
+                ("draft".to_string(), "false".to_string()),
+            ]
+        );
+        assert!(super::front_matter("---\n\nSome text\n---\n").is_none());
+        assert!(super::front_matter("# Title\n---\na: b\n---\n").is_none());
+    }
+
+    #[test]
+    fn test_preview_source_keeps_lines() {
+        let source = "---\ntitle: Guide\n---\n- [ ] Write\n- [x] Read\n";
+        let (front_matter, text, _) = preview_source(source);
+        assert!(front_matter.is_some());
+        assert_eq!(
+            text,
+            "   \n            \n   \n- [☐](fulgur-task:3) Write\n- [☑](fulgur-task:4) Read\n"
+        );
+        assert_eq!(task_link_line("fulgur-task:3"), Some(3));
+        assert_eq!(task_link_line("https://example.com"), None);
+    }
+
+    #[test]
+    fn test_preview_source_links_footnotes() {
+        let source = "See[^note] and[^b].\n\n[^b]: Bee.\n\n[^note]: A note\n    over lines.\n";
+        let (_, text, ast) = preview_source(source);
+        assert_eq!(
+            text,
+            "See[\\[1\\]](#fn-note) and[\\[2\\]](#fn-b).\n\n\\[2\\] Bee. [↩](#fnref-b)\n\n\\[1\\] A note\nover lines. [↩](#fnref-note)\n"
+        );
+        let blocks = split_markdown_blocks(&text, ast.as_ref());
+        let lines: Vec<_> = blocks.iter().map(|block| block.lines.clone()).collect();
+        assert_eq!(lines, vec![0..1, 2..3, 4..6]);
+        assert_eq!(blocks[1].text, "\\[2\\] Bee. [↩](#fnref-b)");
+        assert_eq!(
+            footnote_anchors(source),
+            vec![
+                ("fn-b".to_string(), 2),
+                ("fn-note".to_string(), 4),
+                ("fnref-note".to_string(), 0),
+                ("fnref-b".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toggle_task() {
+        let source = "Intro\n\n1. [ ] One\n   - [X] Nested\n";
+        assert_eq!(
+            toggle_task(source, 2).as_deref(),
+            Some("Intro\n\n1. [x] One\n   - [X] Nested\n")
+        );
+        assert_eq!(
+            toggle_task(source, 3).as_deref(),
+            Some("Intro\n\n1. [ ] One\n   - [ ] Nested\n")
+        );
+        assert_eq!(toggle_task(source, 0), None);
+    }
+}
@@ -0,0 +1,513 @@
+//! Resolve the links of a Markdown document so the preview can follow them.
+
+use crate::fulgur::languages::supported_languages::{SupportedLanguage, language_from_filename};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// Number of locations the link history remembers in each direction
+const MAX_HISTORY: usize = 100;
+
+/// Where a link of a Markdown document leads
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkTarget {
+    External(String), // A web or mail address, opened by the system
+    Anchor(String),   // A heading of the same document, empty for its top
+    File {
+        path: PathBuf,          // The local file, resolved against the document directory
+        anchor: Option<String>, // A heading of that file
+    },
+}
+
+/// A link of a Markdown document whose target does not exist
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BrokenLink {
+    pub line: usize,             // Zero-based line of the link
+    pub columns: (usize, usize), // Character columns the link spans on that line
+    pub url: String,             // The target as written
+    pub problem: String,         // Why the target cannot be found
+}
+
+/// Resolve the target of a Markdown link
+///
+/// ### Description
+/// `file://` URLs and paths are local files, relative paths being resolved
+/// against the directory of the document. A `#fragment` names a heading, of
+/// the same document when it is the whole target. Percent-encoded characters
+/// and queries are handled the way a browser would.
+///
+/// ### Arguments
+/// - `url`: The link target, as written in the document
+/// - `base_dir`: The directory of the document, `None` when it is unsaved
+///
+/// ### Returns
+/// - `Some(LinkTarget)`: Where the link leads
+/// - `None`: The target is empty, or relative in an unsaved document
+#[must_use]
+pub fn resolve_link(url: &str, base_dir: Option<&Path>) -> Option<LinkTarget> {
+    let url = url.trim();
+    if url.is_empty() {
+        return None;
+    }
+    if let Some(anchor) = url.strip_prefix('#') {
+        return Some(LinkTarget::Anchor(percent_decode(anchor)));
+    }
+    if url.starts_with("//") {
+        return Some(LinkTarget::External(format!("https:{url}")));
+    }
+    // A single letter scheme is a Windows drive, not a URL
+    if let Ok(parsed) = Url::parse(url)
+        && parsed.scheme().len() > 1
+    {
+        if parsed.scheme() != "file" {
+            return Some(LinkTarget::External(url.to_string()));
+        }
+        return Some(LinkTarget::File {
+            path: parsed.to_file_path().ok()?,
+            anchor: parsed
+                .fragment()
+                .map(percent_decode)
+                .filter(|anchor| !anchor.is_empty()),
+        });
+    }
+    let (location, anchor) = match url.split_once('#') {
+        Some((location, anchor)) => (location, Some(percent_decode(anchor))),
+        None => (url, None),
+    };
+    let location = location.split_once('?').map_or(location, |(path, _)| path);
+    if location.is_empty() {
+        return anchor.map(LinkTarget::Anchor);
+    }
+    let relative = PathBuf::from(percent_decode(location));
+    let path = if relative.is_absolute() {
+        relative
+    } else {
+        base_dir?.join(relative)
+    };
+    Some(LinkTarget::File {
+        path,
+        anchor: anchor.filter(|anchor| !anchor.is_empty()),
+    })
+}
+
+/// Decode the percent-encoded characters of a link, keeping it as is when invalid
+///
+/// ### Arguments
+/// - `text`: The encoded text
+///
+/// ### Returns
+/// - `String`: The decoded text
+fn percent_decode(text: &str) -> String {
+    urlencoding::decode(text).map_or_else(|_| text.to_string(), Cow::into_owned)
+}
+
+/// Build the anchor of a heading, the way GitHub does
+///
+/// ### Arguments
+/// - `text`: The text of the heading
+///
+/// ### Returns
+/// - `String`: The lowercased text, with whitespace turned into hyphens and
+///   punctuation other than hyphens and underscores removed
+#[must_use]
+pub fn heading_slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c)
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// List the anchors of the headings of a Markdown document
+///
+/// ### Description
+/// A repeated heading gets a `-1`, `-2`... suffix, as on GitHub.
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+///
+/// ### Returns
+/// - `Vec<(String, usize)>`: The anchor and zero-based line of each heading, in document order
+#[must_use]
+pub fn heading_anchors(source: &str) -> Vec<(String, usize)> {
+    let Ok(ast) = markdown::to_mdast(source, &markdown::ParseOptions::gfm()) else {
+        return Vec::new();
+    };
+    let mut headings = Vec::new();
+    collect_headings(&ast, &mut headings);
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    headings
+        .into_iter()
+        .map(|(slug, line)| {
+            let count = seen.entry(slug.clone()).or_insert(0);
+            let anchor = if *count == 0 {
+                slug
+            } else {
+                format!("{slug}-{count}")
+            };
+            *count += 1;
+            (anchor, line)
+        })
+        .collect()
+}
+
+/// Walk the AST, collecting the slug and line of every heading
+///
+/// ### Arguments
+/// - `node`: The current AST node being visited
+/// - `headings`: Accumulator for the slugs and zero-based lines
+fn collect_headings(node: &markdown::mdast::Node, headings: &mut Vec<(String, usize)>) {
+    use markdown::mdast::Node;
+
+    if let Node::Heading(heading) = node {
+        if let Some(position) = &heading.position {
+            let mut text = String::new();
+            for child in &heading.children {
+                collect_text(child, &mut text);
+            }
+            headings.push((heading_slug(&text), position.start.line - 1));
+        }
+        return;
+    }
+    if let Some(children) = node.children() {
+        for child in children {
+            collect_headings(child, headings);
+        }
+    }
+}
+
+/// Append the plain text of an inline node and its children
+///
+/// ### Arguments
+/// - `node`: The inline node
+/// - `text`: Accumulator for the text
+pub(crate) fn collect_text(node: &markdown::mdast::Node, text: &mut String) {
+    use markdown::mdast::Node;
+
+    match node {
+        Node::Text(inline) => text.push_str(&inline.value),
+        Node::InlineCode(code) => text.push_str(&code.value),
+        _ => {
+            if let Some(children) = node.children() {
+                for child in children {
+                    collect_text(child, text);
+                }
+            }
+        }
+    }
+}
+
+/// Find the line of the heading an anchor names
+///
+/// ### Arguments
+/// - `anchors`: The anchors of the document, from `heading_anchors`
+/// - `anchor`: The anchor, without `#`; empty for the top of the document
+///
+/// ### Returns
+/// - `Some(usize)`: The zero-based line of the heading
+/// - `None`: No heading has this anchor
+#[must_use]
+pub fn anchor_line(anchors: &[(String, usize)], anchor: &str) -> Option<usize> {
+    if anchor.is_empty() {
+        return Some(0);
+    }
+    let anchor = anchor.to_lowercase();
+    anchors
+        .iter()
+        .find(|(candidate, _)| *candidate == anchor)
+        .map(|(_, line)| *line)
+}
+
+/// Check whether a file holds Markdown, from its name
+///
+/// ### Arguments
+/// - `path`: The path of the file
+///
+/// ### Returns
+/// - `bool`: True if the file name has a Markdown extension
+#[must_use]
+pub fn is_markdown_path(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| language_from_filename(name) == SupportedLanguage::Markdown)
+}
+
+/// Find the links and link definitions of a document whose target is missing
+///
+/// ### Description
+/// Web addresses are not checked. Local files must exist, and a heading of a
+/// Markdown file must exist in it. Relative links of an unsaved document
+/// cannot be resolved and are not reported.
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+/// - `base_dir`: The directory of the document, `None` when it is unsaved
+///
+/// ### Returns
+/// - `Vec<BrokenLink>`: The broken links, in document order
+#[must_use]
+pub fn find_broken_links(source: &str, base_dir: Option<&Path>) -> Vec<BrokenLink> {
+    let Ok(ast) = markdown::to_mdast(source, &markdown::ParseOptions::gfm()) else {
+        return Vec::new();
+    };
+    let mut links = Vec::new();
+    collect_links(&ast, &mut links);
+    let anchors = heading_anchors(source);
+    links
+        .into_iter()
+        .filter_map(|(url, start, end)| {
+            let problem = match resolve_link(&url, base_dir)? {
+                LinkTarget::External(_) => return None,
+                LinkTarget::Anchor(anchor) => anchor_line(&anchors, &anchor)
+                    .is_none()
+                    .then(|| format!("No heading matches #{anchor}"))?,
+                LinkTarget::File { path, anchor } => file_link_problem(&path, anchor.as_deref())?,
+            };
+            let line_start = source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+            let line_end = source[start..]
+                .find('\n')
+                .map_or(source.len(), |newline| start + newline);
+            let first = source[line_start..start].chars().count();
+            let last = first + source[start..end.min(line_end)].chars().count();
+            Some(BrokenLink {
+                line: source[..start].matches('\n').count(),
+                columns: (first, last.max(first + 1)),
+                url,
+                problem,
+            })
+        })
+        .collect()
+}
+
+/// Walk the AST, collecting the target and byte range of links and link definitions
+///
+/// ### Arguments
+/// - `node`: The current AST node being visited
+/// - `links`: Accumulator for the targets and their start and end offsets
+fn collect_links(node: &markdown::mdast::Node, links: &mut Vec<(String, usize, usize)>) {
+    use markdown::mdast::Node;
+
+    let link = match node {
+        Node::Link(link) => Some((&link.url, &link.position)),
+        Node::Definition(definition) => Some((&definition.url, &definition.position)),
+        _ => None,
+    };
+    if let Some((url, Some(position))) = link {
+        links.push((url.clone(), position.start.offset, position.end.offset));
+    }
+    if let Some(children) = node.children() {
+        for child in children {
+            collect_links(child, links);
+        }
+    }
+}
+
+/// Describe what is missing for a link to a local file
+///
+/// ### Arguments
+/// - `path`: The linked file
+/// - `anchor`: The linked heading of the file, if any
+///
+/// ### Returns
+/// - `Some(String)`: The file or the heading does not exist
+/// - `None`: The target exists, or its heading cannot be checked
+fn file_link_problem(path: &Path, anchor: Option<&str>) -> Option<String> {
+    if !path.exists() {
+        return Some(format!("No file at {}", path.display()));
+    }
+    let anchor = anchor.filter(|_| is_markdown_path(path))?;
+    let target = std::fs::read_to_string(path).ok()?;
+    anchor_line(&heading_anchors(&target), anchor)
+        .is_none()
+        .then(|| {
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().to_string(),
+            );
+            format!("No heading matches #{anchor} in {name}")
+        })
+}
+
+/// The locations left by following links, to go back and forward through them
+#[derive(Debug)]
+pub struct LinkHistory<T> {
+    back: Vec<T>,
+    forward: Vec<T>,
+}
+
+impl<T> LinkHistory<T> {
+    /// Create an empty history
+    ///
+    /// ### Returns
+    /// - `LinkHistory<T>`: A history without locations
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            back: Vec::new(),
+            forward: Vec::new(),
+        }
+    }
+
+    /// Remember the location a link was followed from
+    ///
+    /// ### Arguments
+    /// - `from`: The location before following the link
+    pub fn visit(&mut self, from: T) {
+        if self.back.len() == MAX_HISTORY {
+            self.back.remove(0);
+        }
+        self.back.push(from);
+        self.forward.clear();
+    }
+
+    /// Go back to the location the last link was followed from
+    ///
+    /// ### Arguments
+    /// - `current`: The current location, to come back to by going forward
+    ///
+    /// ### Returns
+    /// - `Some(T)`: The location to show
+    /// - `None`: There is nothing to go back to
+    pub fn back(&mut self, current: T) -> Option<T> {
+        let location = self.back.pop()?;
+        self.forward.push(current);
+        Some(location)
+    }
+
+    /// Go forward to the location left by going back
+    ///
+    /// ### Arguments
+    /// - `current`: The current location, to come back to by going back
+    ///
+    /// ### Returns
+    /// - `Some(T)`: The location to show
+    /// - `None`: There is nothing to go forward to
+    pub fn forward(&mut self, current: T) -> Option<T> {
+        let location = self.forward.pop()?;
+        self.back.push(current);
+        Some(location)
+    }
+}
+
+impl<T> Default for LinkHistory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn base() -> PathBuf {
+        if cfg!(windows) {
+            PathBuf::from(r"C:\docs")
+        } else {
+            PathBuf::from("/docs")
+        }
+    }
+
+    #[test]
+    fn test_resolve_link_relative_paths_and_anchors() {
+        assert_eq!(
+            resolve_link("setup/install%20guide.md?raw=1#step-2", Some(&base())),
+            Some(LinkTarget::File {
+                path: base().join("setup/install guide.md"),
+                anchor: Some("step-2".to_string()),
+            })
+        );
+        assert_eq!(
+            resolve_link("#usage", Some(&base())),
+            Some(LinkTarget::Anchor("usage".to_string()))
+        );
+        assert_eq!(resolve_link("notes.md", None), None);
+        assert_eq!(resolve_link("  ", Some(&base())), None);
+    }
+
+    #[test]
+    fn test_resolve_link_urls() {
+        assert_eq!(
+            resolve_link("https://example.com/a#b", None),
+            Some(LinkTarget::External("https://example.com/a#b".to_string()))
+        );
+        assert_eq!(
+            resolve_link("mailto:team@example.com", None),
+            Some(LinkTarget::External("mailto:team@example.com".to_string()))
+        );
+        let file_url = Url::from_file_path(base().join("a.md")).unwrap();
+        assert_eq!(
+            resolve_link(&format!("{file_url}#top"), None),
+            Some(LinkTarget::File {
+                path: base().join("a.md"),
+                anchor: Some("top".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_heading_anchors_follow_github() {
+        let anchors =
+            heading_anchors("# Getting Started!\n\n## `cargo` & Rust\n\n## Notes\n\nNotes\n---\n");
+        assert_eq!(
+            anchors,
+            vec![
+                ("getting-started".to_string(), 0),
+                ("cargo--rust".to_string(), 2),
+                ("notes".to_string(), 4),
+                ("notes-1".to_string(), 6),
+            ]
+        );
+        assert_eq!(anchor_line(&anchors, "Notes-1"), Some(6));
+        assert_eq!(anchor_line(&anchors, ""), Some(0));
+        assert_eq!(anchor_line(&anchors, "missing"), None);
+    }
+
+    #[test]
+    fn test_find_broken_links() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        std::fs::write(dir.path().join("setup.md"), "# Install\n").expect("failed to write");
+        let source = "# Intro\n\n[ok](setup.md#install) [gone](missing.md)\n\
+                      [bad](setup.md#usage) [web](https://example.com) [self](#intro) [nope](#outro)\n\n\
+                      [ref]: other/missing.txt\n";
+        let broken = find_broken_links(source, Some(dir.path()));
+        let urls: Vec<_> = broken.iter().map(|link| link.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "missing.md",
+                "setup.md#usage",
+                "#outro",
+                "other/missing.txt"
+            ]
+        );
+        assert_eq!(broken[0].line, 2);
+        assert_eq!(broken[0].columns, (23, 41));
+        assert_eq!(broken[1].problem, "No heading matches #usage in setup.md");
+        assert!(find_broken_links("[a](missing.md)", None).is_empty());
+    }
+
+    #[test]
+    fn test_link_history_goes_back_and_forward() {
+        let mut history = LinkHistory::new();
+        history.visit(1);
+        history.visit(2);
+        assert_eq!(history.back(3), Some(2));
+        assert_eq!(history.back(2), Some(1));
+        assert_eq!(history.back(1), None);
+        assert_eq!(history.forward(1), Some(2));
+        history.visit(2);
+        assert_eq!(history.forward(4), None);
+    }
+}
@@ -0,0 +1,509 @@
+//! Lint rules for Markdown documents, with fixes for the mechanical ones.
+
+use crate::fulgur::{
+    settings::MarkdownLintSettings,
+    utils::{
+        markdown_extensions::{front_matter, without_front_matter},
+        markdown_links::collect_text,
+    },
+};
+use markdown::mdast::Node;
+use std::{collections::HashSet, ops::Range};
+
+/// A Markdown lint rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintRule {
+    HeadingIncrement,
+    ListMarkerStyle,
+    TrailingSpaces,
+    LineLength,
+    DuplicateHeading,
+    MissingAltText,
+}
+
+impl LintRule {
+    /// Get the code of the rule
+    ///
+    /// ### Returns
+    /// - `&'static str`: The code markdownlint gives to the same rule
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::HeadingIncrement => "MD001",
+            Self::ListMarkerStyle => "MD004",
+            Self::TrailingSpaces => "MD009",
+            Self::LineLength => "MD013",
+            Self::DuplicateHeading => "MD024",
+            Self::MissingAltText => "MD045",
+        }
+    }
+}
+
+/// A replacement that fixes a lint problem
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintFix {
+    pub label: String,       // Context menu label of the fix
+    pub range: Range<usize>, // Byte range to replace
+    pub original: String,    // The text of `range` when the problem was found
+    pub replacement: String,
+}
+
+/// A lint problem found in a Markdown document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintProblem {
+    pub rule: LintRule,
+    pub range: Range<usize>, // Byte range of the problem, within one line
+    pub line: u32,           // Zero-based line of the problem
+    pub columns: (u32, u32), // Character columns of the problem on its line
+    pub message: String,
+    pub fix: Option<LintFix>,
+}
+
+/// The parts of a document the lint rules look at
+#[derive(Default)]
+struct Found {
+    headings: Vec<(u8, String, Range<usize>)>, // Level, text and range of each heading
+    bullets: Vec<usize>,                       // Offset of each bullet list item
+    images_without_alt: Vec<Range<usize>>,
+    verbatim_lines: Vec<Range<usize>>, // Zero-based lines of code blocks, tables and HTML
+}
+
+/// The lines of a document and the problems found in it
+struct Linter<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+    problems: Vec<LintProblem>,
+}
+
+impl Linter<'_> {
+    /// Get the zero-based line of an offset
+    fn line_of(&self, offset: usize) -> usize {
+        self.line_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1)
+    }
+
+    /// Get the byte range of a line, without its line break
+    fn line_range(&self, line: usize) -> Range<usize> {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.text.len(), |next| next - 1);
+        let content = self.text[start..end].trim_end_matches('\r');
+        start..start + content.len()
+    }
+
+    /// Record a problem, cut to the line it starts on
+    fn report(
+        &mut self,
+        rule: LintRule,
+        range: Range<usize>,
+        message: String,
+        fix: Option<(String, String)>,
+    ) {
+        let line = self.line_of(range.start);
+        let line_range = self.line_range(line);
+        let range = range.start
+            ..range
+                .end
+                .clamp(range.start, line_range.end.max(range.start));
+        let start = self.text[line_range.start..range.start].chars().count();
+        let length = self.text[range.clone()].chars().count();
+        let fix = fix.map(|(label, replacement)| LintFix {
+            label,
+            range: range.clone(),
+            original: self.text[range.clone()].to_string(),
+            replacement,
+        });
+        self.problems.push(LintProblem {
+            rule,
+            range,
+            line: u32::try_from(line).unwrap_or(u32::MAX),
+            columns: (
+                u32::try_from(start).unwrap_or(u32::MAX),
+                u32::try_from(start + length).unwrap_or(u32::MAX),
+            ),
+            message,
+            fix,
+        });
+    }
+}
+
+/// Lint a Markdown document
+///
+/// ### Description
+/// The front matter of the document is not linted.
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+/// - `settings`: The rules to check
+///
+/// ### Returns
+/// - `Vec<LintProblem>`: The problems, in document order
+#[must_use]
+pub fn lint_markdown(source: &str, settings: &MarkdownLintSettings) -> Vec<LintProblem> {
+    if !settings.enabled {
+        return Vec::new();
+    }
+    let front_matter = front_matter(source);
+    let text = without_front_matter(source, front_matter.as_ref());
+    let Ok(ast) = markdown::to_mdast(&text, &markdown::ParseOptions::gfm()) else {
+        return Vec::new();
+    };
+    let mut found = Found::default();
+    collect_lint_nodes(&ast, &mut found);
+    let mut linter = Linter {
+        text: &text,
+        line_starts: std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect(),
+        problems: Vec::new(),
+    };
+    if settings.heading_increment {
+        check_heading_increment(&mut linter, &found);
+    }
+    if settings.duplicate_headings {
+        check_duplicate_headings(&mut linter, &found);
+    }
+    if settings.list_marker_style {
+        check_list_markers(&mut linter, &found);
+    }
+    if settings.missing_alt_text {
+        for range in &found.images_without_alt {
+            linter.report(
+                LintRule::MissingAltText,
+                range.clone(),
+                "Image has no alternative text".to_string(),
+                None,
+            );
+        }
+    }
+    let skipped = front_matter.map(|front_matter| front_matter.lines);
+    for line in 0..linter.line_starts.len() {
+        if skipped.as_ref().is_some_and(|lines| lines.contains(&line)) {
+            continue;
+        }
+        if settings.trailing_spaces {
+            check_trailing_spaces(&mut linter, line);
+        }
+        let verbatim = found
+            .verbatim_lines
+            .iter()
+            .any(|lines| lines.contains(&line));
+        if settings.line_length && !verbatim {
+            check_line_length(&mut linter, line, settings.max_line_length);
+        }
+    }
+    let mut problems = linter.problems;
+    problems.sort_by_key(|problem| (problem.range.start, problem.range.end));
+    problems
+}
+
+/// Walk the AST, collecting what the lint rules look at
+///
+/// ### Arguments
+/// - `node`: The current AST node being visited
+/// - `found`: Accumulator for the headings, bullets, images and verbatim lines
+fn collect_lint_nodes(node: &Node, found: &mut Found) {
+    let range = node
+        .position()
+        .map(|position| position.start.offset..position.end.offset);
+    let lines = node
+        .position()
+        .map(|position| position.start.line - 1..position.end.line);
+    match node {
+        Node::Heading(heading) => {
+            let mut text = String::new();
+            for child in &heading.children {
+                collect_text(child, &mut text);
+            }
+            if let Some(range) = range {
+                found
+                    .headings
+                    .push((heading.depth, text.trim().to_string(), range));
+            }
+        }
+        Node::List(list) if !list.ordered => {
+            found.bullets.extend(
+                list.children
+                    .iter()
+                    .filter_map(|item| Some(item.position()?.start.offset)),
+            );
+        }
+        Node::Image(image) if image.alt.trim().is_empty() => {
+            found.images_without_alt.extend(range);
+        }
+        Node::ImageReference(image) if image.alt.trim().is_empty() => {
+            found.images_without_alt.extend(range);
+        }
+        Node::Code(_) | Node::Math(_) | Node::Table(_) | Node::Html(_) => {
+            found.verbatim_lines.extend(lines);
+        }
+        _ => {}
+    }
+    for child in node.children().map(Vec::as_slice).unwrap_or_default() {
+        collect_lint_nodes(child, found);
+    }
+}
+
+/// Report headings that skip a level below the previous heading
+///
+/// ### Arguments
+/// - `linter`: The document and its problems
+/// - `found`: The headings of the document
+fn check_heading_increment(linter: &mut Linter, found: &Found) {
+    let mut previous: Option<u8> = None;
+    for (level, _, range) in &found.headings {
+        if let Some(previous) = previous
+            && *level > previous + 1
+        {
+            let expected = previous + 1;
+            let hashes = linter.text[range.clone()]
+                .trim_start()
+                .bytes()
+                .take_while(|&byte| byte == b'#')
+                .count();
+            let fix = if hashes > 0 {
+                let start = range.start + linter.text[range.clone()].len()
+                    - linter.text[range.clone()].trim_start().len();
+                Some((
+                    start..start + hashes,
+                    format!("Make It a Level {expected} Heading"),
+                    "#".repeat(usize::from(expected)),
+                ))
+            } else {
+                None
+            };
+            let message = format!("Heading level {level} follows level {previous}");
+            match fix {
+                Some((hashes, label, replacement)) => {
+                    linter.report(
+                        LintRule::HeadingIncrement,
+                        hashes,
+                        message,
+                        Some((label, replacement)),
+                    );
+                }
+                None => linter.report(LintRule::HeadingIncrement, range.clone(), message, None),
+            }
+        }
+        previous = Some(*level);
+    }
+}
+
+/// Report headings with the same text as an earlier heading
+///
+/// ### Arguments
+/// - `linter`: The document and its problems
+/// - `found`: The headings of the document
+fn check_duplicate_headings(linter: &mut Linter, found: &Found) {
+    let mut seen = HashSet::new();
+    for (_, text, range) in &found.headings {
+        if !text.is_empty() && !seen.insert(text.as_str()) {
+            linter.report(
+                LintRule::DuplicateHeading,
+                range.clone(),
+                format!("Duplicate heading \"{text}\""),
+                None,
+            );
+        }
+    }
+}
+
+/// Report bullets that differ from the first bullet of the document
+///
+/// ### Arguments
+/// - `linter`: The document and its problems
+/// - `found`: The bullet list items of the document
+fn check_list_markers(linter: &mut Linter, found: &Found) {
+    let markers: Vec<(usize, char)> = found
+        .bullets
+        .iter()
+        .filter_map(|&offset| {
+            let item = &linter.text[offset..];
+            let marker = item.trim_start_matches([' ', '\t']);
+            let start = offset + item.len() - marker.len();
+            marker
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '-' | '*' | '+'))
+                .map(|c| (start, c))
+        })
+        .collect();
+    let Some(&(_, expected)) = markers.first() else {
+        return;
+    };
+    for (start, marker) in markers {
+        if marker != expected {
+            linter.report(
+                LintRule::ListMarkerStyle,
+                start..start + 1,
+                format!("List marker '{marker}' differs from the '{expected}' used first"),
+                Some((
+                    format!("Use '{expected}' as List Marker"),
+                    expected.to_string(),
+                )),
+            );
+        }
+    }
+}
+
+/// Report spaces at the end of a line
+///
+/// ### Description
+/// Exactly two spaces after text are a hard line break and are kept.
+///
+/// ### Arguments
+/// - `linter`: The document and its problems
+/// - `line`: The zero-based line
+fn check_trailing_spaces(linter: &mut Linter, line: usize) {
+    let range = linter.line_range(line);
+    let content = &linter.text[range.clone()];
+    let trimmed = content.trim_end_matches([' ', '\t']);
+    let trailing = &content[trimmed.len()..];
+    if trailing.is_empty() || (trailing == "  " && !trimmed.trim().is_empty()) {
+        return;
+    }
+    linter.report(
+        LintRule::TrailingSpaces,
+        range.start + trimmed.len()..range.end,
+        "Trailing spaces".to_string(),
+        Some(("Remove Trailing Spaces".to_string(), String::new())),
+    );
+}
+
+/// Report a line longer than the limit
+///
+/// ### Description
+/// Lines that only run over the limit with a single word, such as a long
+/// address, are accepted since they cannot be wrapped.
+///
+/// ### Arguments
+/// - `linter`: The document and its problems
+/// - `line`: The zero-based line
+/// - `max_length`: The longest line accepted, in characters
+fn check_line_length(linter: &mut Linter, line: usize, max_length: usize) {
+    let range = linter.line_range(line);
+    let content = &linter.text[range.clone()];
+    let length = content.chars().count();
+    if length <= max_length {
+        return;
+    }
+    let Some((over, _)) = content.char_indices().nth(max_length) else {
+        return;
+    };
+    if !content[over..].contains(char::is_whitespace) {
+        return;
+    }
+    linter.report(
+        LintRule::LineLength,
+        range.start + over..range.end,
+        format!("Line is {length} characters long, over the limit of {max_length}"),
+        None,
+    );
+}
+
+/// Get the fixes of the problems at an offset
+///
+/// ### Arguments
+/// - `problems`: The problems of the document, in document order
+/// - `offset`: The byte offset, such as the cursor
+///
+/// ### Returns
+/// - `Vec<LintFix>`: The fixes of the problems touching the offset
+#[must_use]
+pub fn fixes_at_offset(problems: &[LintProblem], offset: usize) -> Vec<LintFix> {
+    problems
+        .iter()
+        .filter(|problem| problem.range.start <= offset && offset <= problem.range.end)
+        .filter_map(|problem| problem.fix.clone())
+        .collect()
+}
+
+/// Apply fixes to a document
+///
+/// ### Description
+/// Fixes whose text changed since the problem was found, or that overlap a
+/// fix applied before them, are skipped.
+///
+/// ### Arguments
+/// - `source`: The Markdown document
+/// - `fixes`: The fixes to apply
+///
+/// ### Returns
+/// - `String`: The fixed document
+#[must_use]
+pub fn apply_fixes(source: &str, fixes: &[LintFix]) -> String {
+    let mut fixes: Vec<&LintFix> = fixes.iter().collect();
+    fixes.sort_by_key(|fix| std::cmp::Reverse((fix.range.start, fix.range.end)));
+    let mut fixed = source.to_string();
+    let mut applied_start = usize::MAX;
+    for fix in fixes {
+        if fix.range.end > applied_start
+            || source.get(fix.range.clone()) != Some(fix.original.as_str())
+        {
+            continue;
+        }
+        fixed.replace_range(fix.range.clone(), &fix.replacement);
+        applied_start = fix.range.start;
+    }
+    fixed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(source: &str, settings: &MarkdownLintSettings) -> Vec<(LintRule, u32)> {
+        lint_markdown(source, settings)
+            .into_iter()
+            .map(|problem| (problem.rule, problem.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_markdown_reports_each_rule() {
+        let mut settings = MarkdownLintSettings::new();
+        settings.line_length = true;
+        settings.max_line_length = 20;
+        let source = "# Title\n\n### Skipped\n\n- one \n* two\n\n![](shot.png)\n\n# Title\n\nA line that is much too long to read\n";
+        assert_eq!(
+            rules(source, &settings),
+            vec![
+                (LintRule::HeadingIncrement, 2),
+                (LintRule::TrailingSpaces, 4),
+                (LintRule::ListMarkerStyle, 5),
+                (LintRule::MissingAltText, 7),
+                (LintRule::DuplicateHeading, 9),
+                (LintRule::LineLength, 11),
+            ]
+        );
+        settings.enabled = false;
+        assert!(rules(source, &settings).is_empty());
+    }
+
+    #[test]
+    fn test_lint_markdown_skips_accepted_cases() {
+        let mut settings = MarkdownLintSettings::new();
+        settings.line_length = true;
+        settings.max_line_length = 10;
+        let source = "---\ntitle: x   \n---\nHard  \nbreak\n\n```\nlong code line here\n```\n\nhttps://example.com/a/long/address\n";
+        assert!(rules(source, &settings).is_empty());
+    }
+
+    #[test]
+    fn test_fixes_apply_to_the_source() {
+        let source = "# Title\n\n### Skipped \n\n- one\n+ two\n";
+        let problems = lint_markdown(source, &MarkdownLintSettings::new());
+        let fixes: Vec<LintFix> = problems.iter().filter_map(|p| p.fix.clone()).collect();
+        assert_eq!(fixes.len(), 3);
+        assert_eq!(fixes[0].label, "Make It a Level 2 Heading");
+        assert_eq!(
+            apply_fixes(source, &fixes),
+            "# Title\n\n## Skipped\n\n- one\n- two\n"
+        );
+        assert_eq!(fixes_at_offset(&problems, 10).len(), 1);
+        assert!(fixes_at_offset(&problems, 0).is_empty());
+    }
+}
@@ -0,0 +1,606 @@
+//! Parse, edit and realign GitHub Flavored Markdown pipe tables.
+
+use crate::fulgur::{
+    files::csv_support::{CsvData, parse_csv},
+    utils::text_diff::TextEdit,
+};
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+/// Narrowest column, so that every delimiter cell can hold `:-:`
+const MIN_COLUMN_WIDTH: usize = 3;
+
+/// Alignment of a table column, set by the colons of its delimiter cell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColumnAlignment {
+    #[default]
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// An edit of the table around the cursor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableEdit {
+    Format,
+    InsertRowAbove,
+    InsertRowBelow,
+    DeleteRow,
+    InsertColumnBefore,
+    InsertColumnAfter,
+    DeleteColumn,
+    MoveColumnLeft,
+    MoveColumnRight,
+    Align(ColumnAlignment),
+    NextCell,     // Appends a row past the last cell
+    PreviousCell, // Stays on the first cell
+}
+
+/// The replacement of a table and the cell to select afterwards
+pub struct TableEditResult {
+    pub edit: TextEdit,
+    pub selection: Range<usize>, // Byte range in the text after the edit
+}
+
+/// A pipe table whose rows all have one cell per column
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MarkdownTable {
+    pub indent: String, // Leading whitespace of the table lines
+    pub header: Vec<String>,
+    pub alignments: Vec<ColumnAlignment>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// A rendered table and the byte range of each cell's content in it
+struct RenderedTable {
+    text: String,
+    cells: Vec<Vec<Range<usize>>>, // Row 0 is the header, then the body rows
+}
+
+/// A table found around the cursor
+struct LocatedTable {
+    range: Range<usize>, // Byte range of the table lines, without the final line break
+    table: MarkdownTable,
+    row: usize, // 0 for the header and the delimiter row, then the body rows
+    column: usize,
+}
+
+/// Get the byte offsets at which unescaped pipes stand in a line
+///
+/// ### Arguments
+/// - `line`: The line
+///
+/// ### Returns
+/// - `Vec<usize>`: The offsets of the pipes that separate cells
+fn pipe_offsets(line: &str) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut escaped = false;
+    for (offset, character) in line.char_indices() {
+        match character {
+            '\\' => escaped = !escaped,
+            '|' if !escaped => offsets.push(offset),
+            _ => escaped = false,
+        }
+    }
+    offsets
+}
+
+/// Check whether a line can be a row of a pipe table
+///
+/// ### Arguments
+/// - `line`: The line
+///
+/// ### Returns
+/// - `bool`: True if the line holds an unescaped pipe
+#[must_use]
+pub fn is_table_row(line: &str) -> bool {
+    !pipe_offsets(line).is_empty()
+}
+
+/// Split a table row into its trimmed cells
+///
+/// ### Arguments
+/// - `line`: The row
+///
+/// ### Returns
+/// - `Vec<String>`: The cells, without the outer pipes
+fn split_cells(line: &str) -> Vec<String> {
+    let row = line.trim();
+    let pipes = pipe_offsets(row);
+    let mut bounds = vec![0];
+    bounds.extend(pipes.iter().flat_map(|pipe| [*pipe, pipe + 1]));
+    bounds.push(row.len());
+    let mut cells: Vec<String> = bounds
+        .chunks(2)
+        .map(|bound| row[bound[0]..bound[1]].trim().to_string())
+        .collect();
+    // Outer pipes are optional and leave an empty cell outside the table
+    if pipes.first() == Some(&0) {
+        cells.remove(0);
+    }
+    if pipes.last() == Some(&(row.len() - 1)) {
+        cells.pop();
+    }
+    cells
+}
+
+/// Parse a delimiter row, e.g. `| --- | :-: |`
+///
+/// ### Arguments
+/// - `line`: The line
+///
+/// ### Returns
+/// - `Some(Vec<ColumnAlignment>)`: The alignment of each column
+/// - `None`: The line is not a delimiter row
+fn parse_delimiter_row(line: &str) -> Option<Vec<ColumnAlignment>> {
+    if !is_table_row(line) {
+        return None;
+    }
+    split_cells(line)
+        .iter()
+        .map(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.bytes().all(|byte| byte == b'-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => ColumnAlignment::Center,
+                (true, false) => ColumnAlignment::Left,
+                (false, true) => ColumnAlignment::Right,
+                (false, false) => ColumnAlignment::None,
+            })
+        })
+        .collect()
+}
+
+/// Check whether a line opens or closes a fenced code block
+///
+/// ### Arguments
+/// - `line`: The line
+///
+/// ### Returns
+/// - `bool`: True for a fence of backticks or tildes
+fn is_fence(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+/// Get the column of a byte offset in a table row
+///
+/// ### Arguments
+/// - `line`: The row
+/// - `offset`: The byte offset in the row
+///
+/// ### Returns
+/// - `usize`: The index of the cell holding the offset
+fn column_at(line: &str, offset: usize) -> usize {
+    let leading_pipe = line.trim_start().starts_with('|');
+    let pipes = pipe_offsets(line)
+        .into_iter()
+        .filter(|pipe| *pipe < offset)
+        .count();
+    pipes.saturating_sub(usize::from(leading_pipe))
+}
+
+/// Find the table around a byte offset
+///
+/// ### Arguments
+/// - `text`: The document
+/// - `offset`: The byte offset of the cursor
+///
+/// ### Returns
+/// - `Some(LocatedTable)`: The table and the cell holding the cursor
+/// - `None`: The cursor is not in a table
+fn locate_table(text: &str, offset: usize) -> Option<LocatedTable> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in text.split('\n') {
+        lines.push(start..start + line.len());
+        start += line.len() + 1;
+    }
+    let line_text = |index: usize| text[lines[index].clone()].trim_end_matches('\r');
+    let current = lines.iter().position(|line| offset <= line.end)?;
+    let fences = (0..current)
+        .filter(|index| is_fence(line_text(*index)))
+        .count();
+    if fences % 2 == 1 || !is_table_row(line_text(current)) {
+        return None;
+    }
+    let mut first = current;
+    while first > 0 && is_table_row(line_text(first - 1)) {
+        first -= 1;
+    }
+    let mut last = current;
+    while last + 1 < lines.len() && is_table_row(line_text(last + 1)) {
+        last += 1;
+    }
+    let delimiter = (first + 1..=last).find(|index| {
+        parse_delimiter_row(line_text(*index))
+            .is_some_and(|alignments| alignments.len() == split_cells(line_text(index - 1)).len())
+    })?;
+    let header = delimiter - 1;
+    if current < header {
+        return None;
+    }
+    let mut table = MarkdownTable {
+        indent: line_text(header)
+            .chars()
+            .take_while(|character| character.is_whitespace())
+            .collect(),
+        header: split_cells(line_text(header)),
+        alignments: parse_delimiter_row(line_text(delimiter)).unwrap_or_default(),
+        rows: (delimiter + 1..=last)
+            .map(|index| split_cells(line_text(index)))
+            .collect(),
+    };
+    table.normalize();
+    let column = column_at(line_text(current), offset - lines[current].start);
+    Some(LocatedTable {
+        range: lines[header].start..lines[last].start + line_text(last).len(),
+        row: current.saturating_sub(delimiter),
+        column: column.min(table.header.len() - 1),
+        table,
+    })
+}
+
+/// Pad a cell to a display width
+///
+/// ### Arguments
+/// - `cell`: The cell content
+/// - `width`: The display width of the column
+/// - `alignment`: The alignment of the column
+///
+/// ### Returns
+/// - `(String, usize)`: The padded cell and the byte offset of the content in it
+fn pad_cell(cell: &str, width: usize, alignment: ColumnAlignment) -> (String, usize) {
+    let padding = width.saturating_sub(cell.width());
+    let before = match alignment {
+        ColumnAlignment::Right => padding,
+        ColumnAlignment::Center => padding / 2,
+        ColumnAlignment::None | ColumnAlignment::Left => 0,
+    };
+    let padded = format!(
+        "{}{cell}{}",
+        " ".repeat(before),
+        " ".repeat(padding - before)
+    );
+    (padded, before)
+}
+
+impl MarkdownTable {
+    /// Give every row, the header and the alignments one entry per column
+    fn normalize(&mut self) {
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain([self.header.len(), self.alignments.len(), 1])
+            .max()
+            .unwrap_or(1);
+        self.header.resize(columns, String::new());
+        self.alignments.resize(columns, ColumnAlignment::None);
+        for row in &mut self.rows {
+            row.resize(columns, String::new());
+        }
+    }
+
+    /// Build a table from CSV data, the first record being the header
+    ///
+    /// ### Arguments
+    /// - `data`: The CSV data
+    ///
+    /// ### Returns
+    /// - `MarkdownTable`: The table, with pipes escaped and line breaks turned into `<br>`
+    #[must_use]
+    pub fn from_csv(data: &CsvData) -> Self {
+        let cell = |value: &String| {
+            value
+                .trim()
+                .replace('|', "\\|")
+                .replace("\r\n", "<br>")
+                .replace('\n', "<br>")
+        };
+        let mut table = Self {
+            indent: String::new(),
+            header: data.headers.iter().map(cell).collect(),
+            alignments: Vec::new(),
+            rows: data
+                .rows
+                .iter()
+                .map(|row| row.iter().map(cell).collect())
+                .collect(),
+        };
+        table.normalize();
+        table
+    }
+
+    /// Render the table with its pipes aligned
+    ///
+    /// ### Returns
+    /// - `RenderedTable`: The lines, without a final line break, and the cell positions
+    fn render(&self) -> RenderedTable {
+        let widths: Vec<usize> = (0..self.header.len())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| row[column].width())
+                    .chain([self.header[column].width(), MIN_COLUMN_WIDTH])
+                    .max()
+                    .unwrap_or(MIN_COLUMN_WIDTH)
+            })
+            .collect();
+        let mut text = String::new();
+        let mut cells = Vec::with_capacity(self.rows.len() + 1);
+        let mut push_row = |text: &mut String, row: &[String]| {
+            text.push_str(&self.indent);
+            text.push('|');
+            let mut positions = Vec::with_capacity(row.len());
+            for ((cell, width), alignment) in row.iter().zip(&widths).zip(&self.alignments) {
+                let (padded, before) = pad_cell(cell, *width, *alignment);
+                let start = text.len() + 1 + before;
+                positions.push(start..start + cell.len());
+                text.push(' ');
+                text.push_str(&padded);
+                text.push_str(" |");
+            }
+            cells.push(positions);
+        };
+        push_row(&mut text, &self.header);
+        text.push('\n');
+        text.push_str(&self.indent);
+        text.push('|');
+        for (width, alignment) in widths.iter().zip(&self.alignments) {
+            let dashes = "-".repeat(*width);
+            let delimiter = match alignment {
+                ColumnAlignment::None => dashes,
+                ColumnAlignment::Left => format!(":{}", &dashes[1..]),
+                ColumnAlignment::Center => format!(":{}:", &dashes[2..]),
+                ColumnAlignment::Right => format!("{}:", &dashes[1..]),
+            };
+            text.push(' ');
+            text.push_str(&delimiter);
+            text.push_str(" |");
+        }
+        for row in &self.rows {
+            text.push('\n');
+            push_row(&mut text, row);
+        }
+        RenderedTable { text, cells }
+    }
+
+    /// Render the table with its pipes aligned
+    ///
+    /// ### Returns
+    /// - `String`: The table lines, each ending with a line break
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut text = self.render().text;
+        text.push('\n');
+        text
+    }
+
+    /// Apply an edit to the table
+    ///
+    /// ### Arguments
+    /// - `edit`: The edit
+    /// - `row`: The row of the cursor, 0 for the header
+    /// - `column`: The column of the cursor
+    ///
+    /// ### Returns
+    /// - `Some((usize, usize))`: The row and column to select afterwards
+    /// - `None`: The edit does not apply, e.g. deleting the header row
+    fn apply(&mut self, edit: TableEdit, row: usize, column: usize) -> Option<(usize, usize)> {
+        let columns = self.header.len();
+        let empty_row = vec![String::new(); columns];
+        match edit {
+            TableEdit::Format => Some((row, column)),
+            TableEdit::InsertRowAbove => {
+                let index = row.saturating_sub(1);
+                self.rows.insert(index, empty_row);
+                Some((index + 1, column))
+            }
+            TableEdit::InsertRowBelow => {
+                self.rows.insert(row, empty_row);
+                Some((row + 1, column))
+            }
+            TableEdit::DeleteRow => {
+                if row == 0 {
+                    return None;
+                }
+                self.rows.remove(row - 1);
+                Some((row.min(self.rows.len()), column))
+            }
+            TableEdit::InsertColumnBefore | TableEdit::InsertColumnAfter => {
+                let index = column + usize::from(edit == TableEdit::InsertColumnAfter);
+                self.header.insert(index, String::new());
+                self.alignments.insert(index, ColumnAlignment::None);
+                for cells in &mut self.rows {
+                    cells.insert(index, String::new());
+                }
+                Some((row, index))
+            }
+            TableEdit::DeleteColumn => {
+                if columns == 1 {
+                    return None;
+                }
+                self.header.remove(column);
+                self.alignments.remove(column);
+                for cells in &mut self.rows {
+                    cells.remove(column);
+                }
+                Some((row, column.min(columns - 2)))
+            }
+            TableEdit::MoveColumnLeft | TableEdit::MoveColumnRight => {
+                let target = if edit == TableEdit::MoveColumnLeft {
+                    column.checked_sub(1)?
+                } else {
+                    Some(column + 1).filter(|target| *target < columns)?
+                };
+                self.header.swap(column, target);
+                self.alignments.swap(column, target);
+                for cells in &mut self.rows {
+                    cells.swap(column, target);
+                }
+                Some((row, target))
+            }
+            TableEdit::Align(alignment) => {
+                self.alignments[column] = alignment;
+                Some((row, column))
+            }
+            TableEdit::NextCell => {
+                if column + 1 < columns {
+                    return Some((row, column + 1));
+                }
+                if row == self.rows.len() {
+                    self.rows.push(empty_row);
+                }
+                Some((row + 1, 0))
+            }
+            TableEdit::PreviousCell => Some(match (row, column) {
+                (0, 0) => (0, 0),
+                (_, 0) => (row - 1, columns - 1),
+                _ => (row, column - 1),
+            }),
+        }
+    }
+}
+
+/// Apply an edit to the table around the cursor and realign it
+///
+/// ### Arguments
+/// - `text`: The document
+/// - `cursor`: The byte offset of the cursor
+/// - `edit`: The edit
+///
+/// ### Returns
+/// - `Some(TableEditResult)`: The replacement of the table and the cell to select
+/// - `None`: The cursor is not in a table, or the edit does not apply
+#[must_use]
+pub fn edit_table(text: &str, cursor: usize, edit: TableEdit) -> Option<TableEditResult> {
+    let LocatedTable {
+        range,
+        mut table,
+        row,
+        column,
+    } = locate_table(text, cursor)?;
+    let (row, column) = table.apply(edit, row, column)?;
+    let rendered = table.render();
+    let cell = rendered.cells.get(row)?.get(column)?;
+    Some(TableEditResult {
+        selection: range.start + cell.start..range.start + cell.end,
+        edit: TextEdit {
+            range,
+            new_text: rendered.text,
+        },
+    })
+}
+
+/// Convert CSV text to a Markdown table
+///
+/// ### Arguments
+/// - `text`: The CSV text
+/// - `delimiter`: The field delimiter of `text`
+///
+/// ### Returns
+/// - `Some(String)`: The table lines, each ending with a line break
+/// - `None`: The text holds no CSV record
+#[must_use]
+pub fn csv_to_markdown_table(text: &str, delimiter: u8) -> Option<String> {
+    let outcome = parse_csv(text, delimiter);
+    if outcome.data.headers.is_empty() {
+        return None;
+    }
+    Some(MarkdownTable::from_csv(&outcome.data).to_markdown())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply an edit with the cursor at the `^` marker, returning the new text and selected text
+    fn edit_at_marker(marked: &str, edit: TableEdit) -> Option<(String, String)> {
+        let cursor = marked.find('^')?;
+        let text = marked.replacen('^', "", 1);
+        let result = edit_table(&text, cursor, edit)?;
+        let mut edited = text.clone();
+        edited.replace_range(result.edit.range.clone(), &result.edit.new_text);
+        let selected = edited[result.selection].to_string();
+        Some((edited, selected))
+    }
+
+    #[test]
+    fn test_format_aligns_pipes_and_keeps_alignment() {
+        let (text, selected) = edit_at_marker(
+            "intro\n\n|Name|Qty|\n|:-|--:|\n|ap^ple|3|\n|kiwi \\| lime|12|\n\nend",
+            TableEdit::Format,
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            "intro\n\n\
+             | Name         | Qty |\n\
+             | :----------- | --: |\n\
+             | apple        |   3 |\n\
+             | kiwi \\| lime |  12 |\n\
+             \nend"
+        );
+        assert_eq!(selected, "apple");
+    }
+
+    #[test]
+    fn test_format_measures_wide_characters() {
+        let (text, _) = edit_at_marker("|^名前|x|\n|-|-|\n|😀|abcd|", TableEdit::Format).unwrap();
+        assert_eq!(text, "| 名前 | x    |\n| ---- | ---- |\n| 😀   | abcd |");
+    }
+
+    #[test]
+    fn test_row_and_column_edits() {
+        let table = "| a | b |\n| --- | --- |\n| 1 | ^2 |";
+        let (text, _) = edit_at_marker(table, TableEdit::InsertRowAbove).unwrap();
+        assert_eq!(
+            text,
+            "| a   | b   |\n| --- | --- |\n|     |     |\n| 1   | 2   |"
+        );
+        let (text, _) = edit_at_marker(table, TableEdit::DeleteColumn).unwrap();
+        assert_eq!(text, "| a   |\n| --- |\n| 1   |");
+        let (text, selected) = edit_at_marker(table, TableEdit::MoveColumnLeft).unwrap();
+        assert_eq!(text, "| b   | a   |\n| --- | --- |\n| 2   | 1   |");
+        assert_eq!(selected, "2");
+        let (text, _) = edit_at_marker(table, TableEdit::Align(ColumnAlignment::Center)).unwrap();
+        assert_eq!(text, "| a   |  b  |\n| --- | :-: |\n| 1   |  2  |");
+        assert!(edit_at_marker("| ^a | b |\n| - | - |", TableEdit::DeleteRow).is_none());
+    }
+
+    #[test]
+    fn test_next_cell_moves_on_and_appends_a_row() {
+        let (_, selected) =
+            edit_at_marker("| ^a | b |\n| - | - |\n| 1 | 2 |", TableEdit::NextCell).unwrap();
+        assert_eq!(selected, "b");
+        let (text, selected) =
+            edit_at_marker("| a | b |\n| - | - |\n| 1 | ^2 |", TableEdit::NextCell).unwrap();
+        assert_eq!(
+            text,
+            "| a   | b   |\n| --- | --- |\n| 1   | 2   |\n|     |     |"
+        );
+        assert_eq!(selected, "");
+        let (_, selected) =
+            edit_at_marker("| a | b |\n| - | - |\n| ^1 | 2 |", TableEdit::PreviousCell).unwrap();
+        assert_eq!(selected, "b");
+    }
+
+    #[test]
+    fn test_pipes_outside_tables_are_ignored() {
+        assert!(edit_at_marker("a ^| b\nno table", TableEdit::Format).is_none());
+        assert!(edit_at_marker("```\n| ^a | b |\n| - | - |\n```", TableEdit::Format).is_none());
+    }
+
+    #[test]
+    fn test_csv_to_markdown_table_escapes_cells() {
+        assert_eq!(
+            csv_to_markdown_table("name;note\nAda;\"a|b\"\n", b';').unwrap(),
+            "| name | note |\n| ---- | ---- |\n| Ada  | a\\|b |\n"
+        );
+        assert!(csv_to_markdown_table("", b',').is_none());
+    }
+}
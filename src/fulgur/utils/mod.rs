@@ -1,15 +1,40 @@
+pub mod ansi;
 pub mod atomic_write;
+pub mod code_html;
+pub mod code_rtf;
 pub mod crypto_helper;
+pub mod fuzzy;
 pub mod http;
+pub mod json_query;
 #[cfg(target_os = "windows")]
 pub mod jump_list;
 pub mod logger;
+pub mod markdown_assets;
+pub mod markdown_blocks;
+pub mod markdown_export;
+pub mod markdown_extensions;
 pub mod markdown_images;
+pub mod markdown_links;
+pub mod markdown_lint;
+pub mod markdown_table;
 pub mod paths;
+pub mod print_html;
+#[cfg(target_os = "linux")]
+pub mod privileged_write;
 pub mod retry;
+pub mod rich_clipboard;
 pub mod sanitize;
+pub mod shell_pipe;
 #[cfg(target_os = "windows")]
 pub mod single_instance;
+pub mod structured_data;
+pub mod task_output;
+pub mod task_process;
+pub mod tasks;
+#[cfg(target_os = "linux")]
+pub mod terminal_input;
+pub mod text_diff;
+pub mod text_transform;
 pub mod updater;
 pub mod utilities;
 pub mod worker;
@@ -0,0 +1,254 @@
+//! Lay out documents for printing: highlighted code lines, a header and page numbers.
+
+use crate::fulgur::utils::{
+    code_html::{SyntaxSpan, clip_spans, escape_html, highlighted_code_html},
+    markdown_export::ExportPalette,
+};
+use std::fmt::Write;
+use std::ops::Range;
+
+/// What the header of a printed document shows
+pub struct PrintHeader {
+    pub file_name: String,
+    pub path: Option<String>, // `None` for untitled tabs
+    pub date: String,
+}
+
+/// Restrict spans to one line, with offsets relative to the start of the line
+///
+/// ### Arguments
+/// - `spans`: The styled spans of the whole text, in order
+/// - `first`: Index of the first span that may still reach `line`, advanced past finished spans
+/// - `line`: The byte range of the line in the whole text
+///
+/// ### Returns
+/// - `Vec<SyntaxSpan>`: The spans clipped to the line
+fn line_spans(spans: &[SyntaxSpan], first: &mut usize, line: &Range<usize>) -> Vec<SyntaxSpan> {
+    while spans
+        .get(*first)
+        .is_some_and(|span| span.range.end <= line.start)
+    {
+        *first += 1;
+    }
+    clip_spans(&spans[*first..], line)
+}
+
+/// Render a range of highlighted text as a table of lines
+///
+/// ### Description
+/// Line numbers count from the start of `text`, so a printed selection keeps
+/// the numbers it has in the editor. A final line break does not start an
+/// empty line.
+///
+/// ### Arguments
+/// - `text`: The whole text
+/// - `spans`: The styled spans of `text`
+/// - `range`: The byte range to print; the whole text when it is empty or invalid
+/// - `line_numbers`: Whether to add a line number column
+///
+/// ### Returns
+/// - `String`: The `<table class="code">` element
+#[must_use]
+pub fn code_lines_html(
+    text: &str,
+    spans: &[SyntaxSpan],
+    range: Range<usize>,
+    line_numbers: bool,
+) -> String {
+    let range = if range.is_empty() || text.get(range.clone()).is_none() {
+        0..text.len()
+    } else {
+        range
+    };
+    let printed = &text[range.clone()];
+    let printed = printed.strip_suffix('\n').unwrap_or(printed);
+    let first_number = text[..range.start].matches('\n').count() + 1;
+    let mut start = range.start;
+    let mut first = 0;
+    let mut html = String::from("<table class=\"code\">\n");
+    for (number, line) in (first_number..).zip(printed.split('\n')) {
+        let content = line.strip_suffix('\r').unwrap_or(line);
+        let bounds = start..start + content.len();
+        let styled = highlighted_code_html(content, &line_spans(spans, &mut first, &bounds));
+        html.push_str("<tr>");
+        if line_numbers {
+            let _ = write!(html, "<td class=\"line-number\">{number}</td>");
+        }
+        // A space keeps empty lines at their height
+        let styled = if styled.is_empty() { " " } else { &styled };
+        let _ = writeln!(html, "<td class=\"line\">{styled}</td></tr>");
+        start += line.len() + 1;
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Get the styles of printed source code
+///
+/// ### Arguments
+/// - `palette`: The colors of the current theme
+/// - `font_family`: The editor font
+///
+/// ### Returns
+/// - `String`: The CSS rules
+#[must_use]
+pub fn code_print_css(palette: &ExportPalette, font_family: &str) -> String {
+    let ExportPalette {
+        background,
+        foreground,
+        muted_foreground,
+        border,
+        ..
+    } = *palette;
+    let font_family = font_family.replace(['"', '\\', ';', '{', '}'], "");
+    format!(
+        r#"body {{ margin: 0; background: #{background:06x}; color: #{foreground:06x}; }}
+table.code {{ border-collapse: collapse; width: 100%; font-family: "{font_family}", ui-monospace, Menlo, Consolas, monospace; font-size: 9pt; line-height: 1.4; }}
+td.line {{ white-space: pre-wrap; overflow-wrap: anywhere; padding: 0 0 0 0.8em; vertical-align: top; }}
+td.line-number {{ width: 1%; padding: 0 0.6em 0 0; text-align: right; vertical-align: top; color: #{muted_foreground:06x}; border-right: 1px solid #{border:06x}; user-select: none; }}
+tr {{ break-inside: avoid; }}
+"#
+    )
+}
+
+/// Get the page styles shared by every printed document
+///
+/// ### Description
+/// Colors are printed as on screen, and the footer is a page margin box with
+/// the page number and count, which browsers fill in when printing.
+///
+/// ### Arguments
+/// - `palette`: The colors of the current theme
+/// - `footer`: Whether to number the pages
+///
+/// ### Returns
+/// - `String`: The CSS rules
+#[must_use]
+pub fn page_css(palette: &ExportPalette, footer: bool) -> String {
+    let ExportPalette {
+        muted_foreground,
+        border,
+        ..
+    } = *palette;
+    let footer = if footer {
+        format!(
+            " @bottom-center {{ content: counter(page) \" / \" counter(pages); font-family: sans-serif; font-size: 8pt; color: #{muted_foreground:06x}; }}"
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        r"@page {{ margin: 1.5cm;{footer} }}
+html {{ print-color-adjust: exact; -webkit-print-color-adjust: exact; }}
+header.print-header {{ display: flex; gap: 1.5em; align-items: baseline; margin-bottom: 1em; padding-bottom: 0.4em; border-bottom: 1px solid #{border:06x}; font-family: sans-serif; font-size: 9pt; color: #{muted_foreground:06x}; }}
+header.print-header .path {{ flex: 1; overflow-wrap: anywhere; }}
+"
+    )
+}
+
+/// Render the header of a printed document
+///
+/// ### Arguments
+/// - `header`: The file name, path and date
+///
+/// ### Returns
+/// - `String`: The `<header>` element
+#[must_use]
+pub fn print_header_html(header: &PrintHeader) -> String {
+    format!(
+        "<header class=\"print-header\"><strong>{}</strong><span class=\"path\">{}</span><span>{}</span></header>\n",
+        escape_html(&header.file_name),
+        escape_html(header.path.as_deref().unwrap_or_default()),
+        escape_html(&header.date)
+    )
+}
+
+/// Wrap a body in an HTML document that opens the print dialog once loaded
+///
+/// ### Arguments
+/// - `title`: The document title
+/// - `css`: The styles of the document
+/// - `body`: The HTML content of `<body>`
+///
+/// ### Returns
+/// - `String`: The HTML document
+#[must_use]
+pub fn print_document(title: &str, css: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n{css}</style>\n</head>\n<body>\n{body}\
+         <script>window.onload = function() {{ window.print(); }};</script>\n</body>\n</html>\n",
+        escape_html(title)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(range: Range<usize>, color: u32) -> SyntaxSpan {
+        SyntaxSpan {
+            range,
+            color: Some(color),
+            bold: false,
+            italic: false,
+        }
+    }
+
+    const PALETTE: ExportPalette = ExportPalette {
+        background: 0xff_ff_ff,
+        foreground: 0x00_00_00,
+        muted_background: 0xee_ee_ee,
+        muted_foreground: 0x80_80_80,
+        border: 0xcc_cc_cc,
+        link: 0x00_00_ff,
+    };
+
+    #[test]
+    fn test_code_lines_html_splits_spans_across_lines() {
+        let text = "/* a\nb */\nx\n";
+        let html = code_lines_html(text, &[color(0..10, 0x80_80_80)], 0..0, true);
+        assert_eq!(
+            html,
+            "<table class=\"code\">\n\
+             <tr><td class=\"line-number\">1</td><td class=\"line\"><span style=\"color:#808080;\">/* a</span></td></tr>\n\
+             <tr><td class=\"line-number\">2</td><td class=\"line\"><span style=\"color:#808080;\">b */</span></td></tr>\n\
+             <tr><td class=\"line-number\">3</td><td class=\"line\">x</td></tr>\n\
+             </table>\n"
+        );
+    }
+
+    #[test]
+    fn test_code_lines_html_numbers_a_selection_from_its_first_line() {
+        let text = "one\r\ntwo\n\nfour";
+        let html = code_lines_html(text, &[color(5..8, 0xff_00_00)], 5..14, false);
+        assert_eq!(
+            html,
+            "<table class=\"code\">\n\
+             <tr><td class=\"line\"><span style=\"color:#ff0000;\">two</span></td></tr>\n\
+             <tr><td class=\"line\"> </td></tr>\n\
+             <tr><td class=\"line\">four</td></tr>\n\
+             </table>\n"
+        );
+        assert!(code_lines_html(text, &[], 10..14, true).contains(">4</td>"));
+    }
+
+    #[test]
+    fn test_page_css_numbers_pages_only_with_a_footer() {
+        assert!(page_css(&PALETTE, true).contains("counter(pages)"));
+        assert!(!page_css(&PALETTE, false).contains("counter(page)"));
+    }
+
+    #[test]
+    fn test_print_header_html_escapes_its_fields() {
+        let header = PrintHeader {
+            file_name: "a<b>.rs".to_string(),
+            path: None,
+            date: "2026-01-02 03:04:05".to_string(),
+        };
+        assert_eq!(
+            print_header_html(&header),
+            "<header class=\"print-header\"><strong>a&lt;b&gt;.rs</strong><span class=\"path\"></span><span>2026-01-02 03:04:05</span></header>\n"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use crate::fulgur::utils::atomic_write::resolve_target;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static STAGING_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Shell script run by the helper as root. It copies the staged content into a
+/// sibling temporary file, gives it the owner and mode of the destination, then
+/// renames it over the destination, mirroring `atomic_write_file`.
+///
+/// Positional parameters: `$1` is the staged file, `$2` the destination path.
+const PRIVILEGED_WRITE_SCRIPT: &str = r#"set -e
+target="$2"
+tmp=$(mktemp "$(dirname "$target")/.$(basename "$target").XXXXXX")
+trap 'rm -f "$tmp"' EXIT
+cat "$1" > "$tmp"
+if [ -e "$target" ]; then
+    chown --reference="$target" "$tmp"
+    chmod --reference="$target" "$tmp"
+else
+    chmod 644 "$tmp"
+fi
+sync "$tmp" 2>/dev/null || true
+mv -f "$tmp" "$target"
+trap - EXIT
+"#;
+
+/// Check whether a failed write was refused for lack of permissions.
+///
+/// ### Arguments
+/// - `error`: The error returned by the write
+///
+/// ### Returns
+/// - `true`: if an I/O error in the chain is a permission denial
+/// - `false`: otherwise
+#[must_use]
+pub fn is_permission_denied(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::PermissionDenied)
+    })
+}
+
+/// Split the configured helper command into a program and its arguments.
+///
+/// ### Arguments
+/// - `helper`: The helper command line, e.g. `pkexec` or `sudo -A`
+///
+/// ### Returns
+/// - `Some((String, Vec<String>))`: the program and its leading arguments
+/// - `None`: if the command is blank
+fn parse_helper(helper: &str) -> Option<(String, Vec<String>)> {
+    let mut parts = helper.split_whitespace().map(str::to_string);
+    let program = parts.next()?;
+    Some((program, parts.collect()))
+}
+
+/// Write the content to a private staging file readable by the helper.
+///
+/// ### Arguments
+/// - `contents`: The content to stage
+///
+/// ### Errors
+/// Returns an error if the staging file cannot be created or written.
+///
+/// ### Returns
+/// - `Ok(PathBuf)`: the path of the staging file
+fn stage_contents(contents: &[u8]) -> anyhow::Result<PathBuf> {
+    let staging_path = std::env::temp_dir().join(format!(
+        "fulgur-privileged-{}-{}.tmp",
+        std::process::id(),
+        STAGING_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut staging_file = OpenOptions::new()
+        .create_new(true)
+        .write(true)
+        .mode(0o600)
+        .open(&staging_path)
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to create staging file '{}': {e}",
+                staging_path.display()
+            )
+        })?;
+    staging_file
+        .write_all(contents)
+        .and_then(|()| staging_file.sync_all())
+        .map_err(|e| {
+            let _ = fs::remove_file(&staging_path);
+            anyhow::anyhow!(
+                "Failed to write staging file '{}': {e}",
+                staging_path.display()
+            )
+        })?;
+    Ok(staging_path)
+}
+
+/// Write file contents with elevated privileges through a helper command.
+///
+/// ### Description
+/// The content is staged in a private temporary file, then the helper runs a
+/// shell script as root that writes a sibling temporary file, copies the owner
+/// and mode of the destination onto it and renames it over the destination.
+/// The helper receives `/bin/sh -c <script> sh <staged> <destination>` after its
+/// own arguments, so any command that runs its arguments as root works.
+///
+/// ### Arguments
+/// - `helper`: The helper command line, e.g. `pkexec` or `sudo -A`
+/// - `path`: The path to the file to write
+/// - `contents`: The content to write in the file
+///
+/// ### Errors
+/// Returns an error if the helper is blank, if the content cannot be staged, if
+/// the helper cannot be started, or if it exits unsuccessfully (including when
+/// the user dismisses the authentication prompt).
+///
+/// ### Returns
+/// - `Ok(())`: the write is successful
+/// - `Err()`: error while writing the file
+pub fn privileged_write_file(helper: &str, path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    let (program, helper_args) = parse_helper(helper)
+        .ok_or_else(|| anyhow::anyhow!("No privileged save helper is configured"))?;
+    let target = resolve_target(path);
+    let staging_path = stage_contents(contents)?;
+    let output = Command::new(&program)
+        .args(&helper_args)
+        .arg("/bin/sh")
+        .arg("-c")
+        .arg(PRIVILEGED_WRITE_SCRIPT)
+        .arg("sh")
+        .arg(&staging_path)
+        .arg(&target)
+        .output();
+    let _ = fs::remove_file(&staging_path);
+    let output = output
+        .map_err(|e| anyhow::anyhow!("Failed to run privileged save helper '{program}': {e}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stderr = stderr.trim();
+    if stderr.is_empty() {
+        Err(anyhow::anyhow!(
+            "Privileged save helper '{program}' failed ({})",
+            output.status
+        ))
+    } else {
+        Err(anyhow::anyhow!(
+            "Privileged save helper '{program}' failed ({}): {stderr}",
+            output.status
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_permission_denied, parse_helper, privileged_write_file};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Create a helper stand-in that runs its arguments without elevation.
+    ///
+    /// ### Arguments
+    /// - `dir`: The directory to create the script in
+    /// - `body`: The script body
+    ///
+    /// ### Returns
+    /// - `String`: the helper command line running the script through `/bin/sh`
+    fn stand_in_helper(dir: &Path, body: &str) -> String {
+        let path = dir.join("helper.sh");
+        fs::write(&path, format!("{body}\n")).expect("failed to write helper");
+        format!("/bin/sh {}", path.display())
+    }
+
+    #[test]
+    fn parse_helper_splits_program_and_arguments() {
+        assert_eq!(
+            parse_helper("sudo -A"),
+            Some(("sudo".to_string(), vec!["-A".to_string()]))
+        );
+        assert_eq!(parse_helper("pkexec"), Some(("pkexec".to_string(), vec![])));
+        assert_eq!(parse_helper("   "), None);
+    }
+
+    #[test]
+    fn privileged_write_file_replaces_content_and_keeps_mode() {
+        let temp_dir = TempDir::new().expect("failed to create temp dir");
+        let helper = stand_in_helper(temp_dir.path(), "exec \"$@\"");
+        let path = temp_dir.path().join("hosts");
+        fs::write(&path, b"127.0.0.1 localhost\n").expect("failed to write file");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640))
+            .expect("failed to set permissions");
+
+        privileged_write_file(&helper, &path, b"::1 localhost\n")
+            .expect("privileged write should succeed");
+
+        assert_eq!(
+            fs::read(&path).expect("failed to read file"),
+            b"::1 localhost\n"
+        );
+        let mode = fs::metadata(&path)
+            .expect("failed to read metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o640);
+        let leftovers = fs::read_dir(temp_dir.path())
+            .expect("failed to list dir")
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(".hosts"))
+            .count();
+        assert_eq!(leftovers, 0, "no temporary file should be left behind");
+    }
+
+    #[test]
+    fn privileged_write_file_reports_helper_failure() {
+        let temp_dir = TempDir::new().expect("failed to create temp dir");
+        let helper = stand_in_helper(temp_dir.path(), "echo 'Not authorized' >&2\nexit 126");
+        let path = temp_dir.path().join("hosts");
+        fs::write(&path, b"keep").expect("failed to write file");
+
+        let error = privileged_write_file(&helper, &path, b"new")
+            .expect_err("a failing helper must be reported");
+
+        assert!(error.to_string().contains("Not authorized"));
+        assert_eq!(fs::read(&path).expect("failed to read file"), b"keep");
+    }
+
+    #[test]
+    fn is_permission_denied_detects_io_permission_errors() {
+        let denied =
+            anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+                .context("Failed to create temp file");
+        let missing = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+
+        assert!(is_permission_denied(&denied));
+        assert!(!is_permission_denied(&missing));
+        assert!(!is_permission_denied(&anyhow::anyhow!("Permission denied")));
+    }
+}
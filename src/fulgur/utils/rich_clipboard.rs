@@ -0,0 +1,296 @@
+//! Put rich text on the system clipboard, which GPUI only fills with plain text.
+//!
+//! macOS goes through `osascript`, Linux through `arboard` and Windows through
+//! the clipboard API, each offering plain text next to the rich text. Linux
+//! clipboards only take HTML that way; RTF there, and rich text on other
+//! platforms, is reported as unavailable so that callers copy plain text
+//! instead.
+
+#[cfg(target_os = "macos")]
+use anyhow::anyhow;
+#[cfg(target_os = "macos")]
+use std::{
+    io::{ErrorKind, Write},
+    process::{Command, Stdio},
+};
+
+/// A rich text format offered on the clipboard
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RichTextFormat {
+    Html,
+    Rtf,
+}
+
+impl RichTextFormat {
+    /// Get the name of the format shown to users
+    ///
+    /// ### Returns
+    /// - `&'static str`: The name of the format
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Html => "HTML",
+            Self::Rtf => "RTF",
+        }
+    }
+}
+
+/// Put rich text on the clipboard
+///
+/// ### Description
+/// `plain` is offered next to the rich text for applications that only paste
+/// plain text.
+///
+/// ### Arguments
+/// - `format`: The format of `rich`
+/// - `rich`: The document in that format; a fragment for HTML
+/// - `plain`: The same content as plain text
+///
+/// ### Returns
+/// - `Ok(true)`: The clipboard holds the rich text
+/// - `Ok(false)`: Rich text is not available on this system, nothing was copied
+///
+/// ### Errors
+/// - Returns an error when the clipboard could not be written
+pub fn write_rich_text(format: RichTextFormat, rich: &str, plain: &str) -> anyhow::Result<bool> {
+    #[cfg(target_os = "macos")]
+    {
+        let class = match format {
+            RichTextFormat::Html => "HTML",
+            RichTextFormat::Rtf => "RTF ",
+        };
+        let script = format!(
+            "set the clipboard to {{«class {class}»:«data {class}{}», «class utf8»:«data utf8{}»}}",
+            hex::encode_upper(rich),
+            hex::encode_upper(plain)
+        );
+        pipe_to("osascript", &[], &script)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        match format {
+            RichTextFormat::Html => linux_clipboard::write_html(rich, plain).map(|()| true),
+            RichTextFormat::Rtf => Ok(false),
+        }
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let (format_name, data) = match format {
+            RichTextFormat::Html => ("HTML Format", cf_html(rich)),
+            RichTextFormat::Rtf => ("Rich Text Format", rich.to_string()),
+        };
+        windows_clipboard::write(format_name, &data, plain).map(|()| true)
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        let _ = (format, rich, plain);
+        Ok(false)
+    }
+}
+
+/// Write text to the standard input of a clipboard tool
+///
+/// ### Arguments
+/// - `program`: The clipboard tool
+/// - `args`: Its arguments
+/// - `input`: The text to write
+///
+/// ### Returns
+/// - `Ok(true)`: The tool accepted the text
+/// - `Ok(false)`: The tool is not installed
+///
+/// ### Errors
+/// - Returns an error when the tool could not be run or failed
+#[cfg(target_os = "macos")]
+fn pipe_to(program: &str, args: &[&str], input: &str) -> anyhow::Result<bool> {
+    // Clipboard tools keep serving the selection from a forked process, which
+    // would hold piped output open: only the exit status is read
+    let spawned = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            log::info!("{program} is not installed, rich text is not available");
+            return Ok(false);
+        }
+        Err(e) => return Err(anyhow!("Failed to run {program}: {e}")),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| anyhow!("Failed to write to {program}: {e}"))?;
+    }
+    let status = child
+        .wait()
+        .map_err(|e| anyhow!("Failed to wait for {program}: {e}"))?;
+    if status.success() {
+        Ok(true)
+    } else {
+        Err(anyhow!("{program} exited with {status}"))
+    }
+}
+
+/// Wrap an HTML fragment in the `HTML Format` clipboard envelope of Windows
+///
+/// ### Arguments
+/// - `fragment`: The HTML fragment
+///
+/// ### Returns
+/// - `String`: The header with the byte offsets of the document and fragment, then the document
+#[cfg(any(target_os = "windows", test))]
+fn cf_html(fragment: &str) -> String {
+    const PREFIX: &str = "<html><body>\r\n<!--StartFragment-->";
+    const SUFFIX: &str = "<!--EndFragment-->\r\n</body></html>";
+    let header =
+        |start_html: usize, end_html: usize, start_fragment: usize, end_fragment: usize| {
+            format!(
+                "Version:0.9\r\nStartHTML:{start_html:010}\r\nEndHTML:{end_html:010}\r\n\
+                 StartFragment:{start_fragment:010}\r\nEndFragment:{end_fragment:010}\r\n"
+            )
+        };
+    let start_html = header(0, 0, 0, 0).len();
+    let start_fragment = start_html + PREFIX.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + SUFFIX.len();
+    format!(
+        "{}{PREFIX}{fragment}{SUFFIX}",
+        header(start_html, end_html, start_fragment, end_fragment)
+    )
+}
+
+#[cfg(target_os = "linux")]
+mod linux_clipboard {
+    use anyhow::anyhow;
+    use arboard::Clipboard;
+    use std::sync::{Mutex, PoisonError};
+
+    /// The X11 clipboard only serves what was copied while its handle is alive,
+    /// so the handle is kept until the next copy
+    static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+    /// Replace the clipboard with HTML and its plain text
+    ///
+    /// ### Arguments
+    /// - `html`: The HTML fragment
+    /// - `plain`: The same content as plain text
+    ///
+    /// ### Errors
+    /// - Returns an error when the clipboard could not be opened or written
+    pub(super) fn write_html(html: &str, plain: &str) -> anyhow::Result<()> {
+        let mut handle = CLIPBOARD.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut clipboard = match handle.take() {
+            Some(clipboard) => clipboard,
+            None => Clipboard::new().map_err(|e| anyhow!("Failed to open the clipboard: {e}"))?,
+        };
+        let written = clipboard
+            .set_html(html, Some(plain))
+            .map_err(|e| anyhow!("Failed to write the clipboard: {e}"));
+        *handle = Some(clipboard);
+        written
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows_clipboard {
+    use anyhow::anyhow;
+    use windows::{
+        Win32::{
+            Foundation::HANDLE,
+            System::{
+                DataExchange::{
+                    CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW,
+                    SetClipboardData,
+                },
+                Memory::{GMEM_MOVEABLE, GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock},
+                Ole::CF_UNICODETEXT,
+            },
+        },
+        core::{HSTRING, PCWSTR},
+    };
+
+    /// Copy bytes to global memory and hand it to the open clipboard
+    ///
+    /// ### Arguments
+    /// - `format`: The clipboard format
+    /// - `bytes`: The data, including its terminating null
+    ///
+    /// ### Errors
+    /// - Returns an error when the memory could not be allocated or the clipboard refused it
+    fn set_data(format: u32, bytes: &[u8]) -> windows::core::Result<()> {
+        unsafe {
+            let memory = GlobalAlloc(GMEM_MOVEABLE, bytes.len())?;
+            let target = GlobalLock(memory).cast::<u8>();
+            if target.is_null() {
+                let _ = GlobalFree(Some(memory));
+                return Err(windows::core::Error::from_win32());
+            }
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), target, bytes.len());
+            let _ = GlobalUnlock(memory);
+            // The clipboard owns the memory once it accepts it
+            if let Err(e) = SetClipboardData(format, Some(HANDLE(memory.0))) {
+                let _ = GlobalFree(Some(memory));
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the clipboard with a registered format and its plain text
+    ///
+    /// ### Arguments
+    /// - `format_name`: The registered name of the rich format
+    /// - `data`: The rich data
+    /// - `plain`: The same content as plain text
+    ///
+    /// ### Errors
+    /// - Returns an error when the clipboard could not be opened or written
+    pub(super) fn write(format_name: &str, data: &str, plain: &str) -> anyhow::Result<()> {
+        unsafe { OpenClipboard(None) }.map_err(|e| anyhow!("Failed to open the clipboard: {e}"))?;
+        let result = (|| {
+            unsafe { EmptyClipboard() }?;
+            let format =
+                unsafe { RegisterClipboardFormatW(PCWSTR(HSTRING::from(format_name).as_ptr())) };
+            if format == 0 {
+                return Err(windows::core::Error::from_win32());
+            }
+            let mut rich = data.as_bytes().to_vec();
+            rich.push(0);
+            set_data(format, &rich)?;
+            let wide: Vec<u8> = plain
+                .encode_utf16()
+                .chain(std::iter::once(0))
+                .flat_map(u16::to_ne_bytes)
+                .collect();
+            set_data(u32::from(CF_UNICODETEXT.0), &wide)
+        })();
+        let _ = unsafe { CloseClipboard() };
+        result.map_err(|e| anyhow!("Failed to write the clipboard: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cf_html;
+
+    #[test]
+    fn test_cf_html_offsets_point_at_the_fragment() {
+        let envelope = cf_html("<pre>é</pre>");
+        let offset = |key: &str| -> usize {
+            let start = envelope.find(key).unwrap() + key.len();
+            envelope[start..start + 10].parse().unwrap()
+        };
+        assert_eq!(
+            &envelope[offset("StartFragment:")..offset("EndFragment:")],
+            "<pre>é</pre>"
+        );
+        assert_eq!(
+            &envelope[offset("StartHTML:")..offset("StartHTML:") + 6],
+            "<html>"
+        );
+        assert_eq!(offset("EndHTML:"), envelope.len());
+    }
+}
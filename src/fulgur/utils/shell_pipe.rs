@@ -0,0 +1,487 @@
+use crate::fulgur::utils::text_diff::TextEdit;
+use anyhow::anyhow;
+use std::io::{Read, Write};
+use std::ops::Range;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Longest a piped command may run before it is killed
+pub const PIPE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval at which a running command is checked for completion, timeout and cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The outcome of a command that ran to completion
+#[derive(Debug)]
+pub struct PipeOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub status: ExitStatus,
+}
+
+impl PipeOutput {
+    /// Describe how the command exited
+    ///
+    /// ### Returns
+    /// - `String`: e.g. `exited with status 1`, or `was terminated by a signal`
+    #[must_use]
+    pub fn status_summary(&self) -> String {
+        match self.status.code() {
+            Some(code) => format!("exited with status {code}"),
+            None => "was terminated by a signal".to_string(),
+        }
+    }
+}
+
+/// Build the command running a command line through the platform shell
+///
+/// ### Description
+/// On Unix the shell leads a process group of its own, so
+/// `signal_process_group` reaches every process the command line starts.
+///
+/// ### Arguments
+/// - `command`: The command line
+///
+/// ### Returns
+/// - `Command`: `sh -c <command>`, or `cmd /C <command>` on Windows
+pub(crate) fn shell_command(command: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C").arg(command);
+        shell
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+        shell
+    }
+}
+
+/// Ask a shell started by `shell_command` and every process it started to terminate
+///
+/// ### Description
+/// On Unix the group is only signalled while the shell has not been reaped: until
+/// then its pid, which is also the group id, cannot be given to another process.
+///
+/// ### Arguments
+/// - `child`: The shell, leader of its process group on Unix
+/// - `force`: Kill rather than ask to terminate
+pub(crate) fn signal_process_group(child: &mut Child, force: bool) {
+    #[cfg(unix)]
+    {
+        let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+        match libc::pid_t::try_from(child.id()) {
+            Ok(group) if is_unreaped(group) => {
+                // SAFETY: `killpg` only reads its arguments, and the unreaped shell keeps
+                // `group` from naming any other process group.
+                if unsafe { libc::killpg(group, signal) } != 0 {
+                    log::debug!(
+                        "Failed to signal the process group of a command: {}",
+                        std::io::Error::last_os_error()
+                    );
+                }
+            }
+            _ => log::debug!("Not signalling the process group of a command that was reaped"),
+        }
+    }
+    #[cfg(windows)]
+    {
+        let _ = force;
+        if let Err(e) = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+        {
+            log::debug!("Failed to kill the process tree of a command: {e}");
+        }
+    }
+    if force && let Err(e) = child.kill() {
+        log::debug!("Failed to kill a command: {e}");
+    }
+}
+
+/// Check whether a child has not been reaped yet, without reaping it
+///
+/// ### Arguments
+/// - `pid`: The process id of the child
+///
+/// ### Returns
+/// - `true`: The child is running or is a zombie, so its pid is still reserved
+/// - `false`: The child was already waited for
+#[cfg(unix)]
+fn is_unreaped(pid: libc::pid_t) -> bool {
+    let Ok(id) = libc::id_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: `info` is a valid, writable `siginfo_t`; `WNOWAIT` leaves the child waitable.
+    unsafe {
+        let mut info: libc::siginfo_t = std::mem::zeroed();
+        libc::waitid(
+            libc::P_PID,
+            id,
+            &raw mut info,
+            libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+        ) == 0
+    }
+}
+
+/// Check whether a child has exited, leaving it unreaped on Unix
+///
+/// ### Description
+/// The exited shell stays a zombie until `wait` is called, so its process group
+/// can still be signalled safely while its background processes keep running.
+///
+/// ### Arguments
+/// - `child`: The child process
+///
+/// ### Returns
+/// - `Ok(bool)`: Whether the child has exited
+///
+/// ### Errors
+/// - When the status of the child cannot be queried
+fn has_exited(child: &mut Child) -> std::io::Result<bool> {
+    #[cfg(unix)]
+    {
+        let id = libc::id_t::try_from(child.id()).map_err(std::io::Error::other)?;
+        // SAFETY: `info` is a valid, writable `siginfo_t`; `WNOWAIT` leaves the child waitable.
+        unsafe {
+            let mut info: libc::siginfo_t = std::mem::zeroed();
+            if libc::waitid(
+                libc::P_PID,
+                id,
+                &raw mut info,
+                libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+            ) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(info.si_pid() != 0)
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        child.try_wait().map(|status| status.is_some())
+    }
+}
+
+/// Drain a child output stream on its own thread, so a full pipe never blocks the child
+///
+/// ### Arguments
+/// - `stream`: The stream to read
+///
+/// ### Returns
+/// - `JoinHandle<Vec<u8>>`: The thread, returning everything read until end of stream
+fn read_in_background<R: Read + Send + 'static>(stream: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut stream) = stream
+            && let Err(e) = stream.read_to_end(&mut bytes)
+        {
+            log::warn!("Failed to read the output of a piped command: {e}");
+        }
+        bytes
+    })
+}
+
+/// Kill a child that is no longer waited for, and the processes it started,
+/// reaping it to avoid a zombie
+///
+/// ### Arguments
+/// - `child`: The child process
+fn abandon(child: &mut Child) {
+    signal_process_group(child, true);
+    let _ = child.wait();
+}
+
+/// Run a command line through the shell with `input` on its standard input.
+///
+/// ### Description
+/// The input is written and both outputs are read on helper threads. The call
+/// blocks until the command exits and has closed its outputs, so it must run on
+/// a background thread. The command, and every process it started, is killed
+/// when `cancel` becomes `true` or when it runs longer than `timeout`.
+///
+/// ### Arguments
+/// - `command`: The command line, e.g. `sort -u` or `jq .`
+/// - `input`: The text written to the standard input of the command
+/// - `timeout`: The longest the command may run
+/// - `cancel`: A flag the caller sets to stop the command
+///
+/// ### Errors
+/// Returns an error if the shell cannot be started, if waiting on it fails, or if
+/// the command is cancelled or times out. A non-zero exit status is not an error:
+/// it is reported in the returned `PipeOutput`.
+///
+/// ### Returns
+/// - `Ok(PipeOutput)`: The standard output, standard error and exit status
+pub fn run_pipe_command(
+    command: &str,
+    input: &str,
+    timeout: Duration,
+    cancel: &AtomicBool,
+) -> anyhow::Result<PipeOutput> {
+    let mut child = shell_command(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start '{command}': {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = input.to_string();
+        // Never joined: the command may exit, or hand its input to a lingering
+        // process, without reading everything.
+        thread::spawn(move || {
+            if let Err(e) = stdin.write_all(input.as_bytes()) {
+                log::debug!("Piped command did not read all of its input: {e}");
+            }
+        });
+    }
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let started = Instant::now();
+    let mut exited = false;
+    loop {
+        if !exited {
+            exited = has_exited(&mut child).map_err(|e| {
+                abandon(&mut child);
+                anyhow!("Failed to wait for '{command}': {e}")
+            })?;
+        }
+        if exited && stdout.is_finished() && stderr.is_finished() {
+            break;
+        }
+        if cancel.load(Ordering::Acquire) {
+            abandon(&mut child);
+            return Err(anyhow!("'{command}' was cancelled"));
+        }
+        if started.elapsed() >= timeout {
+            abandon(&mut child);
+            return Err(anyhow!(
+                "'{command}' timed out after {} seconds",
+                timeout.as_secs_f32()
+            ));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    let status = child
+        .wait()
+        .map_err(|e| anyhow!("Failed to wait for '{command}': {e}"))?;
+    let join = |handle: JoinHandle<Vec<u8>>| {
+        handle
+            .join()
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .map_err(|_| anyhow!("Failed to read the output of '{command}'"))
+    };
+    Ok(PipeOutput {
+        stdout: join(stdout)?,
+        stderr: join(stderr)?,
+        status,
+    })
+}
+
+/// Build the edit inserting command output on new lines below a selection
+///
+/// ### Arguments
+/// - `text`: The text of the document
+/// - `selection`: The selected byte range of `text`, empty at the cursor
+/// - `output`: The command output
+///
+/// ### Returns
+/// - `TextEdit`: An insertion at the end of the last selected line, with the
+///   output's own final line break moved in front of it
+#[must_use]
+pub fn insert_below(text: &str, selection: Range<usize>, output: &str) -> TextEdit {
+    // A selection of whole lines ends at the start of the next one
+    let anchor = if !selection.is_empty() && text[..selection.end].ends_with('\n') {
+        selection.end - 1
+    } else {
+        selection.end
+    };
+    let mut line_end = text[anchor..]
+        .find('\n')
+        .map_or(text.len(), |position| anchor + position);
+    if text[..line_end].ends_with('\r') {
+        line_end -= 1;
+    }
+    let line_ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let output = fit_replacement("", output);
+    TextEdit {
+        range: line_end..line_end,
+        new_text: format!("{line_ending}{output}"),
+    }
+}
+
+/// Drop the final line break of command output replacing text that has none
+///
+/// ### Description
+/// Most commands end their output with a line break, so filtering part of a line
+/// would otherwise split it.
+///
+/// ### Arguments
+/// - `replaced`: The text being replaced
+/// - `output`: The command output
+///
+/// ### Returns
+/// - `&str`: The output, without its final line break when `replaced` has none
+#[must_use]
+pub fn fit_replacement<'a>(replaced: &str, output: &'a str) -> &'a str {
+    if replaced.ends_with('\n') {
+        return output;
+    }
+    output
+        .strip_suffix('\n')
+        .map_or(output, |output| output.strip_suffix('\r').unwrap_or(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn run(command: &str, input: &str) -> PipeOutput {
+        run_pipe_command(command, input, PIPE_TIMEOUT, &AtomicBool::new(false))
+            .expect("command should run")
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pipe_command_filters_input() {
+        let output = run("tr a-z A-Z", "hello\nworld\n");
+        assert!(output.status.success());
+        assert_eq!(output.stdout, "HELLO\nWORLD\n");
+        assert_eq!(output.stderr, "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pipe_command_reports_status_and_stderr() {
+        let output = run("echo oops >&2; exit 3", "");
+        assert!(!output.status.success());
+        assert_eq!(output.stderr, "oops\n");
+        assert_eq!(output.status_summary(), "exited with status 3");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pipe_command_ignoring_large_input() {
+        let input = "x".repeat(1024 * 1024);
+        let output = run("echo done", &input);
+        assert_eq!(output.stdout, "done\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pipe_command_times_out() {
+        let started = Instant::now();
+        let result = run_pipe_command(
+            "sleep 5",
+            "",
+            Duration::from_millis(100),
+            &AtomicBool::new(false),
+        );
+        let error = result.expect_err("command should time out").to_string();
+        assert!(error.contains("timed out"), "{error}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pipe_command_timeout_kills_background_processes() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let pid_file = dir.path().join("pid");
+        let command = format!("sleep 30 & echo $! > '{}'; wait", pid_file.display());
+        let started = Instant::now();
+        let result = run_pipe_command(
+            &command,
+            "",
+            Duration::from_millis(200),
+            &AtomicBool::new(false),
+        );
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let pid = std::fs::read_to_string(&pid_file).expect("pid written");
+        let alive = || {
+            Command::new("kill")
+                .args(["-0", pid.trim()])
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while alive() && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!alive(), "background process {} survived", pid.trim());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pipe_command_timeout_kills_processes_left_by_an_exited_shell() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let pid_file = dir.path().join("pid");
+        let command = format!("sleep 30 & echo $! > '{}'", pid_file.display());
+        let result = run_pipe_command(
+            &command,
+            "",
+            Duration::from_millis(200),
+            &AtomicBool::new(false),
+        );
+        assert!(
+            result.is_err(),
+            "the background process holds the output open"
+        );
+        let pid = std::fs::read_to_string(&pid_file).expect("pid written");
+        let alive = || {
+            Command::new("kill")
+                .args(["-0", pid.trim()])
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while alive() && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+        }
+        assert!(!alive(), "background process {} survived", pid.trim());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_pipe_command_cancelled() {
+        let result = run_pipe_command("sleep 5", "", PIPE_TIMEOUT, &AtomicBool::new(true));
+        let error = result.expect_err("command should be cancelled").to_string();
+        assert!(error.contains("cancelled"), "{error}");
+    }
+
+    #[test]
+    fn test_insert_below_current_line() {
+        let text = "one\ntwo\nthree";
+        let edit = insert_below(text, 5..5, "out\n");
+        assert_eq!(edit.range, 7..7);
+        assert_eq!(edit.new_text, "\nout");
+        assert_eq!(insert_below(text, 0..8, "out").range, 7..7);
+    }
+
+    #[test]
+    fn test_insert_below_last_line_and_crlf() {
+        assert_eq!(insert_below("one", 1..1, "out").new_text, "\nout");
+        let edit = insert_below("one\r\ntwo\r\n", 0..0, "out\r\n");
+        assert_eq!(edit.range, 3..3);
+        assert_eq!(edit.new_text, "\r\nout");
+    }
+
+    #[test]
+    fn test_fit_replacement_follows_replaced_line_break() {
+        assert_eq!(fit_replacement("abc", "ABC\n"), "ABC");
+        assert_eq!(fit_replacement("abc", "ABC\r\n"), "ABC");
+        assert_eq!(fit_replacement("abc\n", "ABC\n"), "ABC\n");
+        assert_eq!(fit_replacement("abc", "ABC"), "ABC");
+    }
+}
@@ -0,0 +1,727 @@
+use crate::fulgur::{
+    files::indentation::Indentation,
+    languages::{comments::has_comments, supported_languages::SupportedLanguage},
+    utils::text_diff::line_and_column,
+};
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use serde_json::{Map, Value, ser::PrettyFormatter};
+use std::fmt;
+
+/// A structured data format the data commands understand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl DataFormat {
+    /// Every data format, in menu order
+    pub const ALL: [DataFormat; 3] = [Self::Json, Self::Yaml, Self::Toml];
+
+    /// Get the data format of a language
+    ///
+    /// ### Arguments
+    /// - `language`: The language of the tab
+    ///
+    /// ### Returns
+    /// - `Some(DataFormat)`: The matching format
+    /// - `None`: The language is not a structured data format
+    #[must_use]
+    pub fn from_language(language: SupportedLanguage) -> Option<Self> {
+        match language {
+            SupportedLanguage::Json => Some(Self::Json),
+            SupportedLanguage::Yaml => Some(Self::Yaml),
+            SupportedLanguage::Toml => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Get the language of the format
+    ///
+    /// ### Returns
+    /// - `SupportedLanguage`: The language to give tabs holding this format
+    #[must_use]
+    pub fn language(self) -> SupportedLanguage {
+        match self {
+            Self::Json => SupportedLanguage::Json,
+            Self::Yaml => SupportedLanguage::Yaml,
+            Self::Toml => SupportedLanguage::Toml,
+        }
+    }
+
+    /// Get the file extension of the format
+    ///
+    /// ### Returns
+    /// - `&'static str`: The extension, without the dot
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+        }
+    }
+
+    /// Get the display name of the format
+    ///
+    /// ### Returns
+    /// - `&'static str`: The name
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Yaml => "YAML",
+            Self::Toml => "TOML",
+        }
+    }
+}
+
+/// A parse error located in the source text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataError {
+    pub message: String,
+    pub line: u32,   // Zero-based line of the error
+    pub column: u32, // Zero-based column of the error on its line, in characters
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (line {}, column {})",
+            self.message,
+            self.line + 1,
+            self.column + 1
+        )
+    }
+}
+
+impl DataError {
+    /// Build an error located at a byte offset of the source
+    ///
+    /// ### Arguments
+    /// - `text`: The source text
+    /// - `offset`: The byte offset of the error, clamped to the text
+    /// - `message`: The description of the error
+    ///
+    /// ### Returns
+    /// - `DataError`: The error with its line and character column
+    fn at_offset(text: &str, offset: usize, message: String) -> Self {
+        let (line, column) = line_and_column(text, offset);
+        Self {
+            message,
+            line,
+            column,
+        }
+    }
+
+    /// Build an error located at a one-based line and byte column of the source
+    ///
+    /// ### Arguments
+    /// - `text`: The source text
+    /// - `line`: The one-based line
+    /// - `column`: The one-based byte column on that line
+    /// - `message`: The description of the error
+    ///
+    /// ### Returns
+    /// - `DataError`: The error with its line and character column
+    fn at_line_column(text: &str, line: usize, column: usize, message: String) -> Self {
+        let line_start = text
+            .split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(str::len)
+            .sum::<usize>();
+        Self::at_offset(text, line_start + column.saturating_sub(1), message)
+    }
+}
+
+/// Locate a JSON parse error in its source
+///
+/// ### Arguments
+/// - `text`: The source text
+/// - `error`: The JSON error
+///
+/// ### Returns
+/// - `DataError`: The error with its line and character column
+fn json_error(text: &str, error: &serde_json::Error) -> DataError {
+    DataError::at_line_column(text, error.line(), error.column(), without_location(error))
+}
+
+/// Locate a YAML parse error in its source
+///
+/// ### Arguments
+/// - `text`: The source text
+/// - `error`: The YAML error
+///
+/// ### Returns
+/// - `DataError`: The error with its line and character column
+fn yaml_error(text: &str, error: &serde_yaml_ng::Error) -> DataError {
+    let offset = error.location().map_or(0, |location| location.index());
+    DataError::at_offset(text, offset, without_location(error))
+}
+
+/// Locate a TOML parse error in its source
+///
+/// ### Arguments
+/// - `text`: The source text
+/// - `error`: The TOML error
+///
+/// ### Returns
+/// - `DataError`: The error with its line and character column
+fn toml_error(text: &str, error: &toml::de::Error) -> DataError {
+    let offset = error.span().map_or(0, |span| span.start);
+    DataError::at_offset(text, offset, error.message().to_string())
+}
+
+/// Strip the trailing "at line N column M" a parser appends to its messages
+///
+/// ### Arguments
+/// - `error`: The parser error
+///
+/// ### Returns
+/// - `String`: The message without its location
+fn without_location(error: &impl fmt::Display) -> String {
+    let message = error.to_string();
+    message
+        .rsplit_once(" at line ")
+        .map_or(message.as_str(), |(head, _)| head)
+        .to_string()
+}
+
+/// Check that a document is valid
+///
+/// ### Description
+/// Each format is checked against its own data model, so YAML documents with
+/// non-string keys or tags and TOML documents with dates are valid even though
+/// they cannot be converted.
+///
+/// ### Arguments
+/// - `format`: The format of the document
+/// - `text`: The document
+///
+/// ### Errors
+/// - `DataError`: The first parse error, with its position
+pub fn validate(format: DataFormat, text: &str) -> std::result::Result<(), DataError> {
+    match format {
+        DataFormat::Json => serde_json::from_str::<Value>(text)
+            .map(|_| ())
+            .map_err(|e| json_error(text, &e)),
+        DataFormat::Yaml => serde_yaml_ng::from_str::<serde_yaml_ng::Value>(text)
+            .map(|_| ())
+            .map_err(|e| yaml_error(text, &e)),
+        DataFormat::Toml => text
+            .parse::<toml::Table>()
+            .map(|_| ())
+            .map_err(|e| toml_error(text, &e)),
+    }
+}
+
+/// Parse a document into a JSON value, keeping the order of its keys
+///
+/// ### Arguments
+/// - `format`: The format of the document
+/// - `text`: The document
+///
+/// ### Returns
+/// - `Ok(Value)`: The parsed document
+///
+/// ### Errors
+/// - When the document is invalid, or holds values JSON cannot represent, such
+///   as YAML tags or TOML dates
+fn parse(format: DataFormat, text: &str) -> Result<Value> {
+    let invalid = |error: DataError| anyhow!("Invalid {}: {error}", format.label());
+    match format {
+        DataFormat::Json => serde_json::from_str(text).map_err(|e| invalid(json_error(text, &e))),
+        DataFormat::Yaml => {
+            serde_yaml_ng::from_str(text).map_err(|e| invalid(yaml_error(text, &e)))
+        }
+        DataFormat::Toml => toml_to_json(toml::Value::Table(parse_toml(text)?)),
+    }
+}
+
+/// Parse a TOML document into a table, keeping its dates and the order of its keys
+///
+/// ### Arguments
+/// - `text`: The document
+///
+/// ### Returns
+/// - `Ok(toml::Table)`: The parsed document
+///
+/// ### Errors
+/// - When the document is invalid
+fn parse_toml(text: &str) -> Result<toml::Table> {
+    text.parse::<toml::Table>()
+        .map_err(|e| anyhow!("Invalid TOML: {}", toml_error(text, &e)))
+}
+
+/// Convert a TOML value into a JSON value
+///
+/// ### Arguments
+/// - `value`: The TOML value
+///
+/// ### Returns
+/// - `Ok(Value)`: The JSON value
+///
+/// ### Errors
+/// - When the value holds a date or time, which JSON and YAML would only keep as a string
+fn toml_to_json(value: toml::Value) -> Result<Value> {
+    Ok(match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => {
+            return Err(anyhow!(
+                "The TOML date {datetime} would become a plain string; convert a copy without dates"
+            ));
+        }
+        toml::Value::Array(items) => {
+            Value::Array(items.into_iter().map(toml_to_json).collect::<Result<_>>()?)
+        }
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| Ok((key, toml_to_json(value)?)))
+                .collect::<Result<_>>()?,
+        ),
+    })
+}
+
+/// Sort the keys of every object of a value, recursively
+///
+/// ### Arguments
+/// - `value`: The value to sort in place
+fn sort_keys_recursively(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(sort_keys_recursively),
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            *map = entries
+                .into_iter()
+                .map(|(key, mut value)| {
+                    sort_keys_recursively(&mut value);
+                    (key, value)
+                })
+                .collect::<Map<String, Value>>();
+        }
+        _ => {}
+    }
+}
+
+/// Sort the keys of a TOML document, keeping its comments and formatting
+///
+/// ### Description
+/// Keys are sorted within each table, tables are sorted under their parent, and
+/// comments move along with the key or table header they precede. The comment
+/// above the first key is taken as the header of the document and stays at the
+/// top. Arrays keep their order.
+///
+/// ### Arguments
+/// - `text`: The TOML document
+///
+/// ### Returns
+/// - `Ok(String)`: The sorted document
+///
+/// ### Errors
+/// - When the document is invalid
+fn sort_toml_keys(text: &str) -> Result<String> {
+    let mut document = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| anyhow!("Invalid TOML: {}", e.message()))?;
+    let root = document.as_table_mut();
+    let header = root.iter_mut().next().and_then(|(mut key, _)| {
+        let decor = key.leaf_decor_mut();
+        let header = decor.prefix().cloned();
+        decor.set_prefix("");
+        header
+    });
+    sort_toml_table(root);
+    if let Some(header) = header
+        && let Some((mut key, _)) = root.iter_mut().next()
+    {
+        let decor = key.leaf_decor_mut();
+        let prefix = decor.prefix().cloned().unwrap_or_default();
+        decor.set_prefix(format!(
+            "{}{}",
+            header.as_str().unwrap_or_default(),
+            prefix.as_str().unwrap_or_default()
+        ));
+    }
+    Ok(document.to_string())
+}
+
+/// Sort a TOML table and everything nested in it
+///
+/// ### Description
+/// The document position of each table is reset, so tables are written in the
+/// sorted order of their keys rather than where they were parsed.
+///
+/// ### Arguments
+/// - `table`: The table to sort in place
+fn sort_toml_table(table: &mut toml_edit::Table) {
+    table.set_position(None);
+    table.sort_values();
+    for (_, item) in table.iter_mut() {
+        match item {
+            toml_edit::Item::Table(table) => sort_toml_table(table),
+            toml_edit::Item::ArrayOfTables(tables) => {
+                tables.iter_mut().for_each(sort_toml_table);
+            }
+            toml_edit::Item::Value(value) => sort_toml_value(value),
+            toml_edit::Item::None => {}
+        }
+    }
+}
+
+/// Sort the inline tables of a TOML value, recursively
+///
+/// ### Arguments
+/// - `value`: The value to sort in place
+fn sort_toml_value(value: &mut toml_edit::Value) {
+    match value {
+        toml_edit::Value::InlineTable(table) => {
+            table.sort_values();
+            table.fmt();
+            table
+                .iter_mut()
+                .for_each(|(_, value)| sort_toml_value(value));
+        }
+        toml_edit::Value::Array(items) => items.iter_mut().for_each(sort_toml_value),
+        _ => {}
+    }
+}
+
+/// Serialize a value in a format
+///
+/// ### Description
+/// JSON is indented with the tab's indentation. YAML keeps the emitter's two-space
+/// indentation, since the nesting of sequence items depends on it, and TOML tables
+/// are not indented.
+///
+/// ### Arguments
+/// - `format`: The output format
+/// - `value`: The value to write
+/// - `indentation`: The indentation of the tab
+///
+/// ### Returns
+/// - `Ok(String)`: The serialized document, ending with a newline
+///
+/// ### Errors
+/// - When the value cannot be represented in the format (e.g. `null` or a top-level array in TOML)
+pub fn write(format: DataFormat, value: &Value, indentation: Indentation) -> Result<String> {
+    match format {
+        DataFormat::Json => {
+            let indent = if indentation.use_spaces {
+                " ".repeat(indentation.width)
+            } else {
+                "\t".to_string()
+            };
+            let mut output = Vec::new();
+            let mut serializer = serde_json::Serializer::with_formatter(
+                &mut output,
+                PrettyFormatter::with_indent(indent.as_bytes()),
+            );
+            value
+                .serialize(&mut serializer)
+                .map_err(|e| anyhow!("Failed to write JSON: {e}"))?;
+            let mut text =
+                String::from_utf8(output).map_err(|e| anyhow!("Failed to write JSON: {e}"))?;
+            text.push('\n');
+            Ok(text)
+        }
+        DataFormat::Yaml => {
+            serde_yaml_ng::to_string(value).map_err(|e| anyhow!("Failed to write YAML: {e}"))
+        }
+        DataFormat::Toml => {
+            if !value.is_object() {
+                return Err(anyhow!("TOML documents must be a table at the top level"));
+            }
+            toml::to_string_pretty(value).map_err(|e| anyhow!("Failed to write TOML: {e}"))
+        }
+    }
+}
+
+/// A structured data command that rewrites the document in place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataRewrite {
+    PrettyPrint,
+    Minify,
+    SortKeys,
+}
+
+impl DataRewrite {
+    /// Get the display name of the rewrite
+    ///
+    /// ### Returns
+    /// - `&'static str`: The name, as used in messages
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::PrettyPrint => "Pretty-printing",
+            Self::Minify => "Minifying",
+            Self::SortKeys => "Sorting the keys of",
+        }
+    }
+}
+
+/// Rewrite a document in its own format
+///
+/// ### Description
+/// JSON can always be rewritten, and sorting the keys of TOML keeps its comments
+/// and formatting. Other YAML and TOML rewrites go through a plain data model
+/// that has no place for comments, so they are refused when the document has
+/// any. Minified YAML is written as a single flow-style line, and minified TOML
+/// drops the multi-line layout of arrays and strings, since TOML tables cannot
+/// be written inline.
+///
+/// ### Arguments
+/// - `format`: The format of the document
+/// - `text`: The document
+/// - `rewrite`: The rewrite to perform
+/// - `indentation`: The indentation of the tab
+///
+/// ### Returns
+/// - `Ok(String)`: The rewritten document
+///
+/// ### Errors
+/// - When the document is invalid, or holds comments the rewrite would drop
+pub fn rewrite(
+    format: DataFormat,
+    text: &str,
+    rewrite: DataRewrite,
+    indentation: Indentation,
+) -> Result<String> {
+    match (format, rewrite) {
+        (DataFormat::Json, DataRewrite::Minify) => serde_json::to_string(&parse(format, text)?)
+            .map_err(|e| anyhow!("Failed to write JSON: {e}")),
+        (DataFormat::Json, _) => {
+            let mut value = parse(format, text)?;
+            if rewrite == DataRewrite::SortKeys {
+                sort_keys_recursively(&mut value);
+            }
+            write(format, &value, indentation)
+        }
+        (DataFormat::Toml, DataRewrite::SortKeys) => sort_toml_keys(text),
+        _ if has_comments(format.language(), text) => Err(anyhow!(
+            "{} {} would drop the comments of the document; rewrite a copy without them",
+            rewrite.label(),
+            format.label()
+        )),
+        (DataFormat::Toml, DataRewrite::Minify) => {
+            toml::to_string(&parse_toml(text)?).map_err(|e| anyhow!("Failed to write TOML: {e}"))
+        }
+        (DataFormat::Toml, _) => toml::to_string_pretty(&parse_toml(text)?)
+            .map_err(|e| anyhow!("Failed to write TOML: {e}")),
+        (DataFormat::Yaml, DataRewrite::Minify) => serde_json::to_string(&parse(format, text)?)
+            .map(|line| line + "\n")
+            .map_err(|e| anyhow!("Failed to write YAML: {e}")),
+        (DataFormat::Yaml, _) => {
+            let mut value = parse(format, text)?;
+            if rewrite == DataRewrite::SortKeys {
+                sort_keys_recursively(&mut value);
+            }
+            write(format, &value, indentation)
+        }
+    }
+}
+
+/// Convert a document from one format to another
+///
+/// ### Description
+/// Conversions that would lose part of the document are refused: comments have
+/// no place in JSON and are not carried over by the YAML and TOML writers, and
+/// TOML dates would become plain strings.
+///
+/// ### Arguments
+/// - `from`: The format of the document
+/// - `to`: The format to convert to
+/// - `text`: The document
+/// - `indentation`: The indentation used for JSON output
+///
+/// ### Returns
+/// - `Ok(String)`: The converted document
+///
+/// ### Errors
+/// - When the document is invalid, holds comments, or has values the target format cannot hold
+pub fn convert(
+    from: DataFormat,
+    to: DataFormat,
+    text: &str,
+    indentation: Indentation,
+) -> Result<String> {
+    let value = parse(from, text)?;
+    if has_comments(from.language(), text) {
+        return Err(anyhow!(
+            "The comments of the {} document would be lost; convert a copy without them",
+            from.label()
+        ));
+    }
+    write(to, &value, indentation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataFormat, DataRewrite, convert, rewrite, validate};
+    use crate::fulgur::files::indentation::{Indentation, IndentationSource};
+
+    const TWO_SPACES: Indentation = Indentation {
+        width: 2,
+        use_spaces: true,
+        source: IndentationSource::Settings,
+    };
+
+    #[test]
+    fn validate_reports_the_error_position() {
+        let json = "{\n  \"a\": 1,\n  \"b\": ]\n}";
+        let error = validate(DataFormat::Json, json).err();
+        assert_eq!(error.as_ref().map(|e| (e.line, e.column)), Some((2, 7)));
+
+        let toml = "name = \"x\"\nport = = 1\n";
+        let error = validate(DataFormat::Toml, toml).err();
+        assert_eq!(error.as_ref().map(|e| e.line), Some(1));
+
+        let yaml = "a: 1\nb: [1, 2\n";
+        assert!(validate(DataFormat::Yaml, yaml).is_err());
+        assert!(validate(DataFormat::Yaml, "? [1, 2]\n: pair\n").is_ok());
+    }
+
+    #[test]
+    fn pretty_print_uses_the_tab_indentation_and_keeps_key_order() {
+        let json = "{\"b\":1,\"a\":[true,null]}";
+        let tabs = Indentation {
+            use_spaces: false,
+            ..TWO_SPACES
+        };
+        assert_eq!(
+            rewrite(DataFormat::Json, json, DataRewrite::PrettyPrint, tabs).ok(),
+            Some("{\n\t\"b\": 1,\n\t\"a\": [\n\t\ttrue,\n\t\tnull\n\t]\n}\n".to_string())
+        );
+        assert_eq!(
+            rewrite(
+                DataFormat::Json,
+                "{ \"b\": 1 }\n",
+                DataRewrite::Minify,
+                TWO_SPACES
+            )
+            .ok(),
+            Some("{\"b\":1}".to_string())
+        );
+    }
+
+    #[test]
+    fn sort_keys_sorts_nested_objects() {
+        let json = "{\"b\":{\"z\":1,\"y\":2},\"a\":0}";
+        assert_eq!(
+            rewrite(DataFormat::Json, json, DataRewrite::SortKeys, TWO_SPACES).ok(),
+            Some("{\n  \"a\": 0,\n  \"b\": {\n    \"y\": 2,\n    \"z\": 1\n  }\n}\n".to_string())
+        );
+    }
+
+    #[test]
+    fn sort_keys_keeps_toml_comments_and_dates() {
+        let toml = "# top\nb = 1\na = 2 # two\nwhen = 1979-05-27\n\n[z]\nk = 1\n\n# why\n[y]\nk = { d = 1, c = 2 }\n";
+        assert_eq!(
+            rewrite(DataFormat::Toml, toml, DataRewrite::SortKeys, TWO_SPACES).ok(),
+            Some(
+                "# top\na = 2 # two\nb = 1\nwhen = 1979-05-27\n\n# why\n[y]\nk = { c = 2, d = 1 }\n\n[z]\nk = 1\n"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn rewrites_yaml_and_toml_without_comments() {
+        let yaml = "b: [1, 2]\na: {d: x, c: y}\n";
+        assert_eq!(
+            rewrite(DataFormat::Yaml, yaml, DataRewrite::SortKeys, TWO_SPACES).ok(),
+            Some("a:\n  c: y\n  d: x\nb:\n- 1\n- 2\n".to_string())
+        );
+        assert_eq!(
+            rewrite(DataFormat::Yaml, yaml, DataRewrite::Minify, TWO_SPACES).ok(),
+            Some("{\"b\":[1,2],\"a\":{\"d\":\"x\",\"c\":\"y\"}}\n".to_string())
+        );
+
+        let toml = "when = 1979-05-27\nports = [\n    80,\n    443,\n]\n";
+        assert_eq!(
+            rewrite(DataFormat::Toml, toml, DataRewrite::Minify, TWO_SPACES).ok(),
+            Some("when = 1979-05-27\nports = [80, 443]\n".to_string())
+        );
+        assert_eq!(
+            rewrite(
+                DataFormat::Toml,
+                "when = 1979-05-27\nports = [80, 443]\n",
+                DataRewrite::PrettyPrint,
+                TWO_SPACES
+            )
+            .ok(),
+            Some(toml.to_string())
+        );
+    }
+
+    #[test]
+    fn lossy_rewrites_are_refused() {
+        let yaml = "b: 1 # one\na: 0\n";
+        assert!(rewrite(DataFormat::Yaml, yaml, DataRewrite::SortKeys, TWO_SPACES).is_err());
+        assert!(rewrite(DataFormat::Yaml, yaml, DataRewrite::Minify, TWO_SPACES).is_err());
+        assert!(
+            rewrite(
+                DataFormat::Toml,
+                "# one\na = 1\n",
+                DataRewrite::PrettyPrint,
+                TWO_SPACES
+            )
+            .is_err()
+        );
+        assert!(convert(DataFormat::Yaml, DataFormat::Json, yaml, TWO_SPACES).is_err());
+        assert!(
+            convert(
+                DataFormat::Yaml,
+                DataFormat::Json,
+                "a: \"# not a comment\"\n",
+                TWO_SPACES
+            )
+            .is_ok()
+        );
+        assert!(
+            convert(
+                DataFormat::Toml,
+                DataFormat::Json,
+                "when = 1979-05-27T07:32:00Z\n",
+                TWO_SPACES
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn convert_between_formats() {
+        let toml = "title = \"demo\"\n\n[server]\nport = 8080\n";
+        let json = convert(DataFormat::Toml, DataFormat::Json, toml, TWO_SPACES).ok();
+        assert_eq!(
+            json.as_deref(),
+            Some("{\n  \"title\": \"demo\",\n  \"server\": {\n    \"port\": 8080\n  }\n}\n")
+        );
+
+        let yaml = convert(
+            DataFormat::Json,
+            DataFormat::Yaml,
+            "{\"a\":[1,2]}",
+            TWO_SPACES,
+        )
+        .ok();
+        assert_eq!(yaml.as_deref(), Some("a:\n- 1\n- 2\n"));
+
+        let back = convert(
+            DataFormat::Yaml,
+            DataFormat::Toml,
+            "a: 1\nb:\n  c: x\n",
+            TWO_SPACES,
+        );
+        assert_eq!(back.ok().as_deref(), Some("a = 1\n\n[b]\nc = \"x\"\n"));
+
+        assert!(convert(DataFormat::Json, DataFormat::Toml, "[1]", TWO_SPACES).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// A `file:line:column` reference found in a line of task output
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileReference {
+    pub range: Range<usize>, // Byte range of the reference in the line
+    pub path: PathBuf,       // Resolved against the working directory of the task
+    pub line: u32,           // 1-based
+    pub column: Option<u32>, // 1-based
+}
+
+/// How serious a problem reported by a task is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProblemSeverity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// An error or warning reported by a task at a position in a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub path: PathBuf,
+    pub line: u32,           // 1-based
+    pub column: Option<u32>, // 1-based
+    pub severity: ProblemSeverity,
+    pub message: String,
+}
+
+/// Characters that cannot be part of a reference, such as quotes and brackets around it
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '"' | '\'' | '`' | '(' | ')' | '[' | ']' | '<' | '>' | ','
+        )
+}
+
+/// Parse a positive line or column number
+fn parse_position(text: &str) -> Option<u32> {
+    if text.is_empty() || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok().filter(|position| *position > 0)
+}
+
+/// Check whether text looks like a file path rather than a host, a time or a label
+fn looks_like_path(path: &str) -> bool {
+    if path.is_empty() || path.contains("://") {
+        return false;
+    }
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    path.contains(['/', '\\'])
+        || name.rsplit_once('.').is_some_and(|(stem, extension)| {
+            !stem.is_empty() && extension.chars().any(|c| c.is_ascii_alphabetic())
+        })
+}
+
+/// Resolve a path printed by a task against its working directory
+fn resolve(path: &str, cwd: &Path) -> PathBuf {
+    let path = Path::new(path.strip_prefix("./").unwrap_or(path));
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        cwd.join(path)
+    }
+}
+
+/// Parse one `path:line` or `path:line:column` token
+///
+/// ### Arguments
+/// - `token`: The token, without surrounding delimiters
+///
+/// ### Returns
+/// - `Some((usize, &str, u32, Option<u32>))`: The length of the reference in the token, the
+///   path, the line and the column
+/// - `None`: The token is not a reference
+fn parse_token(token: &str) -> Option<(usize, &str, u32, Option<u32>)> {
+    let trimmed = token.trim_end_matches([':', '.', ';']);
+    let mut parts = trimmed.rsplitn(3, ':');
+    let last = parts.next()?;
+    let middle = parts.next()?;
+    let (path, line, column) = match (parse_position(middle), parts.next()) {
+        (Some(line), Some(path)) if parse_position(last).is_some() => {
+            (path, line, parse_position(last))
+        }
+        _ => (trimmed.rsplit_once(':')?.0, parse_position(last)?, None),
+    };
+    looks_like_path(path).then_some((trimmed.len(), path, line, column))
+}
+
+/// Find the Python traceback reference `File "path", line N` in a line
+fn find_python_reference(line: &str, cwd: &Path) -> Option<FileReference> {
+    let start = line.find("File \"")? + "File ".len();
+    let path_end = start + 1 + line[start + 1..].find('"')?;
+    let rest = line[path_end + 1..].strip_prefix(", line ")?;
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let number = parse_position(&rest[..digits])?;
+    Some(FileReference {
+        range: start..path_end + 1 + ", line ".len() + digits,
+        path: resolve(&line[start + 1..path_end], cwd),
+        line: number,
+        column: None,
+    })
+}
+
+/// Find the file references in a line of task output
+///
+/// ### Description
+/// Recognizes `path:line` and `path:line:column` (compilers, linters, test
+/// runners) and Python's `File "path", line N`. Whether the files exist is left
+/// to the caller.
+///
+/// ### Arguments
+/// - `line`: The line, without escape sequences
+/// - `cwd`: The working directory of the task, for relative paths
+///
+/// ### Returns
+/// - `Vec<FileReference>`: The references, in order
+#[must_use]
+pub fn find_references(line: &str, cwd: &Path) -> Vec<FileReference> {
+    if let Some(reference) = find_python_reference(line, cwd) {
+        return vec![reference];
+    }
+    let mut references = Vec::new();
+    let mut start = None;
+    for (index, c) in line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')))
+    {
+        match (start, is_delimiter(c)) {
+            (None, false) => start = Some(index),
+            (Some(token_start), true) => {
+                start = None;
+                if let Some((length, path, number, column)) = parse_token(&line[token_start..index])
+                {
+                    references.push(FileReference {
+                        range: token_start..token_start + length,
+                        path: resolve(path, cwd),
+                        line: number,
+                        column,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    references
+}
+
+/// Split a compiler message into its severity and text
+///
+/// ### Arguments
+/// - `message`: e.g. `error[E0308]: mismatched types` or `warning: unused variable`
+///
+/// ### Returns
+/// - `Some((ProblemSeverity, &str))`: The severity and the message text
+/// - `None`: The message does not start with a severity
+fn split_severity(message: &str) -> Option<(ProblemSeverity, &str)> {
+    let (label, text) = message.split_once(": ")?;
+    let label = label.split('[').next().unwrap_or(label).trim();
+    let severity = match label {
+        "error" | "fatal error" => ProblemSeverity::Error,
+        "warning" => ProblemSeverity::Warning,
+        "note" | "help" | "info" => ProblemSeverity::Note,
+        _ => return None,
+    };
+    Some((severity, text.trim()))
+}
+
+/// Collect the errors and warnings reported in task output
+///
+/// ### Description
+/// Two shapes are recognized: a `severity: message` header followed by a
+/// `--> path:line:column` line (rustc, cargo), and `path:line[:column]: message`
+/// lines (gcc, clang, go, eslint's unix format, pytest). A message without a
+/// severity in the second shape is an error.
+///
+/// ### Arguments
+/// - `lines`: The lines of output, without escape sequences
+/// - `cwd`: The working directory of the task, for relative paths
+///
+/// ### Returns
+/// - `Vec<Problem>`: The problems, in output order
+#[must_use]
+pub fn parse_problems<'a>(lines: impl IntoIterator<Item = &'a str>, cwd: &Path) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut header: Option<(ProblemSeverity, String)> = None;
+    for line in lines {
+        let trimmed = line.trim_start();
+        if let Some(location) = trimmed.strip_prefix("--> ") {
+            if let (Some((severity, message)), Some(reference)) = (
+                header.take(),
+                find_references(location, cwd).into_iter().next(),
+            ) {
+                problems.push(Problem {
+                    path: reference.path,
+                    line: reference.line,
+                    column: reference.column,
+                    severity,
+                    message,
+                });
+            }
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace)
+            && let Some((severity, message)) = split_severity(line)
+        {
+            header = Some((severity, message.to_string()));
+            continue;
+        }
+        let Some(reference) = find_references(trimmed, cwd).into_iter().next() else {
+            continue;
+        };
+        if reference.range.start != 0 {
+            continue;
+        }
+        let Some(rest) = trimmed[reference.range.end..]
+            .strip_prefix(':')
+            .map(str::trim)
+            .filter(|rest| !rest.is_empty())
+        else {
+            continue;
+        };
+        let (severity, message) = split_severity(rest).unwrap_or((ProblemSeverity::Error, rest));
+        problems.push(Problem {
+            path: reference.path,
+            line: reference.line,
+            column: reference.column,
+            severity,
+            message: message.to_string(),
+        });
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cwd() -> PathBuf {
+        PathBuf::from("/project")
+    }
+
+    #[test]
+    fn test_find_references_with_and_without_column() {
+        let references = find_references("  --> src/main.rs:12:5", &cwd());
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].range, 6..22);
+        assert_eq!(references[0].path, PathBuf::from("/project/src/main.rs"));
+        assert_eq!(references[0].line, 12);
+        assert_eq!(references[0].column, Some(5));
+
+        let references = find_references("tests/test_app.py:40: AssertionError", &cwd());
+        assert_eq!(references[0].range, 0..20);
+        assert_eq!(references[0].line, 40);
+        assert_eq!(references[0].column, None);
+    }
+
+    #[test]
+    fn test_find_references_ignores_hosts_urls_and_times() {
+        assert!(find_references("listening on localhost:8080", &cwd()).is_empty());
+        assert!(find_references("see https://example.com:443/a.html", &cwd()).is_empty());
+        assert!(find_references("finished at 12:30:15", &cwd()).is_empty());
+        assert!(find_references("version 1.2:3", &cwd()).is_empty());
+    }
+
+    #[test]
+    fn test_find_references_absolute_and_quoted() {
+        let references = find_references("at (/tmp/app/index.js:3:14)", &cwd());
+        assert_eq!(references[0].path, PathBuf::from("/tmp/app/index.js"));
+        assert_eq!(references[0].range, 4..26);
+        assert_eq!(references[0].column, Some(14));
+    }
+
+    #[test]
+    fn test_find_references_python_traceback() {
+        let line = "  File \"app/main.py\", line 7, in <module>";
+        let references = find_references(line, &cwd());
+        assert_eq!(references.len(), 1);
+        assert_eq!(
+            &line[references[0].range.clone()],
+            "\"app/main.py\", line 7"
+        );
+        assert_eq!(references[0].path, PathBuf::from("/project/app/main.py"));
+        assert_eq!(references[0].line, 7);
+    }
+
+    #[test]
+    fn test_parse_problems_rustc() {
+        let output = [
+            "error[E0308]: mismatched types",
+            " --> src/lib.rs:4:9",
+            "  |",
+            "warning: unused variable: `x`",
+            "  --> src/main.rs:2:9",
+            "error: could not compile `demo` (lib) due to 1 previous error",
+        ];
+        let problems = parse_problems(output, &cwd());
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].severity, ProblemSeverity::Error);
+        assert_eq!(problems[0].message, "mismatched types");
+        assert_eq!(problems[0].path, PathBuf::from("/project/src/lib.rs"));
+        assert_eq!((problems[0].line, problems[0].column), (4, Some(9)));
+        assert_eq!(problems[1].severity, ProblemSeverity::Warning);
+        assert_eq!(problems[1].message, "unused variable: `x`");
+    }
+
+    #[test]
+    fn test_parse_problems_gcc_and_go() {
+        let output = [
+            "main.c:3:5: warning: implicit declaration of function 'foo'",
+            "./main.go:10:2: undefined: bar",
+            "    main.c:9:1: note: declared here",
+            "make: *** [all] Error 1",
+        ];
+        let problems = parse_problems(output, &cwd());
+        assert_eq!(problems.len(), 3);
+        assert_eq!(problems[0].severity, ProblemSeverity::Warning);
+        assert_eq!(
+            problems[0].message,
+            "implicit declaration of function 'foo'"
+        );
+        assert_eq!(problems[1].severity, ProblemSeverity::Error);
+        assert_eq!(problems[1].path, PathBuf::from("/project/main.go"));
+        assert_eq!(problems[1].message, "undefined: bar");
+        assert_eq!(problems[2].severity, ProblemSeverity::Note);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::fulgur::utils::shell_pipe::{shell_command, signal_process_group};
+use anyhow::anyhow;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Interval at which a running task is checked for exit and for a stop request
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Time a stopped task gets to exit after being asked to terminate, before it is killed
+const STOP_GRACE: Duration = Duration::from_secs(1);
+
+/// Time the output of an exited task is still read, in case a child it left behind holds the pipes open
+const DRAIN_GRACE: Duration = Duration::from_millis(500);
+
+/// What a running task reports
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TaskEvent {
+    /// A line of standard output or standard error, escape sequences included
+    Output(String),
+    /// The task exited, with its exit code unless a signal ended it
+    Exited(Option<i32>),
+    /// The task could no longer be waited for
+    Failed(String),
+}
+
+/// A task running as a child process of the shell
+pub struct TaskProcess {
+    stop: Arc<AtomicBool>,
+}
+
+/// Keep the text a terminal would show for one line of output
+///
+/// ### Description
+/// Progress bars redraw a line by returning to its start with `\r`; only the
+/// text after the last carriage return remains visible.
+///
+/// ### Arguments
+/// - `bytes`: The line, with or without its line break
+///
+/// ### Returns
+/// - `String`: The visible text of the line
+fn visible_line(bytes: &[u8]) -> String {
+    let line = String::from_utf8_lossy(bytes);
+    let line = line.trim_end_matches(['\n', '\r']);
+    line.rsplit('\r').next().unwrap_or(line).to_string()
+}
+
+/// Forward each line of a child output stream on its own thread
+///
+/// ### Arguments
+/// - `stream`: The stream to read
+/// - `on_event`: The callback receiving the lines
+///
+/// ### Returns
+/// - `JoinHandle<()>`: The thread, finishing at end of stream
+fn forward_lines<R, F>(stream: Option<R>, on_event: F) -> JoinHandle<()>
+where
+    R: Read + Send + 'static,
+    F: Fn(TaskEvent) + Send + 'static,
+{
+    thread::spawn(move || {
+        let Some(stream) = stream else {
+            return;
+        };
+        let mut reader = BufReader::new(stream);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(_) => on_event(TaskEvent::Output(visible_line(&line))),
+                Err(e) => {
+                    log::warn!("Failed to read the output of a task: {e}");
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// Wait until the output threads are done, or until `DRAIN_GRACE` has passed
+fn drain(readers: &[JoinHandle<()>]) {
+    let started = Instant::now();
+    while started.elapsed() < DRAIN_GRACE && !readers.iter().all(JoinHandle::is_finished) {
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Wait for a task on its own thread, stopping it on request
+///
+/// ### Arguments
+/// - `child`: The running task
+/// - `readers`: The threads forwarding its output
+/// - `stop`: The flag set to stop the task
+/// - `on_event`: The callback receiving the exit
+fn supervise<F>(mut child: Child, readers: &[JoinHandle<()>], stop: &AtomicBool, on_event: &F)
+where
+    F: Fn(TaskEvent),
+{
+    let mut stop_requested: Option<Instant> = None;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                drain(readers);
+                on_event(TaskEvent::Exited(status.code()));
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                signal_process_group(&mut child, true);
+                on_event(TaskEvent::Failed(format!(
+                    "Failed to wait for the task: {e}"
+                )));
+                return;
+            }
+        }
+        match stop_requested {
+            None if stop.load(Ordering::Acquire) => {
+                signal_process_group(&mut child, false);
+                stop_requested = Some(Instant::now());
+            }
+            Some(requested) if requested.elapsed() >= STOP_GRACE => {
+                signal_process_group(&mut child, true);
+                stop_requested = Some(Instant::now());
+            }
+            _ => {}
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+impl TaskProcess {
+    /// Start a command line through the shell, reporting its output line by line
+    ///
+    /// ### Description
+    /// The task gets no standard input, and is asked to color its output as it
+    /// would in a terminal. It runs in its own process group on Unix, so stopping
+    /// it also stops the processes it started. `on_event` is called from helper
+    /// threads: once per line of output, then once when the task exits.
+    ///
+    /// ### Arguments
+    /// - `command`: The command line, e.g. `cargo check`
+    /// - `cwd`: The working directory of the task
+    /// - `on_event`: The callback receiving the output and the exit of the task
+    ///
+    /// ### Errors
+    /// Returns an error if the shell cannot be started.
+    ///
+    /// ### Returns
+    /// - `Ok(TaskProcess)`: A handle stopping the task when asked or dropped
+    pub fn spawn<F>(command: &str, cwd: &Path, on_event: F) -> anyhow::Result<Self>
+    where
+        F: Fn(TaskEvent) + Clone + Send + 'static,
+    {
+        let mut shell = shell_command(command);
+        shell
+            .current_dir(cwd)
+            .env("CLICOLOR_FORCE", "1")
+            .env("FORCE_COLOR", "1")
+            .env("CARGO_TERM_COLOR", "always")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = shell
+            .spawn()
+            .map_err(|e| anyhow!("Failed to start '{command}' in {}: {e}", cwd.display()))?;
+        let readers = [
+            forward_lines(child.stdout.take(), on_event.clone()),
+            forward_lines(child.stderr.take(), on_event.clone()),
+        ];
+        let stop = Arc::new(AtomicBool::new(false));
+        let supervisor_stop = Arc::clone(&stop);
+        thread::spawn(move || supervise(child, &readers, &supervisor_stop, &on_event));
+        Ok(Self { stop })
+    }
+
+    /// Stop the task and the processes it started, if it is still running
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Release);
+    }
+}
+
+impl Drop for TaskProcess {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn collect(receiver: &mpsc::Receiver<TaskEvent>) -> Vec<TaskEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.recv_timeout(Duration::from_secs(5)) {
+            let exited = matches!(event, TaskEvent::Exited(_) | TaskEvent::Failed(_));
+            events.push(event);
+            if exited {
+                break;
+            }
+        }
+        events
+    }
+
+    fn spawn(command: &str) -> (TaskProcess, mpsc::Receiver<TaskEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        let process = TaskProcess::spawn(command, &std::env::temp_dir(), move |event| {
+            let _ = sender.send(event);
+        })
+        .expect("task should start");
+        (process, receiver)
+    }
+
+    #[test]
+    fn test_visible_line_keeps_the_last_redraw() {
+        assert_eq!(visible_line(b"plain\r\n"), "plain");
+        assert_eq!(visible_line(b"10%\r50%\r100%\n"), "100%");
+        assert_eq!(visible_line(b"no line break"), "no line break");
+    }
+
+    #[test]
+    fn test_task_process_reports_output_and_exit_code() {
+        let (_process, receiver) = spawn("echo one; echo two >&2; exit 4");
+        let events = collect(&receiver);
+        assert!(events.contains(&TaskEvent::Output("one".to_string())));
+        assert!(events.contains(&TaskEvent::Output("two".to_string())));
+        assert_eq!(events.last(), Some(&TaskEvent::Exited(Some(4))));
+    }
+
+    #[test]
+    fn test_task_process_stop_ends_the_whole_group() {
+        let started = Instant::now();
+        let (process, receiver) = spawn("sleep 30 & sleep 30; echo unreachable");
+        thread::sleep(Duration::from_millis(100));
+        process.stop();
+        let events = collect(&receiver);
+        assert!(matches!(events.last(), Some(TaskEvent::Exited(_))));
+        assert!(!events.contains(&TaskEvent::Output("unreachable".to_string())));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::fulgur::languages::supported_languages::SupportedLanguage;
+use anyhow::anyhow;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Location of the task definitions of a folder, relative to the folder
+pub const TASKS_FILE: &str = ".fulgur/tasks.json";
+
+/// Files marking the root of a project, where the language tasks run
+const PROJECT_MARKERS: [&str; 7] = [
+    "Cargo.toml",
+    "package.json",
+    "go.mod",
+    "pyproject.toml",
+    "build.zig",
+    "mix.exs",
+    ".git",
+];
+
+/// A task as written in a tasks file
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct TaskDefinition {
+    pub label: String,
+    pub command: String, // May use the `{file}`, `{dir}` and `{root}` placeholders
+    #[serde(default)]
+    pub cwd: Option<String>, // Relative to the folder holding the tasks file
+}
+
+/// The content of a tasks file
+#[derive(Debug, Deserialize)]
+struct TasksFile {
+    #[serde(default)]
+    tasks: Vec<TaskDefinition>,
+}
+
+/// A task ready to run: its placeholders expanded and its working directory resolved
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedTask {
+    pub label: String,
+    pub command: String,
+    pub cwd: PathBuf,
+}
+
+/// Quote a path for the platform shell
+///
+/// ### Arguments
+/// - `path`: The path to quote
+///
+/// ### Returns
+/// - `String`: The path as a single shell word
+fn shell_quote(path: &Path) -> String {
+    let path = path.display().to_string();
+    if cfg!(target_os = "windows") {
+        format!("\"{path}\"")
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+/// Expand the placeholders of a task command
+///
+/// ### Arguments
+/// - `command`: The command, with `{file}`, `{dir}` and `{root}` placeholders
+/// - `file`: The file of the active tab
+/// - `root`: The folder the task belongs to
+///
+/// ### Returns
+/// - `String`: The command with each placeholder replaced by a quoted path
+#[must_use]
+pub fn expand_command(command: &str, file: &Path, root: &Path) -> String {
+    let dir = file.parent().unwrap_or(root);
+    command
+        .replace("{file}", &shell_quote(file))
+        .replace("{dir}", &shell_quote(dir))
+        .replace("{root}", &shell_quote(root))
+}
+
+/// Find the tasks file applying to a file, looking in its folder and then in each parent
+///
+/// ### Arguments
+/// - `file`: The file of the active tab
+///
+/// ### Returns
+/// - `Some(PathBuf)`: The nearest tasks file
+/// - `None`: No folder above the file defines tasks
+#[must_use]
+pub fn find_tasks_file(file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .map(|folder| folder.join(TASKS_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Find the root of the project a file belongs to
+///
+/// ### Arguments
+/// - `file`: The file of the active tab
+///
+/// ### Returns
+/// - `PathBuf`: The nearest folder holding a project marker such as `Cargo.toml` or
+///   `.git`, or the folder of the file
+#[must_use]
+pub fn project_root(file: &Path) -> PathBuf {
+    let folder = file.parent().unwrap_or(file);
+    folder
+        .ancestors()
+        .find(|ancestor| {
+            PROJECT_MARKERS
+                .iter()
+                .any(|marker| ancestor.join(marker).exists())
+        })
+        .unwrap_or(folder)
+        .to_path_buf()
+}
+
+/// Parse the task definitions of a tasks file
+///
+/// ### Arguments
+/// - `json`: The content of the file, e.g. `{"tasks": [{"label": "Build", "command": "make"}]}`
+///
+/// ### Errors
+/// Returns an error if the content is not a valid tasks file.
+///
+/// ### Returns
+/// - `Ok(Vec<TaskDefinition>)`: The tasks, in file order
+pub fn parse_tasks(json: &str) -> anyhow::Result<Vec<TaskDefinition>> {
+    let file: TasksFile =
+        serde_json::from_str(json).map_err(|e| anyhow!("Invalid tasks file: {e}"))?;
+    Ok(file.tasks)
+}
+
+/// Get the default tasks of a language
+///
+/// ### Arguments
+/// - `language`: The language of the active tab
+///
+/// ### Returns
+/// - `&'static [(&'static str, &'static str)]`: The label and command of each task, empty
+///   for languages without a usual build or test command
+#[must_use]
+pub fn language_tasks(language: SupportedLanguage) -> &'static [(&'static str, &'static str)] {
+    match language {
+        SupportedLanguage::Rust => &[
+            ("cargo check", "cargo check"),
+            ("cargo test", "cargo test"),
+            ("cargo run", "cargo run"),
+        ],
+        SupportedLanguage::Python => &[("pytest", "pytest {file}"), ("python", "python3 {file}")],
+        SupportedLanguage::Go => &[
+            ("go build", "go build ./..."),
+            ("go test", "go test ./..."),
+            ("go vet", "go vet ./..."),
+        ],
+        SupportedLanguage::JavaScript
+        | SupportedLanguage::TypeScript
+        | SupportedLanguage::React => {
+            &[("npm test", "npm test"), ("npm run build", "npm run build")]
+        }
+        SupportedLanguage::C | SupportedLanguage::Cpp | SupportedLanguage::Make => {
+            &[("make", "make")]
+        }
+        SupportedLanguage::Zig => &[("zig build", "zig build"), ("zig test", "zig test {file}")],
+        SupportedLanguage::Elixir => &[("mix compile", "mix compile"), ("mix test", "mix test")],
+        SupportedLanguage::Ruby => &[("ruby", "ruby {file}")],
+        SupportedLanguage::Bash => &[("sh", "sh {file}")],
+        _ => &[],
+    }
+}
+
+/// Collect the tasks available for a file: those of the nearest tasks file, then those of its language
+///
+/// ### Arguments
+/// - `file`: The file of the active tab
+/// - `language`: The language of the active tab
+///
+/// ### Errors
+/// Returns an error if a tasks file exists but cannot be read or parsed.
+///
+/// ### Returns
+/// - `Ok(Vec<ResolvedTask>)`: The tasks, with their placeholders expanded
+pub fn available_tasks(
+    file: &Path,
+    language: SupportedLanguage,
+) -> anyhow::Result<Vec<ResolvedTask>> {
+    let mut tasks = Vec::new();
+    if let Some(tasks_file) = find_tasks_file(file) {
+        // `<folder>/.fulgur/tasks.json`
+        let folder = tasks_file
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let json = fs::read_to_string(&tasks_file)
+            .map_err(|e| anyhow!("Failed to read {}: {e}", tasks_file.display()))?;
+        let definitions =
+            parse_tasks(&json).map_err(|e| anyhow!("{e} ({})", tasks_file.display()))?;
+        tasks.extend(definitions.into_iter().map(|definition| {
+            ResolvedTask {
+                command: expand_command(&definition.command, file, &folder),
+                cwd: definition
+                    .cwd
+                    .as_deref()
+                    .map_or_else(|| folder.clone(), |cwd| folder.join(cwd)),
+                label: definition.label,
+            }
+        }));
+    }
+    let root = project_root(file);
+    tasks.extend(
+        language_tasks(language)
+            .iter()
+            .map(|(label, command)| ResolvedTask {
+                label: (*label).to_string(),
+                command: expand_command(command, file, &root),
+                cwd: root.clone(),
+            }),
+    );
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tasks() {
+        let tasks = parse_tasks(
+            r#"{"tasks": [
+                {"label": "Build", "command": "make"},
+                {"label": "Test", "command": "pytest {file}", "cwd": "tests"}
+            ]}"#,
+        )
+        .expect("tasks file should parse");
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].label, "Build");
+        assert_eq!(tasks[0].cwd, None);
+        assert_eq!(tasks[1].cwd.as_deref(), Some("tests"));
+        assert!(parse_tasks("{}").expect("empty file").is_empty());
+        assert!(parse_tasks(r#"{"tasks": [{"label": "x"}]}"#).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expand_command_quotes_paths() {
+        let command = expand_command(
+            "pytest {file} --rootdir {root} -C {dir}",
+            Path::new("/work/it's/test_a.py"),
+            Path::new("/work"),
+        );
+        assert_eq!(
+            command,
+            "pytest '/work/it'\\''s/test_a.py' --rootdir '/work' -C '/work/it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn test_available_tasks_reads_the_nearest_tasks_file() {
+        let folder = std::env::temp_dir().join(format!("fulgur-tasks-{}", std::process::id()));
+        let nested = folder.join("src");
+        fs::create_dir_all(&nested).expect("create folders");
+        fs::create_dir_all(folder.join(".fulgur")).expect("create folders");
+        fs::write(
+            folder.join(TASKS_FILE),
+            r#"{"tasks": [{"label": "Lint", "command": "lint", "cwd": "src"}]}"#,
+        )
+        .expect("write tasks file");
+        fs::write(folder.join("Cargo.toml"), "").expect("write marker");
+        let file = nested.join("main.rs");
+        let tasks = available_tasks(&file, SupportedLanguage::Rust).expect("tasks");
+        fs::remove_dir_all(&folder).ok();
+        assert_eq!(tasks[0].label, "Lint");
+        assert_eq!(tasks[0].cwd, folder.join("src"));
+        assert_eq!(tasks[1].label, "cargo check");
+        assert_eq!(tasks[1].cwd, folder);
+        assert_eq!(
+            tasks.len(),
+            1 + language_tasks(SupportedLanguage::Rust).len()
+        );
+    }
+}
@@ -0,0 +1,247 @@
+/// Modifier keys held with a key press, as far as a terminal is concerned
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyModifiers {
+    pub control: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl KeyModifiers {
+    /// Get the xterm modifier parameter of these modifiers
+    ///
+    /// ### Returns
+    /// - `Some(u8)`: The parameter, e.g. `5` for Control
+    /// - `None`: No modifier is held
+    fn parameter(self) -> Option<u8> {
+        let value =
+            1 + u8::from(self.shift) + 2 * u8::from(self.alt) + 4 * u8::from(self.control);
+        (value > 1).then_some(value)
+    }
+}
+
+/// Encode a cursor or function key whose plain form is `ESC [ <letter>` or `ESC O <letter>`
+///
+/// ### Arguments
+/// - `letter`: The final letter, e.g. `A` for the up arrow
+/// - `modifiers`: The held modifiers
+/// - `application`: Whether the plain form uses `ESC O`, as in application cursor mode
+///
+/// ### Returns
+/// - `Vec<u8>`: The escape sequence
+fn letter_key(letter: char, modifiers: KeyModifiers, application: bool) -> Vec<u8> {
+    match modifiers.parameter() {
+        Some(parameter) => format!("\x1b[1;{parameter}{letter}").into_bytes(),
+        None if application => format!("\x1bO{letter}").into_bytes(),
+        None => format!("\x1b[{letter}").into_bytes(),
+    }
+}
+
+/// Encode an editing or function key whose plain form is `ESC [ <number> ~`
+///
+/// ### Arguments
+/// - `number`: The key number, e.g. `3` for Delete
+/// - `modifiers`: The held modifiers
+///
+/// ### Returns
+/// - `Vec<u8>`: The escape sequence
+fn tilde_key(number: u8, modifiers: KeyModifiers) -> Vec<u8> {
+    match modifiers.parameter() {
+        Some(parameter) => format!("\x1b[{number};{parameter}~").into_bytes(),
+        None => format!("\x1b[{number}~").into_bytes(),
+    }
+}
+
+/// Encode a character typed with Control held
+///
+/// ### Arguments
+/// - `character`: The character, e.g. `c`
+///
+/// ### Returns
+/// - `Some(u8)`: The control code, e.g. `0x03` for `c`
+/// - `None`: The character has no control code
+fn control_code(character: char) -> Option<u8> {
+    match character {
+        'a'..='z' => Some(character as u8 - b'a' + 1),
+        '@' | ' ' | '2' => Some(0),
+        '[' | '3' => Some(0x1b),
+        '\\' | '4' => Some(0x1c),
+        ']' | '5' => Some(0x1d),
+        '^' | '6' => Some(0x1e),
+        '_' | '-' | '7' => Some(0x1f),
+        '?' | '8' => Some(0x7f),
+        _ => None,
+    }
+}
+
+/// Prefix a sequence with Escape when Alt is held, as terminals encode Meta
+///
+/// ### Arguments
+/// - `bytes`: The sequence of the key without Alt
+/// - `alt`: Whether Alt is held
+///
+/// ### Returns
+/// - `Vec<u8>`: The sequence to send
+fn with_meta(bytes: Vec<u8>, alt: bool) -> Vec<u8> {
+    if alt {
+        let mut prefixed = Vec::with_capacity(bytes.len() + 1);
+        prefixed.push(0x1b);
+        prefixed.extend(bytes);
+        prefixed
+    } else {
+        bytes
+    }
+}
+
+/// Encode a key press as the bytes a terminal application expects
+///
+/// ### Description
+/// Follows the xterm conventions: cursor keys honour the application cursor
+/// mode, modified cursor and function keys carry the modifier parameter,
+/// Control maps letters to control codes and Alt prefixes Escape.
+///
+/// ### Arguments
+/// - `key`: The key name, e.g. `a`, `enter` or `up`
+/// - `key_char`: The text the key types with the current layout and modifiers, if any
+/// - `modifiers`: The held modifiers
+/// - `application_cursor`: Whether the terminal is in application cursor mode
+///
+/// ### Returns
+/// - `Some(Vec<u8>)`: The bytes to write to the terminal
+/// - `None`: The key sends nothing, e.g. a lone modifier
+#[must_use]
+pub fn encode_key(
+    key: &str,
+    key_char: Option<&str>,
+    modifiers: KeyModifiers,
+    application_cursor: bool,
+) -> Option<Vec<u8>> {
+    let plain = KeyModifiers::default();
+    let bytes = match key {
+        "enter" => with_meta(b"\r".to_vec(), modifiers.alt),
+        "tab" if modifiers.shift => b"\x1b[Z".to_vec(),
+        "tab" => with_meta(b"\t".to_vec(), modifiers.alt),
+        "backspace" if modifiers.control => with_meta(vec![0x08], modifiers.alt),
+        "backspace" => with_meta(vec![0x7f], modifiers.alt),
+        "escape" => with_meta(vec![0x1b], modifiers.alt),
+        "space" if modifiers.control => with_meta(vec![0], modifiers.alt),
+        "up" => letter_key('A', modifiers, application_cursor),
+        "down" => letter_key('B', modifiers, application_cursor),
+        "right" => letter_key('C', modifiers, application_cursor),
+        "left" => letter_key('D', modifiers, application_cursor),
+        "home" => letter_key('H', modifiers, application_cursor),
+        "end" => letter_key('F', modifiers, application_cursor),
+        "f1" => letter_key('P', modifiers, modifiers == plain),
+        "f2" => letter_key('Q', modifiers, modifiers == plain),
+        "f3" => letter_key('R', modifiers, modifiers == plain),
+        "f4" => letter_key('S', modifiers, modifiers == plain),
+        "insert" => tilde_key(2, modifiers),
+        "delete" => tilde_key(3, modifiers),
+        "pageup" => tilde_key(5, modifiers),
+        "pagedown" => tilde_key(6, modifiers),
+        "f5" => tilde_key(15, modifiers),
+        "f6" => tilde_key(17, modifiers),
+        "f7" => tilde_key(18, modifiers),
+        "f8" => tilde_key(19, modifiers),
+        "f9" => tilde_key(20, modifiers),
+        "f10" => tilde_key(21, modifiers),
+        "f11" => tilde_key(23, modifiers),
+        "f12" => tilde_key(24, modifiers),
+        _ if modifiers.control => {
+            let mut characters = key.chars();
+            let character = characters.next()?.to_ascii_lowercase();
+            if characters.next().is_some() {
+                return None;
+            }
+            with_meta(vec![control_code(character)?], modifiers.alt)
+        }
+        "space" => with_meta(b" ".to_vec(), modifiers.alt),
+        _ => {
+            let text = key_char.filter(|text| !text.is_empty())?;
+            with_meta(text.as_bytes().to_vec(), modifiers.alt)
+        }
+    };
+    Some(bytes)
+}
+
+/// Prepare pasted text for a terminal
+///
+/// ### Description
+/// Line breaks become carriage returns, as if typed. In bracketed paste mode the
+/// text is wrapped in the paste markers, and escape characters are removed so
+/// the pasted text cannot end the paste early.
+///
+/// ### Arguments
+/// - `text`: The clipboard text
+/// - `bracketed`: Whether the terminal asked for bracketed paste
+///
+/// ### Returns
+/// - `Vec<u8>`: The bytes to write to the terminal
+#[must_use]
+pub fn encode_paste(text: &str, bracketed: bool) -> Vec<u8> {
+    let text = text.replace("\r\n", "\r").replace('\n', "\r");
+    if bracketed {
+        let text = text.replace('\x1b', "");
+        format!("\x1b[200~{text}\x1b[201~").into_bytes()
+    } else {
+        text.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTROL: KeyModifiers = KeyModifiers {
+        control: true,
+        alt: false,
+        shift: false,
+    };
+
+    fn encode(key: &str, key_char: Option<&str>, modifiers: KeyModifiers) -> Option<Vec<u8>> {
+        encode_key(key, key_char, modifiers, false)
+    }
+
+    #[test]
+    fn test_encode_key_text_and_control_codes() {
+        let plain = KeyModifiers::default();
+        assert_eq!(encode("a", Some("a"), plain), Some(b"a".to_vec()));
+        assert_eq!(encode("c", None, CONTROL), Some(vec![0x03]));
+        assert_eq!(encode("[", None, CONTROL), Some(vec![0x1b]));
+        assert_eq!(encode("enter", None, plain), Some(b"\r".to_vec()));
+        assert_eq!(encode("backspace", None, plain), Some(vec![0x7f]));
+        assert_eq!(encode("shift", None, plain), None);
+        let alt = KeyModifiers {
+            alt: true,
+            ..KeyModifiers::default()
+        };
+        assert_eq!(encode("b", Some("b"), alt), Some(b"\x1bb".to_vec()));
+    }
+
+    #[test]
+    fn test_encode_key_cursor_keys() {
+        let plain = KeyModifiers::default();
+        assert_eq!(encode("up", None, plain), Some(b"\x1b[A".to_vec()));
+        assert_eq!(
+            encode_key("up", None, plain, true),
+            Some(b"\x1bOA".to_vec())
+        );
+        assert_eq!(encode("right", None, CONTROL), Some(b"\x1b[1;5C".to_vec()));
+        assert_eq!(encode("delete", None, plain), Some(b"\x1b[3~".to_vec()));
+        let shift = KeyModifiers {
+            shift: true,
+            ..KeyModifiers::default()
+        };
+        assert_eq!(encode("pageup", None, shift), Some(b"\x1b[5;2~".to_vec()));
+        assert_eq!(encode("tab", None, shift), Some(b"\x1b[Z".to_vec()));
+        assert_eq!(encode("f1", None, plain), Some(b"\x1bOP".to_vec()));
+    }
+
+    #[test]
+    fn test_encode_paste() {
+        assert_eq!(encode_paste("ls\npwd\r\n", false), b"ls\rpwd\r".to_vec());
+        assert_eq!(
+            encode_paste("echo \x1b[201~hi", true),
+            b"\x1b[200~echo [201~hi\x1b[201~".to_vec()
+        );
+    }
+}
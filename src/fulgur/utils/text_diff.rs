@@ -0,0 +1,492 @@
+use ropey::Rope;
+use std::ops::Range;
+
+/// Upper bound on the LCS table size (in cells) before falling back to a single hunk.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// A single replacement against the old text, expressed in old-text byte offsets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub new_text: String,
+}
+
+/// Compute the line-level edits that turn `old` into `new`
+///
+/// ### Description
+/// Lines are compared whole, including their terminator. Common leading and trailing
+/// lines are skipped, the remaining middle block is diffed with a longest common
+/// subsequence, and each run of differing lines becomes one edit. When the middle
+/// block is too large for the LCS table, it is replaced as a single edit.
+///
+/// ### Arguments
+/// - `old`: The current buffer content
+/// - `new`: The target content
+///
+/// ### Returns
+/// - `Vec<TextEdit>`: Non-overlapping edits sorted by ascending offset, empty when both texts are equal
+#[must_use]
+pub fn line_edits(old: &str, new: &str) -> Vec<TextEdit> {
+    if old == new {
+        return Vec::new();
+    }
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old_lines[prefix..old_lines.len() - suffix];
+    let new_middle = &new_lines[prefix..new_lines.len() - suffix];
+    let start_offset: usize = old_lines[..prefix].iter().map(|line| line.len()).sum();
+
+    if old_middle.len().saturating_mul(new_middle.len()) > MAX_LCS_CELLS {
+        let old_len: usize = old_middle.iter().map(|line| line.len()).sum();
+        return vec![TextEdit {
+            range: start_offset..start_offset + old_len,
+            new_text: new_middle.concat(),
+        }];
+    }
+
+    let matches = lcs_matches(old_middle, new_middle);
+    let mut edits = Vec::new();
+    let mut old_index = 0;
+    let mut new_index = 0;
+    let mut offset = start_offset;
+    let sentinel = (old_middle.len(), new_middle.len());
+    for (match_old, match_new) in matches.into_iter().chain(std::iter::once(sentinel)) {
+        let removed_len: usize = old_middle[old_index..match_old]
+            .iter()
+            .map(|line| line.len())
+            .sum();
+        if match_old > old_index || match_new > new_index {
+            edits.push(TextEdit {
+                range: offset..offset + removed_len,
+                new_text: new_middle[new_index..match_new].concat(),
+            });
+        }
+        offset += removed_len;
+        if match_old < old_middle.len() {
+            offset += old_middle[match_old].len();
+        }
+        old_index = match_old + 1;
+        new_index = match_new + 1;
+    }
+    edits
+}
+
+/// Pair up equal lines of both slices along a longest common subsequence
+///
+/// ### Arguments
+/// - `old`: The old lines
+/// - `new`: The new lines
+///
+/// ### Returns
+/// - `Vec<(usize, usize)>`: Matching `(old_index, new_index)` pairs in ascending order
+fn lcs_matches(old: &[&str], new: &[&str]) -> Vec<(usize, usize)> {
+    let width = new.len() + 1;
+    let mut lengths = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if old[i] == new[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+    let mut matches = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+/// Map a byte offset in the old text to the matching offset after the edits are applied
+///
+/// ### Description
+/// Offsets before an edit are unchanged, offsets after it shift by the length delta,
+/// and offsets inside a replaced region keep their distance from the region start,
+/// clamped to the replacement. The result is floored to a char boundary of `new`.
+///
+/// ### Arguments
+/// - `edits`: The edits produced by `line_edits`
+/// - `offset`: A byte offset in the old text
+/// - `new`: The text after the edits are applied
+///
+/// ### Returns
+/// - `usize`: The mapped byte offset in `new`
+#[must_use]
+pub fn map_offset(edits: &[TextEdit], offset: usize, new: &str) -> usize {
+    let mut delta: isize = 0;
+    let mut mapped = None;
+    for edit in edits {
+        if edit.range.start >= offset {
+            break;
+        }
+        if edit.range.end <= offset {
+            delta += edit.new_text.len().cast_signed() - edit.range.len().cast_signed();
+            continue;
+        }
+        let within = (offset - edit.range.start).min(edit.new_text.len());
+        mapped = Some(edit.range.start.saturating_add_signed(delta) + within);
+        break;
+    }
+    let mut mapped = mapped
+        .unwrap_or_else(|| offset.saturating_add_signed(delta))
+        .min(new.len());
+    while !new.is_char_boundary(mapped) {
+        mapped -= 1;
+    }
+    mapped
+}
+
+/// Convert the byte ranges of `edits` into UTF-16 code unit ranges of `text`
+///
+/// ### Arguments
+/// - `text`: The text the edits were computed against
+/// - `edits`: Non-overlapping edits sorted by ascending offset
+///
+/// ### Returns
+/// - `Vec<Range<usize>>`: One UTF-16 range per edit, in the same order
+#[must_use]
+pub fn utf16_ranges(text: &str, edits: &[TextEdit]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::with_capacity(edits.len());
+    let mut byte_cursor = 0;
+    let mut utf16_cursor = 0;
+    let mut advance = |to: usize| {
+        utf16_cursor += text[byte_cursor..to].encode_utf16().count();
+        byte_cursor = to;
+        utf16_cursor
+    };
+    for edit in edits {
+        let start = advance(edit.range.start);
+        let end = advance(edit.range.end);
+        ranges.push(start..end);
+    }
+    ranges
+}
+
+/// Convert a UTF-16 code unit offset of `text` into a byte offset
+///
+/// ### Arguments
+/// - `text`: The text the offset points into
+/// - `utf16_offset`: The offset in UTF-16 code units, as reported by the input handler
+///
+/// ### Returns
+/// - `usize`: The byte offset of the first char starting at or after the offset,
+///   clamped to the length of `text`
+#[must_use]
+pub fn byte_offset_from_utf16(text: &str, utf16_offset: usize) -> usize {
+    let mut utf16_cursor = 0;
+    for (index, ch) in text.char_indices() {
+        if utf16_cursor >= utf16_offset {
+            return index;
+        }
+        utf16_cursor += ch.len_utf16();
+    }
+    text.len()
+}
+
+/// Get the zero-based line and character column of a byte offset
+///
+/// ### Arguments
+/// - `text`: The text the offset points into
+/// - `offset`: The byte offset, clamped to the text and moved back to a char boundary
+///
+/// ### Returns
+/// - `(u32, u32)`: The line and the column in characters on that line, as editor positions count them
+#[must_use]
+pub fn line_and_column(text: &str, offset: usize) -> (u32, u32) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let line_start = text[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line = text[..line_start].matches('\n').count();
+    let column = text[line_start..offset].chars().count();
+    (
+        u32::try_from(line).unwrap_or(u32::MAX),
+        u32::try_from(column).unwrap_or(u32::MAX),
+    )
+}
+
+/// Compute the single edit that turns `old` into `new`, where `new` was derived from `old`
+///
+/// ### Description
+/// The changed region is found by skipping the common leading and trailing bytes.
+/// Rope clones share the chunks an edit did not touch, so those chunks compare
+/// by address and the walk costs one step per chunk, plus the bytes of the
+/// chunks around the edit. The region is widened to char boundaries.
+///
+/// ### Arguments
+/// - `old`: The text before the edit
+/// - `new`: The text after the edit
+///
+/// ### Returns
+/// - `Some(TextEdit)`: The replacement of `old` bytes turning it into `new`
+/// - `None`: Both texts are equal
+#[must_use]
+pub fn rope_edit(old: &Rope, new: &Rope) -> Option<TextEdit> {
+    let old_chunks: Vec<&str> = old.chunks().collect();
+    let new_chunks: Vec<&str> = new.chunks().collect();
+    let prefix = common_chunk_bytes(
+        old_chunks.iter().copied(),
+        new_chunks.iter().copied(),
+        false,
+    );
+    if prefix == old.len() && prefix == new.len() {
+        return None;
+    }
+    let start = old.floor_char_boundary(prefix);
+    let suffix = common_chunk_bytes(
+        old_chunks.iter().rev().copied(),
+        new_chunks.iter().rev().copied(),
+        true,
+    )
+    .min(old.len().min(new.len()) - start);
+    let old_end = old.ceil_char_boundary(old.len() - suffix);
+    let new_end = new.len() - (old.len() - old_end);
+    Some(TextEdit {
+        range: start..old_end,
+        new_text: new.slice(start..new_end).to_string(),
+    })
+}
+
+/// Count the bytes two chunk sequences have in common, from their start or their end
+///
+/// ### Arguments
+/// - `old`: The chunks of the first text, in walking order
+/// - `new`: The chunks of the second text, in walking order
+/// - `from_end`: Whether the chunks are walked backwards, comparing their bytes from the end
+///
+/// ### Returns
+/// - `usize`: The number of equal bytes before the first difference
+fn common_chunk_bytes<'a>(
+    mut old: impl Iterator<Item = &'a str>,
+    mut new: impl Iterator<Item = &'a str>,
+    from_end: bool,
+) -> usize {
+    let (mut a, mut b): (&[u8], &[u8]) = (&[], &[]);
+    let mut common = 0;
+    loop {
+        if a.is_empty() {
+            let Some(chunk) = old.next() else {
+                return common;
+            };
+            a = chunk.as_bytes();
+            continue;
+        }
+        if b.is_empty() {
+            let Some(chunk) = new.next() else {
+                return common;
+            };
+            b = chunk.as_bytes();
+            continue;
+        }
+        if std::ptr::eq(a, b) {
+            common += a.len();
+            (a, b) = (&[], &[]);
+            continue;
+        }
+        let len = a.len().min(b.len());
+        let (a_part, b_part) = if from_end {
+            (&a[a.len() - len..], &b[b.len() - len..])
+        } else {
+            (&a[..len], &b[..len])
+        };
+        let equal = if from_end {
+            a_part
+                .iter()
+                .rev()
+                .zip(b_part.iter().rev())
+                .take_while(|(x, y)| x == y)
+                .count()
+        } else {
+            a_part
+                .iter()
+                .zip(b_part)
+                .take_while(|(x, y)| x == y)
+                .count()
+        };
+        common += equal;
+        if equal < len {
+            return common;
+        }
+        if from_end {
+            a = &a[..a.len() - len];
+            b = &b[..b.len() - len];
+        } else {
+            a = &a[len..];
+            b = &b[len..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TextEdit, byte_offset_from_utf16, line_and_column, line_edits, map_offset, rope_edit,
+        utf16_ranges,
+    };
+    use std::fmt::Write as _;
+
+    fn apply(old: &str, edits: &[TextEdit]) -> String {
+        let mut result = old.to_string();
+        for edit in edits.iter().rev() {
+            result.replace_range(edit.range.clone(), &edit.new_text);
+        }
+        result
+    }
+
+    #[test]
+    fn test_line_edits_identical_texts_produce_no_edits() {
+        assert!(line_edits("a\nb\n", "a\nb\n").is_empty());
+    }
+
+    #[test]
+    fn test_line_edits_single_changed_line_is_one_edit() {
+        let old = "one\ntwo\nthree\n";
+        let new = "one\nTWO\nthree\n";
+        let edits = line_edits(old, new);
+        assert_eq!(
+            edits,
+            vec![TextEdit {
+                range: 4..8,
+                new_text: "TWO\n".to_string(),
+            }]
+        );
+        assert_eq!(apply(old, &edits), new);
+    }
+
+    #[test]
+    fn test_line_edits_separate_changes_stay_separate() {
+        let old = "a\nb\nc\nd\ne\n";
+        let new = "a\nB\nc\nd\nE\n";
+        let edits = line_edits(old, new);
+        assert_eq!(edits.len(), 2);
+        assert_eq!(apply(old, &edits), new);
+    }
+
+    #[test]
+    fn test_line_edits_insertions_and_deletions_round_trip() {
+        let cases = [
+            ("", "new\n"),
+            ("gone\n", ""),
+            ("a\nc\n", "a\nb\nc\n"),
+            ("a\nb\nc\n", "a\nc\n"),
+            ("no newline", "no newline\nadded"),
+            ("x\ny\nz", "z\ny\nx"),
+            ("caf\u{e9}\n\u{1F643}\n", "caf\u{e9}\nsmile\n"),
+        ];
+        for (old, new) in cases {
+            assert_eq!(apply(old, &line_edits(old, new)), new, "{old:?} -> {new:?}");
+        }
+    }
+
+    #[test]
+    fn test_map_offset_preserves_position_in_unchanged_regions() {
+        let old = "one\ntwo\nthree\nfour\n";
+        let new = "zero\none\ntwo\nthree\nfour\n";
+        let edits = line_edits(old, new);
+        let caret = old.find("three").expect("fixture contains the anchor");
+        let mapped = map_offset(&edits, caret, new);
+        assert_eq!(&new[mapped..mapped + 5], "three");
+    }
+
+    #[test]
+    fn test_map_offset_clamps_inside_replaced_region() {
+        let old = "keep\nlong replaced line\n";
+        let new = "keep\nx\n";
+        let edits = line_edits(old, new);
+        let mapped = map_offset(&edits, old.len() - 2, new);
+        assert!(mapped <= new.len());
+        assert!(new.is_char_boundary(mapped));
+    }
+
+    #[test]
+    fn test_utf16_ranges_count_surrogate_pairs() {
+        let old = "\u{1F643}\nb\n";
+        let edits = line_edits(old, "\u{1F643}\nc\n");
+        assert_eq!(utf16_ranges(old, &edits), vec![3..5]);
+    }
+
+    #[test]
+    fn test_byte_offset_from_utf16_skips_surrogate_pairs() {
+        let text = "a\u{1F643}b\u{e9}";
+        assert_eq!(byte_offset_from_utf16(text, 0), 0);
+        assert_eq!(byte_offset_from_utf16(text, 1), 1);
+        assert_eq!(byte_offset_from_utf16(text, 3), 5);
+        assert_eq!(byte_offset_from_utf16(text, 4), 6);
+        assert_eq!(byte_offset_from_utf16(text, 99), text.len());
+    }
+
+    #[test]
+    fn test_rope_edit_finds_the_changed_region_of_an_edited_clone() {
+        let text = (0..2_000).fold(String::new(), |mut acc, i| {
+            writeln!(acc, "line {i}").expect("writing to a String cannot fail");
+            acc
+        });
+        let old = ropey::Rope::from_str(&text);
+        let mut new = old.clone();
+        let at = text.find("line 1500").expect("expected the anchor line");
+        new.insert(at, "inserted ");
+        assert_eq!(
+            rope_edit(&old, &new),
+            Some(TextEdit {
+                range: at..at,
+                new_text: "inserted ".to_string(),
+            })
+        );
+        assert_eq!(rope_edit(&old, &old.clone()), None);
+    }
+
+    #[test]
+    fn test_rope_edit_keeps_multibyte_chars_whole() {
+        let old = ropey::Rope::from_str("café au lait");
+        let new = ropey::Rope::from_str("cafè au lait");
+        let edit = rope_edit(&old, &new).expect("expected an edit");
+        assert_eq!(edit.range, 3..5);
+        assert_eq!(edit.new_text, "è");
+    }
+
+    #[test]
+    fn test_rope_edit_handles_repeated_text_at_the_edit() {
+        let old = ropey::Rope::from_str("aaaa");
+        let new = ropey::Rope::from_str("aaaaaa");
+        let edit = rope_edit(&old, &new).expect("expected an edit");
+        let mut applied = "aaaa".to_string();
+        applied.replace_range(edit.range, &edit.new_text);
+        assert_eq!(applied, "aaaaaa");
+    }
+
+    #[test]
+    fn test_line_and_column_counts_characters() {
+        let text = "first\n\u{1F600} é x\n";
+        let x = text.find('x').expect("expected the marker");
+        assert_eq!(line_and_column(text, x), (1, 4));
+        assert_eq!(line_and_column(text, 0), (0, 0));
+        assert_eq!(line_and_column(text, x + 1_000), (2, 0));
+        assert_eq!(
+            line_and_column(text, text.find('é').unwrap_or(0) + 1),
+            (1, 2)
+        );
+    }
+}
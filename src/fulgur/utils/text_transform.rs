@@ -0,0 +1,693 @@
+use anyhow::{Result, anyhow};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Characters escaped by `RegexEscape`, matching the metacharacters of the `regex` crate
+const REGEX_META_CHARACTERS: &str = "\\.+*?()|[]{}^$#&-~";
+
+/// A text transformation applied to the selection or to the whole buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextTransform {
+    SortNatural,
+    SortNumeric,
+    SortReverse,
+    SortCaseInsensitive,
+    RemoveDuplicateLines,
+    ShuffleLines,
+    ReverseLines,
+    UpperCase,
+    LowerCase,
+    TitleCase,
+    SnakeCase,
+    CamelCase,
+    JoinLines,
+    SplitLines,
+    JsonEscape,
+    JsonUnescape,
+    HtmlEscape,
+    HtmlUnescape,
+    RegexEscape,
+    RegexUnescape,
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+}
+
+impl TextTransform {
+    /// Transforms grouped as they appear in the menus, each group separated from the next
+    pub const GROUPS: &[&[TextTransform]] = &[
+        &[
+            Self::SortNatural,
+            Self::SortNumeric,
+            Self::SortReverse,
+            Self::SortCaseInsensitive,
+            Self::RemoveDuplicateLines,
+            Self::ShuffleLines,
+            Self::ReverseLines,
+        ],
+        &[
+            Self::UpperCase,
+            Self::LowerCase,
+            Self::TitleCase,
+            Self::SnakeCase,
+            Self::CamelCase,
+        ],
+        &[Self::JoinLines, Self::SplitLines],
+        &[
+            Self::JsonEscape,
+            Self::JsonUnescape,
+            Self::HtmlEscape,
+            Self::HtmlUnescape,
+            Self::RegexEscape,
+            Self::RegexUnescape,
+        ],
+        &[
+            Self::Base64Encode,
+            Self::Base64Decode,
+            Self::UrlEncode,
+            Self::UrlDecode,
+        ],
+    ];
+
+    /// Get the menu label of the transform
+    ///
+    /// ### Returns
+    /// - `&'static str`: The label
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::SortNatural => "Sort Lines",
+            Self::SortNumeric => "Sort Lines Numerically",
+            Self::SortReverse => "Sort Lines in Reverse",
+            Self::SortCaseInsensitive => "Sort Lines (Case Insensitive)",
+            Self::RemoveDuplicateLines => "Remove Duplicate Lines",
+            Self::ShuffleLines => "Shuffle Lines",
+            Self::ReverseLines => "Reverse Lines",
+            Self::UpperCase => "UPPER CASE",
+            Self::LowerCase => "lower case",
+            Self::TitleCase => "Title Case",
+            Self::SnakeCase => "snake_case",
+            Self::CamelCase => "camelCase",
+            Self::JoinLines => "Join Lines",
+            Self::SplitLines => "Split Lines at Spaces",
+            Self::JsonEscape => "Escape JSON String",
+            Self::JsonUnescape => "Unescape JSON String",
+            Self::HtmlEscape => "Escape HTML",
+            Self::HtmlUnescape => "Unescape HTML",
+            Self::RegexEscape => "Escape Regex",
+            Self::RegexUnescape => "Unescape Regex",
+            Self::Base64Encode => "Base64 Encode",
+            Self::Base64Decode => "Base64 Decode",
+            Self::UrlEncode => "URL Encode",
+            Self::UrlDecode => "URL Decode",
+        }
+    }
+
+    /// Apply the transform to a text
+    ///
+    /// ### Arguments
+    /// - `text`: The selected text, or the whole buffer when nothing is selected
+    ///
+    /// ### Returns
+    /// - `Ok(String)`: The transformed text
+    ///
+    /// ### Errors
+    /// - When decoding or unescaping input that is not valid for the format
+    pub fn apply(self, text: &str) -> Result<String> {
+        let transformed = match self {
+            Self::SortNatural => map_lines(text, |lines| lines.sort_by(|a, b| natural_cmp(a, b))),
+            Self::SortNumeric => map_lines(text, |lines| {
+                lines.sort_by(|a, b| numeric_cmp(a, b).then_with(|| natural_cmp(a, b)));
+            }),
+            Self::SortReverse => map_lines(text, |lines| lines.sort_by(|a, b| natural_cmp(b, a))),
+            Self::SortCaseInsensitive => map_lines(text, |lines| {
+                lines.sort_by_cached_key(|line| line.to_lowercase());
+            }),
+            Self::RemoveDuplicateLines => map_lines(text, |lines| {
+                let mut seen = HashSet::new();
+                lines.retain(|line| seen.insert(*line));
+            }),
+            Self::ShuffleLines => map_lines(text, |lines| shuffle(lines, time_seed())),
+            Self::ReverseLines => map_lines(text, |lines| lines.reverse()),
+            Self::UpperCase => text.to_uppercase(),
+            Self::LowerCase => text.to_lowercase(),
+            Self::TitleCase => title_case(text),
+            Self::SnakeCase => map_each_line(text, |line| join_words(line, WordCase::Snake)),
+            Self::CamelCase => map_each_line(text, |line| join_words(line, WordCase::Camel)),
+            Self::JoinLines => join_lines(text),
+            Self::SplitLines => split_lines(text),
+            Self::JsonEscape => json_escape(text)?,
+            Self::JsonUnescape => serde_json::from_str::<String>(&format!("\"{text}\""))
+                .map_err(|e| anyhow!("Invalid JSON string: {e}"))?,
+            Self::HtmlEscape => html_escape(text),
+            Self::HtmlUnescape => html_unescape(text),
+            Self::RegexEscape => regex_escape(text),
+            Self::RegexUnescape => regex_unescape(text),
+            Self::Base64Encode => BASE64.encode(text),
+            Self::Base64Decode => {
+                let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+                let bytes = BASE64
+                    .decode(compact)
+                    .map_err(|e| anyhow!("Invalid base64: {e}"))?;
+                String::from_utf8(bytes)
+                    .map_err(|e| anyhow!("Decoded base64 is not UTF-8 text: {e}"))?
+            }
+            Self::UrlEncode => urlencoding::encode(text).into_owned(),
+            Self::UrlDecode => urlencoding::decode(text)
+                .map_err(|e| anyhow!("Decoded URL is not UTF-8 text: {e}"))?
+                .into_owned(),
+        };
+        Ok(transformed)
+    }
+}
+
+/// Get the line terminator used by a text
+///
+/// ### Arguments
+/// - `text`: The text
+///
+/// ### Returns
+/// - `&'static str`: `\r\n` when the text contains it, `\n` otherwise
+fn line_ending(text: &str) -> &'static str {
+    if text.contains("\r\n") { "\r\n" } else { "\n" }
+}
+
+/// Rearrange the lines of a text, keeping its terminator style and final newline
+///
+/// ### Arguments
+/// - `text`: The text
+/// - `f`: Reorders or filters the lines in place
+///
+/// ### Returns
+/// - `String`: The lines after `f`, joined back together
+fn map_lines(text: &str, f: impl FnOnce(&mut Vec<&str>)) -> String {
+    let ending = line_ending(text);
+    let mut lines: Vec<&str> = text.lines().collect();
+    f(&mut lines);
+    let mut result = lines.join(ending);
+    if text.ends_with('\n') {
+        result.push_str(ending);
+    }
+    result
+}
+
+/// Transform every line of a text on its own
+///
+/// ### Arguments
+/// - `text`: The text
+/// - `f`: Transforms the content of one line, without its terminator
+///
+/// ### Returns
+/// - `String`: The transformed lines with their original terminators
+fn map_each_line(text: &str, f: impl Fn(&str) -> String) -> String {
+    let mut result = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        result.push_str(&f(content));
+        result.push_str(&line[content.len()..]);
+    }
+    result
+}
+
+/// Compare two strings, ordering runs of digits by their numeric value
+///
+/// ### Arguments
+/// - `a`: The first string
+/// - `b`: The second string
+///
+/// ### Returns
+/// - `Ordering`: `file2` sorts before `file10`
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(c);
+                    }
+                    digits
+                };
+                let x_digits = take_number(&mut a_chars);
+                let y_digits = take_number(&mut b_chars);
+                let x_trimmed = x_digits.trim_start_matches('0');
+                let y_trimmed = y_digits.trim_start_matches('0');
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed))
+                    .then_with(|| x_digits.len().cmp(&y_digits.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+/// Parse the number a line starts with
+///
+/// ### Arguments
+/// - `line`: The line
+///
+/// ### Returns
+/// - `Some(f64)`: The leading number, ignoring leading whitespace
+/// - `None`: The line does not start with a number
+fn leading_number(line: &str) -> Option<f64> {
+    let trimmed = line.trim_start();
+    let end = trimmed
+        .char_indices()
+        .find(|&(index, c)| {
+            !(c.is_ascii_digit() || c == '.' || ((c == '-' || c == '+') && index == 0))
+        })
+        .map_or(trimmed.len(), |(index, _)| index);
+    trimmed[..end].parse().ok()
+}
+
+/// Compare two lines by their leading number, lines without one sorting last
+///
+/// ### Arguments
+/// - `a`: The first line
+/// - `b`: The second line
+///
+/// ### Returns
+/// - `Ordering`: The numeric order
+fn numeric_cmp(a: &str, b: &str) -> Ordering {
+    match (leading_number(a), leading_number(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Derive a shuffle seed from the clock
+///
+/// ### Returns
+/// - `u64`: A non-zero seed
+fn time_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.subsec_nanos());
+    u64::from(nanos) | 1
+}
+
+/// Shuffle lines with a Fisher-Yates pass driven by a xorshift generator
+///
+/// ### Arguments
+/// - `lines`: The lines to shuffle in place
+/// - `seed`: A non-zero seed
+fn shuffle(lines: &mut [&str], seed: u64) {
+    let mut state = seed.max(1);
+    for index in (1..lines.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let bound = u64::try_from(index + 1).unwrap_or(u64::MAX);
+        let other = usize::try_from(state % bound).unwrap_or(0);
+        lines.swap(index, other);
+    }
+}
+
+/// Capitalize the first letter of every word and lower-case the rest
+///
+/// ### Arguments
+/// - `text`: The text
+///
+/// ### Returns
+/// - `String`: The text in title case, whitespace unchanged
+fn title_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut at_word_start = true;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if at_word_start {
+                result.extend(c.to_uppercase());
+            } else {
+                result.extend(c.to_lowercase());
+            }
+            at_word_start = false;
+        } else {
+            result.push(c);
+            at_word_start = c.is_whitespace() || c == '-' || c == '_';
+        }
+    }
+    result
+}
+
+/// How `join_words` assembles identifier words
+#[derive(Clone, Copy)]
+enum WordCase {
+    Snake,
+    Camel,
+}
+
+/// Split a line into identifier words and join them in snake or camel case
+///
+/// ### Description
+/// Words are separated by any non-alphanumeric character, by lower-to-upper
+/// case changes and before the last capital of an acronym followed by a
+/// lowercase letter, so `HTTP server`, `httpServer`, `HTTPServer` and
+/// `http-server` are all read as the same two words.
+///
+/// ### Arguments
+/// - `line`: The line, keeping its indentation
+/// - `case`: The case to produce
+///
+/// ### Returns
+/// - `String`: The indentation followed by the joined identifier
+fn join_words(line: &str, case: WordCase) -> String {
+    let content = line.trim_start();
+    let indentation = &line[..line.len() - content.len()];
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut previous: Option<char> = None;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous = None;
+            continue;
+        }
+        let starts_word = c.is_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_uppercase() && chars.peek().is_some_and(|next| next.is_lowercase()))
+            });
+        if starts_word && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous = Some(c);
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    let joined = match case {
+        WordCase::Snake => words.join("_"),
+        WordCase::Camel => words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| {
+                if index == 0 {
+                    return word.clone();
+                }
+                let mut chars = word.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_uppercase().chain(chars).collect()
+                })
+            })
+            .collect(),
+    };
+    format!("{indentation}{joined}")
+}
+
+/// Join all lines with single spaces, dropping the indentation of continuation lines
+///
+/// ### Arguments
+/// - `text`: The text
+///
+/// ### Returns
+/// - `String`: One line, keeping the final newline when `text` had one
+fn join_lines(text: &str) -> String {
+    let mut lines = text.lines();
+    let mut result = lines.next().unwrap_or_default().trim_end().to_string();
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !result.is_empty() {
+            result.push(' ');
+        }
+        result.push_str(line);
+    }
+    if text.ends_with('\n') {
+        result.push_str(line_ending(text));
+    }
+    result
+}
+
+/// Put every whitespace-separated word of a text on its own line
+///
+/// ### Arguments
+/// - `text`: The text
+///
+/// ### Returns
+/// - `String`: One word per line, keeping the final newline when `text` had one
+fn split_lines(text: &str) -> String {
+    let ending = line_ending(text);
+    let mut result = text.split_whitespace().collect::<Vec<_>>().join(ending);
+    if text.ends_with('\n') {
+        result.push_str(ending);
+    }
+    result
+}
+
+/// Escape a text as the content of a JSON string, without the surrounding quotes
+///
+/// ### Arguments
+/// - `text`: The text
+///
+/// ### Returns
+/// - `Ok(String)`: The escaped text
+///
+/// ### Errors
+/// - When `serde_json` fails to serialize the string
+fn json_escape(text: &str) -> Result<String> {
+    let quoted = serde_json::to_string(text).map_err(|e| anyhow!("Failed to escape JSON: {e}"))?;
+    Ok(quoted[1..quoted.len() - 1].to_string())
+}
+
+/// Escape the characters with a meaning in HTML
+///
+/// ### Arguments
+/// - `text`: The text
+///
+/// ### Returns
+/// - `String`: The text with `&`, `<`, `>`, `"` and `'` replaced by entities
+fn html_escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Decode the named entities produced by `html_escape`, `&nbsp;` and numeric references
+///
+/// ### Arguments
+/// - `text`: The text
+///
+/// ### Returns
+/// - `String`: The decoded text, unknown entities left as they are
+fn html_unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity.strip_prefix('#').and_then(|number| {
+                    let code = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => number.parse().ok(),
+                    };
+                    code.and_then(char::from_u32)
+                }),
+            };
+            c.map(|c| (c, end))
+        });
+        if let Some((c, end)) = decoded {
+            result.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            result.push('&');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Escape regular expression metacharacters with a backslash
+///
+/// ### Arguments
+/// - `text`: The text
+///
+/// ### Returns
+/// - `String`: A pattern matching `text` literally
+fn regex_escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if REGEX_META_CHARACTERS.contains(c) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Remove the backslashes escaping regular expression metacharacters
+///
+/// ### Arguments
+/// - `text`: The pattern
+///
+/// ### Returns
+/// - `String`: The pattern with escaped metacharacters turned back into literals
+fn regex_unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\'
+            && let Some(&next) = chars.peek()
+            && REGEX_META_CHARACTERS.contains(next)
+        {
+            result.push(next);
+            chars.next();
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextTransform, shuffle};
+
+    fn apply(transform: TextTransform, text: &str) -> String {
+        transform.apply(text).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[test]
+    fn sorts_keep_the_final_newline_and_line_endings() {
+        let text = "file10\nfile2\nFile1\nfile2\n";
+        assert_eq!(
+            apply(TextTransform::SortNatural, text),
+            "File1\nfile2\nfile2\nfile10\n"
+        );
+        assert_eq!(
+            apply(TextTransform::SortReverse, text),
+            "file10\nfile2\nfile2\nFile1\n"
+        );
+        assert_eq!(
+            apply(TextTransform::RemoveDuplicateLines, text),
+            "file10\nfile2\nFile1\n"
+        );
+        assert_eq!(apply(TextTransform::ReverseLines, "a\r\nb"), "b\r\na");
+        assert_eq!(
+            apply(TextTransform::SortCaseInsensitive, "b\nA\na"),
+            "A\na\nb"
+        );
+    }
+
+    #[test]
+    fn numeric_sort_orders_by_leading_number() {
+        assert_eq!(
+            apply(
+                TextTransform::SortNumeric,
+                "10 ten\nnone\n-1 minus\n2.5 half\n"
+            ),
+            "-1 minus\n2.5 half\n10 ten\nnone\n"
+        );
+    }
+
+    #[test]
+    fn shuffle_keeps_every_line() {
+        let mut lines = vec!["a", "b", "c", "d", "e"];
+        shuffle(&mut lines, 42);
+        let mut sorted = lines.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, vec!["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn case_transforms_split_identifier_words() {
+        assert_eq!(
+            apply(TextTransform::TitleCase, "hello wORLD-wide"),
+            "Hello World-Wide"
+        );
+        assert_eq!(
+            apply(TextTransform::SnakeCase, "  parseHTTPRequest now\nUserId\n"),
+            "  parse_http_request_now\nuser_id\n"
+        );
+        assert_eq!(
+            apply(TextTransform::CamelCase, "user-id value\n"),
+            "userIdValue\n"
+        );
+    }
+
+    #[test]
+    fn join_and_split_lines_are_inverse() {
+        let text = "one\n    two\n\nthree\n";
+        let joined = apply(TextTransform::JoinLines, text);
+        assert_eq!(joined, "one two three\n");
+        assert_eq!(
+            apply(TextTransform::SplitLines, &joined),
+            "one\ntwo\nthree\n"
+        );
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        let text = "say \"hi\" <b>&</b> 'x'\n\tpath\\to\u{e9}";
+        for (escape, unescape) in [
+            (TextTransform::JsonEscape, TextTransform::JsonUnescape),
+            (TextTransform::HtmlEscape, TextTransform::HtmlUnescape),
+            (TextTransform::RegexEscape, TextTransform::RegexUnescape),
+            (TextTransform::Base64Encode, TextTransform::Base64Decode),
+            (TextTransform::UrlEncode, TextTransform::UrlDecode),
+        ] {
+            let escaped = apply(escape, text);
+            assert_eq!(apply(unescape, &escaped), text, "{escape:?}");
+        }
+        assert_eq!(apply(TextTransform::RegexEscape, "a.b*"), "a\\.b\\*");
+        assert_eq!(
+            apply(TextTransform::HtmlUnescape, "&#x41;&#66;&bogus;"),
+            "AB&bogus;"
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_reported() {
+        assert!(TextTransform::Base64Decode.apply("not base64!").is_err());
+        assert!(TextTransform::JsonUnescape.apply("bad \" quote").is_err());
+        assert!(TextTransform::UrlDecode.apply("%FF").is_err());
+    }
+}
@@ -80,6 +80,8 @@ fn test_state_concurrent_writes_no_corruption() {
                     }],
                     active_tab_index: Some(0),
                     window_bounds: SerializedWindowBounds::default(),
+                    splits: Vec::new(),
+                    terminals: Vec::new(),
                 };
                 state.windows.push(window);
                 state.save_to_path(&path).unwrap();
@@ -210,6 +212,8 @@ fn test_state_concurrent_writes_large_data() {
                             height: 800.0,
                             display_id: Some(i),
                         },
+                        splits: Vec::new(),
+                        terminals: Vec::new(),
                     });
                 }
 
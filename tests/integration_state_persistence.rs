@@ -207,6 +207,8 @@ fn test_state_roundtrip_single_window_with_mixed_tabs() {
                 height: 768.0,
                 display_id: Some(1),
             },
+            splits: Vec::new(),
+            terminals: Vec::new(),
         }],
     };
     original
@@ -243,6 +245,8 @@ fn test_state_roundtrip_multiple_windows() {
                     height: 800.0,
                     display_id: Some(1),
                 },
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 3,
@@ -256,6 +260,8 @@ fn test_state_roundtrip_multiple_windows() {
                     height: 1080.0,
                     display_id: Some(2),
                 },
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 4,
@@ -273,6 +279,8 @@ fn test_state_roundtrip_multiple_windows() {
                     height: 1440.0,
                     display_id: None,
                 },
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
         ],
     };
@@ -321,6 +329,8 @@ fn test_state_roundtrip_window_no_tabs() {
             tabs: vec![],
             active_tab_index: None,
             window_bounds: SerializedWindowBounds::default(),
+            splits: Vec::new(),
+            terminals: Vec::new(),
         }],
     };
     original
@@ -371,6 +381,8 @@ fn test_window_bounds_variants() {
                 tabs: vec![],
                 active_tab_index: None,
                 window_bounds: bounds.clone(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             }],
         };
         original
@@ -436,6 +448,8 @@ fn test_state_roundtrip_with_real_temp_files() {
             ],
             active_tab_index: Some(0),
             window_bounds: SerializedWindowBounds::default(),
+            splits: Vec::new(),
+            terminals: Vec::new(),
         }],
     };
     original
@@ -479,6 +493,8 @@ fn test_state_roundtrip_with_unicode_content() {
             }],
             active_tab_index: Some(0),
             window_bounds: SerializedWindowBounds::default(),
+            splits: Vec::new(),
+            terminals: Vec::new(),
         }],
     };
     original
@@ -549,6 +565,8 @@ fn test_state_multiple_save_load_cycles() {
             tabs: vec![create_file_tab_unmodified(0)],
             active_tab_index: Some(0),
             window_bounds: SerializedWindowBounds::default(),
+            splits: Vec::new(),
+            terminals: Vec::new(),
         }],
     };
     for i in 0..5 {
@@ -608,6 +626,8 @@ fn test_state_is_written_as_a_sqlite_database() {
                 height: 600.0,
                 display_id: Some(1),
             },
+            splits: Vec::new(),
+            terminals: Vec::new(),
         }],
     };
     state.save_to_path(&state_path).expect("Failed to save");
@@ -659,6 +679,8 @@ fn test_state_roundtrip_preserves_remote_spec_without_password_fields() {
             }],
             active_tab_index: Some(0),
             window_bounds: SerializedWindowBounds::default(),
+            splits: Vec::new(),
+            terminals: Vec::new(),
         }],
     };
 
@@ -713,6 +735,8 @@ fn test_state_preserves_window_order() {
                 height: 600.0,
                 display_id: Some(i),
             },
+            splits: Vec::new(),
+            terminals: Vec::new(),
         });
     }
     let original = WindowsState { windows };
@@ -749,6 +773,8 @@ fn test_state_windows_with_different_active_tabs() {
                 ],
                 active_tab_index: Some(0), // First tab active
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 14,
@@ -759,6 +785,8 @@ fn test_state_windows_with_different_active_tabs() {
                 ],
                 active_tab_index: Some(1), // Second tab active
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 15,
@@ -769,6 +797,8 @@ fn test_state_windows_with_different_active_tabs() {
                 ],
                 active_tab_index: Some(2), // Third tab active
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 16,
@@ -779,6 +809,8 @@ fn test_state_windows_with_different_active_tabs() {
                 ],
                 active_tab_index: None, // No active tab
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
         ],
     };
@@ -811,6 +843,8 @@ fn test_state_windows_on_different_displays() {
                     height: 800.0,
                     display_id: Some(1), // Primary display
                 },
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 18,
@@ -824,6 +858,8 @@ fn test_state_windows_on_different_displays() {
                     height: 1080.0,
                     display_id: Some(2), // Secondary display
                 },
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 19,
@@ -837,6 +873,8 @@ fn test_state_windows_on_different_displays() {
                     height: 1440.0,
                     display_id: Some(3), // Tertiary display
                 },
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
         ],
     };
@@ -864,6 +902,8 @@ fn test_state_mixed_window_and_tab_counts() {
                 tabs: vec![create_file_tab_unmodified(0)], // 1 tab
                 active_tab_index: Some(0),
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 21,
@@ -876,18 +916,24 @@ fn test_state_mixed_window_and_tab_counts() {
                 ], // 5 tabs
                 active_tab_index: Some(2),
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 22,
                 tabs: vec![], // 0 tabs
                 active_tab_index: None,
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
             WindowState {
                 window_id: 23,
                 tabs: vec![create_file_tab_unmodified(0), create_unsaved_tab(1)], // 2 tabs
                 active_tab_index: Some(1),
                 window_bounds: SerializedWindowBounds::default(),
+                splits: Vec::new(),
+                terminals: Vec::new(),
             },
         ],
     };